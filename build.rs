@@ -29,6 +29,17 @@ fn build_flags(){
 	}
 }
 
+// Cross compiling for Android, the host cfg doesn't apply so the target
+// has to be read from the environment
+fn android_flags(){
+	if std::env::var("CARGO_CFG_TARGET_OS").map(|os| os == "android").unwrap_or(false){
+		if let Ok(gst_root) = std::env::var("GSTREAMER_ROOT_ANDROID"){
+			println!("cargo:rustc-flags= -L native={}/lib",gst_root);
+		}
+	}
+}
+
 fn main(){
 	build_flags();
+	android_flags();
 }
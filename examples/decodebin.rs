@@ -31,10 +31,10 @@ fn main(){
     filesrc.set("location", uri);
     let mut decodebin = gst::Element::new("decodebin", "").unwrap();
     let mut sink = gst::Element::new("glimagesink", "").unwrap();
-    let mut sink_pad = sink.static_pad("sink").unwrap();
-    unsafe{
-        decodebin.signal_connect("pad-added", mem::transmute(signal_callback as *mut c_void), &mut sink_pad);
-    }
+    let sink_pad = sink.static_pad("sink").unwrap();
+    let _pad_added = unsafe{
+        decodebin.connect_owned("pad-added", mem::transmute(signal_callback as *mut c_void), sink_pad)
+    };
     if !pipeline.add_and_link(filesrc, decodebin){
         panic!("couldn't link filesrc and decodebin");
     }
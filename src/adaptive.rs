@@ -0,0 +1,295 @@
+use ffi::*;
+use util::*;
+
+use bin::Bin;
+use element::Element;
+use message::Message;
+use structure::Structure;
+use value::Value;
+use reference::Reference;
+use registration::factory_name;
+
+use std::ops::{Deref, DerefMut};
+
+// the demuxers of the legacy adaptivedemux and of adaptivedemux2
+static FACTORIES: &'static [&'static str] = &[
+    "hlsdemux", "dashdemux", "mssdemux",
+    "hlsdemux2", "dashdemux2", "mssdemux2",
+];
+
+/// Wrapper for the HLS, DASH and Smooth Streaming demuxers, to control
+/// which variant they download, eg: for a quality menu in a player:
+///
+/// ```ignore
+/// playbin.connect_deep_element_added(|_bin, element| {
+///     if let Some(mut demux) = gst::adaptive::AdaptiveDemux::from_element(element.reference()){
+///         // cap the quality at 720p bitrates
+///         demux.set_max_bitrate(3_000_000);
+///     }
+/// }).unwrap().forget();
+/// ```
+///
+/// The demuxer picks the best variant whose bandwidth fits the measured
+/// download speed, within the limits set here, and switches at
+/// fragment boundaries. Setting the connection speed pins the quality
+/// to the variant that fits it instead.
+pub struct AdaptiveDemux{
+    demux: Element
+}
+
+impl AdaptiveDemux{
+    /// Wraps element if it's one of the adaptive demuxers
+    pub fn from_element(element: Element) -> Option<AdaptiveDemux>{
+        match factory_name(&element){
+            Some(ref factory) if FACTORIES.contains(&&factory[..]) => Some(AdaptiveDemux{ demux: element }),
+            _ => None
+        }
+    }
+
+    /// Finds the adaptive demuxer inside bin, eg: a playbin or
+    /// uridecodebin once it has started playing
+    pub fn find(bin: &Bin) -> Option<AdaptiveDemux>{
+        bin.iter_recurse()
+            .filter_map(|element| element.ok())
+            .filter_map(AdaptiveDemux::from_element)
+            .next()
+    }
+
+    /// True for the demuxers based on adaptivedemux2, eg: hlsdemux2,
+    /// which support the min, max and start bitrates
+    pub fn is_adaptivedemux2(&self) -> bool{
+        factory_name(&self.demux).map(|factory| factory.ends_with('2')).unwrap_or(false)
+    }
+
+    /// Download speed in kbps to use instead of measuring it, which
+    /// fixes the variant to the best one that fits. 0 goes back to
+    /// measuring it
+    pub fn set_connection_speed(&mut self, kbps: u32){
+        self.set("connection-speed", kbps);
+    }
+
+    pub fn connection_speed(&self) -> u32{
+        self.get::<u32>("connection-speed")
+    }
+
+    /// Fraction of the download speed the bitrate of a variant can use,
+    /// 0.8 by default so there's margin for bandwidth variations
+    pub fn set_bitrate_limit(&mut self, limit: f32){
+        self.set("bitrate-limit", limit as f64);
+    }
+
+    /// Never selects variants with a higher bitrate in bits per second,
+    /// 0 for no limit. Returns false if the demuxer doesn't support it,
+    /// only dashdemux and the adaptivedemux2 ones do
+    pub fn set_max_bitrate(&mut self, bps: u32) -> bool{
+        self.set_value("max-bitrate", &Value::from(bps))
+    }
+
+    /// Never selects variants with a lower bitrate in bits per second.
+    /// Only supported by the adaptivedemux2 demuxers
+    pub fn set_min_bitrate(&mut self, bps: u32) -> bool{
+        self.set_value("min-bitrate", &Value::from(bps))
+    }
+
+    /// Bitrate of the variant to start with, before the download speed
+    /// is known. Only supported by the adaptivedemux2 demuxers
+    pub fn set_start_bitrate(&mut self, bps: u32) -> bool{
+        self.set_value("start-bitrate", &Value::from(bps))
+    }
+
+    /// Only downloads variant from now on, as far as the demuxer allows
+    /// it, by limiting the bitrate to the one of the variant. Use
+    /// select_auto to go back to adaptive selection
+    pub fn select_variant(&mut self, variant: &Variant){
+        if self.set_max_bitrate(variant.bandwidth as u32){
+            // so adaptivedemux2 doesn't go lower either
+            self.set_min_bitrate(variant.bandwidth as u32);
+        }else{
+            // no max-bitrate, the connection speed has to be faked
+            let kbps = (variant.bandwidth as f64 / self.get::<f32>("bitrate-limit").max(0.01) as f64 / 1000.).ceil();
+            self.set_connection_speed(kbps as u32);
+        }
+    }
+
+    /// Goes back to choosing the variant from the download speed
+    pub fn select_auto(&mut self){
+        self.set_max_bitrate(0);
+        self.set_min_bitrate(0);
+        self.set_connection_speed(0);
+    }
+
+    /// Download speed in bits per second currently measured. Only
+    /// reported by the adaptivedemux2 demuxers
+    pub fn current_bandwidth(&self) -> Option<u32>{
+        self.get_value("current-bandwidth").and_then(|value| value.get::<u32>())
+    }
+}
+
+/// A variant of an HLS master playlist
+#[derive(Clone,Debug,PartialEq)]
+pub struct Variant{
+    /// Peak bitrate in bits per second
+    pub bandwidth: u64,
+    /// Width and height, if declared
+    pub resolution: Option<(u32, u32)>,
+    pub codecs: Option<String>,
+    pub frame_rate: Option<f64>,
+    /// URI of the media playlist, relative to the master playlist
+    pub uri: String,
+}
+
+/// Parses the variants of an HLS master playlist, sorted by bandwidth,
+/// to list the qualities a player can select. Media playlists have no
+/// variants
+pub fn parse_hls_variants(playlist: &str) -> Vec<Variant>{
+    let mut variants = vec![];
+    let mut lines = playlist.lines().map(|line| line.trim()).filter(|line| !line.is_empty());
+    while let Some(line) = lines.next(){
+        if !line.starts_with("#EXT-X-STREAM-INF:"){
+            continue;
+        }
+        let attributes = parse_attributes(&line["#EXT-X-STREAM-INF:".len()..]);
+        let attribute = |name: &str| attributes.iter().find(|&&(ref key, _)| key == name).map(|&(_, ref value)| value.clone());
+        let uri = match lines.next(){
+            Some(uri) if !uri.starts_with('#') => uri.to_string(),
+            _ => continue
+        };
+        let bandwidth = match attribute("BANDWIDTH").and_then(|bandwidth| bandwidth.parse().ok()){
+            Some(bandwidth) => bandwidth,
+            None => continue
+        };
+        let resolution = attribute("RESOLUTION").and_then(|resolution| {
+            let mut parts = resolution.split('x').map(|part| part.parse::<u32>());
+            match (parts.next(), parts.next()){
+                (Some(Ok(width)), Some(Ok(height))) => Some((width, height)),
+                _ => None
+            }
+        });
+        variants.push(Variant{
+            bandwidth: bandwidth,
+            resolution: resolution,
+            codecs: attribute("CODECS"),
+            frame_rate: attribute("FRAME-RATE").and_then(|rate| rate.parse().ok()),
+            uri: uri,
+        });
+    }
+    variants.sort_by_key(|variant| variant.bandwidth);
+    variants
+}
+
+// attributes are KEY=value or KEY="quoted, value" separated by commas
+fn parse_attributes(list: &str) -> Vec<(String, String)>{
+    let mut attributes = vec![];
+    let mut rest = list;
+    while let Some(eq) = rest.find('='){
+        let key = rest[..eq].trim().to_string();
+        rest = &rest[eq + 1..];
+        let value;
+        if rest.starts_with('"'){
+            let end = rest[1..].find('"').map(|end| end + 1).unwrap_or(rest.len());
+            value = rest[1..end].to_string();
+            rest = &rest[(end + 1).min(rest.len())..];
+        }else{
+            let end = rest.find(',').unwrap_or(rest.len());
+            value = rest[..end].to_string();
+            rest = &rest[end..];
+        }
+        attributes.push((key, value));
+        rest = rest.trim_start_matches(',');
+    }
+    attributes
+}
+
+/// Download of a fragment, posted by the demuxers as an element message
+/// after each one, to show the measured bandwidth in a player
+#[derive(Clone,Debug,PartialEq)]
+pub struct FragmentStats{
+    pub manifest_uri: String,
+    /// URI of the fragment
+    pub uri: String,
+    /// Stream times in nanoseconds of the fragment
+    pub start_time: u64,
+    pub stop_time: u64,
+    /// Size of the fragment in bytes
+    pub size: u64,
+    /// Time it took to download in nanoseconds
+    pub download_time: u64,
+}
+
+impl FragmentStats{
+    /// Parses an element message posted by an adaptive demuxer, None
+    /// for any other message
+    pub fn from_message(msg: &Message) -> Option<FragmentStats>{
+        match *msg{
+            Message::Element(_) => FragmentStats::from_structure(&msg.get_structure()?),
+            _ => None
+        }
+    }
+
+    pub fn from_structure(structure: &Structure) -> Option<FragmentStats>{
+        if structure.name() != "adaptive-streaming-statistics"{
+            return None;
+        }
+        Some(FragmentStats{
+            manifest_uri: structure.get_string("manifest-uri").unwrap_or("").to_string(),
+            uri: structure.get_string("uri")?.to_string(),
+            start_time: structure.get::<u64>("fragment-start-time").unwrap_or(0),
+            stop_time: structure.get::<u64>("fragment-stop-time").unwrap_or(0),
+            size: structure.get::<u64>("fragment-size")?,
+            download_time: structure.get::<u64>("fragment-download-time")?,
+        })
+    }
+
+    /// Download speed of the fragment in bits per second
+    pub fn bitrate(&self) -> u64{
+        if self.download_time == 0{
+            0
+        }else{
+            (self.size as f64 * 8. * 1_000_000_000. / self.download_time as f64) as u64
+        }
+    }
+}
+
+impl Reference for AdaptiveDemux{
+    fn reference(&self) -> AdaptiveDemux{
+        AdaptiveDemux{ demux: self.demux.reference() }
+    }
+}
+
+impl AsRef<Element> for AdaptiveDemux{
+    fn as_ref(&self) -> &Element{
+        &self.demux
+    }
+}
+
+impl AsMut<Element> for AdaptiveDemux{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.demux
+    }
+}
+
+impl From<AdaptiveDemux> for Element{
+    fn from(d: AdaptiveDemux) -> Element{
+        d.demux
+    }
+}
+
+impl Deref for AdaptiveDemux{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.demux
+    }
+}
+
+impl DerefMut for AdaptiveDemux{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.demux
+    }
+}
+
+impl ::Transfer for AdaptiveDemux{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.demux.transfer()
+    }
+}
+
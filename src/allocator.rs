@@ -0,0 +1,250 @@
+use ffi::*;
+use util::*;
+use object::Object;
+use memory::Memory;
+use reference::Reference;
+use version::ApiAvailability;
+
+use std::ops::{Deref, DerefMut};
+#[cfg(target_os="linux")]
+use std::os::unix::io::IntoRawFd;
+
+pub const FD_MEMORY: ApiAvailability = ApiAvailability::new("FdAllocator", 1, 6, 0);
+pub const DMABUF_MEMORY: ApiAvailability = ApiAvailability::new("DmaBufAllocator", 1, 2, 0);
+
+/// How memory has to be allocated: its alignment, the bytes to reserve
+/// before and after the data and GST_MEMORY_FLAG_* flags, eg: aligned to
+/// 32 bytes with some padding so SIMD code can read past the end:
+///
+/// ```ignore
+/// let params = gst::AllocationParams{ align: 31, padding: 32, ..gst::AllocationParams::default() };
+/// let buffer = gst::Buffer::new_allocate(None, size, &params).unwrap();
+/// ```
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct AllocationParams{
+    /// GST_MEMORY_FLAG_*, eg: GST_MEMORY_FLAG_ZERO_PADDED
+    pub flags: GstMemoryFlags,
+    /// Alignment minus one, has to be a power of two minus one, eg: 15
+    /// for 16 bytes alignment
+    pub align: usize,
+    /// Bytes reserved before the data
+    pub prefix: usize,
+    /// Bytes reserved after the data
+    pub padding: usize,
+}
+
+impl AllocationParams{
+    /// Params for memory aligned to align bytes, align has to be a power
+    /// of two, eg: 16 or 4096
+    pub fn aligned(align: usize) -> AllocationParams{
+        AllocationParams{
+            align: align.saturating_sub(1),
+            ..AllocationParams::default()
+        }
+    }
+
+    pub fn from_gst_params(params: &GstAllocationParams) -> AllocationParams{
+        AllocationParams{
+            flags: params.flags,
+            align: params.align as usize,
+            prefix: params.prefix as usize,
+            padding: params.padding as usize,
+        }
+    }
+
+    pub fn to_gst_params(&self) -> GstAllocationParams{
+        let mut params = GstAllocationParams::default();
+        unsafe{
+            gst_allocation_params_init(&mut params);
+        }
+        params.flags = self.flags;
+        params.align = self.align as gsize;
+        params.prefix = self.prefix as gsize;
+        params.padding = self.padding as gsize;
+        params
+    }
+}
+
+/// Allocates the memory used by buffers, elements can provide their own
+/// allocators, eg: to allocate memory a hardware device can access
+pub struct Allocator{
+    allocator: Object<GstAllocator>
+}
+
+impl Allocator{
+    pub unsafe fn new(allocator: *mut GstAllocator) -> Option<Allocator>{
+        Object::new(allocator).map(|obj| Allocator{ allocator: obj })
+    }
+
+    /// Returns the default system memory allocator
+    pub fn system() -> Option<Allocator>{
+        unsafe{
+            Allocator::new(gst_allocator_find(ptr::null()))
+        }
+    }
+
+    /// Returns the allocator registered with name, eg: "SystemMemory"
+    pub fn find(name: &str) -> Option<Allocator>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            Allocator::new(gst_allocator_find(cname.as_ptr()))
+        }
+    }
+
+    /// Allocates a new memory of size bytes
+    pub fn alloc(&mut self, size: usize) -> Option<Memory>{
+        unsafe{
+            Memory::new(gst_allocator_alloc(self.gst_allocator_mut(), size as gsize, ptr::null_mut()))
+        }
+    }
+
+    /// Allocates a new memory of size bytes aligned to align + 1 bytes,
+    /// align has to be a power of two minus one, eg: 15 for 16 bytes
+    /// alignment
+    pub fn alloc_aligned(&mut self, size: usize, align: usize) -> Option<Memory>{
+        self.alloc_with_params(size, &AllocationParams{ align: align, ..AllocationParams::default() })
+    }
+
+    /// Allocates a new memory of size bytes, not counting the prefix and
+    /// padding of params
+    pub fn alloc_with_params(&mut self, size: usize, params: &AllocationParams) -> Option<Memory>{
+        let mut params = params.to_gst_params();
+        unsafe{
+            Memory::new(gst_allocator_alloc(self.gst_allocator_mut(), size as gsize, &mut params))
+        }
+    }
+
+    pub unsafe fn gst_allocator(&self) -> *const GstAllocator{
+        self.allocator.as_ptr()
+    }
+
+    pub unsafe fn gst_allocator_mut(&mut self) -> *mut GstAllocator{
+        self.allocator.as_mut_ptr()
+    }
+}
+
+impl Reference for Allocator{
+    fn reference(&self) -> Allocator{
+        Allocator{ allocator: self.allocator.reference() }
+    }
+}
+
+impl AsRef<Object> for Allocator{
+    fn as_ref(&self) -> &Object{
+        self.allocator.upcast_ref()
+    }
+}
+
+impl AsMut<Object> for Allocator{
+    fn as_mut(&mut self) -> &mut Object{
+        self.allocator.upcast_mut()
+    }
+}
+
+impl From<Allocator> for Object{
+    fn from(a: Allocator) -> Object{
+        a.allocator.upcast()
+    }
+}
+
+impl Deref for Allocator{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.allocator.upcast_ref()
+    }
+}
+
+impl DerefMut for Allocator{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.allocator.upcast_mut()
+    }
+}
+
+/// Allocator that wraps file descriptors as memory, mapping them when
+/// the memory is read, eg: memfds shared with another process
+#[cfg(target_os="linux")]
+pub struct FdAllocator{
+    allocator: Allocator
+}
+
+#[cfg(target_os="linux")]
+impl FdAllocator{
+    /// None with GStreamer older than 1.6
+    pub fn new() -> Option<FdAllocator>{
+        unsafe{
+            let fd_allocator_new: unsafe extern "C" fn() -> *mut GstAllocator = FD_MEMORY.function("gst_fd_allocator_new").ok()?;
+            Allocator::new(fd_allocator_new()).map(|allocator| FdAllocator{ allocator: allocator })
+        }
+    }
+
+    /// Wraps size bytes of fd as memory, the fd is closed when the memory
+    /// is freed unless flags has GST_FD_MEMORY_FLAG_DONT_CLOSE
+    pub fn alloc_fd<F: IntoRawFd>(&mut self, fd: F, size: usize, flags: GstFdMemoryFlags) -> Option<Memory>{
+        unsafe{
+            // there's an allocator so the function is available
+            let alloc: unsafe extern "C" fn(*mut GstAllocator, gint, gsize, GstFdMemoryFlags) -> *mut GstMemory =
+                FD_MEMORY.function("gst_fd_allocator_alloc").unwrap();
+            Memory::new(alloc(self.gst_allocator_mut(), fd.into_raw_fd(), size as gsize, flags))
+        }
+    }
+}
+
+/// Allocator that wraps DMA-BUF file descriptors as memory, so buffers
+/// can be exchanged without copies with V4L2 devices, wayland
+/// compositors or GPU APIs that import DMA-BUFs. See Buffer::from_dmabuf
+#[cfg(target_os="linux")]
+pub struct DmaBufAllocator{
+    allocator: Allocator
+}
+
+#[cfg(target_os="linux")]
+impl DmaBufAllocator{
+    /// None with GStreamer older than 1.2
+    pub fn new() -> Option<DmaBufAllocator>{
+        unsafe{
+            let dmabuf_allocator_new: unsafe extern "C" fn() -> *mut GstAllocator = DMABUF_MEMORY.function("gst_dmabuf_allocator_new").ok()?;
+            Allocator::new(dmabuf_allocator_new()).map(|allocator| DmaBufAllocator{ allocator: allocator })
+        }
+    }
+
+    /// Wraps size bytes of the dmabuf fd as memory, the fd is closed
+    /// when the memory is freed
+    pub fn alloc_dmabuf<F: IntoRawFd>(&mut self, fd: F, size: usize) -> Option<Memory>{
+        unsafe{
+            // there's an allocator so the function is available
+            let alloc: unsafe extern "C" fn(*mut GstAllocator, gint, gsize) -> *mut GstMemory =
+                DMABUF_MEMORY.function("gst_dmabuf_allocator_alloc").unwrap();
+            Memory::new(alloc(self.gst_allocator_mut(), fd.into_raw_fd(), size as gsize))
+        }
+    }
+}
+
+#[cfg(target_os="linux")]
+impl Deref for FdAllocator{
+    type Target = Allocator;
+    fn deref(&self) -> &Allocator{
+        &self.allocator
+    }
+}
+
+#[cfg(target_os="linux")]
+impl DerefMut for FdAllocator{
+    fn deref_mut(&mut self) -> &mut Allocator{
+        &mut self.allocator
+    }
+}
+
+#[cfg(target_os="linux")]
+impl Deref for DmaBufAllocator{
+    type Target = Allocator;
+    fn deref(&self) -> &Allocator{
+        &self.allocator
+    }
+}
+
+#[cfg(target_os="linux")]
+impl DerefMut for DmaBufAllocator{
+    fn deref_mut(&mut self) -> &mut Allocator{
+        &mut self.allocator
+    }
+}
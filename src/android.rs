@@ -0,0 +1,79 @@
+use ffi::*;
+use registry::Registry;
+use element_factory::ElementFactory;
+
+use std::env;
+use std::os::raw::c_void;
+use std::path::PathBuf;
+
+// Provided by libgstreamer_android.so, generated by the ndk-build
+// scripts shipped with the GStreamer Android binaries
+extern "C" {
+    fn gst_android_init(env: *mut c_void, context: *mut c_void);
+    fn gst_android_get_java_vm() -> *mut c_void;
+    fn gst_android_get_application_context() -> *mut c_void;
+}
+
+/// Initializes GStreamer on Android, to be called instead of gst::init
+/// from a JNI method with the JNIEnv of the calling thread and the
+/// application Context:
+///
+/// ```ignore
+/// #[no_mangle]
+/// pub unsafe extern "C" fn Java_org_example_Player_nativeInit(env: *mut c_void, _class: *mut c_void, context: *mut c_void){
+///     gst::android::init(env, context);
+/// }
+/// ```
+///
+/// Apart from gst_init this sets up the cache and registry
+/// directories, the CA certificates and fonts, registers the statically
+/// linked plugins and stores the JavaVM and class loader the
+/// androidmedia plugin needs to use the hardware codecs through
+/// MediaCodec. Returns true if GStreamer is initialized.
+///
+/// Pipelines have to be paused from Activity.onPause, see
+/// lifecycle::Lifecycle.
+pub unsafe fn init(env: *mut c_void, context: *mut c_void) -> bool{
+    gst_android_init(env, context);
+    gst_is_initialized() != 0
+}
+
+/// The JavaVM stored by init, a JavaVM* that can be used to attach
+/// native threads. Null if init hasn't been called
+pub fn java_vm() -> *mut c_void{
+    unsafe{
+        gst_android_get_java_vm()
+    }
+}
+
+/// Global reference to the application Context passed to init, a
+/// jobject. Null if init hasn't been called
+pub fn application_context() -> *mut c_void{
+    unsafe{
+        gst_android_get_application_context()
+    }
+}
+
+/// Hardware encoders and decoders exposed by the androidmedia plugin
+/// through MediaCodec. Empty if the plugin isn't linked or init wasn't
+/// called with a valid Context
+pub fn hardware_codecs() -> Vec<ElementFactory>{
+    Registry::get().features_by_plugin("androidmedia").into_iter()
+        .filter_map(|feature| feature.element_factory())
+        .filter(|factory| factory.is_type(GST_ELEMENT_FACTORY_TYPE_DECODER | GST_ELEMENT_FACTORY_TYPE_ENCODER))
+        .collect()
+}
+
+/// The application's files directory, Context.getFilesDir(), which
+/// init sets as HOME. None if init hasn't been called
+pub fn files_dir() -> Option<PathBuf>{
+    env::var_os("HOME").map(PathBuf::from)
+}
+
+/// URI of a file in the application's files directory to play it with
+/// playbin or uridecodebin. Files in the APK assets can't be read by
+/// GStreamer directly, they have to be copied there first or played
+/// through a content:// URI with a file descriptor
+pub fn files_uri(name: &str) -> Option<String>{
+    ::filename_to_uri(files_dir()?.join(name).to_str()?).ok()
+}
@@ -1,13 +1,28 @@
 use ffi::*;
 
 use std::ptr;
-use std::mem;
 use std::sync::mpsc::{Sender,Receiver,TryRecvError,RecvError,SendError,channel};
 use std::ops::{Deref, DerefMut};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, Condvar};
+use std::time::{Duration, Instant};
 
 use sample::Sample;
 use element::Element;
 use caps::Caps;
+use videoinfo::VideoInfo;
+use audioinfo::AudioInfo;
+use std::ffi::CString;
+use util::Guarded;
+
+#[cfg(feature = "futures")]
+use futures::channel::mpsc::{unbounded, UnboundedSender, UnboundedReceiver};
+#[cfg(feature = "futures")]
+use futures::stream::Stream;
+#[cfg(feature = "futures")]
+use futures::task::{Context, Poll};
+#[cfg(feature = "futures")]
+use std::pin::Pin;
 
 pub enum Message{
 	NewSample(Sample),
@@ -38,9 +53,18 @@ impl Message{
     }
 }
 
-unsafe impl Sync for AppSink {}
-unsafe impl Send for AppSink {}
+/// What an appsink negotiated, as returned by AppSink::negotiated_info
+pub enum MediaInfo{
+    /// Raw video
+    Video(VideoInfo),
+    /// Raw audio
+    Audio(AudioInfo),
+    /// Anything else, like encoded media, with the caps to inspect them
+    Other(Caps),
+}
 
+/// Send but not Sync, samples can only be received from one thread at a
+/// time. Move the appsink to the thread that pulls the samples.
 #[allow(dead_code)] // we need to keep the samples_sender around
 pub struct AppSink{
     appsink: Element,
@@ -62,7 +86,7 @@ impl AppSink{
                                 new_sample: Some(on_new_sample_from_source),
                                 _gst_reserved: [ptr::null_mut(); 4]
                     };
-                    gst_app_sink_set_callbacks(a.gst_element() as *mut GstAppSink, &mut gst_callbacks, mem::transmute(&*sender), None);
+                    gst_app_sink_set_callbacks(a.gst_element() as *mut GstAppSink, &mut gst_callbacks, &*sender as *const Sender<Message> as gpointer, None);
                     Some(AppSink{ appsink: a, samples_receiver: receiver, samples_sender: sender })
                 },
 
@@ -81,7 +105,7 @@ impl AppSink{
                         new_sample: Some(on_new_sample_from_source),
                         _gst_reserved: [ptr::null_mut(); 4]
             };
-            gst_app_sink_set_callbacks(element.gst_element() as *mut GstAppSink, &mut gst_callbacks, mem::transmute(&*sender), None);
+            gst_app_sink_set_callbacks(element.gst_element() as *mut GstAppSink, &mut gst_callbacks, &*sender as *const Sender<Message> as gpointer, None);
         }
         AppSink{ appsink: element, samples_receiver: receiver, samples_sender: sender }
     }
@@ -102,6 +126,40 @@ impl AppSink{
         self.appsink.gst_element() as *mut GstAppSink
     }
 
+    /// The format negotiated by the appsink parsed from its caps, to
+    /// branch on the media received without inspecting caps strings:
+    ///
+    /// ```ignore
+    /// match appsink.negotiated_info(){
+    ///     Some(gst::appsink::MediaInfo::Video(info)) => println!("{}x{} {}", info.width(), info.height(), info.format_name()),
+    ///     Some(gst::appsink::MediaInfo::Audio(info)) => println!("{}Hz {} channels", info.rate(), info.channels()),
+    ///     Some(gst::appsink::MediaInfo::Other(caps)) => println!("{}", caps.to_string()),
+    ///     None => println!("not negotiated yet"),
+    /// }
+    /// ```
+    ///
+    /// None until caps are negotiated, which is done once the pipeline
+    /// has prerolled.
+    pub fn negotiated_info(&self) -> Option<MediaInfo>{
+        let caps = unsafe{
+            let cname = CString::new("sink").unwrap();
+            let pad = gst_element_get_static_pad(self.appsink.gst_element() as *mut GstElement, cname.as_ptr());
+            if pad == ptr::null_mut(){
+                return None;
+            }
+            let caps = Caps::new(gst_pad_get_current_caps(pad));
+            gst_object_unref(pad as gpointer);
+            caps?
+        };
+        if let Some(info) = caps.video_info(){
+            Some(MediaInfo::Video(info))
+        }else if let Some(info) = caps.audio_info(){
+            Some(MediaInfo::Audio(info))
+        }else{
+            Some(MediaInfo::Other(caps))
+        }
+    }
+
     pub fn set_caps(&mut self, caps: Caps){
 		unsafe{
 			gst_app_sink_set_caps(self.gst_appsink_mut(), caps.gst_caps() as *const GstCaps);
@@ -110,14 +168,14 @@ impl AppSink{
 
 	pub fn get_caps(&self) -> Option<Caps>{
 		unsafe{
-			let caps = gst_app_sink_get_caps(mem::transmute(self.gst_appsink()));
+			let caps = gst_app_sink_get_caps(self.gst_appsink() as *mut GstAppSink);
 			Caps::new(caps)
 		}
 	}
 
 	pub fn is_eos(&self) -> bool{
 		unsafe{
-			gst_app_sink_is_eos(mem::transmute(self.gst_appsink())) == 1
+			gst_app_sink_is_eos(self.gst_appsink() as *mut GstAppSink) == 1
 		}
 	}
 
@@ -129,7 +187,7 @@ impl AppSink{
 
     pub fn get_emit_signals(&self) -> bool{
 		unsafe{
-			gst_app_sink_get_emit_signals(mem::transmute(self.gst_appsink())) == 1
+			gst_app_sink_get_emit_signals(self.gst_appsink() as *mut GstAppSink) == 1
 		}
 	}
 
@@ -141,7 +199,7 @@ impl AppSink{
 
 	pub fn max_buffers(&self) -> u32{
 		unsafe{
-			gst_app_sink_get_max_buffers(mem::transmute(self.gst_appsink()))
+			gst_app_sink_get_max_buffers(self.gst_appsink() as *mut GstAppSink)
 		}
 	}
 
@@ -153,9 +211,414 @@ impl AppSink{
 
 	pub fn get_drop(&self) -> bool{
 		unsafe{
-			gst_app_sink_get_drop(mem::transmute(self.gst_appsink())) == 1
+			gst_app_sink_get_drop(self.gst_appsink() as *mut GstAppSink) == 1
 		}
 	}
+
+    /// The samples of the appsink as a futures Stream that ends on EOS,
+    /// so an async application can consume them without a thread
+    /// blocked in recv:
+    ///
+    /// ```ignore
+    /// let mut samples = appsink.samples();
+    /// while let Some(sample) = samples.next().await{
+    ///     let buffer = sample.buffer().unwrap();
+    ///     // ...
+    /// }
+    /// ```
+    ///
+    /// Preroll samples aren't sent since the same sample arrives again
+    /// once the pipeline is playing.
+    #[cfg(feature = "futures")]
+    pub fn samples(self) -> SampleStream{
+        let (sender, receiver) = unbounded();
+        let sender = Box::into_raw(Box::new(sender));
+        unsafe{
+            let mut gst_callbacks = GstAppSinkCallbacks{
+                        eos: Some(on_eos_to_stream),
+                        new_preroll: None,
+                        new_sample: Some(on_new_sample_to_stream),
+                        _gst_reserved: [ptr::null_mut(); 4]
+            };
+            gst_app_sink_set_callbacks(self.appsink.gst_element() as *mut GstAppSink, &mut gst_callbacks, sender as gpointer, Some(destroy_stream_sender));
+        }
+        SampleStream{ appsink: self.appsink, receiver: receiver }
+    }
+
+    /// Keeps up to capacity samples in a queue, applying policy when
+    /// it's full, for consumers whose processing time varies, eg: an
+    /// inference step that sometimes takes longer than a frame:
+    ///
+    /// ```ignore
+    /// let queue = appsink.bounded(8, gst::appsink::OverflowPolicy::Block);
+    /// queue.connect_drop(|drops| println!("{} frames dropped so far", drops));
+    /// while let Some(sample) = queue.recv(){
+    ///     process(sample);
+    ///     // skip frames instead of falling behind when it's too slow
+    ///     if queue.stats().len == 8{
+    ///         queue.set_policy(gst::appsink::OverflowPolicy::DropOldest);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Preroll samples aren't queued since the same sample arrives again
+    /// once the pipeline is playing.
+    pub fn bounded(self, capacity: usize, policy: OverflowPolicy) -> BoundedAppSink{
+        let queue = Arc::new(BoundedQueue{
+            state: Mutex::new(QueueState{
+                samples: VecDeque::new(),
+                capacity: capacity,
+                policy: policy,
+                eos: false,
+                closed: false,
+                stats: QueueStats{ capacity: capacity, ..QueueStats::default() },
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            on_drop: Mutex::new(None),
+            on_high_watermark: Mutex::new(None),
+        });
+        unsafe{
+            let cname = CString::new("sink").unwrap();
+            let pad = gst_element_get_static_pad(self.appsink.gst_element() as *mut GstElement, cname.as_ptr());
+            if pad != ptr::null_mut(){
+                // only used to check the flushing flag, the appsink keeps
+                // it alive as long as the callbacks are set
+                gst_object_unref(pad as gpointer);
+            }
+            let data = Box::into_raw(Box::new(QueueCallbackData{ queue: queue.clone(), sink_pad: pad as usize }));
+            let mut gst_callbacks = GstAppSinkCallbacks{
+                        eos: Some(on_eos_to_queue),
+                        new_preroll: None,
+                        new_sample: Some(on_new_sample_to_queue),
+                        _gst_reserved: [ptr::null_mut(); 4]
+            };
+            gst_app_sink_set_callbacks(self.appsink.gst_element() as *mut GstAppSink, &mut gst_callbacks, data as gpointer, Some(destroy_queue_data));
+        }
+        BoundedAppSink{ appsink: self.appsink, queue: queue }
+    }
+}
+
+/// What a BoundedAppSink does with a new sample when its queue is full
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum OverflowPolicy{
+    /// Blocks the streaming thread until there's room, which pushes
+    /// back to the upstream elements, eg: a live source will drop
+    /// buffers and a file source will slow down
+    Block,
+    /// Drops the oldest sample in the queue to make room, so the
+    /// consumer always gets the latest ones
+    DropOldest,
+}
+
+/// Counters of a BoundedAppSink, see BoundedAppSink::stats
+#[derive(Copy,Clone,Debug,Default,PartialEq)]
+pub struct QueueStats{
+    /// Samples in the queue right now
+    pub len: usize,
+    pub capacity: usize,
+    /// Maximum number of samples there has been in the queue
+    pub high_watermark: usize,
+    /// Samples dropped because the queue was full
+    pub drops: u64,
+    /// Samples received from the appsink, including the dropped ones
+    pub received: u64,
+    /// Times the streaming thread blocked waiting for room
+    pub blocked: u64,
+}
+
+struct QueueState{
+    samples: VecDeque<Sample>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    eos: bool,
+    closed: bool,
+    stats: QueueStats,
+}
+
+struct BoundedQueue{
+    state: Mutex<QueueState>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    on_drop: Mutex<Option<Guarded<dyn FnMut(u64) + Send>>>,
+    on_high_watermark: Mutex<Option<Guarded<dyn FnMut(usize) + Send>>>,
+}
+
+struct QueueCallbackData{
+    queue: Arc<BoundedQueue>,
+    // *mut GstPad, usize so the data is Send
+    sink_pad: usize,
+}
+
+/// An appsink with a queue of bounded size, returned by
+/// AppSink::bounded. The policy and capacity can be changed while it's
+/// running. Derefs to the appsink element to configure it or change its
+/// state
+pub struct BoundedAppSink{
+    appsink: Element,
+    queue: Arc<BoundedQueue>,
+}
+
+impl BoundedAppSink{
+    /// Waits for the next sample, None on EOS once the queue is empty
+    pub fn recv(&self) -> Option<Sample>{
+        let mut state = self.queue.state.lock().unwrap_or_else(|e| e.into_inner());
+        loop{
+            if let Some(sample) = self.pop(&mut state){
+                return Some(sample);
+            }
+            if state.eos{
+                return None;
+            }
+            state = self.queue.not_empty.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Waits up to timeout for the next sample, None on timeout or EOS
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Sample>{
+        let deadline = Instant::now() + timeout;
+        let mut state = self.queue.state.lock().unwrap_or_else(|e| e.into_inner());
+        loop{
+            if let Some(sample) = self.pop(&mut state){
+                return Some(sample);
+            }
+            let now = Instant::now();
+            if state.eos || now >= deadline{
+                return None;
+            }
+            state = self.queue.not_empty.wait_timeout(state, deadline - now).unwrap_or_else(|e| e.into_inner()).0;
+        }
+    }
+
+    /// The next sample if there's one in the queue, without waiting
+    pub fn try_recv(&self) -> Option<Sample>{
+        let mut state = self.queue.state.lock().unwrap_or_else(|e| e.into_inner());
+        self.pop(&mut state)
+    }
+
+    /// True once EOS was received and all the samples consumed
+    pub fn is_eos(&self) -> bool{
+        let state = self.queue.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.eos && state.samples.is_empty()
+    }
+
+    pub fn set_policy(&self, policy: OverflowPolicy){
+        let mut state = self.queue.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.policy = policy;
+        self.queue.not_full.notify_all();
+    }
+
+    pub fn policy(&self) -> OverflowPolicy{
+        self.queue.state.lock().unwrap_or_else(|e| e.into_inner()).policy
+    }
+
+    /// Changes the capacity, if there are more samples in the queue
+    /// they're kept until consumed
+    pub fn set_capacity(&self, capacity: usize){
+        let mut state = self.queue.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.capacity = capacity;
+        state.stats.capacity = capacity;
+        self.queue.not_full.notify_all();
+    }
+
+    pub fn stats(&self) -> QueueStats{
+        let state = self.queue.state.lock().unwrap_or_else(|e| e.into_inner());
+        QueueStats{ len: state.samples.len(), ..state.stats }
+    }
+
+    /// Resets the high watermark and the counters, eg: after changing
+    /// the policy to measure its effect
+    pub fn reset_stats(&self){
+        let mut state = self.queue.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.stats = QueueStats{ capacity: state.capacity, ..QueueStats::default() };
+    }
+
+    /// Calls handler with the total number of dropped samples every
+    /// time one is dropped. It's called from the streaming thread
+    pub fn connect_drop<F: FnMut(u64) + Send + 'static>(&self, handler: F){
+        *self.queue.on_drop.lock().unwrap_or_else(|e| e.into_inner()) = Some(Guarded::new(Box::new(handler)));
+    }
+
+    /// Calls handler with the new high watermark every time the queue
+    /// holds more samples than ever before. It's called from the
+    /// streaming thread
+    pub fn connect_high_watermark<F: FnMut(usize) + Send + 'static>(&self, handler: F){
+        *self.queue.on_high_watermark.lock().unwrap_or_else(|e| e.into_inner()) = Some(Guarded::new(Box::new(handler)));
+    }
+
+    fn pop(&self, state: &mut QueueState) -> Option<Sample>{
+        let sample = state.samples.pop_front();
+        if sample.is_some(){
+            self.queue.not_full.notify_all();
+        }
+        sample
+    }
+}
+
+impl Drop for BoundedAppSink{
+    fn drop(&mut self){
+        let mut state = self.queue.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.closed = true;
+        state.samples.clear();
+        self.queue.not_full.notify_all();
+    }
+}
+
+impl Deref for BoundedAppSink{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.appsink
+    }
+}
+
+impl DerefMut for BoundedAppSink{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.appsink
+    }
+}
+
+extern "C" fn on_new_sample_to_queue(elt: *mut GstAppSink, data: gpointer) -> GstFlowReturn{
+    unsafe{
+        let data = &*(data as *const QueueCallbackData);
+        let queue = &data.queue;
+        let sample = match Sample::new(gst_app_sink_pull_sample(elt)){
+            Some(sample) => sample,
+            None => return GST_FLOW_EOS,
+        };
+        let mut dropped = None;
+        let mut high_watermark = None;
+        {
+            let mut state = queue.state.lock().unwrap_or_else(|e| e.into_inner());
+            // samples after a flushing seek following EOS
+            state.eos = false;
+            state.stats.received += 1;
+            let mut blocked = false;
+            while !state.closed && state.samples.len() >= state.capacity{
+                match state.policy{
+                    OverflowPolicy::DropOldest => {
+                        if state.samples.pop_front().is_none(){
+                            // capacity 0, nothing to make room for
+                            break;
+                        }
+                        state.stats.drops += 1;
+                        dropped = Some(state.stats.drops);
+                    }
+                    OverflowPolicy::Block => {
+                        if !blocked{
+                            state.stats.blocked += 1;
+                            blocked = true;
+                        }
+                        // wake up regularly to not deadlock a state
+                        // change that deactivates the pad
+                        state = queue.not_full.wait_timeout(state, Duration::from_millis(100)).unwrap_or_else(|e| e.into_inner()).0;
+                        let pad = data.sink_pad as *mut GstObject;
+                        if pad != ptr::null_mut() && (*pad).flags & GST_PAD_FLAG_FLUSHING != 0{
+                            return GST_FLOW_FLUSHING;
+                        }
+                    }
+                }
+            }
+            if state.closed{
+                return GST_FLOW_EOS;
+            }
+            if state.capacity == 0{
+                state.stats.drops += 1;
+                dropped = Some(state.stats.drops);
+            }else{
+                state.samples.push_back(sample);
+                if state.samples.len() > state.stats.high_watermark{
+                    state.stats.high_watermark = state.samples.len();
+                    high_watermark = Some(state.stats.high_watermark);
+                }
+                queue.not_empty.notify_all();
+            }
+        }
+        if let Some(drops) = dropped{
+            if let Some(ref mut handler) = *queue.on_drop.lock().unwrap_or_else(|e| e.into_inner()){
+                handler.call(elt as *mut GstElement, (), |handler| handler(drops));
+            }
+        }
+        if let Some(high_watermark) = high_watermark{
+            if let Some(ref mut handler) = *queue.on_high_watermark.lock().unwrap_or_else(|e| e.into_inner()){
+                handler.call(elt as *mut GstElement, (), |handler| handler(high_watermark));
+            }
+        }
+        GST_FLOW_OK
+    }
+}
+
+extern "C" fn on_eos_to_queue(_elt: *mut GstAppSink, data: gpointer){
+    unsafe{
+        let queue = &(*(data as *const QueueCallbackData)).queue;
+        queue.state.lock().unwrap_or_else(|e| e.into_inner()).eos = true;
+        queue.not_empty.notify_all();
+    }
+}
+
+extern "C" fn destroy_queue_data(data: gpointer){
+    unsafe{
+        drop(Box::from_raw(data as *mut QueueCallbackData));
+    }
+}
+
+/// Stream of the samples of an appsink, returned by AppSink::samples.
+/// Derefs to the appsink element to configure it or change its state
+#[cfg(feature = "futures")]
+pub struct SampleStream{
+    appsink: Element,
+    receiver: UnboundedReceiver<Sample>,
+}
+
+#[cfg(feature = "futures")]
+impl Stream for SampleStream{
+    type Item = Sample;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Sample>>{
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl Deref for SampleStream{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.appsink
+    }
+}
+
+#[cfg(feature = "futures")]
+impl DerefMut for SampleStream{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.appsink
+    }
+}
+
+#[cfg(feature = "futures")]
+extern "C" fn on_new_sample_to_stream(elt: *mut GstAppSink, data: gpointer) -> GstFlowReturn{
+    unsafe{
+        let sender = &*(data as *const UnboundedSender<Sample>);
+        match Sample::new(gst_app_sink_pull_sample(elt)){
+            Some(sample) => match sender.unbounded_send(sample){
+                Ok(()) => GST_FLOW_OK,
+                Err(_) => GST_FLOW_EOS
+            },
+            None => GST_FLOW_EOS
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+extern "C" fn on_eos_to_stream(_elt: *mut GstAppSink, data: gpointer){
+    unsafe{
+        let sender = &*(data as *const UnboundedSender<Sample>);
+        sender.close_channel();
+    }
+}
+
+#[cfg(feature = "futures")]
+extern "C" fn destroy_stream_sender(data: gpointer){
+    unsafe{
+        drop(Box::from_raw(data as *mut UnboundedSender<Sample>));
+    }
 }
 
 extern "C" fn on_new_sample_from_source (elt: *mut GstAppSink, data: gpointer ) -> GstFlowReturn{
@@ -193,7 +656,7 @@ extern "C" fn on_new_preroll_from_source (elt: *mut GstAppSink, data: gpointer)
 extern "C" fn on_eos_from_source (_elt: *mut GstAppSink, data: gpointer){
     unsafe{
 		let sender = data as *mut Sender<Message>;
-        (*sender).send(Message::Eos).unwrap();
+        let _ = (*sender).send(Message::Eos);
     }
 }
 
@@ -2,18 +2,17 @@ use ffi::*;
 use ::Transfer;
 use ::Element;
 use ::Caps;
-use std::mem;
 use reference::Reference;
+use version::ApiAvailability;
 
 use std::ops::{Deref, DerefMut};
 
+const PUSH_BUFFER_LIST: ApiAvailability = ApiAvailability::new("AppSrc::push_buffer_list", 1, 14, 0);
+
 pub struct AppSrc{
     appsrc: ::Element
 }
 
-unsafe impl Sync for AppSrc {}
-unsafe impl Send for AppSrc {}
-
 impl AppSrc{
     pub fn new(name: &str) -> Option<AppSrc>{
         let appsrc = ::Element::new("appsrc",name);
@@ -43,7 +42,7 @@ impl AppSrc{
 
     pub fn caps(&self) -> Option<Caps>{
         unsafe{
-	        let gst_caps = gst_app_src_get_caps(mem::transmute(self.gst_appsrc()));
+	        let gst_caps = gst_app_src_get_caps(self.gst_appsrc() as *mut GstAppSrc);
 	        Caps::new(gst_caps)
 	    }
     }
@@ -52,7 +51,7 @@ impl AppSrc{
         unsafe{
             let mut min: u64 = 0;
             let mut max: u64 = 0;
-            gst_app_src_get_latency(mem::transmute(self.gst_appsrc()), &mut min, &mut max);
+            gst_app_src_get_latency(self.gst_appsrc() as *mut GstAppSrc, &mut min, &mut max);
             (min,max)
         }
     }
@@ -63,6 +62,26 @@ impl AppSrc{
         }
     }
 
+    /// Pushes all the buffers of list with a single call, eg: the
+    /// packets of a frame. With GStreamer older than 1.14 the buffers
+    /// are pushed one by one instead
+    pub fn push_buffer_list(&mut self, list: ::BufferList) -> GstFlowReturn{
+        unsafe{
+            match PUSH_BUFFER_LIST.function::<unsafe extern "C" fn(*mut GstAppSrc, *mut GstBufferList) -> GstFlowReturn>("gst_app_src_push_buffer_list"){
+                Ok(push_buffer_list) => push_buffer_list(self.gst_appsrc_mut(), list.transfer()),
+                Err(_) => {
+                    for buffer in list.iter(){
+                        let ret = self.push_buffer(buffer);
+                        if ret != GST_FLOW_OK{
+                            return ret;
+                        }
+                    }
+                    GST_FLOW_OK
+                }
+            }
+        }
+    }
+
     pub fn end_of_stream(&mut self) -> GstFlowReturn{
         unsafe{
             gst_app_src_end_of_stream(self.gst_appsrc_mut())
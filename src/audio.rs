@@ -0,0 +1,154 @@
+use ffi::*;
+use appsrc::AppSrc;
+use audioinfo::AudioInfo;
+use buffer::Buffer;
+use element::Element;
+use pad::Pad;
+use pipeline::Pipeline;
+use reference::Reference;
+use sample::Sample;
+use error::{Error, Result};
+use version::ApiAvailability;
+
+use std::path::Path;
+
+// the appsink is polled with this timeout, in nanoseconds, so errors
+// posted on the bus are noticed while waiting for samples
+const PULL_TIMEOUT: GstClockTime = 100_000_000;
+
+const TRY_PULL_SAMPLE: ApiAvailability = ApiAvailability::new("decode_audio_file", 1, 10, 0);
+
+/// Where decode_audio_file reads the encoded audio from
+pub enum AudioInput<'a>{
+    File(&'a Path),
+    /// The whole encoded file in memory
+    Data(&'a [u8]),
+}
+
+impl<'a> From<&'a Path> for AudioInput<'a>{
+    fn from(path: &'a Path) -> AudioInput<'a>{
+        AudioInput::File(path)
+    }
+}
+
+impl<'a> From<&'a str> for AudioInput<'a>{
+    fn from(path: &'a str) -> AudioInput<'a>{
+        AudioInput::File(Path::new(path))
+    }
+}
+
+impl<'a> From<&'a [u8]> for AudioInput<'a>{
+    fn from(data: &'a [u8]) -> AudioInput<'a>{
+        AudioInput::Data(data)
+    }
+}
+
+impl<'a> From<&'a Vec<u8>> for AudioInput<'a>{
+    fn from(data: &'a Vec<u8>) -> AudioInput<'a>{
+        AudioInput::Data(data)
+    }
+}
+
+/// Decodes the first audio stream of a file, or of its contents in
+/// memory, to interleaved samples in the format, rate and channels of
+/// info:
+///
+/// ```ignore
+/// let info = gst::AudioInfo::new(gst::ffi::GST_AUDIO_FORMAT_F32LE, 48000, 2);
+/// let data = gst::audio::decode_audio_file("/tmp/music.ogg", &info).unwrap();
+/// let samples: &[f32] = unsafe{ std::slice::from_raw_parts(data.as_ptr() as *const f32, data.len() / 4) };
+/// ```
+pub fn decode_audio_file<'a, I: Into<AudioInput<'a>>>(input: I, info: &AudioInfo) -> Result<Vec<u8>>{
+    let mut samples = vec![];
+    decode_audio_file_with(input, info, |data| {
+        samples.extend_from_slice(data);
+        true
+    })?;
+    Ok(samples)
+}
+
+/// Same as decode_audio_file but calls f with each chunk of decoded
+/// samples as they are produced instead of collecting the whole file.
+/// Decoding stops when f returns false.
+///
+/// Both need GStreamer 1.10, they return an unsupported error with
+/// older versions
+pub fn decode_audio_file_with<'a, I, F>(input: I, info: &AudioInfo, mut f: F) -> Result<()>
+    where I: Into<AudioInput<'a>>, F: FnMut(&[u8]) -> bool{
+    let try_pull_sample: unsafe extern "C" fn(*mut GstAppSink, GstClockTime) -> *mut GstSample =
+        unsafe{ TRY_PULL_SAMPLE.function("gst_app_sink_try_pull_sample")? };
+    let caps = info.to_caps().ok_or(Error::new(0, 0, "invalid audio info"))?;
+    let mut pipeline = Pipeline::new("decode_audio").ok_or(Error::new(0, 0, "couldn't create pipeline"))?;
+    let mut decodebin = Element::new("decodebin", "decoder").ok_or(Error::new(0, 0, "couldn't create decodebin"))?;
+    let mut convert = Element::new("audioconvert", "convert").ok_or(Error::new(0, 0, "couldn't create audioconvert"))?;
+    let mut resample = Element::new("audioresample", "resample").ok_or(Error::new(0, 0, "couldn't create audioresample"))?;
+    let mut appsink = Element::new("appsink", "sink").ok_or(Error::new(0, 0, "couldn't create appsink"))?;
+    appsink.set("caps", &caps);
+    appsink.set("sync", false as gboolean);
+
+    let mut src = match input.into(){
+        AudioInput::File(path) => {
+            let mut filesrc = Element::new("filesrc", "src").ok_or(Error::new(0, 0, "couldn't create filesrc"))?;
+            filesrc.set("location", path.to_str().ok_or(Error::new(0, 0, "invalid path"))?);
+            filesrc
+        }
+        AudioInput::Data(data) => {
+            let mut appsrc = AppSrc::new("src").ok_or(Error::new(0, 0, "couldn't create appsrc"))?;
+            let buffer = Buffer::from_slice_zero_copy(data.to_vec()).ok_or(Error::new(0, 0, "couldn't create buffer"))?;
+            appsrc.push_buffer(buffer);
+            appsrc.end_of_stream();
+            appsrc.into()
+        }
+    };
+
+    let mut converter = convert.reference();
+    let handle = decodebin.connect_values("pad-added", move |args| {
+        if let Some(mut pad) = args.get(1).and_then(|pad| pad.get::<Pad>()){
+            let is_audio = pad.query_caps(None)
+                .and_then(|caps| caps.structure(0).map(|structure| structure.name().starts_with("audio/")))
+                .unwrap_or(false);
+            if let Some(mut sink) = converter.static_pad("sink"){
+                if is_audio && !sink.is_linked(){
+                    let _ = pad.link(&mut sink);
+                }
+            }
+        }
+        None
+    });
+    if let Some(handle) = handle{
+        handle.forget();
+    }
+
+    if !pipeline.add(src.reference()) || !pipeline.add(decodebin.reference()) || !pipeline.add(convert.reference())
+        || !pipeline.add(resample.reference()) || !pipeline.add(appsink.reference()){
+        return Err(Error::new(0, 0, "couldn't add elements to the pipeline"));
+    }
+    if !src.link(&mut decodebin) || !Element::link_many(&[&mut convert, &mut resample, &mut appsink]){
+        return Err(Error::new(0, 0, "couldn't link elements"));
+    }
+
+    let mut bus = pipeline.bus().ok_or(Error::new(0, 0, "couldn't get pipeline bus"))?;
+    pipeline.play();
+    let result = loop{
+        if let Some(err) = bus.pop_error(){
+            break Err(err);
+        }
+        let sample = unsafe{
+            Sample::new(try_pull_sample(appsink.gst_element() as *mut GstAppSink, PULL_TIMEOUT))
+        };
+        match sample.and_then(|sample| sample.buffer()){
+            Some(buffer) => {
+                if !buffer.map_read(|mapinfo| f(mapinfo.data::<u8>())).unwrap_or(true){
+                    break Ok(());
+                }
+            }
+            None => unsafe{
+                if gst_app_sink_is_eos(appsink.gst_element() as *mut GstAppSink) != 0{
+                    break Ok(());
+                }
+            }
+        }
+    };
+    pipeline.set_null_state();
+    result
+}
@@ -0,0 +1,178 @@
+use ffi::*;
+use util::*;
+use audioinfo::AudioInfo;
+use error::{Error, Result};
+use version::ApiAvailability;
+
+const AUDIO_CONVERTER: ApiAvailability = ApiAvailability::new("AudioConverter", 1, 8, 0);
+
+unsafe fn converter_fn<F: Copy>(symbol: &str) -> F{
+    AUDIO_CONVERTER.function(symbol).expect("AudioConverter without GStreamer 1.8")
+}
+
+/// Converts interleaved raw audio between formats, rates and channel
+/// layouts outside of a pipeline, eg: to play samples captured by a
+/// pipeline with an audio library that needs a fixed format:
+///
+/// ```ignore
+/// let in_info = match appsink.negotiated_info(){
+///     Some(gst::appsink::MediaInfo::Audio(info)) => info,
+///     _ => return,
+/// };
+/// let out_info = gst::AudioInfo::new(gst::ffi::GST_AUDIO_FORMAT_F32LE, 48000, 2);
+/// let mut converter = gst::AudioConverter::new(&in_info, &out_info).unwrap();
+/// let data = buffer.map_read(|map| converter.convert(map.data::<u8>())).unwrap()?;
+/// ```
+///
+/// When resampling, the converter keeps up to max_latency() frames of
+/// history, so the output of each call lags the input by that much.
+pub struct AudioConverter{
+    converter: *mut GstAudioConverter,
+    in_info: AudioInfo,
+    out_info: AudioInfo,
+}
+
+unsafe impl Send for AudioConverter{}
+
+impl AudioConverter{
+    /// None if the formats can't be converted, eg: they aren't
+    /// interleaved. Needs GStreamer 1.8, returns None with older versions
+    pub fn new(in_info: &AudioInfo, out_info: &AudioInfo) -> Option<AudioConverter>{
+        if !in_info.is_interleaved() || !out_info.is_interleaved(){
+            return None;
+        }
+        let mut in_info = *in_info;
+        let mut out_info = *out_info;
+        let flags = if in_info.rate() != out_info.rate(){
+            GST_AUDIO_CONVERTER_FLAG_VARIABLE_RATE
+        }else{
+            GST_AUDIO_CONVERTER_FLAG_NONE
+        };
+        unsafe{
+            let converter_new: unsafe extern "C" fn(GstAudioConverterFlags, *mut GstAudioInfo, *mut GstAudioInfo, *mut GstStructure) -> *mut GstAudioConverter
+                = AUDIO_CONVERTER.function("gst_audio_converter_new").ok()?;
+            let converter = converter_new(flags, &mut in_info, &mut out_info, ptr::null_mut());
+            if converter == ptr::null_mut(){
+                None
+            }else{
+                Some(AudioConverter{
+                    converter: converter,
+                    in_info: in_info,
+                    out_info: out_info,
+                })
+            }
+        }
+    }
+
+    pub fn in_info(&self) -> &AudioInfo{
+        &self.in_info
+    }
+
+    pub fn out_info(&self) -> &AudioInfo{
+        &self.out_info
+    }
+
+    /// Converts input, which has to contain whole frames, returning the
+    /// converted frames
+    pub fn convert(&mut self, input: &[u8]) -> Result<Vec<u8>>{
+        let in_frames = self.frames(input)?;
+        let out_frames = self.out_frames(in_frames);
+        let mut output = vec![0u8; out_frames * self.out_info.bpf() as usize];
+        let written = self.convert_into(input, &mut output)?;
+        output.truncate(written);
+        Ok(output)
+    }
+
+    /// Same as convert but writing into output, which has to have room
+    /// for out_frames() of the input. Returns the number of bytes
+    /// written
+    pub fn convert_into(&mut self, input: &[u8], output: &mut [u8]) -> Result<usize>{
+        let in_frames = self.frames(input)?;
+        let out_frames = self.out_frames(in_frames);
+        let out_size = out_frames * self.out_info.bpf() as usize;
+        if output.len() < out_size{
+            return Err(Error::new(0, 0, &format!("the output needs {} bytes but has {}", out_size, output.len())));
+        }
+        unsafe{
+            let mut in_ptr = input.as_ptr() as gpointer;
+            let mut out_ptr = output.as_mut_ptr() as gpointer;
+            let samples: unsafe extern "C" fn(*mut GstAudioConverter, GstAudioConverterFlags, *mut gpointer, gsize, *mut gpointer, gsize) -> gboolean
+                = converter_fn("gst_audio_converter_samples");
+            if samples(self.converter, GST_AUDIO_CONVERTER_FLAG_NONE, &mut in_ptr, in_frames as gsize, &mut out_ptr, out_frames as gsize) == 0{
+                return Err(Error::new(0, 0, "couldn't convert the samples"));
+            }
+        }
+        Ok(out_size)
+    }
+
+    /// Number of frames converting in_frames produces
+    pub fn out_frames(&self, in_frames: usize) -> usize{
+        unsafe{
+            let get_out_frames: unsafe extern "C" fn(*mut GstAudioConverter, gsize) -> gsize
+                = converter_fn("gst_audio_converter_get_out_frames");
+            get_out_frames(self.converter, in_frames as gsize) as usize
+        }
+    }
+
+    /// Number of input frames needed to produce out_frames
+    pub fn in_frames(&self, out_frames: usize) -> usize{
+        unsafe{
+            let get_in_frames: unsafe extern "C" fn(*mut GstAudioConverter, gsize) -> gsize
+                = converter_fn("gst_audio_converter_get_in_frames");
+            get_in_frames(self.converter, out_frames as gsize) as usize
+        }
+    }
+
+    /// Frames the output lags the input because of the resampler
+    pub fn max_latency(&self) -> usize{
+        unsafe{
+            let get_max_latency: unsafe extern "C" fn(*mut GstAudioConverter) -> gsize
+                = converter_fn("gst_audio_converter_get_max_latency");
+            get_max_latency(self.converter) as usize
+        }
+    }
+
+    /// Changes the rates, only possible when the converter was created
+    /// with different input and output rates, eg: to compensate the
+    /// drift between the capture and the playback clocks
+    pub fn set_rates(&mut self, in_rate: i32, out_rate: i32) -> bool{
+        unsafe{
+            let update_config: unsafe extern "C" fn(*mut GstAudioConverter, gint, gint, *mut GstStructure) -> gboolean
+                = converter_fn("gst_audio_converter_update_config");
+            update_config(self.converter, in_rate, out_rate, ptr::null_mut()) != 0
+        }
+    }
+
+    /// Discards the history kept by the resampler, eg: after a seek
+    pub fn reset(&mut self){
+        unsafe{
+            let reset: unsafe extern "C" fn(*mut GstAudioConverter) = converter_fn("gst_audio_converter_reset");
+            reset(self.converter);
+        }
+    }
+
+    fn frames(&self, input: &[u8]) -> Result<usize>{
+        let bpf = self.in_info.bpf() as usize;
+        if bpf == 0 || input.len() % bpf != 0{
+            return Err(Error::new(0, 0, &format!("the input has to be a multiple of {} bytes", bpf)));
+        }
+        Ok(input.len() / bpf)
+    }
+
+    pub unsafe fn gst_audio_converter(&self) -> *const GstAudioConverter{
+        self.converter
+    }
+
+    pub unsafe fn gst_audio_converter_mut(&mut self) -> *mut GstAudioConverter{
+        self.converter
+    }
+}
+
+impl Drop for AudioConverter{
+    fn drop(&mut self){
+        unsafe{
+            let free: unsafe extern "C" fn(*mut GstAudioConverter) = converter_fn("gst_audio_converter_free");
+            free(self.converter);
+        }
+    }
+}
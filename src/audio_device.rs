@@ -0,0 +1,60 @@
+use element::Element;
+use device_monitor::{Device, DeviceMonitor};
+
+/// Lists the audio output devices in the system, as reported by the
+/// platform's device provider: pulseaudio on linux, wasapi on windows
+/// and core audio on macOS
+pub fn output_devices() -> Vec<Device>{
+    match DeviceMonitor::new(){
+        Some(mut monitor) => {
+            monitor.add_filter("Audio/Sink", None);
+            monitor.devices()
+        }
+        None => vec![]
+    }
+}
+
+/// Returns the first output device whose display name contains name
+pub fn find_output_device(name: &str) -> Option<Device>{
+    output_devices().into_iter().find(|device| device.display_name().contains(name))
+}
+
+/// Creates an audio sink that plays to device, or to the system's
+/// default output if device is None.
+///
+/// The element is created by the device provider so it's the right sink
+/// for the platform, pulsesink, wasapisink, osxaudiosink..., with its
+/// device property already set to the chosen device. When no device is
+/// passed the native sink is used, falling back to autoaudiosink.
+pub fn create_sink(device: Option<&Device>, name: &str) -> Option<Element>{
+    match device{
+        Some(device) => device.create_element(name),
+        None => native_sinks().iter()
+            .filter_map(|factory| Element::new(factory, name))
+            .next()
+    }
+}
+
+/// Switches a sink previously created with create_sink to play to a
+/// different device. Returns false if the sink can't be reconfigured,
+/// for example because it was created for a device of another provider,
+/// in which case a new sink has to be created and swapped in with
+/// Bin::replace_element.
+pub fn set_output_device(sink: &mut Element, device: &Device) -> bool{
+    device.reconfigure_element(sink)
+}
+
+#[cfg(target_os="windows")]
+fn native_sinks() -> &'static [&'static str]{
+    &["wasapisink", "directsoundsink", "autoaudiosink"]
+}
+
+#[cfg(target_os="macos")]
+fn native_sinks() -> &'static [&'static str]{
+    &["osxaudiosink", "autoaudiosink"]
+}
+
+#[cfg(not(any(target_os="windows", target_os="macos")))]
+fn native_sinks() -> &'static [&'static str]{
+    &["pulsesink", "alsasink", "autoaudiosink"]
+}
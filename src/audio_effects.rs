@@ -0,0 +1,253 @@
+use ffi::*;
+use element::Element;
+use object::Object;
+use reference::Reference;
+
+use std::ops::{Deref, DerefMut};
+
+/// Lowest gain of an equalizer band in dB
+pub const BAND_GAIN_MIN: f64 = -24.;
+/// Highest gain of an equalizer band in dB
+pub const BAND_GAIN_MAX: f64 = 12.;
+
+macro_rules! element_wrapper(
+    ($t: ident, $field: ident) => (
+        impl Reference for $t{
+            fn reference(&self) -> $t{
+                $t{ $field: self.$field.reference() }
+            }
+        }
+
+        impl AsRef<Element> for $t{
+            fn as_ref(&self) -> &Element{
+                &self.$field
+            }
+        }
+
+        impl AsMut<Element> for $t{
+            fn as_mut(&mut self) -> &mut Element{
+                &mut self.$field
+            }
+        }
+
+        impl From<$t> for Element{
+            fn from(s: $t) -> Element{
+                s.$field
+            }
+        }
+
+        impl Deref for $t{
+            type Target = Element;
+            fn deref(&self) -> &Element{
+                &self.$field
+            }
+        }
+
+        impl DerefMut for $t{
+            fn deref_mut(&mut self) -> &mut Element{
+                &mut self.$field
+            }
+        }
+
+        impl ::Transfer for $t{
+            unsafe fn transfer(self) -> *mut GstElement{
+                self.$field.transfer()
+            }
+        }
+    )
+);
+
+/// Wrapper for equalizer-nbands, an equalizer with any number of bands
+/// whose frequencies and bandwidths can be changed, eg: for the
+/// equalizer of a music player:
+///
+/// ```ignore
+/// let mut eq = gst::audio_effects::Equalizer::new("eq", 10).unwrap();
+/// eq.set_gains(&[4., 3., 0., 0., -2., 0., 0., 2., 3., 4.]);
+/// playbin.set("audio-filter", &*eq);
+/// ```
+///
+/// The bands are children of the element, the frequencies are spread
+/// logarithmically when the number of bands changes
+pub struct Equalizer{
+    eq: Element
+}
+
+impl Equalizer{
+    pub fn new(name: &str, bands: u32) -> Option<Equalizer>{
+        let mut eq = Element::new("equalizer-nbands", name).map(Equalizer::new_from_element)?;
+        eq.set_num_bands(bands);
+        Some(eq)
+    }
+
+    pub fn new_from_element(element: Element) -> Equalizer{
+        Equalizer{ eq: element }
+    }
+
+    pub fn num_bands(&self) -> u32{
+        self.get::<u32>("num-bands")
+    }
+
+    /// Changes the number of bands, between 1 and 64. The gains of the
+    /// bands are kept but their frequencies are recomputed
+    pub fn set_num_bands(&mut self, bands: u32){
+        self.set("num-bands", bands.max(1).min(64));
+    }
+
+    /// Gain of band in dB, None if there's no such band
+    pub fn band_gain(&self, band: u32) -> Option<f64>{
+        self.band(band).map(|band| band.get::<f64>("gain"))
+    }
+
+    /// Sets the gain of band in dB, clamped to BAND_GAIN_MIN..BAND_GAIN_MAX.
+    /// Returns false if there's no such band
+    pub fn set_band_gain(&mut self, band: u32, gain: f64) -> bool{
+        self.band(band).map(|mut band| band.set("gain", gain.max(BAND_GAIN_MIN).min(BAND_GAIN_MAX))).is_some()
+    }
+
+    /// Center frequency of band in Hz
+    pub fn band_freq(&self, band: u32) -> Option<f64>{
+        self.band(band).map(|band| band.get::<f64>("freq"))
+    }
+
+    pub fn set_band_freq(&mut self, band: u32, freq: f64) -> bool{
+        self.band(band).map(|mut band| band.set("freq", freq)).is_some()
+    }
+
+    /// Width of band in Hz
+    pub fn band_bandwidth(&self, band: u32) -> Option<f64>{
+        self.band(band).map(|band| band.get::<f64>("bandwidth"))
+    }
+
+    pub fn set_band_bandwidth(&mut self, band: u32, bandwidth: f64) -> bool{
+        self.band(band).map(|mut band| band.set("bandwidth", bandwidth)).is_some()
+    }
+
+    /// Gains of all the bands in dB
+    pub fn gains(&self) -> Vec<f64>{
+        (0..self.num_bands()).filter_map(|band| self.band_gain(band)).collect()
+    }
+
+    /// Sets the gains of the first bands, eg: from a preset. Bands past
+    /// the end of gains are left untouched
+    pub fn set_gains(&mut self, gains: &[f64]){
+        for (band, gain) in gains.iter().enumerate(){
+            self.set_band_gain(band as u32, *gain);
+        }
+    }
+
+    /// Sets the gain of all the bands to 0dB
+    pub fn reset(&mut self){
+        for band in 0..self.num_bands(){
+            self.set_band_gain(band, 0.);
+        }
+    }
+
+    fn band(&self, band: u32) -> Option<Object>{
+        self.child_by_index(band)
+    }
+}
+
+element_wrapper!(Equalizer, eq);
+
+/// Wrapper for the soundtouch pitch element, which changes the pitch
+/// and the tempo of audio independently
+pub struct Pitch{
+    pitch: Element
+}
+
+impl Pitch{
+    pub fn new(name: &str) -> Option<Pitch>{
+        Element::new("pitch", name).map(Pitch::new_from_element)
+    }
+
+    pub fn new_from_element(element: Element) -> Pitch{
+        Pitch{ pitch: element }
+    }
+
+    /// Multiplier of the pitch without changing the tempo, eg: 2.0 for
+    /// an octave up
+    pub fn set_pitch(&mut self, pitch: f32){
+        self.set("pitch", pitch as f64);
+    }
+
+    pub fn pitch(&self) -> f32{
+        self.get::<f32>("pitch")
+    }
+
+    /// Multiplier of the speed without changing the pitch
+    pub fn set_tempo(&mut self, tempo: f32){
+        self.set("tempo", tempo as f64);
+    }
+
+    pub fn tempo(&self) -> f32{
+        self.get::<f32>("tempo")
+    }
+
+    /// Multiplier of both the speed and the pitch, like playing a tape
+    /// faster
+    pub fn set_rate(&mut self, rate: f32){
+        self.set("rate", rate as f64);
+    }
+
+    pub fn rate(&self) -> f32{
+        self.get::<f32>("rate")
+    }
+
+    /// Shifts the pitch by semitones without changing the tempo
+    pub fn set_semitones(&mut self, semitones: f32){
+        self.set_pitch(2f32.powf(semitones / 12.));
+    }
+}
+
+element_wrapper!(Pitch, pitch);
+
+/// Wrapper for scaletempo, which keeps the pitch of audio when the
+/// playback rate of the pipeline changes, eg: for a 1.5x speed control
+/// in a podcast player. It follows the rate of the segment so the speed
+/// is changed with a seek, see Element::set_trick_rate, and only the
+/// quality of the stretching is configured here:
+///
+/// ```ignore
+/// let tempo = gst::audio_effects::ScaleTempo::new("tempo").unwrap();
+/// playbin.set("audio-filter", &*tempo);
+/// playbin.set_trick_rate(1.5);
+/// ```
+pub struct ScaleTempo{
+    tempo: Element
+}
+
+impl ScaleTempo{
+    pub fn new(name: &str) -> Option<ScaleTempo>{
+        Element::new("scaletempo", name).map(ScaleTempo::new_from_element)
+    }
+
+    pub fn new_from_element(element: Element) -> ScaleTempo{
+        ScaleTempo{ tempo: element }
+    }
+
+    /// The current playback rate
+    pub fn rate(&self) -> f64{
+        self.get::<f64>("rate")
+    }
+
+    /// Length in milliseconds of the chunks of audio stretched at a
+    /// time, 30 by default
+    pub fn set_stride(&mut self, stride_ms: u32){
+        self.set("stride", stride_ms);
+    }
+
+    /// Proportion of each chunk that's blended with the next one,
+    /// between 0.0 and 1.0, 0.2 by default
+    pub fn set_overlap(&mut self, overlap: f64){
+        self.set("overlap", overlap.max(0.).min(1.));
+    }
+
+    /// Length in milliseconds to search for the best overlap position,
+    /// 14 by default. Longer sounds better but uses more cpu
+    pub fn set_search(&mut self, search_ms: u32){
+        self.set("search", search_ms);
+    }
+}
+
+element_wrapper!(ScaleTempo, tempo);
@@ -0,0 +1,103 @@
+use ffi::*;
+use util::*;
+use ::Caps;
+use version::ApiAvailability;
+
+pub type AudioInfo = GstAudioInfo;
+
+const IS_EQUAL: ApiAvailability = ApiAvailability::new("AudioInfo::eq", 1, 2, 0);
+
+impl AudioInfo{
+    /// Creates the info for raw audio in format, eg:
+    /// GST_AUDIO_FORMAT_S16LE, with the default channel positions
+    pub fn new(format: GstAudioFormat, rate: i32, channels: i32) -> AudioInfo{
+        unsafe{
+            let mut info = mem::zeroed();
+            gst_audio_info_init(&mut info);
+            gst_audio_info_set_format(&mut info, format, rate, channels, ptr::null());
+            info
+        }
+    }
+
+    #[inline]
+    pub fn format_info(&self) -> &GstAudioFormatInfo{
+        unsafe{ &(*self.finfo) }
+    }
+
+    pub fn format(&self) -> GstAudioFormat{
+        self.format_info().format
+    }
+
+    /// The format as in caps, eg: "S16LE" or "F32LE"
+    pub fn format_name(&self) -> String{
+        unsafe{ from_c_str!(self.format_info().name).to_string() }
+    }
+
+    pub fn is_integer(&self) -> bool{
+        self.format_info().flags & GST_AUDIO_FORMAT_FLAG_INTEGER == GST_AUDIO_FORMAT_FLAG_INTEGER
+    }
+
+    pub fn is_float(&self) -> bool{
+        self.format_info().flags & GST_AUDIO_FORMAT_FLAG_FLOAT == GST_AUDIO_FORMAT_FLAG_FLOAT
+    }
+
+    pub fn is_signed(&self) -> bool{
+        self.format_info().flags & GST_AUDIO_FORMAT_FLAG_SIGNED == GST_AUDIO_FORMAT_FLAG_SIGNED
+    }
+
+    /// Bits used to store each sample
+    pub fn width(&self) -> i32{
+        self.format_info().width
+    }
+
+    /// Bits of each sample that are actually used
+    pub fn depth(&self) -> i32{
+        self.format_info().depth
+    }
+
+    pub fn flags(&self) -> GstAudioFlags{
+        self.flags
+    }
+
+    pub fn layout(&self) -> GstAudioLayout{
+        self.layout
+    }
+
+    pub fn is_interleaved(&self) -> bool{
+        self.layout == GST_AUDIO_LAYOUT_INTERLEAVED
+    }
+
+    pub fn rate(&self) -> i32{
+        self.rate
+    }
+
+    pub fn channels(&self) -> i32{
+        self.channels
+    }
+
+    /// Bytes per frame, the size of one sample of every channel
+    pub fn bpf(&self) -> i32{
+        self.bpf
+    }
+
+    pub fn to_caps(&self) -> Option<::Caps>{
+        unsafe{ Caps::new(gst_audio_info_to_caps(self)) }
+    }
+}
+
+impl PartialEq for AudioInfo{
+    fn eq(&self, other: &AudioInfo) -> bool{
+        unsafe{
+            match IS_EQUAL.function::<unsafe extern "C" fn(*const GstAudioInfo, *const GstAudioInfo) -> gboolean>("gst_audio_info_is_equal"){
+                Ok(is_equal) => is_equal(self, other) != 0,
+                // what gst_audio_info_is_equal compares, the format info
+                // is static so it's the same pointer for the same format
+                Err(_) => self.finfo == other.finfo && self.flags == other.flags && self.layout == other.layout
+                    && self.rate == other.rate && self.channels == other.channels && self.bpf == other.bpf
+                    && self.position[..self.channels.max(0).min(64) as usize] == other.position[..other.channels.max(0).min(64) as usize],
+            }
+        }
+    }
+}
+
+impl Eq for AudioInfo{}
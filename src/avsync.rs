@@ -0,0 +1,416 @@
+use ffi::*;
+use util::*;
+
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use segment::Segment;
+use handle::ProbeHandle;
+use element::Element;
+use reference::Reference;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+// skew samples kept for history()
+const MAX_HISTORY: usize = 1000;
+
+/// A skew measurement
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct SkewSample{
+    /// Running time of the video buffer it was measured at, in
+    /// nanoseconds
+    pub running_time: u64,
+    /// How far ahead audio is from video in nanoseconds, negative if
+    /// audio is behind
+    pub skew: i64,
+}
+
+/// Summary of the skew measured by an AvSyncMonitor, all in nanoseconds
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct AvSyncStats{
+    pub samples: u64,
+    pub current: Option<i64>,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub average: Option<i64>,
+}
+
+#[derive(Default)]
+struct SyncState{
+    // running time of the end of the last buffer of each branch and when
+    // it went through the pad
+    audio: Option<(Instant, u64)>,
+    video: Option<(Instant, u64)>,
+    stats: AvSyncStats,
+    total: i64,
+    history: VecDeque<SkewSample>,
+}
+
+impl SyncState{
+    fn update(&mut self){
+        let (video_instant, video_time) = match self.video{
+            Some(video) => video,
+            None => return,
+        };
+        let (audio_instant, audio_time) = match self.audio{
+            Some(audio) => audio,
+            None => return,
+        };
+        // audio progresses in real time from its last buffer until the
+        // video one arrived
+        let elapsed = video_instant.duration_since(audio_instant);
+        let elapsed = elapsed.as_secs() * 1_000_000_000 + elapsed.subsec_nanos() as u64;
+        let skew = (audio_time + elapsed) as i64 - video_time as i64;
+
+        self.total += skew;
+        self.stats.samples += 1;
+        self.stats.current = Some(skew);
+        self.stats.min = Some(self.stats.min.map_or(skew, |min| min.min(skew)));
+        self.stats.max = Some(self.stats.max.map_or(skew, |max| max.max(skew)));
+        self.stats.average = Some(self.total / self.stats.samples as i64);
+        if self.history.len() == MAX_HISTORY{
+            self.history.pop_front();
+        }
+        self.history.push_back(SkewSample{ running_time: video_time, skew: skew });
+    }
+}
+
+/// Measures the A/V skew of a pipeline by comparing how the running time
+/// of the buffers of an audio and a video branch progresses:
+///
+/// ```ignore
+/// let mut audio_pad = audiosink.static_pad("sink").unwrap();
+/// let mut video_pad = videosink.static_pad("sink").unwrap();
+/// let monitor = gst::avsync::AvSyncMonitor::attach(&mut audio_pad, &mut video_pad);
+/// pipeline.play();
+/// // later
+/// let stats = monitor.stats();
+/// println!("skew {:?}ms, worst {:?}ms", stats.current.map(|s| s / 1_000_000), stats.max.map(|s| s / 1_000_000));
+/// ```
+///
+/// The skew is measured each time a video buffer arrives, assuming audio
+/// progresses in real time since its last buffer, so it's only
+/// meaningful while PLAYING and with pads close to the sinks, where
+/// buffers are paced by the clock. A constant offset usually comes from
+/// latency not being reported, a growing one from clock drift.
+pub struct AvSyncMonitor{
+    state: Arc<Mutex<SyncState>>,
+    _probes: Vec<ProbeHandle>,
+}
+
+impl AvSyncMonitor{
+    pub fn attach(audio_pad: &mut Pad, video_pad: &mut Pad) -> AvSyncMonitor{
+        let state = Arc::new(Mutex::new(SyncState::default()));
+        let mut probes = vec![];
+
+        let audio_state = state.clone();
+        let probe = audio_pad.add_probe(GST_PAD_PROBE_TYPE_BUFFER, move |pad: &mut Pad, info: &mut PadProbeInfo| {
+            if let Some(running_time) = info.buffer().and_then(|buffer| buffer_end_running_time(pad, buffer)){
+                audio_state.lock().unwrap_or_else(|e| e.into_inner()).audio = Some((Instant::now(), running_time));
+            }
+            PadProbeReturn::Ok
+        });
+        probes.extend(probe);
+
+        let video_state = state.clone();
+        let probe = video_pad.add_probe(GST_PAD_PROBE_TYPE_BUFFER, move |pad: &mut Pad, info: &mut PadProbeInfo| {
+            if let Some(running_time) = info.buffer().and_then(|buffer| buffer_end_running_time(pad, buffer)){
+                let mut state = video_state.lock().unwrap_or_else(|e| e.into_inner());
+                state.video = Some((Instant::now(), running_time));
+                state.update();
+            }
+            PadProbeReturn::Ok
+        });
+        probes.extend(probe);
+
+        AvSyncMonitor{
+            state: state,
+            _probes: probes,
+        }
+    }
+
+    pub fn stats(&self) -> AvSyncStats{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).stats
+    }
+
+    /// The last measurements, oldest first, to see how the skew evolves
+    pub fn history(&self) -> Vec<SkewSample>{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).history.iter().cloned().collect()
+    }
+
+    /// Starts measuring again, eg: after a seek
+    pub fn reset(&self){
+        *self.state.lock().unwrap_or_else(|e| e.into_inner()) = SyncState::default();
+    }
+}
+
+/// Running time of the end of buffer in the segment of pad
+fn buffer_end_running_time(pad: &mut Pad, buffer: &::Buffer) -> Option<u64>{
+    let end = buffer.pts()? + buffer.duration().unwrap_or(0);
+    unsafe{
+        let event = gst_pad_get_sticky_event(pad.gst_pad_mut(), GST_EVENT_SEGMENT, 0);
+        let segment = Segment::new_from_event(event);
+        if event != ptr::null_mut(){
+            gst_mini_object_unref(event as *mut GstMiniObject);
+        }
+        segment?.to_running_time(GST_FORMAT_TIME, end)
+    }
+}
+
+/// Timing of the buffers of one stream arriving at its sink, compared to
+/// the clock. Offsets are how late the buffers arrive in nanoseconds,
+/// the clock running time minus their running time, negative when they
+/// arrive early as they should with a prerolled sink
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct StreamTiming{
+    pub buffers: u64,
+    pub offset: Option<i64>,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    /// How much the offset grows per second of running time, in
+    /// nanoseconds. Close to 0 unless the stream drifts from the clock
+    pub drift: Option<f64>,
+}
+
+/// Report of a SyncCheck
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct SyncReport{
+    pub audio: StreamTiming,
+    pub video: StreamTiming,
+    /// How much later audio arrives than video in nanoseconds, negative
+    /// if it arrives earlier
+    pub av_offset: Option<i64>,
+    /// How much av_offset grows per second of running time
+    pub av_drift: Option<f64>,
+}
+
+/// A measurement of a SyncCheck, taken each time a buffer arrives once
+/// both streams have buffers
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct SyncSample{
+    /// Running time of the clock when it was measured
+    pub running_time: u64,
+    pub audio_offset: i64,
+    pub video_offset: i64,
+}
+
+// least squares fit of the offsets against the running time
+#[derive(Default)]
+struct Regression{
+    n: f64,
+    x: f64,
+    y: f64,
+    xx: f64,
+    xy: f64,
+}
+
+impl Regression{
+    fn add(&mut self, running_time: u64, offset: i64){
+        let x = running_time as f64 / 1_000_000_000.;
+        let y = offset as f64;
+        self.n += 1.;
+        self.x += x;
+        self.y += y;
+        self.xx += x * x;
+        self.xy += x * y;
+    }
+
+    fn slope(&self) -> Option<f64>{
+        let denominator = self.n * self.xx - self.x * self.x;
+        if self.n < 2. || denominator.abs() < 1e-9{
+            None
+        }else{
+            Some((self.n * self.xy - self.x * self.y) / denominator)
+        }
+    }
+}
+
+#[derive(Default)]
+struct StreamState{
+    timing: StreamTiming,
+    regression: Regression,
+}
+
+impl StreamState{
+    fn update(&mut self, running_time: u64, offset: i64){
+        self.timing.buffers += 1;
+        self.timing.offset = Some(offset);
+        self.timing.min = Some(self.timing.min.map_or(offset, |min| min.min(offset)));
+        self.timing.max = Some(self.timing.max.map_or(offset, |max| max.max(offset)));
+        self.regression.add(running_time, offset);
+        self.timing.drift = self.regression.slope();
+    }
+}
+
+#[derive(Default)]
+struct CheckState{
+    audio: StreamState,
+    video: StreamState,
+    av: Regression,
+    history: VecDeque<SyncSample>,
+}
+
+impl CheckState{
+    fn update(&mut self, running_time: u64){
+        let (audio_offset, video_offset) = match (self.audio.timing.offset, self.video.timing.offset){
+            (Some(audio), Some(video)) => (audio, video),
+            _ => return,
+        };
+        self.av.add(running_time, audio_offset - video_offset);
+        if self.history.len() == MAX_HISTORY{
+            self.history.pop_front();
+        }
+        self.history.push_back(SyncSample{
+            running_time: running_time,
+            audio_offset: audio_offset,
+            video_offset: video_offset,
+        });
+    }
+
+    fn report(&self) -> SyncReport{
+        SyncReport{
+            audio: self.audio.timing,
+            video: self.video.timing,
+            av_offset: self.audio.timing.offset.and_then(|audio| self.video.timing.offset.map(|video| audio - video)),
+            av_drift: self.av.slope(),
+        }
+    }
+}
+
+/// Lip-sync diagnostics: compares the running time of the buffers
+/// arriving at the audio and the video sinks with the running time of
+/// the clock, to find which stream is late and whether it drifts:
+///
+/// ```ignore
+/// let check = gst::avsync::sync_check(&audiosink, &videosink).unwrap();
+/// pipeline.play();
+/// // later
+/// let report = check.report();
+/// println!("av offset {:?}ms drifting {:?}ns/s", report.av_offset.map(|o| o / 1_000_000), report.av_drift);
+/// if let Some(offset) = report.av_offset{
+///     gst::avsync::set_av_offset(&mut playbin, -offset / 1_000_000);
+/// }
+/// ```
+///
+/// Unlike AvSyncMonitor, which compares the streams with each other,
+/// each stream is measured against the clock so a sink that renders
+/// late or a source whose clock drifts shows up on its own.
+pub struct SyncCheck{
+    state: Arc<Mutex<CheckState>>,
+    _probes: Vec<ProbeHandle>,
+}
+
+/// Installs probes on the sink pads of audio_sink and video_sink,
+/// None if any of them has no sink pad
+pub fn sync_check(audio_sink: &Element, video_sink: &Element) -> Option<SyncCheck>{
+    let mut audio_pad = audio_sink.reference().static_pad("sink")?;
+    let mut video_pad = video_sink.reference().static_pad("sink")?;
+    Some(SyncCheck::attach(&mut audio_pad, &mut video_pad))
+}
+
+impl SyncCheck{
+    /// Same as sync_check but with the pads to measure, eg: when the
+    /// sinks are inside a bin
+    pub fn attach(audio_pad: &mut Pad, video_pad: &mut Pad) -> SyncCheck{
+        let state = Arc::new(Mutex::new(CheckState::default()));
+        let mut probes = vec![];
+
+        let audio_state = state.clone();
+        let probe = audio_pad.add_probe(GST_PAD_PROBE_TYPE_BUFFER, move |pad: &mut Pad, info: &mut PadProbeInfo| {
+            if let Some((now, offset)) = info.buffer().and_then(|buffer| clock_offset(pad, buffer)){
+                let mut state = audio_state.lock().unwrap_or_else(|e| e.into_inner());
+                state.audio.update(now, offset);
+                state.update(now);
+            }
+            PadProbeReturn::Ok
+        });
+        probes.extend(probe);
+
+        let video_state = state.clone();
+        let probe = video_pad.add_probe(GST_PAD_PROBE_TYPE_BUFFER, move |pad: &mut Pad, info: &mut PadProbeInfo| {
+            if let Some((now, offset)) = info.buffer().and_then(|buffer| clock_offset(pad, buffer)){
+                let mut state = video_state.lock().unwrap_or_else(|e| e.into_inner());
+                state.video.update(now, offset);
+                state.update(now);
+            }
+            PadProbeReturn::Ok
+        });
+        probes.extend(probe);
+
+        SyncCheck{
+            state: state,
+            _probes: probes,
+        }
+    }
+
+    pub fn report(&self) -> SyncReport{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).report()
+    }
+
+    /// The last measurements, oldest first
+    pub fn history(&self) -> Vec<SyncSample>{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).history.iter().cloned().collect()
+    }
+
+    /// Starts measuring again, eg: after a seek or changing the offset
+    pub fn reset(&self){
+        *self.state.lock().unwrap_or_else(|e| e.into_inner()) = CheckState::default();
+    }
+}
+
+/// Running time of the clock of the element of pad and how late buffer
+/// arrives compared to it
+fn clock_offset(pad: &mut Pad, buffer: &::Buffer) -> Option<(u64, i64)>{
+    let pts = buffer.pts()?;
+    unsafe{
+        let event = gst_pad_get_sticky_event(pad.gst_pad_mut(), GST_EVENT_SEGMENT, 0);
+        let segment = Segment::new_from_event(event);
+        if event != ptr::null_mut(){
+            gst_mini_object_unref(event as *mut GstMiniObject);
+        }
+        let running_time = segment?.to_running_time(GST_FORMAT_TIME, pts)?;
+
+        let element = gst_pad_get_parent_element(pad.gst_pad_mut());
+        if element == ptr::null_mut(){
+            return None;
+        }
+        let clock = gst_element_get_clock(element);
+        let base_time = gst_element_get_base_time(element);
+        gst_object_unref(element as gpointer);
+        if clock == ptr::null_mut(){
+            return None;
+        }
+        let now = gst_clock_get_time(clock).saturating_sub(base_time);
+        gst_object_unref(clock as gpointer);
+        Some((now, now as i64 - running_time as i64))
+    }
+}
+
+/// Delays audio by ms milliseconds relative to video, negative to make
+/// it play earlier, to correct the offset found with a SyncCheck. Uses
+/// av-offset on playbin and ts-offset on audio sinks, returns false if
+/// element has none of them
+pub fn set_av_offset(element: &mut Element, ms: i64) -> bool{
+    let ns = ms * 1_000_000;
+    if element.get_value("av-offset").is_some(){
+        // playbin delays video for positive offsets
+        element.set("av-offset", -ns);
+        true
+    }else if element.get_value("ts-offset").is_some(){
+        element.set("ts-offset", ns);
+        true
+    }else{
+        false
+    }
+}
+
+/// The offset set with set_av_offset in milliseconds
+pub fn av_offset(element: &Element) -> Option<i64>{
+    if element.get_value("av-offset").is_some(){
+        Some(-element.get::<i64>("av-offset") / 1_000_000)
+    }else if element.get_value("ts-offset").is_some(){
+        Some(element.get::<i64>("ts-offset") / 1_000_000)
+    }else{
+        None
+    }
+}
@@ -0,0 +1,377 @@
+use ffi::*;
+use util::*;
+
+use bin::Bin;
+use caps::Caps;
+use element::Element;
+use pipeline::Pipeline;
+use reference::Reference;
+use handle::SignalHandle;
+use testsrc::{VideoTestSrc, AudioTestSrc, VideoPattern, AudioWave, FakeSink};
+use error::{Error, Result};
+
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Number of buckets of an IntervalHistogram, the last one covers
+/// intervals of 2^(INTERVAL_BUCKETS - 1) microseconds and longer
+pub const INTERVAL_BUCKETS: usize = 24;
+
+/// What a bench source generates
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum TestSrcKind{
+    Video(VideoPattern),
+    Audio(AudioWave),
+}
+
+/// Configuration of a source of synthetic buffers for benchmarks, eg:
+/// 1000 frames of 1080p video as fast as possible:
+///
+/// ```ignore
+/// let src = gst::bench::TestSrcConfig::video(gst::testsrc::VideoPattern::Smpte)
+///     .caps("video/x-raw,format=I420,width=1920,height=1080,framerate=30/1")
+///     .num_buffers(1000)
+///     .build("src")
+///     .unwrap();
+/// ```
+#[derive(Clone,Debug)]
+pub struct TestSrcConfig{
+    kind: TestSrcKind,
+    caps: Option<String>,
+    num_buffers: i32,
+    is_live: bool,
+}
+
+impl TestSrcConfig{
+    pub fn new(kind: TestSrcKind) -> TestSrcConfig{
+        TestSrcConfig{
+            kind: kind,
+            caps: None,
+            num_buffers: -1,
+            is_live: false,
+        }
+    }
+
+    pub fn video(pattern: VideoPattern) -> TestSrcConfig{
+        TestSrcConfig::new(TestSrcKind::Video(pattern))
+    }
+
+    pub fn audio(wave: AudioWave) -> TestSrcConfig{
+        TestSrcConfig::new(TestSrcKind::Audio(wave))
+    }
+
+    /// Format of the generated buffers, eg:
+    /// "video/x-raw,format=NV12,width=1280,height=720"
+    pub fn caps(mut self, caps: &str) -> TestSrcConfig{
+        self.caps = Some(caps.to_string());
+        self
+    }
+
+    /// Buffers to generate before EOS, -1 for unlimited
+    pub fn num_buffers(mut self, num_buffers: i32) -> TestSrcConfig{
+        self.num_buffers = num_buffers;
+        self
+    }
+
+    /// Generates buffers at the rate of the clock instead of as fast as
+    /// possible, to measure the latency of a live pipeline
+    pub fn is_live(mut self, is_live: bool) -> TestSrcConfig{
+        self.is_live = is_live;
+        self
+    }
+
+    /// Creates a bin called name with the source followed by a
+    /// capsfilter, with a "src" ghost pad. None if the caps can't be
+    /// parsed or the elements aren't installed
+    pub fn build(&self, name: &str) -> Option<Bin>{
+        let src: Element = match self.kind{
+            TestSrcKind::Video(pattern) => {
+                let mut src = VideoTestSrc::new("")?;
+                src.set_pattern(pattern);
+                src.set_num_buffers(self.num_buffers);
+                src.set_is_live(self.is_live);
+                src.into()
+            }
+            TestSrcKind::Audio(wave) => {
+                let mut src = AudioTestSrc::new("")?;
+                src.set_wave(wave);
+                src.set_num_buffers(self.num_buffers);
+                src.set_is_live(self.is_live);
+                src.into()
+            }
+        };
+        let caps = match self.caps{
+            Some(ref caps) => Caps::from_string(caps)?,
+            None => Caps::new_any(),
+        };
+        let mut capsfilter = Element::new("capsfilter", "")?;
+        capsfilter.set("caps", &caps);
+
+        let mut bin = Bin::new(name)?;
+        if !bin.add_and_link(src, capsfilter.reference()){
+            return None;
+        }
+        let mut target = capsfilter.static_pad("src")?;
+        let cname = CString::new("src").unwrap();
+        unsafe{
+            let pad = gst_ghost_pad_new(cname.as_ptr(), target.gst_pad_mut());
+            if pad == ptr::null_mut(){
+                return None;
+            }
+            gst_pad_set_active(pad, 1);
+            if gst_element_add_pad(bin.gst_element_mut(), pad) == 0{
+                return None;
+            }
+        }
+        Some(bin)
+    }
+}
+
+/// Histogram of the time between consecutive buffers, bucket i counts
+/// the intervals between 2^i and 2^(i+1) microseconds, the first one
+/// also the shorter ones
+#[derive(Clone,Debug,PartialEq)]
+pub struct IntervalHistogram{
+    pub buckets: [u64; INTERVAL_BUCKETS],
+}
+
+impl IntervalHistogram{
+    fn new() -> IntervalHistogram{
+        IntervalHistogram{ buckets: [0; INTERVAL_BUCKETS] }
+    }
+
+    fn add(&mut self, interval: Duration){
+        let us = duration_ns(interval) / 1000;
+        let bucket = if us == 0 { 0 } else { 63 - us.leading_zeros() as usize };
+        self.buckets[bucket.min(INTERVAL_BUCKETS - 1)] += 1;
+    }
+
+    pub fn count(&self) -> u64{
+        self.buckets.iter().sum()
+    }
+
+    /// Upper bound of the interval below which fraction of the
+    /// intervals are, eg: percentile(0.99). None if there are none
+    pub fn percentile(&self, fraction: f64) -> Option<Duration>{
+        let count = self.count();
+        if count == 0{
+            return None;
+        }
+        let target = (count as f64 * fraction.max(0.).min(1.)).ceil().max(1.) as u64;
+        let mut seen = 0;
+        for (bucket, n) in self.buckets.iter().enumerate(){
+            seen += *n;
+            if seen >= target{
+                return Some(Duration::from_micros(1 << (bucket + 1)));
+            }
+        }
+        None
+    }
+}
+
+/// Measurements of a MeasureSink
+#[derive(Clone,Debug,PartialEq)]
+pub struct BenchReport{
+    pub buffers: u64,
+    pub bytes: u64,
+    /// Time from the first buffer to the last one
+    pub elapsed: Duration,
+    pub mean_interval: Duration,
+    pub max_interval: Duration,
+    /// Standard deviation of the time between buffers
+    pub jitter: Duration,
+    pub intervals: IntervalHistogram,
+}
+
+impl BenchReport{
+    pub fn buffers_per_second(&self) -> f64{
+        let secs = duration_ns(self.elapsed) as f64 / 1e9;
+        if secs > 0.{ self.buffers.saturating_sub(1) as f64 / secs } else { 0. }
+    }
+
+    pub fn bytes_per_second(&self) -> f64{
+        let secs = duration_ns(self.elapsed) as f64 / 1e9;
+        if secs > 0.{ self.bytes as f64 / secs } else { 0. }
+    }
+}
+
+impl Display for BenchReport{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        writeln!(fmt, "{} buffers, {} bytes in {:?}", self.buffers, self.bytes, self.elapsed)?;
+        writeln!(fmt, "{:.1} buffers/s, {:.1} MB/s", self.buffers_per_second(), self.bytes_per_second() / 1e6)?;
+        write!(fmt, "interval mean {:?} max {:?} jitter {:?}", self.mean_interval, self.max_interval, self.jitter)
+    }
+}
+
+struct Measurements{
+    buffers: u64,
+    bytes: u64,
+    first: Option<Instant>,
+    last: Option<Instant>,
+    max_interval: Duration,
+    // running mean and sum of squared differences of the intervals in
+    // nanoseconds, Welford's method
+    mean_ns: f64,
+    m2_ns: f64,
+    intervals: IntervalHistogram,
+}
+
+impl Measurements{
+    fn new() -> Measurements{
+        Measurements{
+            buffers: 0,
+            bytes: 0,
+            first: None,
+            last: None,
+            max_interval: Duration::from_secs(0),
+            mean_ns: 0.,
+            m2_ns: 0.,
+            intervals: IntervalHistogram::new(),
+        }
+    }
+
+    fn add(&mut self, size: u64){
+        let now = Instant::now();
+        if let Some(last) = self.last{
+            let interval = now - last;
+            let n = self.buffers as f64;
+            let interval_ns = duration_ns(interval) as f64;
+            let delta = interval_ns - self.mean_ns;
+            self.mean_ns += delta / n;
+            self.m2_ns += delta * (interval_ns - self.mean_ns);
+            self.max_interval = self.max_interval.max(interval);
+            self.intervals.add(interval);
+        }else{
+            self.first = Some(now);
+        }
+        self.last = Some(now);
+        self.buffers += 1;
+        self.bytes += size;
+    }
+
+    fn report(&self) -> BenchReport{
+        let n_intervals = self.buffers.saturating_sub(1);
+        BenchReport{
+            buffers: self.buffers,
+            bytes: self.bytes,
+            elapsed: match (self.first, self.last){
+                (Some(first), Some(last)) => last - first,
+                _ => Duration::from_secs(0),
+            },
+            mean_interval: Duration::from_nanos(self.mean_ns as u64),
+            max_interval: self.max_interval,
+            jitter: if n_intervals > 1{
+                Duration::from_nanos((self.m2_ns / (n_intervals - 1) as f64).sqrt() as u64)
+            }else{
+                Duration::from_secs(0)
+            },
+            intervals: self.intervals.clone(),
+        }
+    }
+}
+
+/// A fakesink that measures the throughput and the time between the
+/// buffers it receives, to detect performance regressions in CI:
+///
+/// ```ignore
+/// let sink = gst::bench::MeasureSink::new("sink").unwrap();
+/// pipeline.add_and_link(src.into(), sink.reference().into());
+/// let report = gst::bench::run(&mut pipeline, &sink, Duration::from_secs(60)).unwrap();
+/// assert!(report.buffers_per_second() > 200.);
+/// ```
+///
+/// It doesn't sync on the clock by default so it measures how fast
+/// the pipeline can run, set_sync(true) measures the jitter of a live
+/// pipeline instead
+pub struct MeasureSink{
+    sink: FakeSink,
+    measurements: Arc<Mutex<Measurements>>,
+}
+
+impl MeasureSink{
+    pub fn new(name: &str) -> Option<MeasureSink>{
+        let mut sink = FakeSink::new(name)?;
+        let measurements = Arc::new(Mutex::new(Measurements::new()));
+        let handoff_measurements = measurements.clone();
+        let handle: SignalHandle = sink.connect_handoff(move |buffer, _pad| {
+            handoff_measurements.lock().unwrap_or_else(|e| e.into_inner()).add(buffer.size());
+        })?;
+        // connected for as long as the sink exists
+        handle.forget();
+        Some(MeasureSink{
+            sink: sink,
+            measurements: measurements,
+        })
+    }
+
+    /// The measurements so far
+    pub fn report(&self) -> BenchReport{
+        self.measurements.lock().unwrap_or_else(|e| e.into_inner()).report()
+    }
+
+    /// Starts measuring again, eg: after a warm up period
+    pub fn reset(&self){
+        *self.measurements.lock().unwrap_or_else(|e| e.into_inner()) = Measurements::new();
+    }
+}
+
+impl Reference for MeasureSink{
+    fn reference(&self) -> MeasureSink{
+        MeasureSink{
+            sink: self.sink.reference(),
+            measurements: self.measurements.clone(),
+        }
+    }
+}
+
+impl AsRef<Element> for MeasureSink{
+    fn as_ref(&self) -> &Element{
+        &self.sink
+    }
+}
+
+impl AsMut<Element> for MeasureSink{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.sink
+    }
+}
+
+impl From<MeasureSink> for Element{
+    fn from(s: MeasureSink) -> Element{
+        s.sink.into()
+    }
+}
+
+impl Deref for MeasureSink{
+    type Target = FakeSink;
+    fn deref(&self) -> &FakeSink{
+        &self.sink
+    }
+}
+
+impl DerefMut for MeasureSink{
+    fn deref_mut(&mut self) -> &mut FakeSink{
+        &mut self.sink
+    }
+}
+
+/// Plays pipeline until EOS and returns the measurements of sink.
+/// Fails with the error posted by the pipeline or if it doesn't reach
+/// EOS before timeout
+pub fn run(pipeline: &mut Pipeline, sink: &MeasureSink, timeout: Duration) -> Result<BenchReport>{
+    let mut bus = pipeline.bus().ok_or(Error::new(0, 0, "pipeline has no bus"))?;
+    pipeline.play();
+    let eos = bus.wait_eos(duration_ns(timeout));
+    pipeline.set_state(GST_STATE_NULL);
+    if eos?{
+        Ok(sink.report())
+    }else{
+        Err(Error::new(0, 0, "timed out waiting for EOS"))
+    }
+}
+
+fn duration_ns(duration: Duration) -> u64{
+    duration.as_secs() * 1_000_000_000 + duration.subsec_nanos() as u64
+}
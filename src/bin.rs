@@ -3,9 +3,22 @@ use element::Element;
 use util::*;
 use iterator::Iter;
 use ::Transfer;
-use reference::Reference;
+use reference::{Reference, Downgrade};
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use handle::{SignalHandle, ProbeHandle};
+
+use error::{Result, ParseError, parse_result};
+use version::ApiAvailability;
 
 use std::ops::{Deref, DerefMut};
+use std::result;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+const DOT_DATA: ApiAvailability = ApiAvailability::new("Bin::debug_to_dot_data", 1, 12, 0);
 
 /**
 GstBin is an element that can contain other GstElement, allowing them to be managed as a group. Pads from the child elements can be ghosted to the bin, see GstGhostPad. This makes the bin look like any other elements and enables creation of higher-level abstraction elements.
@@ -82,9 +95,6 @@ pub struct Bin{
     bin: Element
 }
 
-unsafe impl Sync for Bin {}
-unsafe impl Send for Bin {}
-
 impl Bin{
     /// Creates a new bin with the given name.
     pub fn new(name: &str) -> Option<Bin>{
@@ -97,7 +107,7 @@ impl Bin{
             };
             let bin = gst_bin_new(name);
             if bin != ptr::null_mut(){
-	            gst_object_ref_sink(mem::transmute(bin));
+	            gst_object_ref_sink(bin as gpointer);
 	            Bin::new_from_gst_bin(bin as *mut GstBin)
 	        }else{
 	            None
@@ -115,6 +125,34 @@ impl Bin{
     }
 
 
+    /// Creates a bin from a pipeline description like "videoconvert !
+    /// videoscale". With ghost_unlinked_pads the unlinked pads of the
+    /// first and last elements are ghosted on the bin so it can be linked
+    /// like any other element
+    pub fn from_description(description: &str, ghost_unlinked_pads: bool) -> Result<Bin>{
+        Bin::from_description_full(description, ghost_unlinked_pads, GST_PARSE_FLAG_NONE).map_err(|err| err.error)
+    }
+
+    /// Same as from_description with GST_PARSE_FLAG_* flags, errors that
+    /// aren't fatal return the partially built bin, see
+    /// Pipeline::new_from_str_full
+    pub fn from_description_full(description: &str, ghost_unlinked_pads: bool, flags: GstParseFlags) -> result::Result<Bin, ParseError<Bin>>{
+        let cdescription = CString::new(description).unwrap();
+        unsafe{
+            let context = gst_parse_context_new();
+            let mut error = ptr::null_mut();
+            let element = gst_parse_bin_from_description_full(cdescription.as_ptr(), ghost_unlinked_pads as gboolean, context, flags, &mut error);
+            parse_result(element, error, context, |element| {
+                if g_type_check_instance_is_a(element as *mut GTypeInstance, gst_bin_get_type()) != 0{
+                    Bin::new_from_gst_bin(element as *mut GstBin)
+                }else{
+                    gst_object_unref(element as gpointer);
+                    None
+                }
+            })
+        }
+    }
+
     /// Adds the given element to the bin. Sets the element's parent,
     /// and thus adds a reference.
     ///
@@ -159,7 +197,7 @@ impl Bin{
     /// unlinked before the element is removed from the bin.
     pub fn remove(&mut self, element: &Element) -> bool{
         unsafe{
-            gst_bin_remove(self.gst_bin_mut(), mem::transmute(element.gst_element())) == 1
+            gst_bin_remove(self.gst_bin_mut(), element.gst_element() as *mut GstElement) == 1
         }
     }
 
@@ -202,6 +240,67 @@ impl Bin{
         }
     }
 
+    /// Calls handler with every element added to this bin, not to its
+    /// child bins, see connect_deep_element_added for those
+    pub fn connect_element_added<F>(&mut self, mut handler: F) -> Option<SignalHandle>
+        where F: FnMut(&Element) + Send + 'static{
+        self.connect_values("element-added", move |args| {
+            if let Some(element) = args[1].get::<Element>(){
+                handler(&element);
+            }
+            None
+        })
+    }
+
+    /// Calls handler with every element removed from this bin
+    pub fn connect_element_removed<F>(&mut self, mut handler: F) -> Option<SignalHandle>
+        where F: FnMut(&Element) + Send + 'static{
+        self.connect_values("element-removed", move |args| {
+            if let Some(element) = args[1].get::<Element>(){
+                handler(&element);
+            }
+            None
+        })
+    }
+
+    /// Calls handler with the child bin and the element every time an
+    /// element is added to any bin inside this one, at any depth. Useful
+    /// to configure the elements playbin or decodebin create as soon as
+    /// they're created:
+    ///
+    /// ```ignore
+    /// playbin.connect_deep_element_added(|_bin, element| {
+    ///     // every sink has a sync property
+    ///     if element.get_value("sync").is_some(){
+    ///         element.reference().set("sync", false);
+    ///     }
+    /// }).unwrap().forget();
+    /// ```
+    ///
+    /// It's called from the thread that adds the element, often a
+    /// streaming thread for decodebin.
+    pub fn connect_deep_element_added<F>(&mut self, mut handler: F) -> Option<SignalHandle>
+        where F: FnMut(&Element, &Element) + Send + 'static{
+        self.connect_values("deep-element-added", move |args| {
+            if let (Some(bin), Some(element)) = (args[1].get::<Element>(), args[2].get::<Element>()){
+                handler(&bin, &element);
+            }
+            None
+        })
+    }
+
+    /// Calls handler with the child bin and the element every time an
+    /// element is removed from any bin inside this one
+    pub fn connect_deep_element_removed<F>(&mut self, mut handler: F) -> Option<SignalHandle>
+        where F: FnMut(&Element, &Element) + Send + 'static{
+        self.connect_values("deep-element-removed", move |args| {
+            if let (Some(bin), Some(element)) = (args[1].get::<Element>(), args[2].get::<Element>()){
+                handler(&bin, &element);
+            }
+            None
+        })
+    }
+
     /// Query bin for the current latency using and reconfigures this latency
     /// to all the elements with a LATENCY event.
 	///
@@ -235,6 +334,200 @@ impl Bin{
         self.set("message-forward", forward);
     }
 
+    /// Replaces old with new, linking new to the same pads old was linked
+    /// to. Both elements need to have a "sink" and a "src" static pad.
+    ///
+    /// If the bin is PAUSED or PLAYING, the upstream pad is blocked, old is
+    /// drained by sending it an EOS and once the EOS comes out of it, it's
+    /// removed and new is added, linked and set to the same state as the
+    /// bin. This happens in the streaming thread so this function returns
+    /// before the replacement is done. old is set to NULL from a new
+    /// thread once it's removed, it can't be done from the streaming
+    /// thread old itself is pushing from.
+    ///
+    /// The returned PendingChange cancels the replacement when dropped if
+    /// no data reached the upstream pad yet, eg: to give up after a
+    /// timeout when the pipeline isn't flowing:
+    ///
+    /// ```ignore
+    /// let change = bin.replace_element(&old, new).unwrap();
+    /// thread::sleep(Duration::from_secs(1));
+    /// if !change.is_done(){
+    ///     change.cancel();
+    /// }
+    /// ```
+    ///
+    /// Returns None if the elements don't have the needed pads or, when
+    /// the bin is stopped, if the new element couldn't be linked.
+    pub fn replace_element<E: Into<Element>>(&mut self, old: &Element, new: E) -> Option<PendingChange>{
+        let new = new.into();
+        let mut old = old.reference();
+        let old_sink = old.static_pad("sink")?;
+        let mut upstream = old_sink.peer()?;
+        if old.static_pad("src").is_none(){
+            return None;
+        }
+
+        match self.get_state(0).0{
+            GST_STATE_PAUSED | GST_STATE_PLAYING => (),
+            _ => {
+                let mut bin = self.reference();
+                old.set_null_state();
+                return if swap_elements(&mut bin, &mut old, new, &mut upstream){
+                    Some(PendingChange::done())
+                }else{
+                    None
+                };
+            }
+        }
+
+        // the probes only keep weak references to the bin and old, the
+        // bin owns the pads they are installed in
+        let state = Arc::new(Mutex::new(ChangeState::new(new)));
+        let block_state = state.clone();
+        let bin = self.downgrade();
+        let old = old.downgrade();
+        let block = upstream.add_probe(GST_PAD_PROBE_TYPE_BLOCK_DOWNSTREAM, move |upstream: &mut Pad, _info: &mut PadProbeInfo|{
+            let (mut old_sink, mut old_src) = {
+                let mut state = block_state.lock().unwrap();
+                if state.started{
+                    return PadProbeReturn::Ok;
+                }
+                if state.cancelled{
+                    return PadProbeReturn::Remove;
+                }
+                match old.upgrade().and_then(|mut old| Some((old.static_pad("sink")?, old.static_pad("src")?))){
+                    Some(pads) => {
+                        state.started = true;
+                        state.in_block = true;
+                        pads
+                    }
+                    None => {
+                        state.unblocked = true;
+                        return PadProbeReturn::Remove;
+                    }
+                }
+            };
+
+            let eos_state = block_state.clone();
+            let bin = bin.clone();
+            let old = old.clone();
+            let mut upstream = upstream.reference();
+            let eos_probe = old_src.add_probe(GST_PAD_PROBE_TYPE_BLOCK | GST_PAD_PROBE_TYPE_EVENT_DOWNSTREAM, move |_pad: &mut Pad, info: &mut PadProbeInfo|{
+                if !info.event().map_or(false, |event| event.event_type() == GST_EVENT_EOS){
+                    return PadProbeReturn::Pass;
+                }
+                let mut state = eos_state.lock().unwrap();
+                if let (Some(new), Some(mut bin), Some(mut old)) = (state.new.take(), bin.upgrade(), old.upgrade()){
+                    swap_elements(&mut bin, &mut old, new, &mut upstream);
+                    // this is old's streaming thread, setting it to NULL
+                    // waits for it to return from here
+                    thread::spawn(move || old.set_null_state());
+                }
+                state.done = true;
+                if !state.in_block && !state.unblocked && state.block_id != 0{
+                    unsafe{ gst_pad_remove_probe(upstream.gst_pad_mut(), state.block_id); }
+                    state.unblocked = true;
+                }
+                // old has been removed, don't let the EOS reach the rest
+                // of the pipeline
+                PadProbeReturn::Drop
+            });
+            // stays in old's pad, which is destroyed with old
+            if let Some(eos_probe) = eos_probe{
+                eos_probe.forget();
+            }
+
+            unsafe{ old_sink.send_event(gst_event_new_eos()); }
+
+            // if old processes the EOS in this same thread the swap is
+            // already done and the pad can be unblocked, otherwise the
+            // EOS probe will remove this probe once the EOS comes out
+            let mut state = block_state.lock().unwrap();
+            state.in_block = false;
+            if state.done{
+                state.unblocked = true;
+                PadProbeReturn::Remove
+            }else{
+                PadProbeReturn::Ok
+            }
+        });
+
+        block.map(|block| PendingChange::new(block, state))
+    }
+
+    /// Inserts new after existing, between existing's "src" pad and the
+    /// pad it's linked to. new needs to have a "sink" and a "src" static
+    /// pad.
+    ///
+    /// If the bin is PAUSED or PLAYING the link is done from a blocking
+    /// probe in the streaming thread so this function returns before new
+    /// is actually inserted. Dropping the returned PendingChange cancels
+    /// the insertion if it didn't happen yet, see replace_element.
+    pub fn insert_after<E: Into<Element>>(&mut self, existing: &Element, new: E) -> Option<PendingChange>{
+        let new = new.into();
+        let mut existing_src = existing.reference().static_pad("src")?;
+
+        match self.get_state(0).0{
+            GST_STATE_PAUSED | GST_STATE_PLAYING => (),
+            _ => {
+                let mut bin = self.reference();
+                return if insert_element(&mut bin, &mut existing_src, new){
+                    Some(PendingChange::done())
+                }else{
+                    None
+                };
+            }
+        }
+
+        let state = Arc::new(Mutex::new(ChangeState::new(new)));
+        let block_state = state.clone();
+        let bin = self.downgrade();
+        let block = existing_src.add_probe(GST_PAD_PROBE_TYPE_BLOCK_DOWNSTREAM, move |pad: &mut Pad, _info: &mut PadProbeInfo|{
+            let mut state = block_state.lock().unwrap();
+            if !state.cancelled{
+                if let (Some(new), Some(mut bin)) = (state.new.take(), bin.upgrade()){
+                    insert_element(&mut bin, pad, new);
+                    state.done = true;
+                }
+                state.unblocked = true;
+            }
+            PadProbeReturn::Remove
+        });
+
+        block.map(|block| PendingChange::new(block, state))
+    }
+
+    /// Returns the graph of the bin and its children in graphviz dot
+    /// format, details is a combination of the GST_DEBUG_GRAPH_SHOW_*
+    /// constants, eg: GST_DEBUG_GRAPH_SHOW_ALL to include the negotiated
+    /// caps, the states and the non default properties.
+    ///
+    /// Render it with `dot -Tpng pipeline.dot -o pipeline.png`
+    ///
+    /// Needs GStreamer 1.12, returns an unsupported error with older
+    /// versions
+    pub fn debug_to_dot_data(&self, details: GstDebugGraphDetails) -> Result<String>{
+        unsafe{
+            let to_dot_data: unsafe extern "C" fn(*mut GstBin, GstDebugGraphDetails) -> *mut gchar =
+                DOT_DATA.function("gst_debug_bin_to_dot_data")?;
+            let data = to_dot_data(self.gst_bin() as *mut GstBin, details);
+            let ret = from_c_str!(data).to_string();
+            g_free(data as gpointer);
+            Ok(ret)
+        }
+    }
+
+    /// Writes the graph of the bin to path, unlike the
+    /// GST_DEBUG_BIN_TO_DOT_FILE macro this doesn't need
+    /// GST_DEBUG_DUMP_DOT_DIR to be set. Returns the graph as well
+    pub fn debug_to_dot_file<P: AsRef<Path>>(&self, path: P, details: GstDebugGraphDetails) -> io::Result<String>{
+        let data = self.debug_to_dot_data(details).map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        let mut file = File::create(path)?;
+        file.write_all(data.as_bytes())?;
+        Ok(data)
+    }
+
     /// Returns a const raw pointer to the internal GstElement
     pub unsafe fn gst_bin(&self) -> *const GstBin{
         self.bin.gst_element() as *const GstBin
@@ -246,6 +539,153 @@ impl Bin{
     }
 }
 
+/// Returned by Bin::replace_element and Bin::insert_after while the
+/// change waits for data to reach the blocked pad. Dropping it cancels
+/// the change if the streaming thread didn't start it yet.
+#[must_use]
+pub struct PendingChange{
+    block: Option<ProbeHandle>,
+    state: Arc<Mutex<ChangeState>>,
+}
+
+impl PendingChange{
+    fn new(block: ProbeHandle, state: Arc<Mutex<ChangeState>>) -> PendingChange{
+        let block = {
+            let mut state = state.lock().unwrap();
+            state.block_id = block.id() as gulong;
+            if state.done && !state.in_block && !state.unblocked{
+                // the EOS came out of old before the id of the block was
+                // known so the EOS probe couldn't remove it, dropping the
+                // handle does
+                state.unblocked = true;
+                drop(block);
+                None
+            }else{
+                Some(block)
+            }
+        };
+        PendingChange{ block: block, state: state }
+    }
+
+    fn done() -> PendingChange{
+        let mut state = ChangeState::new(None);
+        state.done = true;
+        state.unblocked = true;
+        PendingChange{ block: None, state: Arc::new(Mutex::new(state)) }
+    }
+
+    /// Whether new has been added and linked
+    pub fn is_done(&self) -> bool{
+        self.state.lock().unwrap().done
+    }
+
+    /// Cancels the change and unblocks the pad, same as dropping it.
+    /// Returns false if the streaming thread already started it, a
+    /// replacement can't be undone once old received the EOS
+    pub fn cancel(mut self) -> bool{
+        self.cancel_block()
+    }
+
+    /// Keeps waiting for data to reach the pad to do the change. The
+    /// probe only keeps weak references to the bin so it doesn't keep it
+    /// alive if that never happens
+    pub fn forget(mut self){
+        if let Some(block) = self.block.take(){
+            block.forget();
+        }
+    }
+
+    fn cancel_block(&mut self) -> bool{
+        let block = match self.block.take(){
+            Some(block) => block,
+            None => return false
+        };
+        let mut state = self.state.lock().unwrap();
+        if state.started || state.unblocked{
+            // the streaming thread removes the probe once it's done
+            block.forget();
+            false
+        }else{
+            state.cancelled = true;
+            state.unblocked = true;
+            state.new = None;
+            drop(block);
+            true
+        }
+    }
+}
+
+impl Drop for PendingChange{
+    fn drop(&mut self){
+        self.cancel_block();
+    }
+}
+
+struct ChangeState{
+    new: Option<Element>,
+    // id of the block probe, 0 until add_probe returned
+    block_id: gulong,
+    started: bool,
+    cancelled: bool,
+    in_block: bool,
+    done: bool,
+    // the block probe has been removed, by itself, the EOS probe or the
+    // PendingChange
+    unblocked: bool,
+}
+
+impl ChangeState{
+    fn new<E: Into<Option<Element>>>(new: E) -> ChangeState{
+        ChangeState{
+            new: new.into(),
+            block_id: 0,
+            started: false,
+            cancelled: false,
+            in_block: false,
+            done: false,
+            unblocked: false,
+        }
+    }
+}
+
+fn swap_elements(bin: &mut Bin, old: &mut Element, new: Element, upstream: &mut Pad) -> bool{
+    let downstream = old.static_pad("src").and_then(|pad| pad.peer());
+    bin.remove(old);
+    let mut new_ref = new.reference();
+    if !bin.add(new){
+        return false;
+    }
+    let linked = match (new_ref.static_pad("sink"), new_ref.static_pad("src")){
+        (Some(mut sink), Some(mut src)) => {
+            upstream.link(&mut sink).is_ok() &&
+            downstream.map(|mut downstream| src.link(&mut downstream).is_ok()).unwrap_or(true)
+        }
+        _ => false
+    };
+    new_ref.sync_state_with_parent();
+    linked
+}
+
+fn insert_element(bin: &mut Bin, existing_src: &mut Pad, new: Element) -> bool{
+    let downstream = existing_src.peer();
+    if let Some(mut downstream) = downstream.as_ref().map(|pad| pad.reference()){
+        existing_src.unlink(&mut downstream);
+    }
+    let mut new_ref = new.reference();
+    if !bin.add(new){
+        return false;
+    }
+    let linked = match (new_ref.static_pad("sink"), new_ref.static_pad("src")){
+        (Some(mut sink), Some(mut src)) => {
+            existing_src.link(&mut sink).is_ok() &&
+            downstream.map(|mut downstream| src.link(&mut downstream).is_ok()).unwrap_or(true)
+        }
+        _ => false
+    };
+    new_ref.sync_state_with_parent();
+    linked
+}
+
 impl AsRef<Element> for Bin{
     fn as_ref(&self) -> &Element{
         &self.bin
@@ -288,3 +728,13 @@ impl Reference for Bin{
         Bin{bin: self.bin.reference()}
     }
 }
+
+impl Downgrade for Bin{
+    unsafe fn gst_object_ptr(&self) -> *mut GstObject{
+        self.gst_bin() as *mut GstObject
+    }
+
+    unsafe fn from_gst_object_ptr(object: *mut GstObject) -> Option<Bin>{
+        Bin::new_from_gst_bin(object as *mut GstBin)
+    }
+}
@@ -0,0 +1,440 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+
+use std::marker::PhantomData;
+use std::slice;
+
+/// Reads integers and slices from a byte slice, eg: to parse an ADTS
+/// header or the boxes of an mp4. Every getter returns None without
+/// advancing when there's not enough data left:
+///
+/// ```ignore
+/// let mut reader = gst::bitstream::ByteReader::new(data);
+/// let offset = reader.masked_scan_u32(0xffffff00, 0x00000100, 0, data.len() as u32);
+/// ```
+pub struct ByteReader<'a>{
+    reader: GstByteReader,
+    _data: PhantomData<&'a [u8]>,
+}
+
+impl<'a> ByteReader<'a>{
+    pub fn new(data: &'a [u8]) -> ByteReader<'a>{
+        let mut reader = GstByteReader::default();
+        unsafe{
+            gst_byte_reader_init(&mut reader, data.as_ptr(), data.len() as guint);
+        }
+        ByteReader{ reader: reader, _data: PhantomData }
+    }
+
+    /// Position in bytes from the start of the data
+    pub fn position(&self) -> u32{
+        unsafe{
+            gst_byte_reader_get_pos(&self.reader)
+        }
+    }
+
+    pub fn set_position(&mut self, pos: u32) -> bool{
+        unsafe{
+            gst_byte_reader_set_pos(&mut self.reader, pos) != 0
+        }
+    }
+
+    /// Bytes left to read
+    pub fn remaining(&self) -> u32{
+        unsafe{
+            gst_byte_reader_get_remaining(&self.reader)
+        }
+    }
+
+    pub fn skip(&mut self, nbytes: u32) -> bool{
+        unsafe{
+            gst_byte_reader_skip(&mut self.reader, nbytes) != 0
+        }
+    }
+
+    pub fn get_u8(&mut self) -> Option<u8>{
+        let mut val = 0;
+        unsafe{
+            if gst_byte_reader_get_uint8(&mut self.reader, &mut val) != 0 { Some(val) } else { None }
+        }
+    }
+
+    pub fn get_u16_be(&mut self) -> Option<u16>{
+        let mut val = 0;
+        unsafe{
+            if gst_byte_reader_get_uint16_be(&mut self.reader, &mut val) != 0 { Some(val) } else { None }
+        }
+    }
+
+    pub fn get_u16_le(&mut self) -> Option<u16>{
+        let mut val = 0;
+        unsafe{
+            if gst_byte_reader_get_uint16_le(&mut self.reader, &mut val) != 0 { Some(val) } else { None }
+        }
+    }
+
+    pub fn get_u24_be(&mut self) -> Option<u32>{
+        let mut val = 0;
+        unsafe{
+            if gst_byte_reader_get_uint24_be(&mut self.reader, &mut val) != 0 { Some(val) } else { None }
+        }
+    }
+
+    pub fn get_u24_le(&mut self) -> Option<u32>{
+        let mut val = 0;
+        unsafe{
+            if gst_byte_reader_get_uint24_le(&mut self.reader, &mut val) != 0 { Some(val) } else { None }
+        }
+    }
+
+    pub fn get_u32_be(&mut self) -> Option<u32>{
+        let mut val = 0;
+        unsafe{
+            if gst_byte_reader_get_uint32_be(&mut self.reader, &mut val) != 0 { Some(val) } else { None }
+        }
+    }
+
+    pub fn get_u32_le(&mut self) -> Option<u32>{
+        let mut val = 0;
+        unsafe{
+            if gst_byte_reader_get_uint32_le(&mut self.reader, &mut val) != 0 { Some(val) } else { None }
+        }
+    }
+
+    pub fn get_u64_be(&mut self) -> Option<u64>{
+        let mut val = 0;
+        unsafe{
+            if gst_byte_reader_get_uint64_be(&mut self.reader, &mut val) != 0 { Some(val) } else { None }
+        }
+    }
+
+    pub fn get_u64_le(&mut self) -> Option<u64>{
+        let mut val = 0;
+        unsafe{
+            if gst_byte_reader_get_uint64_le(&mut self.reader, &mut val) != 0 { Some(val) } else { None }
+        }
+    }
+
+    pub fn peek_u8(&self) -> Option<u8>{
+        let mut val = 0;
+        unsafe{
+            if gst_byte_reader_peek_uint8(&self.reader, &mut val) != 0 { Some(val) } else { None }
+        }
+    }
+
+    pub fn peek_u16_be(&self) -> Option<u16>{
+        let mut val = 0;
+        unsafe{
+            if gst_byte_reader_peek_uint16_be(&self.reader, &mut val) != 0 { Some(val) } else { None }
+        }
+    }
+
+    pub fn peek_u32_be(&self) -> Option<u32>{
+        let mut val = 0;
+        unsafe{
+            if gst_byte_reader_peek_uint32_be(&self.reader, &mut val) != 0 { Some(val) } else { None }
+        }
+    }
+
+    /// The next size bytes, without copying them
+    pub fn get_data(&mut self, size: u32) -> Option<&'a [u8]>{
+        let mut data = ptr::null();
+        unsafe{
+            if gst_byte_reader_get_data(&mut self.reader, size, &mut data) != 0{
+                Some(slice::from_raw_parts(data, size as usize))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn peek_data(&self, size: u32) -> Option<&'a [u8]>{
+        let mut data = ptr::null();
+        unsafe{
+            if gst_byte_reader_peek_data(&self.reader, size, &mut data) != 0{
+                Some(slice::from_raw_parts(data, size as usize))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Offset from the current position of the first 4 bytes that equal
+    /// pattern once masked, searching size bytes from offset, eg: to find
+    /// an H.264 start code. None if not found
+    pub fn masked_scan_u32(&self, mask: u32, pattern: u32, offset: u32, size: u32) -> Option<u32>{
+        unsafe{
+            match gst_byte_reader_masked_scan_uint32(&self.reader, mask, pattern, offset, size){
+                0xffffffff => None,
+                pos => Some(pos),
+            }
+        }
+    }
+}
+
+/// Reads fields of any number of bits from a byte slice, most
+/// significant bit first, eg: to parse an SPS or PPS once the emulation
+/// prevention bytes are removed:
+///
+/// ```ignore
+/// let mut reader = gst::bitstream::BitReader::new(&sps[1..]);
+/// let profile_idc = reader.get_bits(8)?;
+/// reader.skip(16);
+/// let sps_id = reader.get_ue()?;
+/// ```
+pub struct BitReader<'a>{
+    reader: GstBitReader,
+    _data: PhantomData<&'a [u8]>,
+}
+
+impl<'a> BitReader<'a>{
+    pub fn new(data: &'a [u8]) -> BitReader<'a>{
+        let mut reader = GstBitReader::default();
+        unsafe{
+            gst_bit_reader_init(&mut reader, data.as_ptr(), data.len() as guint);
+        }
+        BitReader{ reader: reader, _data: PhantomData }
+    }
+
+    /// Position in bits from the start of the data
+    pub fn position(&self) -> u32{
+        unsafe{
+            gst_bit_reader_get_pos(&self.reader)
+        }
+    }
+
+    pub fn set_position(&mut self, pos: u32) -> bool{
+        unsafe{
+            gst_bit_reader_set_pos(&mut self.reader, pos) != 0
+        }
+    }
+
+    /// Bits left to read
+    pub fn remaining(&self) -> u32{
+        unsafe{
+            gst_bit_reader_get_remaining(&self.reader)
+        }
+    }
+
+    pub fn skip(&mut self, nbits: u32) -> bool{
+        unsafe{
+            gst_bit_reader_skip(&mut self.reader, nbits) != 0
+        }
+    }
+
+    /// Skips to the start of the next byte if not already at one
+    pub fn skip_to_byte(&mut self) -> bool{
+        unsafe{
+            gst_bit_reader_skip_to_byte(&mut self.reader) != 0
+        }
+    }
+
+    /// Reads nbits, up to 32
+    pub fn get_bits(&mut self, nbits: u32) -> Option<u32>{
+        let mut val = 0;
+        unsafe{
+            if gst_bit_reader_get_bits_uint32(&mut self.reader, &mut val, nbits) != 0 { Some(val) } else { None }
+        }
+    }
+
+    /// Reads nbits, up to 64
+    pub fn get_bits_u64(&mut self, nbits: u32) -> Option<u64>{
+        let mut val = 0;
+        unsafe{
+            if gst_bit_reader_get_bits_uint64(&mut self.reader, &mut val, nbits) != 0 { Some(val) } else { None }
+        }
+    }
+
+    pub fn peek_bits(&self, nbits: u32) -> Option<u32>{
+        let mut val = 0;
+        unsafe{
+            if gst_bit_reader_peek_bits_uint32(&self.reader, &mut val, nbits) != 0 { Some(val) } else { None }
+        }
+    }
+
+    pub fn get_bool(&mut self) -> Option<bool>{
+        self.get_bits(1).map(|bit| bit != 0)
+    }
+
+    /// Reads an unsigned Exp-Golomb code, the ue(v) of the H.264 and
+    /// H.265 specs. The position is left unchanged if the code is invalid
+    pub fn get_ue(&mut self) -> Option<u32>{
+        let pos = self.position();
+        let ue = self.read_ue();
+        if ue.is_none(){
+            self.set_position(pos);
+        }
+        ue
+    }
+
+    /// Reads a signed Exp-Golomb code, the se(v) of the H.264 and H.265
+    /// specs
+    pub fn get_se(&mut self) -> Option<i32>{
+        self.get_ue().map(|ue| {
+            if ue & 1 == 1{
+                (ue / 2 + 1) as i32
+            }else{
+                -((ue / 2) as i32)
+            }
+        })
+    }
+
+    fn read_ue(&mut self) -> Option<u32>{
+        let mut leading_zeros = 0;
+        while !self.get_bool()?{
+            leading_zeros += 1;
+            if leading_zeros > 31{
+                return None;
+            }
+        }
+        if leading_zeros == 0{
+            return Some(0);
+        }
+        let suffix = self.get_bits(leading_zeros)?;
+        Some(((1u64 << leading_zeros) - 1 + suffix as u64) as u32)
+    }
+}
+
+/// Writes integers and slices into a growing or fixed size memory
+/// area, eg: to build codec_data or a header to prepend to a buffer:
+///
+/// ```ignore
+/// let mut writer = gst::bitstream::ByteWriter::new();
+/// writer.put_u8(1);
+/// writer.put_data(&sps);
+/// let codec_data = writer.into_buffer();
+/// ```
+pub struct ByteWriter{
+    writer: GstByteWriter
+}
+
+impl ByteWriter{
+    pub fn new() -> ByteWriter{
+        let mut writer = GstByteWriter::default();
+        unsafe{
+            gst_byte_writer_init(&mut writer);
+        }
+        ByteWriter{ writer: writer }
+    }
+
+    /// Preallocates size bytes. If fixed, writing past them fails
+    /// instead of growing the memory
+    pub fn with_size(size: u32, fixed: bool) -> ByteWriter{
+        let mut writer = GstByteWriter::default();
+        unsafe{
+            gst_byte_writer_init_with_size(&mut writer, size, fixed as gboolean);
+        }
+        ByteWriter{ writer: writer }
+    }
+
+    /// Position in bytes from the start of the data
+    pub fn position(&self) -> u32{
+        unsafe{
+            gst_byte_reader_get_pos(&self.writer.parent)
+        }
+    }
+
+    /// Bytes that can still be written, u32::MAX if not fixed
+    pub fn remaining(&self) -> u32{
+        unsafe{
+            gst_byte_writer_get_remaining(&self.writer)
+        }
+    }
+
+    pub fn put_u8(&mut self, val: u8) -> bool{
+        unsafe{
+            gst_byte_writer_put_uint8(&mut self.writer, val) != 0
+        }
+    }
+
+    pub fn put_u16_be(&mut self, val: u16) -> bool{
+        unsafe{
+            gst_byte_writer_put_uint16_be(&mut self.writer, val) != 0
+        }
+    }
+
+    pub fn put_u16_le(&mut self, val: u16) -> bool{
+        unsafe{
+            gst_byte_writer_put_uint16_le(&mut self.writer, val) != 0
+        }
+    }
+
+    pub fn put_u24_be(&mut self, val: u32) -> bool{
+        unsafe{
+            gst_byte_writer_put_uint24_be(&mut self.writer, val) != 0
+        }
+    }
+
+    pub fn put_u24_le(&mut self, val: u32) -> bool{
+        unsafe{
+            gst_byte_writer_put_uint24_le(&mut self.writer, val) != 0
+        }
+    }
+
+    pub fn put_u32_be(&mut self, val: u32) -> bool{
+        unsafe{
+            gst_byte_writer_put_uint32_be(&mut self.writer, val) != 0
+        }
+    }
+
+    pub fn put_u32_le(&mut self, val: u32) -> bool{
+        unsafe{
+            gst_byte_writer_put_uint32_le(&mut self.writer, val) != 0
+        }
+    }
+
+    pub fn put_u64_be(&mut self, val: u64) -> bool{
+        unsafe{
+            gst_byte_writer_put_uint64_be(&mut self.writer, val) != 0
+        }
+    }
+
+    pub fn put_u64_le(&mut self, val: u64) -> bool{
+        unsafe{
+            gst_byte_writer_put_uint64_le(&mut self.writer, val) != 0
+        }
+    }
+
+    pub fn put_data(&mut self, data: &[u8]) -> bool{
+        unsafe{
+            gst_byte_writer_put_data(&mut self.writer, data.as_ptr(), data.len() as guint) != 0
+        }
+    }
+
+    /// Writes size bytes with value
+    pub fn fill(&mut self, value: u8, size: u32) -> bool{
+        unsafe{
+            gst_byte_writer_fill(&mut self.writer, value, size) != 0
+        }
+    }
+
+    /// The bytes written so far
+    pub fn into_vec(mut self) -> Vec<u8>{
+        unsafe{
+            let size = self.writer.parent.size as usize;
+            let data = gst_byte_writer_reset_and_get_data(&mut self.writer);
+            if data == ptr::null_mut(){
+                return vec![];
+            }
+            let vec = slice::from_raw_parts(data, size).to_vec();
+            g_free(data as gpointer);
+            vec
+        }
+    }
+
+    /// A buffer with the bytes written so far, without copying them
+    pub fn into_buffer(mut self) -> Buffer{
+        unsafe{
+            Buffer::new(gst_byte_writer_reset_and_get_buffer(&mut self.writer)).unwrap()
+        }
+    }
+}
+
+impl Drop for ByteWriter{
+    fn drop(&mut self){
+        unsafe{
+            gst_byte_writer_reset(&mut self.writer);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use ffi::*;
+use bin::Bin;
+use element::Element;
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use pipeline::Pipeline;
+use reference::Reference;
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A branch added to a tee with Pipeline::add_branch: the request pad
+/// of the tee, the queue that decouples the branch from the other ones
+/// and the bin with the elements of the branch
+pub struct Branch{
+    tee: Element,
+    tee_pad: Pad,
+    queue: Element,
+    bin: Bin,
+}
+
+impl Branch{
+    pub fn bin(&self) -> &Bin{
+        &self.bin
+    }
+
+    pub fn queue(&self) -> &Element{
+        &self.queue
+    }
+
+    /// The src pad requested from the tee for this branch
+    pub fn tee_pad(&self) -> &Pad{
+        &self.tee_pad
+    }
+}
+
+impl Pipeline{
+    /// Adds bin as a new branch of tee, even while the pipeline is
+    /// running, eg: to start recording while previewing:
+    ///
+    /// ```ignore
+    /// let recording = gst::Bin::from_description("x264enc ! mp4mux ! filesink location=rec.mp4", true).unwrap();
+    /// let branch = pipeline.add_branch(&tee, recording).unwrap();
+    /// // later
+    /// pipeline.remove_branch(branch, true);
+    /// ```
+    ///
+    /// bin needs a "sink" pad, a ghost pad if it was created from a
+    /// description. A queue is added before it so each branch runs in
+    /// its own thread, both are set to the state of the pipeline before
+    /// the tee pad is linked so data only flows once they're ready.
+    pub fn add_branch(&mut self, tee: &Element, bin: Bin) -> Option<Branch>{
+        let mut tee = tee.reference();
+        let mut queue = Element::new("queue", "")?;
+        let mut bin_ref = bin.reference();
+        if !self.add(queue.reference()){
+            return None;
+        }
+        if !self.add(bin){
+            self.remove(&queue);
+            return None;
+        }
+        let mut queue_sink = queue.static_pad("sink")?;
+        if !queue.link(&mut bin_ref){
+            self.remove(&queue);
+            self.remove(&bin_ref);
+            return None;
+        }
+        bin_ref.sync_state_with_parent();
+        queue.sync_state_with_parent();
+        let mut tee_pad = match tee.request_pad("src_%u"){
+            Some(pad) => pad,
+            None => {
+                bin_ref.set_null_state();
+                queue.set_null_state();
+                self.remove(&queue);
+                self.remove(&bin_ref);
+                return None;
+            }
+        };
+        if tee_pad.link(&mut queue_sink).is_err(){
+            tee.release_request_pad(tee_pad);
+            bin_ref.set_null_state();
+            queue.set_null_state();
+            self.remove(&queue);
+            self.remove(&bin_ref);
+            return None;
+        }
+        Some(Branch{
+            tee: tee,
+            tee_pad: tee_pad,
+            queue: queue,
+            bin: bin_ref,
+        })
+    }
+
+    /// Removes a branch added with add_branch. The tee pad is unlinked
+    /// and released once no data is going through it, so the rest of
+    /// the pipeline keeps running.
+    ///
+    /// If drain is true an EOS is sent through the branch before it's
+    /// stopped, so muxers can finish the files they are writing, and
+    /// the branch is only removed once the EOS reaches its sinks. That
+    /// EOS doesn't reach the bus. This happens in the streaming threads
+    /// so this function returns before the branch is removed.
+    pub fn remove_branch(&mut self, branch: Branch, drain: bool) -> bool{
+        let Branch{ tee, mut tee_pad, queue, bin } = branch;
+        let drain = drain && match self.get_state(0).0{
+            GST_STATE_PAUSED | GST_STATE_PLAYING => true,
+            _ => false,
+        };
+        let pipeline = self.reference();
+        let mut pending = Some((tee, queue, bin));
+        // an idle probe is called right away if no data is flowing, in
+        // that case add_probe returns None but the branch is removed
+        let probe = tee_pad.add_probe(GST_PAD_PROBE_TYPE_IDLE, move |pad: &mut Pad, _info: &mut PadProbeInfo| {
+            if let Some((mut tee, mut queue, bin)) = pending.take(){
+                if let Some(mut queue_sink) = queue.static_pad("sink"){
+                    pad.unlink(&mut queue_sink);
+                    tee.release_request_pad(pad.reference());
+                    if drain{
+                        drain_branch(pipeline.reference(), queue, bin, queue_sink);
+                    }else{
+                        teardown(pipeline.reference(), queue, bin);
+                    }
+                }
+            }
+            PadProbeReturn::Remove
+        });
+        // the probe removes itself
+        if let Some(probe) = probe{
+            probe.forget();
+        }
+        true
+    }
+}
+
+// sends an EOS to the branch and removes it once it has reached every
+// sink in the branch
+fn drain_branch(pipeline: Pipeline, queue: Element, bin: Bin, mut queue_sink: Pad){
+    let sinks: Vec<Element> = bin.iter_recurse()
+        .filter_map(|element| element.ok())
+        .filter(|element| element.src_pads().is_empty() && !element.sink_pads().is_empty())
+        .collect();
+    if sinks.is_empty(){
+        teardown(pipeline, queue, bin);
+        return;
+    }
+    let pending = Arc::new(Mutex::new((sinks.len(), Some((pipeline, queue, bin)))));
+    for sink in sinks{
+        for mut pad in sink.sink_pads(){
+            let pending = pending.clone();
+            let probe = pad.add_probe(GST_PAD_PROBE_TYPE_EVENT_DOWNSTREAM, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+                if !info.event().map_or(false, |event| event.event_type() == GST_EVENT_EOS){
+                    return PadProbeReturn::Pass;
+                }
+                let mut pending = pending.lock().unwrap_or_else(|e| e.into_inner());
+                pending.0 = pending.0.saturating_sub(1);
+                if pending.0 == 0{
+                    if let Some((pipeline, queue, bin)) = pending.1.take(){
+                        teardown(pipeline, queue, bin);
+                    }
+                }
+                // the branch is being removed, the EOS would otherwise be
+                // counted by the pipeline as one of its sinks finishing
+                PadProbeReturn::Drop
+            });
+            // stays until the sink is destroyed with the branch
+            if let Some(probe) = probe{
+                probe.forget();
+            }
+        }
+    }
+    unsafe{
+        queue_sink.send_event(gst_event_new_eos());
+    }
+}
+
+// stops and removes the branch from another thread, the probes that
+// call this run in the streaming threads of the branch which can't
+// stop themselves
+fn teardown(mut pipeline: Pipeline, mut queue: Element, mut bin: Bin){
+    thread::spawn(move || {
+        bin.set_null_state();
+        queue.set_null_state();
+        pipeline.remove(&queue);
+        pipeline.remove(&bin);
+    });
+}
@@ -0,0 +1,269 @@
+use ffi::*;
+use util::*;
+
+use element::Element;
+use appsrc::AppSrc;
+use caps::Caps;
+use sample::Sample;
+use reference::Reference;
+use panic::catch_panic;
+
+use std::sync::{Arc, Mutex};
+
+/// What to do with the buffers the consumer can't take in time
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum LeakyPolicy{
+    /// Block the producer until there's room
+    None,
+    /// Drop the newest buffers
+    Upstream,
+    /// Drop the oldest buffers
+    Downstream,
+}
+
+impl LeakyPolicy{
+    pub fn nick(self) -> &'static str{
+        match self{
+            LeakyPolicy::None => "none",
+            LeakyPolicy::Upstream => "upstream",
+            LeakyPolicy::Downstream => "downstream",
+        }
+    }
+}
+
+struct BridgeState{
+    src: Option<AppSrc>,
+    caps: Option<Caps>,
+    leaky: LeakyPolicy,
+    max_buffers: u32,
+    forward_eos: bool,
+    /// Difference between the running time of the consumer and the one
+    /// of the producer, recomputed when either of them restarts
+    offset: Option<i64>,
+    base_time: GstClockTime,
+    last_running_time: u64,
+}
+
+impl BridgeState{
+    fn configure_src(&self, src: &mut AppSrc){
+        src.set_from_str("format", "time");
+        src.set("is-live", true as gboolean);
+        src.set("max-buffers", self.max_buffers as u64);
+        src.set("block", (self.leaky == LeakyPolicy::None) as gboolean);
+        if self.leaky != LeakyPolicy::None{
+            src.set_from_str("leaky-type", self.leaky.nick());
+        }
+        if let Some(ref caps) = self.caps{
+            src.set_caps(caps);
+        }
+    }
+
+    /// Translates the timestamps of a buffer from the producer's running
+    /// time to the consumer's. None if the consumer isn't playing
+    fn retimestamp(&mut self, src: &AppSrc, sample: &Sample) -> Option<::Buffer>{
+        let buffer = match sample.buffer(){
+            Some(buffer) => buffer,
+            None => return None,
+        };
+        let segment = sample.segment();
+        let running_time = buffer.pts().and_then(|pts| segment.to_running_time(GST_FORMAT_TIME, pts));
+        let (now, base_time) = unsafe{
+            let clock = gst_element_get_clock(src.gst_element() as *mut GstElement);
+            if clock == ptr::null_mut(){
+                self.offset = None;
+                return None;
+            }
+            let base_time = gst_element_get_base_time(src.gst_element() as *mut GstElement);
+            let now = gst_clock_get_time(clock);
+            gst_object_unref(clock as gpointer);
+            (now.saturating_sub(base_time), base_time)
+        };
+
+        let mut buffer = buffer.make_writable();
+        if let Some(running_time) = running_time{
+            if self.offset.is_none() || base_time != self.base_time || running_time < self.last_running_time{
+                self.offset = Some(now as i64 - running_time as i64);
+                self.base_time = base_time;
+                buffer.set_discont(true);
+            }
+            self.last_running_time = running_time;
+            let offset = self.offset.unwrap();
+            let dts = buffer.dts().and_then(|dts| segment.to_running_time(GST_FORMAT_TIME, dts));
+            buffer.set_pts(Some((running_time as i64 + offset).max(0) as u64));
+            buffer.set_dts(dts.map(|dts| (dts as i64 + offset).max(0) as u64));
+        }else{
+            buffer.set_pts(None);
+            buffer.set_dts(None);
+        }
+        Some(buffer)
+    }
+}
+
+/// Moves buffers from a producer pipeline to a consumer pipeline in the
+/// same process, through an appsink at the end of the producer and an
+/// appsrc at the start of the consumer.
+///
+/// Each pipeline has its own clock and state so either of them can be
+/// stopped or rebuilt without affecting the other, eg: a capture
+/// pipeline that keeps running while recording or streaming pipelines
+/// come and go. The caps of the producer are set on the appsrc and the
+/// timestamps are translated to the consumer's running time. While
+/// there's no consumer, or it isn't playing, the buffers are dropped.
+///
+/// ```ignore
+/// let bridge = gst::bridge::Bridge::new();
+/// capture.add_and_link(encoder, bridge.new_sink("bridge-sink").unwrap());
+/// capture.play();
+/// // later, and again every time the consumer is rebuilt
+/// let src = bridge.new_src("bridge-src").unwrap();
+/// recorder.add_and_link(src.into(), muxer);
+/// recorder.play();
+/// ```
+#[derive(Clone)]
+pub struct Bridge{
+    state: Arc<Mutex<BridgeState>>
+}
+
+impl Bridge{
+    /// Creates a bridge that drops the oldest buffers when more than 5
+    /// are waiting for the consumer
+    pub fn new() -> Bridge{
+        Bridge{
+            state: Arc::new(Mutex::new(BridgeState{
+                src: None,
+                caps: None,
+                leaky: LeakyPolicy::Downstream,
+                max_buffers: 5,
+                forward_eos: false,
+                offset: None,
+                base_time: 0,
+                last_running_time: 0,
+            }))
+        }
+    }
+
+    /// Sets how many buffers can be queued for the consumer and what to
+    /// do when there's no more room. LeakyPolicy::None makes a slow
+    /// consumer slow down the producer too
+    pub fn set_leaky(&self, policy: LeakyPolicy, max_buffers: u32){
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.leaky = policy;
+        state.max_buffers = max_buffers;
+        if let Some(mut src) = state.src.as_ref().map(|src| src.reference()){
+            state.configure_src(&mut src);
+        }
+    }
+
+    /// Send EOS to the consumer when the producer finishes, by default
+    /// the consumer keeps running waiting for the producer to restart
+    pub fn set_forward_eos(&self, forward: bool){
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).forward_eos = forward;
+    }
+
+    /// Creates the appsink to add at the end of the producer pipeline
+    pub fn new_sink(&self, name: &str) -> Option<Element>{
+        let mut appsink = match Element::new("appsink", name){
+            Some(appsink) => appsink,
+            None => return None,
+        };
+        unsafe{
+            let mut callbacks = GstAppSinkCallbacks{
+                eos: Some(on_eos),
+                new_preroll: None,
+                new_sample: Some(on_new_sample),
+                _gst_reserved: [ptr::null_mut(); 4]
+            };
+            let state = Box::into_raw(Box::new(self.state.clone()));
+            gst_app_sink_set_callbacks(appsink.gst_element_mut() as *mut GstAppSink, &mut callbacks, state as gpointer, Some(free_state));
+        }
+        Some(appsink)
+    }
+
+    /// Creates an appsrc to add at the start of a consumer pipeline,
+    /// replacing the previous consumer if any
+    pub fn new_src(&self, name: &str) -> Option<AppSrc>{
+        AppSrc::new(name).map(|src| {
+            self.set_src(&src);
+            src
+        })
+    }
+
+    /// Makes src the consumer of the bridge, configuring it as needed.
+    /// Useful when the appsrc comes from a parsed pipeline description
+    pub fn set_src(&self, src: &AppSrc){
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut src = src.reference();
+        state.configure_src(&mut src);
+        state.src = Some(src);
+        state.offset = None;
+    }
+
+    /// Disconnects the consumer, buffers are dropped until a new one is
+    /// set
+    pub fn remove_src(&self){
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.src = None;
+        state.offset = None;
+    }
+
+    /// Caps of the last buffer received from the producer
+    pub fn caps(&self) -> Option<Caps>{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).caps.clone()
+    }
+}
+
+extern "C" fn on_new_sample(appsink: *mut GstAppSink, data: gpointer) -> GstFlowReturn{
+    unsafe{
+        let state = &*(data as *const Arc<Mutex<BridgeState>>);
+        let sample = match Sample::new(gst_app_sink_pull_sample(appsink)){
+            Some(sample) => sample,
+            None => return GST_FLOW_EOS,
+        };
+        catch_panic(appsink as *mut GstElement, GST_FLOW_ERROR, || {
+            // the lock is released before pushing, with LeakyPolicy::None
+            // pushing blocks until the consumer has room
+            let pending = {
+                let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+                let mut src = match state.src.as_ref().map(|src| src.reference()){
+                    Some(src) => src,
+                    None => return GST_FLOW_OK,
+                };
+                if let Some(caps) = sample.caps(){
+                    if state.caps.as_ref() != Some(&caps){
+                        src.set_caps(&caps);
+                        state.caps = Some(caps);
+                    }
+                }
+                state.retimestamp(&src, &sample).map(|buffer| (src, buffer))
+            };
+            if let Some((mut src, buffer)) = pending{
+                // a consumer that is shutting down returns FLUSHING, that
+                // mustn't stop the producer
+                src.push_buffer(buffer);
+            }
+            GST_FLOW_OK
+        })
+    }
+}
+
+extern "C" fn on_eos(_appsink: *mut GstAppSink, data: gpointer){
+    unsafe{
+        let state = &*(data as *const Arc<Mutex<BridgeState>>);
+        let src = {
+            let state = state.lock().unwrap_or_else(|e| e.into_inner());
+            if !state.forward_eos{
+                return;
+            }
+            state.src.as_ref().map(|src| src.reference())
+        };
+        if let Some(mut src) = src{
+            src.end_of_stream();
+        }
+    }
+}
+
+extern "C" fn free_state(data: gpointer){
+    unsafe{
+        let _ = Box::from_raw(data as *mut Arc<Mutex<BridgeState>>);
+    }
+}
@@ -1,14 +1,25 @@
 use ffi::*;
 use reference::Reference;
 use miniobject::MiniObject;
+use memory::Memory;
+use allocator::{Allocator, AllocationParams};
+use meta::{self, Meta, VideoMeta, VideoCropMeta, ReferenceTimestampMeta, NetControlMessageMeta, ProtectionMeta, CustomMeta};
+use structure::Structure;
+use caps::Caps;
+use ::Transfer;
 
 use std::mem;
+use std::ptr;
 use std::fmt::{Debug, Formatter, Error};
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, BitOr, BitOrAssign, BitAnd, Not};
+#[cfg(unix)]
+use std::os::unix::io::RawFd;
+#[cfg(target_os="linux")]
+use std::os::unix::io::IntoRawFd;
 
 #[derive(Clone)]
 pub struct Buffer{
-    buffer: MiniObject
+    buffer: MiniObject<GstBuffer>
 }
 
 macro_rules! gst_buffer_flag {
@@ -19,6 +30,7 @@ macro_rules! gst_buffer_flag {
         }
 
         pub fn $setter(&mut self, value: bool) {
+            self.buffer.make_mut();
             if !value {
                 /* Clear */
                 unsafe {
@@ -34,12 +46,512 @@ macro_rules! gst_buffer_flag {
     )
 }
 
+#[inline]
+fn time_to_option(time: GstClockTime) -> Option<u64>{
+    if time != GST_CLOCK_TIME_NONE{
+        Some(time)
+    }else{
+        None
+    }
+}
+
+/// Set of the GST_BUFFER_FLAG_* flags of a buffer, combined with |:
+///
+/// ```ignore
+/// if buffer.buffer_flags().contains(gst::BufferFlags::DISCONT | gst::BufferFlags::DELTA_UNIT){
+///     // a gap in a stream that can't be decoded until the next keyframe
+/// }
+/// ```
+#[derive(Copy,Clone,PartialEq,Eq,Hash,Default)]
+pub struct BufferFlags(guint);
+
+impl BufferFlags{
+    pub const LIVE: BufferFlags = BufferFlags(GST_BUFFER_FLAG_LIVE);
+    pub const DECODE_ONLY: BufferFlags = BufferFlags(GST_BUFFER_FLAG_DECODE_ONLY);
+    pub const DISCONT: BufferFlags = BufferFlags(GST_BUFFER_FLAG_DISCONT);
+    pub const RESYNC: BufferFlags = BufferFlags(GST_BUFFER_FLAG_RESYNC);
+    pub const CORRUPTED: BufferFlags = BufferFlags(GST_BUFFER_FLAG_CORRUPTED);
+    pub const MARKER: BufferFlags = BufferFlags(GST_BUFFER_FLAG_MARKER);
+    pub const HEADER: BufferFlags = BufferFlags(GST_BUFFER_FLAG_HEADER);
+    pub const GAP: BufferFlags = BufferFlags(GST_BUFFER_FLAG_GAP);
+    pub const DROPPABLE: BufferFlags = BufferFlags(GST_BUFFER_FLAG_DROPPABLE);
+    pub const DELTA_UNIT: BufferFlags = BufferFlags(GST_BUFFER_FLAG_DELTA_UNIT);
+    pub const TAG_MEMORY: BufferFlags = BufferFlags(GST_BUFFER_FLAG_TAG_MEMORY);
+    pub const SYNC_AFTER: BufferFlags = BufferFlags(GST_BUFFER_FLAG_SYNC_AFTER);
+    pub const NON_DROPPABLE: BufferFlags = BufferFlags(GST_BUFFER_FLAG_NON_DROPPABLE);
+
+    pub fn empty() -> BufferFlags{
+        BufferFlags(0)
+    }
+
+    /// The flags in bits, other bits, like the mini object flags, are
+    /// kept as they are
+    pub fn from_bits(bits: guint) -> BufferFlags{
+        BufferFlags(bits)
+    }
+
+    pub fn bits(self) -> guint{
+        self.0
+    }
+
+    pub fn is_empty(self) -> bool{
+        self.0 == 0
+    }
+
+    /// True if all the flags in other are set
+    pub fn contains(self, other: BufferFlags) -> bool{
+        self.0 & other.0 == other.0
+    }
+
+    /// True if any of the flags in other is set
+    pub fn intersects(self, other: BufferFlags) -> bool{
+        self.0 & other.0 != 0
+    }
+
+    pub fn insert(&mut self, other: BufferFlags){
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: BufferFlags){
+        self.0 &= !other.0;
+    }
+
+    fn names(self) -> Vec<&'static str>{
+        [(BufferFlags::LIVE, "LIVE"), (BufferFlags::DECODE_ONLY, "DECODE_ONLY"),
+         (BufferFlags::DISCONT, "DISCONT"), (BufferFlags::RESYNC, "RESYNC"),
+         (BufferFlags::CORRUPTED, "CORRUPTED"), (BufferFlags::MARKER, "MARKER"),
+         (BufferFlags::HEADER, "HEADER"), (BufferFlags::GAP, "GAP"),
+         (BufferFlags::DROPPABLE, "DROPPABLE"), (BufferFlags::DELTA_UNIT, "DELTA_UNIT"),
+         (BufferFlags::TAG_MEMORY, "TAG_MEMORY"), (BufferFlags::SYNC_AFTER, "SYNC_AFTER"),
+         (BufferFlags::NON_DROPPABLE, "NON_DROPPABLE")]
+            .iter()
+            .filter(|&&(flag, _)| self.contains(flag))
+            .map(|&(_, name)| name)
+            .collect()
+    }
+}
+
+impl BitOr for BufferFlags{
+    type Output = BufferFlags;
+    fn bitor(self, other: BufferFlags) -> BufferFlags{
+        BufferFlags(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for BufferFlags{
+    fn bitor_assign(&mut self, other: BufferFlags){
+        self.0 |= other.0;
+    }
+}
+
+impl BitAnd for BufferFlags{
+    type Output = BufferFlags;
+    fn bitand(self, other: BufferFlags) -> BufferFlags{
+        BufferFlags(self.0 & other.0)
+    }
+}
+
+impl Not for BufferFlags{
+    type Output = BufferFlags;
+    fn not(self) -> BufferFlags{
+        BufferFlags(!self.0)
+    }
+}
+
+impl Debug for BufferFlags{
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), Error>{
+        write!(fmt, "BufferFlags({})", self.names().join(" | "))
+    }
+}
+
+/// What a buffer of an encoded stream contains, from its flags
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum FrameKind{
+    /// Codec headers, eg: SPS/PPS, that come before the first keyframe
+    Header,
+    /// No data, only marks that the stream continues
+    Gap,
+    /// Can be decoded on its own, a stream can be cut or start here
+    Keyframe,
+    /// Needs the previous frames to be decoded
+    Delta,
+}
+
 impl Buffer{
     pub unsafe fn new(buffer: *mut GstBuffer) -> Option<Buffer>{
-		MiniObject::new_from_gst_miniobject(buffer as *mut GstMiniObject)
+		MiniObject::new(buffer)
             .map(|miniobject| Buffer{ buffer: miniobject })
     }
 
+    /// Creates a new buffer without any memory, memories can be added
+    /// later with append_memory
+    pub fn new_empty() -> Buffer{
+        unsafe{
+            Buffer::new(gst_buffer_new()).unwrap()
+        }
+    }
+
+    /// Allocates a buffer of size bytes with allocator, the default one if
+    /// None, eg: to get memory aligned for SIMD or GPU processing
+    pub fn new_allocate(allocator: Option<&Allocator>, size: usize, params: &AllocationParams) -> Option<Buffer>{
+        let mut gst_params = params.to_gst_params();
+        unsafe{
+            let allocator = allocator.map(|a| a.gst_allocator() as *mut GstAllocator).unwrap_or(ptr::null_mut());
+            Buffer::new(gst_buffer_new_allocate(allocator, size as gsize, &mut gst_params))
+        }
+    }
+
+    /// Allocates a buffer of size bytes with the default allocator,
+    /// aligned to align bytes which has to be a power of two
+    pub fn new_aligned(size: usize, align: usize) -> Option<Buffer>{
+        Buffer::new_allocate(None, size, &AllocationParams::aligned(align))
+    }
+
+    /// Creates a buffer backed by size bytes of a DMA-BUF, eg: exported
+    /// by a V4L2 device or a GPU API, without copying it. The fd is
+    /// closed when the buffer is freed
+    #[cfg(target_os="linux")]
+    pub fn from_dmabuf<F: IntoRawFd>(fd: F, size: usize) -> Option<Buffer>{
+        let memory = ::DmaBufAllocator::new()?.alloc_dmabuf(fd, size)?;
+        let mut buffer = Buffer::new_empty();
+        buffer.append_memory(memory);
+        Some(buffer)
+    }
+
+    /// Creates a buffer that uses the contents of data without copying
+    /// them, the vector is dropped once the buffer isn't used anymore
+    pub fn from_slice_zero_copy(data: Vec<u8>) -> Option<Buffer>{
+        Memory::from_vec(data).map(|memory| {
+            let mut buffer = Buffer::new_empty();
+            buffer.append_memory(memory);
+            buffer
+        })
+    }
+
+    /// Creates a buffer that wraps len bytes starting at data without
+    /// copying them. drop_fn is called once the buffer isn't used anymore
+    /// so the data can be released.
+    ///
+    /// Unsafe because data has to stay valid until drop_fn is called,
+    /// possibly from a different thread
+    pub unsafe fn wrap_with_destructor<F: FnOnce() + Send + 'static>(data: *mut u8, len: usize, drop_fn: F) -> Option<Buffer>{
+        Memory::wrap_with_destructor(data, len, drop_fn).map(|memory| {
+            let mut buffer = Buffer::new_empty();
+            buffer.append_memory(memory);
+            buffer
+        })
+    }
+
+    /// Number of memories in this buffer
+    pub fn n_memory(&self) -> u32{
+        unsafe{ gst_buffer_n_memory(self.gst_buffer() as *mut GstBuffer) }
+    }
+
+    /// Returns a new reference to the memory at idx
+    pub fn memory(&self, idx: u32) -> Option<Memory>{
+        if idx >= self.n_memory(){
+            return None;
+        }
+        unsafe{
+            Memory::new(gst_buffer_get_memory(self.gst_buffer() as *mut GstBuffer, idx))
+        }
+    }
+
+    /// Adds memory at the end of the buffer. The buffer has to be
+    /// writable, returns false otherwise
+    pub fn append_memory(&mut self, memory: Memory) -> bool{
+        if !self.is_writable(){
+            return false;
+        }
+        unsafe{
+            gst_buffer_append_memory(self.gst_buffer_mut(), memory.transfer());
+        }
+        true
+    }
+
+    /// Adds memory at the start of the buffer. The buffer has to be
+    /// writable, returns false otherwise
+    pub fn prepend_memory(&mut self, memory: Memory) -> bool{
+        if !self.is_writable(){
+            return false;
+        }
+        unsafe{
+            gst_buffer_prepend_memory(self.gst_buffer_mut(), memory.transfer());
+        }
+        true
+    }
+
+    /// Inserts memory at idx, or at the end if idx is None. The buffer
+    /// has to be writable, returns false otherwise
+    pub fn insert_memory(&mut self, idx: Option<u32>, memory: Memory) -> bool{
+        if !self.is_writable(){
+            return false;
+        }
+        unsafe{
+            let idx = idx.map(|idx| idx as gint).unwrap_or(-1);
+            gst_buffer_insert_memory(self.gst_buffer_mut(), idx, memory.transfer());
+        }
+        true
+    }
+
+    /// Removes the memory at idx. The buffer has to be writable, returns
+    /// false otherwise
+    pub fn remove_memory(&mut self, idx: u32) -> bool{
+        if !self.is_writable() || idx >= self.n_memory(){
+            return false;
+        }
+        unsafe{
+            gst_buffer_remove_memory(self.gst_buffer_mut(), idx);
+        }
+        true
+    }
+
+    /// Returns all the metas attached to the buffer
+    pub fn metas(&self) -> Vec<&Meta>{
+        let mut metas = vec![];
+        unsafe{
+            let mut state: gpointer = ptr::null_mut();
+            loop{
+                let meta = gst_buffer_iterate_meta(self.gst_buffer() as *mut GstBuffer, &mut state);
+                if meta == ptr::null_mut(){
+                    break;
+                }
+                metas.push(&*meta);
+            }
+        }
+        metas
+    }
+
+    /// Removes the first meta with the given api type, as returned by
+    /// Meta::api_type. The buffer has to be writable, returns false
+    /// otherwise or if there's no such meta
+    pub fn remove_meta(&mut self, api: GType) -> bool{
+        if !self.is_writable(){
+            return false;
+        }
+        unsafe{
+            let meta = gst_buffer_get_meta(self.gst_buffer_mut(), api);
+            meta != ptr::null_mut() && gst_buffer_remove_meta(self.gst_buffer_mut(), meta) != 0
+        }
+    }
+
+    /// Returns the layout of the video planes if the producer attached
+    /// it, when present it should be used instead of the default strides
+    /// and offsets for the format
+    pub fn video_meta(&self) -> Option<&VideoMeta>{
+        unsafe{
+            let meta = gst_buffer_get_meta(self.gst_buffer() as *mut GstBuffer, gst_video_meta_api_get_type());
+            if meta != ptr::null_mut(){
+                Some(&*(meta as *const VideoMeta))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Attaches the layout of the planes of a video frame with the given
+    /// offsets and strides per plane. The buffer has to be writable
+    pub fn add_video_meta(&mut self, format: GstVideoFormat, width: u32, height: u32, offsets: &[usize], strides: &[i32]) -> Option<&mut VideoMeta>{
+        if !self.is_writable() || offsets.len() != strides.len() || offsets.len() > 4{
+            return None;
+        }
+        let mut offset = [0 as gsize; 4];
+        let mut stride = [0 as gint; 4];
+        for (i, (o, s)) in offsets.iter().zip(strides.iter()).enumerate(){
+            offset[i] = *o as gsize;
+            stride[i] = *s;
+        }
+        unsafe{
+            let meta = gst_buffer_add_video_meta_full(self.gst_buffer_mut(), GST_VIDEO_FRAME_FLAG_NONE, format,
+                width, height, offsets.len() as guint, offset.as_mut_ptr(), stride.as_mut_ptr());
+            if meta != ptr::null_mut(){
+                Some(&mut *meta)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn video_crop_meta(&self) -> Option<&VideoCropMeta>{
+        unsafe{
+            let meta = gst_buffer_get_meta(self.gst_buffer() as *mut GstBuffer, gst_video_crop_meta_api_get_type());
+            if meta != ptr::null_mut(){
+                Some(&*(meta as *const VideoCropMeta))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Attaches the region of the frame that should be displayed. The
+    /// buffer has to be writable
+    pub fn add_video_crop_meta(&mut self, x: u32, y: u32, width: u32, height: u32) -> Option<&mut VideoCropMeta>{
+        if !self.is_writable(){
+            return None;
+        }
+        unsafe{
+            let meta = gst_buffer_add_meta(self.gst_buffer_mut(), gst_video_crop_meta_get_info(), ptr::null_mut()) as *mut VideoCropMeta;
+            if meta != ptr::null_mut(){
+                (*meta).x = x;
+                (*meta).y = y;
+                (*meta).width = width;
+                (*meta).height = height;
+                Some(&mut *meta)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns the first reference timestamp whose reference caps are
+    /// compatible with reference, or the first one if reference is None
+    pub fn reference_timestamp_meta(&self, reference: Option<&Caps>) -> Option<&ReferenceTimestampMeta>{
+        unsafe{
+            let reference = reference.map(|caps| caps.gst_caps() as *mut GstCaps).unwrap_or(ptr::null_mut());
+            let get_meta: unsafe extern "C" fn(*mut GstBuffer, *mut GstCaps) -> *mut GstReferenceTimestampMeta =
+                meta::REFERENCE_TIMESTAMP_META.function("gst_buffer_get_reference_timestamp_meta").ok()?;
+            let meta = get_meta(self.gst_buffer() as *mut GstBuffer, reference);
+            if meta != ptr::null_mut(){
+                Some(&*meta)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Attaches a timestamp from the clock described by reference. The
+    /// buffer has to be writable and it needs GStreamer 1.14, returns
+    /// None otherwise
+    pub fn add_reference_timestamp_meta(&mut self, reference: &Caps, timestamp: GstClockTime, duration: GstClockTime) -> Option<&mut ReferenceTimestampMeta>{
+        if !self.is_writable(){
+            return None;
+        }
+        unsafe{
+            let add_meta: unsafe extern "C" fn(*mut GstBuffer, *mut GstCaps, GstClockTime, GstClockTime) -> *mut GstReferenceTimestampMeta =
+                meta::REFERENCE_TIMESTAMP_META.function("gst_buffer_add_reference_timestamp_meta").ok()?;
+            let meta = add_meta(self.gst_buffer_mut(), reference.gst_caps() as *mut GstCaps, timestamp, duration);
+            if meta != ptr::null_mut(){
+                Some(&mut *meta)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Encryption parameters of the buffer, set by demuxers of
+    /// encrypted streams
+    pub fn protection_meta(&self) -> Option<&ProtectionMeta>{
+        self.metas().into_iter().filter_map(|meta| meta.protection_meta()).next()
+    }
+
+    /// Attaches the encryption parameters in info, eg: from a demuxer
+    /// implemented in rust. The buffer has to be writable and it needs
+    /// GStreamer 1.6, returns None otherwise
+    pub fn add_protection_meta(&mut self, info: &Structure) -> Option<&mut ProtectionMeta>{
+        if !self.is_writable(){
+            return None;
+        }
+        unsafe{
+            let add_meta: unsafe extern "C" fn(*mut GstBuffer, *mut GstStructure) -> *mut GstProtectionMeta =
+                meta::PROTECTION_META.function("gst_buffer_add_protection_meta").ok()?;
+            let meta = add_meta(self.gst_buffer_mut(), gst_structure_copy(info.gst_structure()));
+            if meta != ptr::null_mut(){
+                Some(&mut *meta)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Socket control messages received with the data, sources like
+    /// udpsrc or socketsrc add one per message the socket reported
+    pub fn net_control_message_metas(&self) -> Vec<&NetControlMessageMeta>{
+        self.metas().into_iter().filter_map(|meta| meta.net_control_message_meta()).collect()
+    }
+
+    /// Attaches a control message with the given level, type and raw
+    /// payload. The buffer has to be writable, GIO has to know how to
+    /// parse the message and it needs GStreamer 1.4, otherwise None is
+    /// returned
+    pub fn add_net_control_message_meta(&mut self, level: i32, msg_type: i32, data: &[u8]) -> Option<&mut NetControlMessageMeta>{
+        if !self.is_writable(){
+            return None;
+        }
+        unsafe{
+            let message = g_socket_control_message_deserialize(level, msg_type, data.len() as gsize, data.as_ptr() as gpointer);
+            self.add_net_control_message(message)
+        }
+    }
+
+    /// Attaches a control message passing fds, like an SCM_RIGHTS
+    /// message would. The fds are duplicated so the caller keeps
+    /// ownership of them. The buffer has to be writable, None if it
+    /// isn't or an fd can't be duplicated
+    #[cfg(unix)]
+    pub fn add_net_control_message_meta_fds(&mut self, fds: &[RawFd]) -> Option<&mut NetControlMessageMeta>{
+        if !self.is_writable(){
+            return None;
+        }
+        unsafe{
+            let list = g_unix_fd_list_new();
+            for &fd in fds{
+                let mut error = ptr::null_mut();
+                if g_unix_fd_list_append(list, fd, &mut error) < 0{
+                    g_error_free(error);
+                    g_object_unref(list as gpointer);
+                    return None;
+                }
+            }
+            let message = g_unix_fd_message_new_with_fd_list(list);
+            g_object_unref(list as gpointer);
+            self.add_net_control_message(message)
+        }
+    }
+
+    unsafe fn add_net_control_message(&mut self, message: *mut GSocketControlMessage) -> Option<&mut NetControlMessageMeta>{
+        if message == ptr::null_mut(){
+            return None;
+        }
+        let add_meta: unsafe extern "C" fn(*mut GstBuffer, *mut GSocketControlMessage) -> *mut GstNetControlMessageMeta =
+            match meta::NET_CONTROL_MESSAGE_META.function("gst_buffer_add_net_control_message_meta"){
+                Ok(add_meta) => add_meta,
+                Err(_) => {
+                    g_object_unref(message as gpointer);
+                    return None;
+                }
+            };
+        let meta = add_meta(self.gst_buffer_mut(), message);
+        g_object_unref(message as gpointer);
+        if meta != ptr::null_mut(){
+            Some(&mut *meta)
+        }else{
+            None
+        }
+    }
+
+    /// Attaches data to the buffer as a meta registered from rust. The
+    /// buffer has to be writable, returns false otherwise
+    pub fn add_custom_meta<T: CustomMeta>(&mut self, data: T) -> bool{
+        self.is_writable() && unsafe{ meta::add_custom_meta(self.gst_buffer_mut(), data) }
+    }
+
+    pub fn custom_meta<T: CustomMeta>(&self) -> Option<&T>{
+        unsafe{
+            meta::get_custom_meta::<T>(self.gst_buffer() as *mut GstBuffer).map(|data| &*data)
+        }
+    }
+
+    /// The data of the meta for T to modify it. The buffer has to be
+    /// writable, returns None otherwise
+    pub fn custom_meta_mut<T: CustomMeta>(&mut self) -> Option<&mut T>{
+        if !self.is_writable(){
+            return None;
+        }
+        unsafe{
+            meta::get_custom_meta::<T>(self.gst_buffer_mut())
+        }
+    }
+
     pub fn map_read<'a,F:FnMut(&::MapInfo)->U,U>(&'a self, mut f: F ) -> Result<U,()>{
         unsafe{
 	        let mut mapinfo = mem::zeroed();
@@ -53,7 +565,9 @@ impl Buffer{
 	    }
     }
 
+    /// Copies the buffer first if it's shared, see make_mut
     pub fn map_write<'a,F:FnMut(&mut ::MapInfo)->U,U>(&'a mut self, mut f: F ) -> Result<U,()>{
+        self.buffer.make_mut();
         unsafe{
 	        let mut mapinfo = mem::zeroed();
 	        if gst_buffer_map(self.gst_buffer_mut(), &mut mapinfo, GST_MAP_WRITE) != 0{
@@ -66,7 +580,11 @@ impl Buffer{
 	    }
     }
 
+    /// Copies the buffer first if it's shared and flags include Write
     pub fn map<'a,F:FnMut(&mut ::MapInfo)->U,U>(&'a mut self, flags: ::Map, mut f: F ) -> Result<U,()>{
+        if flags as u32 & GST_MAP_WRITE != 0{
+            self.buffer.make_mut();
+        }
         unsafe{
 	        let mut mapinfo = mem::zeroed();
 	        if gst_buffer_map(self.gst_buffer_mut(), &mut mapinfo, flags as u32) != 0{
@@ -79,6 +597,31 @@ impl Buffer{
 	    }
     }
 
+    /// Returns a writable version of this buffer, if the buffer is only
+    /// referenced once it's returned as is, otherwise a copy is made
+    pub fn make_writable(self) -> Buffer{
+        Buffer{ buffer: self.buffer.make_writable() }
+    }
+
+    pub fn is_writable(&self) -> bool{
+        self.buffer.is_writable()
+    }
+
+    /// Makes the buffer writable in place, copying it only if it's
+    /// shared, and returns it to modify it safely, eg: in a pad probe:
+    ///
+    /// ```ignore
+    /// if let Some(buffer) = info.buffer_mut(){
+    ///     buffer.make_mut().set_flags(gst::BufferFlags::DISCONT);
+    /// }
+    /// ```
+    ///
+    /// The buffer stays writable until a new reference to it is taken
+    pub fn make_mut(&mut self) -> &mut Buffer{
+        self.buffer.make_mut();
+        self
+    }
+
     pub fn size(&self) -> u64{
         unsafe{ gst_buffer_get_size(self.gst_buffer() as *mut GstBuffer) as u64 }
     }
@@ -88,11 +631,43 @@ impl Buffer{
 	}
 
     pub unsafe fn gst_buffer(&self) -> *const GstBuffer{
-        self.buffer.gst_miniobject() as *const GstBuffer
+        self.buffer.as_ptr()
     }
 
     pub unsafe fn gst_buffer_mut(&mut self) -> *mut GstBuffer{
-        self.buffer.gst_miniobject_mut() as *mut GstBuffer
+        self.buffer.as_mut_ptr()
+    }
+
+    /// Presentation timestamp in nanoseconds, None if unknown
+    pub fn pts(&self) -> Option<u64>{
+        time_to_option(unsafe{ (*self.gst_buffer()).pts })
+    }
+
+    /// Sets the pts, like the rest of setters that can't fail it makes
+    /// the buffer writable first, copying it if it's shared
+    pub fn set_pts(&mut self, pts: Option<u64>){
+        self.buffer.make_mut();
+        unsafe{ (*self.gst_buffer_mut()).pts = pts.unwrap_or(GST_CLOCK_TIME_NONE) }
+    }
+
+    /// Decoding timestamp in nanoseconds, None if unknown
+    pub fn dts(&self) -> Option<u64>{
+        time_to_option(unsafe{ (*self.gst_buffer()).dts })
+    }
+
+    pub fn set_dts(&mut self, dts: Option<u64>){
+        self.buffer.make_mut();
+        unsafe{ (*self.gst_buffer_mut()).dts = dts.unwrap_or(GST_CLOCK_TIME_NONE) }
+    }
+
+    /// Duration in nanoseconds, None if unknown
+    pub fn duration(&self) -> Option<u64>{
+        time_to_option(unsafe{ (*self.gst_buffer()).duration })
+    }
+
+    pub fn set_duration(&mut self, duration: Option<u64>){
+        self.buffer.make_mut();
+        unsafe{ (*self.gst_buffer_mut()).duration = duration.unwrap_or(GST_CLOCK_TIME_NONE) }
     }
 
     pub fn flags(&self) -> guint {
@@ -110,11 +685,53 @@ impl Buffer{
     gst_buffer_flag!(is_droppable, set_droppable, GST_BUFFER_FLAG_DROPPABLE);
     gst_buffer_flag!(is_delta_unit, set_delta_unit, GST_BUFFER_FLAG_DELTA_UNIT);
     gst_buffer_flag!(is_tag_memory, set_tag_memory, GST_BUFFER_FLAG_TAG_MEMORY);
+
+    /// The GST_BUFFER_FLAG_* flags of the buffer as a set
+    pub fn buffer_flags(&self) -> BufferFlags{
+        BufferFlags::from_bits(self.flags()) & BufferFlags(!(GST_MINI_OBJECT_FLAG_LAST - 1))
+    }
+
+    /// Sets the flags in flags, leaving the rest as they are
+    pub fn set_flags(&mut self, flags: BufferFlags){
+        self.buffer.make_mut();
+        unsafe{
+            (*self.gst_buffer_mut()).mini_object.flags |= flags.bits();
+        }
+    }
+
+    /// Clears the flags in flags, leaving the rest as they are
+    pub fn unset_flags(&mut self, flags: BufferFlags){
+        self.buffer.make_mut();
+        unsafe{
+            (*self.gst_buffer_mut()).mini_object.flags &= !flags.bits();
+        }
+    }
+
+    /// True if the buffer can be decoded on its own, eg: to start a new
+    /// file at it when recording in segments
+    pub fn is_keyframe(&self) -> bool{
+        self.frame_kind() == FrameKind::Keyframe
+    }
+
+    /// Classifies the buffer from its flags. Streams whose frames are all
+    /// keyframes, like raw video or most audio, never set DELTA_UNIT
+    pub fn frame_kind(&self) -> FrameKind{
+        let flags = self.buffer_flags();
+        if flags.contains(BufferFlags::HEADER){
+            FrameKind::Header
+        }else if flags.contains(BufferFlags::GAP){
+            FrameKind::Gap
+        }else if flags.contains(BufferFlags::DELTA_UNIT){
+            FrameKind::Delta
+        }else{
+            FrameKind::Keyframe
+        }
+    }
 }
 
 impl ::Transfer<GstBuffer> for Buffer{
     unsafe fn transfer(self) ->  *mut GstBuffer{
-        self.buffer.transfer() as *mut GstBuffer
+        self.buffer.transfer()
     }
 }
 
@@ -155,31 +772,31 @@ impl Debug for Buffer {
 
 impl AsRef<MiniObject> for Buffer{
     fn as_ref(&self) -> &MiniObject{
-        &self.buffer
+        self.buffer.upcast_ref()
     }
 }
 
 impl AsMut<MiniObject> for Buffer{
     fn as_mut(&mut self) -> &mut MiniObject{
-        &mut self.buffer
+        self.buffer.upcast_mut()
     }
 }
 
 impl From<Buffer> for MiniObject{
     fn from(b: Buffer) -> MiniObject{
-        b.buffer
+        b.buffer.upcast()
     }
 }
 
 impl Deref for Buffer{
     type Target = MiniObject;
     fn deref(&self) -> &MiniObject{
-        &self.buffer
+        self.buffer.upcast_ref()
     }
 }
 
 impl DerefMut for Buffer{
     fn deref_mut(&mut self) -> &mut MiniObject{
-        &mut self.buffer
+        self.buffer.upcast_mut()
     }
 }
@@ -0,0 +1,201 @@
+use ffi::*;
+use buffer::Buffer;
+use reference::Reference;
+use miniobject::MiniObject;
+use ::Transfer;
+
+use std::ptr;
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+/// A group of buffers pushed downstream in a single call, eg: the RTP
+/// packets of a video frame, so the locking and the checks done for each
+/// push happen once per list instead of once per buffer:
+///
+/// ```ignore
+/// let list: gst::BufferList = packets.into_iter().filter_map(gst::Buffer::from_slice_zero_copy).collect();
+/// appsrc.push_buffer_list(list);
+/// ```
+#[derive(Clone)]
+pub struct BufferList{
+    list: MiniObject<GstBufferList>
+}
+
+impl BufferList{
+    pub unsafe fn new_from_gst_buffer_list(list: *mut GstBufferList) -> Option<BufferList>{
+        MiniObject::new(list)
+            .map(|miniobject| BufferList{ list: miniobject })
+    }
+
+    pub fn new() -> BufferList{
+        unsafe{
+            BufferList::new_from_gst_buffer_list(gst_buffer_list_new()).unwrap()
+        }
+    }
+
+    /// Creates an empty list with room for capacity buffers
+    pub fn with_capacity(capacity: u32) -> BufferList{
+        unsafe{
+            BufferList::new_from_gst_buffer_list(gst_buffer_list_new_sized(capacity)).unwrap()
+        }
+    }
+
+    /// Adds buffer at the end of the list, copying the list first if
+    /// it's shared, see make_mut
+    pub fn push(&mut self, buffer: Buffer){
+        self.insert(-1, buffer)
+    }
+
+    /// Inserts buffer at idx, -1 to add it at the end, copying the list
+    /// first if it's shared, see make_mut
+    pub fn insert(&mut self, idx: i32, buffer: Buffer){
+        unsafe{
+            gst_buffer_list_insert(self.make_mut().gst_buffer_list_mut(), idx, buffer.transfer());
+        }
+    }
+
+    /// Removes len buffers starting at idx, copying the list first if
+    /// it's shared, see make_mut. Returns false if they are out of the
+    /// list
+    pub fn remove(&mut self, idx: u32, len: u32) -> bool{
+        if idx.saturating_add(len) > self.len(){
+            return false;
+        }
+        unsafe{
+            gst_buffer_list_remove(self.make_mut().gst_buffer_list_mut(), idx, len);
+        }
+        true
+    }
+
+    pub fn len(&self) -> u32{
+        unsafe{
+            gst_buffer_list_length(self.gst_buffer_list() as *mut GstBufferList)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+
+    /// A new reference to the buffer at idx
+    pub fn get(&self, idx: u32) -> Option<Buffer>{
+        if idx >= self.len(){
+            return None;
+        }
+        unsafe{
+            let buffer = gst_buffer_list_get(self.gst_buffer_list() as *mut GstBufferList, idx);
+            if buffer != ptr::null_mut(){
+                gst_mini_object_ref(buffer as *mut GstMiniObject);
+            }
+            Buffer::new(buffer)
+        }
+    }
+
+    pub fn iter<'a>(&'a self) -> BufferListIter<'a>{
+        BufferListIter{
+            list: self,
+            idx: 0,
+        }
+    }
+
+    /// Total size in bytes of the buffers in the list
+    pub fn size(&self) -> u64{
+        self.iter().map(|buffer| buffer.size()).sum()
+    }
+
+    pub fn is_writable(&self) -> bool{
+        unsafe{
+            gst_mini_object_is_writable(self.list.gst_miniobject()) != 0
+        }
+    }
+
+    pub fn make_writable(self) -> BufferList{
+        BufferList{ list: self.list.make_writable() }
+    }
+
+    /// Makes the list writable in place, copying it only if it's shared
+    pub fn make_mut(&mut self) -> &mut BufferList{
+        self.list.make_mut();
+        self
+    }
+
+    pub unsafe fn gst_buffer_list(&self) -> *const GstBufferList{
+        self.list.as_ptr()
+    }
+
+    pub unsafe fn gst_buffer_list_mut(&mut self) -> *mut GstBufferList{
+        self.list.as_mut_ptr()
+    }
+}
+
+/// Iterates over new references to the buffers of a BufferList
+pub struct BufferListIter<'a>{
+    list: &'a BufferList,
+    idx: u32,
+}
+
+impl<'a> Iterator for BufferListIter<'a>{
+    type Item = Buffer;
+    fn next(&mut self) -> Option<Buffer>{
+        let buffer = self.list.get(self.idx);
+        if buffer.is_some(){
+            self.idx += 1;
+        }
+        buffer
+    }
+}
+
+impl<'a> IntoIterator for &'a BufferList{
+    type Item = Buffer;
+    type IntoIter = BufferListIter<'a>;
+    fn into_iter(self) -> BufferListIter<'a>{
+        self.iter()
+    }
+}
+
+impl FromIterator<Buffer> for BufferList{
+    fn from_iter<I: IntoIterator<Item = Buffer>>(buffers: I) -> BufferList{
+        let mut list = BufferList::new();
+        for buffer in buffers{
+            list.push(buffer);
+        }
+        list
+    }
+}
+
+impl ::Transfer<GstBufferList> for BufferList{
+    unsafe fn transfer(self) -> *mut GstBufferList{
+        self.list.transfer()
+    }
+}
+
+impl Reference for BufferList{
+    fn reference(&self) -> BufferList{
+        BufferList{ list: self.list.reference() }
+    }
+}
+
+impl AsRef<MiniObject> for BufferList{
+    fn as_ref(&self) -> &MiniObject{
+        self.list.upcast_ref()
+    }
+}
+
+impl AsMut<MiniObject> for BufferList{
+    fn as_mut(&mut self) -> &mut MiniObject{
+        self.list.upcast_mut()
+    }
+}
+
+impl Deref for BufferList{
+    type Target = MiniObject;
+    fn deref(&self) -> &MiniObject{
+        self.list.upcast_ref()
+    }
+}
+
+impl DerefMut for BufferList{
+    fn deref_mut(&mut self) -> &mut MiniObject{
+        self.list.upcast_mut()
+    }
+}
@@ -1,26 +1,23 @@
 use ffi::*;
 use std::ptr;
-use std::mem;
 use std::os::raw::c_void;
 use std::ops::{Deref, DerefMut};
 use ::Buffer;
 use ::Object;
+use ::Transfer;
 use reference::Reference;
 
 pub struct BufferPool{
-    pool: Object
+    pool: Object<GstBufferPool>
 }
 
-unsafe impl Sync for BufferPool {}
-unsafe impl Send for BufferPool {}
-
 impl BufferPool{
     pub fn new() -> Option<BufferPool>{
         unsafe{
 	        let pool = gst_buffer_pool_new();
 	        if pool!=ptr::null_mut(){
                 gst_object_ref_sink(pool as *mut c_void);
-	        	Some(BufferPool{ pool: Object::new(pool as *mut GstObject).unwrap() })
+	        	Some(BufferPool{ pool: Object::new(pool).unwrap() })
 	        }else{
 	            None
 	        }
@@ -28,6 +25,16 @@ impl BufferPool{
     }
 
     pub fn set_params(&mut self, caps: &::Caps, size: u32, min_buffers: u32, max_buffers: u32){
+        self.set_config(caps, size, min_buffers, max_buffers);
+    }
+
+    /// Configures the pool to allocate buffers of size bytes for the
+    /// given caps, keeping at least min_buffers allocated and never more
+    /// than max_buffers, 0 meaning unlimited.
+    ///
+    /// The pool can only be configured while inactive, returns false if
+    /// the configuration wasn't accepted
+    pub fn set_config(&mut self, caps: &::Caps, size: u32, min_buffers: u32, max_buffers: u32) -> bool{
         unsafe{
 	        let config = gst_buffer_pool_get_config(self.gst_bufferpool_mut());
 	        /*let mut current_caps = gst_caps_new_empty();
@@ -37,7 +44,7 @@ impl BufferPool{
 	        gst_buffer_pool_config_get_params(config, &mut current_caps, &mut curret_size, &mut current_min_buffers, &mut current_max_buffers);
 			gst_mini_object_unref(current_caps as *mut GstMiniObject);*/
 
-			gst_buffer_pool_config_set_params(config, mem::transmute(caps.gst_caps()), size, min_buffers, max_buffers);
+			gst_buffer_pool_config_set_params(config, caps.gst_caps() as *mut GstCaps, size, min_buffers, max_buffers);
             /*let mut params = GstAllocationParams {
 			    flags: GST_MEMORY_FLAG_PHYSICALLY_CONTIGUOUS,
 			    align: 0,
@@ -48,10 +55,65 @@ impl BufferPool{
             gst_allocation_params_init(&mut params);
             params.flags = GST_MEMORY_FLAG_PHYSICALLY_CONTIGUOUS;
             gst_buffer_pool_config_set_allocator(config,ptr::null_mut(),&params);*/
-            gst_buffer_pool_set_config(self.gst_bufferpool_mut(), config);
+            gst_buffer_pool_set_config(self.gst_bufferpool_mut(), config) != 0
 		}
     }
 
+    /// Returns the current configuration as (caps, size, min_buffers,
+    /// max_buffers)
+    pub fn config(&self) -> Option<(::Caps, u32, u32, u32)>{
+        unsafe{
+            let config = gst_buffer_pool_get_config(self.gst_bufferpool() as *mut GstBufferPool);
+            let mut caps: *mut GstCaps = ptr::null_mut();
+            let mut size = 0;
+            let mut min_buffers = 0;
+            let mut max_buffers = 0;
+            let ret = gst_buffer_pool_config_get_params(config, &mut caps, &mut size, &mut min_buffers, &mut max_buffers);
+            let caps = if ret != 0 && caps != ptr::null_mut(){
+                gst_mini_object_ref(caps as *mut GstMiniObject);
+                ::Caps::new(caps)
+            }else{
+                None
+            };
+            gst_structure_free(config);
+            caps.map(|caps| (caps, size, min_buffers, max_buffers))
+        }
+    }
+
+    /// Makes the pool allocate its buffers with allocator, the default
+    /// one if None, and params, eg: to get buffers aligned for SIMD. Like
+    /// set_config it only works while the pool is inactive
+    pub fn set_allocator(&mut self, allocator: Option<&::Allocator>, params: &::AllocationParams) -> bool{
+        let gst_params = params.to_gst_params();
+        unsafe{
+            let config = gst_buffer_pool_get_config(self.gst_bufferpool_mut());
+            let allocator = allocator.map(|a| a.gst_allocator() as *mut GstAllocator).unwrap_or(ptr::null_mut());
+            gst_buffer_pool_config_set_allocator(config, allocator, &gst_params);
+            gst_buffer_pool_set_config(self.gst_bufferpool_mut(), config) != 0
+        }
+    }
+
+    /// Returns the allocator, None for the default one, and the params
+    /// the pool allocates its buffers with
+    pub fn allocator(&self) -> Option<(Option<::Allocator>, ::AllocationParams)>{
+        unsafe{
+            let config = gst_buffer_pool_get_config(self.gst_bufferpool() as *mut GstBufferPool);
+            let mut allocator: *mut GstAllocator = ptr::null_mut();
+            let mut params = GstAllocationParams::default();
+            let ret = gst_buffer_pool_config_get_allocator(config, &mut allocator, &mut params);
+            let result = if ret != 0{
+                if allocator != ptr::null_mut(){
+                    gst_object_ref(allocator as gpointer);
+                }
+                Some((::Allocator::new(allocator), ::AllocationParams::from_gst_params(&params)))
+            }else{
+                None
+            };
+            gst_structure_free(config);
+            result
+        }
+    }
+
     pub fn acquire_buffer(&mut self) -> Option<Buffer>{
         /*let mut params = GstBufferPoolAcquireParams{
             format: GST_FORMAT_DEFAULT,
@@ -71,6 +133,27 @@ impl BufferPool{
 	    }
     }
 
+    /// Returns a buffer to the pool so it can be reused by a later
+    /// acquire_buffer().
+    ///
+    /// Dropping the last reference to a buffer acquired from the pool
+    /// also returns it, so this is only needed to give it back
+    /// explicitly before the buffer would otherwise be dropped.
+    ///
+    /// Returns false if there are other references to the buffer, which
+    /// would see it reused while still in use. The buffer is only
+    /// unreffed then and goes back to the pool once the last reference
+    /// is dropped
+    pub fn release_buffer(&mut self, buffer: Buffer) -> bool{
+        if !buffer.is_writable(){
+            return false;
+        }
+        unsafe{
+            gst_buffer_pool_release_buffer(self.gst_bufferpool_mut(), buffer.transfer());
+        }
+        true
+    }
+
     pub fn active(&self) -> bool{
         unsafe{
             gst_buffer_pool_is_active(self.gst_bufferpool() as *mut GstBufferPool) != 0
@@ -88,18 +171,18 @@ impl BufferPool{
     }
 
     pub unsafe fn gst_bufferpool(&self) -> *const GstBufferPool{
-        self.pool.gst_object() as *const GstBufferPool
+        self.pool.as_ptr()
     }
 
     pub unsafe fn gst_bufferpool_mut(&mut self) -> *mut GstBufferPool{
-        self.pool.gst_object_mut() as *mut GstBufferPool
+        self.pool.as_mut_ptr()
     }
 }
 
 
 impl ::Transfer<GstBufferPool> for BufferPool{
     unsafe fn transfer(self) -> *mut GstBufferPool{
-        self.pool.transfer() as *mut GstBufferPool
+        self.pool.transfer()
     }
 }
 
@@ -111,31 +194,31 @@ impl Reference for BufferPool{
 
 impl AsRef<Object> for BufferPool{
     fn as_ref(&self) -> &Object{
-        &self.pool
+        self.pool.upcast_ref()
     }
 }
 
 impl AsMut<Object> for BufferPool{
     fn as_mut(&mut self) -> &mut Object{
-        &mut self.pool
+        self.pool.upcast_mut()
     }
 }
 
 impl From<BufferPool> for Object{
     fn from(b: BufferPool) -> Object{
-        b.pool
+        b.pool.upcast()
     }
 }
 
 impl Deref for BufferPool{
     type Target = Object;
     fn deref(&self) -> &Object{
-        &self.pool
+        self.pool.upcast_ref()
     }
 }
 
 impl DerefMut for BufferPool{
     fn deref_mut(&mut self) -> &mut Object{
-        &mut self.pool
+        self.pool.upcast_mut()
     }
 }
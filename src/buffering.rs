@@ -0,0 +1,102 @@
+use ffi::*;
+use util::*;
+
+/// The contents of a Buffering message, see Message::buffering
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct BufferingMessage{
+    /// How full the buffer is, the pipeline should stay paused until it
+    /// reaches 100
+    pub percent: i32,
+    /// GST_BUFFERING_STREAM, GST_BUFFERING_DOWNLOAD,
+    /// GST_BUFFERING_TIMESHIFT or GST_BUFFERING_LIVE
+    pub mode: GstBufferingMode,
+    /// Average rate at which data comes in, in bytes per second, -1 if
+    /// unknown
+    pub avg_in: i32,
+    /// Average rate at which data is consumed, in bytes per second, -1
+    /// if unknown
+    pub avg_out: i32,
+    /// Estimated milliseconds until buffering finishes, None if unknown
+    pub buffering_left: Option<i64>,
+}
+
+impl BufferingMessage{
+    pub unsafe fn new_from_gst_message(message: *mut GstMessage) -> Option<BufferingMessage>{
+        if message == ptr::null_mut() || (*message)._type != GST_MESSAGE_BUFFERING{
+            return None;
+        }
+        let mut buffering = BufferingMessage{
+            percent: 0,
+            mode: GST_BUFFERING_STREAM,
+            avg_in: -1,
+            avg_out: -1,
+            buffering_left: None,
+        };
+        let mut buffering_left: gint64 = -1;
+        gst_message_parse_buffering(message, &mut buffering.percent);
+        gst_message_parse_buffering_stats(message, &mut buffering.mode, &mut buffering.avg_in, &mut buffering.avg_out, &mut buffering_left);
+        if buffering_left >= 0{
+            buffering.buffering_left = Some(buffering_left);
+        }
+        Some(buffering)
+    }
+}
+
+/// The result of a buffering query, see Element::query_buffering
+#[derive(Clone,Debug,PartialEq)]
+pub struct BufferingQuery{
+    /// Whether the element is currently buffering
+    pub busy: bool,
+    pub percent: i32,
+    pub mode: GstBufferingMode,
+    pub avg_in: i32,
+    pub avg_out: i32,
+    /// Estimated milliseconds until buffering finishes, None if unknown
+    pub buffering_left: Option<i64>,
+    /// Format of start, stop, estimated_total and ranges
+    pub format: GstFormat,
+    /// Range of the stream that's currently buffered, -1 if unknown
+    pub start: i64,
+    pub stop: i64,
+    /// Estimated total size of the stream when downloading, -1 if
+    /// unknown
+    pub estimated_total: i64,
+    /// Every range of the stream that's available, eg: to draw the
+    /// downloaded parts in a seek bar
+    pub ranges: Vec<(i64, i64)>,
+}
+
+impl BufferingQuery{
+    pub unsafe fn new_from_gst_query(query: *mut GstQuery) -> BufferingQuery{
+        let mut busy: gboolean = 0;
+        let mut buffering_left: gint64 = -1;
+        let mut result = BufferingQuery{
+            busy: false,
+            percent: 0,
+            mode: GST_BUFFERING_STREAM,
+            avg_in: -1,
+            avg_out: -1,
+            buffering_left: None,
+            format: GST_FORMAT_UNDEFINED,
+            start: -1,
+            stop: -1,
+            estimated_total: -1,
+            ranges: vec![],
+        };
+        gst_query_parse_buffering_percent(query, &mut busy, &mut result.percent);
+        gst_query_parse_buffering_stats(query, &mut result.mode, &mut result.avg_in, &mut result.avg_out, &mut buffering_left);
+        gst_query_parse_buffering_range(query, &mut result.format, &mut result.start, &mut result.stop, &mut result.estimated_total);
+        for i in 0..gst_query_get_n_buffering_ranges(query){
+            let mut start: gint64 = 0;
+            let mut stop: gint64 = 0;
+            if gst_query_parse_nth_buffering_range(query, i, &mut start, &mut stop) != 0{
+                result.ranges.push((start, stop));
+            }
+        }
+        result.busy = busy != 0;
+        if buffering_left >= 0{
+            result.buffering_left = Some(buffering_left);
+        }
+        result
+    }
+}
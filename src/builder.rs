@@ -0,0 +1,252 @@
+use ffi::*;
+use caps::Caps;
+use element::Element;
+use pipeline::Pipeline;
+use reference::Reference;
+
+use std::error;
+use std::fmt::{self, Display, Debug, Formatter};
+
+/// Builds a linear pipeline element by element, in between a
+/// gst-launch string and adding and linking the elements by hand:
+///
+/// ```ignore
+/// let pipeline = gst::builder::PipelineBuilder::new()
+///     .src(gst::Element::new("v4l2src", "").unwrap())
+///     .pipe("videoconvert")
+///     .pipe_with("x264enc", |e| e.set("bitrate", 2048))
+///     .sink("filesink", |e| e.set("location", "out.h264"))
+///     .build()?;
+/// ```
+///
+/// Errors are deferred until build() so the calls can be chained, the
+/// first one is returned saying which step failed.
+pub struct PipelineBuilder{
+    name: Option<String>,
+    steps: Vec<Step>,
+    caps: Option<String>,
+    error: Option<BuildError>,
+    has_sink: bool,
+}
+
+struct Step{
+    element: Element,
+    // caps to filter the link from the previous step
+    caps: Option<String>,
+}
+
+/// What failed while building
+#[derive(Clone,Debug,PartialEq)]
+pub enum BuildErrorKind{
+    /// The pipeline couldn't be created
+    Pipeline,
+    /// There's no element for the factory, probably a missing plugin
+    NoFactory(String),
+    /// The builder has no source, src() wasn't the first call
+    NoSource,
+    /// The builder has no sink or there are steps after it
+    NoSink,
+    /// The element couldn't be added to the pipeline, usually because
+    /// its name is taken
+    Add(String),
+    /// The caps string couldn't be parsed
+    Caps(String),
+    /// The element couldn't be linked to the previous one, the formats
+    /// are incompatible or there are no free pads
+    Link{src: String, sink: String, caps: Option<String>},
+}
+
+/// Error returned by PipelineBuilder::build, step is the position of
+/// the element in the chain starting at 0 for the source
+#[derive(Clone,PartialEq)]
+pub struct BuildError{
+    pub step: usize,
+    pub kind: BuildErrorKind,
+}
+
+impl Display for BuildError{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        match self.kind{
+            BuildErrorKind::Pipeline => write!(fmt, "couldn't create pipeline"),
+            BuildErrorKind::NoFactory(ref factory) => write!(fmt, "step {}: no element {}, is the plugin installed?", self.step, factory),
+            BuildErrorKind::NoSource => write!(fmt, "step {}: the pipeline needs a source first", self.step),
+            BuildErrorKind::NoSink => write!(fmt, "step {}: the pipeline needs a sink last", self.step),
+            BuildErrorKind::Add(ref name) => write!(fmt, "step {}: couldn't add {} to the pipeline", self.step, name),
+            BuildErrorKind::Caps(ref caps) => write!(fmt, "step {}: invalid caps {}", self.step, caps),
+            BuildErrorKind::Link{ref src, ref sink, caps: Some(ref caps)} =>
+                write!(fmt, "step {}: couldn't link {} to {} with caps {}", self.step, src, sink, caps),
+            BuildErrorKind::Link{ref src, ref sink, caps: None} =>
+                write!(fmt, "step {}: couldn't link {} to {}", self.step, src, sink),
+        }
+    }
+}
+
+impl Debug for BuildError{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        Display::fmt(self, fmt)
+    }
+}
+
+impl error::Error for BuildError{}
+
+pub type Result<T> = ::std::result::Result<T, BuildError>;
+
+impl PipelineBuilder{
+    pub fn new() -> PipelineBuilder{
+        PipelineBuilder{
+            name: None,
+            steps: vec![],
+            caps: None,
+            error: None,
+            has_sink: false,
+        }
+    }
+
+    /// Name of the pipeline, an automatic one if not set
+    pub fn name(mut self, name: &str) -> PipelineBuilder{
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// The first element of the chain
+    pub fn src(mut self, element: Element) -> PipelineBuilder{
+        if !self.steps.is_empty(){
+            self.fail(BuildErrorKind::NoSource);
+        }
+        self.push(element);
+        self
+    }
+
+    /// Appends a new element from factory, linked to the previous one
+    pub fn pipe(self, factory: &str) -> PipelineBuilder{
+        self.pipe_with(factory, |_| ())
+    }
+
+    /// Same as pipe but calling configure with the element before
+    /// linking it, eg: to set its properties
+    pub fn pipe_with<F: FnOnce(&mut Element)>(mut self, factory: &str, configure: F) -> PipelineBuilder{
+        if let Some(mut element) = self.make(factory){
+            configure(&mut element);
+            self.push_middle(element);
+        }
+        self
+    }
+
+    /// Appends an already created element, linked to the previous one
+    pub fn pipe_element(mut self, element: Element) -> PipelineBuilder{
+        self.push_middle(element);
+        self
+    }
+
+    /// Restricts the format of the next link, eg:
+    /// "video/x-raw,width=640,height=480"
+    pub fn caps(mut self, caps: &str) -> PipelineBuilder{
+        self.caps = Some(caps.to_string());
+        self
+    }
+
+    /// The last element of the chain, created from factory and
+    /// configured by configure
+    pub fn sink<F: FnOnce(&mut Element)>(mut self, factory: &str, configure: F) -> PipelineBuilder{
+        if let Some(mut element) = self.make(factory){
+            configure(&mut element);
+            self.push_sink(element);
+        }
+        self
+    }
+
+    /// Same as sink with an already created element
+    pub fn sink_element(mut self, element: Element) -> PipelineBuilder{
+        self.push_sink(element);
+        self
+    }
+
+    /// Creates the pipeline, adding and linking all the elements
+    pub fn build(self) -> Result<Pipeline>{
+        if let Some(error) = self.error{
+            return Err(error);
+        }
+        if self.steps.is_empty(){
+            return Err(BuildError{ step: 0, kind: BuildErrorKind::NoSource });
+        }
+        if !self.has_sink{
+            return Err(BuildError{ step: self.steps.len(), kind: BuildErrorKind::NoSink });
+        }
+        let mut pipeline = Pipeline::new(self.name.as_ref().map(|name| &name[..]).unwrap_or(""))
+            .ok_or(BuildError{ step: 0, kind: BuildErrorKind::Pipeline })?;
+        let mut previous: Option<Element> = None;
+        for (i, step) in self.steps.into_iter().enumerate(){
+            let mut element = step.element;
+            let name = element.name();
+            if !pipeline.add(element.reference()){
+                return Err(BuildError{ step: i, kind: BuildErrorKind::Add(name) });
+            }
+            if let Some(mut src) = previous.take(){
+                link(&mut src, &mut element, step.caps).map_err(|kind| BuildError{ step: i, kind: kind })?;
+            }
+            previous = Some(element);
+        }
+        Ok(pipeline)
+    }
+
+    fn make(&mut self, factory: &str) -> Option<Element>{
+        if self.error.is_some(){
+            return None;
+        }
+        let element = Element::new(factory, "");
+        if element.is_none(){
+            self.fail(BuildErrorKind::NoFactory(factory.to_string()));
+        }
+        element
+    }
+
+    fn push_middle(&mut self, element: Element){
+        if self.steps.is_empty(){
+            self.fail(BuildErrorKind::NoSource);
+        }
+        self.push(element);
+    }
+
+    fn push_sink(&mut self, element: Element){
+        self.push_middle(element);
+        self.has_sink = true;
+    }
+
+    fn push(&mut self, element: Element){
+        if self.has_sink{
+            self.fail(BuildErrorKind::NoSink);
+        }
+        let caps = self.caps.take();
+        self.steps.push(Step{ element: element, caps: caps });
+    }
+
+    // keeps only the first error
+    fn fail(&mut self, kind: BuildErrorKind){
+        if self.error.is_none(){
+            self.error = Some(BuildError{ step: self.steps.len(), kind: kind });
+        }
+    }
+}
+
+impl Default for PipelineBuilder{
+    fn default() -> PipelineBuilder{
+        PipelineBuilder::new()
+    }
+}
+
+fn link(src: &mut Element, sink: &mut Element, caps: Option<String>) -> ::std::result::Result<(), BuildErrorKind>{
+    let linked = match caps{
+        Some(ref caps_str) => {
+            let mut filter = Caps::from_string(caps_str).ok_or(BuildErrorKind::Caps(caps_str.clone()))?;
+            unsafe{
+                gst_element_link_filtered(src.gst_element_mut(), sink.gst_element_mut(), filter.gst_caps_mut()) != 0
+            }
+        }
+        None => src.link(sink),
+    };
+    if linked{
+        Ok(())
+    }else{
+        Err(BuildErrorKind::Link{ src: src.name(), sink: sink.name(), caps: caps })
+    }
+}
@@ -1,31 +1,129 @@
 use ffi::*;
 
 use std::sync::mpsc::{self,channel,Receiver};
+use std::collections::VecDeque;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, MutexGuard};
+use std::cell::RefCell;
 
 use message::Message;
 use util::*;
-use reference::Reference;
+use ::Transfer;
+use reference::{Reference, Downgrade};
 use object::Object;
+use mainloop::MainContext;
+use panic::catch_panic;
+use error::{Error, Result};
+use handle::WatchHandle;
+
+#[cfg(feature = "futures")]
+use futures::channel::mpsc::{unbounded, UnboundedReceiver};
+#[cfg(feature = "futures")]
+use futures::stream::Stream;
+#[cfg(feature = "futures")]
+use futures::task::{Context, Poll};
+#[cfg(feature = "futures")]
+use std::pin::Pin;
 
 static REMOVE_WATCH_MESSAGE_STR: &'static str = "gstreamer1.0-rs_remove_watch_message";
 
-unsafe impl Sync for Bus {}
-unsafe impl Send for Bus {}
+// the sync handler set from rust on each bus is kept as qdata so
+// BusStream can tell if it's still the one it set. Held while setting or
+// unsetting one so checking and unsetting it happen at once
+static SYNC_HANDLER_LOCK: Mutex<()> = Mutex::new(());
+
+fn sync_handler_quark() -> GQuark{
+    unsafe{
+        g_quark_from_static_string(b"gstreamer1.0-rs-sync-handler\0".as_ptr() as *const gchar)
+    }
+}
+
+// messages pop_filtered_from took from a bus while looking for one from
+// another source, oldest first. Kept as qdata of the GstBus so every Bus
+// wrapping it sees them, the pop methods look at them before the ones
+// still on the bus
+type SkippedMessages = Mutex<VecDeque<Message>>;
+
+// held while creating the skipped messages of a bus
+static SKIPPED_LOCK: Mutex<()> = Mutex::new(());
+
+fn skipped_quark() -> GQuark{
+    unsafe{
+        g_quark_from_static_string(b"gstreamer1.0-rs-skipped-messages\0".as_ptr() as *const gchar)
+    }
+}
+
+extern "C" fn skipped_destroy(data: gpointer){
+    unsafe{
+        catch_panic(ptr::null_mut(), (), || drop(Box::from_raw(data as *mut SkippedMessages)));
+    }
+}
 
 pub struct Bus{
-    bus: Object
+    bus: Object<GstBus>,
 }
 
 impl Bus{
     pub unsafe fn new(bus: *mut GstBus) -> Option<Bus>{
-        Object::new(bus as *mut GstObject).map(|obj| Bus{bus: obj})
+        Object::new(bus).map(|obj| Bus{bus: obj})
     }
 
-    pub fn add_watch<W: Watch>(&mut self, watch: W) -> u32{
+    /// Calls watch for every message posted on the bus, until it returns
+    /// false, remove_watch() is called or the returned handle is dropped.
+    /// None if the bus already has a watch.
+    ///
+    /// The watch is attached to the thread-default main context of the
+    /// calling thread, which is the global default context unless a
+    /// context was pushed with MainContext::push_thread_default, so it
+    /// will be dispatched by the MainLoop running that context.
+    pub fn add_watch<W: Watch>(&mut self, watch: W) -> Option<WatchHandle>{
         unsafe{
-            let watch: Box<Watch> = Box::new(watch);
-            let watch: *mut Box<Watch> = Box::into_raw(Box::new(watch));
-            gst_bus_add_watch (self.gst_bus_mut(), Some(bus_callback), mem::transmute(watch))
+            let watch: Guarded<dyn Watch> = Guarded::new(Box::new(watch));
+            let watch: *mut Guarded<dyn Watch> = Box::into_raw(Box::new(watch));
+            let id = gst_bus_add_watch_full(self.gst_bus_mut(), 0, Some(bus_callback), watch as gpointer, Some(bus_watch_destroy));
+            if id == 0{
+                return None;
+            }
+            let context = g_main_context_ref_thread_default();
+            let handle = WatchHandle::new(context, id);
+            g_main_context_unref(context);
+            Some(handle)
+        }
+    }
+
+    /// Same as add_watch but the watch is dispatched from the passed
+    /// context instead of the thread-default one, so it'll be called from
+    /// whichever thread is running a MainLoop or iterating that context.
+    ///
+    /// Useful when the application runs several loops, for example one
+    /// per window, and each pipeline's messages need to be handled by
+    /// its own loop:
+    ///
+    /// ```ignore
+    /// let context = gst::MainContext::new();
+    /// let mut mainloop = gst::MainLoop::new_with_context(&context);
+    /// let watch = bus.add_watch_with_context(|msg| {
+    ///     match msg{
+    ///         gst::Message::Eos(_) => false,
+    ///         _ => true
+    ///     }
+    /// }, &context).unwrap();
+    /// mainloop.spawn();
+    /// ```
+    pub fn add_watch_with_context<W: Watch>(&mut self, watch: W, context: &MainContext) -> Option<WatchHandle>{
+        unsafe{
+            let watch: Guarded<dyn Watch> = Guarded::new(Box::new(watch));
+            let watch: *mut Guarded<dyn Watch> = Box::into_raw(Box::new(watch));
+            let source = gst_bus_create_watch(self.gst_bus_mut());
+            if source == ptr::null_mut(){
+                drop(Box::from_raw(watch));
+                return None;
+            }
+            // bus sources call their callback as a GstBusFunc
+            g_source_set_callback(source, c_callback(bus_callback as *const ()), watch as gpointer, Some(bus_watch_destroy));
+            let id = g_source_attach(source, context.gst_maincontext() as *mut GMainContext);
+            g_source_unref(source);
+            Some(WatchHandle::new(context.gst_maincontext() as *mut GMainContext, id))
         }
     }
 
@@ -38,25 +136,245 @@ impl Bus{
         }
     }
 
+    /// Posts msg on the bus, returns false if the bus is flushing
+    pub fn post(&mut self, msg: Message) -> bool{
+        unsafe{
+            gst_bus_post(self.gst_bus_mut(), ::Transfer::transfer(msg)) != 0
+        }
+    }
+
     pub fn receiver(&mut self) -> Receiver<Message>{
 		let (watch,receiver) = channel();
-		self.add_watch(watch);
+		// the watch removes itself once the receiver is dropped
+		if let Some(handle) = self.add_watch(watch){
+			handle.forget();
+		}
 		receiver
 	}
 
+    /// Same as receiver but the messages are sent from the passed context
+    pub fn receiver_with_context(&mut self, context: &MainContext) -> Receiver<Message>{
+		let (watch,receiver) = channel();
+		if let Some(handle) = self.add_watch_with_context(watch, context){
+			handle.forget();
+		}
+		receiver
+	}
+
+    /// Calls handler synchronously, from the thread that posts it, for
+    /// every message posted on the bus before it's queued for the
+    /// watches. Needed for messages that have to be answered before the
+    /// posting element continues, like prepare-window-handle from video
+    /// sinks. Replaces any previous sync handler.
+    ///
+    /// The handler is called from the streaming threads so it should do
+    /// as little as possible. Messages posted from several threads at
+    /// once are handed to it one at a time. Messages posted on the bus
+    /// from inside the handler, like the ones of a state change it
+    /// makes, aren't handed to it and are queued for the watches.
+    pub fn set_sync_handler<H: SyncHandler + 'static>(&mut self, handler: H){
+        let _lock = SYNC_HANDLER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        self.replace_sync_handler(handler);
+    }
+
+    /// Removes the sync handler
+    pub fn unset_sync_handler(&mut self){
+        let _lock = SYNC_HANDLER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        unsafe{
+            gst_bus_set_sync_handler(self.gst_bus_mut(), None, ptr::null_mut(), None);
+            g_object_set_qdata(self.gst_bus_mut() as *mut GObject, sync_handler_quark(), ptr::null_mut());
+        }
+    }
+
+    /// True if a sync handler was set from rust and not unset since.
+    /// Handlers set directly through the C API aren't known
+    pub fn has_sync_handler(&self) -> bool{
+        unsafe{
+            g_object_get_qdata(self.gst_bus() as *mut GObject, sync_handler_quark()) != ptr::null_mut()
+        }
+    }
+
+    // sets handler, the caller holds SYNC_HANDLER_LOCK. Returns the
+    // handler data which identifies it in the bus qdata
+    fn replace_sync_handler<H: SyncHandler + 'static>(&mut self, handler: H) -> gpointer{
+        unsafe{
+            let handler: SyncHandlerData = Mutex::new(Guarded::new(Box::new(handler)));
+            let handler: *mut SyncHandlerData = Box::into_raw(Box::new(handler));
+            gst_bus_set_sync_handler(self.gst_bus_mut(), None, ptr::null_mut(), None);
+            gst_bus_set_sync_handler(self.gst_bus_mut(), Some(bus_sync_callback), handler as gpointer, Some(bus_sync_destroy));
+            g_object_set_qdata(self.gst_bus_mut() as *mut GObject, sync_handler_quark(), handler as gpointer);
+            handler as gpointer
+        }
+    }
+
+    /// The messages posted on the bus as a futures Stream, so they can be
+    /// handled from an async application without a main loop or a thread
+    /// blocked waiting for them:
+    ///
+    /// ```ignore
+    /// let mut messages = pipeline.bus().unwrap().stream().unwrap();
+    /// while let Some(msg) = messages.next().await{
+    ///     match msg{
+    ///         gst::Message::Eos(_) => break,
+    ///         gst::Message::Error(_) => break,
+    ///         _ => ()
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// The messages are taken from the bus with a sync handler and
+    /// aren't dispatched to the watches. None if the bus already has a
+    /// sync handler, which would be replaced otherwise. The sync handler
+    /// is unset when the stream is dropped, unless it was replaced in
+    /// the meantime.
+    #[cfg(feature = "futures")]
+    pub fn stream(&mut self) -> Option<BusStream>{
+        let _lock = SYNC_HANDLER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        if self.has_sync_handler(){
+            return None;
+        }
+        let (sender, receiver) = unbounded();
+        let handler = self.replace_sync_handler(move |msg: &Message| {
+            let _ = sender.unbounded_send(msg.reference());
+            BusSyncReply::Drop
+        });
+        Some(BusStream{
+            bus: self.reference(),
+            receiver: receiver,
+            handler: handler as usize,
+        })
+    }
+
+    /// Takes the oldest error message from the bus, without waiting,
+    /// None if there's none. Useful to check for errors while driving a
+    /// pipeline from a loop instead of a watch
+    pub fn pop_error(&mut self) -> Option<Error>{
+        if let Some(msg) = self.pop_skipped(GST_MESSAGE_ERROR, None){
+            return Some(unsafe{ take_error(msg.transfer()) });
+        }
+        unsafe{
+            let msg = gst_bus_pop_filtered(self.gst_bus_mut(), GST_MESSAGE_ERROR);
+            if msg == ptr::null_mut(){
+                None
+            }else{
+                Some(take_error(msg))
+            }
+        }
+    }
+
+    /// Takes the oldest message of types, eg: GST_MESSAGE_ERROR |
+    /// GST_MESSAGE_EOS, posted by element or any element inside it,
+    /// without waiting. None if there's none.
+    ///
+    /// Messages of those types from other sources found before it have
+    /// to be taken from the bus too, they are kept with the bus, in
+    /// order, so the next calls to the pop methods of any Bus wrapping
+    /// it still return them, and can be taken with take_skipped. Watches
+    /// don't see them
+    pub fn pop_filtered_from(&mut self, element: &Object, types: GstMessageType) -> Option<Message>{
+        if let Some(msg) = self.pop_skipped(types, Some(element)){
+            return Some(msg);
+        }
+        let bus = self.reference();
+        let mut skipped = bus.skipped();
+        unsafe{
+            loop{
+                let msg = gst_bus_pop_filtered(self.gst_bus_mut(), types);
+                if msg == ptr::null_mut(){
+                    return None;
+                }
+                let message = Message::new(msg);
+                gst_mini_object_unref(msg as *mut GstMiniObject);
+                match message{
+                    Some(message) => if message.is_from(element){
+                        return Some(message);
+                    }else{
+                        skipped.push_back(message);
+                    },
+                    None => ()
+                }
+            }
+        }
+    }
+
+    /// The messages pop_filtered_from skipped while looking for messages
+    /// from a specific source, oldest first
+    pub fn take_skipped(&mut self) -> Vec<Message>{
+        self.skipped().drain(..).collect()
+    }
+
+    // the oldest skipped message of types from element if passed
+    fn pop_skipped(&mut self, types: GstMessageType, element: Option<&Object>) -> Option<Message>{
+        let mut skipped = self.skipped();
+        let idx = skipped.iter()
+            .position(|msg| msg.ty() & types != 0 && element.map_or(true, |element| msg.is_from(element)))?;
+        skipped.remove(idx)
+    }
+
+    // the skipped messages of the bus, created the first time. They live
+    // as long as the GstBus, which self holds a reference to
+    fn skipped(&self) -> MutexGuard<'_, VecDeque<Message>>{
+        unsafe{
+            let bus = self.gst_bus() as *mut GObject;
+            let mut skipped = g_object_get_qdata(bus, skipped_quark()) as *const SkippedMessages;
+            if skipped == ptr::null(){
+                let _lock = SKIPPED_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+                skipped = g_object_get_qdata(bus, skipped_quark()) as *const SkippedMessages;
+                if skipped == ptr::null(){
+                    let new: *mut SkippedMessages = Box::into_raw(Box::new(Mutex::new(VecDeque::new())));
+                    g_object_set_qdata_full(bus, skipped_quark(), new as gpointer, Some(skipped_destroy));
+                    skipped = new;
+                }
+            }
+            (*skipped).lock().unwrap_or_else(|e| e.into_inner())
+        }
+    }
+
+    /// Waits up to timeout nanoseconds, GST_CLOCK_TIME_NONE to wait
+    /// forever, for EOS or an error. Returns Ok(true) on EOS, Ok(false) if
+    /// the timeout expired and the error if one was posted first. The
+    /// messages are taken from the bus
+    pub fn wait_eos(&mut self, timeout: GstClockTime) -> Result<bool>{
+        unsafe{
+            let msg = match self.pop_skipped(GST_MESSAGE_EOS | GST_MESSAGE_ERROR, None){
+                Some(msg) => msg.transfer(),
+                None => gst_bus_timed_pop_filtered(self.gst_bus_mut(), timeout, GST_MESSAGE_EOS | GST_MESSAGE_ERROR),
+            };
+            if msg == ptr::null_mut(){
+                Ok(false)
+            }else if (*msg)._type == GST_MESSAGE_ERROR{
+                Err(take_error(msg))
+            }else{
+                gst_mini_object_unref(msg as *mut GstMiniObject);
+                Ok(true)
+            }
+        }
+    }
+
     pub unsafe fn gst_bus(&self) -> *const GstBus{
-        self.bus.gst_object() as *const GstBus
+        self.bus.as_ptr()
     }
 
     pub unsafe fn gst_bus_mut(&mut self) -> *mut GstBus{
-        self.bus.gst_object_mut() as *mut GstBus
+        self.bus.as_mut_ptr()
     }
 }
 
+// parses an error message and releases it
+unsafe fn take_error(msg: *mut GstMessage) -> Error{
+    let mut error: *mut GError = ptr::null_mut();
+    let mut debug: *mut gchar = ptr::null_mut();
+    gst_message_parse_error(msg, &mut error, &mut debug);
+    let error = Error::new_from_message(error, (*msg).src, debug);
+    g_free(debug as gpointer);
+    gst_mini_object_unref(msg as *mut GstMiniObject);
+    error
+}
+
 extern "C" fn bus_callback(_bus: *mut GstBus, msg: *mut GstMessage, data: gpointer) -> gboolean {
     unsafe{
         let alive = {
-            let mut watch: &mut Box<Watch> = mem::transmute(data);
+            let watch = &mut *(data as *mut Guarded<dyn Watch>);
             match Message::new(msg){
                 Some(Message::Application(app_msg)) => {
                     let structure = gst_message_get_structure(app_msg);
@@ -64,20 +382,128 @@ extern "C" fn bus_callback(_bus: *mut GstBus, msg: *mut GstMessage, data: gpoint
                     if from_c_str!(cname) == REMOVE_WATCH_MESSAGE_STR{
                         false
                     }else{
-                        watch.call(Message::Application(app_msg))
+                        watch.call(ptr::null_mut(), false, |watch| watch.call(Message::Application(app_msg)))
                     }
                 }
-    			Some(msg) => watch.call(msg),
+    			Some(msg) => watch.call(ptr::null_mut(), false, |watch| watch.call(msg)),
     			None => true,
             }
         };
-        if !alive{
-            Box::from_raw(data);
-        }
         if alive {1} else {0}
     }
 }
 
+extern "C" fn bus_watch_destroy(data: gpointer){
+    unsafe{
+        catch_panic(ptr::null_mut(), (), || drop(Box::from_raw(data as *mut Guarded<dyn Watch>)));
+    }
+}
+
+// sync handlers are called from whichever thread posts the message, and
+// several threads can post at once
+type SyncHandlerData = Mutex<Guarded<dyn SyncHandler>>;
+
+thread_local!(
+    // the sync handlers running on this thread. A handler that posts on
+    // its own bus, directly or through something like a state change,
+    // is called again from inside itself and would wait for its own lock
+    static RUNNING_SYNC_HANDLERS: RefCell<Vec<gpointer>> = RefCell::new(Vec::new())
+);
+
+// removes the handler from RUNNING_SYNC_HANDLERS when it returns
+struct RunningSyncHandler(gpointer);
+
+impl Drop for RunningSyncHandler{
+    fn drop(&mut self){
+        RUNNING_SYNC_HANDLERS.with(|running| running.borrow_mut().retain(|&handler| handler != self.0));
+    }
+}
+
+extern "C" fn bus_sync_callback(_bus: *mut GstBus, msg: *mut GstMessage, data: gpointer) -> GstBusSyncReply{
+    let reentered = RUNNING_SYNC_HANDLERS.with(|running| {
+        let mut running = running.borrow_mut();
+        if running.contains(&data){
+            true
+        }else{
+            running.push(data);
+            false
+        }
+    });
+    if reentered{
+        // messages the handler posts itself are queued as usual
+        return GST_BUS_PASS;
+    }
+    let _running = RunningSyncHandler(data);
+    unsafe{
+        let mut handler = (*(data as *const SyncHandlerData)).lock().unwrap_or_else(|e| e.into_inner());
+        match Message::new(msg){
+            Some(msg) => handler.call(ptr::null_mut(), BusSyncReply::Pass, |handler| handler.call(&msg)) as GstBusSyncReply,
+            None => GST_BUS_PASS,
+        }
+    }
+}
+
+extern "C" fn bus_sync_destroy(data: gpointer){
+    unsafe{
+        catch_panic(ptr::null_mut(), (), || drop(Box::from_raw(data as *mut SyncHandlerData)));
+    }
+}
+
+/// What to do with a message after a sync handler, mirrors
+/// GstBusSyncReply
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum BusSyncReply{
+    /// The message was handled, don't queue it for the watches
+    Drop = GST_BUS_DROP,
+    /// Queue the message for the watches as usual
+    Pass = GST_BUS_PASS,
+    /// Queue the message and block the posting thread until it's
+    /// handled by a watch
+    Async = GST_BUS_ASYNC,
+}
+
+/// Stream of the messages of a bus, returned by Bus::stream
+#[cfg(feature = "futures")]
+pub struct BusStream{
+    bus: Bus,
+    receiver: UnboundedReceiver<Message>,
+    // the sync handler data this stream set, to not unset someone else's
+    handler: usize,
+}
+
+#[cfg(feature = "futures")]
+impl Stream for BusStream{
+    type Item = Message;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Message>>{
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl Drop for BusStream{
+    fn drop(&mut self){
+        let _lock = SYNC_HANDLER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        unsafe{
+            let bus = self.bus.gst_bus_mut();
+            if g_object_get_qdata(bus as *mut GObject, sync_handler_quark()) as usize == self.handler{
+                gst_bus_set_sync_handler(bus, None, ptr::null_mut(), None);
+                g_object_set_qdata(bus as *mut GObject, sync_handler_quark(), ptr::null_mut());
+            }
+        }
+    }
+}
+
+pub trait SyncHandler: Send{
+    fn call(&mut self, msg: &Message) -> BusSyncReply;
+}
+
+impl<F: FnMut(&Message) -> BusSyncReply + Send> SyncHandler for F{
+    fn call(&mut self, msg: &Message) -> BusSyncReply{
+        self(msg)
+    }
+}
+
 pub trait Watch: Send{
     fn call(&mut self, msg: Message) -> bool;
 }
@@ -88,8 +514,49 @@ impl Watch for mpsc::Sender<Message>{
 	}
 }
 
+impl<F: FnMut(Message) -> bool + Send> Watch for F{
+	fn call(&mut self, msg: Message) -> bool{
+        self(msg)
+	}
+}
+
 impl Reference for Bus{
     fn reference(&self) -> Bus{
         Bus{ bus: self.bus.reference() }
     }
 }
+
+impl Downgrade for Bus{
+    unsafe fn gst_object_ptr(&self) -> *mut GstObject{
+        self.gst_bus() as *mut GstObject
+    }
+
+    unsafe fn from_gst_object_ptr(object: *mut GstObject) -> Option<Bus>{
+        Bus::new(object as *mut GstBus)
+    }
+}
+
+impl AsRef<Object> for Bus{
+    fn as_ref(&self) -> &Object{
+        self.bus.upcast_ref()
+    }
+}
+
+impl AsMut<Object> for Bus{
+    fn as_mut(&mut self) -> &mut Object{
+        self.bus.upcast_mut()
+    }
+}
+
+impl Deref for Bus{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.bus.upcast_ref()
+    }
+}
+
+impl DerefMut for Bus{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.bus.upcast_mut()
+    }
+}
@@ -0,0 +1,173 @@
+use ffi::*;
+use util::*;
+
+use pipeline::Pipeline;
+use element::Element;
+use ::Transfer;
+use reference::Reference;
+
+use std::ops::{Deref, DerefMut};
+use std::os::raw::c_void;
+
+#[repr(i32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum Mode{
+    Image = 1,
+    Video = 2,
+}
+
+/// Wrapper for camerabin, a high level element to capture still images
+/// and videos from a camera source.
+///
+/// The captures are triggered with start_capture() and, for video, stopped
+/// with stop_capture(). Once a capture is finished camerabin posts an
+/// element message on the bus with a structure named "image-done" or
+/// "video-done" containing a "filename" field.
+pub struct CameraBin{
+    camerabin: Pipeline
+}
+
+impl CameraBin{
+    pub fn new(name: &str) -> Option<CameraBin>{
+        let pipeline = Element::new("camerabin",name);
+        match pipeline{
+            Some(p) => {
+                match unsafe{ Pipeline::new_from_gst_pipeline( p.transfer() as *mut GstPipeline) }{
+                    Some(p) => Some(CameraBin{ camerabin: p }),
+                    None => None
+                }
+            }
+            None => None
+        }
+    }
+
+    /// Switches between still image and video capture
+    pub fn set_mode(&mut self, mode: Mode){
+        self.set("mode", mode as i32);
+    }
+
+    pub fn mode(&self) -> Mode{
+        if self.get::<i32>("mode") == Mode::Video as i32{
+            Mode::Video
+        }else{
+            Mode::Image
+        }
+    }
+
+    /// Location to save the captures to. A %d in the location will be
+    /// replaced with a capture counter so each capture gets its own file.
+    pub fn set_location(&mut self, location: &str){
+        self.set("location", location);
+    }
+
+    /// Starts a capture, in image mode a single picture is taken while
+    /// in video mode the recording goes on until stop_capture()
+    pub fn start_capture(&mut self){
+        self.emit("start-capture");
+    }
+
+    /// Stops an ongoing video capture
+    pub fn stop_capture(&mut self){
+        self.emit("stop-capture");
+    }
+
+    /// Returns true if the camera source is ready to accept a new
+    /// capture
+    pub fn is_ready_for_capture(&self) -> bool{
+        self.get::<gboolean>("ready-for-capture") != 0
+    }
+
+    /// Returns true when there's no capture being processed
+    pub fn is_idle(&self) -> bool{
+        self.get::<gboolean>("idle") != 0
+    }
+
+    /// Sets the element used as camera source, usually a
+    /// wrappercamerabinsrc wrapping something like v4l2src
+    pub fn set_camera_source(&mut self, source: &Element){
+        self.set("camera-source", source);
+    }
+
+    pub fn camera_source(&self) -> Option<Element>{
+        let cname = CString::new("camera-source").unwrap();
+        unsafe{
+            let mut source: *mut GstElement = ptr::null_mut();
+            g_object_get(self.gst_element() as *mut c_void, cname.as_ptr(), &mut source, ptr::null::<gchar>());
+            Element::new_from_gst_element(source)
+        }
+    }
+
+    pub fn set_viewfinder_sink(&mut self, sink: &Element){
+        self.set("viewfinder-sink", sink);
+    }
+
+    pub fn set_image_capture_caps(&mut self, caps: &::Caps){
+        self.set("image-capture-caps", caps);
+    }
+
+    pub fn set_video_capture_caps(&mut self, caps: &::Caps){
+        self.set("video-capture-caps", caps);
+    }
+
+    pub fn set_viewfinder_caps(&mut self, caps: &::Caps){
+        self.set("viewfinder-caps", caps);
+    }
+
+    pub fn set_zoom(&mut self, zoom: f32){
+        self.set("zoom", zoom as f64);
+    }
+
+    pub fn set_mute(&mut self, mute: bool){
+        self.set("mute", mute as gboolean);
+    }
+
+    /// Returns the photography interface of the camera source if it
+    /// implements it
+    #[cfg(feature = "photography")]
+    pub fn photography(&self) -> Option<::Photography>{
+        self.camera_source().and_then(|src| ::Photography::new(&src))
+    }
+}
+
+impl ::Transfer for CameraBin{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.camerabin.transfer()
+    }
+}
+
+impl Reference for CameraBin{
+    fn reference(&self) -> CameraBin{
+        CameraBin{ camerabin: self.camerabin.reference()}
+    }
+}
+
+impl AsRef<Pipeline> for CameraBin{
+    fn as_ref(&self) -> &Pipeline{
+        &self.camerabin
+    }
+}
+
+impl AsMut<Pipeline> for CameraBin{
+    fn as_mut(&mut self) -> &mut Pipeline{
+        &mut self.camerabin
+    }
+}
+
+impl From<CameraBin> for Pipeline{
+    fn from(b: CameraBin) -> Pipeline{
+        b.camerabin
+    }
+}
+
+impl Deref for CameraBin{
+    type Target = Pipeline;
+    fn deref(&self) -> &Pipeline{
+        &self.camerabin
+    }
+}
+
+impl DerefMut for CameraBin{
+    fn deref_mut(&mut self) -> &mut Pipeline{
+        &mut self.camerabin
+    }
+}
@@ -3,7 +3,7 @@ use std::os::raw::c_void;
 use util::*;
 use std::ops::{Deref, DerefMut};
 
-use structure::Structure;
+use structure::{StructureRef, StructureRefMut};
 use reference::Reference;
 use object::{Property, FromProperty};
 use miniobject::MiniObject;
@@ -11,12 +11,12 @@ use object::Object;
 
 #[derive(Clone)]
 pub struct Caps{
-	caps: MiniObject
+	caps: MiniObject<GstCaps>
 }
 
 impl Caps{
 	pub unsafe fn new(caps: *mut GstCaps) -> Option<Caps>{
-		MiniObject::new_from_gst_miniobject(caps as *mut GstMiniObject)
+		MiniObject::new(caps)
 			.map(|miniobject| Caps{ caps: miniobject })
 	}
 
@@ -40,28 +40,44 @@ impl Caps{
 	}
 
 	pub fn is_writable(&self) -> bool{
-		unsafe{
-			gst_mini_object_is_writable(self.caps.gst_miniobject())!=0
-		}
+		self.caps.is_writable()
+	}
+
+	/// Returns these caps if they are writable or a copy otherwise, to
+	/// modify caps that could be shared, eg: the ones of a pad
+	pub fn make_writable(self) -> Caps{
+		Caps{ caps: self.caps.make_writable() }
+	}
+
+	/// Makes the caps writable in place, copying them only if they are
+	/// shared, and returns them, eg: to change a field of the caps
+	/// event in a pad probe. They stay writable until a new reference
+	/// to them is taken
+	pub fn make_mut(&mut self) -> &mut Caps{
+		self.caps.make_mut();
+		self
 	}
 
 	pub fn from_string(desc: &str) -> Option<Caps>{
 		let cdesc = CString::new(desc).unwrap();
 	    unsafe{
-	    	Caps::new(gst_caps_from_string(mem::transmute(cdesc.as_ptr())))
+	    	Caps::new(gst_caps_from_string(cdesc.as_ptr()))
 	    }
 	}
 
-	pub fn to_string(&self) -> &str{
+	pub fn to_string(&self) -> String{
 		unsafe{
-			from_c_str!(gst_caps_to_string(self.gst_caps()))
+			let string = gst_caps_to_string(self.gst_caps());
+			let ret = from_c_str!(string).to_string();
+			g_free(string as gpointer);
+			ret
 		}
 	}
 
 	pub fn video_info(&self) -> Option<::VideoInfo>{
 		unsafe{
-			let videoinfo = mem::zeroed();
-			if gst_video_info_from_caps (mem::transmute(&videoinfo), self.gst_caps()) == 1 {
+			let mut videoinfo = mem::zeroed();
+			if gst_video_info_from_caps (&mut videoinfo, self.gst_caps()) == 1 {
 				Some(videoinfo)
 			}else{
 			    None
@@ -69,18 +85,97 @@ impl Caps{
 		}
 	}
 
+	/// The audio info of caps describing raw audio, None for other
+	/// media or caps that aren't fixed
+	pub fn audio_info(&self) -> Option<::AudioInfo>{
+		unsafe{
+			let mut audioinfo = mem::zeroed();
+			if gst_audio_info_from_caps(&mut audioinfo, self.gst_caps()) == 1 {
+				Some(audioinfo)
+			}else{
+			    None
+			}
+		}
+	}
+
 	pub unsafe fn gst_caps(&self) -> *const GstCaps{
-		self.caps.gst_miniobject() as *const GstCaps
+		self.caps.as_ptr()
 	}
 
 	pub unsafe fn gst_caps_mut(&mut self) -> *mut GstCaps{
-		self.caps.gst_miniobject_mut() as *mut GstCaps
+		self.caps.as_mut_ptr()
+	}
+
+	/// Number of structures in the caps
+	pub fn len(&self) -> u32{
+		unsafe{
+			gst_caps_get_size(self.gst_caps())
+		}
+	}
+
+	pub fn is_any(&self) -> bool{
+		unsafe{
+			gst_caps_is_any(self.gst_caps()) != 0
+		}
+	}
+
+	pub fn is_empty(&self) -> bool{
+		unsafe{
+			gst_caps_is_empty(self.gst_caps()) != 0
+		}
+	}
+
+	/// Returns true if the intersection of both caps wouldn't be empty
+	pub fn can_intersect(&self, other: &Caps) -> bool{
+		unsafe{
+			gst_caps_can_intersect(self.gst_caps(), other.gst_caps()) != 0
+		}
+	}
+
+	pub fn structure<'a>(&'a self, index: u32) -> Option<StructureRef<'a>>{
+		unsafe{
+			StructureRef::new(gst_caps_get_structure(self.gst_caps(), index))
+		}
 	}
 
-	pub fn structure(&self, index: u32) -> Option<Structure>{
+	/// The structure at index to modify its fields, None if the caps
+	/// aren't writable, eg: because they are shared with an element
+	pub fn structure_mut<'a>(&'a mut self, index: u32) -> Option<StructureRefMut<'a>>{
+		if !self.is_writable(){
+			return None;
+		}
 		unsafe{
-			let structure = gst_caps_get_structure(self.gst_caps(), index);
-			Structure::new_from_gst_structure(structure)
+			StructureRefMut::new(gst_caps_get_structure(self.gst_caps_mut(), index))
+		}
+	}
+
+	/// Headers of streaming formats like ogg, flv or mpeg-ts, the buffers
+	/// in the "streamheader" field. They have to be sent before any other
+	/// data to a client that joins in the middle of the stream, eg: by a
+	/// custom network protocol that doesn't transmit the caps
+	pub fn streamheader(&self) -> Vec<::Buffer>{
+		self.structure(0).map(|s| s.get_buffer_list("streamheader")).unwrap_or(vec![])
+	}
+
+	/// Sets the "streamheader" field, the buffers should have the header
+	/// flag set. The caps have to be writable
+	pub fn set_streamheader(&mut self, buffers: &[::Buffer]){
+		if let Some(mut s) = self.structure_mut(0){
+			s.set_buffer_list("streamheader", buffers);
+		}
+	}
+
+	/// Codec configuration needed to decode the stream, eg: the
+	/// avcC box of H.264 in avc format or the AudioSpecificConfig of AAC.
+	/// It's only in the caps so it has to be transmitted out of band
+	pub fn codec_data(&self) -> Option<::Buffer>{
+		self.structure(0).and_then(|s| s.get::<::Buffer>("codec_data"))
+	}
+
+	/// Sets the "codec_data" field, the caps have to be writable
+	pub fn set_codec_data(&mut self, codec_data: &::Buffer){
+		if let Some(mut s) = self.structure_mut(0){
+			s.set("codec_data", codec_data);
 		}
 	}
 }
@@ -88,7 +183,7 @@ impl Caps{
 
 impl ::Transfer<GstCaps> for Caps{
     unsafe fn transfer(self) ->  *mut GstCaps{
-        self.caps.transfer() as *mut GstCaps
+        self.caps.transfer()
     }
 }
 
@@ -135,7 +230,7 @@ impl<'a> FromProperty for Caps{
 impl PartialEq for Caps{
     fn eq(&self, other: &Caps) -> bool{
         unsafe{
-            gst_caps_is_equal(mem::transmute(self), mem::transmute(other)) != 0
+            gst_caps_is_equal(self.gst_caps(), other.gst_caps()) != 0
         }
     }
 }
@@ -145,31 +240,31 @@ impl Eq for Caps{}
 
 impl AsRef<MiniObject> for Caps{
     fn as_ref(&self) -> &MiniObject{
-        &self.caps
+        self.caps.upcast_ref()
     }
 }
 
 impl AsMut<MiniObject> for Caps{
     fn as_mut(&mut self) -> &mut MiniObject{
-        &mut self.caps
+        self.caps.upcast_mut()
     }
 }
 
 impl From<Caps> for MiniObject{
     fn from(b: Caps) -> MiniObject{
-        b.caps
+        b.caps.upcast()
     }
 }
 
 impl Deref for Caps{
     type Target = MiniObject;
     fn deref(&self) -> &MiniObject{
-        &self.caps
+        self.caps.upcast_ref()
     }
 }
 
 impl DerefMut for Caps{
     fn deref_mut(&mut self) -> &mut MiniObject{
-        &mut self.caps
+        self.caps.upcast_mut()
     }
 }
@@ -0,0 +1,203 @@
+use caps::Caps;
+use encoding_profile::{EncodingContainerProfile, EncodingVideoProfile, EncodingAudioProfile};
+
+/// Video codecs with the caps of their encoded streams
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum VideoCodec{
+    /// H264 with a profile, eg: "baseline", "main" or "high"
+    H264(&'static str),
+    /// H265 with a profile, eg: "main" or "main-10"
+    H265(&'static str),
+    Vp8,
+    Vp9,
+    Av1,
+    /// Uncompressed video in a format, eg: "I420" or "NV12"
+    Raw(&'static str),
+}
+
+impl VideoCodec{
+    /// The caps of the stream without size or framerate
+    pub fn caps_string(&self) -> String{
+        match *self{
+            VideoCodec::H264(profile) => format!("video/x-h264,profile={}", profile),
+            VideoCodec::H265(profile) => format!("video/x-h265,profile={}", profile),
+            VideoCodec::Vp8 => "video/x-vp8".to_string(),
+            VideoCodec::Vp9 => "video/x-vp9".to_string(),
+            VideoCodec::Av1 => "video/x-av1".to_string(),
+            VideoCodec::Raw(format) => format!("video/x-raw,format={}", format),
+        }
+    }
+
+    pub fn caps(&self) -> Caps{
+        Caps::from_string(&self.caps_string()).unwrap()
+    }
+}
+
+/// Audio codecs with the caps of their encoded streams
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum AudioCodec{
+    Opus,
+    Aac,
+    Mp3,
+    Vorbis,
+    Flac,
+    /// Uncompressed interleaved audio in a format, eg: "S16LE" or "F32LE"
+    Raw(&'static str),
+}
+
+impl AudioCodec{
+    /// The caps of the stream without rate or channels
+    pub fn caps_string(&self) -> String{
+        match *self{
+            AudioCodec::Opus => "audio/x-opus".to_string(),
+            AudioCodec::Aac => "audio/mpeg,mpegversion=4".to_string(),
+            AudioCodec::Mp3 => "audio/mpeg,mpegversion=1,layer=3".to_string(),
+            AudioCodec::Vorbis => "audio/x-vorbis".to_string(),
+            AudioCodec::Flac => "audio/x-flac".to_string(),
+            AudioCodec::Raw(format) => format!("audio/x-raw,format={},layout=interleaved", format),
+        }
+    }
+
+    pub fn caps(&self) -> Caps{
+        Caps::from_string(&self.caps_string()).unwrap()
+    }
+}
+
+/// A video format commonly used for encoding or capture, to get caps
+/// and encoding profiles without writing caps strings by hand:
+///
+/// ```ignore
+/// use gst::caps_presets::{VideoPreset, AudioPreset, Container};
+/// let capsfilter_caps = VideoPreset::H264_MAIN_1080P30.caps();
+/// let profile = Container::Mp4.profile("recording",
+///     Some(VideoPreset::H264_MAIN_1080P30), Some(AudioPreset::AAC_48K_STEREO));
+/// encodebin.set_profile(&profile.into());
+/// ```
+///
+/// Other combinations can be built with the fields, eg:
+/// `VideoPreset{ width: 640, height: 480, ..VideoPreset::VP8_720P30 }`
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub struct VideoPreset{
+    pub codec: VideoCodec,
+    pub width: u32,
+    pub height: u32,
+    pub fps_n: u32,
+    pub fps_d: u32,
+}
+
+impl VideoPreset{
+    pub const H264_BASELINE_720P30: VideoPreset = VideoPreset{ codec: VideoCodec::H264("constrained-baseline"), width: 1280, height: 720, fps_n: 30, fps_d: 1 };
+    pub const H264_MAIN_720P30: VideoPreset = VideoPreset{ codec: VideoCodec::H264("main"), width: 1280, height: 720, fps_n: 30, fps_d: 1 };
+    pub const H264_MAIN_1080P30: VideoPreset = VideoPreset{ codec: VideoCodec::H264("main"), width: 1920, height: 1080, fps_n: 30, fps_d: 1 };
+    pub const H264_HIGH_1080P30: VideoPreset = VideoPreset{ codec: VideoCodec::H264("high"), width: 1920, height: 1080, fps_n: 30, fps_d: 1 };
+    pub const H264_HIGH_1080P60: VideoPreset = VideoPreset{ codec: VideoCodec::H264("high"), width: 1920, height: 1080, fps_n: 60, fps_d: 1 };
+    pub const H265_MAIN_1080P30: VideoPreset = VideoPreset{ codec: VideoCodec::H265("main"), width: 1920, height: 1080, fps_n: 30, fps_d: 1 };
+    pub const H265_MAIN_2160P30: VideoPreset = VideoPreset{ codec: VideoCodec::H265("main"), width: 3840, height: 2160, fps_n: 30, fps_d: 1 };
+    pub const VP8_720P30: VideoPreset = VideoPreset{ codec: VideoCodec::Vp8, width: 1280, height: 720, fps_n: 30, fps_d: 1 };
+    pub const VP9_1080P30: VideoPreset = VideoPreset{ codec: VideoCodec::Vp9, width: 1920, height: 1080, fps_n: 30, fps_d: 1 };
+    pub const AV1_1080P30: VideoPreset = VideoPreset{ codec: VideoCodec::Av1, width: 1920, height: 1080, fps_n: 30, fps_d: 1 };
+    pub const I420_720P30: VideoPreset = VideoPreset{ codec: VideoCodec::Raw("I420"), width: 1280, height: 720, fps_n: 30, fps_d: 1 };
+    pub const NV12_1080P30: VideoPreset = VideoPreset{ codec: VideoCodec::Raw("NV12"), width: 1920, height: 1080, fps_n: 30, fps_d: 1 };
+
+    fn size_string(&self) -> String{
+        format!("width={},height={},framerate={}/{}", self.width, self.height, self.fps_n, self.fps_d)
+    }
+
+    /// The complete caps, eg: for a capsfilter after the encoder
+    pub fn caps(&self) -> Caps{
+        Caps::from_string(&format!("{},{}", self.codec.caps_string(), self.size_string())).unwrap()
+    }
+
+    /// Raw video caps with the size and framerate of the preset, in any
+    /// format, eg: for a capsfilter before the encoder
+    pub fn raw_caps(&self) -> Caps{
+        Caps::from_string(&format!("video/x-raw,{}", self.size_string())).unwrap()
+    }
+
+    /// A profile encoding to the codec of the preset, scaling and
+    /// changing the framerate of the input to the ones of the preset
+    pub fn encoding_profile(&self) -> EncodingVideoProfile{
+        EncodingVideoProfile::new(&self.codec.caps())
+            .with_restriction(&self.raw_caps())
+            .with_presence(1)
+    }
+}
+
+/// An audio format commonly used for encoding or capture, see
+/// VideoPreset
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub struct AudioPreset{
+    pub codec: AudioCodec,
+    pub rate: u32,
+    pub channels: u32,
+}
+
+impl AudioPreset{
+    pub const OPUS_48K_MONO: AudioPreset = AudioPreset{ codec: AudioCodec::Opus, rate: 48000, channels: 1 };
+    pub const OPUS_48K_STEREO: AudioPreset = AudioPreset{ codec: AudioCodec::Opus, rate: 48000, channels: 2 };
+    pub const AAC_44K_STEREO: AudioPreset = AudioPreset{ codec: AudioCodec::Aac, rate: 44100, channels: 2 };
+    pub const AAC_48K_STEREO: AudioPreset = AudioPreset{ codec: AudioCodec::Aac, rate: 48000, channels: 2 };
+    pub const MP3_44K_STEREO: AudioPreset = AudioPreset{ codec: AudioCodec::Mp3, rate: 44100, channels: 2 };
+    pub const VORBIS_44K_STEREO: AudioPreset = AudioPreset{ codec: AudioCodec::Vorbis, rate: 44100, channels: 2 };
+    pub const FLAC_48K_STEREO: AudioPreset = AudioPreset{ codec: AudioCodec::Flac, rate: 48000, channels: 2 };
+    pub const S16LE_48K_STEREO: AudioPreset = AudioPreset{ codec: AudioCodec::Raw("S16LE"), rate: 48000, channels: 2 };
+    pub const F32LE_48K_STEREO: AudioPreset = AudioPreset{ codec: AudioCodec::Raw("F32LE"), rate: 48000, channels: 2 };
+
+    /// The complete caps, eg: for a capsfilter after the encoder
+    pub fn caps(&self) -> Caps{
+        Caps::from_string(&format!("{},rate={},channels={}", self.codec.caps_string(), self.rate, self.channels)).unwrap()
+    }
+
+    /// Raw audio caps with the rate and channels of the preset, in any
+    /// format
+    pub fn raw_caps(&self) -> Caps{
+        Caps::from_string(&format!("audio/x-raw,rate={},channels={}", self.rate, self.channels)).unwrap()
+    }
+
+    /// A profile encoding to the codec of the preset, resampling and
+    /// mixing the input to the rate and channels of the preset
+    pub fn encoding_profile(&self) -> EncodingAudioProfile{
+        EncodingAudioProfile::new(&self.codec.caps())
+            .with_restriction(&self.raw_caps())
+            .with_presence(1)
+    }
+}
+
+/// Common containers
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum Container{
+    Mp4,
+    Matroska,
+    WebM,
+    MpegTs,
+    Ogg,
+}
+
+impl Container{
+    pub fn caps_string(&self) -> &'static str{
+        match *self{
+            Container::Mp4 => "video/quicktime,variant=iso",
+            Container::Matroska => "video/x-matroska",
+            Container::WebM => "video/webm",
+            Container::MpegTs => "video/mpegts,systemstream=true,packetsize=188",
+            Container::Ogg => "application/ogg",
+        }
+    }
+
+    pub fn caps(&self) -> Caps{
+        Caps::from_string(self.caps_string()).unwrap()
+    }
+
+    /// A profile muxing the streams of the passed presets in this
+    /// container, for encodebin or transcoding
+    pub fn profile(&self, name: &str, video: Option<VideoPreset>, audio: Option<AudioPreset>) -> EncodingContainerProfile{
+        let mut profile = EncodingContainerProfile::new(name, None, &self.caps());
+        if let Some(video) = video{
+            profile = profile.add_profile(video.encoding_profile());
+        }
+        if let Some(audio) = audio{
+            profile = profile.add_profile(audio.encoding_profile());
+        }
+        profile
+    }
+}
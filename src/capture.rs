@@ -0,0 +1,83 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use element::Element;
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use pipeline::Pipeline;
+use sample::Sample;
+use videoinfo::VideoInfo;
+use error::{Error, Result};
+
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+impl Pipeline{
+    /// Captures the next frame that reaches the video sink of the
+    /// pipeline converted to packed RGBA, eg: for thumbnails or to check
+    /// the output in tests:
+    ///
+    /// ```ignore
+    /// let (info, rgba) = pipeline.capture_frame(1_000_000_000).unwrap();
+    /// image::save_buffer("frame.png", &rgba, info.width() as u32, info.height() as u32, image::ColorType::Rgba8)?;
+    /// ```
+    ///
+    /// Waits up to timeout nanoseconds for the frame. If no frame arrives,
+    /// eg: because the pipeline is paused, the last frame the sink
+    /// rendered is returned if it keeps it, see the enable-last-sample
+    /// property of the sinks. Rows can be padded, the stride is in the
+    /// returned VideoInfo
+    pub fn capture_frame(&self, timeout: GstClockTime) -> Result<(VideoInfo, Vec<u8>)>{
+        self.capture_frame_with_format("RGBA", timeout)
+    }
+
+    /// Same as capture_frame but converting the frame to format, eg:
+    /// "RGB" or "GRAY8"
+    pub fn capture_frame_with_format(&self, format: &str, timeout: GstClockTime) -> Result<(VideoInfo, Vec<u8>)>{
+        let mut sink = video_sink(self).ok_or(Error::new(0, 0, "the pipeline has no negotiated video sink"))?;
+        let sample = next_sample(&mut sink, timeout)
+            .or_else(|| sink.get_value("last-sample").and_then(|value| value.get::<Sample>()))
+            .ok_or(Error::new(0, 0, "no frame received before the timeout"))?;
+        let to_caps = Caps::from_string(&format!("video/x-raw,format={}", format))
+            .ok_or(Error::new(0, 0, &format!("invalid format {}", format)))?;
+        let converted = sample.convert_video(&to_caps, timeout)?;
+        let info = converted.caps().and_then(|caps| caps.video_info())
+            .ok_or(Error::new(0, 0, "the converted frame has no video caps"))?;
+        let buffer = converted.buffer().ok_or(Error::new(0, 0, "the converted frame has no data"))?;
+        let data = buffer.map_read(|map| map.data::<u8>().to_vec())
+            .map_err(|_| Error::new(0, 0, "couldn't map the converted frame"))?;
+        Ok((info, data))
+    }
+}
+
+// the sink pad of the first sink in the pipeline negotiated to raw video
+fn video_sink(pipeline: &Pipeline) -> Option<Pad>{
+    pipeline.iter_recurse()
+        .filter_map(|element| element.ok())
+        .filter(|element: &Element| element.src_pads().is_empty())
+        .flat_map(|element| element.sink_pads())
+        .find(|pad| pad.current_caps()
+            .and_then(|caps| caps.structure(0).map(|structure| structure.name() == "video/x-raw"))
+            .unwrap_or(false))
+}
+
+// waits for the next buffer going through pad and builds a sample with
+// the caps of the pad
+fn next_sample(pad: &mut Pad, timeout: GstClockTime) -> Option<Sample>{
+    let (sender, receiver) = channel();
+    let mut sender = Some(sender);
+    let probe = pad.add_probe(GST_PAD_PROBE_TYPE_BUFFER, move |pad: &mut Pad, info: &mut PadProbeInfo| {
+        if let (Some(buffer), Some(caps)) = (info.buffer(), pad.current_caps()){
+            let sample = unsafe{
+                Sample::new(gst_sample_new(buffer.gst_buffer() as *mut GstBuffer, caps.gst_caps() as *mut GstCaps,
+                    ptr::null(), ptr::null_mut()))
+            };
+            if let (Some(sender), Some(sample)) = (sender.take(), sample){
+                let _ = sender.send(sample);
+            }
+        }
+        PadProbeReturn::Ok
+    });
+    let sample = receiver.recv_timeout(Duration::from_nanos(timeout)).ok();
+    drop(probe);
+    sample
+}
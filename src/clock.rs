@@ -1,5 +1,110 @@
 use ffi::*;
+use object::Object;
+use reference::Reference;
+use version::ApiAvailability;
 
-struct Clock{
-    clock: *mut GstClock
+use std::ops::{Deref, DerefMut};
+
+const CLOCK_SYNC: ApiAvailability = ApiAvailability::new("Clock synchronization", 1, 6, 0);
+
+/// A clock pipelines can be synchronized to, the system clock or one of
+/// the network clocks in the net module
+pub struct Clock{
+    clock: Object<GstClock>
+}
+
+impl Clock{
+    pub unsafe fn new(clock: *mut GstClock) -> Option<Clock>{
+        Object::new(clock).map(|obj| Clock{ clock: obj })
+    }
+
+    /// The default monotonic clock of the system
+    pub fn system() -> Clock{
+        unsafe{
+            Clock::new(gst_system_clock_obtain()).unwrap()
+        }
+    }
+
+    /// Current time of the clock in nanoseconds
+    pub fn time(&self) -> u64{
+        unsafe{
+            gst_clock_get_time(self.gst_clock() as *mut GstClock)
+        }
+    }
+
+    /// Accuracy of the clock in nanoseconds
+    pub fn resolution(&self) -> u64{
+        unsafe{
+            gst_clock_get_resolution(self.gst_clock() as *mut GstClock)
+        }
+    }
+
+    /// Blocks until the clock is synchronized with its source or timeout
+    /// nanoseconds pass, None waits forever. Returns whether the clock
+    /// is synchronized. Clocks that don't need synchronization return
+    /// immediately. Needs GStreamer 1.6, older versions don't track
+    /// synchronization and this returns true without waiting
+    pub fn wait_for_sync(&self, timeout: Option<u64>) -> bool{
+        unsafe{
+            match CLOCK_SYNC.function::<unsafe extern "C" fn(*mut GstClock, GstClockTime) -> gboolean>("gst_clock_wait_for_sync"){
+                Ok(wait_for_sync) => wait_for_sync(self.gst_clock() as *mut GstClock, timeout.unwrap_or(GST_CLOCK_TIME_NONE)) != 0,
+                Err(_) => true,
+            }
+        }
+    }
+
+    /// Needs GStreamer 1.6, returns true with older versions
+    pub fn is_synced(&self) -> bool{
+        unsafe{
+            match CLOCK_SYNC.function::<unsafe extern "C" fn(*mut GstClock) -> gboolean>("gst_clock_is_synced"){
+                Ok(is_synced) => is_synced(self.gst_clock() as *mut GstClock) != 0,
+                Err(_) => true,
+            }
+        }
+    }
+
+    pub unsafe fn gst_clock(&self) -> *const GstClock{
+        self.clock.as_ptr()
+    }
+
+    pub unsafe fn gst_clock_mut(&mut self) -> *mut GstClock{
+        self.clock.as_mut_ptr()
+    }
+}
+
+impl Reference for Clock{
+    fn reference(&self) -> Clock{
+        Clock{ clock: self.clock.reference() }
+    }
+}
+
+impl AsRef<Object> for Clock{
+    fn as_ref(&self) -> &Object{
+        self.clock.upcast_ref()
+    }
+}
+
+impl AsMut<Object> for Clock{
+    fn as_mut(&mut self) -> &mut Object{
+        self.clock.upcast_mut()
+    }
+}
+
+impl From<Clock> for Object{
+    fn from(c: Clock) -> Object{
+        c.clock.upcast()
+    }
+}
+
+impl Deref for Clock{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.clock.upcast_ref()
+    }
+}
+
+impl DerefMut for Clock{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.clock.upcast_mut()
+    }
 }
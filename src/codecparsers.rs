@@ -0,0 +1,613 @@
+use ffi::*;
+use bitstream::BitReader;
+
+/// A NAL unit found by H264Parser, data starts with the NAL header and
+/// still contains the emulation prevention bytes
+#[derive(Clone,Copy,Debug)]
+pub struct H264NalUnit<'a>{
+    /// One of GST_H264_NAL_*, eg: GST_H264_NAL_SPS
+    pub nal_type: GstH264NalUnitType,
+    pub ref_idc: u16,
+    pub idr: bool,
+    pub data: &'a [u8],
+}
+
+/// Splits H.264 elementary streams into NAL units, eg: in a probe
+/// before a payloader or a muxer:
+///
+/// ```ignore
+/// let mut parser = gst::codecparsers::H264Parser::new();
+/// buffer.map_read(|mapping| {
+///     for nal in parser.nal_units(mapping.data::<u8>()){
+///         if nal.nal_type == GST_H264_NAL_SPS{
+///             if let Some(sps) = gst::codecparsers::H264Sps::parse(nal.data){
+///                 println!("{} {}x{}", sps.profile().unwrap_or("unknown"), sps.width, sps.height);
+///             }
+///         }
+///     }
+/// });
+/// ```
+pub struct H264Parser{
+    parser: *mut GstH264NalParser
+}
+
+impl H264Parser{
+    pub fn new() -> H264Parser{
+        unsafe{
+            H264Parser{ parser: gst_h264_nal_parser_new() }
+        }
+    }
+
+    /// NAL units of data in byte-stream format, the ones separated by
+    /// start codes as in the video/x-h264,stream-format=byte-stream caps
+    pub fn nal_units<'a>(&mut self, data: &'a [u8]) -> Vec<H264NalUnit<'a>>{
+        let mut nals = vec![];
+        let mut offset = 0;
+        loop{
+            let mut nalu = GstH264NalUnit::default();
+            let res = unsafe{
+                gst_h264_parser_identify_nalu(self.parser, data.as_ptr(), offset, data.len() as gsize, &mut nalu)
+            };
+            if (res != GST_H264_PARSER_OK && res != GST_H264_PARSER_NO_NAL_END) || nalu.size == 0{
+                break;
+            }
+            nals.push(h264_nal_unit(data, &nalu));
+            offset = nalu.offset + nalu.size;
+            if res == GST_H264_PARSER_NO_NAL_END{
+                break;
+            }
+        }
+        nals
+    }
+
+    /// NAL units of data in avc format, each one prefixed with its size
+    /// in nal_length_size bytes as in the video/x-h264,stream-format=avc
+    /// caps. nal_length_size comes from the codec_data, usually 4
+    pub fn nal_units_avc<'a>(&mut self, data: &'a [u8], nal_length_size: u8) -> Vec<H264NalUnit<'a>>{
+        let mut nals = vec![];
+        let mut offset = 0;
+        while (offset as usize) < data.len(){
+            let mut nalu = GstH264NalUnit::default();
+            let res = unsafe{
+                gst_h264_parser_identify_nalu_avc(self.parser, data.as_ptr(), offset, data.len() as gsize, nal_length_size, &mut nalu)
+            };
+            if res != GST_H264_PARSER_OK || nalu.size == 0{
+                break;
+            }
+            nals.push(h264_nal_unit(data, &nalu));
+            offset = nalu.offset + nalu.size;
+        }
+        nals
+    }
+}
+
+impl Drop for H264Parser{
+    fn drop(&mut self){
+        unsafe{
+            gst_h264_nal_parser_free(self.parser);
+        }
+    }
+}
+
+fn h264_nal_unit<'a>(data: &'a [u8], nalu: &GstH264NalUnit) -> H264NalUnit<'a>{
+    let start = nalu.offset as usize;
+    H264NalUnit{
+        nal_type: nalu._type as GstH264NalUnitType,
+        ref_idc: nalu.ref_idc,
+        idr: nalu.idr_pic_flag != 0,
+        data: &data[start .. start + nalu.size as usize],
+    }
+}
+
+/// The fields of an H.264 sequence parameter set needed to describe the
+/// stream. The SPS is parsed with BitReader instead of through
+/// GstH264SPS whose layout changes between GStreamer versions
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct H264Sps{
+    pub sps_id: u32,
+    pub profile_idc: u8,
+    /// constraint_set0_flag in the highest bit to constraint_set5_flag
+    pub constraint_flags: u8,
+    pub level_idc: u8,
+    pub chroma_format_idc: u32,
+    pub bit_depth_luma: u32,
+    pub bit_depth_chroma: u32,
+    pub max_num_ref_frames: u32,
+    /// Width and height in pixels once cropped
+    pub width: u32,
+    pub height: u32,
+    pub interlaced: bool,
+}
+
+impl H264Sps{
+    /// Parses an SPS NAL unit, starting with the NAL header as returned
+    /// by H264Parser. None if it's not an SPS or it's truncated
+    pub fn parse(nal: &[u8]) -> Option<H264Sps>{
+        if nal.is_empty() || (nal[0] & 0x1f) as GstH264NalUnitType != GST_H264_NAL_SPS{
+            return None;
+        }
+        let rbsp = remove_emulation_prevention(&nal[1..]);
+        let mut reader = BitReader::new(&rbsp);
+        let profile_idc = reader.get_bits(8)? as u8;
+        let constraint_flags = reader.get_bits(8)? as u8;
+        let level_idc = reader.get_bits(8)? as u8;
+        let sps_id = reader.get_ue()?;
+
+        let mut chroma_format_idc = 1;
+        let mut separate_colour_plane = false;
+        let mut bit_depth_luma = 8;
+        let mut bit_depth_chroma = 8;
+        match profile_idc{
+            100 | 110 | 122 | 244 | 44 | 83 | 86 | 118 | 128 | 138 | 139 | 134 | 135 => {
+                chroma_format_idc = reader.get_ue()?;
+                if chroma_format_idc == 3{
+                    separate_colour_plane = reader.get_bool()?;
+                }
+                bit_depth_luma = reader.get_ue()?.checked_add(8)?;
+                bit_depth_chroma = reader.get_ue()?.checked_add(8)?;
+                // qpprime_y_zero_transform_bypass_flag
+                reader.get_bits(1)?;
+                if reader.get_bool()?{
+                    let n_lists = if chroma_format_idc != 3 { 8 } else { 12 };
+                    for i in 0..n_lists{
+                        if reader.get_bool()?{
+                            skip_scaling_list(&mut reader, if i < 6 { 16 } else { 64 })?;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        // log2_max_frame_num_minus4
+        reader.get_ue()?;
+        match reader.get_ue()?{
+            0 => {
+                // log2_max_pic_order_cnt_lsb_minus4
+                reader.get_ue()?;
+            }
+            1 => {
+                // delta_pic_order_always_zero_flag, offset_for_non_ref_pic
+                // and offset_for_top_to_bottom_field
+                reader.get_bits(1)?;
+                reader.get_se()?;
+                reader.get_se()?;
+                for _ in 0..reader.get_ue()?{
+                    reader.get_se()?;
+                }
+            }
+            _ => {}
+        }
+        let max_num_ref_frames = reader.get_ue()?;
+        // gaps_in_frame_num_value_allowed_flag
+        reader.get_bits(1)?;
+        let width_in_mbs = reader.get_ue()?.checked_add(1)?;
+        let height_in_map_units = reader.get_ue()?.checked_add(1)?;
+        let frame_mbs_only = reader.get_bool()?;
+        if !frame_mbs_only{
+            // mb_adaptive_frame_field_flag
+            reader.get_bits(1)?;
+        }
+        // direct_8x8_inference_flag
+        reader.get_bits(1)?;
+        let (mut crop_left, mut crop_right, mut crop_top, mut crop_bottom) = (0, 0, 0, 0);
+        if reader.get_bool()?{
+            crop_left = reader.get_ue()?;
+            crop_right = reader.get_ue()?;
+            crop_top = reader.get_ue()?;
+            crop_bottom = reader.get_ue()?;
+        }
+
+        let chroma_array_type = if separate_colour_plane { 0 } else { chroma_format_idc };
+        let (sub_width, sub_height) = match chroma_array_type{
+            1 => (2, 2),
+            2 => (2, 1),
+            _ => (1, 1),
+        };
+        let field_factor = if frame_mbs_only { 1 } else { 2 };
+        let crop_x = crop_left.checked_add(crop_right)?.checked_mul(sub_width)?;
+        let crop_y = crop_top.checked_add(crop_bottom)?.checked_mul(sub_height * field_factor)?;
+        let width = width_in_mbs.checked_mul(16)?.checked_sub(crop_x)?;
+        let height = height_in_map_units.checked_mul(16 * field_factor)?.checked_sub(crop_y)?;
+
+        Some(H264Sps{
+            sps_id: sps_id,
+            profile_idc: profile_idc,
+            constraint_flags: constraint_flags,
+            level_idc: level_idc,
+            chroma_format_idc: chroma_format_idc,
+            bit_depth_luma: bit_depth_luma,
+            bit_depth_chroma: bit_depth_chroma,
+            max_num_ref_frames: max_num_ref_frames,
+            width: width,
+            height: height,
+            interlaced: !frame_mbs_only,
+        })
+    }
+
+    /// The profile as named in the video/x-h264 caps, eg: "high"
+    pub fn profile(&self) -> Option<&'static str>{
+        let constraint_set1 = self.constraint_flags & 0x40 != 0;
+        match self.profile_idc{
+            66 if constraint_set1 => Some("constrained-baseline"),
+            66 => Some("baseline"),
+            77 => Some("main"),
+            88 => Some("extended"),
+            100 => Some("high"),
+            110 => Some("high-10"),
+            122 => Some("high-4:2:2"),
+            244 => Some("high-4:4:4"),
+            _ => None,
+        }
+    }
+
+    /// The level as named in the video/x-h264 caps, eg: "3.1"
+    pub fn level(&self) -> String{
+        let constraint_set3 = self.constraint_flags & 0x10 != 0;
+        if self.level_idc == 9 || (self.level_idc == 11 && constraint_set3 && (self.profile_idc == 66 || self.profile_idc == 77 || self.profile_idc == 88)){
+            "1b".to_string()
+        }else if self.level_idc % 10 == 0{
+            format!("{}", self.level_idc / 10)
+        }else{
+            format!("{}.{}", self.level_idc / 10, self.level_idc % 10)
+        }
+    }
+}
+
+fn skip_scaling_list(reader: &mut BitReader, size: u32) -> Option<()>{
+    let mut last_scale = 8i64;
+    let mut next_scale = 8i64;
+    for _ in 0..size{
+        if next_scale != 0{
+            let delta_scale = reader.get_se()? as i64;
+            next_scale = ((last_scale + delta_scale) % 256 + 256) % 256;
+        }
+        if next_scale != 0{
+            last_scale = next_scale;
+        }
+    }
+    Some(())
+}
+
+/// The ids of an H.264 picture parameter set
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct H264Pps{
+    pub pps_id: u32,
+    /// The SPS this PPS refers to
+    pub sps_id: u32,
+    /// CABAC if true, CAVLC otherwise
+    pub entropy_coding_mode: bool,
+}
+
+impl H264Pps{
+    /// Parses a PPS NAL unit, starting with the NAL header. None if it's
+    /// not a PPS or it's truncated
+    pub fn parse(nal: &[u8]) -> Option<H264Pps>{
+        if nal.is_empty() || (nal[0] & 0x1f) as GstH264NalUnitType != GST_H264_NAL_PPS{
+            return None;
+        }
+        let rbsp = remove_emulation_prevention(&nal[1..]);
+        let mut reader = BitReader::new(&rbsp);
+        Some(H264Pps{
+            pps_id: reader.get_ue()?,
+            sps_id: reader.get_ue()?,
+            entropy_coding_mode: reader.get_bool()?,
+        })
+    }
+}
+
+/// A NAL unit found by H265Parser, data starts with the NAL header and
+/// still contains the emulation prevention bytes
+#[derive(Clone,Copy,Debug)]
+pub struct H265NalUnit<'a>{
+    /// One of GST_H265_NAL_*, eg: GST_H265_NAL_SPS
+    pub nal_type: GstH265NalUnitType,
+    pub layer_id: u8,
+    pub temporal_id: u8,
+    pub data: &'a [u8],
+}
+
+impl<'a> H265NalUnit<'a>{
+    /// Whether the NAL unit is a slice of an IRAP picture, eg: an IDR,
+    /// where decoding can start
+    pub fn is_irap(&self) -> bool{
+        self.nal_type >= GST_H265_NAL_SLICE_BLA_W_LP && self.nal_type <= 23
+    }
+}
+
+/// Splits H.265 elementary streams into NAL units, works like
+/// H264Parser
+pub struct H265Parser{
+    parser: *mut GstH265Parser
+}
+
+impl H265Parser{
+    pub fn new() -> H265Parser{
+        unsafe{
+            H265Parser{ parser: gst_h265_parser_new() }
+        }
+    }
+
+    /// NAL units of data in byte-stream format, the ones separated by
+    /// start codes as in the video/x-h265,stream-format=byte-stream caps
+    pub fn nal_units<'a>(&mut self, data: &'a [u8]) -> Vec<H265NalUnit<'a>>{
+        let mut nals = vec![];
+        let mut offset = 0;
+        loop{
+            let mut nalu = GstH265NalUnit::default();
+            let res = unsafe{
+                gst_h265_parser_identify_nalu(self.parser, data.as_ptr(), offset, data.len() as gsize, &mut nalu)
+            };
+            if (res != GST_H265_PARSER_OK && res != GST_H265_PARSER_NO_NAL_END) || nalu.size == 0{
+                break;
+            }
+            nals.push(h265_nal_unit(data, &nalu));
+            offset = nalu.offset + nalu.size;
+            if res == GST_H265_PARSER_NO_NAL_END{
+                break;
+            }
+        }
+        nals
+    }
+
+    /// NAL units of data in hvc1 or hev1 format, each one prefixed with
+    /// its size in nal_length_size bytes
+    pub fn nal_units_hevc<'a>(&mut self, data: &'a [u8], nal_length_size: u8) -> Vec<H265NalUnit<'a>>{
+        let mut nals = vec![];
+        let mut offset = 0;
+        while (offset as usize) < data.len(){
+            let mut nalu = GstH265NalUnit::default();
+            let res = unsafe{
+                gst_h265_parser_identify_nalu_hevc(self.parser, data.as_ptr(), offset, data.len() as gsize, nal_length_size, &mut nalu)
+            };
+            if res != GST_H265_PARSER_OK || nalu.size == 0{
+                break;
+            }
+            nals.push(h265_nal_unit(data, &nalu));
+            offset = nalu.offset + nalu.size;
+        }
+        nals
+    }
+}
+
+impl Drop for H265Parser{
+    fn drop(&mut self){
+        unsafe{
+            gst_h265_parser_free(self.parser);
+        }
+    }
+}
+
+fn h265_nal_unit<'a>(data: &'a [u8], nalu: &GstH265NalUnit) -> H265NalUnit<'a>{
+    let start = nalu.offset as usize;
+    H265NalUnit{
+        nal_type: nalu._type as GstH265NalUnitType,
+        layer_id: nalu.layer_id,
+        temporal_id: nalu.temporal_id_plus1.saturating_sub(1),
+        data: &data[start .. start + nalu.size as usize],
+    }
+}
+
+/// The fields of an H.265 sequence parameter set needed to describe the
+/// stream, parsed like H264Sps
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct H265Sps{
+    pub sps_id: u32,
+    pub profile_idc: u8,
+    /// High tier if true, main tier otherwise
+    pub high_tier: bool,
+    pub level_idc: u8,
+    pub chroma_format_idc: u32,
+    pub bit_depth_luma: u32,
+    pub bit_depth_chroma: u32,
+    /// Width and height in pixels once cropped to the conformance window
+    pub width: u32,
+    pub height: u32,
+}
+
+impl H265Sps{
+    /// Parses an SPS NAL unit, starting with the NAL header as returned
+    /// by H265Parser. None if it's not an SPS or it's truncated
+    pub fn parse(nal: &[u8]) -> Option<H265Sps>{
+        if nal.len() < 2 || ((nal[0] >> 1) & 0x3f) as GstH265NalUnitType != GST_H265_NAL_SPS{
+            return None;
+        }
+        let rbsp = remove_emulation_prevention(&nal[2..]);
+        let mut reader = BitReader::new(&rbsp);
+        // sps_video_parameter_set_id
+        reader.get_bits(4)?;
+        let max_sub_layers_minus1 = reader.get_bits(3)?;
+        // sps_temporal_id_nesting_flag
+        reader.get_bits(1)?;
+
+        // profile_tier_level: general_profile_space, tier and profile,
+        // the 32 compatibility flags and 48 bits of constraint flags
+        reader.get_bits(2)?;
+        let high_tier = reader.get_bool()?;
+        let profile_idc = reader.get_bits(5)? as u8;
+        reader.get_bits(32)?;
+        reader.get_bits_u64(48)?;
+        let level_idc = reader.get_bits(8)? as u8;
+        let mut sub_layers = vec![];
+        for _ in 0..max_sub_layers_minus1{
+            sub_layers.push((reader.get_bool()?, reader.get_bool()?));
+        }
+        if max_sub_layers_minus1 > 0{
+            for _ in max_sub_layers_minus1..8{
+                reader.get_bits(2)?;
+            }
+        }
+        for &(profile_present, level_present) in &sub_layers{
+            if profile_present{
+                reader.get_bits_u64(44)?;
+                reader.get_bits_u64(44)?;
+            }
+            if level_present{
+                reader.get_bits(8)?;
+            }
+        }
+
+        let sps_id = reader.get_ue()?;
+        let chroma_format_idc = reader.get_ue()?;
+        let separate_colour_plane = if chroma_format_idc == 3{
+            reader.get_bool()?
+        }else{
+            false
+        };
+        let mut width = reader.get_ue()?;
+        let mut height = reader.get_ue()?;
+        if reader.get_bool()?{
+            let (sub_width, sub_height) = match (chroma_format_idc, separate_colour_plane){
+                (1, false) => (2, 2),
+                (2, false) => (2, 1),
+                _ => (1, 1),
+            };
+            let left = reader.get_ue()?;
+            let right = reader.get_ue()?;
+            let top = reader.get_ue()?;
+            let bottom = reader.get_ue()?;
+            width = width.checked_sub(left.checked_add(right)?.checked_mul(sub_width)?)?;
+            height = height.checked_sub(top.checked_add(bottom)?.checked_mul(sub_height)?)?;
+        }
+        let bit_depth_luma = reader.get_ue()?.checked_add(8)?;
+        let bit_depth_chroma = reader.get_ue()?.checked_add(8)?;
+
+        Some(H265Sps{
+            sps_id: sps_id,
+            profile_idc: profile_idc,
+            high_tier: high_tier,
+            level_idc: level_idc,
+            chroma_format_idc: chroma_format_idc,
+            bit_depth_luma: bit_depth_luma,
+            bit_depth_chroma: bit_depth_chroma,
+            width: width,
+            height: height,
+        })
+    }
+
+    /// The profile as named in the video/x-h265 caps, eg: "main-10".
+    /// None for the range extensions and later profiles, which need the
+    /// constraint flags to be told apart
+    pub fn profile(&self) -> Option<&'static str>{
+        match self.profile_idc{
+            1 => Some("main"),
+            2 => Some("main-10"),
+            3 => Some("main-still-picture"),
+            _ => None,
+        }
+    }
+
+    /// The level as named in the video/x-h265 caps, eg: "4.1"
+    pub fn level(&self) -> String{
+        let level = self.level_idc / 3;
+        if level % 10 == 0{
+            format!("{}", level / 10)
+        }else{
+            format!("{}.{}", level / 10, level % 10)
+        }
+    }
+}
+
+/// The ids of an H.265 picture parameter set
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct H265Pps{
+    pub pps_id: u32,
+    /// The SPS this PPS refers to
+    pub sps_id: u32,
+}
+
+impl H265Pps{
+    /// Parses a PPS NAL unit, starting with the NAL header. None if it's
+    /// not a PPS or it's truncated
+    pub fn parse(nal: &[u8]) -> Option<H265Pps>{
+        if nal.len() < 2 || ((nal[0] >> 1) & 0x3f) as GstH265NalUnitType != GST_H265_NAL_PPS{
+            return None;
+        }
+        let rbsp = remove_emulation_prevention(&nal[2..]);
+        let mut reader = BitReader::new(&rbsp);
+        Some(H265Pps{
+            pps_id: reader.get_ue()?,
+            sps_id: reader.get_ue()?,
+        })
+    }
+}
+
+/// Removes the 0x03 bytes inserted after two 0x00 so the payload of a
+/// NAL unit doesn't contain start codes, the payload has to be unescaped
+/// before reading its fields
+pub fn remove_emulation_prevention(data: &[u8]) -> Vec<u8>{
+    let mut rbsp = Vec::with_capacity(data.len());
+    let mut zeros = 0;
+    for &byte in data{
+        if zeros >= 2 && byte == 3{
+            zeros = 0;
+            continue;
+        }
+        zeros = if byte == 0 { zeros + 1 } else { 0 };
+        rbsp.push(byte);
+    }
+    rbsp
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn exp_golomb(){
+        // ue(v) 0, 1, 2, 3 and 254 then se(v) 1, -1, 2 and -2
+        let data = [0xa6, 0x40, 0x1f, 0xe9, 0x90, 0xa0];
+        let mut reader = BitReader::new(&data);
+        assert_eq!(reader.get_ue(), Some(0));
+        assert_eq!(reader.get_ue(), Some(1));
+        assert_eq!(reader.get_ue(), Some(2));
+        assert_eq!(reader.get_ue(), Some(3));
+        assert_eq!(reader.get_ue(), Some(254));
+        assert_eq!(reader.get_se(), Some(1));
+        assert_eq!(reader.get_se(), Some(-1));
+        assert_eq!(reader.get_se(), Some(2));
+        assert_eq!(reader.get_se(), Some(-2));
+        // only the zero padding is left
+        assert_eq!(reader.get_ue(), None);
+        assert_eq!(reader.position(), 43);
+    }
+
+    #[test]
+    fn exp_golomb_invalid(){
+        // more than 31 leading zeros
+        let data = [0, 0, 0, 0, 0x80];
+        let mut reader = BitReader::new(&data);
+        assert_eq!(reader.get_ue(), None);
+        assert_eq!(reader.position(), 0);
+
+        // truncated suffix
+        let data = [0x00, 0x10];
+        let mut reader = BitReader::new(&data);
+        assert_eq!(reader.get_ue(), None);
+        assert_eq!(reader.position(), 0);
+    }
+
+    #[test]
+    fn h264_sps(){
+        let sps = H264Sps::parse(&[0x67, 0x42, 0xc0, 0x1e, 0xf4, 0x0a, 0x0f, 0xc8]).unwrap();
+        assert_eq!(sps.sps_id, 0);
+        assert_eq!(sps.profile(), Some("constrained-baseline"));
+        assert_eq!(sps.level(), "3");
+        assert_eq!(sps.max_num_ref_frames, 1);
+        assert_eq!((sps.width, sps.height), (320, 240));
+        assert!(!sps.interlaced);
+        assert_eq!(H264Sps::parse(&[0x67, 0x42, 0xc0, 0x1e, 0xf4]), None);
+        assert_eq!(H264Sps::parse(&[0x68, 0xce, 0x38, 0x80]), None);
+    }
+
+    #[test]
+    fn h264_pps(){
+        let pps = H264Pps::parse(&[0x68, 0xce, 0x38, 0x80]).unwrap();
+        assert_eq!(pps, H264Pps{ pps_id: 0, sps_id: 0, entropy_coding_mode: false });
+    }
+
+    #[test]
+    fn emulation_prevention(){
+        assert_eq!(remove_emulation_prevention(&[0, 0, 3, 1, 0, 0, 3, 0, 3]), vec![0, 0, 1, 0, 0, 0, 3]);
+    }
+}
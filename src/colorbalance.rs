@@ -0,0 +1,188 @@
+use ffi::*;
+use util::*;
+use element::Element;
+use gobject::GObjectRef;
+use handle::SignalHandle;
+use reference::Reference;
+
+/// Whether the adjustments are done by the hardware, eg: the overlay of
+/// a video card, or by the element itself
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum BalanceType{
+    Hardware,
+    Software,
+}
+
+/// One of the adjustable channels of a ColorBalance, usually
+/// "BRIGHTNESS", "CONTRAST", "HUE" and "SATURATION"
+#[derive(Clone)]
+pub struct ColorBalanceChannel{
+    channel: GObjectRef
+}
+
+impl ColorBalanceChannel{
+    pub unsafe fn new_ref(channel: *mut GstColorBalanceChannel) -> Option<ColorBalanceChannel>{
+        GObjectRef::new_ref(channel as gpointer).map(|channel| ColorBalanceChannel{ channel: channel })
+    }
+
+    pub fn label(&self) -> &str{
+        unsafe{
+            from_c_str!((*self.gst_channel()).label)
+        }
+    }
+
+    pub fn min_value(&self) -> i32{
+        unsafe{
+            (*self.gst_channel()).min_value
+        }
+    }
+
+    pub fn max_value(&self) -> i32{
+        unsafe{
+            (*self.gst_channel()).max_value
+        }
+    }
+
+    pub unsafe fn gst_channel(&self) -> *mut GstColorBalanceChannel{
+        self.channel.object as *mut GstColorBalanceChannel
+    }
+}
+
+/// Access to the GstColorBalance interface implemented by video sinks,
+/// capture sources and filters like videobalance, to build brightness,
+/// contrast, hue and saturation controls:
+///
+/// ```ignore
+/// let mut balance = gst::ColorBalance::new(&playbin).unwrap();
+/// balance.set_normalized("BRIGHTNESS", 0.2);
+/// for channel in balance.channels(){
+///     println!("{}: {}", channel.label(), balance.value(&channel));
+/// }
+/// ```
+pub struct ColorBalance{
+    element: Element
+}
+
+impl ColorBalance{
+    /// Returns the color balance interface of element. If the element
+    /// is a bin that doesn't implement it, the first child implementing
+    /// it is returned
+    pub fn new(element: &Element) -> Option<ColorBalance>{
+        unsafe{
+            let instance = element.gst_element() as *mut GTypeInstance;
+            if g_type_check_instance_is_a(instance, gst_color_balance_get_type()) != 0{
+                Some(ColorBalance{ element: element.reference() })
+            }else if g_type_check_instance_is_a(instance, gst_bin_get_type()) != 0{
+                let element = gst_bin_get_by_interface(element.gst_element() as *mut GstBin, gst_color_balance_get_type());
+                Element::new_from_gst_element(element).map(|element| ColorBalance{ element: element })
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn channels(&self) -> Vec<ColorBalanceChannel>{
+        let mut channels = vec![];
+        unsafe{
+            let mut node = gst_color_balance_list_channels(self.gst_color_balance() as *mut GstColorBalance);
+            while node != ptr::null(){
+                if let Some(channel) = ColorBalanceChannel::new_ref((*node).data as *mut GstColorBalanceChannel){
+                    channels.push(channel);
+                }
+                node = (*node).next;
+            }
+        }
+        channels
+    }
+
+    /// The channel with label, eg: "BRIGHTNESS". Labels are usually
+    /// uppercase but some elements use their own names, the comparison
+    /// ignores the case
+    pub fn channel(&self, label: &str) -> Option<ColorBalanceChannel>{
+        self.channels().into_iter().find(|channel| channel.label().eq_ignore_ascii_case(label))
+    }
+
+    pub fn value(&self, channel: &ColorBalanceChannel) -> i32{
+        unsafe{
+            gst_color_balance_get_value(self.gst_color_balance() as *mut GstColorBalance, channel.gst_channel())
+        }
+    }
+
+    /// Sets the value of channel, between its min_value and max_value
+    pub fn set_value(&mut self, channel: &ColorBalanceChannel, value: i32){
+        let value = value.max(channel.min_value()).min(channel.max_value());
+        unsafe{
+            gst_color_balance_set_value(self.gst_color_balance_mut(), channel.gst_channel(), value);
+        }
+    }
+
+    /// The value of the channel with label scaled to -1..1, 0 being the
+    /// middle of its range, which is the default for most elements.
+    /// None if there's no such channel
+    pub fn normalized(&self, label: &str) -> Option<f64>{
+        let channel = self.channel(label)?;
+        let (min, max) = (channel.min_value() as f64, channel.max_value() as f64);
+        if max <= min{
+            return Some(0.);
+        }
+        Some((self.value(&channel) as f64 - min) / (max - min) * 2. - 1.)
+    }
+
+    /// Sets the value of the channel with label from a value in -1..1,
+    /// so controls work the same with any element whatever the range of
+    /// its channels. Returns false if there's no such channel
+    pub fn set_normalized(&mut self, label: &str, value: f64) -> bool{
+        match self.channel(label){
+            Some(channel) => {
+                let (min, max) = (channel.min_value() as f64, channel.max_value() as f64);
+                let value = (value.max(-1.).min(1.) + 1.) / 2. * (max - min) + min;
+                self.set_value(&channel, value.round() as i32);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn balance_type(&self) -> BalanceType{
+        unsafe{
+            match gst_color_balance_get_balance_type(self.gst_color_balance() as *mut GstColorBalance){
+                GST_COLOR_BALANCE_HARDWARE => BalanceType::Hardware,
+                _ => BalanceType::Software,
+            }
+        }
+    }
+
+    /// Calls handler with the channel and its new value every time a
+    /// value changes, also when changed by the element itself
+    pub fn connect_value_changed<F>(&mut self, mut handler: F) -> Option<SignalHandle>
+        where F: FnMut(&ColorBalanceChannel, i32) + Send + 'static{
+        self.element.connect_values("value-changed", move |args| {
+            let channel = args.get(1).and_then(|channel| unsafe{
+                ColorBalanceChannel::new_ref(g_value_get_object(channel.gst_value()) as *mut GstColorBalanceChannel)
+            });
+            let value = args.get(2).and_then(|value| value.get::<i32>());
+            if let (Some(channel), Some(value)) = (channel, value){
+                handler(&channel, value);
+            }
+            None
+        })
+    }
+
+    pub fn element(&self) -> &Element{
+        &self.element
+    }
+
+    pub unsafe fn gst_color_balance(&self) -> *const GstColorBalance{
+        self.element.gst_element() as *const GstColorBalance
+    }
+
+    pub unsafe fn gst_color_balance_mut(&mut self) -> *mut GstColorBalance{
+        self.element.gst_element_mut() as *mut GstColorBalance
+    }
+}
+
+impl Reference for ColorBalance{
+    fn reference(&self) -> ColorBalance{
+        ColorBalance{ element: self.element.reference() }
+    }
+}
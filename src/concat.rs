@@ -0,0 +1,157 @@
+use ffi::*;
+
+use bin::Bin;
+use element::Element;
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use handle::ProbeHandle;
+use reference::Reference;
+use error::{Error, Result};
+
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+
+type SourceFinished = Arc<Mutex<Option<Box<dyn FnMut(usize) + Send>>>>;
+
+/// Wrapper for the concat element, which plays its sources one after the
+/// other as a single stream, eg: to encode a playlist into a single file
+/// without gaps:
+///
+/// ```ignore
+/// let mut pipeline = gst::Pipeline::new_from_str("concat name=c ! audioconvert ! audioresample ! vorbisenc ! oggmux ! filesink location=all.ogg").unwrap();
+/// let mut concat = gst::Concat::new_from_element(pipeline.get_by_name("c").unwrap());
+/// for uri in &playlist{
+///     concat.add_uri(&mut pipeline, uri, "audio/").unwrap();
+/// }
+/// concat.connect_source_finished(|index| println!("finished source {}", index));
+/// pipeline.play();
+/// ```
+///
+/// The timestamps of each source are shifted so the output is
+/// continuous and only the EOS of the last source goes downstream. All
+/// the sources must produce the same kind of stream, with converters
+/// after concat if the formats can differ.
+pub struct Concat{
+    concat: Element,
+    sink_pads: Vec<Pad>,
+    source_finished: SourceFinished,
+    _probes: Vec<ProbeHandle>,
+}
+
+impl Concat{
+    pub fn new(name: &str) -> Option<Concat>{
+        Element::new("concat", name).map(Concat::new_from_element)
+    }
+
+    pub fn new_from_element(concat: Element) -> Concat{
+        Concat{
+            concat: concat,
+            sink_pads: vec![],
+            source_finished: Arc::new(Mutex::new(None)),
+            _probes: vec![],
+        }
+    }
+
+    /// Links the "src" pad of source after the previously added sources.
+    /// Both have to be in the same bin already
+    pub fn add_source(&mut self, source: &mut Element) -> Result<()>{
+        let mut src = source.static_pad("src").ok_or(Error::new(0, 0, "source has no src pad"))?;
+        let mut sink = self.next_sink_pad()?;
+        src.link(&mut sink).map_err(|err| Error::new(0, 0, &format!("couldn't link source to concat: {:?}", err)))
+    }
+
+    /// Adds a uridecodebin to bin, which has to contain concat, and links
+    /// its first decoded stream whose caps start with media, eg: "audio/"
+    /// or "video/", after the previously added sources. Returns the
+    /// uridecodebin
+    pub fn add_uri(&mut self, bin: &mut Bin, uri: &str, media: &str) -> Result<Element>{
+        let mut decodebin = ::DecodeBin::new_uri("").ok_or(Error::new(0, 0, "couldn't create uridecodebin"))?;
+        decodebin.set_uri(uri);
+        let mut sink = self.next_sink_pad()?;
+        let media = media.to_string();
+        let handle = decodebin.connect_values("pad-added", move |args| {
+            if let Some(mut pad) = args.get(1).and_then(|pad| pad.get::<Pad>()){
+                let matches = pad.query_caps(None)
+                    .and_then(|caps| caps.structure(0).map(|structure| structure.name().starts_with(&media)))
+                    .unwrap_or(false);
+                if matches && !sink.is_linked(){
+                    let _ = pad.link(&mut sink);
+                }
+            }
+            None
+        });
+        if let Some(handle) = handle{
+            handle.forget();
+        }
+        let mut decodebin: Element = decodebin.into();
+        if !bin.add(decodebin.reference()){
+            return Err(Error::new(0, 0, "couldn't add uridecodebin to the bin"));
+        }
+        decodebin.sync_state_with_parent();
+        Ok(decodebin)
+    }
+
+    /// Number of sources added
+    pub fn n_sources(&self) -> usize{
+        self.sink_pads.len()
+    }
+
+    /// Index of the source being played, None if concat hasn't started
+    /// or all the sources finished
+    pub fn active_source(&self) -> Option<usize>{
+        let active = self.concat.get_value("active-pad").and_then(|value| value.get::<Pad>())?;
+        unsafe{
+            self.sink_pads.iter().position(|pad| pad.gst_pad() == active.gst_pad())
+        }
+    }
+
+    /// Calls callback with the index of each source when it reaches EOS,
+    /// from the streaming thread of the source. Replaces any previous
+    /// callback
+    pub fn connect_source_finished<F: FnMut(usize) + Send + 'static>(&mut self, callback: F){
+        *self.source_finished.lock().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(callback));
+    }
+
+    fn next_sink_pad(&mut self) -> Result<Pad>{
+        let mut sink = self.concat.request_pad("sink_%u").ok_or(Error::new(0, 0, "couldn't request concat sink pad"))?;
+        let index = self.sink_pads.len();
+        let source_finished = self.source_finished.clone();
+        let probe = sink.add_probe(GST_PAD_PROBE_TYPE_EVENT_DOWNSTREAM, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+            if let Some(event) = info.event(){
+                if event.event_type() == GST_EVENT_EOS{
+                    if let Some(ref mut callback) = *source_finished.lock().unwrap_or_else(|e| e.into_inner()){
+                        callback(index);
+                    }
+                }
+            }
+            PadProbeReturn::Ok
+        });
+        self._probes.extend(probe);
+        self.sink_pads.push(sink.reference());
+        Ok(sink)
+    }
+}
+
+impl AsRef<Element> for Concat{
+    fn as_ref(&self) -> &Element{
+        &self.concat
+    }
+}
+
+impl AsMut<Element> for Concat{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.concat
+    }
+}
+
+impl Deref for Concat{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.concat
+    }
+}
+
+impl DerefMut for Concat{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.concat
+    }
+}
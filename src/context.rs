@@ -0,0 +1,145 @@
+use ffi::*;
+use util::*;
+use reference::Reference;
+use miniobject::MiniObject;
+use structure::{StructureRef, StructureRefMut};
+use version::ApiAvailability;
+use error::Result;
+
+use std::ops::{Deref, DerefMut};
+
+pub const CONTEXT: ApiAvailability = ApiAvailability::new("Context", 1, 2, 0);
+
+// the functions used once there's a context, which can only exist if
+// they are available
+unsafe fn context_fn<F: Copy>(symbol: &str) -> F{
+    CONTEXT.function(symbol).expect("Context without GStreamer 1.2")
+}
+
+/// Resources shared between elements, identified by a context type, eg:
+/// the OpenGL display of the GL elements, "gst.gl.GLDisplay", or the VA
+/// display of the VAAPI elements, "gst.vaapi.Display".
+///
+/// Elements that need a context post a NeedContext message on the bus,
+/// the application can answer it, from a sync handler since the element
+/// waits for it, by setting a context on the element:
+///
+/// ```ignore
+/// bus.set_sync_handler(move |msg: &gst::Message| {
+///     if msg.context_type().map(|t| t == "gst.gl.GLDisplay").unwrap_or(false){
+///         let mut context = gst::Context::new("gst.gl.GLDisplay", true).unwrap();
+///         context.structure_mut().unwrap().set("gst.gl.GLDisplay", &display);
+///         msg.src_element().unwrap().set_context(&context).unwrap();
+///     }
+///     gst::BusSyncReply::Pass
+/// });
+/// ```
+///
+/// Contexts created by the elements themselves are posted in
+/// HaveContext messages and can be set on other pipelines to share them.
+#[derive(Clone)]
+pub struct Context{
+    context: MiniObject<GstContext>
+}
+
+impl Context{
+    pub unsafe fn new_from_gst_context(context: *mut GstContext) -> Option<Context>{
+        MiniObject::new(context)
+            .map(|miniobject| Context{ context: miniobject })
+    }
+
+    /// Creates a new empty context of context_type, persistent contexts
+    /// are kept by the elements when going to NULL. Needs GStreamer 1.2,
+    /// returns an unsupported error with older versions
+    pub fn new(context_type: &str, persistent: bool) -> Result<Context>{
+        let ccontext_type = CString::new(context_type).unwrap();
+        unsafe{
+            let context_new: unsafe extern "C" fn(*const gchar, gboolean) -> *mut GstContext = CONTEXT.function("gst_context_new")?;
+            Ok(Context::new_from_gst_context(context_new(ccontext_type.as_ptr(), persistent as gboolean)).unwrap())
+        }
+    }
+
+    pub fn context_type(&self) -> &str{
+        unsafe{
+            let get_context_type: unsafe extern "C" fn(*const GstContext) -> *const gchar = context_fn("gst_context_get_context_type");
+            from_c_str!(get_context_type(self.gst_context()))
+        }
+    }
+
+    pub fn has_context_type(&self, context_type: &str) -> bool{
+        self.context_type() == context_type
+    }
+
+    pub fn is_persistent(&self) -> bool{
+        unsafe{
+            let is_persistent: unsafe extern "C" fn(*const GstContext) -> gboolean = context_fn("gst_context_is_persistent");
+            is_persistent(self.gst_context()) != 0
+        }
+    }
+
+    /// The fields holding the shared resources, eg: the display object
+    pub fn structure<'a>(&'a self) -> StructureRef<'a>{
+        unsafe{
+            let get_structure: unsafe extern "C" fn(*const GstContext) -> *const GstStructure = context_fn("gst_context_get_structure");
+            StructureRef::new(get_structure(self.gst_context())).unwrap()
+        }
+    }
+
+    /// The structure to set the fields of the context, None if the
+    /// context isn't writable, eg: because it's already been set on an
+    /// element
+    pub fn structure_mut<'a>(&'a mut self) -> Option<StructureRefMut<'a>>{
+        unsafe{
+            if gst_mini_object_is_writable(self.gst_context() as *const GstMiniObject) == 0{
+                return None;
+            }
+            let writable_structure: unsafe extern "C" fn(*mut GstContext) -> *mut GstStructure = context_fn("gst_context_writable_structure");
+            StructureRefMut::new(writable_structure(self.gst_context_mut()))
+        }
+    }
+
+    pub unsafe fn gst_context(&self) -> *const GstContext{
+        self.context.as_ptr()
+    }
+
+    pub unsafe fn gst_context_mut(&mut self) -> *mut GstContext{
+        self.context.as_mut_ptr()
+    }
+}
+
+impl ::Transfer<GstContext> for Context{
+    unsafe fn transfer(self) -> *mut GstContext{
+        self.context.transfer()
+    }
+}
+
+impl Reference for Context{
+    fn reference(&self) -> Context{
+        Context{ context: self.context.reference() }
+    }
+}
+
+impl AsRef<MiniObject> for Context{
+    fn as_ref(&self) -> &MiniObject{
+        self.context.upcast_ref()
+    }
+}
+
+impl AsMut<MiniObject> for Context{
+    fn as_mut(&mut self) -> &mut MiniObject{
+        self.context.upcast_mut()
+    }
+}
+
+impl Deref for Context{
+    type Target = MiniObject;
+    fn deref(&self) -> &MiniObject{
+        self.context.upcast_ref()
+    }
+}
+
+impl DerefMut for Context{
+    fn deref_mut(&mut self) -> &mut MiniObject{
+        self.context.upcast_mut()
+    }
+}
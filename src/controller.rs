@@ -0,0 +1,383 @@
+use ffi::*;
+use util::*;
+use object::Object;
+use reference::Reference;
+use value::{G_TYPE_BOOLEAN, G_TYPE_INT, G_TYPE_UINT, G_TYPE_INT64, G_TYPE_UINT64, G_TYPE_ENUM, G_TYPE_FLOAT, G_TYPE_DOUBLE};
+
+use std::ops::{Deref, DerefMut};
+use std::slice;
+
+/// How the values between two control points are computed
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum InterpolationMode{
+    /// Keeps the value of the previous control point
+    None = GST_INTERPOLATION_MODE_NONE,
+    Linear = GST_INTERPOLATION_MODE_LINEAR,
+    /// Smooth curve through the control points, can overshoot them
+    Cubic = GST_INTERPOLATION_MODE_CUBIC,
+    /// Smooth curve that never overshoots the control points
+    CubicMonotonic = GST_INTERPOLATION_MODE_CUBIC_MONOTONIC,
+}
+
+/// A curve defined by control points, values at a given time, and
+/// interpolated between them. Attached to a property with a
+/// DirectControlBinding the property follows the curve as the element
+/// processes buffers, eg: a fade out of a volume element:
+///
+/// ```ignore
+/// use gst::controller::{InterpolationControlSource, InterpolationMode, DirectControlBinding};
+/// let mut source = InterpolationControlSource::new(InterpolationMode::Linear).unwrap();
+/// source.set(5 * 1_000_000_000, 1.0);
+/// source.set(7 * 1_000_000_000, 0.0);
+/// let binding = DirectControlBinding::new_absolute(&mut volume, "volume", &source).unwrap();
+/// ```
+///
+/// Times are in the stream time of the element, the position reported
+/// by the pipeline for most of them.
+pub struct InterpolationControlSource{
+    source: Object
+}
+
+impl InterpolationControlSource{
+    pub fn new(mode: InterpolationMode) -> Option<InterpolationControlSource>{
+        unsafe{
+            let source = gst_interpolation_control_source_new();
+            if source != ptr::null_mut(){
+                gst_object_ref_sink(source as gpointer);
+            }
+            Object::new(source as *mut GstObject).map(|obj| {
+                let mut source = InterpolationControlSource{ source: obj };
+                source.set_mode(mode);
+                source
+            })
+        }
+    }
+
+    pub fn mode(&self) -> InterpolationMode{
+        match self.get::<i32>("mode") as u32{
+            GST_INTERPOLATION_MODE_LINEAR => InterpolationMode::Linear,
+            GST_INTERPOLATION_MODE_CUBIC => InterpolationMode::Cubic,
+            GST_INTERPOLATION_MODE_CUBIC_MONOTONIC => InterpolationMode::CubicMonotonic,
+            _ => InterpolationMode::None,
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: InterpolationMode){
+        self.source.set("mode", mode as i32);
+    }
+
+    /// Adds a control point, or replaces the one at the same timestamp.
+    /// With DirectControlBinding::new the values are in the range 0..1
+    pub fn set(&mut self, timestamp: u64, value: f64) -> bool{
+        unsafe{
+            gst_timed_value_control_source_set(self.gst_timed_value_control_source_mut(), timestamp, value) != 0
+        }
+    }
+
+    /// Removes the control point at timestamp
+    pub fn unset(&mut self, timestamp: u64) -> bool{
+        unsafe{
+            gst_timed_value_control_source_unset(self.gst_timed_value_control_source_mut(), timestamp) != 0
+        }
+    }
+
+    pub fn unset_all(&mut self){
+        unsafe{
+            gst_timed_value_control_source_unset_all(self.gst_timed_value_control_source_mut());
+        }
+    }
+
+    /// Number of control points
+    pub fn count(&self) -> usize{
+        unsafe{
+            gst_timed_value_control_source_get_count(self.gst_control_source() as *mut GstTimedValueControlSource) as usize
+        }
+    }
+
+    /// The value of the curve at timestamp, None before the first
+    /// control point
+    pub fn value_at(&self, timestamp: u64) -> Option<f64>{
+        let mut value = 0.;
+        unsafe{
+            if gst_control_source_get_value(self.gst_control_source() as *mut GstControlSource, timestamp, &mut value) != 0{
+                Some(value)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub unsafe fn gst_control_source(&self) -> *const GstControlSource{
+        self.source.gst_object() as *const GstControlSource
+    }
+
+    pub unsafe fn gst_timed_value_control_source_mut(&mut self) -> *mut GstTimedValueControlSource{
+        self.source.gst_object_mut() as *mut GstTimedValueControlSource
+    }
+}
+
+impl Reference for InterpolationControlSource{
+    fn reference(&self) -> InterpolationControlSource{
+        InterpolationControlSource{ source: self.source.reference() }
+    }
+}
+
+impl Deref for InterpolationControlSource{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        &self.source
+    }
+}
+
+impl DerefMut for InterpolationControlSource{
+    fn deref_mut(&mut self) -> &mut Object{
+        &mut self.source
+    }
+}
+
+/// Drives a property of an object with the values of a control source.
+/// The binding is added to the object when created and stays there
+/// until removed, even if the wrapper is dropped
+pub struct DirectControlBinding{
+    binding: Object<GstControlBinding>,
+    object: Object,
+}
+
+impl DirectControlBinding{
+    /// Maps the values of source, in the range 0..1, to the range of
+    /// the property, eg: 0.5 is 180 for a property from 0 to 360
+    pub fn new<O: AsMut<Object>>(object: &mut O, property: &str, source: &InterpolationControlSource) -> Option<DirectControlBinding>{
+        DirectControlBinding::new_with(object.as_mut(), property, source, gst_direct_control_binding_new)
+    }
+
+    /// Sets the values of source on the property as they are, eg: 0.5
+    /// for the volume of a volume element is half the volume
+    pub fn new_absolute<O: AsMut<Object>>(object: &mut O, property: &str, source: &InterpolationControlSource) -> Option<DirectControlBinding>{
+        DirectControlBinding::new_with(object.as_mut(), property, source, gst_direct_control_binding_new_absolute)
+    }
+
+    fn new_with(object: &mut Object, property: &str, source: &InterpolationControlSource,
+            constructor: unsafe extern "C" fn(*mut GstObject, *const gchar, *mut GstControlSource) -> *mut GstControlBinding) -> Option<DirectControlBinding>{
+        let cproperty = CString::new(property).unwrap();
+        unsafe{
+            let binding = constructor(object.gst_object_mut(), cproperty.as_ptr(), source.gst_control_source() as *mut GstControlSource);
+            if binding == ptr::null_mut(){
+                return None;
+            }
+            gst_object_ref_sink(binding as gpointer);
+            if gst_object_add_control_binding(object.gst_object_mut(), binding) == 0{
+                gst_object_unref(binding as gpointer);
+                return None;
+            }
+            Object::new(binding).map(|binding| DirectControlBinding{
+                binding: binding,
+                object: object.reference(),
+            })
+        }
+    }
+
+    /// Stops controlling the property, which keeps its last value
+    pub fn remove(mut self) -> bool{
+        unsafe{
+            gst_object_remove_control_binding(self.object.gst_object_mut(), self.gst_control_binding_mut()) != 0
+        }
+    }
+
+    pub fn is_disabled(&self) -> bool{
+        unsafe{
+            gst_control_binding_is_disabled(self.gst_control_binding() as *mut GstControlBinding) != 0
+        }
+    }
+
+    /// Temporarily stops controlling the property, eg: while the user
+    /// moves a slider for it
+    pub fn set_disabled(&mut self, disabled: bool){
+        unsafe{
+            gst_control_binding_set_disabled(self.gst_control_binding_mut(), disabled as gboolean);
+        }
+    }
+
+    pub unsafe fn gst_control_binding(&self) -> *const GstControlBinding{
+        self.binding.as_ptr()
+    }
+
+    pub unsafe fn gst_control_binding_mut(&mut self) -> *mut GstControlBinding{
+        self.binding.as_mut_ptr()
+    }
+}
+
+impl Reference for DirectControlBinding{
+    fn reference(&self) -> DirectControlBinding{
+        DirectControlBinding{ binding: self.binding.reference(), object: self.object.reference() }
+    }
+}
+
+impl Deref for DirectControlBinding{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.binding.upcast_ref()
+    }
+}
+
+impl DerefMut for DirectControlBinding{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.binding.upcast_mut()
+    }
+}
+
+/// Range and default value of a controllable property
+#[derive(Clone,Debug,PartialEq)]
+pub enum PropertyRange{
+    Boolean{default: bool},
+    /// Integer properties of any size and sign
+    Int{min: i64, max: i64, default: i64},
+    UInt{min: u64, max: u64, default: u64},
+    /// Float and double properties
+    Double{min: f64, max: f64, default: f64},
+    /// The values of the enum with their nicks
+    Enum{values: Vec<(i32, String)>, default: i32},
+    /// Any other type, which can't be interpolated
+    Other,
+}
+
+/// A property flagged as controllable, that can be driven by a
+/// DirectControlBinding. Listing them allows to build the controls of
+/// an animation UI for any element:
+///
+/// ```ignore
+/// for property in gst::controller::ControllableProperty::list(&volume){
+///     if let gst::controller::PropertyRange::Double{min, max, ..} = property.range{
+///         add_slider(&property.nick, min, max);
+///     }
+/// }
+/// ```
+#[derive(Clone,Debug)]
+pub struct ControllableProperty{
+    pub name: String,
+    /// Short human readable name of the property, the name if it has
+    /// none
+    pub nick: String,
+    /// Description of the property, if it has one
+    pub blurb: Option<String>,
+    pub value_type: GType,
+    pub range: PropertyRange,
+    /// GST_PARAM_MUTABLE_READY, PAUSED or PLAYING if the property can
+    /// only be changed up to that state, 0 if it can always be changed
+    pub mutable_state: i32,
+}
+
+impl ControllableProperty{
+    /// The controllable properties of object
+    pub fn list(object: &Object) -> Vec<ControllableProperty>{
+        let mut properties = vec![];
+        unsafe{
+            let class = (*(object.gst_object() as *mut GTypeInstance)).g_class as *mut GObjectClass;
+            let mut n_pspecs = 0;
+            let pspecs = g_object_class_list_properties(class, &mut n_pspecs);
+            for &pspec in slice::from_raw_parts(pspecs, n_pspecs as usize){
+                let flags = (*pspec).flags;
+                if flags & GST_PARAM_CONTROLLABLE == 0 || flags & G_PARAM_WRITABLE == 0{
+                    continue;
+                }
+                let name = from_c_str!((*pspec).name).to_string();
+                let nick = g_param_spec_get_nick(pspec);
+                let nick = if nick != ptr::null(){
+                    from_c_str!(nick).to_string()
+                }else{
+                    name.clone()
+                };
+                let blurb = g_param_spec_get_blurb(pspec);
+                let blurb = if blurb != ptr::null(){
+                    Some(from_c_str!(blurb).to_string())
+                }else{
+                    None
+                };
+                properties.push(ControllableProperty{
+                    name: name,
+                    nick: nick,
+                    blurb: blurb,
+                    value_type: (*pspec).value_type,
+                    range: property_range(pspec),
+                    mutable_state: flags & (GST_PARAM_MUTABLE_READY | GST_PARAM_MUTABLE_PAUSED | GST_PARAM_MUTABLE_PLAYING),
+                });
+            }
+            g_free(pspecs as gpointer);
+        }
+        properties
+    }
+
+    /// Maps a value of the property to the range 0..1 used by the
+    /// control sources of a DirectControlBinding::new, None if the
+    /// property isn't numeric
+    pub fn normalize(&self, value: f64) -> Option<f64>{
+        let (min, max) = match self.range{
+            PropertyRange::Int{min, max, ..} => (min as f64, max as f64),
+            PropertyRange::UInt{min, max, ..} => (min as f64, max as f64),
+            PropertyRange::Double{min, max, ..} => (min, max),
+            PropertyRange::Boolean{..} => (0., 1.),
+            _ => return None,
+        };
+        if max > min{
+            Some(((value - min) / (max - min)).max(0.).min(1.))
+        }else{
+            Some(0.)
+        }
+    }
+
+    /// The value of the property for a control value in the range 0..1,
+    /// None if the property isn't numeric
+    pub fn denormalize(&self, control_value: f64) -> Option<f64>{
+        let (min, max) = match self.range{
+            PropertyRange::Int{min, max, ..} => (min as f64, max as f64),
+            PropertyRange::UInt{min, max, ..} => (min as f64, max as f64),
+            PropertyRange::Double{min, max, ..} => (min, max),
+            PropertyRange::Boolean{..} => (0., 1.),
+            _ => return None,
+        };
+        Some(min + control_value.max(0.).min(1.) * (max - min))
+    }
+}
+
+unsafe fn property_range(pspec: *mut GParamSpec) -> PropertyRange{
+    match g_type_fundamental((*pspec).value_type){
+        G_TYPE_BOOLEAN => {
+            let pspec = pspec as *mut GParamSpecBoolean;
+            PropertyRange::Boolean{ default: (*pspec).default_value != 0 }
+        }
+        G_TYPE_INT => {
+            let pspec = pspec as *mut GParamSpecInt;
+            PropertyRange::Int{ min: (*pspec).minimum as i64, max: (*pspec).maximum as i64, default: (*pspec).default_value as i64 }
+        }
+        G_TYPE_INT64 => {
+            let pspec = pspec as *mut GParamSpecInt64;
+            PropertyRange::Int{ min: (*pspec).minimum, max: (*pspec).maximum, default: (*pspec).default_value }
+        }
+        G_TYPE_UINT => {
+            let pspec = pspec as *mut GParamSpecUInt;
+            PropertyRange::UInt{ min: (*pspec).minimum as u64, max: (*pspec).maximum as u64, default: (*pspec).default_value as u64 }
+        }
+        G_TYPE_UINT64 => {
+            let pspec = pspec as *mut GParamSpecUInt64;
+            PropertyRange::UInt{ min: (*pspec).minimum, max: (*pspec).maximum, default: (*pspec).default_value }
+        }
+        G_TYPE_FLOAT => {
+            let pspec = pspec as *mut GParamSpecFloat;
+            PropertyRange::Double{ min: (*pspec).minimum as f64, max: (*pspec).maximum as f64, default: (*pspec).default_value as f64 }
+        }
+        G_TYPE_DOUBLE => {
+            let pspec = pspec as *mut GParamSpecDouble;
+            PropertyRange::Double{ min: (*pspec).minimum, max: (*pspec).maximum, default: (*pspec).default_value }
+        }
+        G_TYPE_ENUM => {
+            let pspec = pspec as *mut GParamSpecEnum;
+            let enum_class = (*pspec).enum_class;
+            let values = slice::from_raw_parts((*enum_class).values, (*enum_class).n_values as usize)
+                .iter()
+                .map(|value| (value.value, from_c_str!(value.value_nick).to_string()))
+                .collect();
+            PropertyRange::Enum{ values: values, default: (*pspec).default_value }
+        }
+        _ => PropertyRange::Other,
+    }
+}
@@ -0,0 +1,335 @@
+use ffi::*;
+use util::*;
+use value::{Value, ToValue, FromValue};
+use version::ApiAvailability;
+
+use std::fmt::{self, Display, Debug, Formatter};
+
+const ISO8601: ApiAvailability = ApiAvailability::new("DateTime iso8601 conversions", 1, 2, 0);
+
+/// A date and time with a time zone offset, as found in the datetime
+/// tag of recordings or in structure fields. Some fields can be missing,
+/// eg: a tag with only the year of an album, so the getters of the
+/// fields return None when they aren't set:
+///
+/// ```ignore
+/// if let Some(date) = tags.get::<gst::DateTime>("datetime"){
+///     println!("recorded in {}", date.year());
+/// }
+/// let mut tags = gst::TagList::new();
+/// tags.set("datetime", &gst::DateTime::now_utc());
+/// ```
+pub struct DateTime{
+    datetime: *mut GstDateTime
+}
+
+// GstDateTime is refcounted atomically and immutable
+unsafe impl Send for DateTime {}
+unsafe impl Sync for DateTime {}
+
+impl DateTime{
+    /// Takes ownership of the passed reference
+    pub unsafe fn new_from_gst_date_time(datetime: *mut GstDateTime) -> Option<DateTime>{
+        if datetime != ptr::null_mut(){
+            Some(DateTime{ datetime: datetime })
+        }else{
+            None
+        }
+    }
+
+    /// Full date and time, tz_offset is the offset from UTC in hours, eg:
+    /// -5.5. None if a value is out of range
+    pub fn new(tz_offset: f32, year: i32, month: i32, day: i32, hour: i32, minute: i32, seconds: f64) -> Option<DateTime>{
+        unsafe{
+            DateTime::new_from_gst_date_time(gst_date_time_new(tz_offset, year, month, day, hour, minute, seconds))
+        }
+    }
+
+    /// Date and time in the local time zone
+    pub fn new_local_time(year: i32, month: i32, day: i32, hour: i32, minute: i32, seconds: f64) -> Option<DateTime>{
+        unsafe{
+            DateTime::new_from_gst_date_time(gst_date_time_new_local_time(year, month, day, hour, minute, seconds))
+        }
+    }
+
+    /// A date without time
+    pub fn new_ymd(year: i32, month: i32, day: i32) -> Option<DateTime>{
+        unsafe{
+            DateTime::new_from_gst_date_time(gst_date_time_new_ymd(year, month, day))
+        }
+    }
+
+    /// Only a year and a month
+    pub fn new_ym(year: i32, month: i32) -> Option<DateTime>{
+        unsafe{
+            DateTime::new_from_gst_date_time(gst_date_time_new_ym(year, month))
+        }
+    }
+
+    /// Only a year
+    pub fn new_y(year: i32) -> Option<DateTime>{
+        unsafe{
+            DateTime::new_from_gst_date_time(gst_date_time_new_y(year))
+        }
+    }
+
+    pub fn now_utc() -> DateTime{
+        unsafe{
+            DateTime::new_from_gst_date_time(gst_date_time_new_now_utc()).unwrap()
+        }
+    }
+
+    pub fn now_local_time() -> DateTime{
+        unsafe{
+            DateTime::new_from_gst_date_time(gst_date_time_new_now_local_time()).unwrap()
+        }
+    }
+
+    /// From seconds since 1970-01-01 00:00:00 UTC
+    pub fn from_unix_epoch_utc(secs: i64) -> Option<DateTime>{
+        unsafe{
+            DateTime::new_from_gst_date_time(gst_date_time_new_from_unix_epoch_utc(secs))
+        }
+    }
+
+    pub fn from_unix_epoch_local_time(secs: i64) -> Option<DateTime>{
+        unsafe{
+            DateTime::new_from_gst_date_time(gst_date_time_new_from_unix_epoch_local_time(secs))
+        }
+    }
+
+    /// Parses an ISO 8601 date, eg: "2016-05-12T18:30:00Z" or "2016-05".
+    /// None with GStreamer older than 1.2
+    pub fn from_iso8601(date: &str) -> Option<DateTime>{
+        let cdate = CString::new(date).unwrap();
+        unsafe{
+            let new_from_iso8601_string: unsafe extern "C" fn(*const gchar) -> *mut GstDateTime =
+                ISO8601.function("gst_date_time_new_from_iso8601_string").ok()?;
+            DateTime::new_from_gst_date_time(new_from_iso8601_string(cdate.as_ptr()))
+        }
+    }
+
+    /// The date as ISO 8601 with only the fields that are set
+    pub fn to_iso8601(&self) -> Option<String>{
+        unsafe{
+            let to_iso8601_string: unsafe extern "C" fn(*mut GstDateTime) -> *mut gchar =
+                match ISO8601.function("gst_date_time_to_iso8601_string"){
+                    Ok(to_iso8601_string) => to_iso8601_string,
+                    Err(_) => return Some(self.format_iso8601()),
+                };
+            let string = to_iso8601_string(self.datetime);
+            if string != ptr::null_mut(){
+                let ret = from_c_str!(string).to_string();
+                g_free(string as gpointer);
+                Some(ret)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Seconds since 1970-01-01 00:00:00 UTC, None if the date doesn't
+    /// have a day
+    pub fn to_unix_epoch(&self) -> Option<i64>{
+        unsafe{
+            let to_g_date_time: unsafe extern "C" fn(*mut GstDateTime) -> *mut GDateTime =
+                match ISO8601.function("gst_date_time_to_g_date_time"){
+                    Ok(to_g_date_time) => to_g_date_time,
+                    Err(_) => return self.unix_epoch_from_fields(),
+                };
+            let datetime = to_g_date_time(self.datetime);
+            if datetime == ptr::null_mut(){
+                return None;
+            }
+            let secs = g_date_time_to_unix(datetime);
+            g_date_time_unref(datetime);
+            Some(secs)
+        }
+    }
+
+    pub fn year(&self) -> i32{
+        unsafe{
+            gst_date_time_get_year(self.datetime)
+        }
+    }
+
+    /// 1 to 12
+    pub fn month(&self) -> Option<i32>{
+        unsafe{
+            if gst_date_time_has_month(self.datetime) != 0{
+                Some(gst_date_time_get_month(self.datetime))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// 1 to 31
+    pub fn day(&self) -> Option<i32>{
+        unsafe{
+            if gst_date_time_has_day(self.datetime) != 0{
+                Some(gst_date_time_get_day(self.datetime))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn hour(&self) -> Option<i32>{
+        unsafe{
+            if gst_date_time_has_time(self.datetime) != 0{
+                Some(gst_date_time_get_hour(self.datetime))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn minute(&self) -> Option<i32>{
+        unsafe{
+            if gst_date_time_has_time(self.datetime) != 0{
+                Some(gst_date_time_get_minute(self.datetime))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn second(&self) -> Option<i32>{
+        unsafe{
+            if gst_date_time_has_second(self.datetime) != 0{
+                Some(gst_date_time_get_second(self.datetime))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn microsecond(&self) -> Option<i32>{
+        unsafe{
+            if gst_date_time_has_second(self.datetime) != 0{
+                Some(gst_date_time_get_microsecond(self.datetime))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Offset from UTC in hours, None if the date doesn't have a time
+    pub fn time_zone_offset(&self) -> Option<f32>{
+        unsafe{
+            if gst_date_time_has_time(self.datetime) != 0{
+                Some(gst_date_time_get_time_zone_offset(self.datetime))
+            }else{
+                None
+            }
+        }
+    }
+
+    // what gst_date_time_to_iso8601_string produces, for versions
+    // without it
+    fn format_iso8601(&self) -> String{
+        let mut string = format!("{:04}", self.year());
+        if let Some(month) = self.month(){
+            string += &format!("-{:02}", month);
+        }
+        if let Some(day) = self.day(){
+            string += &format!("-{:02}", day);
+        }
+        if let (Some(hour), Some(minute)) = (self.hour(), self.minute()){
+            string += &format!("T{:02}:{:02}", hour, minute);
+            if let Some(second) = self.second(){
+                string += &format!(":{:02}", second);
+                match self.microsecond(){
+                    Some(microsecond) if microsecond > 0 => string += &format!(".{:06}", microsecond),
+                    _ => (),
+                }
+            }
+            match self.time_zone_offset(){
+                Some(offset) if offset != 0.0 => {
+                    let minutes = (offset.abs() * 60.0).round() as i32;
+                    let sign = if offset < 0.0 { '-' } else { '+' };
+                    string += &format!("{}{:02}{:02}", sign, minutes / 60, minutes % 60);
+                }
+                _ => string.push('Z'),
+            }
+        }
+        string
+    }
+
+    // the same as converting to a GDateTime, for versions without
+    // gst_date_time_to_g_date_time
+    fn unix_epoch_from_fields(&self) -> Option<i64>{
+        let (year, month, day) = (self.year() as i64, self.month()? as i64, self.day()? as i64);
+        // days since 1970-01-01 of the proleptic gregorian date
+        let y = if month <= 2 { year - 1 } else { year };
+        let era = (if y >= 0 { y } else { y - 399 }) / 400;
+        let yoe = y - era * 400;
+        let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146097 + doe - 719468;
+        let secs = self.hour().unwrap_or(0) as i64 * 3600 + self.minute().unwrap_or(0) as i64 * 60 + self.second().unwrap_or(0) as i64;
+        let offset = (self.time_zone_offset().unwrap_or(0.0) * 3600.0).round() as i64;
+        Some(days * 86400 + secs - offset)
+    }
+
+    pub unsafe fn gst_date_time(&self) -> *const GstDateTime{
+        self.datetime
+    }
+}
+
+impl Clone for DateTime{
+    fn clone(&self) -> DateTime{
+        unsafe{
+            DateTime{ datetime: gst_date_time_ref(self.datetime) }
+        }
+    }
+}
+
+impl Drop for DateTime{
+    fn drop(&mut self){
+        unsafe{
+            gst_date_time_unref(self.datetime);
+        }
+    }
+}
+
+impl PartialEq for DateTime{
+    fn eq(&self, other: &DateTime) -> bool{
+        self.to_iso8601() == other.to_iso8601()
+    }
+}
+
+impl Display for DateTime{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "{}", self.to_iso8601().unwrap_or_default())
+    }
+}
+
+impl Debug for DateTime{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "DateTime({})", self.to_iso8601().unwrap_or_default())
+    }
+}
+
+impl ToValue for DateTime{
+    fn to_value(&self) -> Value{
+        unsafe{
+            let mut value = Value::new(gst_date_time_get_type());
+            g_value_set_boxed(value.gst_value_mut(), self.datetime as gconstpointer);
+            value
+        }
+    }
+}
+
+impl FromValue for DateTime{
+    fn from_value(value: &Value) -> Option<DateTime>{
+        unsafe{
+            if value.holds(gst_date_time_get_type()){
+                DateTime::new_from_gst_date_time(g_value_dup_boxed(value.gst_value()) as *mut GstDateTime)
+            }else{
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,505 @@
+use ffi::*;
+use util::*;
+use object::Object;
+use pad::Pad;
+use caps::Caps;
+use panic::catch_panic;
+use version::ApiAvailability;
+
+use std::os::raw::c_char;
+use std::borrow::Cow;
+
+// before 1.2 caps have no features, there's nothing to compare
+const CAPS_FEATURES: ApiAvailability = ApiAvailability::new("caps features", 1, 2, 0);
+
+/// Severity of a debug message, mirrors GstDebugLevel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(isize)]
+pub enum DebugLevel{
+    None = GST_LEVEL_NONE as isize,
+    Error = GST_LEVEL_ERROR as isize,
+    Warning = GST_LEVEL_WARNING as isize,
+    Fixme = GST_LEVEL_FIXME as isize,
+    Info = GST_LEVEL_INFO as isize,
+    Debug = GST_LEVEL_DEBUG as isize,
+    Log = GST_LEVEL_LOG as isize,
+    Trace = GST_LEVEL_TRACE as isize,
+    Memdump = GST_LEVEL_MEMDUMP as isize,
+}
+
+impl DebugLevel{
+    pub fn from_gst_level(level: GstDebugLevel) -> DebugLevel{
+        match level{
+            GST_LEVEL_NONE => DebugLevel::None,
+            GST_LEVEL_ERROR => DebugLevel::Error,
+            GST_LEVEL_WARNING => DebugLevel::Warning,
+            GST_LEVEL_FIXME => DebugLevel::Fixme,
+            GST_LEVEL_INFO => DebugLevel::Info,
+            GST_LEVEL_DEBUG => DebugLevel::Debug,
+            GST_LEVEL_LOG => DebugLevel::Log,
+            GST_LEVEL_TRACE => DebugLevel::Trace,
+            _ => DebugLevel::Memdump,
+        }
+    }
+
+    pub fn to_gst_level(self) -> GstDebugLevel{
+        self as GstDebugLevel
+    }
+
+    /// Name of the level as it appears in the GStreamer log, eg: "WARN"
+    pub fn name(self) -> &'static str{
+        unsafe{
+            let name = gst_debug_level_get_name(self.to_gst_level());
+            if name != ptr::null(){
+                from_c_str!(name)
+            }else{
+                ""
+            }
+        }
+    }
+}
+
+/// A category in the GStreamer debug system, rust code can log to it
+/// and its output is filtered with GST_DEBUG like any C element's:
+///
+/// ```ignore
+/// let cat = gst::DebugCategory::new("rsinvert", 0, "Rust video inverter");
+/// gst_debug!(cat, "processing buffer of {} bytes", size);
+/// gst_warning!(cat, obj: element, "dropping frame {}", n);
+/// ```
+///
+/// Categories are never freed by GStreamer so they can be copied around
+/// and shared between threads freely.
+#[derive(Clone, Copy)]
+pub struct DebugCategory{
+    category: *mut GstDebugCategory
+}
+
+// categories live until the process exits and their threshold is only
+// read or written as a single int
+unsafe impl Send for DebugCategory {}
+unsafe impl Sync for DebugCategory {}
+
+impl DebugCategory{
+    /// Registers a new category or returns the existing one with the same
+    /// name. color is a combination of the GST_DEBUG_FG_* / BG_* flags or
+    /// 0 for the default color
+    pub fn new(name: &str, color: u32, description: &str) -> DebugCategory{
+        let cname = CString::new(name).unwrap();
+        let cdescription = CString::new(description).unwrap();
+        unsafe{
+            DebugCategory{
+                category: _gst_debug_category_new(cname.as_ptr(), color, cdescription.as_ptr())
+            }
+        }
+    }
+
+    /// Returns an already registered category, like "GST_PADS" or the one
+    /// of some element
+    pub fn get(name: &str) -> Option<DebugCategory>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let category = _gst_debug_get_category(cname.as_ptr());
+            if category != ptr::null_mut(){
+                Some(DebugCategory{ category: category })
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn name(&self) -> &str{
+        unsafe{
+            from_c_str!(gst_debug_category_get_name(self.category))
+        }
+    }
+
+    pub fn description(&self) -> Option<&str>{
+        unsafe{
+            let description = gst_debug_category_get_description(self.category);
+            if description != ptr::null(){
+                Some(from_c_str!(description))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn threshold(&self) -> DebugLevel{
+        unsafe{
+            DebugLevel::from_gst_level(gst_debug_category_get_threshold(self.category))
+        }
+    }
+
+    /// Overrides the threshold set through GST_DEBUG for this category
+    pub fn set_threshold(&self, level: DebugLevel){
+        unsafe{
+            gst_debug_category_set_threshold(self.category, level.to_gst_level());
+        }
+    }
+
+    /// Goes back to the threshold set through GST_DEBUG
+    pub fn reset_threshold(&self){
+        unsafe{
+            gst_debug_category_reset_threshold(self.category);
+        }
+    }
+
+    /// True if a message at level would be output, used by the logging
+    /// macros to avoid formatting messages that are going to be discarded
+    pub fn is_enabled(&self, level: DebugLevel) -> bool{
+        unsafe{
+            let level = level.to_gst_level();
+            level <= _gst_debug_min && level as gint <= (*self.category).threshold
+        }
+    }
+
+    /// Logs msg at level in this category. Usually called through the
+    /// gst_error!, gst_warning!... macros which fill in the location
+    pub fn log(&self, level: DebugLevel, object: Option<&Object>, file: &str, function: &str, line: u32, msg: &str){
+        if !self.is_enabled(level){
+            return;
+        }
+        let cfile = CString::new(file).unwrap();
+        let cfunction = CString::new(function).unwrap();
+        let cmsg = CString::new(msg.replace('\0', "")).unwrap();
+        let format = CString::new("%s").unwrap();
+        unsafe{
+            let object = object.map(|obj| obj.gst_object() as *mut GObject).unwrap_or(ptr::null_mut());
+            gst_debug_log(self.category, level.to_gst_level(), cfile.as_ptr(), cfunction.as_ptr(),
+                line as gint, object, format.as_ptr(), cmsg.as_ptr());
+        }
+    }
+
+    pub unsafe fn gst_debug_category(&self) -> *mut GstDebugCategory{
+        self.category
+    }
+}
+
+/// A message from the GStreamer debug system as received by a function
+/// added with debug_add_log_function
+pub struct DebugRecord<'a>{
+    pub category: DebugCategory,
+    pub level: DebugLevel,
+    pub file: &'a str,
+    pub function: &'a str,
+    pub line: u32,
+    /// Name of the object the message was logged for if any
+    pub object: Option<String>,
+    pub message: &'a str,
+}
+
+type LogFunction = Box<dyn Fn(&DebugRecord) + Send + Sync>;
+
+/// Identifies a function added with debug_add_log_function
+pub struct LogFunctionId(usize);
+
+/// Calls f for every message logged through the GStreamer debug system,
+/// both from C elements and from rust through DebugCategory, so they can
+/// be routed to the log crate or any other logging framework:
+///
+/// ```ignore
+/// gst::debug_remove_default_log_function();
+/// gst::debug_add_log_function(|record| {
+///     let level = match record.level{
+///         gst::DebugLevel::Error => log::LogLevel::Error,
+///         gst::DebugLevel::Warning => log::LogLevel::Warn,
+///         gst::DebugLevel::Fixme | gst::DebugLevel::Info => log::LogLevel::Info,
+///         gst::DebugLevel::Debug => log::LogLevel::Debug,
+///         _ => log::LogLevel::Trace,
+///     };
+///     log!(target: record.category.name(), level, "{}:{} {}", record.file, record.line, record.message);
+/// });
+/// ```
+///
+/// Only messages that pass the GST_DEBUG thresholds reach the function.
+/// It's called from whichever thread logged the message.
+pub fn debug_add_log_function<F: Fn(&DebugRecord) + Send + Sync + 'static>(f: F) -> LogFunctionId{
+    unsafe{
+        let f: LogFunction = Box::new(f);
+        let f: *mut LogFunction = Box::into_raw(Box::new(f));
+        gst_debug_add_log_function(Some(log_function), f as gpointer, Some(free_log_function));
+        LogFunctionId(f as usize)
+    }
+}
+
+/// Removes a function added with debug_add_log_function
+pub fn debug_remove_log_function(id: LogFunctionId) -> bool{
+    unsafe{
+        gst_debug_remove_log_function_by_data(id.0 as gpointer) > 0
+    }
+}
+
+/// Stops GStreamer from printing to stderr, usually called after
+/// adding a log function so messages aren't output twice
+pub fn debug_remove_default_log_function() -> bool{
+    unsafe{
+        // None stands for the default log function
+        gst_debug_remove_log_function(None) > 0
+    }
+}
+
+/// Sets the threshold of all the categories that don't have a specific
+/// one, same as GST_DEBUG=level
+pub fn debug_set_default_threshold(level: DebugLevel){
+    unsafe{
+        gst_debug_set_default_threshold(level.to_gst_level());
+    }
+}
+
+pub fn debug_default_threshold() -> DebugLevel{
+    unsafe{
+        DebugLevel::from_gst_level(gst_debug_get_default_threshold())
+    }
+}
+
+/// Sets thresholds from a string with the same syntax as GST_DEBUG,
+/// eg: "rsinvert:6,GST_PADS:4". If reset is true the thresholds not in
+/// the list go back to the default
+pub fn debug_set_threshold_from_string(list: &str, reset: bool){
+    let clist = CString::new(list).unwrap();
+    unsafe{
+        gst_debug_set_threshold_from_string(clist.as_ptr(), reset as gboolean);
+    }
+}
+
+/// Explains in a human readable way why src can't be linked to sink
+/// or why they failed to negotiate, eg: after a not-negotiated error or
+/// Pad::link returning LinkReturn::NoFormat:
+///
+/// ```ignore
+/// if let Err(_) = src.link(&mut sink){
+///     println!("{}", gst::explain_link_failure(&src, &sink));
+/// }
+/// ```
+///
+/// Lists the caps each pad can handle and, for the media types both
+/// support, the fields whose values don't overlap, eg: a format or
+/// a framerate, or the caps features that differ, eg: memory:DMABuf
+pub fn explain_link_failure(src: &Pad, sink: &Pad) -> String{
+    let src_name = pad_name(src);
+    let sink_name = pad_name(sink);
+    let src_caps = match src.query_caps(None){
+        Some(caps) => caps,
+        None => return format!("{} didn't return its caps", src_name),
+    };
+    let sink_caps = match sink.query_caps(None){
+        Some(caps) => caps,
+        None => return format!("{} didn't return its caps", sink_name),
+    };
+    let mut explanation = format!("{} produces {}\n{} accepts {}\n", src_name, src_caps.to_string(),
+        sink_name, sink_caps.to_string());
+    if src_caps.is_empty(){
+        explanation += &format!("{} can't produce any caps, eg: its upstream was already negotiated to caps it can't convert\n", src_name);
+        return explanation;
+    }
+    if sink_caps.is_empty(){
+        explanation += &format!("{} can't accept any caps, eg: its downstream was already negotiated to caps it can't convert\n", sink_name);
+        return explanation;
+    }
+    let intersection = unsafe{
+        Caps::new(gst_caps_intersect(src_caps.gst_caps() as *mut GstCaps, sink_caps.gst_caps() as *mut GstCaps))
+    };
+    if let Some(intersection) = intersection.as_ref().and_then(|caps| if caps.is_empty() { None } else { Some(caps) }){
+        explanation += &format!("the caps intersect in {}, linking should work. ", intersection.to_string());
+        explanation += "If negotiation failed, the caps upstream elements send or the ones fixated downstream are outside of the intersection\n";
+        if let Some(current) = src.current_caps(){
+            explanation += &format!("{} is currently negotiated to {}\n", src_name, current.to_string());
+        }
+        return explanation;
+    }
+    explanation += "the caps don't intersect:\n";
+    let get_features = unsafe{
+        CAPS_FEATURES.function::<unsafe extern "C" fn(*const GstCaps, guint) -> *mut GstCapsFeatures>("gst_caps_get_features")
+    };
+    let features_equal = unsafe{
+        CAPS_FEATURES.function::<unsafe extern "C" fn(*const GstCapsFeatures, *const GstCapsFeatures) -> gboolean>("gst_caps_features_is_equal")
+    };
+    for i in 0..src_caps.len(){
+        unsafe{
+            let src_structure = gst_caps_get_structure(src_caps.gst_caps(), i);
+            let media_type = from_c_str!(gst_structure_get_name(src_structure));
+            let mut same_media_type = false;
+            for j in 0..sink_caps.len(){
+                let sink_structure = gst_caps_get_structure(sink_caps.gst_caps(), j);
+                if gst_structure_has_name(sink_structure, gst_structure_get_name(src_structure)) == 0{
+                    continue;
+                }
+                same_media_type = true;
+                if let (&Ok(get_features), &Ok(features_equal)) = (&get_features, &features_equal){
+                    let src_features = get_features(src_caps.gst_caps(), i);
+                    let sink_features = get_features(sink_caps.gst_caps(), j);
+                    if features_equal(src_features, sink_features) == 0{
+                        explanation += &format!("  {}: {} has features {} but {} needs {}\n", media_type,
+                            src_name, features_string(src_features), sink_name, features_string(sink_features));
+                        continue;
+                    }
+                }
+                for field in 0..gst_structure_n_fields(src_structure) as guint{
+                    let field_name = gst_structure_nth_field_name(src_structure, field);
+                    let src_value = gst_structure_get_value(src_structure, field_name);
+                    let sink_value = gst_structure_get_value(sink_structure, field_name);
+                    // a field missing on one side doesn't restrict it
+                    if sink_value == ptr::null() || gst_value_can_intersect(src_value, sink_value) != 0{
+                        continue;
+                    }
+                    explanation += &format!("  {}: {} offers {}={} but {} accepts {}\n", media_type,
+                        src_name, from_c_str!(field_name), value_string(src_value), sink_name, value_string(sink_value));
+                }
+            }
+            if !same_media_type{
+                explanation += &format!("  {}: not accepted by {}\n", media_type, sink_name);
+            }
+        }
+    }
+    explanation
+}
+
+fn pad_name(pad: &Pad) -> String{
+    unsafe{
+        let parent = gst_pad_get_parent_element(pad.gst_pad() as *mut GstPad);
+        if parent == ptr::null_mut(){
+            return pad.name();
+        }
+        let parent_name = gst_object_get_name(parent as *mut GstObject);
+        let ret = format!("{}:{}", opt_c_str(parent_name), pad.name());
+        g_free(parent_name as gpointer);
+        gst_object_unref(parent as gpointer);
+        ret
+    }
+}
+
+unsafe fn features_string(features: *const GstCapsFeatures) -> String{
+    if features == ptr::null(){
+        return "memory:SystemMemory".to_string();
+    }
+    // only called with features, so the function is available
+    let to_string: unsafe extern "C" fn(*const GstCapsFeatures) -> *mut gchar =
+        CAPS_FEATURES.function("gst_caps_features_to_string").unwrap();
+    let string = to_string(features);
+    let ret = opt_c_str(string).to_string();
+    g_free(string as gpointer);
+    ret
+}
+
+unsafe fn value_string(value: *const GValue) -> String{
+    let string = gst_value_serialize(value);
+    let ret = opt_c_str(string).to_string();
+    g_free(string as gpointer);
+    ret
+}
+
+unsafe fn opt_c_str<'a>(s: *const c_char) -> &'a str{
+    if s != ptr::null(){
+        from_c_str!(s)
+    }else{
+        ""
+    }
+}
+
+extern "C" fn log_function(category: *mut GstDebugCategory, level: GstDebugLevel, file: *const gchar,
+        function: *const gchar, line: gint, object: *mut GObject, message: *mut GstDebugMessage,
+        data: gpointer){
+    unsafe{
+        let f: &LogFunction = &*(data as *const LogFunction);
+        catch_panic(ptr::null_mut(), (), || {
+            let object = if object != ptr::null_mut() && g_type_check_instance_is_a(object as *mut GTypeInstance, gst_object_get_type()) != 0{
+                // gst_object_get_name takes the object lock, which the code
+                // logging might be holding, read it directly like the default
+                // log function does
+                let name = (*(object as *mut GstObject)).name;
+                if name != ptr::null_mut(){
+                    Some(lossy_c_str(name).into_owned())
+                }else{
+                    None
+                }
+            }else{
+                None
+            };
+            // neither the file paths nor the messages are guaranteed to
+            // be valid UTF-8
+            let file = lossy_c_str(file);
+            let function = lossy_c_str(function);
+            let message = lossy_c_str(gst_debug_message_get(message));
+            let record = DebugRecord{
+                category: DebugCategory{ category: category },
+                level: DebugLevel::from_gst_level(level),
+                file: &file,
+                function: &function,
+                line: line as u32,
+                object: object,
+                message: &message,
+            };
+            f(&record)
+        });
+    }
+}
+
+unsafe fn lossy_c_str<'a>(s: *const c_char) -> Cow<'a, str>{
+    if s != ptr::null(){
+        CStr::from_ptr(s).to_string_lossy()
+    }else{
+        Cow::Borrowed("")
+    }
+}
+
+extern "C" fn free_log_function(data: gpointer){
+    unsafe{
+        let _ = Box::from_raw(data as *mut LogFunction);
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! gst_log_with_level(
+    ($cat:expr, $level:expr, obj: $obj:expr, $($args:tt)*) => ({
+        let cat: &$crate::DebugCategory = &$cat;
+        if cat.is_enabled($level){
+            let obj: &$crate::Object = &$obj;
+            cat.log($level, Some(obj), file!(), module_path!(), line!(), &format!($($args)*));
+        }
+    });
+    ($cat:expr, $level:expr, $($args:tt)*) => ({
+        let cat: &$crate::DebugCategory = &$cat;
+        if cat.is_enabled($level){
+            cat.log($level, None, file!(), module_path!(), line!(), &format!($($args)*));
+        }
+    });
+);
+
+/// Logs an error to a DebugCategory: gst_error!(cat, "{}", x) or
+/// gst_error!(cat, obj: element, "{}", x)
+#[macro_export]
+macro_rules! gst_error(
+    ($cat:expr, $($args:tt)*) => (gst_log_with_level!($cat, $crate::DebugLevel::Error, $($args)*));
+);
+
+#[macro_export]
+macro_rules! gst_warning(
+    ($cat:expr, $($args:tt)*) => (gst_log_with_level!($cat, $crate::DebugLevel::Warning, $($args)*));
+);
+
+#[macro_export]
+macro_rules! gst_fixme(
+    ($cat:expr, $($args:tt)*) => (gst_log_with_level!($cat, $crate::DebugLevel::Fixme, $($args)*));
+);
+
+#[macro_export]
+macro_rules! gst_info(
+    ($cat:expr, $($args:tt)*) => (gst_log_with_level!($cat, $crate::DebugLevel::Info, $($args)*));
+);
+
+#[macro_export]
+macro_rules! gst_debug(
+    ($cat:expr, $($args:tt)*) => (gst_log_with_level!($cat, $crate::DebugLevel::Debug, $($args)*));
+);
+
+#[macro_export]
+macro_rules! gst_log(
+    ($cat:expr, $($args:tt)*) => (gst_log_with_level!($cat, $crate::DebugLevel::Log, $($args)*));
+);
+
+#[macro_export]
+macro_rules! gst_trace(
+    ($cat:expr, $($args:tt)*) => (gst_log_with_level!($cat, $crate::DebugLevel::Trace, $($args)*));
+);
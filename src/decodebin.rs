@@ -0,0 +1,180 @@
+use ffi::*;
+
+use bin::Bin;
+use element::Element;
+use element_factory::ElementFactory;
+use pad::Pad;
+use caps::Caps;
+use handle::SignalHandle;
+use value::{Value, ToValue};
+use ::Transfer;
+use reference::Reference;
+
+use std::ops::{Deref, DerefMut};
+
+/// What decodebin should do with the factory proposed in autoplug-select
+#[repr(i32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum AutoplugSelectResult{
+    /// Try to plug an element created by the factory
+    Try = 0,
+    /// Don't plug anything and expose the pad with its current caps
+    Expose = 1,
+    /// Skip the factory and try the next one
+    Skip = 2,
+}
+
+/// Wrapper for decodebin and uridecodebin, which autoplug the demuxers,
+/// parsers and decoders needed to decode a stream.
+///
+/// The autoplug signals allow to change which elements are plugged for
+/// each stream, eg: to force a software decoder:
+///
+/// ```ignore
+/// let mut decodebin = gst::DecodeBin::new_uri("decoder").unwrap();
+/// decodebin.set_uri("file:///home/user/video.mp4");
+/// decodebin.connect_autoplug_select(|_pad, _caps, factory| {
+///     if factory.klass().contains("Hardware"){
+///         gst::AutoplugSelectResult::Skip
+///     }else{
+///         gst::AutoplugSelectResult::Try
+///     }
+/// }).unwrap().forget();
+/// ```
+///
+/// The handlers are called from the streaming threads.
+pub struct DecodeBin{
+    decodebin: Bin
+}
+
+impl DecodeBin{
+    /// Creates a decodebin, which decodes the data coming to its sink pad
+    pub fn new(name: &str) -> Option<DecodeBin>{
+        DecodeBin::new_from_factory("decodebin", name)
+    }
+
+    /// Creates a uridecodebin, which decodes the data read from its uri
+    pub fn new_uri(name: &str) -> Option<DecodeBin>{
+        DecodeBin::new_from_factory("uridecodebin", name)
+    }
+
+    fn new_from_factory(factory: &str, name: &str) -> Option<DecodeBin>{
+        Element::new(factory, name).and_then(|element| unsafe{
+            Bin::new_from_gst_bin(element.transfer() as *mut GstBin)
+        }).map(|bin| DecodeBin{ decodebin: bin })
+    }
+
+    pub fn new_from_bin(bin: Bin) -> DecodeBin{
+        DecodeBin{ decodebin: bin }
+    }
+
+    /// Uri to decode, only for uridecodebin
+    pub fn set_uri(&mut self, uri: &str){
+        self.set("uri", uri);
+    }
+
+    /// Caps at which decodebin stops plugging elements and exposes the
+    /// pad, by default raw audio, video and text
+    pub fn set_caps(&mut self, caps: &Caps){
+        self.set("caps", caps);
+    }
+
+    /// Called for every new pad with its caps, return false to stop
+    /// plugging elements and expose the pad as is
+    pub fn connect_autoplug_continue<F>(&mut self, mut handler: F) -> Option<SignalHandle>
+        where F: FnMut(&Pad, &Caps) -> bool + Send + 'static{
+        self.connect_values("autoplug-continue", move |args| {
+            match (args[1].get::<Pad>(), args[2].get::<Caps>()){
+                (Some(pad), Some(caps)) => Some(handler(&pad, &caps).to_value()),
+                _ => None
+            }
+        })
+    }
+
+    /// Called for every new pad to get the list of factories that can
+    /// handle its caps, returning None keeps the default list, sorted by
+    /// rank. The returned factories are tried in order
+    pub fn connect_autoplug_factories<F>(&mut self, mut handler: F) -> Option<SignalHandle>
+        where F: FnMut(&Pad, &Caps) -> Option<Vec<ElementFactory>> + Send + 'static{
+        self.connect_values("autoplug-factories", move |args| {
+            match (args[1].get::<Pad>(), args[2].get::<Caps>()){
+                (Some(pad), Some(caps)) => handler(&pad, &caps).map(|factories| value_array(&factories)),
+                _ => None
+            }
+        })
+    }
+
+    /// Called for every factory that can handle the caps of a new pad
+    /// before trying it
+    pub fn connect_autoplug_select<F>(&mut self, mut handler: F) -> Option<SignalHandle>
+        where F: FnMut(&Pad, &Caps, &ElementFactory) -> AutoplugSelectResult + Send + 'static{
+        self.connect_values("autoplug-select", move |args| {
+            match (args[1].get::<Pad>(), args[2].get::<Caps>(), args[3].get::<ElementFactory>()){
+                (Some(pad), Some(caps), Some(factory)) => Some((handler(&pad, &caps, &factory) as i32).to_value()),
+                _ => None
+            }
+        })
+    }
+}
+
+/// GValueArray holding the factories as returned by autoplug-factories
+fn value_array(factories: &[ElementFactory]) -> Value{
+    unsafe{
+        let array = g_value_array_new(factories.len() as guint);
+        for factory in factories{
+            g_value_array_append(array, factory.to_value().gst_value());
+        }
+        let mut value = Value::new(g_value_array_get_type());
+        g_value_take_boxed(value.gst_value_mut(), array as gconstpointer);
+        value
+    }
+}
+
+impl ::Transfer for DecodeBin{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.decodebin.transfer()
+    }
+}
+
+impl Reference for DecodeBin{
+    fn reference(&self) -> DecodeBin{
+        DecodeBin{ decodebin: self.decodebin.reference() }
+    }
+}
+
+impl AsRef<Bin> for DecodeBin{
+    fn as_ref(&self) -> &Bin{
+        &self.decodebin
+    }
+}
+
+impl AsMut<Bin> for DecodeBin{
+    fn as_mut(&mut self) -> &mut Bin{
+        &mut self.decodebin
+    }
+}
+
+impl From<DecodeBin> for Bin{
+    fn from(d: DecodeBin) -> Bin{
+        d.decodebin
+    }
+}
+
+impl From<DecodeBin> for Element{
+    fn from(d: DecodeBin) -> Element{
+        d.decodebin.into()
+    }
+}
+
+impl Deref for DecodeBin{
+    type Target = Bin;
+    fn deref(&self) -> &Bin{
+        &self.decodebin
+    }
+}
+
+impl DerefMut for DecodeBin{
+    fn deref_mut(&mut self) -> &mut Bin{
+        &mut self.decodebin
+    }
+}
@@ -0,0 +1,271 @@
+use ffi::*;
+use util::*;
+use bin::Bin;
+use caps::Caps;
+use element::Element;
+use pipeline::Pipeline;
+use value::Value;
+
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt::{self, Display, Debug, Formatter};
+
+/// A pipeline described as data instead of a gst-launch string, to
+/// build pipelines from configuration files. With the serde feature the
+/// description can be deserialized from any format supported by serde,
+/// eg: TOML:
+///
+/// ```toml
+/// [[elements]]
+/// name = "src"
+/// factory = "v4l2src"
+/// properties = { device = "/dev/video1" }
+///
+/// [[elements]]
+/// name = "enc"
+/// factory = "x264enc"
+/// properties = { tune = "zerolatency", bitrate = "2000" }
+///
+/// [[elements]]
+/// name = "sink"
+/// factory = "udpsink"
+/// properties = { host = "10.0.0.2", port = "5000" }
+///
+/// [[links]]
+/// src = "src"
+/// sink = "enc"
+/// caps = "video/x-raw,width=1280,height=720"
+///
+/// [[links]]
+/// src = "enc"
+/// sink = "sink"
+/// ```
+///
+/// ```ignore
+/// let description: gst::description::PipelineDescription = toml::from_str(&config)?;
+/// let pipeline = description.build()?;
+/// ```
+///
+/// Everything is validated while building, errors say which entry of
+/// the description failed.
+#[derive(Clone,Debug,Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct PipelineDescription{
+    /// Name of the pipeline or bin, an automatic one if None
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name: Option<String>,
+    pub elements: Vec<ElementDescription>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub links: Vec<LinkDescription>,
+    /// Pads of the elements exposed on the bin, only when building a bin
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ghost_pads: Vec<GhostPadDescription>,
+}
+
+#[derive(Clone,Debug,Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct ElementDescription{
+    /// Name of the element, used in the links and ghost pads
+    pub name: String,
+    /// Name of the factory, eg: "x264enc"
+    pub factory: String,
+    /// Values of the properties as in gst-launch, eg: "zerolatency" for
+    /// an enum or "video/x-raw,width=640" for caps
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub properties: BTreeMap<String, String>,
+}
+
+/// A link between two elements, src and sink are the name of an
+/// element, to link any compatible pads, or "element.pad", where pad
+/// can also be the template of request pads, eg: "tee.src_%u"
+#[derive(Clone,Debug,Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct LinkDescription{
+    pub src: String,
+    pub sink: String,
+    /// Caps to restrict the format of the link
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub caps: Option<String>,
+}
+
+/// A pad of an element exposed on the bin with name, target is
+/// "element.pad"
+#[derive(Clone,Debug,Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct GhostPadDescription{
+    pub name: String,
+    pub target: String,
+}
+
+/// The entry of a description that caused an error, with its position
+/// in its list
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum Entry{
+    Pipeline,
+    Element(usize),
+    Link(usize),
+    GhostPad(usize),
+}
+
+impl Display for Entry{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        match *self{
+            Entry::Pipeline => write!(fmt, "pipeline"),
+            Entry::Element(i) => write!(fmt, "elements[{}]", i),
+            Entry::Link(i) => write!(fmt, "links[{}]", i),
+            Entry::GhostPad(i) => write!(fmt, "ghost_pads[{}]", i),
+        }
+    }
+}
+
+pub struct DescriptionError{
+    pub entry: Entry,
+    pub message: String,
+}
+
+impl DescriptionError{
+    fn new(entry: Entry, message: String) -> DescriptionError{
+        DescriptionError{ entry: entry, message: message }
+    }
+}
+
+impl Display for DescriptionError{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "{}: {}", self.entry, self.message)
+    }
+}
+
+impl Debug for DescriptionError{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        Display::fmt(self, fmt)
+    }
+}
+
+impl error::Error for DescriptionError{}
+
+pub type Result<T> = ::std::result::Result<T, DescriptionError>;
+
+impl PipelineDescription{
+    /// Builds a pipeline with the elements and links of the description
+    pub fn build(&self) -> Result<Pipeline>{
+        if !self.ghost_pads.is_empty(){
+            return Err(DescriptionError::new(Entry::GhostPad(0), "ghost pads can only be added to bins".to_string()));
+        }
+        let mut pipeline = Pipeline::new(self.name.as_ref().map(|name| &name[..]).unwrap_or("pipeline"))
+            .ok_or(DescriptionError::new(Entry::Pipeline, "couldn't create pipeline".to_string()))?;
+        self.build_into(&mut pipeline)?;
+        Ok(pipeline)
+    }
+
+    /// Builds a bin with the elements and links of the description and
+    /// its ghost pads, to be added to another pipeline
+    pub fn build_bin(&self) -> Result<Bin>{
+        let mut bin = Bin::new(self.name.as_ref().map(|name| &name[..]).unwrap_or(""))
+            .ok_or(DescriptionError::new(Entry::Pipeline, "couldn't create bin".to_string()))?;
+        self.build_into(&mut bin)?;
+        for (i, ghost) in self.ghost_pads.iter().enumerate(){
+            add_ghost_pad(&mut bin, ghost).map_err(|message| DescriptionError::new(Entry::GhostPad(i), message))?;
+        }
+        Ok(bin)
+    }
+
+    fn build_into(&self, bin: &mut Bin) -> Result<()>{
+        for (i, description) in self.elements.iter().enumerate(){
+            if self.elements[..i].iter().any(|other| other.name == description.name){
+                return Err(DescriptionError::new(Entry::Element(i), format!("duplicated element name {}", description.name)));
+            }
+            let element = create_element(description).map_err(|message| DescriptionError::new(Entry::Element(i), message))?;
+            if !bin.add(element){
+                return Err(DescriptionError::new(Entry::Element(i), format!("couldn't add {} to the pipeline", description.name)));
+            }
+        }
+        for (i, link) in self.links.iter().enumerate(){
+            link_elements(bin, link).map_err(|message| DescriptionError::new(Entry::Link(i), message))?;
+        }
+        Ok(())
+    }
+}
+
+fn create_element(description: &ElementDescription) -> ::std::result::Result<Element, String>{
+    if description.name.is_empty(){
+        return Err("elements need a name".to_string());
+    }
+    let mut element = Element::new(&description.factory, &description.name)
+        .ok_or(format!("no element {}, is the plugin installed?", description.factory))?;
+    for (property, value) in description.properties.iter(){
+        let cproperty = CString::new(&property[..]).map_err(|_| format!("invalid property name {}", property))?;
+        let cvalue = CString::new(&value[..]).map_err(|_| format!("invalid value for {}", property))?;
+        unsafe{
+            let class = (*(element.gst_element() as *mut GTypeInstance)).g_class as *mut GObjectClass;
+            let pspec = g_object_class_find_property(class, cproperty.as_ptr());
+            if pspec == ptr::null_mut(){
+                return Err(format!("{} has no property {}", description.factory, property));
+            }
+            if (*pspec).flags & G_PARAM_WRITABLE == 0{
+                return Err(format!("property {} of {} is read only", property, description.factory));
+            }
+            let mut gvalue = Value::new((*pspec).value_type);
+            if gst_value_deserialize(gvalue.gst_value_mut(), cvalue.as_ptr()) == 0{
+                return Err(format!("invalid value {} for property {}", value, property));
+            }
+            if !element.set_value(property, &gvalue){
+                return Err(format!("couldn't set property {} to {}", property, value));
+            }
+        }
+    }
+    Ok(element)
+}
+
+// splits "element.pad" into the element and the pad if there's one
+fn split_pad(endpoint: &str) -> (&str, Option<&str>){
+    match endpoint.find('.'){
+        Some(dot) => (&endpoint[..dot], Some(&endpoint[dot + 1..])),
+        None => (endpoint, None),
+    }
+}
+
+fn link_elements(bin: &mut Bin, link: &LinkDescription) -> ::std::result::Result<(), String>{
+    let (src_name, src_pad) = split_pad(&link.src);
+    let (sink_name, sink_pad) = split_pad(&link.sink);
+    let mut src = bin.get_by_name(src_name).ok_or(format!("no element {}", src_name))?;
+    let mut sink = bin.get_by_name(sink_name).ok_or(format!("no element {}", sink_name))?;
+    let mut caps = match link.caps{
+        Some(ref caps) => Some(Caps::from_string(caps).ok_or(format!("invalid caps {}", caps))?),
+        None => None,
+    };
+    let csrc_pad = src_pad.map(|pad| CString::new(pad).unwrap());
+    let csink_pad = sink_pad.map(|pad| CString::new(pad).unwrap());
+    let linked = unsafe{
+        gst_element_link_pads_filtered(src.gst_element_mut(),
+            csrc_pad.as_ref().map(|pad| pad.as_ptr()).unwrap_or(ptr::null()),
+            sink.gst_element_mut(),
+            csink_pad.as_ref().map(|pad| pad.as_ptr()).unwrap_or(ptr::null()),
+            caps.as_mut().map(|caps| caps.gst_caps_mut()).unwrap_or(ptr::null_mut())) != 0
+    };
+    if linked{
+        Ok(())
+    }else{
+        Err(format!("couldn't link {} to {}", link.src, link.sink))
+    }
+}
+
+fn add_ghost_pad(bin: &mut Bin, ghost: &GhostPadDescription) -> ::std::result::Result<(), String>{
+    let (element_name, pad_name) = split_pad(&ghost.target);
+    let pad_name = pad_name.ok_or(format!("target {} has no pad, use element.pad", ghost.target))?;
+    let mut element = bin.get_by_name(element_name).ok_or(format!("no element {}", element_name))?;
+    let mut target = element.static_pad(pad_name)
+        .or_else(|| element.request_pad(pad_name))
+        .ok_or(format!("{} has no pad {}", element_name, pad_name))?;
+    let cname = CString::new(&ghost.name[..]).map_err(|_| format!("invalid pad name {}", ghost.name))?;
+    unsafe{
+        let pad = gst_ghost_pad_new(cname.as_ptr(), target.gst_pad_mut());
+        if pad == ptr::null_mut(){
+            return Err(format!("couldn't create ghost pad {}", ghost.name));
+        }
+        gst_pad_set_active(pad, 1);
+        if gst_element_add_pad(bin.gst_element_mut(), pad) == 0{
+            return Err(format!("the bin already has a pad {}", ghost.name));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,335 @@
+use ffi::*;
+use util::*;
+use element::Element;
+use caps::Caps;
+use object::Object;
+use reference::Reference;
+use structure::Structure;
+use bus::Bus;
+use version::ApiAvailability;
+
+use std::os::raw::c_void;
+use std::ops::{Deref, DerefMut};
+
+pub const DEVICES: ApiAvailability = ApiAvailability::new("DeviceMonitor", 1, 4, 0);
+const DEVICE_PROPERTIES: ApiAvailability = ApiAvailability::new("Device::properties", 1, 6, 0);
+const MONITOR_PROVIDERS: ApiAvailability = ApiAvailability::new("DeviceMonitor::providers", 1, 6, 0);
+
+// the functions used once there's a device or a monitor, which can only
+// exist if they are available
+unsafe fn device_fn<F: Copy>(symbol: &str) -> F{
+    DEVICES.function(symbol).expect("DeviceMonitor without GStreamer 1.4")
+}
+
+/// A device found by a DeviceMonitor, like a camera, a microphone or a
+/// sound card output
+pub struct Device{
+    device: Object<GstDevice>
+}
+
+impl Device{
+    pub unsafe fn new_from_gst_device(device: *mut GstDevice) -> Option<Device>{
+        Object::new(device).map(|obj| Device{ device: obj })
+    }
+
+    /// Human readable name of the device
+    pub fn display_name(&self) -> String{
+        unsafe{
+            let get_display_name: unsafe extern "C" fn(*mut GstDevice) -> *mut gchar = device_fn("gst_device_get_display_name");
+            let name = get_display_name(self.gst_device() as *mut GstDevice);
+            let ret = from_c_str!(name).to_string();
+            g_free(name as gpointer);
+            ret
+        }
+    }
+
+    /// Class of the device, eg: "Audio/Sink" or "Video/Source"
+    pub fn device_class(&self) -> String{
+        unsafe{
+            let get_device_class: unsafe extern "C" fn(*mut GstDevice) -> *mut gchar = device_fn("gst_device_get_device_class");
+            let class = get_device_class(self.gst_device() as *mut GstDevice);
+            let ret = from_c_str!(class).to_string();
+            g_free(class as gpointer);
+            ret
+        }
+    }
+
+    /// Returns true if the device class contains all the classes
+    /// separated by "/" in classes
+    pub fn has_classes(&self, classes: &str) -> bool{
+        let cclasses = CString::new(classes).unwrap();
+        unsafe{
+            let has_classes: unsafe extern "C" fn(*mut GstDevice, *const gchar) -> gboolean = device_fn("gst_device_has_classes");
+            has_classes(self.gst_device() as *mut GstDevice, cclasses.as_ptr()) != 0
+        }
+    }
+
+    /// Formats supported by the device
+    pub fn caps(&self) -> Option<Caps>{
+        unsafe{
+            let get_caps: unsafe extern "C" fn(*mut GstDevice) -> *mut GstCaps = device_fn("gst_device_get_caps");
+            Caps::new(get_caps(self.gst_device() as *mut GstDevice))
+        }
+    }
+
+    /// Extra information about the device set by its provider, like
+    /// "device.path" for v4l2 or "device.api" for pulse. None with
+    /// GStreamer older than 1.6
+    pub fn properties(&self) -> Option<Structure>{
+        unsafe{
+            let get_properties: unsafe extern "C" fn(*mut GstDevice) -> *mut GstStructure =
+                DEVICE_PROPERTIES.function("gst_device_get_properties").ok()?;
+            Structure::new_owned(get_properties(self.gst_device() as *mut GstDevice))
+        }
+    }
+
+    /// Creates the source or sink element for this device, already
+    /// configured to use it
+    pub fn create_element(&self, name: &str) -> Option<Element>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let name = if name != "" { cname.as_ptr() } else { ptr::null() };
+            let create_element: unsafe extern "C" fn(*mut GstDevice, *const gchar) -> *mut GstElement = device_fn("gst_device_create_element");
+            let element = create_element(self.gst_device() as *mut GstDevice, name);
+            if element == ptr::null_mut(){
+                return None;
+            }
+            gst_object_ref_sink(element as gpointer);
+            Element::new_from_gst_element(element)
+        }
+    }
+
+    /// Configures an element previously created by create_element() to
+    /// use this device instead, returns false if the element can't be
+    /// reconfigured
+    pub fn reconfigure_element(&self, element: &mut Element) -> bool{
+        unsafe{
+            let reconfigure_element: unsafe extern "C" fn(*mut GstDevice, *mut GstElement) -> gboolean = device_fn("gst_device_reconfigure_element");
+            reconfigure_element(self.gst_device() as *mut GstDevice, element.gst_element_mut()) != 0
+        }
+    }
+
+    pub unsafe fn gst_device(&self) -> *const GstDevice{
+        self.device.as_ptr()
+    }
+
+    pub unsafe fn gst_device_mut(&mut self) -> *mut GstDevice{
+        self.device.as_mut_ptr()
+    }
+}
+
+impl Reference for Device{
+    fn reference(&self) -> Device{
+        Device{ device: self.device.reference() }
+    }
+}
+
+impl AsRef<Object> for Device{
+    fn as_ref(&self) -> &Object{
+        self.device.upcast_ref()
+    }
+}
+
+impl AsMut<Object> for Device{
+    fn as_mut(&mut self) -> &mut Object{
+        self.device.upcast_mut()
+    }
+}
+
+impl From<Device> for Object{
+    fn from(d: Device) -> Object{
+        d.device.upcast()
+    }
+}
+
+impl Deref for Device{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.device.upcast_ref()
+    }
+}
+
+impl DerefMut for Device{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.device.upcast_mut()
+    }
+}
+
+/// Lists the devices available in the system using the installed device
+/// providers.
+///
+/// ```ignore
+/// let mut monitor = gst::DeviceMonitor::new().unwrap();
+/// monitor.add_filter("Audio/Sink", None);
+/// for device in monitor.devices(){
+///     println!("{}", device.display_name());
+/// }
+/// ```
+pub struct DeviceMonitor{
+    monitor: Object<GstDeviceMonitor>
+}
+
+impl DeviceMonitor{
+    /// None with GStreamer older than 1.4
+    pub fn new() -> Option<DeviceMonitor>{
+        unsafe{
+            let monitor_new: unsafe extern "C" fn() -> *mut GstDeviceMonitor = DEVICES.function("gst_device_monitor_new").ok()?;
+            let monitor = monitor_new();
+            if monitor != ptr::null_mut(){
+                gst_object_ref_sink(monitor as *mut c_void);
+                Object::new(monitor).map(|obj| DeviceMonitor{ monitor: obj })
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Only list devices whose class matches classes, eg: "Audio/Source",
+    /// and, if caps is passed, that can produce or accept those caps.
+    ///
+    /// Returns an id that can be used to remove the filter or 0 if the
+    /// filter couldn't be added
+    pub fn add_filter(&mut self, classes: &str, caps: Option<&Caps>) -> u32{
+        let cclasses = CString::new(classes).unwrap();
+        unsafe{
+            let caps = caps.map(|caps| caps.gst_caps() as *mut GstCaps).unwrap_or(ptr::null_mut());
+            let add_filter: unsafe extern "C" fn(*mut GstDeviceMonitor, *const gchar, *mut GstCaps) -> guint = device_fn("gst_device_monitor_add_filter");
+            add_filter(self.gst_devicemonitor_mut(), cclasses.as_ptr(), caps)
+        }
+    }
+
+    pub fn remove_filter(&mut self, filter_id: u32) -> bool{
+        unsafe{
+            let remove_filter: unsafe extern "C" fn(*mut GstDeviceMonitor, guint) -> gboolean = device_fn("gst_device_monitor_remove_filter");
+            remove_filter(self.gst_devicemonitor_mut(), filter_id) != 0
+        }
+    }
+
+    /// Returns the devices currently available that match the filters,
+    /// the monitor doesn't need to be started
+    pub fn devices(&self) -> Vec<Device>{
+        let mut devices = vec![];
+        unsafe{
+            let get_devices: unsafe extern "C" fn(*mut GstDeviceMonitor) -> *mut GList = device_fn("gst_device_monitor_get_devices");
+            let list = get_devices(self.gst_devicemonitor() as *mut GstDeviceMonitor);
+            let mut node = list;
+            while node != ptr::null_mut(){
+                if let Some(device) = Device::new_from_gst_device((*node).data as *mut GstDevice){
+                    devices.push(device);
+                }
+                node = (*node).next;
+            }
+            g_list_free(list);
+        }
+        devices
+    }
+
+    /// Bus where Message::DeviceAdded and Message::DeviceRemoved are
+    /// posted while the monitor is started, use Message::device to get
+    /// the device:
+    ///
+    /// ```ignore
+    /// let mut monitor = gst::DeviceMonitor::new().unwrap();
+    /// monitor.add_filter("Video/Source", None);
+    /// let _watch = monitor.bus().unwrap().add_watch(|msg| {
+    ///     match msg{
+    ///         gst::Message::DeviceAdded(_) => println!("plugged {}", msg.device().unwrap().display_name()),
+    ///         gst::Message::DeviceRemoved(_) => println!("unplugged {}", msg.device().unwrap().display_name()),
+    ///         _ => (),
+    ///     }
+    ///     true
+    /// });
+    /// monitor.start();
+    /// ```
+    pub fn bus(&self) -> Option<Bus>{
+        unsafe{
+            let get_bus: unsafe extern "C" fn(*mut GstDeviceMonitor) -> *mut GstBus = device_fn("gst_device_monitor_get_bus");
+            Bus::new(get_bus(self.gst_devicemonitor() as *mut GstDeviceMonitor))
+        }
+    }
+
+    /// Names of the device providers the monitor uses for its filters,
+    /// eg: "pulsedeviceprovider" or "v4l2deviceprovider". Always empty
+    /// with GStreamer older than 1.6
+    pub fn providers(&self) -> Vec<String>{
+        let mut providers = vec![];
+        unsafe{
+            let get_providers: unsafe extern "C" fn(*mut GstDeviceMonitor) -> *mut *mut gchar =
+                match MONITOR_PROVIDERS.function("gst_device_monitor_get_providers"){
+                    Ok(get_providers) => get_providers,
+                    Err(_) => return providers,
+                };
+            let names = get_providers(self.gst_devicemonitor() as *mut GstDeviceMonitor);
+            if names != ptr::null_mut(){
+                let mut name = names;
+                while *name != ptr::null_mut(){
+                    providers.push(from_c_str!(*name).to_string());
+                    name = name.offset(1);
+                }
+                g_strfreev(names);
+            }
+        }
+        providers
+    }
+
+    /// Starts monitoring, needed to receive hotplug messages on the bus.
+    /// Returns false if no provider matching the filters could be started
+    pub fn start(&mut self) -> bool{
+        unsafe{
+            let start: unsafe extern "C" fn(*mut GstDeviceMonitor) -> gboolean = device_fn("gst_device_monitor_start");
+            start(self.gst_devicemonitor_mut()) != 0
+        }
+    }
+
+    pub fn stop(&mut self){
+        unsafe{
+            let stop: unsafe extern "C" fn(*mut GstDeviceMonitor) = device_fn("gst_device_monitor_stop");
+            stop(self.gst_devicemonitor_mut())
+        }
+    }
+
+    pub unsafe fn gst_devicemonitor(&self) -> *const GstDeviceMonitor{
+        self.monitor.as_ptr()
+    }
+
+    pub unsafe fn gst_devicemonitor_mut(&mut self) -> *mut GstDeviceMonitor{
+        self.monitor.as_mut_ptr()
+    }
+}
+
+impl Reference for DeviceMonitor{
+    fn reference(&self) -> DeviceMonitor{
+        DeviceMonitor{ monitor: self.monitor.reference() }
+    }
+}
+
+impl AsRef<Object> for DeviceMonitor{
+    fn as_ref(&self) -> &Object{
+        self.monitor.upcast_ref()
+    }
+}
+
+impl AsMut<Object> for DeviceMonitor{
+    fn as_mut(&mut self) -> &mut Object{
+        self.monitor.upcast_mut()
+    }
+}
+
+impl From<DeviceMonitor> for Object{
+    fn from(m: DeviceMonitor) -> Object{
+        m.monitor.upcast()
+    }
+}
+
+impl Deref for DeviceMonitor{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.monitor.upcast_ref()
+    }
+}
+
+impl DerefMut for DeviceMonitor{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.monitor.upcast_mut()
+    }
+}
@@ -0,0 +1,367 @@
+use ffi::*;
+use util::*;
+use element::Element;
+use caps::Caps;
+use object::Object;
+use structure::Structure;
+use device_monitor::{Device, DEVICES};
+use registry::Plugin;
+use subclass::ElementMetadata;
+use reference::Reference;
+use panic::catch_panic;
+use registration::{type_name, register_type, set_imp, with_imp, finalize, factory_name, Instance, DEVICE_PROVIDER_PREFIX};
+use ::Transfer;
+
+use std::os::raw::c_void;
+use std::ops::{Deref, DerefMut};
+
+// the functions used once there's a provider or its types are
+// registered, which can only happen if they are available
+unsafe fn provider_fn<F: Copy>(symbol: &str) -> F{
+    DEVICES.function(symbol).expect("DeviceProvider without GStreamer 1.4")
+}
+
+/// A device found by a DeviceProviderImpl, returned from probe or added
+/// with DeviceProvider::device_add
+pub struct DeviceDesc{
+    /// Human readable name of the device, eg: "Vendor Camera 4K"
+    pub display_name: String,
+    /// Class of the device, eg: "Video/Source"
+    pub device_class: String,
+    /// Formats supported by the device
+    pub caps: Option<Caps>,
+    /// Extra information about the device, eg: its serial number
+    pub properties: Option<Structure>,
+    /// Factory of the element created for the device, eg: "vendorcamsrc"
+    pub factory: String,
+    /// Configures the element to use this device, eg: by setting its
+    /// "serial" property. Also used to reconfigure elements of the same
+    /// factory
+    pub configure: Option<Box<dyn Fn(&mut Element) + Send + Sync>>,
+}
+
+/// A device provider implemented in rust, to make devices discoverable
+/// through the standard DeviceMonitor, eg: the cameras of a proprietary
+/// capture SDK:
+///
+/// ```ignore
+/// struct VendorCamProvider;
+///
+/// impl gst::device_provider::DeviceProviderImpl for VendorCamProvider{
+///     fn new(_provider: &mut gst::device_provider::DeviceProvider) -> VendorCamProvider{
+///         VendorCamProvider
+///     }
+///
+///     fn metadata() -> gst::ElementMetadata{
+///         gst::ElementMetadata{
+///             long_name: "Vendor camera provider",
+///             klass: "Source/Video",
+///             description: "Lists the cameras of the vendor SDK",
+///             author: "Someone <someone@example.com>",
+///         }
+///     }
+///
+///     fn probe(&mut self) -> Vec<gst::device_provider::DeviceDesc>{
+///         vendor_sdk::cameras().into_iter().map(|camera| gst::device_provider::DeviceDesc{
+///             display_name: camera.name(),
+///             device_class: "Video/Source".to_string(),
+///             caps: gst::Caps::from_string("video/x-raw"),
+///             properties: None,
+///             factory: "vendorcamsrc".to_string(),
+///             configure: Some(Box::new(move |element| element.set("serial", &camera.serial()[..]))),
+///         }).collect()
+///     }
+/// }
+///
+/// gst::device_provider::register_device_provider::<VendorCamProvider>(None, "vendorcamdeviceprovider", gst::ffi::GST_RANK_PRIMARY);
+/// ```
+///
+/// Like in BaseTransformImpl the implementation is kept behind a mutex
+/// and isn't called anymore once it panicked.
+pub trait DeviceProviderImpl: Send + Sized + 'static{
+    /// Creates the state of a new instance of the provider
+    fn new(provider: &mut DeviceProvider) -> Self;
+
+    fn metadata() -> ElementMetadata;
+
+    /// Lists the devices currently available
+    fn probe(&mut self) -> Vec<DeviceDesc>;
+
+    /// Starts monitoring, calling provider.device_add and device_remove
+    /// as devices are plugged and unplugged, eg: from the SDK's hotplug
+    /// callbacks. A reference kept to provider has to be released in
+    /// stop. By default adds the devices found by probe once
+    fn start(&mut self, provider: &mut DeviceProvider) -> bool{
+        for device in self.probe(){
+            provider.device_add(device);
+        }
+        true
+    }
+
+    /// Stops monitoring, the devices added are removed afterwards
+    fn stop(&mut self){}
+}
+
+/// The GstDeviceProvider part of a device provider implemented in rust,
+/// to add and remove devices while monitoring
+pub struct DeviceProvider{
+    provider: Object<GstDeviceProvider>
+}
+
+impl DeviceProvider{
+    pub unsafe fn new_from_gst_device_provider(provider: *mut GstDeviceProvider) -> Option<DeviceProvider>{
+        Object::new(provider).map(|obj| DeviceProvider{ provider: obj })
+    }
+
+    /// Announces a device that was plugged, posting a device added
+    /// message for the monitors. The device is returned to remove it later,
+    /// None if it couldn't be created, eg: a name contains a nul byte
+    pub fn device_add(&mut self, desc: DeviceDesc) -> Option<Device>{
+        unsafe{
+            let device = new_device(desc)?;
+            gst_object_ref(device as *mut c_void);
+            let device_add: unsafe extern "C" fn(*mut GstDeviceProvider, *mut GstDevice) = provider_fn("gst_device_provider_device_add");
+            device_add(self.gst_device_provider_mut(), device);
+            Device::new_from_gst_device(device)
+        }
+    }
+
+    /// Announces that a device previously added was unplugged
+    pub fn device_remove(&mut self, device: &Device){
+        unsafe{
+            let device_remove: unsafe extern "C" fn(*mut GstDeviceProvider, *mut GstDevice) = provider_fn("gst_device_provider_device_remove");
+            device_remove(self.gst_device_provider_mut(), device.gst_device() as *mut GstDevice);
+        }
+    }
+
+    pub unsafe fn gst_device_provider(&self) -> *const GstDeviceProvider{
+        self.provider.as_ptr()
+    }
+
+    pub unsafe fn gst_device_provider_mut(&mut self) -> *mut GstDeviceProvider{
+        self.provider.as_mut_ptr()
+    }
+}
+
+impl Reference for DeviceProvider{
+    fn reference(&self) -> DeviceProvider{
+        DeviceProvider{ provider: self.provider.reference() }
+    }
+}
+
+impl AsRef<Object> for DeviceProvider{
+    fn as_ref(&self) -> &Object{
+        self.provider.upcast_ref()
+    }
+}
+
+impl AsMut<Object> for DeviceProvider{
+    fn as_mut(&mut self) -> &mut Object{
+        self.provider.upcast_mut()
+    }
+}
+
+impl From<DeviceProvider> for Object{
+    fn from(p: DeviceProvider) -> Object{
+        p.provider.upcast()
+    }
+}
+
+impl Deref for DeviceProvider{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.provider.upcast_ref()
+    }
+}
+
+impl DerefMut for DeviceProvider{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.provider.upcast_mut()
+    }
+}
+
+/// Registers T as a device provider factory called name, eg:
+/// "vendorcamdeviceprovider", so DeviceMonitor uses it. plugin and rank
+/// are the same as in subclass::register_base_transform, providers
+/// with a rank lower than GST_RANK_MARGINAL aren't used by monitors.
+/// Returns false with GStreamer older than 1.4
+pub fn register_device_provider<T: DeviceProviderImpl>(plugin: Option<&Plugin>, name: &str, rank: u32) -> bool{
+    let cname = CString::new(name).unwrap();
+    unsafe{
+        let gtype = device_provider_type::<T>(name);
+        if gtype == 0{
+            return false;
+        }
+        let plugin = plugin.map(|plugin| plugin.gst_plugin() as *mut GstPlugin).unwrap_or(ptr::null_mut());
+        let register: unsafe extern "C" fn(*mut GstPlugin, *const gchar, guint, GType) -> gboolean = provider_fn("gst_device_provider_register");
+        register(plugin, cname.as_ptr(), rank, gtype) != 0
+    }
+}
+
+/// Rust state of the devices, the element to create for them
+struct DeviceElement{
+    factory: String,
+    configure: Option<Box<dyn Fn(&mut Element) + Send + Sync>>,
+}
+
+// 0 if device providers aren't available
+unsafe fn device_provider_type<T: DeviceProviderImpl>(name: &str) -> GType{
+    let get_type: unsafe extern "C" fn() -> GType = match DEVICES.function("gst_device_provider_get_type"){
+        Ok(get_type) => get_type,
+        Err(_) => return 0,
+    };
+    register_type::<T>(get_type(), &type_name(DEVICE_PROVIDER_PREFIX, name),
+        mem::size_of::<GstDeviceProviderClass>(), mem::size_of::<Instance<GstDeviceProvider, T>>(),
+        Some(class_init::<T>), Some(instance_init::<T>))
+}
+
+extern "C" fn class_init<T: DeviceProviderImpl>(klass: gpointer, _data: gpointer){
+    catch_panic(ptr::null_mut(), (), || unsafe{
+        let object_class = klass as *mut GObjectClass;
+        (*object_class).finalize = Some(finalize::<GstDeviceProvider, T>);
+
+        let provider_class = klass as *mut GstDeviceProviderClass;
+        let metadata = T::metadata();
+        let long_name = CString::new(metadata.long_name).unwrap();
+        let klass_name = CString::new(metadata.klass).unwrap();
+        let description = CString::new(metadata.description).unwrap();
+        let author = CString::new(metadata.author).unwrap();
+        let set_metadata: unsafe extern "C" fn(*mut GstDeviceProviderClass, *const gchar, *const gchar, *const gchar, *const gchar) =
+            provider_fn("gst_device_provider_class_set_metadata");
+        set_metadata(provider_class, long_name.as_ptr(), klass_name.as_ptr(),
+            description.as_ptr(), author.as_ptr());
+
+        (*provider_class).probe = Some(probe::<T>);
+        (*provider_class).start = Some(start::<T>);
+        (*provider_class).stop = Some(stop::<T>);
+    })
+}
+
+extern "C" fn instance_init<T: DeviceProviderImpl>(instance: *mut GTypeInstance, _klass: gpointer){
+    unsafe{
+        let instance = instance as *mut GstDeviceProvider;
+        gst_object_ref(instance as *mut c_void);
+        let mut provider = DeviceProvider::new_from_gst_device_provider(instance).unwrap();
+        let imp = catch_panic(ptr::null_mut(), None, || Some(T::new(&mut provider)));
+        set_imp(instance, imp);
+    }
+}
+
+extern "C" fn probe<T: DeviceProviderImpl>(provider: *mut GstDeviceProvider) -> *mut GList{
+    unsafe{
+        with_imp(provider, ptr::null_mut(), ptr::null_mut(), |imp: &mut T| {
+            // the provider sinks the floating references of the devices
+            let mut list = ptr::null_mut();
+            for desc in imp.probe(){
+                if let Some(device) = new_device(desc){
+                    list = g_list_append(list, device as gpointer);
+                }
+            }
+            list
+        })
+    }
+}
+
+extern "C" fn start<T: DeviceProviderImpl>(provider: *mut GstDeviceProvider) -> gboolean{
+    unsafe{
+        gst_object_ref(provider as *mut c_void);
+        let mut wrapper = match DeviceProvider::new_from_gst_device_provider(provider){
+            Some(wrapper) => wrapper,
+            None => return 0,
+        };
+        with_imp(provider, ptr::null_mut(), false, |imp: &mut T| imp.start(&mut wrapper)) as gboolean
+    }
+}
+
+extern "C" fn stop<T: DeviceProviderImpl>(provider: *mut GstDeviceProvider){
+    unsafe{
+        with_imp(provider, ptr::null_mut(), (), |imp: &mut T| imp.stop())
+    }
+}
+
+/// Creates a floating device for desc, of the GstRsDevice type, None
+/// if the names of desc contain a nul byte
+unsafe fn new_device(desc: DeviceDesc) -> Option<*mut GstDevice>{
+    let gtype = rust_device_type();
+    if gtype == 0{
+        return None;
+    }
+    let display_name = CString::new(desc.display_name).ok()?;
+    let device_class = CString::new(desc.device_class).ok()?;
+    let caps = desc.caps.as_ref().map(|caps| caps.gst_caps()).unwrap_or(ptr::null());
+    let properties = desc.properties.as_ref().map(|properties| properties.gst_structure()).unwrap_or(ptr::null());
+    // construct only properties, they can't be set after g_object_new
+    let device = g_object_new(gtype,
+        b"display-name\0".as_ptr() as *const gchar, display_name.as_ptr(),
+        b"device-class\0".as_ptr() as *const gchar, device_class.as_ptr(),
+        b"caps\0".as_ptr() as *const gchar, caps,
+        b"properties\0".as_ptr() as *const gchar, properties,
+        ptr::null::<gchar>()) as *mut GstDevice;
+    if device == ptr::null_mut(){
+        return None;
+    }
+    set_imp(device, Some(DeviceElement{
+        factory: desc.factory,
+        configure: desc.configure,
+    }));
+    Some(device)
+}
+
+// 0 if devices aren't available
+unsafe fn rust_device_type() -> GType{
+    let get_type: unsafe extern "C" fn() -> GType = match DEVICES.function("gst_device_get_type"){
+        Ok(get_type) => get_type,
+        Err(_) => return 0,
+    };
+    register_type::<DeviceElement>(get_type(), "GstRsDevice", mem::size_of::<GstDeviceClass>(),
+        mem::size_of::<Instance<GstDevice, DeviceElement>>(), Some(device_class_init), None)
+}
+
+extern "C" fn device_class_init(klass: gpointer, _data: gpointer){
+    unsafe{
+        let object_class = klass as *mut GObjectClass;
+        (*object_class).finalize = Some(finalize::<GstDevice, DeviceElement>);
+
+        let device_class = klass as *mut GstDeviceClass;
+        (*device_class).create_element = Some(device_create_element);
+        (*device_class).reconfigure_element = Some(device_reconfigure_element);
+    }
+}
+
+extern "C" fn device_create_element(device: *mut GstDevice, name: *const gchar) -> *mut GstElement{
+    unsafe{
+        let name = if name != ptr::null() { from_c_str!(name) } else { "" };
+        with_imp(device, ptr::null_mut(), ptr::null_mut(), |imp: &mut DeviceElement| {
+            let mut element = match Element::new(&imp.factory, name){
+                Some(element) => element,
+                None => return ptr::null_mut(),
+            };
+            if let Some(ref configure) = imp.configure{
+                configure(&mut element);
+            }
+            // the caller expects a floating reference
+            let element = element.transfer();
+            g_object_force_floating(element as *mut GObject);
+            element
+        })
+    }
+}
+
+extern "C" fn device_reconfigure_element(device: *mut GstDevice, element: *mut GstElement) -> gboolean{
+    unsafe{
+        gst_object_ref(element as *mut c_void);
+        let mut element = match Element::new_from_gst_element(element){
+            Some(element) => element,
+            None => return 0,
+        };
+        with_imp(device, ptr::null_mut(), false, |imp: &mut DeviceElement| {
+            if factory_name(&element).as_ref() != Some(&imp.factory){
+                return false;
+            }
+            if let Some(ref configure) = imp.configure{
+                configure(&mut element);
+            }
+            true
+        }) as gboolean
+    }
+}
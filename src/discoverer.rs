@@ -0,0 +1,581 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use error::{Error, Result};
+use taglist::TagList;
+use panic::catch_panic;
+use gobject::GObjectRef;
+use version::ApiAvailability;
+
+use std::ops::Deref;
+
+const MISSING_PLUGINS: ApiAvailability = ApiAvailability::new("DiscovererInfo::missing_plugins", 1, 4, 0);
+const LIVE: ApiAvailability = ApiAvailability::new("DiscovererInfo::is_live", 1, 14, 0);
+
+/// Outcome of discovering an uri, mirrors GstDiscovererResult
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum DiscovererResult{
+    Ok = GST_DISCOVERER_OK,
+    UriInvalid = GST_DISCOVERER_URI_INVALID,
+    Error = GST_DISCOVERER_ERROR,
+    Timeout = GST_DISCOVERER_TIMEOUT,
+    Busy = GST_DISCOVERER_BUSY,
+    MissingPlugins = GST_DISCOVERER_MISSING_PLUGINS,
+}
+
+impl DiscovererResult{
+    fn from_gst_result(result: GstDiscovererResult) -> DiscovererResult{
+        match result{
+            GST_DISCOVERER_OK => DiscovererResult::Ok,
+            GST_DISCOVERER_URI_INVALID => DiscovererResult::UriInvalid,
+            GST_DISCOVERER_TIMEOUT => DiscovererResult::Timeout,
+            GST_DISCOVERER_BUSY => DiscovererResult::Busy,
+            GST_DISCOVERER_MISSING_PLUGINS => DiscovererResult::MissingPlugins,
+            _ => DiscovererResult::Error,
+        }
+    }
+}
+
+unsafe fn taglist_from_borrowed(tags: *const GstTagList) -> Option<TagList>{
+    if tags != ptr::null(){
+        gst_mini_object_ref(tags as *mut GstMiniObject);
+    }
+    TagList::new_from_gst_taglist(tags as *mut GstTagList)
+}
+
+unsafe fn stream_infos_from_list(list: *mut GList) -> Vec<StreamInfo>{
+    let mut infos = vec![];
+    let mut node = list;
+    while node != ptr::null_mut(){
+        if let Some(info) = StreamInfo::new_ref((*node).data as *mut GstDiscovererStreamInfo){
+            infos.push(info);
+        }
+        node = (*node).next;
+    }
+    gst_discoverer_stream_info_list_free(list);
+    infos
+}
+
+unsafe fn optional_str<'a>(s: *const gchar) -> Option<&'a str>{
+    if s != ptr::null(){
+        Some(from_c_str!(s))
+    }else{
+        None
+    }
+}
+
+/// Inspects media files or streams to find out their container, codecs,
+/// duration and metadata without playing them:
+///
+/// ```ignore
+/// let discoverer = gst::Discoverer::new(5_000_000_000).unwrap();
+/// let info = discoverer.discover_uri("file:///tmp/video.mp4").unwrap();
+/// println!("duration: {:?}", info.duration());
+/// for video in info.video_streams(){
+///     println!("{}x{}", video.width(), video.height());
+/// }
+/// ```
+pub struct Discoverer{
+    discoverer: GObjectRef
+}
+
+// GstDiscoverer protects its state with its own lock, discover_uri can be
+// called from any thread
+unsafe impl Send for Discoverer {}
+unsafe impl Sync for Discoverer {}
+
+type DiscoveredFunc = Guarded<dyn FnMut(&DiscovererInfo, Option<&Error>) + Send>;
+type FinishedFunc = Guarded<dyn FnMut() + Send>;
+
+impl Discoverer{
+    /// Creates a discoverer that gives up on an uri after timeout
+    /// nanoseconds
+    pub fn new(timeout: u64) -> Result<Discoverer>{
+        unsafe{
+            let mut err: *mut GError = ptr::null_mut();
+            let discoverer = gst_discoverer_new(timeout, &mut err);
+            if err != ptr::null_mut(){
+                Err(Error::new_from_g_error(err))
+            }else{
+                GObjectRef::new(discoverer as gpointer)
+                    .map(|discoverer| Discoverer{ discoverer: discoverer })
+                    .ok_or(Error::new(0, 0, "couldn't create discoverer"))
+            }
+        }
+    }
+
+    /// Discovers uri synchronously, blocking until it's done or the
+    /// timeout expires. A partial info is returned on timeouts or missing
+    /// plugins, check its result()
+    pub fn discover_uri(&self, uri: &str) -> Result<DiscovererInfo>{
+        let curi = CString::new(uri).unwrap();
+        unsafe{
+            let mut err: *mut GError = ptr::null_mut();
+            let info = gst_discoverer_discover_uri(self.gst_discoverer(), curi.as_ptr(), &mut err);
+            match DiscovererInfo::new(info){
+                Some(info) => {
+                    if err != ptr::null_mut(){
+                        g_error_free(err);
+                    }
+                    Ok(info)
+                }
+                None => {
+                    if err != ptr::null_mut(){
+                        Err(Error::new_from_g_error(err))
+                    }else{
+                        Err(Error::new(0, 0, "couldn't discover uri"))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Starts processing the uris queued with discover_uri_async. Results
+    /// are delivered through the "discovered" signal from the thread
+    /// default main context, which has to be running
+    pub fn start(&self){
+        unsafe{
+            gst_discoverer_start(self.gst_discoverer());
+        }
+    }
+
+    /// Stops processing, pending uris are discarded
+    pub fn stop(&self){
+        unsafe{
+            gst_discoverer_stop(self.gst_discoverer());
+        }
+    }
+
+    /// Queues uri to be discovered once start is called
+    pub fn discover_uri_async(&self, uri: &str) -> bool{
+        let curi = CString::new(uri).unwrap();
+        unsafe{
+            gst_discoverer_discover_uri_async(self.gst_discoverer(), curi.as_ptr()) != 0
+        }
+    }
+
+    /// Calls f with the info of every uri discovered asynchronously and
+    /// the error that happened while discovering it, if any. The handler
+    /// stays connected for the lifetime of the discoverer.
+    pub fn connect_discovered<F>(&self, f: F)
+        where F: FnMut(&DiscovererInfo, Option<&Error>) + Send + 'static{
+        let f: DiscoveredFunc = Guarded::new(Box::new(f));
+        unsafe{
+            self.connect_owned("discovered", c_callback(discovered_trampoline as *const ()), f);
+        }
+    }
+
+    /// Calls f once all the queued uris have been discovered
+    pub fn connect_finished<F>(&self, f: F)
+        where F: FnMut() + Send + 'static{
+        let f: FinishedFunc = Guarded::new(Box::new(f));
+        unsafe{
+            self.connect_owned("finished", c_callback(finished_trampoline as *const ()), f);
+        }
+    }
+
+    unsafe fn connect_owned<T: Send + 'static>(&self, signal: &str, callback: GCallback, data: T){
+        let csignal = CString::new(signal).unwrap();
+        let data = Box::into_raw(Box::new(data));
+        g_signal_connect_data(self.discoverer.object, csignal.as_ptr(), callback, data as gpointer, Some(drop_data::<T>), 0);
+    }
+
+    pub unsafe fn gst_discoverer(&self) -> *mut GstDiscoverer{
+        self.discoverer.object as *mut GstDiscoverer
+    }
+}
+
+extern "C" fn discovered_trampoline(_discoverer: *mut GstDiscoverer, info: *mut GstDiscovererInfo, err: *mut GError, data: gpointer){
+    unsafe{
+        let f = &mut *(data as *mut DiscoveredFunc);
+        if let Some(info) = DiscovererInfo::new_ref(info){
+            let err = if err != ptr::null_mut(){
+                Some(Error::new_from_g_error(g_error_copy(err)))
+            }else{
+                None
+            };
+            f.call(ptr::null_mut(), (), |f| f(&info, err.as_ref()));
+        }
+    }
+}
+
+extern "C" fn finished_trampoline(_discoverer: *mut GstDiscoverer, data: gpointer){
+    unsafe{
+        let f = &mut *(data as *mut FinishedFunc);
+        f.call(ptr::null_mut(), (), |f| f());
+    }
+}
+
+extern "C" fn drop_data<T>(data: gpointer, _closure: *mut GClosure){
+    unsafe{
+        catch_panic(ptr::null_mut(), (), || drop(Box::from_raw(data as *mut T)));
+    }
+}
+
+/// Everything found out about an uri
+#[derive(Clone)]
+pub struct DiscovererInfo{
+    info: GObjectRef
+}
+
+// The info is never modified once the discoverer hands it out
+unsafe impl Send for DiscovererInfo {}
+unsafe impl Sync for DiscovererInfo {}
+
+impl DiscovererInfo{
+    pub unsafe fn new(info: *mut GstDiscovererInfo) -> Option<DiscovererInfo>{
+        GObjectRef::new(info as gpointer).map(|info| DiscovererInfo{ info: info })
+    }
+
+    pub unsafe fn new_ref(info: *mut GstDiscovererInfo) -> Option<DiscovererInfo>{
+        GObjectRef::new_ref(info as gpointer).map(|info| DiscovererInfo{ info: info })
+    }
+
+    pub fn uri(&self) -> &str{
+        unsafe{
+            from_c_str!(gst_discoverer_info_get_uri(self.gst_discoverer_info()))
+        }
+    }
+
+    pub fn result(&self) -> DiscovererResult{
+        unsafe{
+            DiscovererResult::from_gst_result(gst_discoverer_info_get_result(self.gst_discoverer_info()))
+        }
+    }
+
+    /// Duration in nanoseconds, None if unknown
+    pub fn duration(&self) -> Option<u64>{
+        unsafe{
+            let duration = gst_discoverer_info_get_duration(self.gst_discoverer_info());
+            if duration != GST_CLOCK_TIME_NONE{
+                Some(duration)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn is_seekable(&self) -> bool{
+        unsafe{
+            gst_discoverer_info_get_seekable(self.gst_discoverer_info()) != 0
+        }
+    }
+
+    /// Always false with GStreamer older than 1.14
+    pub fn is_live(&self) -> bool{
+        unsafe{
+            match LIVE.function::<unsafe extern "C" fn(*const GstDiscovererInfo) -> gboolean>("gst_discoverer_info_get_live"){
+                Ok(get_live) => get_live(self.gst_discoverer_info()) != 0,
+                Err(_) => false,
+            }
+        }
+    }
+
+    /// Global tags of the uri, merged from all its streams
+    pub fn tags(&self) -> Option<TagList>{
+        unsafe{
+            taglist_from_borrowed(gst_discoverer_info_get_tags(self.gst_discoverer_info()))
+        }
+    }
+
+    /// Details to install the plugins that were missing, to be passed to
+    /// the plugin installer. Always empty with GStreamer older than 1.4
+    pub fn missing_plugins(&self) -> Vec<String>{
+        unsafe{
+            let get_details: unsafe extern "C" fn(*const GstDiscovererInfo) -> *mut *const gchar =
+                match MISSING_PLUGINS.function("gst_discoverer_info_get_missing_elements_installer_details"){
+                    Ok(get_details) => get_details,
+                    Err(_) => return vec![],
+                };
+            let details = get_details(self.gst_discoverer_info());
+            let mut ret = vec![];
+            if details != ptr::null_mut(){
+                let mut i = 0;
+                while *details.offset(i) != ptr::null(){
+                    ret.push(from_c_str!(*details.offset(i)).to_string());
+                    i += 1;
+                }
+            }
+            ret
+        }
+    }
+
+    /// The topmost stream, usually a container, from which the rest of
+    /// the streams can be walked
+    pub fn stream_info(&self) -> Option<StreamInfo>{
+        unsafe{
+            StreamInfo::new(gst_discoverer_info_get_stream_info(self.gst_discoverer_info()))
+        }
+    }
+
+    /// All the streams in the uri, flattened
+    pub fn streams(&self) -> Vec<StreamInfo>{
+        unsafe{
+            stream_infos_from_list(gst_discoverer_info_get_stream_list(self.gst_discoverer_info()))
+        }
+    }
+
+    pub fn container_streams(&self) -> Vec<ContainerInfo>{
+        self.streams().into_iter().filter_map(|s| s.container()).collect()
+    }
+
+    pub fn video_streams(&self) -> Vec<VideoInfo>{
+        self.streams().into_iter().filter_map(|s| s.video()).collect()
+    }
+
+    pub fn audio_streams(&self) -> Vec<AudioInfo>{
+        self.streams().into_iter().filter_map(|s| s.audio()).collect()
+    }
+
+    pub fn subtitle_streams(&self) -> Vec<SubtitleInfo>{
+        self.streams().into_iter().filter_map(|s| s.subtitle()).collect()
+    }
+
+    pub unsafe fn gst_discoverer_info(&self) -> *mut GstDiscovererInfo{
+        self.info.object as *mut GstDiscovererInfo
+    }
+}
+
+/// Information common to all the streams found in an uri. Can be
+/// converted to the specific info of its kind with container(), video(),
+/// audio() or subtitle()
+#[derive(Clone)]
+pub struct StreamInfo{
+    info: GObjectRef
+}
+
+// Same as DiscovererInfo
+unsafe impl Send for StreamInfo {}
+unsafe impl Sync for StreamInfo {}
+
+impl StreamInfo{
+    pub unsafe fn new(info: *mut GstDiscovererStreamInfo) -> Option<StreamInfo>{
+        GObjectRef::new(info as gpointer).map(|info| StreamInfo{ info: info })
+    }
+
+    pub unsafe fn new_ref(info: *mut GstDiscovererStreamInfo) -> Option<StreamInfo>{
+        GObjectRef::new_ref(info as gpointer).map(|info| StreamInfo{ info: info })
+    }
+
+    /// Caps of the stream, the codec can be found out from them
+    pub fn caps(&self) -> Option<Caps>{
+        unsafe{
+            Caps::new(gst_discoverer_stream_info_get_caps(self.gst_stream_info()))
+        }
+    }
+
+    pub fn tags(&self) -> Option<TagList>{
+        unsafe{
+            taglist_from_borrowed(gst_discoverer_stream_info_get_tags(self.gst_stream_info()))
+        }
+    }
+
+    pub fn stream_id(&self) -> Option<&str>{
+        unsafe{
+            optional_str(gst_discoverer_stream_info_get_stream_id(self.gst_stream_info()))
+        }
+    }
+
+    /// Kind of the stream, eg: "container", "video", "audio"...
+    pub fn stream_type_nick(&self) -> &str{
+        unsafe{
+            from_c_str!(gst_discoverer_stream_info_get_stream_type_nick(self.gst_stream_info()))
+        }
+    }
+
+    /// Human readable codec from the tags, falls back to the caps
+    pub fn codec(&self) -> Option<String>{
+        let tag = if self.is_video(){
+            "video-codec"
+        }else if self.is_audio(){
+            "audio-codec"
+        }else if self.is_subtitle(){
+            "subtitle-codec"
+        }else{
+            "container-format"
+        };
+        self.tags().and_then(|tags| tags.get::<String>(tag).or_else(|| tags.get::<String>("codec")))
+            .or_else(|| self.caps().and_then(|caps| caps.structure(0).map(|s| s.name().to_string())))
+    }
+
+    pub fn is_container(&self) -> bool{
+        unsafe{ self.info.is_a(gst_discoverer_container_info_get_type()) }
+    }
+
+    pub fn is_video(&self) -> bool{
+        unsafe{ self.info.is_a(gst_discoverer_video_info_get_type()) }
+    }
+
+    pub fn is_audio(&self) -> bool{
+        unsafe{ self.info.is_a(gst_discoverer_audio_info_get_type()) }
+    }
+
+    pub fn is_subtitle(&self) -> bool{
+        unsafe{ self.info.is_a(gst_discoverer_subtitle_info_get_type()) }
+    }
+
+    pub fn container(self) -> Option<ContainerInfo>{
+        if self.is_container(){ Some(ContainerInfo{ info: self }) }else{ None }
+    }
+
+    pub fn video(self) -> Option<VideoInfo>{
+        if self.is_video(){ Some(VideoInfo{ info: self }) }else{ None }
+    }
+
+    pub fn audio(self) -> Option<AudioInfo>{
+        if self.is_audio(){ Some(AudioInfo{ info: self }) }else{ None }
+    }
+
+    pub fn subtitle(self) -> Option<SubtitleInfo>{
+        if self.is_subtitle(){ Some(SubtitleInfo{ info: self }) }else{ None }
+    }
+
+    pub unsafe fn gst_stream_info(&self) -> *mut GstDiscovererStreamInfo{
+        self.info.object as *mut GstDiscovererStreamInfo
+    }
+}
+
+/// A container stream, like a matroska or mp4 file
+#[derive(Clone)]
+pub struct ContainerInfo{
+    info: StreamInfo
+}
+
+impl ContainerInfo{
+    /// Streams contained in this one
+    pub fn streams(&self) -> Vec<StreamInfo>{
+        unsafe{
+            stream_infos_from_list(gst_discoverer_container_info_get_streams(self.gst_stream_info()))
+        }
+    }
+}
+
+/// A video stream or image
+#[derive(Clone)]
+pub struct VideoInfo{
+    info: StreamInfo
+}
+
+impl VideoInfo{
+    pub fn width(&self) -> u32{
+        unsafe{ gst_discoverer_video_info_get_width(self.gst_stream_info()) }
+    }
+
+    pub fn height(&self) -> u32{
+        unsafe{ gst_discoverer_video_info_get_height(self.gst_stream_info()) }
+    }
+
+    pub fn depth(&self) -> u32{
+        unsafe{ gst_discoverer_video_info_get_depth(self.gst_stream_info()) }
+    }
+
+    /// Framerate as numerator, denominator
+    pub fn framerate(&self) -> (u32, u32){
+        unsafe{
+            (gst_discoverer_video_info_get_framerate_num(self.gst_stream_info()),
+             gst_discoverer_video_info_get_framerate_denom(self.gst_stream_info()))
+        }
+    }
+
+    /// Pixel aspect ratio as numerator, denominator
+    pub fn pixel_aspect_ratio(&self) -> (u32, u32){
+        unsafe{
+            (gst_discoverer_video_info_get_par_num(self.gst_stream_info()),
+             gst_discoverer_video_info_get_par_denom(self.gst_stream_info()))
+        }
+    }
+
+    pub fn is_interlaced(&self) -> bool{
+        unsafe{ gst_discoverer_video_info_is_interlaced(self.gst_stream_info()) != 0 }
+    }
+
+    /// True if the stream is a still image rather than a video
+    pub fn is_image(&self) -> bool{
+        unsafe{ gst_discoverer_video_info_is_image(self.gst_stream_info()) != 0 }
+    }
+
+    /// Average bitrate in bits per second, 0 if unknown
+    pub fn bitrate(&self) -> u32{
+        unsafe{ gst_discoverer_video_info_get_bitrate(self.gst_stream_info()) }
+    }
+
+    pub fn max_bitrate(&self) -> u32{
+        unsafe{ gst_discoverer_video_info_get_max_bitrate(self.gst_stream_info()) }
+    }
+}
+
+/// An audio stream
+#[derive(Clone)]
+pub struct AudioInfo{
+    info: StreamInfo
+}
+
+impl AudioInfo{
+    pub fn channels(&self) -> u32{
+        unsafe{ gst_discoverer_audio_info_get_channels(self.gst_stream_info()) }
+    }
+
+    pub fn sample_rate(&self) -> u32{
+        unsafe{ gst_discoverer_audio_info_get_sample_rate(self.gst_stream_info()) }
+    }
+
+    pub fn depth(&self) -> u32{
+        unsafe{ gst_discoverer_audio_info_get_depth(self.gst_stream_info()) }
+    }
+
+    /// Average bitrate in bits per second, 0 if unknown
+    pub fn bitrate(&self) -> u32{
+        unsafe{ gst_discoverer_audio_info_get_bitrate(self.gst_stream_info()) }
+    }
+
+    pub fn max_bitrate(&self) -> u32{
+        unsafe{ gst_discoverer_audio_info_get_max_bitrate(self.gst_stream_info()) }
+    }
+
+    /// ISO-639 language code, if known
+    pub fn language(&self) -> Option<&str>{
+        unsafe{ optional_str(gst_discoverer_audio_info_get_language(self.gst_stream_info())) }
+    }
+}
+
+/// A subtitle stream
+#[derive(Clone)]
+pub struct SubtitleInfo{
+    info: StreamInfo
+}
+
+impl SubtitleInfo{
+    /// ISO-639 language code, if known
+    pub fn language(&self) -> Option<&str>{
+        unsafe{ optional_str(gst_discoverer_subtitle_info_get_language(self.gst_stream_info())) }
+    }
+}
+
+impl Deref for ContainerInfo{
+    type Target = StreamInfo;
+    fn deref(&self) -> &StreamInfo{
+        &self.info
+    }
+}
+
+impl Deref for VideoInfo{
+    type Target = StreamInfo;
+    fn deref(&self) -> &StreamInfo{
+        &self.info
+    }
+}
+
+impl Deref for AudioInfo{
+    type Target = StreamInfo;
+    fn deref(&self) -> &StreamInfo{
+        &self.info
+    }
+}
+
+impl Deref for SubtitleInfo{
+    type Target = StreamInfo;
+    fn deref(&self) -> &StreamInfo{
+        &self.info
+    }
+}
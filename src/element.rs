@@ -1,20 +1,110 @@
 use ffi::*;
 use bus::Bus;
+use handle::SignalHandle;
 use util::*;
 use pad::Pad;
-use reference::Reference;
+use reference::{Reference, Downgrade};
 use object::{Object, Property, FromProperty};
+use value::{Value, ToValue};
+use version::ApiAvailability;
 
 use std::os::raw::c_void;
+use std::cmp;
+use std::time::Instant;
+use std::sync::{Arc, Mutex};
 use std::ops::{Deref, DerefMut};
 
-unsafe impl Sync for GstElement {}
-unsafe impl Send for GstElement {}
-unsafe impl Sync for Element {}
-unsafe impl Send for Element {}
+// get_state is called with this timeout, in nanoseconds, so errors
+// posted on the bus are noticed while waiting for a state change
+const STATE_POLL_INTERVAL: GstClockTime = 100_000_000;
+
+const ELEMENT_CONTEXT: ApiAvailability = ApiAvailability::new("Element::context", 1, 8, 0);
+
+// first error posted by an element or the elements inside it, seen
+// through the bus sync-message signal so the messages stay on the bus
+type FirstError = Mutex<Option<::Error>>;
+
+struct ErrorWatch{
+    bus: Option<Bus>,
+    handle: Option<SignalHandle>,
+    error: Arc<FirstError>,
+}
+
+impl ErrorWatch{
+    fn new(element: &Element) -> ErrorWatch{
+        let error = Arc::new(Mutex::new(None));
+        let mut bus = element.bus();
+        let handle = bus.as_mut().and_then(|bus| unsafe{
+            gst_bus_enable_sync_message_emission(bus.gst_bus_mut());
+            let data = (element.gst_element() as usize, error.clone());
+            bus.connect_owned("sync-message::error", c_callback(error_watch_callback as *const ()), data)
+        });
+        ErrorWatch{
+            bus: bus,
+            handle: handle,
+            error: error,
+        }
+    }
+
+    fn take(&self) -> Option<::Error>{
+        self.error.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+impl Drop for ErrorWatch{
+    fn drop(&mut self){
+        if let Some(mut bus) = self.bus.take(){
+            drop(self.handle.take());
+            unsafe{ gst_bus_disable_sync_message_emission(bus.gst_bus_mut()); }
+        }
+    }
+}
+
+extern "C" fn error_watch_callback(_bus: *mut GstBus, msg: *mut GstMessage, data: gpointer){
+    unsafe{
+        let &(element, ref error) = &*(data as *const (usize, Arc<FirstError>));
+        let src = (*msg).src;
+        if src == ptr::null_mut() || gst_object_has_ancestor(src, element as *mut GstObject) == 0{
+            return;
+        }
+        let mut error = error.lock().unwrap_or_else(|e| e.into_inner());
+        if error.is_none(){
+            let mut gerror: *mut GError = ptr::null_mut();
+            let mut debug: *mut gchar = ptr::null_mut();
+            gst_message_parse_error(msg, &mut gerror, &mut debug);
+            *error = Some(::Error::new_from_message(gerror, src, debug));
+            g_free(debug as gpointer);
+        }
+    }
+}
+
+fn state_name(state: GstState) -> &'static str{
+    unsafe{
+        from_c_str!(gst_element_state_get_name(state))
+    }
+}
+
+/// Whether a URI handler reads from or writes to its URI
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum URIType{
+    Unknown = GST_URI_UNKNOWN,
+    Sink = GST_URI_SINK,
+    Src = GST_URI_SRC,
+}
+
+impl URIType{
+    fn from_gst(uri_type: GstURIType) -> URIType{
+        match uri_type{
+            GST_URI_SINK => URIType::Sink,
+            GST_URI_SRC => URIType::Src,
+            _ => URIType::Unknown,
+        }
+    }
+}
 
 pub struct Element{
-    element: Object
+    element: Object<GstElement>
 }
 
 impl Element {
@@ -30,8 +120,8 @@ impl Element {
             };
             let element = gst_element_factory_make(element_cname.as_ptr(), element_name);
             if element != ptr::null_mut::<GstElement>() {
-                gst_object_ref_sink(mem::transmute(element));
-                Some( Element{element: Object::new(element as * mut GstObject).unwrap()} )
+                gst_object_ref_sink(element as gpointer);
+                Some( Element{element: Object::new(element).unwrap()} )
             } else {
 				println!("Error creating {} return {:?}", factory_name, element);
                 None
@@ -43,8 +133,30 @@ impl Element {
 		Element::new(element,name)
 	}
 
+    /// Creates the source or sink element with the highest rank that
+    /// handles uri, eg: rtspsrc for "rtsp://..." URIs, with the URI
+    /// already set. name is the name of the element, an automatic one
+    /// if None
+    pub fn make_from_uri(uri_type: URIType, uri: &str, name: Option<&str>) -> ::Result<Element>{
+        let curi = CString::new(uri).unwrap();
+        let cname = name.map(|name| CString::new(name).unwrap());
+        unsafe{
+            let mut error: *mut GError = ptr::null_mut();
+            let element = gst_element_make_from_uri(uri_type as GstURIType, curi.as_ptr(),
+                cname.as_ref().map(|name| name.as_ptr()).unwrap_or(ptr::null()), &mut error);
+            if error != ptr::null_mut(){
+                Err(::Error::new_from_g_error(error))
+            }else if element == ptr::null_mut(){
+                Err(::Error::new(0, 0, &format!("No element handles {}", uri)))
+            }else{
+                gst_object_ref_sink(element as gpointer);
+                Element::new_from_gst_element(element).ok_or(::Error::new(0, 0, "Couldn't create element"))
+            }
+        }
+    }
+
     pub unsafe fn new_from_gst_element(element: *mut GstElement) -> Option<Element>{
-		Object::new(element as * mut GstObject)
+		Object::new(element)
             .map(|obj| Element{element: obj})
     }
 
@@ -101,7 +213,7 @@ impl Element {
 	/// will provide a bus for the application.
     pub fn bus(&self) -> Option<Bus>{
         unsafe{
-            Bus::new(gst_element_get_bus(mem::transmute(self.gst_element())))
+            Bus::new(gst_element_get_bus(self.gst_element() as *mut GstElement))
         }
     }
 
@@ -163,7 +275,7 @@ impl Element {
         let mut state: GstState = GST_STATE_NULL;
         let mut pending: GstState = GST_STATE_NULL;
         unsafe{
-            let ret = gst_element_get_state(mem::transmute(self.gst_element()), &mut state, &mut pending, timeout);
+            let ret = gst_element_get_state(self.gst_element() as *mut GstElement, &mut state, &mut pending, timeout);
             (state, pending, ret)
         }
     }
@@ -219,7 +331,7 @@ impl Element {
     pub fn query_duration(&self, format: GstFormat) -> Option<i64>{
         unsafe{
             let mut duration = 0;
-            if gst_element_query_duration(mem::transmute(self.gst_element()), format, &mut duration) == 1{
+            if gst_element_query_duration(self.gst_element() as *mut GstElement, format, &mut duration) == 1{
                 Some(duration)
             }else{
                 None
@@ -236,7 +348,7 @@ impl Element {
     pub fn query_position(&self, format: GstFormat) -> Option<i64>{
         unsafe{
             let mut pos = 0;
-            if gst_element_query_position(mem::transmute(self.gst_element()), format, &mut pos) == 1{
+            if gst_element_query_position(self.gst_element() as *mut GstElement, format, &mut pos) == 1{
                 Some(pos)
             }else{
                 None
@@ -244,6 +356,43 @@ impl Element {
         }
     }
 
+    /// Queries the latency of the element, returns whether it's live and
+    /// the minimum and maximum latency in nanoseconds, the maximum being
+    /// GST_CLOCK_TIME_NONE if unlimited. None if the query failed
+    pub fn query_latency(&self) -> Option<(bool, u64, u64)>{
+        unsafe{
+            let query = gst_query_new_latency();
+            let ret = if gst_element_query(self.gst_element() as *mut GstElement, query) != 0{
+                let mut live = 0;
+                let mut min = 0;
+                let mut max = 0;
+                gst_query_parse_latency(query, &mut live, &mut min, &mut max);
+                Some((live != 0, min, max))
+            }else{
+                None
+            };
+            gst_mini_object_unref(query as *mut GstMiniObject);
+            ret
+        }
+    }
+
+    /// Queries how much of the stream is buffered, usually on a pipeline
+    /// playing from the network. ranges and start/stop are in format,
+    /// eg: GST_FORMAT_PERCENT or GST_FORMAT_BYTES. None if no element
+    /// answered the query
+    pub fn query_buffering(&self, format: GstFormat) -> Option<::buffering::BufferingQuery>{
+        unsafe{
+            let query = gst_query_new_buffering(format);
+            let ret = if gst_element_query(self.gst_element() as *mut GstElement, query) != 0{
+                Some(::buffering::BufferingQuery::new_from_gst_query(query))
+            }else{
+                None
+            };
+            gst_mini_object_unref(query as *mut GstMiniObject);
+            ret
+        }
+    }
+
     /// Shortcut for query_duration with format == TIME
     pub fn duration_ns(&self) -> Option<i64>{
         self.query_duration(GST_FORMAT_TIME)
@@ -340,6 +489,143 @@ impl Element {
         }
     }
 
+    /// Tries to change the state of the element to the same as its
+    /// parent, useful after adding an element to an already running
+    /// pipeline
+    pub fn sync_state_with_parent(&mut self) -> bool{
+        unsafe{
+            gst_element_sync_state_with_parent(self.gst_element_mut()) != 0
+        }
+    }
+
+    /// Sets the clock time at which the running time of the element
+    /// is 0. Together with set_start_time(None) it makes several
+    /// pipelines sharing a clock render in sync
+    pub fn set_base_time(&mut self, time: u64){
+        unsafe{
+            gst_element_set_base_time(self.gst_element_mut(), time);
+        }
+    }
+
+    pub fn base_time(&self) -> u64{
+        unsafe{
+            gst_element_get_base_time(self.gst_element() as *mut GstElement)
+        }
+    }
+
+    /// Sets the running time at which the element starts when going
+    /// to PLAYING. None disables the base time selection on state
+    /// changes so the one set with set_base_time is kept
+    pub fn set_start_time(&mut self, time: Option<u64>){
+        unsafe{
+            gst_element_set_start_time(self.gst_element_mut(), time.unwrap_or(GST_CLOCK_TIME_NONE));
+        }
+    }
+
+    pub fn start_time(&self) -> Option<u64>{
+        unsafe{
+            let time = gst_element_get_start_time(self.gst_element() as *mut GstElement);
+            if time != GST_CLOCK_TIME_NONE{
+                Some(time)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Posts msg on the bus of the element, as if the element posted
+    /// it if the message has no source. Returns false if the element has
+    /// no bus yet, eg: it's not in a pipeline
+    pub fn post_message(&mut self, msg: ::Message) -> bool{
+        unsafe{
+            gst_element_post_message(self.gst_element_mut(), ::Transfer::transfer(msg)) != 0
+        }
+    }
+
+    /// Waits up to timeout nanoseconds, GST_CLOCK_TIME_NONE to wait
+    /// forever, for the element to reach state, usually after calling
+    /// set_state. Instead of blocking in get_state until the timeout when
+    /// an element fails while prerolling, the errors the element or the
+    /// elements inside it post while waiting are returned, the first one's
+    /// source_path is the element that failed:
+    ///
+    /// ```ignore
+    /// pipeline.play();
+    /// if let Err(err) = pipeline.wait_for_state(gst::ffi::GST_STATE_PLAYING, 5_000_000_000){
+    ///     println!("{} failed: {}", err.source_path().unwrap_or("?"), err.message());
+    /// }
+    /// ```
+    ///
+    /// On timeout the error's source_path is the element still changing
+    /// state, the deepest one if the element is a bin. The error messages
+    /// stay on the bus for its watches. Errors posted before calling this
+    /// aren't seen, set_state_and_wait watches them from before the
+    /// state change starts.
+    pub fn wait_for_state(&self, state: GstState, timeout: GstClockTime) -> ::Result<()>{
+        let errors = ErrorWatch::new(self);
+        self.wait_for_state_watching(state, timeout, &errors)
+    }
+
+    /// Sets the element to state and waits for it to reach it, see
+    /// wait_for_state
+    pub fn set_state_and_wait(&mut self, state: GstState, timeout: GstClockTime) -> ::Result<()>{
+        let errors = ErrorWatch::new(self);
+        if self.set_state(state) == GST_STATE_CHANGE_FAILURE{
+            return Err(errors.take().unwrap_or_else(|| self.state_error(&format!("failed to change state to {}", state_name(state)), self)));
+        }
+        self.wait_for_state_watching(state, timeout, &errors)
+    }
+
+    fn wait_for_state_watching(&self, state: GstState, timeout: GstClockTime, errors: &ErrorWatch) -> ::Result<()>{
+        let start = Instant::now();
+        loop{
+            let elapsed = start.elapsed();
+            let elapsed = elapsed.as_secs() * 1_000_000_000 + elapsed.subsec_nanos() as u64;
+            let remaining = if timeout == GST_CLOCK_TIME_NONE { timeout } else { timeout.saturating_sub(elapsed) };
+            let (current, pending, ret) = self.get_state(cmp::min(remaining, STATE_POLL_INTERVAL));
+            if let Some(err) = errors.take(){
+                return Err(err);
+            }
+            match ret{
+                GST_STATE_CHANGE_FAILURE => return Err(self.state_error(&format!("failed to change state to {}", state_name(state)), self)),
+                GST_STATE_CHANGE_SUCCESS | GST_STATE_CHANGE_NO_PREROLL if current == state => return Ok(()),
+                GST_STATE_CHANGE_SUCCESS | GST_STATE_CHANGE_NO_PREROLL if pending == GST_STATE_VOID_PENDING =>
+                    return Err(self.state_error(&format!("state changed to {} instead of {}", state_name(current), state_name(state)), self)),
+                _ => ()
+            }
+            if remaining == 0{
+                let stuck = self.stuck_element();
+                let msg = format!("timed out waiting for {} to go from {} to {}", self.name(), state_name(current), state_name(state));
+                return Err(self.state_error(&msg, stuck.as_ref().unwrap_or(self)));
+            }
+        }
+    }
+
+    fn state_error(&self, msg: &str, source: &Element) -> ::Error{
+        let cmsg = CString::new(msg.replace('\0', "")).unwrap();
+        unsafe{
+            let error = g_error_new_literal(gst_core_error_quark(), GST_CORE_ERROR_STATE_CHANGE as i32, cmsg.as_ptr());
+            ::Error::new_from_message(error, source.gst_element() as *mut GstObject, ptr::null())
+        }
+    }
+
+    /// The deepest element inside this one that is still changing
+    /// state asynchronously, usually a sink that didn't preroll
+    fn stuck_element(&self) -> Option<Element>{
+        unsafe{
+            let instance = self.gst_element() as *mut GTypeInstance;
+            if g_type_check_instance_is_a(instance, gst_bin_get_type()) == 0{
+                return None;
+            }
+            gst_object_ref(self.gst_element() as gpointer);
+            let bin = ::Bin::new_from_gst_bin(self.gst_element() as *mut GstBin)?;
+            bin.iter_recurse()
+                .filter_map(|element| element.ok())
+                .filter(|element| element.get_state(0).2 == GST_STATE_CHANGE_ASYNC)
+                .last()
+        }
+    }
+
     /// shortcut to set_state with state == NULL
     pub fn set_null_state(&mut self) -> GstStateChangeReturn{
         self.set_state(GST_STATE_NULL)
@@ -406,20 +692,424 @@ impl Element {
         }
     }
 
+    /// Sets a context on the element, usually in answer to a NeedContext
+    /// message. Bins set it on all their children. Returns an
+    /// unsupported error if the function can't be found
+    pub fn set_context(&mut self, context: &::Context) -> ::Result<()>{
+        unsafe{
+            let set_context: unsafe extern "C" fn(*mut GstElement, *mut GstContext) =
+                ::context::CONTEXT.function("gst_element_set_context")?;
+            set_context(self.gst_element_mut(), context.gst_context() as *mut GstContext);
+            Ok(())
+        }
+    }
+
+    /// The context of context_type the element has, None if it has none
+    /// or with GStreamer older than 1.8
+    pub fn context(&self, context_type: &str) -> Option<::Context>{
+        let ccontext_type = CString::new(context_type).unwrap();
+        unsafe{
+            let get_context: unsafe extern "C" fn(*mut GstElement, *const gchar) -> *mut GstContext =
+                ELEMENT_CONTEXT.function("gst_element_get_context").ok()?;
+            ::Context::new_from_gst_context(get_context(self.gst_element() as *mut GstElement, ccontext_type.as_ptr()))
+        }
+    }
+
+    /// Requests a pad from element by name or template name, eg:
+    /// "send_rtp_sink_0" from rtpbin or "sink_%u" from a mixer. It has
+    /// to be released with release_request_pad once unused
+    pub fn request_pad(&mut self, name: &str) -> Option<Pad>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            Pad::new(gst_element_get_request_pad(self.gst_element_mut(), cname.as_ptr()))
+        }
+    }
+
+    /// Requests a pad from a template of the element with
+    /// GST_PAD_REQUEST presence. name is the name of the new pad, for
+    /// templates like "sink_%u" an automatic one if None, and caps can
+    /// be used by elements like muxers to choose the pad
+    pub fn request_pad_from_template(&mut self, template: &::PadTemplate, name: Option<&str>, caps: Option<&::Caps>) -> Option<Pad>{
+        let cname = name.map(|name| CString::new(name).unwrap());
+        unsafe{
+            Pad::new(gst_element_request_pad(self.gst_element_mut(), template.gst_pad_template() as *mut GstPadTemplate,
+                cname.as_ref().map(|name| name.as_ptr()).unwrap_or(ptr::null()),
+                caps.map(|caps| caps.gst_caps()).unwrap_or(ptr::null())))
+        }
+    }
+
+    /// Releases a pad obtained with request_pad or
+    /// request_pad_from_template, unlinking it first if needed
+    pub fn release_request_pad(&mut self, mut pad: Pad){
+        unsafe{
+            gst_element_release_request_pad(self.gst_element_mut(), pad.gst_pad_mut());
+        }
+    }
+
+    /// The pad template of the element with the given name template,
+    /// eg: "src_%u" for tee
+    pub fn pad_template(&self, name: &str) -> Option<::PadTemplate>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let class = (*(self.gst_element() as *mut GTypeInstance)).g_class as *mut GstElementClass;
+            let template = gst_element_class_get_pad_template(class, cname.as_ptr());
+            if template != ptr::null_mut(){
+                gst_object_ref(template as gpointer);
+            }
+            ::PadTemplate::new_from_gst_pad_template(template)
+        }
+    }
+
+    /// All the pad templates of the element
+    pub fn pad_templates(&self) -> Vec<::PadTemplate>{
+        unsafe{
+            let class = (*(self.gst_element() as *mut GTypeInstance)).g_class as *mut GstElementClass;
+            let list = gst_element_class_get_pad_template_list(class);
+            ::registry::objects_from_list(list, |template| {
+                gst_object_ref(template);
+                ::PadTemplate::new_from_gst_pad_template(template as *mut GstPadTemplate)
+            })
+        }
+    }
+
+    /// The source pads the element currently has, including dynamic and
+    /// request pads
+    pub fn src_pads(&self) -> Vec<Pad>{
+        unsafe{ self.pads_from_list(|element| (*element).srcpads) }
+    }
+
+    /// The sink pads the element currently has, including dynamic and
+    /// request pads
+    pub fn sink_pads(&self) -> Vec<Pad>{
+        unsafe{ self.pads_from_list(|element| (*element).sinkpads) }
+    }
+
+    unsafe fn pads_from_list<F: Fn(*mut GstElement) -> *mut GList>(&self, list: F) -> Vec<Pad>{
+        let mut pads = vec![];
+        let element = self.gst_element() as *mut GstElement;
+        g_mutex_lock(&mut (*(element as *mut GstObject)).lock);
+        let mut node = list(element);
+        while node != ptr::null_mut(){
+            gst_object_ref((*node).data);
+            if let Some(pad) = Pad::new((*node).data as *mut GstPad){
+                pads.push(pad);
+            }
+            node = (*node).next;
+        }
+        g_mutex_unlock(&mut (*(element as *mut GstObject)).lock);
+        pads
+    }
+
+    /// True if the element implements GstPreset, like most encoders, so
+    /// its settings can be saved and loaded by name
+    pub fn is_preset(&self) -> bool{
+        unsafe{
+            g_type_check_instance_is_a(self.gst_element() as *mut GTypeInstance, gst_preset_get_type()) != 0
+        }
+    }
+
+    /// Names of the presets available for the element, both the ones
+    /// installed with it and the ones saved by the user. Empty if the
+    /// element doesn't implement GstPreset
+    pub fn get_preset_names(&self) -> Vec<String>{
+        if !self.is_preset(){
+            return vec![];
+        }
+        unsafe{
+            let names = gst_preset_get_preset_names(self.gst_element() as *mut GstPreset);
+            let mut ret = vec![];
+            if names != ptr::null_mut(){
+                let mut name = names;
+                while *name != ptr::null_mut(){
+                    ret.push(from_c_str!(*name).to_string());
+                    name = name.offset(1);
+                }
+                g_strfreev(names);
+            }
+            ret
+        }
+    }
+
+    /// Sets the element's properties to the ones stored in the preset
+    pub fn load_preset(&mut self, name: &str) -> bool{
+        if !self.is_preset(){
+            return false;
+        }
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            gst_preset_load_preset(self.gst_element_mut() as *mut GstPreset, cname.as_ptr()) != 0
+        }
+    }
+
+    /// Saves the current properties as a preset in the user's preset
+    /// directory, overwriting any preset with the same name
+    pub fn save_preset(&mut self, name: &str) -> bool{
+        if !self.is_preset(){
+            return false;
+        }
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            gst_preset_save_preset(self.gst_element_mut() as *mut GstPreset, cname.as_ptr()) != 0
+        }
+    }
+
+    pub fn rename_preset(&mut self, old_name: &str, new_name: &str) -> bool{
+        if !self.is_preset(){
+            return false;
+        }
+        let cold_name = CString::new(old_name).unwrap();
+        let cnew_name = CString::new(new_name).unwrap();
+        unsafe{
+            gst_preset_rename_preset(self.gst_element_mut() as *mut GstPreset, cold_name.as_ptr(), cnew_name.as_ptr()) != 0
+        }
+    }
+
+    pub fn delete_preset(&mut self, name: &str) -> bool{
+        if !self.is_preset(){
+            return false;
+        }
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            gst_preset_delete_preset(self.gst_element_mut() as *mut GstPreset, cname.as_ptr()) != 0
+        }
+    }
+
+    /// Returns a piece of metadata of a preset, tag is usually "comment"
+    pub fn get_preset_meta(&self, name: &str, tag: &str) -> Option<String>{
+        if !self.is_preset(){
+            return None;
+        }
+        let cname = CString::new(name).unwrap();
+        let ctag = CString::new(tag).unwrap();
+        unsafe{
+            let mut value: *mut gchar = ptr::null_mut();
+            gst_preset_get_meta(self.gst_element() as *mut GstPreset, cname.as_ptr(), ctag.as_ptr(), &mut value);
+            if value != ptr::null_mut(){
+                let ret = from_c_str!(value).to_string();
+                g_free(value as gpointer);
+                Some(ret)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Sets a piece of metadata of a preset, None removes it
+    pub fn set_preset_meta(&mut self, name: &str, tag: &str, value: Option<&str>) -> bool{
+        if !self.is_preset(){
+            return false;
+        }
+        let cname = CString::new(name).unwrap();
+        let ctag = CString::new(tag).unwrap();
+        let cvalue = value.map(|value| CString::new(value).unwrap());
+        unsafe{
+            let value = cvalue.as_ref().map(|value| value.as_ptr()).unwrap_or(ptr::null());
+            gst_preset_set_meta(self.gst_element_mut() as *mut GstPreset, cname.as_ptr(), ctag.as_ptr(), value) != 0
+        }
+    }
+
+    /// Sets an application specific directory to look for presets in,
+    /// presets in it take precedence over the system ones
+    pub fn set_preset_app_dir(dir: &str) -> bool{
+        let cdir = CString::new(dir).unwrap();
+        unsafe{
+            gst_preset_set_app_dir(cdir.as_ptr()) != 0
+        }
+    }
+
+    /// True if the element implements GstChildProxy, like bins or
+    /// elements with request pads such as compositor, so its children
+    /// can be accessed by name or index
+    pub fn is_child_proxy(&self) -> bool{
+        unsafe{
+            g_type_check_instance_is_a(self.gst_element() as *mut GTypeInstance, gst_child_proxy_get_type()) != 0
+        }
+    }
+
+    /// Returns the child with the given name, eg: an element in a bin
+    /// or a sink pad of compositor
+    pub fn child_by_name(&self, name: &str) -> Option<Object>{
+        if !self.is_child_proxy(){
+            return None;
+        }
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            child_to_object(gst_child_proxy_get_child_by_name(self.gst_element() as *mut GstChildProxy, cname.as_ptr()))
+        }
+    }
+
+    pub fn child_by_index(&self, index: u32) -> Option<Object>{
+        if !self.is_child_proxy(){
+            return None;
+        }
+        unsafe{
+            child_to_object(gst_child_proxy_get_child_by_index(self.gst_element() as *mut GstChildProxy, index))
+        }
+    }
+
+    pub fn children_count(&self) -> u32{
+        if !self.is_child_proxy(){
+            return 0;
+        }
+        unsafe{
+            gst_child_proxy_get_children_count(self.gst_element() as *mut GstChildProxy)
+        }
+    }
+
+    /// Sets a property of a child using a path like "child::property",
+    /// which can be nested, eg: "sink_0::alpha" in compositor or
+    /// "decoder::parser::config-interval". The value is converted to
+    /// the type of the property when possible. Returns false if the path
+    /// doesn't lead to a property or the value can't be converted.
+    pub fn set_child_property<T: ToValue + ?Sized>(&mut self, path: &str, value: &T) -> bool{
+        let pspec = match self.child_property_spec(path){
+            Some(pspec) => pspec,
+            None => return false,
+        };
+        let cpath = CString::new(path).unwrap();
+        unsafe{
+            match value.to_value().convert((*pspec).value_type){
+                Some(value) => {
+                    gst_child_proxy_set_property(self.gst_element_mut() as *mut GstChildProxy, cpath.as_ptr(), value.gst_value());
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    /// Returns the value of a property of a child using a path like
+    /// "child::property", see set_child_property
+    pub fn get_child_property(&self, path: &str) -> Option<Value>{
+        let pspec = match self.child_property_spec(path){
+            Some(pspec) => pspec,
+            None => return None,
+        };
+        let cpath = CString::new(path).unwrap();
+        unsafe{
+            let mut value = Value::new((*pspec).value_type);
+            gst_child_proxy_get_property(self.gst_element() as *mut GstChildProxy, cpath.as_ptr(), value.gst_value_mut());
+            Some(value)
+        }
+    }
+
+    fn child_property_spec(&self, path: &str) -> Option<*mut GParamSpec>{
+        if !self.is_child_proxy(){
+            return None;
+        }
+        let cpath = CString::new(path).unwrap();
+        unsafe{
+            let mut target: *mut GObject = ptr::null_mut();
+            let mut pspec: *mut GParamSpec = ptr::null_mut();
+            if gst_child_proxy_lookup(self.gst_element() as *mut GstChildProxy, cpath.as_ptr(), &mut target, &mut pspec) != 0{
+                // the pspec is owned by the target's class which outlives
+                // the target itself
+                g_object_unref(target as gpointer);
+                Some(pspec)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Whether the element implements the GstURIHandler interface, like
+    /// filesrc, souphttpsrc or udpsink
+    pub fn is_uri_handler(&self) -> bool{
+        unsafe{
+            g_type_check_instance_is_a(self.gst_element() as *mut GTypeInstance, gst_uri_handler_get_type()) != 0
+        }
+    }
+
+    /// Whether the element reads from or writes to its URI, None if it
+    /// isn't a URI handler
+    pub fn uri_type(&self) -> Option<URIType>{
+        if !self.is_uri_handler(){
+            return None;
+        }
+        unsafe{
+            Some(URIType::from_gst(gst_uri_handler_get_uri_type(self.gst_element() as *mut GstURIHandler)))
+        }
+    }
+
+    /// The protocols the element handles, eg: ["rtsp", "rtspu", ...]
+    pub fn uri_protocols(&self) -> Vec<String>{
+        if !self.is_uri_handler(){
+            return vec![];
+        }
+        let mut protocols = vec![];
+        unsafe{
+            let mut protocol = gst_uri_handler_get_protocols(self.gst_element() as *mut GstURIHandler);
+            while protocol != ptr::null() && *protocol != ptr::null(){
+                protocols.push(from_c_str!(*protocol).to_string());
+                protocol = protocol.offset(1);
+            }
+        }
+        protocols
+    }
+
+    /// The URI the element reads from or writes to
+    pub fn uri(&self) -> Option<String>{
+        if !self.is_uri_handler(){
+            return None;
+        }
+        unsafe{
+            let uri = gst_uri_handler_get_uri(self.gst_element() as *mut GstURIHandler);
+            if uri != ptr::null_mut(){
+                let ret = from_c_str!(uri).to_string();
+                g_free(uri as gpointer);
+                Some(ret)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Sets the URI the element reads from or writes to, usually only
+    /// possible in the NULL or READY states
+    pub fn set_uri(&mut self, uri: &str) -> ::Result<()>{
+        if !self.is_uri_handler(){
+            return Err(::Error::new(0, 0, "The element doesn't handle URIs"));
+        }
+        let curi = CString::new(uri).unwrap();
+        unsafe{
+            let mut error: *mut GError = ptr::null_mut();
+            if gst_uri_handler_set_uri(self.gst_element_mut() as *mut GstURIHandler, curi.as_ptr(), &mut error) != 0{
+                Ok(())
+            }else if error != ptr::null_mut(){
+                Err(::Error::new_from_g_error(error))
+            }else{
+                Err(::Error::new(0, 0, &format!("Couldn't set uri {}", uri)))
+            }
+        }
+    }
+
     /// Returns a const raw pointer to the internal GstElement
     pub unsafe fn gst_element(&self) -> *const GstElement{
-        self.element.gst_object() as *const GstElement
+        self.element.as_ptr()
     }
 
     /// Returns a mutable raw pointer to the internal GstElement
     pub unsafe fn gst_element_mut(&mut self) -> *mut GstElement{
-        self.element.gst_object_mut() as *mut GstElement
+        self.element.as_mut_ptr()
+    }
+}
+
+/// Wraps a child returned by the GstChildProxy functions, which can be
+/// any GObject, if it's a GstObject
+unsafe fn child_to_object(child: *mut GObject) -> Option<Object>{
+    if child == ptr::null_mut(){
+        None
+    }else if g_type_check_instance_is_a(child as *mut GTypeInstance, gst_object_get_type()) != 0{
+        Object::new(child as *mut GstObject)
+    }else{
+        g_object_unref(child as gpointer);
+        None
     }
 }
 
 impl ::Transfer for Element{
     unsafe fn transfer(self) -> *mut GstElement{
-        self.element.transfer() as *mut GstElement
+        self.element.transfer()
     }
 }
 
@@ -429,41 +1119,51 @@ impl Reference for Element{
     }
 }
 
+impl Downgrade for Element{
+    unsafe fn gst_object_ptr(&self) -> *mut GstObject{
+        self.gst_element() as *mut GstObject
+    }
+
+    unsafe fn from_gst_object_ptr(object: *mut GstObject) -> Option<Element>{
+        Element::new_from_gst_element(object as *mut GstElement)
+    }
+}
+
 impl AsRef<Object> for Element{
     fn as_ref(&self) -> &Object{
-        &self.element
+        self.element.upcast_ref()
     }
 }
 
 impl AsMut<Object> for Element{
     fn as_mut(&mut self) -> &mut Object{
-        &mut self.element
+        self.element.upcast_mut()
     }
 }
 
 impl From<Element> for Object{
     fn from(b: Element) -> Object{
-        b.element
+        b.element.upcast()
     }
 }
 
 impl Deref for Element{
     type Target = Object;
     fn deref(&self) -> &Object{
-        &self.element
+        self.element.upcast_ref()
     }
 }
 
 impl DerefMut for Element{
     fn deref_mut(&mut self) -> &mut Object{
-        &mut self.element
+        self.element.upcast_mut()
     }
 }
 
 impl ::FromGValue for Element{
     fn from_gvalue(value: &GValue) -> Option<Element>{
         unsafe{
-            if g_type_check_value_holds(mem::transmute(value), gst_element_get_type()) != 0{
+            if g_type_check_value_holds(value as *const GValue as *mut GValue, gst_element_get_type()) != 0{
                 let ptr = g_value_get_object(value);
                 gst_object_ref(ptr);
                 Element::new_from_gst_element(ptr as *mut GstElement)
@@ -0,0 +1,197 @@
+use ffi::*;
+use util::*;
+use element::Element;
+use caps::Caps;
+use pad_template::PadTemplate;
+use registry::{PluginFeature, objects_from_list};
+use reference::Reference;
+
+use std::ops::{Deref, DerefMut};
+
+/// Creates elements of a certain type and describes them: their name,
+/// class, pads... Useful to check at runtime if an element is available
+/// and to find elements that handle certain caps
+pub struct ElementFactory{
+    factory: PluginFeature
+}
+
+impl ElementFactory{
+    pub unsafe fn new(factory: *mut GstElementFactory) -> Option<ElementFactory>{
+        PluginFeature::new(factory as *mut GstPluginFeature).map(ElementFactory::new_from_feature)
+    }
+
+    pub fn new_from_feature(feature: PluginFeature) -> ElementFactory{
+        ElementFactory{ factory: feature }
+    }
+
+    /// Finds the factory with the given name, eg: "x264enc", returns
+    /// None if the plugin providing it isn't installed
+    pub fn find(name: &str) -> Option<ElementFactory>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            ElementFactory::new(gst_element_factory_find(cname.as_ptr()))
+        }
+    }
+
+    /// Returns all the factories of the given type, a combination of the
+    /// GST_ELEMENT_FACTORY_TYPE_* constants, with at least the given
+    /// rank sorted from highest to lowest rank.
+    ///
+    /// ```ignore
+    /// let encoders = gst::ElementFactory::list_get_elements(
+    ///     gst::ffi::GST_ELEMENT_FACTORY_TYPE_VIDEO_ENCODER, gst::ffi::GST_RANK_MARGINAL);
+    /// ```
+    pub fn list_get_elements(factory_type: GstElementFactoryListType, min_rank: u32) -> Vec<ElementFactory>{
+        unsafe{
+            let list = gst_element_factory_list_get_elements(factory_type, min_rank);
+            let mut factories = objects_from_list(list, |factory| ElementFactory::new(factory as *mut GstElementFactory));
+            g_list_free(list);
+            factories.sort_by(|a, b| b.rank().cmp(&a.rank()).then_with(|| a.name().cmp(&b.name())));
+            factories
+        }
+    }
+
+    /// Keeps only the factories that have a pad template in direction
+    /// that can handle caps, if subset_only is true the template caps
+    /// have to be a superset of caps
+    pub fn list_filter(factories: &[ElementFactory], caps: &Caps, direction: GstPadDirection, subset_only: bool) -> Vec<ElementFactory>{
+        factories.iter().filter(|factory| {
+            let can_handle = |all: bool| unsafe{
+                let factory = factory.gst_elementfactory() as *mut GstElementFactory;
+                let caps = caps.gst_caps();
+                match (direction, all){
+                    (GST_PAD_SINK, true) => gst_element_factory_can_sink_all_caps(factory, caps),
+                    (GST_PAD_SINK, false) => gst_element_factory_can_sink_any_caps(factory, caps),
+                    (_, true) => gst_element_factory_can_src_all_caps(factory, caps),
+                    (_, false) => gst_element_factory_can_src_any_caps(factory, caps),
+                }
+            };
+            can_handle(subset_only) != 0
+        }).map(|factory| factory.reference()).collect()
+    }
+
+    /// Creates a new element, None if the plugin couldn't be loaded
+    pub fn create(&self, name: &str) -> Option<Element>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let name = if name != "" { cname.as_ptr() } else { ptr::null() };
+            let element = gst_element_factory_create(self.gst_elementfactory() as *mut GstElementFactory, name);
+            if element == ptr::null_mut(){
+                return None;
+            }
+            // the new element is floating, like in Element::new
+            gst_object_ref_sink(element as gpointer);
+            Element::new_from_gst_element(element)
+        }
+    }
+
+    /// Returns one of the metadata fields of the element, eg: "long-name",
+    /// "klass", "description" or "author"
+    pub fn metadata(&self, key: &str) -> Option<String>{
+        let ckey = CString::new(key).unwrap();
+        unsafe{
+            let value = gst_element_factory_get_metadata(self.gst_elementfactory() as *mut GstElementFactory, ckey.as_ptr());
+            if value != ptr::null(){
+                Some(from_c_str!(value).to_string())
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn long_name(&self) -> String{
+        self.metadata("long-name").unwrap_or(String::new())
+    }
+
+    /// Class of the element, eg: "Codec/Decoder/Video/Hardware"
+    pub fn klass(&self) -> String{
+        self.metadata("klass").unwrap_or(String::new())
+    }
+
+    pub fn description(&self) -> String{
+        self.metadata("description").unwrap_or(String::new())
+    }
+
+    /// Returns true if the factory is of the given type, a combination
+    /// of the GST_ELEMENT_FACTORY_TYPE_* constants
+    pub fn is_type(&self, factory_type: GstElementFactoryListType) -> bool{
+        unsafe{
+            gst_element_factory_list_is_type(self.gst_elementfactory() as *mut GstElementFactory, factory_type) != 0
+        }
+    }
+
+    /// The pad templates of the elements created by this factory,
+    /// without loading the plugin
+    pub fn static_pad_templates(&self) -> Vec<PadTemplate>{
+        unsafe{
+            let list = gst_element_factory_get_static_pad_templates(self.gst_elementfactory() as *mut GstElementFactory);
+            objects_from_list(list as *mut GList, |template| {
+                let template = gst_static_pad_template_get(template as *mut GstStaticPadTemplate);
+                if template != ptr::null_mut(){
+                    gst_object_ref_sink(template as gpointer);
+                }
+                PadTemplate::new_from_gst_pad_template(template)
+            })
+        }
+    }
+
+    /// Returns true if the elements created by this factory implement
+    /// the interface with the given name, eg: "GstVideoOverlay"
+    pub fn has_interface(&self, interface: &str) -> bool{
+        let cinterface = CString::new(interface).unwrap();
+        unsafe{
+            gst_element_factory_has_interface(self.gst_elementfactory() as *mut GstElementFactory, cinterface.as_ptr()) != 0
+        }
+    }
+
+    pub fn can_sink_any_caps(&self, caps: &Caps) -> bool{
+        unsafe{
+            gst_element_factory_can_sink_any_caps(self.gst_elementfactory() as *mut GstElementFactory, caps.gst_caps()) != 0
+        }
+    }
+
+    pub fn can_src_any_caps(&self, caps: &Caps) -> bool{
+        unsafe{
+            gst_element_factory_can_src_any_caps(self.gst_elementfactory() as *mut GstElementFactory, caps.gst_caps()) != 0
+        }
+    }
+
+    pub unsafe fn gst_elementfactory(&self) -> *const GstElementFactory{
+        self.factory.gst_pluginfeature() as *const GstElementFactory
+    }
+
+    pub unsafe fn gst_elementfactory_mut(&mut self) -> *mut GstElementFactory{
+        self.factory.gst_pluginfeature_mut() as *mut GstElementFactory
+    }
+}
+
+impl Reference for ElementFactory{
+    fn reference(&self) -> ElementFactory{
+        ElementFactory{ factory: self.factory.reference() }
+    }
+}
+
+impl AsRef<PluginFeature> for ElementFactory{
+    fn as_ref(&self) -> &PluginFeature{
+        &self.factory
+    }
+}
+
+impl From<ElementFactory> for PluginFeature{
+    fn from(f: ElementFactory) -> PluginFeature{
+        f.factory
+    }
+}
+
+impl Deref for ElementFactory{
+    type Target = PluginFeature;
+    fn deref(&self) -> &PluginFeature{
+        &self.factory
+    }
+}
+
+impl DerefMut for ElementFactory{
+    fn deref_mut(&mut self) -> &mut PluginFeature{
+        &mut self.factory
+    }
+}
@@ -0,0 +1,336 @@
+use ffi::*;
+use util::*;
+
+use bin::Bin;
+use element::Element;
+use pipeline::Pipeline;
+use pad::Pad;
+use caps::Caps;
+use encoding_profile::EncodingProfile;
+use appsrc::AppSrc;
+use buffer::Buffer;
+use videoinfo::VideoInfo;
+use audioinfo::AudioInfo;
+use error::{Error, Result};
+use value::{Value, ToValue};
+use ::Transfer;
+use reference::Reference;
+
+use std::cmp;
+use std::ops::{Deref, DerefMut};
+
+const GST_SECOND: u64 = 1_000_000_000;
+
+/// Wrapper for encodebin, which plugs the encoders and muxer needed to
+/// produce the format described by an EncodingProfile.
+///
+/// Raw streams are fed through request pads, the encoded and muxed
+/// stream comes out of its "src" pad:
+///
+/// ```ignore
+/// let mut encodebin = gst::EncodeBin::new("encoder").unwrap();
+/// encodebin.set_profile(&profile.into());
+/// let mut video_sink = encodebin.request_pad_for_caps(&video_caps).unwrap();
+/// ```
+pub struct EncodeBin{
+    encodebin: Bin
+}
+
+impl EncodeBin{
+    pub fn new(name: &str) -> Option<EncodeBin>{
+        Element::new("encodebin", name).and_then(|element| unsafe{
+            Bin::new_from_gst_bin(element.transfer() as *mut GstBin)
+        }).map(|bin| EncodeBin{ encodebin: bin })
+    }
+
+    pub fn new_from_bin(bin: Bin) -> EncodeBin{
+        EncodeBin{ encodebin: bin }
+    }
+
+    /// Profile to encode to, has to be set before requesting pads
+    pub fn set_profile(&mut self, profile: &EncodingProfile){
+        self.set_value("profile", &profile.to_value());
+    }
+
+    pub fn profile(&self) -> Option<EncodingProfile>{
+        self.get_value("profile").and_then(|value| value.get::<EncodingProfile>())
+    }
+
+    /// Don't decode and encode again streams that already have the
+    /// format of the profile
+    pub fn set_avoid_reencoding(&mut self, avoid: bool){
+        self.set("avoid-reencoding", avoid);
+    }
+
+    /// Returns a sink pad for a stream with the given caps, either raw
+    /// or already encoded. None if the profile has no stream that can
+    /// be produced from them
+    pub fn request_pad_for_caps(&mut self, caps: &Caps) -> Option<Pad>{
+        self.emit_with_values("request-pad", &[caps.to_value()])
+            .and_then(|pad| pad.get::<Pad>())
+    }
+
+    /// Returns a sink pad for the stream profile with the given name
+    pub fn request_profile_pad(&mut self, profile_name: &str) -> Option<Pad>{
+        self.emit_with_values("request-profile-pad", &[Value::from(profile_name)])
+            .and_then(|pad| pad.get::<Pad>())
+    }
+
+    /// Returns a sink pad for the first video stream of the profile
+    pub fn request_video_pad(&mut self) -> Option<Pad>{
+        self.request_pad("video_%u")
+    }
+
+    /// Returns a sink pad for the first audio stream of the profile
+    pub fn request_audio_pad(&mut self) -> Option<Pad>{
+        self.request_pad("audio_%u")
+    }
+}
+
+/// Builds a pipeline that decodes src_uri and encodes it to the file at
+/// dest_location with profile. Streams that the profile has no room for
+/// are dropped. Run the pipeline and wait for EOS on its bus:
+///
+/// ```ignore
+/// let mut pipeline = gst::encodebin::transcode("file:///tmp/in.mkv", "/tmp/out.mp4", &profile).unwrap();
+/// pipeline.play();
+/// ```
+pub fn transcode(src_uri: &str, dest_location: &str, profile: &EncodingProfile) -> Result<Pipeline>{
+    let mut pipeline = Pipeline::new("transcode").ok_or(Error::new(0, 0, "couldn't create pipeline"))?;
+    let mut decodebin = Element::new("uridecodebin", "decoder").ok_or(Error::new(0, 0, "couldn't create uridecodebin"))?;
+    let mut encodebin = EncodeBin::new("encoder").ok_or(Error::new(0, 0, "couldn't create encodebin"))?;
+    let mut filesink = Element::new("filesink", "sink").ok_or(Error::new(0, 0, "couldn't create filesink"))?;
+    decodebin.set("uri", src_uri);
+    filesink.set("location", dest_location);
+    encodebin.set_profile(profile);
+
+    let mut encoder = encodebin.reference();
+    let handle = decodebin.connect_values("pad-added", move |args| {
+        if let Some(mut pad) = args.get(1).and_then(|pad| pad.get::<Pad>()){
+            let sink = pad.query_caps(None).and_then(|caps| encoder.request_pad_for_caps(&caps));
+            if let Some(mut sink) = sink{
+                let _ = pad.link(&mut sink);
+            }
+        }
+        None
+    });
+    if let Some(handle) = handle{
+        handle.forget();
+    }
+
+    if !pipeline.add(decodebin) || !pipeline.add(encodebin.reference()) || !pipeline.add(filesink.reference()){
+        return Err(Error::new(0, 0, "couldn't add elements to the pipeline"));
+    }
+    if !encodebin.link(&mut filesink){
+        return Err(Error::new(0, 0, "couldn't link encodebin to filesink"));
+    }
+    Ok(pipeline)
+}
+
+/// Encodes raw video frames and audio samples produced by the
+/// application to a file, the mirror image of decoding with appsink.
+/// Buffers are timestamped from the frame rate and sample rate of the
+/// infos so they only need the raw data:
+///
+/// ```ignore
+/// let info = gst::Caps::from_string("video/x-raw,format=RGBA,width=640,height=480,framerate=30/1").unwrap().video_info().unwrap();
+/// let mut recorder = gst::encodebin::Recorder::new("/tmp/out.mp4", &profile, Some(&info), None).unwrap();
+/// for frame in frames{
+///     recorder.push_video(gst::Buffer::from_slice_zero_copy(frame).unwrap()).unwrap();
+/// }
+/// recorder.finish().unwrap();
+/// ```
+///
+/// With a variable frame rate, 0/1, video buffers keep the timestamps
+/// they are pushed with.
+pub struct Recorder{
+    pipeline: Pipeline,
+    video: Option<(AppSrc, VideoInfo)>,
+    audio: Option<(AppSrc, AudioInfo)>,
+    video_frames: u64,
+    audio_samples: u64,
+}
+
+impl Recorder{
+    /// Starts recording to the file at path with profile, with a video
+    /// stream if video is passed and an audio one if audio is
+    pub fn new(path: &str, profile: &EncodingProfile, video: Option<&VideoInfo>, audio: Option<&AudioInfo>) -> Result<Recorder>{
+        let mut pipeline = Pipeline::new("recorder").ok_or(Error::new(0, 0, "couldn't create pipeline"))?;
+        let mut encodebin = EncodeBin::new("encoder").ok_or(Error::new(0, 0, "couldn't create encodebin"))?;
+        let mut filesink = Element::new("filesink", "sink").ok_or(Error::new(0, 0, "couldn't create filesink"))?;
+        filesink.set("location", path);
+        encodebin.set_profile(profile);
+        if !pipeline.add(encodebin.reference()) || !pipeline.add(filesink.reference()){
+            return Err(Error::new(0, 0, "couldn't add elements to the pipeline"));
+        }
+        if !encodebin.link(&mut filesink){
+            return Err(Error::new(0, 0, "couldn't link encodebin to filesink"));
+        }
+
+        let video = match video{
+            Some(info) => {
+                let caps = info.to_caps().ok_or(Error::new(0, 0, "invalid video info"))?;
+                Some((Recorder::add_source(&mut pipeline, &mut encodebin, "video_src", &caps)?, *info))
+            }
+            None => None
+        };
+        let audio = match audio{
+            Some(info) => {
+                let caps = info.to_caps().ok_or(Error::new(0, 0, "invalid audio info"))?;
+                Some((Recorder::add_source(&mut pipeline, &mut encodebin, "audio_src", &caps)?, *info))
+            }
+            None => None
+        };
+
+        if pipeline.play() == GST_STATE_CHANGE_FAILURE{
+            let mut bus = pipeline.bus().ok_or(Error::new(0, 0, "couldn't get pipeline bus"))?;
+            return Err(bus.pop_error().unwrap_or(Error::new(0, 0, "couldn't start recording")));
+        }
+        Ok(Recorder{
+            pipeline: pipeline,
+            video: video,
+            audio: audio,
+            video_frames: 0,
+            audio_samples: 0,
+        })
+    }
+
+    fn add_source(pipeline: &mut Pipeline, encodebin: &mut EncodeBin, name: &str, caps: &Caps) -> Result<AppSrc>{
+        let mut appsrc = AppSrc::new(name).ok_or(Error::new(0, 0, "couldn't create appsrc"))?;
+        appsrc.set_caps(caps);
+        appsrc.set_from_str("format", "time");
+        // wait for the encoder instead of queueing every frame in memory
+        appsrc.set("block", true as gboolean);
+        if !pipeline.add(appsrc.reference()){
+            return Err(Error::new(0, 0, "couldn't add appsrc to the pipeline"));
+        }
+        let mut sink = encodebin.request_pad_for_caps(caps).ok_or(Error::new(0, 0, "the profile has no stream for the caps"))?;
+        let mut src = appsrc.static_pad("src").ok_or(Error::new(0, 0, "appsrc has no src pad"))?;
+        src.link(&mut sink).map_err(|_| Error::new(0, 0, "couldn't link appsrc to encodebin"))?;
+        Ok(appsrc)
+    }
+
+    /// Encodes the next video frame, the recorder has to have been
+    /// created with a VideoInfo
+    pub fn push_video(&mut self, buffer: Buffer) -> Result<()>{
+        let mut buffer = buffer.make_writable();
+        let ret = match self.video{
+            Some((ref mut appsrc, ref info)) => {
+                if info.fps_n() > 0{
+                    let frame_time = |frame| unsafe{
+                        gst_util_uint64_scale(frame, GST_SECOND * info.fps_d() as u64, info.fps_n() as u64)
+                    };
+                    let pts = frame_time(self.video_frames);
+                    buffer.set_pts(Some(pts));
+                    buffer.set_duration(Some(frame_time(self.video_frames + 1) - pts));
+                }
+                self.video_frames += 1;
+                appsrc.push_buffer(buffer)
+            }
+            None => return Err(Error::new(0, 0, "the recorder has no video stream"))
+        };
+        self.check_flow(ret)
+    }
+
+    /// Encodes the next interleaved audio samples, the recorder has to
+    /// have been created with an AudioInfo
+    pub fn push_audio(&mut self, buffer: Buffer) -> Result<()>{
+        let mut buffer = buffer.make_writable();
+        let ret = match self.audio{
+            Some((ref mut appsrc, ref info)) => {
+                let samples = buffer.size() / cmp::max(info.bpf(), 1) as u64;
+                let sample_time = |sample| unsafe{
+                    gst_util_uint64_scale(sample, GST_SECOND, info.rate() as u64)
+                };
+                let pts = sample_time(self.audio_samples);
+                buffer.set_pts(Some(pts));
+                buffer.set_duration(Some(sample_time(self.audio_samples + samples) - pts));
+                self.audio_samples += samples;
+                appsrc.push_buffer(buffer)
+            }
+            None => return Err(Error::new(0, 0, "the recorder has no audio stream"))
+        };
+        self.check_flow(ret)
+    }
+
+    fn check_flow(&mut self, ret: GstFlowReturn) -> Result<()>{
+        if ret == GST_FLOW_OK{
+            return Ok(());
+        }
+        let error = self.pipeline.bus().and_then(|mut bus| bus.pop_error());
+        Err(error.unwrap_or(Error::new(0, 0, "couldn't push buffer")))
+    }
+
+    /// Ends the streams and waits for the file to be finalized, eg: for
+    /// the muxer to write the index, then stops the pipeline
+    pub fn finish(mut self) -> Result<()>{
+        if let Some((ref mut appsrc, _)) = self.video{
+            appsrc.end_of_stream();
+        }
+        if let Some((ref mut appsrc, _)) = self.audio{
+            appsrc.end_of_stream();
+        }
+        let mut bus = self.pipeline.bus().ok_or(Error::new(0, 0, "couldn't get pipeline bus"))?;
+        bus.wait_eos(GST_CLOCK_TIME_NONE).map(|_| ())
+    }
+
+    /// The recording pipeline, eg: to tune the encoders
+    pub fn pipeline(&self) -> &Pipeline{
+        &self.pipeline
+    }
+}
+
+impl Drop for Recorder{
+    fn drop(&mut self){
+        self.pipeline.set_null_state();
+    }
+}
+
+impl ::Transfer for EncodeBin{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.encodebin.transfer()
+    }
+}
+
+impl Reference for EncodeBin{
+    fn reference(&self) -> EncodeBin{
+        EncodeBin{ encodebin: self.encodebin.reference() }
+    }
+}
+
+impl AsRef<Bin> for EncodeBin{
+    fn as_ref(&self) -> &Bin{
+        &self.encodebin
+    }
+}
+
+impl AsMut<Bin> for EncodeBin{
+    fn as_mut(&mut self) -> &mut Bin{
+        &mut self.encodebin
+    }
+}
+
+impl From<EncodeBin> for Bin{
+    fn from(e: EncodeBin) -> Bin{
+        e.encodebin
+    }
+}
+
+impl From<EncodeBin> for Element{
+    fn from(e: EncodeBin) -> Element{
+        e.encodebin.into()
+    }
+}
+
+impl Deref for EncodeBin{
+    type Target = Bin;
+    fn deref(&self) -> &Bin{
+        &self.encodebin
+    }
+}
+
+impl DerefMut for EncodeBin{
+    fn deref_mut(&mut self) -> &mut Bin{
+        &mut self.encodebin
+    }
+}
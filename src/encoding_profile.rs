@@ -0,0 +1,415 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use discoverer::DiscovererInfo;
+use reference::Reference;
+use value::{Value, ToValue, FromValue};
+use ::Transfer;
+
+use std::ops::Deref;
+
+/// Describes the format a stream should be encoded to: the caps of the
+/// encoded stream, optional restriction caps for the raw input and an
+/// optional encoder preset. Container profiles hold the profiles of
+/// the streams they mux.
+///
+/// Built from EncodingContainerProfile, EncodingVideoProfile and
+/// EncodingAudioProfile, eg: an MP4 file with H.264 and AAC:
+///
+/// ```ignore
+/// let profile = gst::EncodingContainerProfile::new("mp4", None, &gst::Caps::from_string("video/quicktime,variant=iso").unwrap())
+///     .add_profile(gst::EncodingVideoProfile::new(&gst::Caps::from_string("video/x-h264").unwrap())
+///         .with_restriction(&gst::Caps::from_string("video/x-raw,width=1280,height=720").unwrap()))
+///     .add_profile(gst::EncodingAudioProfile::new(&gst::Caps::from_string("audio/mpeg,mpegversion=4").unwrap()));
+/// ```
+pub struct EncodingProfile{
+    profile: *mut GstEncodingProfile
+}
+
+// Profiles are only modified through the builders, which own them, and
+// GObject reference counting is atomic
+unsafe impl Send for EncodingProfile {}
+unsafe impl Sync for EncodingProfile {}
+
+impl Drop for EncodingProfile{
+    fn drop(&mut self){
+        unsafe{
+            g_object_unref(self.profile as gpointer);
+        }
+    }
+}
+
+fn optional_cstr(s: Option<&str>) -> Option<CString>{
+    s.map(|s| CString::new(s).unwrap())
+}
+
+fn optional_ptr(s: &Option<CString>) -> *const gchar{
+    s.as_ref().map(|s| s.as_ptr()).unwrap_or(ptr::null())
+}
+
+unsafe fn optional_string(s: *const gchar) -> Option<String>{
+    if s != ptr::null(){
+        Some(from_c_str!(s).to_string())
+    }else{
+        None
+    }
+}
+
+impl EncodingProfile{
+    pub unsafe fn new(profile: *mut GstEncodingProfile) -> Option<EncodingProfile>{
+        if profile != ptr::null_mut(){
+            Some(EncodingProfile{ profile: profile })
+        }else{
+            None
+        }
+    }
+
+    /// Creates a profile that encodes to the same formats the
+    /// discovered uri uses, useful to remux or reencode files keeping
+    /// their format
+    pub fn from_discoverer(info: &DiscovererInfo) -> Option<EncodingProfile>{
+        unsafe{
+            EncodingProfile::new(gst_encoding_profile_from_discoverer(info.gst_discoverer_info()))
+        }
+    }
+
+    pub fn name(&self) -> Option<String>{
+        unsafe{
+            optional_string(gst_encoding_profile_get_name(self.profile))
+        }
+    }
+
+    pub fn description(&self) -> Option<String>{
+        unsafe{
+            optional_string(gst_encoding_profile_get_description(self.profile))
+        }
+    }
+
+    /// Caps of the encoded stream
+    pub fn format(&self) -> Option<Caps>{
+        unsafe{
+            Caps::new(gst_encoding_profile_get_format(self.profile))
+        }
+    }
+
+    /// Caps the raw input is converted to before being encoded
+    pub fn restriction(&self) -> Option<Caps>{
+        unsafe{
+            Caps::new(gst_encoding_profile_get_restriction(self.profile))
+        }
+    }
+
+    /// Name of the preset loaded on the encoder or muxer
+    pub fn preset(&self) -> Option<String>{
+        unsafe{
+            optional_string(gst_encoding_profile_get_preset(self.profile))
+        }
+    }
+
+    /// Number of times the stream is expected, 0 means any number
+    pub fn presence(&self) -> u32{
+        unsafe{
+            gst_encoding_profile_get_presence(self.profile)
+        }
+    }
+
+    /// Kind of profile: "container", "video" or "audio"
+    pub fn type_nick(&self) -> &str{
+        unsafe{
+            from_c_str!(gst_encoding_profile_get_type_nick(self.profile))
+        }
+    }
+
+    pub fn is_container(&self) -> bool{
+        unsafe{ self.is_a(gst_encoding_container_profile_get_type()) }
+    }
+
+    pub fn is_video(&self) -> bool{
+        unsafe{ self.is_a(gst_encoding_video_profile_get_type()) }
+    }
+
+    pub fn is_audio(&self) -> bool{
+        unsafe{ self.is_a(gst_encoding_audio_profile_get_type()) }
+    }
+
+    /// Profiles of the streams in a container profile, empty for other
+    /// profiles
+    pub fn profiles(&self) -> Vec<EncodingProfile>{
+        if !self.is_container(){
+            return vec![];
+        }
+        unsafe{
+            let mut profiles = vec![];
+            let mut node = gst_encoding_container_profile_get_profiles(self.profile as *mut GstEncodingContainerProfile);
+            while node != ptr::null(){
+                g_object_ref((*node).data);
+                if let Some(profile) = EncodingProfile::new((*node).data as *mut GstEncodingProfile){
+                    profiles.push(profile);
+                }
+                node = (*node).next;
+            }
+            profiles
+        }
+    }
+
+    unsafe fn is_a(&self, gtype: GType) -> bool{
+        g_type_check_instance_is_a(self.profile as *mut GTypeInstance, gtype) != 0
+    }
+
+    fn set_name(&mut self, name: &str){
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            gst_encoding_profile_set_name(self.profile, cname.as_ptr());
+        }
+    }
+
+    fn set_description(&mut self, description: &str){
+        let cdescription = CString::new(description).unwrap();
+        unsafe{
+            gst_encoding_profile_set_description(self.profile, cdescription.as_ptr());
+        }
+    }
+
+    fn set_preset(&mut self, preset: &str){
+        let cpreset = CString::new(preset).unwrap();
+        unsafe{
+            gst_encoding_profile_set_preset(self.profile, cpreset.as_ptr());
+        }
+    }
+
+    fn set_restriction(&mut self, restriction: &Caps){
+        unsafe{
+            gst_encoding_profile_set_restriction(self.profile, restriction.reference().transfer());
+        }
+    }
+
+    fn set_presence(&mut self, presence: u32){
+        unsafe{
+            gst_encoding_profile_set_presence(self.profile, presence);
+        }
+    }
+
+    pub unsafe fn gst_encoding_profile(&self) -> *mut GstEncodingProfile{
+        self.profile
+    }
+}
+
+impl Reference for EncodingProfile{
+    fn reference(&self) -> EncodingProfile{
+        unsafe{
+            g_object_ref(self.profile as gpointer);
+        }
+        EncodingProfile{ profile: self.profile }
+    }
+}
+
+impl Clone for EncodingProfile{
+    fn clone(&self) -> EncodingProfile{
+        self.reference()
+    }
+}
+
+impl ::Transfer<GstEncodingProfile> for EncodingProfile{
+    unsafe fn transfer(self) -> *mut GstEncodingProfile{
+        let profile = self.profile;
+        mem::forget(self);
+        profile
+    }
+}
+
+impl ToValue for EncodingProfile{
+    fn to_value(&self) -> Value{
+        unsafe{
+            let mut value = Value::new(gst_encoding_profile_get_type());
+            g_value_set_object(value.gst_value_mut(), self.profile as gpointer);
+            value
+        }
+    }
+}
+
+impl FromValue for EncodingProfile{
+    fn from_value(value: &Value) -> Option<EncodingProfile>{
+        unsafe{
+            if value.holds(gst_encoding_profile_get_type()){
+                EncodingProfile::new(g_value_dup_object(value.gst_value()) as *mut GstEncodingProfile)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+/// Builder for the profile of a container and the streams it muxes
+pub struct EncodingContainerProfile{
+    profile: EncodingProfile
+}
+
+impl EncodingContainerProfile{
+    /// format are the caps of the container, eg: video/x-matroska
+    pub fn new(name: &str, description: Option<&str>, format: &Caps) -> EncodingContainerProfile{
+        let cname = CString::new(name).unwrap();
+        let cdescription = optional_cstr(description);
+        unsafe{
+            let profile = gst_encoding_container_profile_new(cname.as_ptr(), optional_ptr(&cdescription),
+                format.gst_caps() as *mut GstCaps, ptr::null());
+            EncodingContainerProfile{ profile: EncodingProfile::new(profile as *mut GstEncodingProfile).unwrap() }
+        }
+    }
+
+    /// Adds the profile of a stream to mux, it's ignored if the
+    /// container already has an identical one
+    pub fn add_profile<P: Into<EncodingProfile>>(self, profile: P) -> EncodingContainerProfile{
+        unsafe{
+            gst_encoding_container_profile_add_profile(self.profile.profile as *mut GstEncodingContainerProfile,
+                profile.into().transfer());
+        }
+        self
+    }
+
+    /// Preset to load on the muxer
+    pub fn with_preset(mut self, preset: &str) -> EncodingContainerProfile{
+        self.profile.set_preset(preset);
+        self
+    }
+
+    pub fn with_description(mut self, description: &str) -> EncodingContainerProfile{
+        self.profile.set_description(description);
+        self
+    }
+}
+
+/// Builder for the profile of a video stream
+pub struct EncodingVideoProfile{
+    profile: EncodingProfile
+}
+
+impl EncodingVideoProfile{
+    /// format are the caps of the encoded video, eg: video/x-h264
+    pub fn new(format: &Caps) -> EncodingVideoProfile{
+        unsafe{
+            let profile = gst_encoding_video_profile_new(format.gst_caps() as *mut GstCaps, ptr::null(), ptr::null_mut(), 0);
+            EncodingVideoProfile{ profile: EncodingProfile::new(profile as *mut GstEncodingProfile).unwrap() }
+        }
+    }
+
+    /// Raw caps the video is converted to before encoding, eg: to scale
+    /// it to a certain size or change its framerate
+    pub fn with_restriction(mut self, restriction: &Caps) -> EncodingVideoProfile{
+        self.profile.set_restriction(restriction);
+        self
+    }
+
+    /// Preset to load on the encoder
+    pub fn with_preset(mut self, preset: &str) -> EncodingVideoProfile{
+        self.profile.set_preset(preset);
+        self
+    }
+
+    /// Number of times the stream has to be present in the container,
+    /// 0 for any
+    pub fn with_presence(mut self, presence: u32) -> EncodingVideoProfile{
+        self.profile.set_presence(presence);
+        self
+    }
+
+    pub fn with_name(mut self, name: &str) -> EncodingVideoProfile{
+        self.profile.set_name(name);
+        self
+    }
+
+    /// Keep the framerate of the input instead of producing a constant
+    /// framerate
+    pub fn with_variable_framerate(self, variable: bool) -> EncodingVideoProfile{
+        unsafe{
+            gst_encoding_video_profile_set_variableframerate(self.profile.profile as *mut GstEncodingVideoProfile, variable as gboolean);
+        }
+        self
+    }
+
+    /// Pass number for multipass encoders, 0 for single pass
+    pub fn with_pass(self, pass: u32) -> EncodingVideoProfile{
+        unsafe{
+            gst_encoding_video_profile_set_pass(self.profile.profile as *mut GstEncodingVideoProfile, pass);
+        }
+        self
+    }
+}
+
+/// Builder for the profile of an audio stream
+pub struct EncodingAudioProfile{
+    profile: EncodingProfile
+}
+
+impl EncodingAudioProfile{
+    /// format are the caps of the encoded audio, eg: audio/x-opus
+    pub fn new(format: &Caps) -> EncodingAudioProfile{
+        unsafe{
+            let profile = gst_encoding_audio_profile_new(format.gst_caps() as *mut GstCaps, ptr::null(), ptr::null_mut(), 0);
+            EncodingAudioProfile{ profile: EncodingProfile::new(profile as *mut GstEncodingProfile).unwrap() }
+        }
+    }
+
+    /// Raw caps the audio is converted to before encoding, eg: to
+    /// resample it or change the number of channels
+    pub fn with_restriction(mut self, restriction: &Caps) -> EncodingAudioProfile{
+        self.profile.set_restriction(restriction);
+        self
+    }
+
+    /// Preset to load on the encoder
+    pub fn with_preset(mut self, preset: &str) -> EncodingAudioProfile{
+        self.profile.set_preset(preset);
+        self
+    }
+
+    /// Number of times the stream has to be present in the container,
+    /// 0 for any
+    pub fn with_presence(mut self, presence: u32) -> EncodingAudioProfile{
+        self.profile.set_presence(presence);
+        self
+    }
+
+    pub fn with_name(mut self, name: &str) -> EncodingAudioProfile{
+        self.profile.set_name(name);
+        self
+    }
+}
+
+impl From<EncodingContainerProfile> for EncodingProfile{
+    fn from(p: EncodingContainerProfile) -> EncodingProfile{
+        p.profile
+    }
+}
+
+impl From<EncodingVideoProfile> for EncodingProfile{
+    fn from(p: EncodingVideoProfile) -> EncodingProfile{
+        p.profile
+    }
+}
+
+impl From<EncodingAudioProfile> for EncodingProfile{
+    fn from(p: EncodingAudioProfile) -> EncodingProfile{
+        p.profile
+    }
+}
+
+impl Deref for EncodingContainerProfile{
+    type Target = EncodingProfile;
+    fn deref(&self) -> &EncodingProfile{
+        &self.profile
+    }
+}
+
+impl Deref for EncodingVideoProfile{
+    type Target = EncodingProfile;
+    fn deref(&self) -> &EncodingProfile{
+        &self.profile
+    }
+}
+
+impl Deref for EncodingAudioProfile{
+    type Target = EncodingProfile;
+    fn deref(&self) -> &EncodingProfile{
+        &self.profile
+    }
+}
@@ -1,18 +1,155 @@
 use ffi::*;
 use std::result;
 use util::*;
-use std::fmt::{self,Debug,Formatter};
+use std::error;
+use std::ffi::NulError;
+use std::fmt::{self,Debug,Display,Formatter};
 
-unsafe impl Send for GError {}
+// the GError is owned and only freed on drop
 unsafe impl Send for Error {}
+// the GError is never modified once created
+unsafe impl Sync for Error {}
 
 pub struct Error{
-    error: *mut GError
+    error: *mut GError,
+    source_path: Option<String>,
+    debug: Option<String>,
+}
+
+/// The GStreamer error domains, the kind of problem an element ran into
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum ErrorDomain{
+    /// Problems in the core GStreamer library or a bug in an element
+    Core,
+    /// Problems in a library used by an element
+    Library,
+    /// Problems accessing a file, a device, the network...
+    Resource,
+    /// Problems with the data being processed, eg: a corrupt file
+    Stream,
+    /// An API that the version of GStreamer in use doesn't have
+    Unsupported,
+    /// Any other GError domain
+    Other,
+}
+
+macro_rules! error_enum(
+    ($name:ident, $($variant:ident = $value:expr),*) => (
+        #[repr(u32)]
+        #[derive(Copy,Clone,Debug,PartialEq)]
+        pub enum $name{
+            $($variant = $value),*
+        }
+
+        impl $name{
+            fn from_code(code: i32) -> Option<$name>{
+                match code as u32{
+                    $(x if x == $value => Some($name::$variant),)*
+                    _ => None
+                }
+            }
+        }
+    )
+);
+
+error_enum!(CoreError,
+    Failed = GST_CORE_ERROR_FAILED,
+    TooLazy = GST_CORE_ERROR_TOO_LAZY,
+    NotImplemented = GST_CORE_ERROR_NOT_IMPLEMENTED,
+    StateChange = GST_CORE_ERROR_STATE_CHANGE,
+    Pad = GST_CORE_ERROR_PAD,
+    Thread = GST_CORE_ERROR_THREAD,
+    Negotiation = GST_CORE_ERROR_NEGOTIATION,
+    Event = GST_CORE_ERROR_EVENT,
+    Seek = GST_CORE_ERROR_SEEK,
+    Caps = GST_CORE_ERROR_CAPS,
+    Tag = GST_CORE_ERROR_TAG,
+    MissingPlugin = GST_CORE_ERROR_MISSING_PLUGIN,
+    Clock = GST_CORE_ERROR_CLOCK,
+    Disabled = GST_CORE_ERROR_DISABLED);
+
+error_enum!(LibraryError,
+    Failed = GST_LIBRARY_ERROR_FAILED,
+    TooLazy = GST_LIBRARY_ERROR_TOO_LAZY,
+    Init = GST_LIBRARY_ERROR_INIT,
+    Shutdown = GST_LIBRARY_ERROR_SHUTDOWN,
+    Settings = GST_LIBRARY_ERROR_SETTINGS,
+    Encode = GST_LIBRARY_ERROR_ENCODE);
+
+error_enum!(ResourceError,
+    Failed = GST_RESOURCE_ERROR_FAILED,
+    TooLazy = GST_RESOURCE_ERROR_TOO_LAZY,
+    NotFound = GST_RESOURCE_ERROR_NOT_FOUND,
+    Busy = GST_RESOURCE_ERROR_BUSY,
+    OpenRead = GST_RESOURCE_ERROR_OPEN_READ,
+    OpenWrite = GST_RESOURCE_ERROR_OPEN_WRITE,
+    OpenReadWrite = GST_RESOURCE_ERROR_OPEN_READ_WRITE,
+    Close = GST_RESOURCE_ERROR_CLOSE,
+    Read = GST_RESOURCE_ERROR_READ,
+    Write = GST_RESOURCE_ERROR_WRITE,
+    Seek = GST_RESOURCE_ERROR_SEEK,
+    Sync = GST_RESOURCE_ERROR_SYNC,
+    Settings = GST_RESOURCE_ERROR_SETTINGS,
+    NoSpaceLeft = GST_RESOURCE_ERROR_NO_SPACE_LEFT,
+    NotAuthorized = GST_RESOURCE_ERROR_NOT_AUTHORIZED);
+
+error_enum!(StreamError,
+    Failed = GST_STREAM_ERROR_FAILED,
+    TooLazy = GST_STREAM_ERROR_TOO_LAZY,
+    NotImplemented = GST_STREAM_ERROR_NOT_IMPLEMENTED,
+    TypeNotFound = GST_STREAM_ERROR_TYPE_NOT_FOUND,
+    WrongType = GST_STREAM_ERROR_WRONG_TYPE,
+    CodecNotFound = GST_STREAM_ERROR_CODEC_NOT_FOUND,
+    Decode = GST_STREAM_ERROR_DECODE,
+    Encode = GST_STREAM_ERROR_ENCODE,
+    Demux = GST_STREAM_ERROR_DEMUX,
+    Mux = GST_STREAM_ERROR_MUX,
+    Format = GST_STREAM_ERROR_FORMAT,
+    Decrypt = GST_STREAM_ERROR_DECRYPT,
+    DecryptNoKey = GST_STREAM_ERROR_DECRYPT_NOKEY);
+
+/// The code of an error in its domain
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum ErrorCode{
+    Core(CoreError),
+    Library(LibraryError),
+    Resource(ResourceError),
+    Stream(StreamError),
+    /// An API that the version of GStreamer in use doesn't have
+    Unsupported,
+    /// A code from another domain or unknown to the bindings
+    Other(i32),
 }
 
 impl Debug for Error{
     fn fmt(&self, fmt: &mut Formatter) -> result::Result<(), fmt::Error>{
-        fmt.write_str(format!("gst::Error: domain: {}, code: {}, message: {}",self.domain(),self.code(),self.message()).as_ref())
+        fmt.write_str(format!("gst::Error: domain: {}, code: {:?}, message: {}",self.domain_name(),self.error_code(),self.message()).as_ref())?;
+        if let Some(ref source_path) = self.source_path{
+            fmt.write_str(format!(", source: {}", source_path).as_ref())?;
+        }
+        if let Some(ref debug) = self.debug{
+            fmt.write_str(format!(", debug: {}", debug).as_ref())?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Error{
+    fn fmt(&self, fmt: &mut Formatter) -> result::Result<(), fmt::Error>{
+        match self.source_path{
+            Some(ref source_path) => write!(fmt, "{}: {}", source_path, self.message()),
+            None => fmt.write_str(&self.message()),
+        }
+    }
+}
+
+impl error::Error for Error{}
+
+impl From<NulError> for Error{
+    fn from(err: NulError) -> Error{
+        unsafe{
+            Error::new(gst_core_error_quark(), GST_CORE_ERROR_FAILED as i32, &format!("{}", err))
+        }
     }
 }
 
@@ -28,20 +165,54 @@ impl Drop for Error{
 
 impl Error{
     pub fn new(domain: u32, code: i32, message: &str) -> Error{
-        let cmessage = CString::new(message).unwrap();
+        let cmessage = CString::new(message.replace('\0', "")).unwrap();
         unsafe{
-            Error{error: g_error_new(domain, code, cmessage.as_ptr())}
+            Error::new_from_g_error(g_error_new_literal(domain, code, cmessage.as_ptr()))
         }
     }
 
+    /// Error returned when api isn't available because GStreamer is
+    /// older than since, see ApiAvailability
+    pub fn unsupported(api: &str, since: (u32, u32, u32)) -> Error{
+        let (major, minor, micro, _) = ::version();
+        Error::new(unsupported_error_quark(), 0, &format!("{} needs GStreamer {}.{}.{} but {}.{}.{} is in use",
+            api, since.0, since.1, since.2, major, minor, micro))
+    }
+
+    /// True if the error comes from calling an API the version of
+    /// GStreamer in use doesn't have
+    pub fn is_unsupported(&self) -> bool{
+        self.error_domain() == ErrorDomain::Unsupported
+    }
+
     pub unsafe fn new_from_g_error(err: *mut GError) -> Error{
-        Error{ error: err }
+        Error{ error: err, source_path: None, debug: None }
+    }
+
+    /// Takes ownership of err and copies the path of the object that
+    /// posted it, eg: "/GstPipeline:pipeline0/GstFileSrc:filesrc0", and
+    /// the debug string, as returned by gst_message_parse_error
+    pub unsafe fn new_from_message(err: *mut GError, src: *mut GstObject, debug: *const gchar) -> Error{
+        let source_path = if src != ptr::null_mut(){
+            let path = gst_object_get_path_string(src);
+            let ret = from_c_str!(path).to_string();
+            g_free(path as gpointer);
+            Some(ret)
+        }else{
+            None
+        };
+        let debug = if debug != ptr::null(){
+            Some(from_c_str!(debug).to_string())
+        }else{
+            None
+        };
+        Error{ error: err, source_path: source_path, debug: debug }
     }
 
     pub fn message(&self) -> String{
         unsafe{
             if self.error != ptr::null_mut(){
-                from_c_str!(mem::transmute((*self.error).message)).to_string()
+                from_c_str!((*self.error).message).to_string()
             }else{
                 "".to_string()
             }
@@ -67,7 +238,145 @@ impl Error{
             }
         }
     }
+
+    /// The domain as a string, eg: "gst-resource-error-quark"
+    pub fn domain_name(&self) -> String{
+        unsafe{
+            let name = g_quark_to_string(self.domain());
+            if name != ptr::null(){
+                from_c_str!(name).to_string()
+            }else{
+                "".to_string()
+            }
+        }
+    }
+
+    /// Which of the GStreamer domains the error belongs to
+    pub fn error_domain(&self) -> ErrorDomain{
+        let domain = self.domain();
+        unsafe{
+            if domain == 0{
+                ErrorDomain::Other
+            }else if domain == gst_core_error_quark(){
+                ErrorDomain::Core
+            }else if domain == gst_library_error_quark(){
+                ErrorDomain::Library
+            }else if domain == gst_resource_error_quark(){
+                ErrorDomain::Resource
+            }else if domain == gst_stream_error_quark(){
+                ErrorDomain::Stream
+            }else if domain == unsupported_error_quark(){
+                ErrorDomain::Unsupported
+            }else{
+                ErrorDomain::Other
+            }
+        }
+    }
+
+    /// The code of the error, eg: ErrorCode::Resource(ResourceError::NotFound)
+    /// when a file doesn't exist
+    pub fn error_code(&self) -> ErrorCode{
+        let code = self.code();
+        let error_code = match self.error_domain(){
+            ErrorDomain::Core => CoreError::from_code(code).map(ErrorCode::Core),
+            ErrorDomain::Library => LibraryError::from_code(code).map(ErrorCode::Library),
+            ErrorDomain::Resource => ResourceError::from_code(code).map(ErrorCode::Resource),
+            ErrorDomain::Stream => StreamError::from_code(code).map(ErrorCode::Stream),
+            ErrorDomain::Unsupported => Some(ErrorCode::Unsupported),
+            ErrorDomain::Other => None,
+        };
+        error_code.unwrap_or(ErrorCode::Other(code))
+    }
+
+    /// Path of the element that posted the error if it came from a
+    /// message, eg: "/GstPipeline:pipeline0/GstFileSrc:filesrc0"
+    pub fn source_path(&self) -> Option<&str>{
+        self.source_path.as_ref().map(|path| path.as_str())
+    }
+
+    /// Additional information for debugging if it came from a message
+    pub fn debug(&self) -> Option<&str>{
+        self.debug.as_ref().map(|debug| debug.as_str())
+    }
 }
 
+fn unsupported_error_quark() -> GQuark{
+    unsafe{
+        g_quark_from_static_string(b"gst-rs-unsupported-error-quark\0".as_ptr() as *const gchar)
+    }
+}
 
 pub type Result<T> = result::Result<T,Error>;
+
+/// Error parsing a pipeline or bin description. Unless
+/// GST_PARSE_FLAG_FATAL_ERRORS is used, errors like an unknown element or
+/// property still produce whatever could be built, in partial
+pub struct ParseError<T>{
+    pub error: Error,
+    /// The pipeline or bin built despite the error, if any
+    pub partial: Option<T>,
+    /// Elements in the description whose plugins aren't installed
+    pub missing_elements: Vec<String>,
+}
+
+impl<T> Debug for ParseError<T>{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        fmt.debug_struct("ParseError")
+            .field("error", &self.error)
+            .field("partial", &self.partial.is_some())
+            .field("missing_elements", &self.missing_elements)
+            .finish()
+    }
+}
+
+impl<T> Display for ParseError<T>{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        Display::fmt(&self.error, fmt)
+    }
+}
+
+impl<T> error::Error for ParseError<T>{}
+
+impl<T> From<ParseError<T>> for Error{
+    fn from(err: ParseError<T>) -> Error{
+        err.error
+    }
+}
+
+/// Builds the result of a gst_parse_*_full call, taking ownership of
+/// element, error and context
+pub unsafe fn parse_result<T, F>(element: *mut GstElement, error: *mut GError, context: *mut GstParseContext, wrap: F) -> result::Result<T, ParseError<T>>
+    where F: FnOnce(*mut GstElement) -> Option<T>{
+    let result = if element != ptr::null_mut(){
+        gst_object_ref_sink(element as gpointer);
+        wrap(element)
+    }else{
+        None
+    };
+    let mut missing_elements = vec![];
+    if context != ptr::null_mut(){
+        let missing = gst_parse_context_get_missing_elements(context);
+        if missing != ptr::null_mut(){
+            let mut i = 0;
+            while *missing.offset(i) != ptr::null_mut(){
+                missing_elements.push(from_c_str!(*missing.offset(i)).to_string());
+                i += 1;
+            }
+            g_strfreev(missing);
+        }
+        gst_parse_context_free(context);
+    }
+    match (result, error != ptr::null_mut()){
+        (Some(result), false) => Ok(result),
+        (partial, true) => Err(ParseError{
+            error: Error::new_from_g_error(error),
+            partial: partial,
+            missing_elements: missing_elements,
+        }),
+        (None, false) => Err(ParseError{
+            error: Error::new(0, 0, "couldn't parse description"),
+            partial: None,
+            missing_elements: missing_elements,
+        }),
+    }
+}
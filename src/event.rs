@@ -0,0 +1,92 @@
+use ffi::*;
+use reference::Reference;
+use miniobject::MiniObject;
+
+use std::ops::{Deref, DerefMut};
+
+/// An event going through a pad, eg: the segment, caps or eos events
+/// seen by an event probe
+pub struct Event{
+    event: MiniObject<GstEvent>
+}
+
+impl Event{
+    pub unsafe fn new(event: *mut GstEvent) -> Option<Event>{
+        MiniObject::new(event)
+            .map(|miniobject| Event{ event: miniobject })
+    }
+
+    pub fn event_type(&self) -> GstEventType{
+        unsafe{
+            (*self.gst_event())._type
+        }
+    }
+
+    pub fn seqnum(&self) -> u32{
+        unsafe{
+            gst_event_get_seqnum(self.gst_event() as *mut GstEvent)
+        }
+    }
+
+    /// Marks the event with seqnum, the messages and events elements
+    /// produce in response to it carry the same seqnum. The event has to
+    /// be writable, returns false otherwise
+    pub fn set_seqnum(&mut self, seqnum: u32) -> bool{
+        if !self.is_writable(){
+            return false;
+        }
+        unsafe{
+            gst_event_set_seqnum(self.gst_event_mut(), seqnum);
+        }
+        true
+    }
+
+    pub fn make_writable(self) -> Event{
+        Event{ event: self.event.make_writable() }
+    }
+
+    pub unsafe fn gst_event(&self) -> *const GstEvent{
+        self.event.as_ptr()
+    }
+
+    pub unsafe fn gst_event_mut(&mut self) -> *mut GstEvent{
+        self.event.as_mut_ptr()
+    }
+}
+
+impl ::Transfer<GstEvent> for Event{
+    unsafe fn transfer(self) -> *mut GstEvent{
+        self.event.transfer()
+    }
+}
+
+impl Reference for Event{
+    fn reference(&self) -> Event{
+        Event{ event: self.event.reference() }
+    }
+}
+
+impl AsRef<MiniObject> for Event{
+    fn as_ref(&self) -> &MiniObject{
+        self.event.upcast_ref()
+    }
+}
+
+impl AsMut<MiniObject> for Event{
+    fn as_mut(&mut self) -> &mut MiniObject{
+        self.event.upcast_mut()
+    }
+}
+
+impl Deref for Event{
+    type Target = MiniObject;
+    fn deref(&self) -> &MiniObject{
+        self.event.upcast_ref()
+    }
+}
+
+impl DerefMut for Event{
+    fn deref_mut(&mut self) -> &mut MiniObject{
+        self.event.upcast_mut()
+    }
+}
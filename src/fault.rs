@@ -0,0 +1,196 @@
+use ffi::*;
+use util::*;
+
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use buffer::Buffer;
+use handle::ProbeHandle;
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// What a FaultInjector does to a buffer
+#[derive(Clone,Debug,PartialEq)]
+pub enum Fault{
+    /// Posts an error message with the text on behalf of the element
+    /// of the pad, as if the element failed, and drops the buffer
+    Error(String),
+    /// Blocks the streaming thread before letting the buffer pass, eg:
+    /// to simulate a stalled network source
+    Delay(Duration),
+    /// Drops the buffer
+    Drop,
+    /// Overwrites this number of bytes of the buffer at positions that
+    /// only depend on the buffer index, so runs are reproducible
+    Corrupt(usize),
+}
+
+/// When a fault is injected, counting the buffers going through the
+/// pad from 0
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum Trigger{
+    /// Only once, for the buffer with this index
+    AtBuffer(u64),
+    /// For every buffer with an index multiple of this one, excluding 0
+    EveryNth(u64),
+    /// Only once, for the first buffer with a pts equal or later than
+    /// this time in nanoseconds
+    AtTime(u64),
+    /// For every buffer
+    Always,
+}
+
+struct Rule{
+    trigger: Trigger,
+    fault: Fault,
+    done: bool,
+}
+
+impl Rule{
+    fn matches(&mut self, index: u64, pts: Option<u64>) -> bool{
+        if self.done{
+            return false;
+        }
+        let matches = match self.trigger{
+            Trigger::AtBuffer(at) => index == at,
+            Trigger::EveryNth(n) => n > 0 && index > 0 && index % n == 0,
+            Trigger::AtTime(time) => pts.map(|pts| pts >= time).unwrap_or(false),
+            Trigger::Always => true,
+        };
+        match self.trigger{
+            Trigger::AtBuffer(_) | Trigger::AtTime(_) => self.done = matches,
+            _ => (),
+        }
+        matches
+    }
+}
+
+struct State{
+    rules: Vec<Rule>,
+    buffers: u64,
+    injected: u64,
+}
+
+/// Injects errors, delays or corrupted data in the buffers going
+/// through a pad, to exercise the error handling and reconnection
+/// logic of an application deterministically, eg: in CI:
+///
+/// ```ignore
+/// let mut pad = source.static_pad("src").unwrap();
+/// let injector = gst::fault::FaultInjector::install(&mut pad).unwrap();
+/// injector.add(gst::fault::Trigger::AtBuffer(100), gst::fault::Fault::Error("connection lost".to_string()));
+/// injector.add(gst::fault::Trigger::EveryNth(10), gst::fault::Fault::Corrupt(16));
+/// pipeline.play();
+/// // the application should now reconnect after the error
+/// ```
+///
+/// When several rules match the same buffer they're applied in the
+/// order they were added, until one drops the buffer. The probe is
+/// removed when the FaultInjector is dropped.
+pub struct FaultInjector{
+    state: Arc<Mutex<State>>,
+    _probe: ProbeHandle,
+}
+
+impl FaultInjector{
+    pub fn install(pad: &mut Pad) -> Option<FaultInjector>{
+        let state = Arc::new(Mutex::new(State{
+            rules: vec![],
+            buffers: 0,
+            injected: 0,
+        }));
+        let probe_state = state.clone();
+        let probe = pad.add_probe(GST_PAD_PROBE_TYPE_BUFFER, move |pad: &mut Pad, info: &mut PadProbeInfo| {
+            let mut faults = vec![];
+            let index = {
+                let mut state = probe_state.lock().unwrap_or_else(|e| e.into_inner());
+                let index = state.buffers;
+                let pts = info.buffer().and_then(|buffer| buffer.pts());
+                for rule in state.rules.iter_mut(){
+                    if rule.matches(index, pts){
+                        faults.push(rule.fault.clone());
+                    }
+                }
+                state.buffers += 1;
+                state.injected += faults.len() as u64;
+                index
+            };
+            // applied without the lock so a delay doesn't block add()
+            for fault in faults{
+                match fault{
+                    Fault::Error(text) => {
+                        post_error(pad, &text);
+                        return PadProbeReturn::Drop;
+                    }
+                    Fault::Delay(duration) => thread::sleep(duration),
+                    Fault::Drop => return PadProbeReturn::Drop,
+                    Fault::Corrupt(bytes) => if let Some(buffer) = info.buffer_mut(){
+                        corrupt(buffer, bytes, index);
+                    },
+                }
+            }
+            PadProbeReturn::Ok
+        })?;
+        Some(FaultInjector{
+            state: state,
+            _probe: probe,
+        })
+    }
+
+    /// Adds a rule injecting fault when trigger matches, it applies
+    /// from the next buffer
+    pub fn add(&self, trigger: Trigger, fault: Fault){
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).rules.push(Rule{
+            trigger: trigger,
+            fault: fault,
+            done: false,
+        });
+    }
+
+    /// Removes all the rules, the buffers pass untouched from now on
+    pub fn clear(&self){
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).rules.clear();
+    }
+
+    /// Number of buffers that went through the pad since installed
+    pub fn buffers(&self) -> u64{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).buffers
+    }
+
+    /// Number of faults injected so far
+    pub fn injected(&self) -> u64{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).injected
+    }
+}
+
+// posts a stream error on behalf of the element of pad
+fn post_error(pad: &Pad, text: &str){
+    unsafe{
+        let element = gst_pad_get_parent_element(pad.gst_pad() as *mut GstPad);
+        if element == ptr::null_mut(){
+            return;
+        }
+        post_element_error(element, gst_stream_error_quark(), GST_STREAM_ERROR_FAILED as gint, text,
+            None, file!(), "post_error", line!());
+        gst_object_unref(element as gpointer);
+    }
+}
+
+// overwrites bytes positions of buffer chosen with a xorshift seeded
+// by the buffer index
+fn corrupt(buffer: &mut Buffer, bytes: usize, index: u64){
+    let mut seed = index.wrapping_mul(0x9E3779B97F4A7C15) | 1;
+    let _ = buffer.map_write(|map| {
+        let data = map.data_mut::<u8>();
+        if data.is_empty(){
+            return;
+        }
+        for _ in 0..bytes{
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let pos = (seed % data.len() as u64) as usize;
+            data[pos] ^= (seed >> 56) as u8 | 1;
+        }
+    });
+}
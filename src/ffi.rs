@@ -3888,7 +3888,7 @@ pub type GstAtomicQueue = Struct__GstAtomicQueue;
 pub type GstElement = Struct__GstElement;
 pub type GstElementClass = Struct__GstElementClass;
 #[repr(C)]
-#[derive(Clone,Copy,Debug)]
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
 pub enum GstState{
 	GST_STATE_VOID_PENDING = 0,
 	GST_STATE_NULL = 1,
@@ -4497,6 +4497,7 @@ pub const GST_EVENT_CUSTOM_DOWNSTREAM_OOB: raw::c_uint = 74242;
 pub const GST_EVENT_CUSTOM_DOWNSTREAM_STICKY: raw::c_uint = 76830;
 pub const GST_EVENT_CUSTOM_BOTH: raw::c_uint = 79367;
 pub const GST_EVENT_CUSTOM_BOTH_OOB: raw::c_uint = 81923;
+pub const GST_EVENT_SELECT_STREAMS: raw::c_uint = 66817;
 pub type GstEventType = Enum_Unnamed141;
 pub type Enum_Unnamed142 = raw::c_uint;
 pub const GST_ITERATOR_DONE: raw::c_uint = 0;
@@ -4690,6 +4691,8 @@ pub const GST_MESSAGE_HAVE_CONTEXT: raw::c_int = 1073741824;
 pub const GST_MESSAGE_EXTENDED: raw::c_int = -2147483648;
 pub const GST_MESSAGE_DEVICE_ADDED: raw::c_int = -2147483647;
 pub const GST_MESSAGE_DEVICE_REMOVED: raw::c_int = -2147483646;
+pub const GST_MESSAGE_STREAM_COLLECTION: raw::c_int = -2147483645;
+pub const GST_MESSAGE_STREAMS_SELECTED: raw::c_int = -2147483644;
 pub const GST_MESSAGE_ANY: raw::c_int = -1;
 pub type GstMessageType = Enum_Unnamed151;
 pub enum Struct__GstTocEntry { }
@@ -15691,3 +15694,1886 @@ extern "C" {
                                                                   *mut GstMessage)
      -> gboolean;
 }
+pub enum Struct__GstPhotography { }
+pub type GstPhotography = Struct__GstPhotography;
+pub type Enum_Unnamed_GstPhotographyFlashMode = raw::c_uint;
+pub const GST_PHOTOGRAPHY_FLASH_MODE_AUTO: raw::c_uint = 0;
+pub const GST_PHOTOGRAPHY_FLASH_MODE_OFF: raw::c_uint = 1;
+pub const GST_PHOTOGRAPHY_FLASH_MODE_ON: raw::c_uint = 2;
+pub const GST_PHOTOGRAPHY_FLASH_MODE_FILL_IN: raw::c_uint = 3;
+pub const GST_PHOTOGRAPHY_FLASH_MODE_RED_EYE: raw::c_uint = 4;
+pub type GstPhotographyFlashMode = Enum_Unnamed_GstPhotographyFlashMode;
+pub type Enum_Unnamed_GstPhotographyFocusMode = raw::c_uint;
+pub const GST_PHOTOGRAPHY_FOCUS_MODE_AUTO: raw::c_uint = 0;
+pub const GST_PHOTOGRAPHY_FOCUS_MODE_MACRO: raw::c_uint = 1;
+pub const GST_PHOTOGRAPHY_FOCUS_MODE_PORTRAIT: raw::c_uint = 2;
+pub const GST_PHOTOGRAPHY_FOCUS_MODE_INFINITY: raw::c_uint = 3;
+pub const GST_PHOTOGRAPHY_FOCUS_MODE_HYPERFOCAL: raw::c_uint = 4;
+pub const GST_PHOTOGRAPHY_FOCUS_MODE_EXTENDED: raw::c_uint = 5;
+pub const GST_PHOTOGRAPHY_FOCUS_MODE_CONTINUOUS_NORMAL: raw::c_uint = 6;
+pub const GST_PHOTOGRAPHY_FOCUS_MODE_CONTINUOUS_EXTENDED: raw::c_uint = 7;
+pub const GST_PHOTOGRAPHY_FOCUS_MODE_MANUAL: raw::c_uint = 8;
+pub type GstPhotographyFocusMode = Enum_Unnamed_GstPhotographyFocusMode;
+pub type Enum_Unnamed_GstPhotographyWhiteBalanceMode = raw::c_uint;
+pub const GST_PHOTOGRAPHY_WB_MODE_AUTO: raw::c_uint = 0;
+pub const GST_PHOTOGRAPHY_WB_MODE_DAYLIGHT: raw::c_uint = 1;
+pub const GST_PHOTOGRAPHY_WB_MODE_CLOUDY: raw::c_uint = 2;
+pub const GST_PHOTOGRAPHY_WB_MODE_SUNSET: raw::c_uint = 3;
+pub const GST_PHOTOGRAPHY_WB_MODE_TUNGSTEN: raw::c_uint = 4;
+pub const GST_PHOTOGRAPHY_WB_MODE_FLUORESCENT: raw::c_uint = 5;
+pub const GST_PHOTOGRAPHY_WB_MODE_MANUAL: raw::c_uint = 6;
+pub const GST_PHOTOGRAPHY_WB_MODE_WARM_FLUORESCENT: raw::c_uint = 7;
+pub const GST_PHOTOGRAPHY_WB_MODE_SHADE: raw::c_uint = 8;
+pub type GstPhotographyWhiteBalanceMode = Enum_Unnamed_GstPhotographyWhiteBalanceMode;
+#[cfg(feature = "photography")]
+extern "C" {
+    pub fn gst_photography_get_type() -> GType;
+    pub fn gst_photography_set_flash_mode(photo: *mut GstPhotography,
+                                          flash_mode: GstPhotographyFlashMode)
+     -> gboolean;
+    pub fn gst_photography_get_flash_mode(photo: *mut GstPhotography,
+                                          flash_mode:
+                                              *mut GstPhotographyFlashMode)
+     -> gboolean;
+    pub fn gst_photography_set_focus_mode(photo: *mut GstPhotography,
+                                          mode: GstPhotographyFocusMode)
+     -> gboolean;
+    pub fn gst_photography_get_focus_mode(photo: *mut GstPhotography,
+                                          mode: *mut GstPhotographyFocusMode)
+     -> gboolean;
+    pub fn gst_photography_set_white_balance_mode(photo: *mut GstPhotography,
+                                                  wb_mode:
+                                                      GstPhotographyWhiteBalanceMode)
+     -> gboolean;
+    pub fn gst_photography_get_white_balance_mode(photo: *mut GstPhotography,
+                                                  wb_mode:
+                                                      *mut GstPhotographyWhiteBalanceMode)
+     -> gboolean;
+    pub fn gst_photography_set_ev_compensation(photo: *mut GstPhotography,
+                                               ev_comp: gfloat) -> gboolean;
+    pub fn gst_photography_get_ev_compensation(photo: *mut GstPhotography,
+                                               ev_comp: *mut gfloat)
+     -> gboolean;
+    pub fn gst_photography_set_iso_speed(photo: *mut GstPhotography,
+                                         iso_speed: guint) -> gboolean;
+    pub fn gst_photography_get_iso_speed(photo: *mut GstPhotography,
+                                         iso_speed: *mut guint) -> gboolean;
+    pub fn gst_photography_set_zoom(photo: *mut GstPhotography, zoom: gfloat)
+     -> gboolean;
+    pub fn gst_photography_get_zoom(photo: *mut GstPhotography,
+                                    zoom: *mut gfloat) -> gboolean;
+    pub fn gst_photography_set_autofocus(photo: *mut GstPhotography,
+                                         on: gboolean);
+}
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstReferenceTimestampMeta {
+    pub parent: GstMeta,
+    pub reference: *mut GstCaps,
+    pub timestamp: GstClockTime,
+    pub duration: GstClockTime,
+}
+impl ::std::default::Default for Struct__GstReferenceTimestampMeta {
+    fn default() -> Struct__GstReferenceTimestampMeta {
+        unsafe { ::std::mem::zeroed() }
+    }
+}
+pub type GstReferenceTimestampMeta = Struct__GstReferenceTimestampMeta;
+extern "C" {
+    pub fn gst_reference_timestamp_meta_api_get_type() -> GType;
+    pub fn gst_reference_timestamp_meta_get_info() -> *const GstMetaInfo;
+    pub fn gst_buffer_add_reference_timestamp_meta(buffer: *mut GstBuffer,
+                                                   reference: *mut GstCaps,
+                                                   timestamp: GstClockTime,
+                                                   duration: GstClockTime)
+     -> *mut GstReferenceTimestampMeta;
+    pub fn gst_buffer_get_reference_timestamp_meta(buffer: *mut GstBuffer,
+                                                   reference: *mut GstCaps)
+     -> *mut GstReferenceTimestampMeta;
+}
+pub const GST_ELEMENT_FACTORY_TYPE_DECODER: GstElementFactoryListType = 1 << 0;
+pub const GST_ELEMENT_FACTORY_TYPE_ENCODER: GstElementFactoryListType = 1 << 1;
+pub const GST_ELEMENT_FACTORY_TYPE_SINK: GstElementFactoryListType = 1 << 2;
+pub const GST_ELEMENT_FACTORY_TYPE_SRC: GstElementFactoryListType = 1 << 3;
+pub const GST_ELEMENT_FACTORY_TYPE_MUXER: GstElementFactoryListType = 1 << 4;
+pub const GST_ELEMENT_FACTORY_TYPE_DEMUXER: GstElementFactoryListType = 1 << 5;
+pub const GST_ELEMENT_FACTORY_TYPE_PARSER: GstElementFactoryListType = 1 << 6;
+pub const GST_ELEMENT_FACTORY_TYPE_PAYLOADER: GstElementFactoryListType = 1 << 7;
+pub const GST_ELEMENT_FACTORY_TYPE_DEPAYLOADER: GstElementFactoryListType = 1 << 8;
+pub const GST_ELEMENT_FACTORY_TYPE_FORMATTER: GstElementFactoryListType = 1 << 9;
+pub const GST_ELEMENT_FACTORY_TYPE_DECRYPTOR: GstElementFactoryListType = 1 << 10;
+pub const GST_ELEMENT_FACTORY_TYPE_ENCRYPTOR: GstElementFactoryListType = 1 << 11;
+pub const GST_ELEMENT_FACTORY_TYPE_MAX_ELEMENTS: GstElementFactoryListType = 1 << 48;
+pub const GST_ELEMENT_FACTORY_TYPE_MEDIA_VIDEO: GstElementFactoryListType = 1 << 49;
+pub const GST_ELEMENT_FACTORY_TYPE_MEDIA_AUDIO: GstElementFactoryListType = 1 << 50;
+pub const GST_ELEMENT_FACTORY_TYPE_MEDIA_IMAGE: GstElementFactoryListType = 1 << 51;
+pub const GST_ELEMENT_FACTORY_TYPE_MEDIA_SUBTITLE: GstElementFactoryListType = 1 << 52;
+pub const GST_ELEMENT_FACTORY_TYPE_MEDIA_METADATA: GstElementFactoryListType = 1 << 53;
+pub const GST_ELEMENT_FACTORY_TYPE_ANY: GstElementFactoryListType = (1 << 49) - 1;
+pub const GST_ELEMENT_FACTORY_TYPE_MEDIA_ANY: GstElementFactoryListType = !0 << 48;
+pub const GST_ELEMENT_FACTORY_TYPE_VIDEO_ENCODER: GstElementFactoryListType = GST_ELEMENT_FACTORY_TYPE_ENCODER | GST_ELEMENT_FACTORY_TYPE_MEDIA_VIDEO | GST_ELEMENT_FACTORY_TYPE_MEDIA_IMAGE;
+pub const GST_ELEMENT_FACTORY_TYPE_AUDIO_ENCODER: GstElementFactoryListType = GST_ELEMENT_FACTORY_TYPE_ENCODER | GST_ELEMENT_FACTORY_TYPE_MEDIA_AUDIO;
+pub const GST_ELEMENT_FACTORY_TYPE_AUDIOVIDEO_SINKS: GstElementFactoryListType = GST_ELEMENT_FACTORY_TYPE_SINK | GST_ELEMENT_FACTORY_TYPE_MEDIA_AUDIO | GST_ELEMENT_FACTORY_TYPE_MEDIA_VIDEO | GST_ELEMENT_FACTORY_TYPE_MEDIA_IMAGE;
+pub const GST_ELEMENT_FACTORY_TYPE_DECODABLE: GstElementFactoryListType = GST_ELEMENT_FACTORY_TYPE_DECODER | GST_ELEMENT_FACTORY_TYPE_DEMUXER | GST_ELEMENT_FACTORY_TYPE_DEPAYLOADER | GST_ELEMENT_FACTORY_TYPE_PARSER | GST_ELEMENT_FACTORY_TYPE_DECRYPTOR;
+extern "C" {
+    pub fn gst_debug_bin_to_dot_data(bin: *mut GstBin,
+                                     details: GstDebugGraphDetails)
+     -> *mut gchar;
+}
+#[repr(C)]
+pub struct Struct__GstPromise {
+    pub parent: GstMiniObject,
+}
+pub type GstPromise = Struct__GstPromise;
+pub type GstPromiseResult = raw::c_uint;
+pub const GST_PROMISE_RESULT_PENDING: GstPromiseResult = 0;
+pub const GST_PROMISE_RESULT_INTERRUPTED: GstPromiseResult = 1;
+pub const GST_PROMISE_RESULT_REPLIED: GstPromiseResult = 2;
+pub const GST_PROMISE_RESULT_EXPIRED: GstPromiseResult = 3;
+pub type GstPromiseChangeFunc =
+    ::std::option::Option<extern "C" fn(promise: *mut GstPromise,
+                                        user_data: gpointer)>;
+extern "C" {
+    pub fn gst_promise_get_type() -> GType;
+    pub fn gst_promise_new() -> *mut GstPromise;
+    pub fn gst_promise_new_with_change_func(func: GstPromiseChangeFunc,
+                                            user_data: gpointer,
+                                            notify: GDestroyNotify)
+     -> *mut GstPromise;
+    pub fn gst_promise_wait(promise: *mut GstPromise) -> GstPromiseResult;
+    pub fn gst_promise_reply(promise: *mut GstPromise, s: *mut GstStructure);
+    pub fn gst_promise_interrupt(promise: *mut GstPromise);
+    pub fn gst_promise_expire(promise: *mut GstPromise);
+    pub fn gst_promise_get_reply(promise: *mut GstPromise)
+     -> *const GstStructure;
+}
+pub enum Struct__GstRTPHeaderExtension { }
+pub type GstRTPHeaderExtension = Struct__GstRTPHeaderExtension;
+pub type GstRTPHeaderExtensionFlags = raw::c_uint;
+pub const GST_RTP_HEADER_EXTENSION_ONE_BYTE: GstRTPHeaderExtensionFlags = 1 << 0;
+pub const GST_RTP_HEADER_EXTENSION_TWO_BYTE: GstRTPHeaderExtensionFlags = 1 << 1;
+extern "C" {
+    pub fn gst_rtp_header_extension_get_type() -> GType;
+    pub fn gst_rtp_header_extension_create_from_uri(uri: *const gchar)
+     -> *mut GstRTPHeaderExtension;
+    pub fn gst_rtp_header_extension_get_uri(ext: *mut GstRTPHeaderExtension)
+     -> *const gchar;
+    pub fn gst_rtp_header_extension_get_id(ext: *mut GstRTPHeaderExtension)
+     -> guint;
+    pub fn gst_rtp_header_extension_set_id(ext: *mut GstRTPHeaderExtension,
+                                           ext_id: guint);
+    pub fn gst_rtp_header_extension_get_supported_flags(ext:
+                                                            *mut GstRTPHeaderExtension)
+     -> GstRTPHeaderExtensionFlags;
+    pub fn gst_rtp_header_extension_get_max_size(ext:
+                                                     *mut GstRTPHeaderExtension,
+                                                 input_meta: *const GstBuffer)
+     -> gsize;
+    pub fn gst_rtp_header_extension_write(ext: *mut GstRTPHeaderExtension,
+                                          input_meta: *const GstBuffer,
+                                          write_flags:
+                                              GstRTPHeaderExtensionFlags,
+                                          output: *mut GstBuffer,
+                                          data: *mut guint8, size: gsize)
+     -> gssize;
+    pub fn gst_rtp_header_extension_read(ext: *mut GstRTPHeaderExtension,
+                                         read_flags:
+                                             GstRTPHeaderExtensionFlags,
+                                         data: *const guint8, size: gsize,
+                                         buffer: *mut GstBuffer) -> gboolean;
+    pub fn gst_rtp_header_extension_set_attributes_from_caps(ext:
+                                                                 *mut GstRTPHeaderExtension,
+                                                             caps:
+                                                                 *const GstCaps)
+     -> gboolean;
+    pub fn gst_rtp_header_extension_set_caps_from_attributes(ext:
+                                                                 *mut GstRTPHeaderExtension,
+                                                             caps:
+                                                                 *mut GstCaps)
+     -> gboolean;
+}
+extern "C" {
+    pub fn gst_device_get_properties(device: *mut GstDevice)
+     -> *mut GstStructure;
+    pub fn gst_device_monitor_get_providers(monitor: *mut GstDeviceMonitor)
+     -> *mut *mut gchar;
+}
+pub enum Struct__GstDiscoverer { }
+pub type GstDiscoverer = Struct__GstDiscoverer;
+pub enum Struct__GstDiscovererInfo { }
+pub type GstDiscovererInfo = Struct__GstDiscovererInfo;
+pub enum Struct__GstDiscovererStreamInfo { }
+pub type GstDiscovererStreamInfo = Struct__GstDiscovererStreamInfo;
+pub type GstDiscovererResult = raw::c_uint;
+pub const GST_DISCOVERER_OK: GstDiscovererResult = 0;
+pub const GST_DISCOVERER_URI_INVALID: GstDiscovererResult = 1;
+pub const GST_DISCOVERER_ERROR: GstDiscovererResult = 2;
+pub const GST_DISCOVERER_TIMEOUT: GstDiscovererResult = 3;
+pub const GST_DISCOVERER_BUSY: GstDiscovererResult = 4;
+pub const GST_DISCOVERER_MISSING_PLUGINS: GstDiscovererResult = 5;
+extern "C" {
+    pub fn gst_discoverer_get_type() -> GType;
+    pub fn gst_discoverer_new(timeout: GstClockTime, err: *mut *mut GError)
+     -> *mut GstDiscoverer;
+    pub fn gst_discoverer_start(discoverer: *mut GstDiscoverer);
+    pub fn gst_discoverer_stop(discoverer: *mut GstDiscoverer);
+    pub fn gst_discoverer_discover_uri_async(discoverer: *mut GstDiscoverer,
+                                             uri: *const gchar) -> gboolean;
+    pub fn gst_discoverer_discover_uri(discoverer: *mut GstDiscoverer,
+                                       uri: *const gchar,
+                                       err: *mut *mut GError)
+     -> *mut GstDiscovererInfo;
+    pub fn gst_discoverer_info_get_type() -> GType;
+    pub fn gst_discoverer_info_get_uri(info: *const GstDiscovererInfo)
+     -> *const gchar;
+    pub fn gst_discoverer_info_get_result(info: *const GstDiscovererInfo)
+     -> GstDiscovererResult;
+    pub fn gst_discoverer_info_get_stream_info(info: *mut GstDiscovererInfo)
+     -> *mut GstDiscovererStreamInfo;
+    pub fn gst_discoverer_info_get_stream_list(info: *mut GstDiscovererInfo)
+     -> *mut GList;
+    pub fn gst_discoverer_info_get_duration(info: *const GstDiscovererInfo)
+     -> GstClockTime;
+    pub fn gst_discoverer_info_get_seekable(info: *const GstDiscovererInfo)
+     -> gboolean;
+    pub fn gst_discoverer_info_get_live(info: *const GstDiscovererInfo)
+     -> gboolean;
+    pub fn gst_discoverer_info_get_tags(info: *const GstDiscovererInfo)
+     -> *const GstTagList;
+    pub fn gst_discoverer_info_get_missing_elements_installer_details(info:
+                                                                          *const GstDiscovererInfo)
+     -> *mut *const gchar;
+    pub fn gst_discoverer_stream_info_list_free(infos: *mut GList);
+    pub fn gst_discoverer_stream_info_get_type() -> GType;
+    pub fn gst_discoverer_stream_info_get_caps(info:
+                                                   *mut GstDiscovererStreamInfo)
+     -> *mut GstCaps;
+    pub fn gst_discoverer_stream_info_get_tags(info:
+                                                   *mut GstDiscovererStreamInfo)
+     -> *const GstTagList;
+    pub fn gst_discoverer_stream_info_get_stream_id(info:
+                                                        *mut GstDiscovererStreamInfo)
+     -> *const gchar;
+    pub fn gst_discoverer_stream_info_get_stream_type_nick(info:
+                                                               *mut GstDiscovererStreamInfo)
+     -> *const gchar;
+    pub fn gst_discoverer_container_info_get_type() -> GType;
+    pub fn gst_discoverer_container_info_get_streams(info:
+                                                         *mut GstDiscovererStreamInfo)
+     -> *mut GList;
+    pub fn gst_discoverer_audio_info_get_type() -> GType;
+    pub fn gst_discoverer_audio_info_get_channels(info:
+                                                      *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_audio_info_get_sample_rate(info:
+                                                         *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_audio_info_get_depth(info:
+                                                   *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_audio_info_get_bitrate(info:
+                                                     *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_audio_info_get_max_bitrate(info:
+                                                         *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_audio_info_get_language(info:
+                                                      *const GstDiscovererStreamInfo)
+     -> *const gchar;
+    pub fn gst_discoverer_video_info_get_type() -> GType;
+    pub fn gst_discoverer_video_info_get_width(info:
+                                                   *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_video_info_get_height(info:
+                                                    *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_video_info_get_depth(info:
+                                                   *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_video_info_get_framerate_num(info:
+                                                           *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_video_info_get_framerate_denom(info:
+                                                             *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_video_info_get_par_num(info:
+                                                     *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_video_info_get_par_denom(info:
+                                                       *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_video_info_is_interlaced(info:
+                                                       *const GstDiscovererStreamInfo)
+     -> gboolean;
+    pub fn gst_discoverer_video_info_get_bitrate(info:
+                                                     *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_video_info_get_max_bitrate(info:
+                                                         *const GstDiscovererStreamInfo)
+     -> guint;
+    pub fn gst_discoverer_video_info_is_image(info:
+                                                  *const GstDiscovererStreamInfo)
+     -> gboolean;
+    pub fn gst_discoverer_subtitle_info_get_type() -> GType;
+    pub fn gst_discoverer_subtitle_info_get_language(info:
+                                                         *const GstDiscovererStreamInfo)
+     -> *const gchar;
+}
+pub enum Struct__GSocketControlMessage { }
+pub type GSocketControlMessage = Struct__GSocketControlMessage;
+pub enum Struct__GUnixFDList { }
+pub type GUnixFDList = Struct__GUnixFDList;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstNetControlMessageMeta {
+    pub meta: GstMeta,
+    pub message: *mut GSocketControlMessage,
+}
+impl ::std::default::Default for Struct__GstNetControlMessageMeta {
+    fn default() -> Struct__GstNetControlMessageMeta {
+        unsafe { ::std::mem::zeroed() }
+    }
+}
+pub type GstNetControlMessageMeta = Struct__GstNetControlMessageMeta;
+extern "C" {
+    pub fn gst_net_control_message_meta_api_get_type() -> GType;
+    pub fn gst_net_control_message_meta_get_info() -> *const GstMetaInfo;
+    pub fn gst_buffer_add_net_control_message_meta(buffer: *mut GstBuffer,
+                                                   message:
+                                                       *mut GSocketControlMessage)
+     -> *mut GstNetControlMessageMeta;
+    pub fn g_socket_control_message_get_level(message:
+                                                  *mut GSocketControlMessage)
+     -> raw::c_int;
+    pub fn g_socket_control_message_get_msg_type(message:
+                                                     *mut GSocketControlMessage)
+     -> raw::c_int;
+    pub fn g_socket_control_message_get_size(message:
+                                                 *mut GSocketControlMessage)
+     -> gsize;
+    pub fn g_socket_control_message_serialize(message:
+                                                  *mut GSocketControlMessage,
+                                              data: gpointer);
+    pub fn g_socket_control_message_deserialize(level: raw::c_int,
+                                                type_: raw::c_int,
+                                                size: gsize, data: gpointer)
+     -> *mut GSocketControlMessage;
+}
+#[cfg(unix)]
+extern "C" {
+    pub fn g_unix_fd_message_get_type() -> GType;
+    pub fn g_unix_fd_message_new_with_fd_list(fd_list: *mut GUnixFDList)
+     -> *mut GSocketControlMessage;
+    pub fn g_unix_fd_message_get_fd_list(message: *mut GSocketControlMessage)
+     -> *mut GUnixFDList;
+    pub fn g_unix_fd_list_new() -> *mut GUnixFDList;
+    pub fn g_unix_fd_list_new_from_array(fds: *const gint, n_fds: gint)
+     -> *mut GUnixFDList;
+    pub fn g_unix_fd_list_append(list: *mut GUnixFDList, fd: gint,
+                                 error: *mut *mut GError) -> gint;
+    pub fn g_unix_fd_list_get_length(list: *mut GUnixFDList) -> gint;
+    pub fn g_unix_fd_list_get(list: *mut GUnixFDList, index_: gint,
+                              error: *mut *mut GError) -> gint;
+}
+pub enum Struct__GstEncodingProfile { }
+pub type GstEncodingProfile = Struct__GstEncodingProfile;
+pub enum Struct__GstEncodingContainerProfile { }
+pub type GstEncodingContainerProfile = Struct__GstEncodingContainerProfile;
+pub enum Struct__GstEncodingVideoProfile { }
+pub type GstEncodingVideoProfile = Struct__GstEncodingVideoProfile;
+pub enum Struct__GstEncodingAudioProfile { }
+pub type GstEncodingAudioProfile = Struct__GstEncodingAudioProfile;
+extern "C" {
+    pub fn gst_encoding_profile_get_type() -> GType;
+    pub fn gst_encoding_profile_get_name(profile: *mut GstEncodingProfile)
+     -> *const gchar;
+    pub fn gst_encoding_profile_set_name(profile: *mut GstEncodingProfile,
+                                         name: *const gchar);
+    pub fn gst_encoding_profile_get_description(profile:
+                                                    *mut GstEncodingProfile)
+     -> *const gchar;
+    pub fn gst_encoding_profile_set_description(profile:
+                                                    *mut GstEncodingProfile,
+                                                description: *const gchar);
+    pub fn gst_encoding_profile_get_format(profile: *mut GstEncodingProfile)
+     -> *mut GstCaps;
+    pub fn gst_encoding_profile_get_preset(profile: *mut GstEncodingProfile)
+     -> *const gchar;
+    pub fn gst_encoding_profile_set_preset(profile: *mut GstEncodingProfile,
+                                           preset: *const gchar);
+    pub fn gst_encoding_profile_get_presence(profile: *mut GstEncodingProfile)
+     -> guint;
+    pub fn gst_encoding_profile_set_presence(profile: *mut GstEncodingProfile,
+                                             presence: guint);
+    pub fn gst_encoding_profile_get_restriction(profile:
+                                                    *mut GstEncodingProfile)
+     -> *mut GstCaps;
+    pub fn gst_encoding_profile_set_restriction(profile:
+                                                    *mut GstEncodingProfile,
+                                                restriction: *mut GstCaps);
+    pub fn gst_encoding_profile_get_type_nick(profile:
+                                                  *mut GstEncodingProfile)
+     -> *const gchar;
+    pub fn gst_encoding_profile_from_discoverer(info: *mut GstDiscovererInfo)
+     -> *mut GstEncodingProfile;
+    pub fn gst_encoding_container_profile_get_type() -> GType;
+    pub fn gst_encoding_container_profile_new(name: *const gchar,
+                                              description: *const gchar,
+                                              format: *mut GstCaps,
+                                              preset: *const gchar)
+     -> *mut GstEncodingContainerProfile;
+    pub fn gst_encoding_container_profile_add_profile(container:
+                                                          *mut GstEncodingContainerProfile,
+                                                      profile:
+                                                          *mut GstEncodingProfile)
+     -> gboolean;
+    pub fn gst_encoding_container_profile_get_profiles(profile:
+                                                           *mut GstEncodingContainerProfile)
+     -> *const GList;
+    pub fn gst_encoding_video_profile_get_type() -> GType;
+    pub fn gst_encoding_video_profile_new(format: *mut GstCaps,
+                                          preset: *const gchar,
+                                          restriction: *mut GstCaps,
+                                          presence: guint)
+     -> *mut GstEncodingVideoProfile;
+    pub fn gst_encoding_video_profile_set_pass(prof:
+                                                   *mut GstEncodingVideoProfile,
+                                               pass: guint);
+    pub fn gst_encoding_video_profile_set_variableframerate(prof:
+                                                                *mut GstEncodingVideoProfile,
+                                                            variableframerate:
+                                                                gboolean);
+    pub fn gst_encoding_audio_profile_get_type() -> GType;
+    pub fn gst_encoding_audio_profile_new(format: *mut GstCaps,
+                                          preset: *const gchar,
+                                          restriction: *mut GstCaps,
+                                          presence: guint)
+     -> *mut GstEncodingAudioProfile;
+}
+
+pub enum Struct__GstSDPMessage { }
+pub type GstSDPMessage = Struct__GstSDPMessage;
+pub type GstSDPResult = raw::c_int;
+pub const GST_SDP_OK: GstSDPResult = 0;
+pub const GST_SDP_EINVAL: GstSDPResult = -1;
+extern "C" {
+    pub fn gst_sdp_message_get_type() -> GType;
+    pub fn gst_sdp_message_new(msg: *mut *mut GstSDPMessage) -> GstSDPResult;
+    pub fn gst_sdp_message_free(msg: *mut GstSDPMessage) -> GstSDPResult;
+    pub fn gst_sdp_message_copy(msg: *const GstSDPMessage,
+                                copy: *mut *mut GstSDPMessage)
+     -> GstSDPResult;
+    pub fn gst_sdp_message_parse_buffer(data: *const guint8, size: guint,
+                                        msg: *mut GstSDPMessage)
+     -> GstSDPResult;
+    pub fn gst_sdp_message_as_text(msg: *const GstSDPMessage) -> *mut gchar;
+}
+
+pub type GstWebRTCSDPType = raw::c_uint;
+pub const GST_WEBRTC_SDP_TYPE_OFFER: GstWebRTCSDPType = 1;
+pub const GST_WEBRTC_SDP_TYPE_PRANSWER: GstWebRTCSDPType = 2;
+pub const GST_WEBRTC_SDP_TYPE_ANSWER: GstWebRTCSDPType = 3;
+pub const GST_WEBRTC_SDP_TYPE_ROLLBACK: GstWebRTCSDPType = 4;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstWebRTCSessionDescription {
+    pub _type: GstWebRTCSDPType,
+    pub sdp: *mut GstSDPMessage,
+}
+impl ::std::default::Default for Struct__GstWebRTCSessionDescription {
+    fn default() -> Struct__GstWebRTCSessionDescription {
+        unsafe { ::std::mem::zeroed() }
+    }
+}
+pub type GstWebRTCSessionDescription = Struct__GstWebRTCSessionDescription;
+extern "C" {
+    pub fn gst_webrtc_session_description_get_type() -> GType;
+    pub fn gst_webrtc_session_description_new(_type: GstWebRTCSDPType,
+                                              sdp: *mut GstSDPMessage)
+     -> *mut GstWebRTCSessionDescription;
+    pub fn gst_webrtc_session_description_copy(src:
+                                                   *const GstWebRTCSessionDescription)
+     -> *mut GstWebRTCSessionDescription;
+    pub fn gst_webrtc_session_description_free(desc:
+                                                   *mut GstWebRTCSessionDescription);
+}
+
+pub enum Struct__GstSDPMedia { }
+pub type GstSDPMedia = Struct__GstSDPMedia;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstSDPAttribute {
+    pub key: *mut gchar,
+    pub value: *mut gchar,
+}
+impl ::std::default::Default for Struct__GstSDPAttribute {
+    fn default() -> Struct__GstSDPAttribute {
+        unsafe { ::std::mem::zeroed() }
+    }
+}
+pub type GstSDPAttribute = Struct__GstSDPAttribute;
+extern "C" {
+    pub fn gst_sdp_message_get_version(msg: *const GstSDPMessage)
+     -> *const gchar;
+    pub fn gst_sdp_message_set_version(msg: *mut GstSDPMessage,
+                                       version: *const gchar) -> GstSDPResult;
+    pub fn gst_sdp_message_set_origin(msg: *mut GstSDPMessage,
+                                      username: *const gchar,
+                                      sess_id: *const gchar,
+                                      sess_version: *const gchar,
+                                      nettype: *const gchar,
+                                      addrtype: *const gchar,
+                                      addr: *const gchar) -> GstSDPResult;
+    pub fn gst_sdp_message_get_session_name(msg: *const GstSDPMessage)
+     -> *const gchar;
+    pub fn gst_sdp_message_set_session_name(msg: *mut GstSDPMessage,
+                                            session_name: *const gchar)
+     -> GstSDPResult;
+    pub fn gst_sdp_message_set_connection(msg: *mut GstSDPMessage,
+                                          nettype: *const gchar,
+                                          addrtype: *const gchar,
+                                          address: *const gchar, ttl: guint,
+                                          addr_number: guint)
+     -> GstSDPResult;
+    pub fn gst_sdp_message_add_time(msg: *mut GstSDPMessage,
+                                    start: *const gchar, stop: *const gchar,
+                                    repeat: *mut *const gchar)
+     -> GstSDPResult;
+    pub fn gst_sdp_message_attributes_len(msg: *const GstSDPMessage)
+     -> guint;
+    pub fn gst_sdp_message_get_attribute(msg: *const GstSDPMessage,
+                                         idx: guint)
+     -> *const GstSDPAttribute;
+    pub fn gst_sdp_message_get_attribute_val(msg: *const GstSDPMessage,
+                                             key: *const gchar)
+     -> *const gchar;
+    pub fn gst_sdp_message_get_attribute_val_n(msg: *const GstSDPMessage,
+                                               key: *const gchar, nth: guint)
+     -> *const gchar;
+    pub fn gst_sdp_message_add_attribute(msg: *mut GstSDPMessage,
+                                         key: *const gchar,
+                                         value: *const gchar)
+     -> GstSDPResult;
+    pub fn gst_sdp_message_remove_attribute(msg: *mut GstSDPMessage,
+                                            idx: guint) -> GstSDPResult;
+    pub fn gst_sdp_message_medias_len(msg: *const GstSDPMessage) -> guint;
+    pub fn gst_sdp_message_get_media(msg: *const GstSDPMessage, idx: guint)
+     -> *const GstSDPMedia;
+    pub fn gst_sdp_message_add_media(msg: *mut GstSDPMessage,
+                                     media: *mut GstSDPMedia) -> GstSDPResult;
+    pub fn gst_sdp_media_new(media: *mut *mut GstSDPMedia) -> GstSDPResult;
+    pub fn gst_sdp_media_free(media: *mut GstSDPMedia) -> GstSDPResult;
+    pub fn gst_sdp_media_copy(media: *const GstSDPMedia,
+                              copy: *mut *mut GstSDPMedia) -> GstSDPResult;
+    pub fn gst_sdp_media_as_text(media: *const GstSDPMedia) -> *mut gchar;
+    pub fn gst_sdp_media_get_media(media: *const GstSDPMedia)
+     -> *const gchar;
+    pub fn gst_sdp_media_set_media(media: *mut GstSDPMedia,
+                                   med: *const gchar) -> GstSDPResult;
+    pub fn gst_sdp_media_get_port(media: *const GstSDPMedia) -> guint;
+    pub fn gst_sdp_media_get_num_ports(media: *const GstSDPMedia) -> guint;
+    pub fn gst_sdp_media_set_port_info(media: *mut GstSDPMedia, port: guint,
+                                       num_ports: guint) -> GstSDPResult;
+    pub fn gst_sdp_media_get_proto(media: *const GstSDPMedia)
+     -> *const gchar;
+    pub fn gst_sdp_media_set_proto(media: *mut GstSDPMedia,
+                                   proto: *const gchar) -> GstSDPResult;
+    pub fn gst_sdp_media_formats_len(media: *const GstSDPMedia) -> guint;
+    pub fn gst_sdp_media_get_format(media: *const GstSDPMedia, idx: guint)
+     -> *const gchar;
+    pub fn gst_sdp_media_add_format(media: *mut GstSDPMedia,
+                                    format: *const gchar) -> GstSDPResult;
+    pub fn gst_sdp_media_attributes_len(media: *const GstSDPMedia) -> guint;
+    pub fn gst_sdp_media_get_attribute(media: *const GstSDPMedia, idx: guint)
+     -> *const GstSDPAttribute;
+    pub fn gst_sdp_media_get_attribute_val(media: *const GstSDPMedia,
+                                           key: *const gchar)
+     -> *const gchar;
+    pub fn gst_sdp_media_get_attribute_val_n(media: *const GstSDPMedia,
+                                             key: *const gchar, nth: guint)
+     -> *const gchar;
+    pub fn gst_sdp_media_add_attribute(media: *mut GstSDPMedia,
+                                       key: *const gchar,
+                                       value: *const gchar) -> GstSDPResult;
+    pub fn gst_sdp_media_remove_attribute(media: *mut GstSDPMedia,
+                                          idx: guint) -> GstSDPResult;
+    pub fn gst_sdp_media_get_caps_from_media(media: *const GstSDPMedia,
+                                             pt: gint) -> *mut GstCaps;
+    pub fn gst_sdp_media_set_media_from_caps(caps: *const GstCaps,
+                                             media: *mut GstSDPMedia)
+     -> GstSDPResult;
+}
+
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstRTPBuffer {
+    pub buffer: *mut GstBuffer,
+    pub state: guint,
+    pub data: [gpointer; 4usize],
+    pub size: [gsize; 4usize],
+    pub map: [GstMapInfo; 4usize],
+}
+impl ::std::default::Default for Struct__GstRTPBuffer {
+    fn default() -> Struct__GstRTPBuffer {
+        unsafe { ::std::mem::zeroed() }
+    }
+}
+pub type GstRTPBuffer = Struct__GstRTPBuffer;
+extern "C" {
+    pub fn gst_rtp_buffer_new_allocate(payload_len: guint, pad_len: guint8,
+                                       csrc_count: guint8) -> *mut GstBuffer;
+    pub fn gst_rtp_buffer_map(buffer: *mut GstBuffer, flags: GstMapFlags,
+                              rtp: *mut GstRTPBuffer) -> gboolean;
+    pub fn gst_rtp_buffer_unmap(rtp: *mut GstRTPBuffer);
+    pub fn gst_rtp_buffer_get_header_len(rtp: *mut GstRTPBuffer) -> guint;
+    pub fn gst_rtp_buffer_get_version(rtp: *mut GstRTPBuffer) -> guint8;
+    pub fn gst_rtp_buffer_get_padding(rtp: *mut GstRTPBuffer) -> gboolean;
+    pub fn gst_rtp_buffer_get_extension(rtp: *mut GstRTPBuffer) -> gboolean;
+    pub fn gst_rtp_buffer_get_ssrc(rtp: *mut GstRTPBuffer) -> guint32;
+    pub fn gst_rtp_buffer_set_ssrc(rtp: *mut GstRTPBuffer, ssrc: guint32);
+    pub fn gst_rtp_buffer_get_csrc_count(rtp: *mut GstRTPBuffer) -> guint8;
+    pub fn gst_rtp_buffer_get_csrc(rtp: *mut GstRTPBuffer, idx: guint8)
+     -> guint32;
+    pub fn gst_rtp_buffer_set_csrc(rtp: *mut GstRTPBuffer, idx: guint8,
+                                   csrc: guint32);
+    pub fn gst_rtp_buffer_get_marker(rtp: *mut GstRTPBuffer) -> gboolean;
+    pub fn gst_rtp_buffer_set_marker(rtp: *mut GstRTPBuffer,
+                                     marker: gboolean);
+    pub fn gst_rtp_buffer_get_payload_type(rtp: *mut GstRTPBuffer) -> guint8;
+    pub fn gst_rtp_buffer_set_payload_type(rtp: *mut GstRTPBuffer,
+                                           payload_type: guint8);
+    pub fn gst_rtp_buffer_get_seq(rtp: *mut GstRTPBuffer) -> guint16;
+    pub fn gst_rtp_buffer_set_seq(rtp: *mut GstRTPBuffer, seq: guint16);
+    pub fn gst_rtp_buffer_get_timestamp(rtp: *mut GstRTPBuffer) -> guint32;
+    pub fn gst_rtp_buffer_set_timestamp(rtp: *mut GstRTPBuffer,
+                                        timestamp: guint32);
+    pub fn gst_rtp_buffer_get_payload_len(rtp: *mut GstRTPBuffer) -> guint;
+    pub fn gst_rtp_buffer_get_payload(rtp: *mut GstRTPBuffer) -> gpointer;
+    pub fn gst_rtp_buffer_get_extension_onebyte_header(rtp:
+                                                           *mut GstRTPBuffer,
+                                                       id: guint8, nth: guint,
+                                                       data: *mut gpointer,
+                                                       size: *mut guint)
+     -> gboolean;
+    pub fn gst_rtp_buffer_get_extension_twobytes_header(rtp:
+                                                            *mut GstRTPBuffer,
+                                                        appbits: *mut guint8,
+                                                        id: guint8,
+                                                        nth: guint,
+                                                        data: *mut gpointer,
+                                                        size: *mut guint)
+     -> gboolean;
+    pub fn gst_rtp_buffer_add_extension_onebyte_header(rtp:
+                                                           *mut GstRTPBuffer,
+                                                       id: guint8,
+                                                       data: gconstpointer,
+                                                       size: guint)
+     -> gboolean;
+    pub fn gst_rtp_buffer_add_extension_twobytes_header(rtp:
+                                                            *mut GstRTPBuffer,
+                                                        appbits: guint8,
+                                                        id: guint8,
+                                                        data: gconstpointer,
+                                                        size: guint)
+     -> gboolean;
+}
+
+extern "C" {
+    pub fn gst_clock_wait_for_sync(clock: *mut GstClock,
+                                   timeout: GstClockTime) -> gboolean;
+    pub fn gst_clock_is_synced(clock: *mut GstClock) -> gboolean;
+}
+
+pub enum Struct__GstNetTimeProvider { }
+pub type GstNetTimeProvider = Struct__GstNetTimeProvider;
+pub const GST_PTP_CLOCK_ID_NONE: guint64 = 0xffffffffffffffff;
+extern "C" {
+    pub fn gst_net_client_clock_new(name: *const gchar,
+                                    remote_address: *const gchar,
+                                    remote_port: gint,
+                                    base_time: GstClockTime)
+     -> *mut GstClock;
+    pub fn gst_ntp_clock_new(name: *const gchar,
+                             remote_address: *const gchar, remote_port: gint,
+                             base_time: GstClockTime) -> *mut GstClock;
+    pub fn gst_net_time_provider_new(clock: *mut GstClock,
+                                     address: *const gchar, port: gint)
+     -> *mut GstNetTimeProvider;
+    pub fn gst_ptp_is_supported() -> gboolean;
+    pub fn gst_ptp_is_initialized() -> gboolean;
+    pub fn gst_ptp_init(clock_id: guint64, interfaces: *mut *mut gchar)
+     -> gboolean;
+    pub fn gst_ptp_deinit();
+    pub fn gst_ptp_clock_new(name: *const gchar, domain: guint)
+     -> *mut GstClock;
+}
+
+pub enum Struct__GstTimedValueControlSource { }
+pub type GstTimedValueControlSource = Struct__GstTimedValueControlSource;
+pub type GstInterpolationMode = raw::c_uint;
+pub const GST_INTERPOLATION_MODE_NONE: raw::c_uint = 0;
+pub const GST_INTERPOLATION_MODE_LINEAR: raw::c_uint = 1;
+pub const GST_INTERPOLATION_MODE_CUBIC: raw::c_uint = 2;
+pub const GST_INTERPOLATION_MODE_CUBIC_MONOTONIC: raw::c_uint = 3;
+extern "C" {
+    pub fn gst_interpolation_control_source_new() -> *mut GstControlSource;
+    pub fn gst_timed_value_control_source_set(_self:
+                                                  *mut GstTimedValueControlSource,
+                                              timestamp: GstClockTime,
+                                              value: gdouble) -> gboolean;
+    pub fn gst_timed_value_control_source_unset(_self:
+                                                    *mut GstTimedValueControlSource,
+                                                timestamp: GstClockTime)
+     -> gboolean;
+    pub fn gst_timed_value_control_source_unset_all(_self:
+                                                        *mut GstTimedValueControlSource);
+    pub fn gst_timed_value_control_source_get_count(_self:
+                                                        *mut GstTimedValueControlSource)
+     -> gint;
+    pub fn gst_direct_control_binding_new(object: *mut GstObject,
+                                          property_name: *const gchar,
+                                          cs: *mut GstControlSource)
+     -> *mut GstControlBinding;
+    pub fn gst_direct_control_binding_new_absolute(object: *mut GstObject,
+                                                   property_name:
+                                                       *const gchar,
+                                                   cs: *mut GstControlSource)
+     -> *mut GstControlBinding;
+}
+
+pub type GstFdMemoryFlags = raw::c_uint;
+pub const GST_FD_MEMORY_FLAG_NONE: raw::c_uint = 0;
+pub const GST_FD_MEMORY_FLAG_KEEP_MAPPED: raw::c_uint = 1;
+pub const GST_FD_MEMORY_FLAG_MAP_PRIVATE: raw::c_uint = 2;
+pub const GST_FD_MEMORY_FLAG_DONT_CLOSE: raw::c_uint = 4;
+extern "C" {
+    pub fn gst_fd_allocator_new() -> *mut GstAllocator;
+    pub fn gst_fd_allocator_alloc(allocator: *mut GstAllocator, fd: gint,
+                                  size: gsize, flags: GstFdMemoryFlags)
+     -> *mut GstMemory;
+    pub fn gst_is_fd_memory(mem: *mut GstMemory) -> gboolean;
+    pub fn gst_fd_memory_get_fd(mem: *mut GstMemory) -> gint;
+}
+
+pub enum Struct__GstPlayer { }
+pub type GstPlayer = Struct__GstPlayer;
+pub enum Struct__GstPlayerVideoRenderer { }
+pub type GstPlayerVideoRenderer = Struct__GstPlayerVideoRenderer;
+pub enum Struct__GstPlayerVideoOverlayVideoRenderer { }
+pub type GstPlayerVideoOverlayVideoRenderer = Struct__GstPlayerVideoOverlayVideoRenderer;
+pub enum Struct__GstPlayerSignalDispatcher { }
+pub type GstPlayerSignalDispatcher = Struct__GstPlayerSignalDispatcher;
+pub enum Struct__GstPlayerMediaInfo { }
+pub type GstPlayerMediaInfo = Struct__GstPlayerMediaInfo;
+pub enum Struct__GstPlayerStreamInfo { }
+pub type GstPlayerStreamInfo = Struct__GstPlayerStreamInfo;
+pub enum Struct__GstPlayerVideoInfo { }
+pub type GstPlayerVideoInfo = Struct__GstPlayerVideoInfo;
+pub enum Struct__GstPlayerAudioInfo { }
+pub type GstPlayerAudioInfo = Struct__GstPlayerAudioInfo;
+pub enum Struct__GstPlayerSubtitleInfo { }
+pub type GstPlayerSubtitleInfo = Struct__GstPlayerSubtitleInfo;
+pub type GstPlayerState = raw::c_uint;
+pub const GST_PLAYER_STATE_STOPPED: raw::c_uint = 0;
+pub const GST_PLAYER_STATE_BUFFERING: raw::c_uint = 1;
+pub const GST_PLAYER_STATE_PAUSED: raw::c_uint = 2;
+pub const GST_PLAYER_STATE_PLAYING: raw::c_uint = 3;
+#[cfg(feature = "player")]
+extern "C" {
+    pub fn gst_player_new(video_renderer: *mut GstPlayerVideoRenderer,
+                          signal_dispatcher: *mut GstPlayerSignalDispatcher)
+     -> *mut GstPlayer;
+    pub fn gst_player_play(player: *mut GstPlayer);
+    pub fn gst_player_pause(player: *mut GstPlayer);
+    pub fn gst_player_stop(player: *mut GstPlayer);
+    pub fn gst_player_seek(player: *mut GstPlayer, position: GstClockTime);
+    pub fn gst_player_set_rate(player: *mut GstPlayer, rate: gdouble);
+    pub fn gst_player_get_rate(player: *mut GstPlayer) -> gdouble;
+    pub fn gst_player_get_uri(player: *mut GstPlayer) -> *mut gchar;
+    pub fn gst_player_set_uri(player: *mut GstPlayer, uri: *const gchar);
+    pub fn gst_player_get_subtitle_uri(player: *mut GstPlayer) -> *mut gchar;
+    pub fn gst_player_set_subtitle_uri(player: *mut GstPlayer,
+                                       uri: *const gchar);
+    pub fn gst_player_get_position(player: *mut GstPlayer) -> GstClockTime;
+    pub fn gst_player_get_duration(player: *mut GstPlayer) -> GstClockTime;
+    pub fn gst_player_get_volume(player: *mut GstPlayer) -> gdouble;
+    pub fn gst_player_set_volume(player: *mut GstPlayer, val: gdouble);
+    pub fn gst_player_get_mute(player: *mut GstPlayer) -> gboolean;
+    pub fn gst_player_set_mute(player: *mut GstPlayer, val: gboolean);
+    pub fn gst_player_get_pipeline(player: *mut GstPlayer) -> *mut GstElement;
+    pub fn gst_player_get_media_info(player: *mut GstPlayer)
+     -> *mut GstPlayerMediaInfo;
+    pub fn gst_player_set_audio_track(player: *mut GstPlayer,
+                                      stream_index: gint) -> gboolean;
+    pub fn gst_player_set_video_track(player: *mut GstPlayer,
+                                      stream_index: gint) -> gboolean;
+    pub fn gst_player_set_subtitle_track(player: *mut GstPlayer,
+                                         stream_index: gint) -> gboolean;
+    pub fn gst_player_set_audio_track_enabled(player: *mut GstPlayer,
+                                              enabled: gboolean);
+    pub fn gst_player_set_video_track_enabled(player: *mut GstPlayer,
+                                              enabled: gboolean);
+    pub fn gst_player_set_subtitle_track_enabled(player: *mut GstPlayer,
+                                                 enabled: gboolean);
+    pub fn gst_player_get_config(player: *mut GstPlayer) -> *mut GstStructure;
+    pub fn gst_player_set_config(player: *mut GstPlayer,
+                                 config: *mut GstStructure) -> gboolean;
+    pub fn gst_player_config_set_position_update_interval(config:
+                                                              *mut GstStructure,
+                                                          interval: guint);
+    pub fn gst_player_config_get_position_update_interval(config:
+                                                              *const GstStructure)
+     -> guint;
+    pub fn gst_player_g_main_context_signal_dispatcher_new(application_context:
+                                                               *mut GMainContext)
+     -> *mut GstPlayerSignalDispatcher;
+    pub fn gst_player_video_overlay_video_renderer_new(window_handle: gpointer)
+     -> *mut GstPlayerVideoRenderer;
+    pub fn gst_player_video_overlay_video_renderer_new_with_sink(window_handle:
+                                                                     gpointer,
+                                                                 video_sink:
+                                                                     *mut GstElement)
+     -> *mut GstPlayerVideoRenderer;
+    pub fn gst_player_video_overlay_video_renderer_set_window_handle(_self:
+                                                                         *mut GstPlayerVideoOverlayVideoRenderer,
+                                                                     window_handle:
+                                                                         gpointer);
+    pub fn gst_player_video_overlay_video_renderer_get_window_handle(_self:
+                                                                         *mut GstPlayerVideoOverlayVideoRenderer)
+     -> gpointer;
+    pub fn gst_player_video_overlay_video_renderer_expose(_self:
+                                                              *mut GstPlayerVideoOverlayVideoRenderer);
+    pub fn gst_player_video_overlay_video_renderer_set_render_rectangle(_self:
+                                                                            *mut GstPlayerVideoOverlayVideoRenderer,
+                                                                        x: gint,
+                                                                        y: gint,
+                                                                        width:
+                                                                            gint,
+                                                                        height:
+                                                                            gint);
+    pub fn gst_player_media_info_get_uri(info: *const GstPlayerMediaInfo)
+     -> *const gchar;
+    pub fn gst_player_media_info_get_title(info: *const GstPlayerMediaInfo)
+     -> *const gchar;
+    pub fn gst_player_media_info_get_container_format(info:
+                                                          *const GstPlayerMediaInfo)
+     -> *const gchar;
+    pub fn gst_player_media_info_get_duration(info: *const GstPlayerMediaInfo)
+     -> GstClockTime;
+    pub fn gst_player_media_info_is_seekable(info: *const GstPlayerMediaInfo)
+     -> gboolean;
+    pub fn gst_player_media_info_is_live(info: *const GstPlayerMediaInfo)
+     -> gboolean;
+    pub fn gst_player_media_info_get_tags(info: *const GstPlayerMediaInfo)
+     -> *mut GstTagList;
+    pub fn gst_player_media_info_get_video_streams(info:
+                                                       *const GstPlayerMediaInfo)
+     -> *mut GList;
+    pub fn gst_player_media_info_get_audio_streams(info:
+                                                       *const GstPlayerMediaInfo)
+     -> *mut GList;
+    pub fn gst_player_media_info_get_subtitle_streams(info:
+                                                          *const GstPlayerMediaInfo)
+     -> *mut GList;
+    pub fn gst_player_stream_info_get_index(info: *const GstPlayerStreamInfo)
+     -> gint;
+    pub fn gst_player_stream_info_get_stream_type(info:
+                                                      *const GstPlayerStreamInfo)
+     -> *const gchar;
+    pub fn gst_player_stream_info_get_codec(info: *const GstPlayerStreamInfo)
+     -> *const gchar;
+    pub fn gst_player_stream_info_get_caps(info: *const GstPlayerStreamInfo)
+     -> *mut GstCaps;
+    pub fn gst_player_stream_info_get_tags(info: *const GstPlayerStreamInfo)
+     -> *mut GstTagList;
+    pub fn gst_player_video_info_get_width(info: *const GstPlayerVideoInfo)
+     -> gint;
+    pub fn gst_player_video_info_get_height(info: *const GstPlayerVideoInfo)
+     -> gint;
+    pub fn gst_player_video_info_get_framerate(info: *const GstPlayerVideoInfo,
+                                               fps_n: *mut gint,
+                                               fps_d: *mut gint);
+    pub fn gst_player_video_info_get_bitrate(info: *const GstPlayerVideoInfo)
+     -> gint;
+    pub fn gst_player_audio_info_get_channels(info: *const GstPlayerAudioInfo)
+     -> gint;
+    pub fn gst_player_audio_info_get_sample_rate(info:
+                                                     *const GstPlayerAudioInfo)
+     -> gint;
+    pub fn gst_player_audio_info_get_bitrate(info: *const GstPlayerAudioInfo)
+     -> gint;
+    pub fn gst_player_audio_info_get_language(info: *const GstPlayerAudioInfo)
+     -> *const gchar;
+    pub fn gst_player_subtitle_info_get_language(info:
+                                                     *const GstPlayerSubtitleInfo)
+     -> *const gchar;
+}
+pub enum Struct__GstStream { }
+pub type GstStream = Struct__GstStream;
+pub enum Struct__GstStreamCollection { }
+pub type GstStreamCollection = Struct__GstStreamCollection;
+pub type GstStreamType = raw::c_uint;
+pub const GST_STREAM_TYPE_UNKNOWN: raw::c_uint = 1;
+pub const GST_STREAM_TYPE_AUDIO: raw::c_uint = 2;
+pub const GST_STREAM_TYPE_VIDEO: raw::c_uint = 4;
+pub const GST_STREAM_TYPE_CONTAINER: raw::c_uint = 8;
+pub const GST_STREAM_TYPE_TEXT: raw::c_uint = 16;
+extern "C" {
+    pub fn gst_stream_get_stream_id(stream: *mut GstStream) -> *const gchar;
+    pub fn gst_stream_get_stream_type(stream: *mut GstStream)
+     -> GstStreamType;
+    pub fn gst_stream_get_stream_flags(stream: *mut GstStream)
+     -> GstStreamFlags;
+    pub fn gst_stream_get_caps(stream: *mut GstStream) -> *mut GstCaps;
+    pub fn gst_stream_get_tags(stream: *mut GstStream) -> *mut GstTagList;
+    pub fn gst_stream_type_get_name(stype: GstStreamType) -> *const gchar;
+    pub fn gst_stream_collection_get_upstream_id(collection:
+                                                     *mut GstStreamCollection)
+     -> *const gchar;
+    pub fn gst_stream_collection_get_size(collection: *mut GstStreamCollection)
+     -> guint;
+    pub fn gst_stream_collection_get_stream(collection:
+                                                *mut GstStreamCollection,
+                                            index: guint) -> *mut GstStream;
+    pub fn gst_message_parse_stream_collection(message: *mut GstMessage,
+                                               collection:
+                                                   *mut *mut GstStreamCollection);
+    pub fn gst_message_parse_streams_selected(message: *mut GstMessage,
+                                              collection:
+                                                  *mut *mut GstStreamCollection);
+    pub fn gst_message_streams_selected_get_size(message: *mut GstMessage)
+     -> guint;
+    pub fn gst_message_streams_selected_get_stream(message: *mut GstMessage,
+                                                   idx: guint)
+     -> *mut GstStream;
+    pub fn gst_event_new_select_streams(streams: *mut GList) -> *mut GstEvent;
+    pub fn gst_event_parse_select_streams(event: *mut GstEvent,
+                                          streams: *mut *mut GList);
+}
+pub type GstByteReader = Struct__GstByteReader;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstByteReader {
+    pub data: *const guint8,
+    pub size: guint,
+    pub byte: guint,
+    pub _gst_reserved: [gpointer; 4usize],
+}
+impl ::std::default::Default for Struct__GstByteReader {
+    fn default() -> Struct__GstByteReader { unsafe { ::std::mem::zeroed() } }
+}
+pub type GstBitReader = Struct__GstBitReader;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstBitReader {
+    pub data: *const guint8,
+    pub size: guint,
+    pub byte: guint,
+    pub bit: guint,
+    pub _gst_reserved: [gpointer; 4usize],
+}
+impl ::std::default::Default for Struct__GstBitReader {
+    fn default() -> Struct__GstBitReader { unsafe { ::std::mem::zeroed() } }
+}
+pub type GstByteWriter = Struct__GstByteWriter;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstByteWriter {
+    pub parent: GstByteReader,
+    pub alloc_size: guint,
+    pub fixed: gboolean,
+    pub owned: gboolean,
+    pub _gst_reserved: [gpointer; 4usize],
+}
+impl ::std::default::Default for Struct__GstByteWriter {
+    fn default() -> Struct__GstByteWriter { unsafe { ::std::mem::zeroed() } }
+}
+extern "C" {
+    pub fn gst_byte_reader_init(reader: *mut GstByteReader,
+                                data: *const guint8, size: guint);
+    pub fn gst_byte_reader_set_pos(reader: *mut GstByteReader, pos: guint)
+     -> gboolean;
+    pub fn gst_byte_reader_get_pos(reader: *const GstByteReader) -> guint;
+    pub fn gst_byte_reader_get_remaining(reader: *const GstByteReader)
+     -> guint;
+    pub fn gst_byte_reader_skip(reader: *mut GstByteReader, nbytes: guint)
+     -> gboolean;
+    pub fn gst_byte_reader_get_uint8(reader: *mut GstByteReader,
+                                     val: *mut guint8) -> gboolean;
+    pub fn gst_byte_reader_get_uint16_le(reader: *mut GstByteReader,
+                                         val: *mut guint16) -> gboolean;
+    pub fn gst_byte_reader_get_uint16_be(reader: *mut GstByteReader,
+                                         val: *mut guint16) -> gboolean;
+    pub fn gst_byte_reader_get_uint24_le(reader: *mut GstByteReader,
+                                         val: *mut guint32) -> gboolean;
+    pub fn gst_byte_reader_get_uint24_be(reader: *mut GstByteReader,
+                                         val: *mut guint32) -> gboolean;
+    pub fn gst_byte_reader_get_uint32_le(reader: *mut GstByteReader,
+                                         val: *mut guint32) -> gboolean;
+    pub fn gst_byte_reader_get_uint32_be(reader: *mut GstByteReader,
+                                         val: *mut guint32) -> gboolean;
+    pub fn gst_byte_reader_get_uint64_le(reader: *mut GstByteReader,
+                                         val: *mut guint64) -> gboolean;
+    pub fn gst_byte_reader_get_uint64_be(reader: *mut GstByteReader,
+                                         val: *mut guint64) -> gboolean;
+    pub fn gst_byte_reader_peek_uint8(reader: *const GstByteReader,
+                                      val: *mut guint8) -> gboolean;
+    pub fn gst_byte_reader_peek_uint16_be(reader: *const GstByteReader,
+                                          val: *mut guint16) -> gboolean;
+    pub fn gst_byte_reader_peek_uint32_be(reader: *const GstByteReader,
+                                          val: *mut guint32) -> gboolean;
+    pub fn gst_byte_reader_get_data(reader: *mut GstByteReader, size: guint,
+                                    val: *mut *const guint8) -> gboolean;
+    pub fn gst_byte_reader_peek_data(reader: *const GstByteReader,
+                                     size: guint, val: *mut *const guint8)
+     -> gboolean;
+    pub fn gst_byte_reader_masked_scan_uint32(reader: *const GstByteReader,
+                                              mask: guint32,
+                                              pattern: guint32, offset: guint,
+                                              size: guint) -> guint;
+    pub fn gst_bit_reader_init(reader: *mut GstBitReader, data: *const guint8,
+                               size: guint);
+    pub fn gst_bit_reader_set_pos(reader: *mut GstBitReader, pos: guint)
+     -> gboolean;
+    pub fn gst_bit_reader_get_pos(reader: *const GstBitReader) -> guint;
+    pub fn gst_bit_reader_get_remaining(reader: *const GstBitReader) -> guint;
+    pub fn gst_bit_reader_skip(reader: *mut GstBitReader, nbits: guint)
+     -> gboolean;
+    pub fn gst_bit_reader_skip_to_byte(reader: *mut GstBitReader) -> gboolean;
+    pub fn gst_bit_reader_get_bits_uint32(reader: *mut GstBitReader,
+                                          val: *mut guint32, nbits: guint)
+     -> gboolean;
+    pub fn gst_bit_reader_get_bits_uint64(reader: *mut GstBitReader,
+                                          val: *mut guint64, nbits: guint)
+     -> gboolean;
+    pub fn gst_bit_reader_peek_bits_uint32(reader: *const GstBitReader,
+                                           val: *mut guint32, nbits: guint)
+     -> gboolean;
+    pub fn gst_byte_writer_init(writer: *mut GstByteWriter);
+    pub fn gst_byte_writer_init_with_size(writer: *mut GstByteWriter,
+                                          size: guint, fixed: gboolean);
+    pub fn gst_byte_writer_reset(writer: *mut GstByteWriter);
+    pub fn gst_byte_writer_reset_and_get_data(writer: *mut GstByteWriter)
+     -> *mut guint8;
+    pub fn gst_byte_writer_reset_and_get_buffer(writer: *mut GstByteWriter)
+     -> *mut GstBuffer;
+    pub fn gst_byte_writer_get_remaining(writer: *const GstByteWriter)
+     -> guint;
+    pub fn gst_byte_writer_put_uint8(writer: *mut GstByteWriter, val: guint8)
+     -> gboolean;
+    pub fn gst_byte_writer_put_uint16_le(writer: *mut GstByteWriter,
+                                         val: guint16) -> gboolean;
+    pub fn gst_byte_writer_put_uint16_be(writer: *mut GstByteWriter,
+                                         val: guint16) -> gboolean;
+    pub fn gst_byte_writer_put_uint24_le(writer: *mut GstByteWriter,
+                                         val: guint32) -> gboolean;
+    pub fn gst_byte_writer_put_uint24_be(writer: *mut GstByteWriter,
+                                         val: guint32) -> gboolean;
+    pub fn gst_byte_writer_put_uint32_le(writer: *mut GstByteWriter,
+                                         val: guint32) -> gboolean;
+    pub fn gst_byte_writer_put_uint32_be(writer: *mut GstByteWriter,
+                                         val: guint32) -> gboolean;
+    pub fn gst_byte_writer_put_uint64_le(writer: *mut GstByteWriter,
+                                         val: guint64) -> gboolean;
+    pub fn gst_byte_writer_put_uint64_be(writer: *mut GstByteWriter,
+                                         val: guint64) -> gboolean;
+    pub fn gst_byte_writer_put_data(writer: *mut GstByteWriter,
+                                    data: *const guint8, size: guint)
+     -> gboolean;
+    pub fn gst_byte_writer_fill(writer: *mut GstByteWriter, value: guint8,
+                                size: guint) -> gboolean;
+}
+
+pub enum Struct__GstH264NalParser { }
+pub type GstH264NalParser = Struct__GstH264NalParser;
+pub enum Struct__GstH265Parser { }
+pub type GstH265Parser = Struct__GstH265Parser;
+pub type GstH264ParserResult = raw::c_uint;
+pub const GST_H264_PARSER_OK: raw::c_uint = 0;
+pub const GST_H264_PARSER_BROKEN_DATA: raw::c_uint = 1;
+pub const GST_H264_PARSER_BROKEN_LINK: raw::c_uint = 2;
+pub const GST_H264_PARSER_ERROR: raw::c_uint = 3;
+pub const GST_H264_PARSER_NO_NAL: raw::c_uint = 4;
+pub const GST_H264_PARSER_NO_NAL_END: raw::c_uint = 5;
+pub type GstH265ParserResult = raw::c_uint;
+pub const GST_H265_PARSER_OK: raw::c_uint = 0;
+pub const GST_H265_PARSER_BROKEN_DATA: raw::c_uint = 1;
+pub const GST_H265_PARSER_BROKEN_LINK: raw::c_uint = 2;
+pub const GST_H265_PARSER_ERROR: raw::c_uint = 3;
+pub const GST_H265_PARSER_NO_NAL: raw::c_uint = 4;
+pub const GST_H265_PARSER_NO_NAL_END: raw::c_uint = 5;
+pub type GstH264NalUnitType = raw::c_uint;
+pub const GST_H264_NAL_UNKNOWN: raw::c_uint = 0;
+pub const GST_H264_NAL_SLICE: raw::c_uint = 1;
+pub const GST_H264_NAL_SLICE_DPA: raw::c_uint = 2;
+pub const GST_H264_NAL_SLICE_DPB: raw::c_uint = 3;
+pub const GST_H264_NAL_SLICE_DPC: raw::c_uint = 4;
+pub const GST_H264_NAL_SLICE_IDR: raw::c_uint = 5;
+pub const GST_H264_NAL_SEI: raw::c_uint = 6;
+pub const GST_H264_NAL_SPS: raw::c_uint = 7;
+pub const GST_H264_NAL_PPS: raw::c_uint = 8;
+pub const GST_H264_NAL_AU_DELIMITER: raw::c_uint = 9;
+pub const GST_H264_NAL_SEQ_END: raw::c_uint = 10;
+pub const GST_H264_NAL_STREAM_END: raw::c_uint = 11;
+pub const GST_H264_NAL_FILLER_DATA: raw::c_uint = 12;
+pub type GstH265NalUnitType = raw::c_uint;
+pub const GST_H265_NAL_SLICE_TRAIL_N: raw::c_uint = 0;
+pub const GST_H265_NAL_SLICE_TRAIL_R: raw::c_uint = 1;
+pub const GST_H265_NAL_SLICE_BLA_W_LP: raw::c_uint = 16;
+pub const GST_H265_NAL_SLICE_BLA_W_RADL: raw::c_uint = 17;
+pub const GST_H265_NAL_SLICE_BLA_N_LP: raw::c_uint = 18;
+pub const GST_H265_NAL_SLICE_IDR_W_RADL: raw::c_uint = 19;
+pub const GST_H265_NAL_SLICE_IDR_N_LP: raw::c_uint = 20;
+pub const GST_H265_NAL_SLICE_CRA_NUT: raw::c_uint = 21;
+pub const GST_H265_NAL_VPS: raw::c_uint = 32;
+pub const GST_H265_NAL_SPS: raw::c_uint = 33;
+pub const GST_H265_NAL_PPS: raw::c_uint = 34;
+pub const GST_H265_NAL_AUD: raw::c_uint = 35;
+pub const GST_H265_NAL_EOS: raw::c_uint = 36;
+pub const GST_H265_NAL_EOB: raw::c_uint = 37;
+pub const GST_H265_NAL_FD: raw::c_uint = 38;
+pub const GST_H265_NAL_PREFIX_SEI: raw::c_uint = 39;
+pub const GST_H265_NAL_SUFFIX_SEI: raw::c_uint = 40;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstH264NalUnitExtensionMVC {
+    pub non_idr_flag: guint8,
+    pub priority_id: guint8,
+    pub view_id: guint16,
+    pub temporal_id: guint8,
+    pub anchor_pic_flag: guint8,
+    pub inter_view_flag: guint8,
+}
+pub type GstH264NalUnitExtensionMVC = Struct__GstH264NalUnitExtensionMVC;
+pub type GstH264NalUnit = Struct__GstH264NalUnit;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstH264NalUnit {
+    pub ref_idc: guint16,
+    pub _type: guint16,
+    pub idr_pic_flag: guint8,
+    pub size: guint,
+    pub offset: guint,
+    pub sc_offset: guint,
+    pub valid: gboolean,
+    pub data: *mut guint8,
+    pub header_bytes: guint8,
+    pub extension_type: guint8,
+    pub extension: GstH264NalUnitExtensionMVC,
+}
+impl ::std::default::Default for Struct__GstH264NalUnit {
+    fn default() -> Struct__GstH264NalUnit { unsafe { ::std::mem::zeroed() } }
+}
+pub type GstH265NalUnit = Struct__GstH265NalUnit;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstH265NalUnit {
+    pub _type: guint8,
+    pub layer_id: guint8,
+    pub temporal_id_plus1: guint8,
+    pub size: guint,
+    pub offset: guint,
+    pub sc_offset: guint,
+    pub valid: gboolean,
+    pub data: *mut guint8,
+    pub header_bytes: guint8,
+}
+impl ::std::default::Default for Struct__GstH265NalUnit {
+    fn default() -> Struct__GstH265NalUnit { unsafe { ::std::mem::zeroed() } }
+}
+#[cfg(feature = "codecparsers")]
+extern "C" {
+    pub fn gst_h264_nal_parser_new() -> *mut GstH264NalParser;
+    pub fn gst_h264_nal_parser_free(nalparser: *mut GstH264NalParser);
+    pub fn gst_h264_parser_identify_nalu(nalparser: *mut GstH264NalParser,
+                                         data: *const guint8, offset: guint,
+                                         size: gsize,
+                                         nalu: *mut GstH264NalUnit)
+     -> GstH264ParserResult;
+    pub fn gst_h264_parser_identify_nalu_avc(nalparser: *mut GstH264NalParser,
+                                             data: *const guint8,
+                                             offset: guint, size: gsize,
+                                             nal_length_size: guint8,
+                                             nalu: *mut GstH264NalUnit)
+     -> GstH264ParserResult;
+    pub fn gst_h265_parser_new() -> *mut GstH265Parser;
+    pub fn gst_h265_parser_free(parser: *mut GstH265Parser);
+    pub fn gst_h265_parser_identify_nalu(parser: *mut GstH265Parser,
+                                         data: *const guint8, offset: guint,
+                                         size: gsize,
+                                         nalu: *mut GstH265NalUnit)
+     -> GstH265ParserResult;
+    pub fn gst_h265_parser_identify_nalu_hevc(parser: *mut GstH265Parser,
+                                              data: *const guint8,
+                                              offset: guint, size: gsize,
+                                              nal_length_size: guint8,
+                                              nalu: *mut GstH265NalUnit)
+     -> GstH265ParserResult;
+}
+extern "C" {
+    pub fn gst_app_src_push_buffer_list(appsrc: *mut GstAppSrc,
+                                        buffer_list: *mut GstBufferList)
+     -> GstFlowReturn;
+}
+
+pub type GstMpegtsSectionType = raw::c_uint;
+pub const GST_MPEGTS_SECTION_UNKNOWN: raw::c_uint = 0;
+pub const GST_MPEGTS_SECTION_PAT: raw::c_uint = 1;
+pub const GST_MPEGTS_SECTION_PMT: raw::c_uint = 2;
+pub const GST_MPEGTS_SECTION_CAT: raw::c_uint = 3;
+pub const GST_MPEGTS_SECTION_TSDT: raw::c_uint = 4;
+pub const GST_MPEGTS_SECTION_EIT: raw::c_uint = 5;
+pub const GST_MPEGTS_SECTION_NIT: raw::c_uint = 6;
+pub const GST_MPEGTS_SECTION_BAT: raw::c_uint = 7;
+pub const GST_MPEGTS_SECTION_SDT: raw::c_uint = 8;
+pub const GST_MPEGTS_SECTION_TDT: raw::c_uint = 9;
+pub const GST_MPEGTS_SECTION_TOT: raw::c_uint = 10;
+pub type GstMpegtsRunningStatus = raw::c_uint;
+pub type GstMpegtsDVBServiceType = raw::c_uint;
+pub const GST_MTS_DESC_DVB_SERVICE: raw::c_uint = 72;
+/// Only the public fields, sections are always allocated by the library
+pub type GstMpegtsSection = Struct__GstMpegtsSection;
+#[repr(C)]
+pub struct Struct__GstMpegtsSection {
+    pub parent: GstMiniObject,
+    pub section_type: GstMpegtsSectionType,
+    pub pid: guint16,
+    pub table_id: guint8,
+    pub subtable_extension: guint16,
+    pub version_number: guint8,
+    pub current_next_indicator: gboolean,
+    pub section_number: guint8,
+    pub last_section_number: guint8,
+    pub crc: guint32,
+}
+pub enum Struct__GstMpegtsDescriptor { }
+pub type GstMpegtsDescriptor = Struct__GstMpegtsDescriptor;
+pub type GstMpegtsPatProgram = Struct__GstMpegtsPatProgram;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstMpegtsPatProgram {
+    pub program_number: guint16,
+    pub network_or_program_map_PID: guint16,
+}
+impl ::std::default::Default for Struct__GstMpegtsPatProgram {
+    fn default() -> Struct__GstMpegtsPatProgram { unsafe { ::std::mem::zeroed() } }
+}
+pub type GstMpegtsPMTStream = Struct__GstMpegtsPMTStream;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstMpegtsPMTStream {
+    pub stream_type: guint8,
+    pub pid: guint16,
+    pub descriptors: *mut GPtrArray,
+}
+impl ::std::default::Default for Struct__GstMpegtsPMTStream {
+    fn default() -> Struct__GstMpegtsPMTStream { unsafe { ::std::mem::zeroed() } }
+}
+pub type GstMpegtsPMT = Struct__GstMpegtsPMT;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstMpegtsPMT {
+    pub pcr_pid: guint16,
+    pub program_number: guint16,
+    pub descriptors: *mut GPtrArray,
+    pub streams: *mut GPtrArray,
+}
+impl ::std::default::Default for Struct__GstMpegtsPMT {
+    fn default() -> Struct__GstMpegtsPMT { unsafe { ::std::mem::zeroed() } }
+}
+pub type GstMpegtsSDTService = Struct__GstMpegtsSDTService;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstMpegtsSDTService {
+    pub service_id: guint16,
+    pub EIT_schedule_flag: gboolean,
+    pub EIT_present_following_flag: gboolean,
+    pub running_status: GstMpegtsRunningStatus,
+    pub free_CA_mode: gboolean,
+    pub descriptors: *mut GPtrArray,
+}
+impl ::std::default::Default for Struct__GstMpegtsSDTService {
+    fn default() -> Struct__GstMpegtsSDTService { unsafe { ::std::mem::zeroed() } }
+}
+pub type GstMpegtsSDT = Struct__GstMpegtsSDT;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstMpegtsSDT {
+    pub original_network_id: guint16,
+    pub actual_ts: gboolean,
+    pub transport_stream_id: guint16,
+    pub services: *mut GPtrArray,
+}
+impl ::std::default::Default for Struct__GstMpegtsSDT {
+    fn default() -> Struct__GstMpegtsSDT { unsafe { ::std::mem::zeroed() } }
+}
+#[cfg(feature = "mpegts")]
+extern "C" {
+    pub fn gst_mpegts_initialize();
+    pub fn gst_mpegts_section_get_type() -> GType;
+    pub fn gst_message_parse_mpegts_section(message: *mut GstMessage)
+     -> *mut GstMpegtsSection;
+    pub fn gst_mpegts_section_send_event(section: *mut GstMpegtsSection,
+                                         element: *mut GstElement)
+     -> gboolean;
+    pub fn gst_mpegts_section_packetize(section: *mut GstMpegtsSection,
+                                        output_size: *mut gsize)
+     -> *mut guint8;
+    pub fn gst_mpegts_section_new(pid: guint16, data: *mut guint8,
+                                  data_size: gsize) -> *mut GstMpegtsSection;
+    pub fn gst_mpegts_section_get_pat(section: *mut GstMpegtsSection)
+     -> *mut GPtrArray;
+    pub fn gst_mpegts_section_get_pmt(section: *mut GstMpegtsSection)
+     -> *const GstMpegtsPMT;
+    pub fn gst_mpegts_section_get_sdt(section: *mut GstMpegtsSection)
+     -> *const GstMpegtsSDT;
+    pub fn gst_mpegts_pat_new() -> *mut GPtrArray;
+    pub fn gst_mpegts_pat_program_new() -> *mut GstMpegtsPatProgram;
+    pub fn gst_mpegts_section_from_pat(programs: *mut GPtrArray,
+                                       ts_id: guint16)
+     -> *mut GstMpegtsSection;
+    pub fn gst_mpegts_pmt_new() -> *mut GstMpegtsPMT;
+    pub fn gst_mpegts_pmt_stream_new() -> *mut GstMpegtsPMTStream;
+    pub fn gst_mpegts_section_from_pmt(pmt: *mut GstMpegtsPMT, pid: guint16)
+     -> *mut GstMpegtsSection;
+    pub fn gst_mpegts_find_descriptor(descriptors: *mut GPtrArray,
+                                      tag: guint8)
+     -> *const GstMpegtsDescriptor;
+    pub fn gst_mpegts_descriptor_parse_dvb_service(descriptor:
+                                                       *const GstMpegtsDescriptor,
+                                                   service_type:
+                                                       *mut GstMpegtsDVBServiceType,
+                                                   service_name:
+                                                       *mut *mut gchar,
+                                                   provider_name:
+                                                       *mut *mut gchar)
+     -> gboolean;
+}
+
+extern "C" {
+    pub fn gst_tracing_get_active_tracers() -> *mut GList;
+}
+
+pub type GstAudioFormat = raw::c_uint;
+pub const GST_AUDIO_FORMAT_UNKNOWN: raw::c_uint = 0;
+pub const GST_AUDIO_FORMAT_ENCODED: raw::c_uint = 1;
+pub const GST_AUDIO_FORMAT_S8: raw::c_uint = 2;
+pub const GST_AUDIO_FORMAT_U8: raw::c_uint = 3;
+pub const GST_AUDIO_FORMAT_S16LE: raw::c_uint = 4;
+pub const GST_AUDIO_FORMAT_S16BE: raw::c_uint = 5;
+pub const GST_AUDIO_FORMAT_U16LE: raw::c_uint = 6;
+pub const GST_AUDIO_FORMAT_U16BE: raw::c_uint = 7;
+pub const GST_AUDIO_FORMAT_S32LE: raw::c_uint = 12;
+pub const GST_AUDIO_FORMAT_S32BE: raw::c_uint = 13;
+pub const GST_AUDIO_FORMAT_F32LE: raw::c_uint = 28;
+pub const GST_AUDIO_FORMAT_F32BE: raw::c_uint = 29;
+pub const GST_AUDIO_FORMAT_F64LE: raw::c_uint = 30;
+pub const GST_AUDIO_FORMAT_F64BE: raw::c_uint = 31;
+pub type GstAudioFormatFlags = raw::c_uint;
+pub const GST_AUDIO_FORMAT_FLAG_INTEGER: raw::c_uint = 1;
+pub const GST_AUDIO_FORMAT_FLAG_FLOAT: raw::c_uint = 2;
+pub const GST_AUDIO_FORMAT_FLAG_SIGNED: raw::c_uint = 4;
+pub const GST_AUDIO_FORMAT_FLAG_COMPLEX: raw::c_uint = 16;
+pub const GST_AUDIO_FORMAT_FLAG_UNPACK: raw::c_uint = 32;
+pub type GstAudioFlags = raw::c_uint;
+pub const GST_AUDIO_FLAG_NONE: raw::c_uint = 0;
+pub const GST_AUDIO_FLAG_UNPOSITIONED: raw::c_uint = 1;
+pub type GstAudioLayout = raw::c_uint;
+pub const GST_AUDIO_LAYOUT_INTERLEAVED: raw::c_uint = 0;
+pub const GST_AUDIO_LAYOUT_NON_INTERLEAVED: raw::c_uint = 1;
+pub type GstAudioChannelPosition = raw::c_int;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstAudioFormatInfo {
+    pub format: GstAudioFormat,
+    pub name: *const gchar,
+    pub description: *const gchar,
+    pub flags: GstAudioFormatFlags,
+    pub endianness: gint,
+    pub width: gint,
+    pub depth: gint,
+    pub silence: [guint8; 8usize],
+    pub unpack_format: GstAudioFormat,
+    pub unpack_func: gpointer,
+    pub pack_func: gpointer,
+    pub _gst_reserved: [gpointer; 4usize],
+}
+impl ::std::default::Default for Struct__GstAudioFormatInfo {
+    fn default() -> Struct__GstAudioFormatInfo { unsafe { ::std::mem::zeroed() } }
+}
+pub type GstAudioFormatInfo = Struct__GstAudioFormatInfo;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstAudioInfo {
+    pub finfo: *const GstAudioFormatInfo,
+    pub flags: GstAudioFlags,
+    pub layout: GstAudioLayout,
+    pub rate: gint,
+    pub channels: gint,
+    pub bpf: gint,
+    pub position: [GstAudioChannelPosition; 64usize],
+    pub _gst_reserved: [gpointer; 4usize],
+}
+impl ::std::default::Default for Struct__GstAudioInfo {
+    fn default() -> Struct__GstAudioInfo { unsafe { ::std::mem::zeroed() } }
+}
+pub type GstAudioInfo = Struct__GstAudioInfo;
+extern "C" {
+    pub fn gst_audio_info_init(info: *mut GstAudioInfo);
+    pub fn gst_audio_info_from_caps(info: *mut GstAudioInfo,
+                                    caps: *const GstCaps) -> gboolean;
+    pub fn gst_audio_info_to_caps(info: *const GstAudioInfo) -> *mut GstCaps;
+    pub fn gst_audio_info_is_equal(info: *const GstAudioInfo,
+                                   other: *const GstAudioInfo) -> gboolean;
+    pub fn gst_audio_info_set_format(info: *mut GstAudioInfo,
+                                     format: GstAudioFormat, rate: gint,
+                                     channels: gint,
+                                     position: *const GstAudioChannelPosition);
+    pub fn gst_audio_format_from_string(format: *const gchar)
+     -> GstAudioFormat;
+    pub fn gst_audio_format_to_string(format: GstAudioFormat) -> *const gchar;
+}
+
+extern "C" {
+    pub fn gst_type_find_helper_for_data(obj: *mut GstObject,
+                                         data: *const guint8, size: gsize,
+                                         prob: *mut GstTypeFindProbability)
+     -> *mut GstCaps;
+}
+
+extern "C" {
+    pub fn gst_app_sink_try_pull_sample(appsink: *mut GstAppSink,
+                                        timeout: GstClockTime)
+     -> *mut GstSample;
+}
+
+extern "C" {
+    pub fn gst_element_get_context(element: *mut GstElement,
+                                   context_type: *const gchar)
+     -> *mut GstContext;
+}
+
+pub const GST_MAP_GL: raw::c_uint = 131072;
+pub type GstGLPlatform = raw::c_uint;
+pub const GST_GL_PLATFORM_NONE: raw::c_uint = 0;
+pub const GST_GL_PLATFORM_EGL: raw::c_uint = 1;
+pub const GST_GL_PLATFORM_GLX: raw::c_uint = 2;
+pub const GST_GL_PLATFORM_WGL: raw::c_uint = 4;
+pub const GST_GL_PLATFORM_CGL: raw::c_uint = 8;
+pub const GST_GL_PLATFORM_EAGL: raw::c_uint = 16;
+pub const GST_GL_PLATFORM_ANY: raw::c_uint = 4294967295;
+pub type GstGLAPI = raw::c_uint;
+pub const GST_GL_API_NONE: raw::c_uint = 0;
+pub const GST_GL_API_OPENGL: raw::c_uint = 1;
+pub const GST_GL_API_OPENGL3: raw::c_uint = 2;
+pub const GST_GL_API_GLES1: raw::c_uint = 32768;
+pub const GST_GL_API_GLES2: raw::c_uint = 65536;
+pub const GST_GL_API_ANY: raw::c_uint = 4294967295;
+pub type GstGLDisplayType = raw::c_uint;
+pub enum Struct__GstGLContext { }
+pub type GstGLContext = Struct__GstGLContext;
+pub enum Struct__GstGLDisplay { }
+pub type GstGLDisplay = Struct__GstGLDisplay;
+pub enum Struct__GstGLSyncMeta { }
+pub type GstGLSyncMeta = Struct__GstGLSyncMeta;
+#[cfg(feature = "gl")]
+extern "C" {
+    pub fn gst_gl_display_new() -> *mut GstGLDisplay;
+    pub fn gst_gl_display_get_handle_type(display: *mut GstGLDisplay)
+     -> GstGLDisplayType;
+    pub fn gst_gl_display_get_handle(display: *mut GstGLDisplay) -> guintptr;
+    pub fn gst_context_set_gl_display(context: *mut GstContext,
+                                      display: *mut GstGLDisplay);
+    pub fn gst_context_get_gl_display(context: *mut GstContext,
+                                      display: *mut *mut GstGLDisplay)
+     -> gboolean;
+    pub fn gst_gl_context_new_wrapped(display: *mut GstGLDisplay,
+                                      handle: guintptr,
+                                      context_type: GstGLPlatform,
+                                      available_apis: GstGLAPI)
+     -> *mut GstGLContext;
+    pub fn gst_gl_context_activate(context: *mut GstGLContext,
+                                   activate: gboolean) -> gboolean;
+    pub fn gst_gl_context_get_display(context: *mut GstGLContext)
+     -> *mut GstGLDisplay;
+    pub fn gst_gl_context_get_gl_api(context: *mut GstGLContext) -> GstGLAPI;
+    pub fn gst_gl_context_get_gl_platform(context: *mut GstGLContext)
+     -> GstGLPlatform;
+    pub fn gst_gl_context_get_gl_context(context: *mut GstGLContext)
+     -> guintptr;
+    pub fn gst_gl_context_get_current_gl_context(context_type: GstGLPlatform)
+     -> guintptr;
+    pub fn gst_gl_sync_meta_api_get_type() -> GType;
+    pub fn gst_gl_sync_meta_wait(sync_meta: *mut GstGLSyncMeta,
+                                 context: *mut GstGLContext);
+    pub fn gst_gl_sync_meta_wait_cpu(sync_meta: *mut GstGLSyncMeta,
+                                     context: *mut GstGLContext);
+}
+
+extern "C" {
+    pub fn gst_dmabuf_allocator_new() -> *mut GstAllocator;
+    pub fn gst_dmabuf_allocator_alloc(allocator: *mut GstAllocator, fd: gint,
+                                      size: gsize) -> *mut GstMemory;
+    pub fn gst_is_dmabuf_memory(mem: *mut GstMemory) -> gboolean;
+    pub fn gst_dmabuf_memory_get_fd(mem: *mut GstMemory) -> gint;
+}
+
+extern "C" {
+    pub fn gst_type_find_helper_for_data_with_extension(obj: *mut GstObject,
+                                                        data: *const guint8,
+                                                        size: gsize,
+                                                        extension:
+                                                            *const gchar,
+                                                        prob:
+                                                            *mut GstTypeFindProbability)
+     -> *mut GstCaps;
+    pub fn gst_type_find_helper_for_buffer(obj: *mut GstObject,
+                                           buf: *mut GstBuffer,
+                                           prob: *mut GstTypeFindProbability)
+     -> *mut GstCaps;
+    pub fn gst_type_find_helper_for_buffer_with_extension(obj: *mut GstObject,
+                                                          buf: *mut GstBuffer,
+                                                          extension:
+                                                              *const gchar,
+                                                          prob:
+                                                              *mut GstTypeFindProbability)
+     -> *mut GstCaps;
+    pub fn gst_type_find_helper_for_extension(obj: *mut GstObject,
+                                              extension: *const gchar)
+     -> *mut GstCaps;
+}
+
+pub const GST_PARAM_CONTROLLABLE: raw::c_int = 512;
+pub const GST_PARAM_MUTABLE_READY: raw::c_int = 1024;
+pub const GST_PARAM_MUTABLE_PAUSED: raw::c_int = 2048;
+pub const GST_PARAM_MUTABLE_PLAYING: raw::c_int = 4096;
+
+extern "C" {
+    pub fn gst_element_message_full_with_details(element: *mut GstElement,
+                                                 _type: GstMessageType,
+                                                 domain: GQuark, code: gint,
+                                                 text: *mut gchar,
+                                                 debug: *mut gchar,
+                                                 file: *const gchar,
+                                                 function: *const gchar,
+                                                 line: gint,
+                                                 structure: *mut GstStructure);
+    pub fn gst_message_parse_error_details(message: *mut GstMessage,
+                                           structure:
+                                               *mut *const GstStructure);
+}
+
+pub const GST_SEEK_FLAG_TRICKMODE: raw::c_uint = 16;
+pub const GST_SEEK_FLAG_TRICKMODE_KEY_UNITS: raw::c_uint = 128;
+pub const GST_SEEK_FLAG_TRICKMODE_NO_AUDIO: raw::c_uint = 256;
+pub const GST_SEEK_FLAG_TRICKMODE_FORWARD_PREDICTED: raw::c_uint = 512;
+pub const GST_SEEK_FLAG_INSTANT_RATE_CHANGE: raw::c_uint = 1024;
+
+pub enum Struct__GstAudioConverter { }
+pub type GstAudioConverter = Struct__GstAudioConverter;
+pub type Enum_GstAudioConverterFlags = raw::c_uint;
+pub const GST_AUDIO_CONVERTER_FLAG_NONE: raw::c_uint = 0;
+pub const GST_AUDIO_CONVERTER_FLAG_IN_WRITABLE: raw::c_uint = 1;
+pub const GST_AUDIO_CONVERTER_FLAG_VARIABLE_RATE: raw::c_uint = 2;
+pub type GstAudioConverterFlags = Enum_GstAudioConverterFlags;
+extern "C" {
+    pub fn gst_audio_converter_new(flags: GstAudioConverterFlags,
+                                   in_info: *mut GstAudioInfo,
+                                   out_info: *mut GstAudioInfo,
+                                   config: *mut GstStructure)
+     -> *mut GstAudioConverter;
+    pub fn gst_audio_converter_free(convert: *mut GstAudioConverter);
+    pub fn gst_audio_converter_reset(convert: *mut GstAudioConverter);
+    pub fn gst_audio_converter_update_config(convert: *mut GstAudioConverter,
+                                             in_rate: gint, out_rate: gint,
+                                             config: *mut GstStructure)
+     -> gboolean;
+    pub fn gst_audio_converter_get_out_frames(convert: *mut GstAudioConverter,
+                                              in_frames: gsize) -> gsize;
+    pub fn gst_audio_converter_get_in_frames(convert: *mut GstAudioConverter,
+                                             out_frames: gsize) -> gsize;
+    pub fn gst_audio_converter_get_max_latency(convert: *mut GstAudioConverter)
+     -> gsize;
+    pub fn gst_audio_converter_samples(convert: *mut GstAudioConverter,
+                                       flags: GstAudioConverterFlags,
+                                       _in: *mut gpointer, in_frames: gsize,
+                                       out: *mut gpointer, out_frames: gsize)
+     -> gboolean;
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct Struct__GstHarness {
+    pub element: *mut GstElement,
+    pub srcpad: *mut GstPad,
+    pub sinkpad: *mut GstPad,
+    pub src_harness: *mut GstHarness,
+    pub sink_harness: *mut GstHarness,
+    pub _priv: gpointer,
+}
+impl ::std::clone::Clone for Struct__GstHarness {
+    fn clone(&self) -> Self { *self }
+}
+pub type GstHarness = Struct__GstHarness;
+#[cfg(feature = "check")]
+extern "C" {
+    pub fn gst_harness_new(element_name: *const gchar) -> *mut GstHarness;
+    pub fn gst_harness_new_with_padnames(element_name: *const gchar,
+                                         element_sinkpad_name: *const gchar,
+                                         element_srcpad_name: *const gchar)
+     -> *mut GstHarness;
+    pub fn gst_harness_new_parse(launchline: *const gchar) -> *mut GstHarness;
+    pub fn gst_harness_teardown(h: *mut GstHarness);
+    pub fn gst_harness_play(h: *mut GstHarness);
+    pub fn gst_harness_set_src_caps_str(h: *mut GstHarness, str: *const gchar);
+    pub fn gst_harness_set_sink_caps_str(h: *mut GstHarness, str: *const gchar);
+    pub fn gst_harness_set_caps_str(h: *mut GstHarness, _in: *const gchar,
+                                    out: *const gchar);
+    pub fn gst_harness_push(h: *mut GstHarness, buffer: *mut GstBuffer)
+     -> GstFlowReturn;
+    pub fn gst_harness_pull(h: *mut GstHarness) -> *mut GstBuffer;
+    pub fn gst_harness_try_pull(h: *mut GstHarness) -> *mut GstBuffer;
+    pub fn gst_harness_push_and_pull(h: *mut GstHarness, buffer: *mut GstBuffer)
+     -> *mut GstBuffer;
+    pub fn gst_harness_buffers_received(h: *mut GstHarness) -> guint;
+    pub fn gst_harness_buffers_in_queue(h: *mut GstHarness) -> guint;
+    pub fn gst_harness_push_event(h: *mut GstHarness, event: *mut GstEvent)
+     -> gboolean;
+    pub fn gst_harness_pull_event(h: *mut GstHarness) -> *mut GstEvent;
+    pub fn gst_harness_try_pull_event(h: *mut GstHarness) -> *mut GstEvent;
+    pub fn gst_harness_events_received(h: *mut GstHarness) -> guint;
+    pub fn gst_harness_events_in_queue(h: *mut GstHarness) -> guint;
+    pub fn gst_harness_push_upstream_event(h: *mut GstHarness,
+                                           event: *mut GstEvent) -> gboolean;
+    pub fn gst_harness_use_systemclock(h: *mut GstHarness);
+    pub fn gst_harness_use_testclock(h: *mut GstHarness);
+    pub fn gst_harness_set_time(h: *mut GstHarness, time: GstClockTime)
+     -> gboolean;
+    pub fn gst_harness_crank_single_clock_wait(h: *mut GstHarness) -> gboolean;
+    pub fn gst_harness_crank_multiple_clock_waits(h: *mut GstHarness,
+                                                  waits: guint) -> gboolean;
+    pub fn gst_harness_wait_for_clock_id_waits(h: *mut GstHarness,
+                                               waits: guint, timeout: guint)
+     -> gboolean;
+    pub fn gst_harness_set_upstream_latency(h: *mut GstHarness,
+                                            latency: GstClockTime);
+    pub fn gst_harness_add_src_parse(h: *mut GstHarness,
+                                     launchline: *const gchar,
+                                     has_clock_wait: gboolean);
+    pub fn gst_harness_push_from_src(h: *mut GstHarness) -> GstFlowReturn;
+    pub fn gst_harness_add_sink_parse(h: *mut GstHarness,
+                                      launchline: *const gchar);
+    pub fn gst_harness_push_to_sink(h: *mut GstHarness) -> GstFlowReturn;
+}
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstTracer {
+    pub parent: GstObject,
+    pub _priv: gpointer,
+    pub _gst_reserved: [gpointer; 4usize],
+}
+impl ::std::default::Default for Struct__GstTracer {
+    fn default() -> Struct__GstTracer { unsafe { ::std::mem::zeroed() } }
+}
+pub type GstTracer = Struct__GstTracer;
+#[repr(C)]
+pub struct Struct__GstTracerClass {
+    pub parent_class: GstObjectClass,
+    pub _gst_reserved: [gpointer; 4usize],
+}
+impl ::std::default::Default for Struct__GstTracerClass {
+    fn default() -> Struct__GstTracerClass { unsafe { ::std::mem::zeroed() } }
+}
+pub type GstTracerClass = Struct__GstTracerClass;
+extern "C" {
+    pub fn gst_tracer_get_type() -> GType;
+    pub fn gst_tracing_register_hook(tracer: *mut GstTracer,
+                                     detail: *const gchar, func: GCallback);
+}
+pub const GST_BUFFER_FLAG_SYNC_AFTER: raw::c_uint = 32768;
+pub const GST_BUFFER_FLAG_NON_DROPPABLE: raw::c_uint = 65536;
+pub const GST_EVENT_PROTECTION: raw::c_uint = 18718;
+#[repr(C)]
+#[derive(Clone,Copy)]
+pub struct Struct__GstProtectionMeta {
+    pub meta: GstMeta,
+    pub info: *mut GstStructure,
+}
+impl ::std::default::Default for Struct__GstProtectionMeta {
+    fn default() -> Struct__GstProtectionMeta {
+        unsafe { ::std::mem::zeroed() }
+    }
+}
+pub type GstProtectionMeta = Struct__GstProtectionMeta;
+extern "C" {
+    pub fn gst_protection_meta_api_get_type() -> GType;
+    pub fn gst_protection_meta_get_info() -> *const GstMetaInfo;
+    pub fn gst_buffer_add_protection_meta(buffer: *mut GstBuffer,
+                                          info: *mut GstStructure)
+     -> *mut GstProtectionMeta;
+    pub fn gst_protection_select_system(system_identifiers:
+                                            *mut *const gchar)
+     -> *const gchar;
+    pub fn gst_protection_filter_systems_by_available_decryptors(system_identifiers:
+                                                                     *mut *const gchar)
+     -> *mut *mut gchar;
+    pub fn gst_event_new_protection(system_id: *const gchar,
+                                    data: *mut GstBuffer,
+                                    origin: *const gchar) -> *mut GstEvent;
+    pub fn gst_event_parse_protection(event: *mut GstEvent,
+                                      system_id: *mut *const gchar,
+                                      data: *mut *mut GstBuffer,
+                                      origin: *mut *const gchar);
+}
+pub enum Struct__GstStreamVolume { }
+pub type GstStreamVolume = Struct__GstStreamVolume;
+pub type GstStreamVolumeFormat = raw::c_uint;
+pub const GST_STREAM_VOLUME_FORMAT_LINEAR: raw::c_uint = 0;
+pub const GST_STREAM_VOLUME_FORMAT_CUBIC: raw::c_uint = 1;
+pub const GST_STREAM_VOLUME_FORMAT_DB: raw::c_uint = 2;
+extern "C" {
+    pub fn gst_stream_volume_get_type() -> GType;
+    pub fn gst_stream_volume_set_volume(volume: *mut GstStreamVolume,
+                                        format: GstStreamVolumeFormat,
+                                        val: gdouble);
+    pub fn gst_stream_volume_get_volume(volume: *mut GstStreamVolume,
+                                        format: GstStreamVolumeFormat)
+     -> gdouble;
+    pub fn gst_stream_volume_set_mute(volume: *mut GstStreamVolume,
+                                      mute: gboolean);
+    pub fn gst_stream_volume_get_mute(volume: *mut GstStreamVolume)
+     -> gboolean;
+    pub fn gst_stream_volume_convert_volume(from: GstStreamVolumeFormat,
+                                            to: GstStreamVolumeFormat,
+                                            val: gdouble) -> gdouble;
+}
+pub enum Struct__GESTimeline { }
+pub type GESTimeline = Struct__GESTimeline;
+pub enum Struct__GESLayer { }
+pub type GESLayer = Struct__GESLayer;
+pub enum Struct__GESTimelineElement { }
+pub type GESTimelineElement = Struct__GESTimelineElement;
+pub enum Struct__GESClip { }
+pub type GESClip = Struct__GESClip;
+pub enum Struct__GESUriClip { }
+pub type GESUriClip = Struct__GESUriClip;
+pub enum Struct__GESTestClip { }
+pub type GESTestClip = Struct__GESTestClip;
+pub enum Struct__GESTitleClip { }
+pub type GESTitleClip = Struct__GESTitleClip;
+pub enum Struct__GESTransitionClip { }
+pub type GESTransitionClip = Struct__GESTransitionClip;
+pub enum Struct__GESAsset { }
+pub type GESAsset = Struct__GESAsset;
+pub enum Struct__GESUriClipAsset { }
+pub type GESUriClipAsset = Struct__GESUriClipAsset;
+pub enum Struct__GESPipeline { }
+pub type GESPipeline = Struct__GESPipeline;
+pub type GESTrackType = raw::c_uint;
+pub const GES_TRACK_TYPE_UNKNOWN: raw::c_uint = 1;
+pub const GES_TRACK_TYPE_AUDIO: raw::c_uint = 2;
+pub const GES_TRACK_TYPE_VIDEO: raw::c_uint = 4;
+pub const GES_TRACK_TYPE_TEXT: raw::c_uint = 8;
+pub const GES_TRACK_TYPE_CUSTOM: raw::c_uint = 16;
+pub type GESPipelineFlags = raw::c_uint;
+pub const GES_PIPELINE_MODE_PREVIEW_AUDIO: raw::c_uint = 1;
+pub const GES_PIPELINE_MODE_PREVIEW_VIDEO: raw::c_uint = 2;
+pub const GES_PIPELINE_MODE_PREVIEW: raw::c_uint = 3;
+pub const GES_PIPELINE_MODE_RENDER: raw::c_uint = 4;
+pub const GES_PIPELINE_MODE_SMART_RENDER: raw::c_uint = 8;
+#[cfg(feature = "ges")]
+extern "C" {
+    pub fn ges_init() -> gboolean;
+    pub fn ges_deinit();
+    pub fn ges_timeline_get_type() -> GType;
+    pub fn ges_timeline_new() -> *mut GESTimeline;
+    pub fn ges_timeline_new_audio_video() -> *mut GESTimeline;
+    pub fn ges_timeline_new_from_uri(uri: *const gchar,
+                                     error: *mut *mut GError)
+     -> *mut GESTimeline;
+    pub fn ges_timeline_save_to_uri(timeline: *mut GESTimeline,
+                                    uri: *const gchar,
+                                    formatter_asset: *mut GESAsset,
+                                    overwrite: gboolean,
+                                    error: *mut *mut GError) -> gboolean;
+    pub fn ges_timeline_append_layer(timeline: *mut GESTimeline)
+     -> *mut GESLayer;
+    pub fn ges_timeline_remove_layer(timeline: *mut GESTimeline,
+                                     layer: *mut GESLayer) -> gboolean;
+    pub fn ges_timeline_get_layers(timeline: *mut GESTimeline) -> *mut GList;
+    pub fn ges_timeline_get_layer(timeline: *mut GESTimeline,
+                                  priority: guint) -> *mut GESLayer;
+    pub fn ges_timeline_commit(timeline: *mut GESTimeline) -> gboolean;
+    pub fn ges_timeline_commit_sync(timeline: *mut GESTimeline) -> gboolean;
+    pub fn ges_timeline_get_duration(timeline: *mut GESTimeline)
+     -> GstClockTime;
+    pub fn ges_layer_get_type() -> GType;
+    pub fn ges_layer_add_clip(layer: *mut GESLayer, clip: *mut GESClip)
+     -> gboolean;
+    pub fn ges_layer_remove_clip(layer: *mut GESLayer, clip: *mut GESClip)
+     -> gboolean;
+    pub fn ges_layer_add_asset(layer: *mut GESLayer, asset: *mut GESAsset,
+                               start: GstClockTime, inpoint: GstClockTime,
+                               duration: GstClockTime,
+                               track_types: GESTrackType) -> *mut GESClip;
+    pub fn ges_layer_get_clips(layer: *mut GESLayer) -> *mut GList;
+    pub fn ges_layer_get_priority(layer: *mut GESLayer) -> guint;
+    pub fn ges_layer_get_auto_transition(layer: *mut GESLayer) -> gboolean;
+    pub fn ges_layer_set_auto_transition(layer: *mut GESLayer,
+                                         auto_transition: gboolean);
+    pub fn ges_layer_get_duration(layer: *mut GESLayer) -> GstClockTime;
+    pub fn ges_timeline_element_get_type() -> GType;
+    pub fn ges_timeline_element_set_start(_self: *mut GESTimelineElement,
+                                          start: GstClockTime) -> gboolean;
+    pub fn ges_timeline_element_set_inpoint(_self: *mut GESTimelineElement,
+                                            inpoint: GstClockTime)
+     -> gboolean;
+    pub fn ges_timeline_element_set_duration(_self: *mut GESTimelineElement,
+                                             duration: GstClockTime)
+     -> gboolean;
+    pub fn ges_timeline_element_trim(_self: *mut GESTimelineElement,
+                                     start: GstClockTime) -> gboolean;
+    pub fn ges_timeline_element_get_name(_self: *mut GESTimelineElement)
+     -> *mut gchar;
+    pub fn ges_clip_get_type() -> GType;
+    pub fn ges_clip_split(clip: *mut GESClip, position: guint64)
+     -> *mut GESClip;
+    pub fn ges_clip_get_layer(clip: *mut GESClip) -> *mut GESLayer;
+    pub fn ges_clip_move_to_layer(clip: *mut GESClip, layer: *mut GESLayer)
+     -> gboolean;
+    pub fn ges_uri_clip_new(uri: *const gchar) -> *mut GESUriClip;
+    pub fn ges_uri_clip_get_uri(_self: *mut GESUriClip) -> *const gchar;
+    pub fn ges_uri_clip_get_type() -> GType;
+    pub fn ges_test_clip_new() -> *mut GESTestClip;
+    pub fn ges_title_clip_new() -> *mut GESTitleClip;
+    pub fn ges_transition_clip_new_for_nick(nick: *mut gchar)
+     -> *mut GESTransitionClip;
+    pub fn ges_uri_clip_asset_request_sync(uri: *const gchar,
+                                           error: *mut *mut GError)
+     -> *mut GESUriClipAsset;
+    pub fn ges_pipeline_get_type() -> GType;
+    pub fn ges_pipeline_new() -> *mut GESPipeline;
+    pub fn ges_pipeline_set_timeline(pipeline: *mut GESPipeline,
+                                     timeline: *mut GESTimeline) -> gboolean;
+    pub fn ges_pipeline_set_mode(pipeline: *mut GESPipeline,
+                                 mode: GESPipelineFlags) -> gboolean;
+    pub fn ges_pipeline_get_mode(pipeline: *mut GESPipeline)
+     -> GESPipelineFlags;
+    pub fn ges_pipeline_set_render_settings(pipeline: *mut GESPipeline,
+                                            output_uri: *const gchar,
+                                            profile: *mut GstEncodingProfile)
+     -> gboolean;
+}
+
+pub enum Struct__GModule { }
+pub type GModule = Struct__GModule;
+pub type GModuleFlags = raw::c_uint;
+pub const G_MODULE_BIND_LAZY: raw::c_uint = 1;
+pub const G_MODULE_BIND_LOCAL: raw::c_uint = 2;
+extern "C" {
+    pub fn g_module_open(file_name: *const gchar, flags: GModuleFlags)
+     -> *mut GModule;
+    pub fn g_module_symbol(module: *mut GModule, symbol_name: *const gchar,
+                           symbol: *mut gpointer) -> gboolean;
+    pub fn g_module_close(module: *mut GModule) -> gboolean;
+}
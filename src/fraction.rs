@@ -0,0 +1,308 @@
+use ffi::*;
+use value::{Value, ToValue, FromValue};
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Debug, Formatter};
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
+/// A rational number as used for framerates and pixel aspect ratios in
+/// caps, always kept reduced with a positive denominator:
+///
+/// ```ignore
+/// let framerate = gst::Fraction::new(60000, 2002);
+/// assert_eq!(framerate, gst::Fraction::new(30000, 1001));
+/// let frame_duration = 1_000_000_000. / framerate.to_f64();
+/// caps.structure_mut(0).unwrap().set("framerate", &framerate);
+/// ```
+///
+/// The checked_* methods return None when the result doesn't fit in an
+/// i32 numerator and denominator. The operators never panic, they
+/// saturate to the closest fraction that fits instead
+#[derive(Copy,Clone,PartialEq,Eq,Hash)]
+pub struct Fraction{
+    numerator: i32,
+    denominator: i32,
+}
+
+impl Fraction{
+    /// Panics if denominator is 0
+    pub fn new(numerator: i32, denominator: i32) -> Fraction{
+        assert!(denominator != 0, "fraction with 0 denominator");
+        saturate(numerator as i128, denominator as i128)
+    }
+
+    /// Closest fraction to value, eg: 29.97 gives 2997/100
+    pub fn from_f64(value: f64) -> Fraction{
+        let (mut numerator, mut denominator) = (0, 1);
+        unsafe{
+            gst_util_double_to_fraction(value, &mut numerator, &mut denominator);
+        }
+        Fraction::new(numerator, denominator)
+    }
+
+    pub fn numerator(&self) -> i32{
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i32{
+        self.denominator
+    }
+
+    pub fn to_f64(&self) -> f64{
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// None if the result overflows
+    pub fn checked_add(self, other: Fraction) -> Option<Fraction>{
+        let (a, b, c, d) = self.wide(other);
+        reduce(a * d + c * b, b * d)
+    }
+
+    /// None if the result overflows
+    pub fn checked_sub(self, other: Fraction) -> Option<Fraction>{
+        let (a, b, c, d) = self.wide(other);
+        reduce(a * d - c * b, b * d)
+    }
+
+    /// None if the result overflows
+    pub fn checked_mul(self, other: Fraction) -> Option<Fraction>{
+        let (a, b, c, d) = self.wide(other);
+        reduce(a * c, b * d)
+    }
+
+    /// None if the result overflows or other is 0
+    pub fn checked_div(self, other: Fraction) -> Option<Fraction>{
+        if other.numerator == 0{
+            return None;
+        }
+        let (a, b, c, d) = self.wide(other);
+        reduce(a * d, b * c)
+    }
+
+    fn wide(self, other: Fraction) -> (i128, i128, i128, i128){
+        (self.numerator as i128, self.denominator as i128, other.numerator as i128, other.denominator as i128)
+    }
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128{
+    a = a.abs();
+    b = b.abs();
+    while b != 0{
+        let rem = a % b;
+        a = b;
+        b = rem;
+    }
+    a
+}
+
+// numerator/denominator reduced with a positive denominator, None if
+// it doesn't fit in a Fraction
+fn reduce(numerator: i128, denominator: i128) -> Option<Fraction>{
+    let gcd = gcd(numerator, denominator).max(1);
+    let sign = if denominator < 0 { -1 } else { 1 };
+    let (numerator, denominator) = (numerator / gcd * sign, denominator / gcd * sign);
+    let max = i32::MAX as i128;
+    if numerator < -max || numerator > max || denominator > max{
+        None
+    }else{
+        Some(Fraction{
+            numerator: numerator as i32,
+            denominator: denominator as i32,
+        })
+    }
+}
+
+// a / b rounded to the closest integer, b > 0
+fn round_div(a: i128, b: i128) -> i128{
+    if a >= 0 { (a + b / 2) / b } else { (a - b / 2) / b }
+}
+
+// the fraction closest to numerator/denominator: itself if it fits,
+// ±i32::MAX if it's bigger than that or the closest one with
+// i32::MAX as numerator or denominator otherwise. A 0 denominator
+// saturates to ±i32::MAX, or 0 for 0/0
+fn saturate(numerator: i128, denominator: i128) -> Fraction{
+    let max = i32::MAX as i128;
+    let (numerator, denominator) = if denominator < 0 { (-numerator, -denominator) } else { (numerator, denominator) };
+    let sign = numerator.signum();
+    if denominator == 0{
+        return reduce(sign * max, 1).unwrap();
+    }
+    if let Some(fraction) = reduce(numerator, denominator){
+        return fraction;
+    }
+    if numerator.abs() <= denominator{
+        reduce(round_div(numerator * max, denominator), max).unwrap()
+    }else{
+        match round_div(denominator * max, numerator.abs()){
+            0 => reduce(sign * max, 1).unwrap(),
+            denominator => reduce(sign * max, denominator).unwrap(),
+        }
+    }
+}
+
+impl From<(i32,i32)> for Fraction{
+    fn from((numerator, denominator): (i32,i32)) -> Fraction{
+        Fraction::new(numerator, denominator)
+    }
+}
+
+impl From<i32> for Fraction{
+    fn from(value: i32) -> Fraction{
+        Fraction::new(value, 1)
+    }
+}
+
+impl From<Fraction> for (i32,i32){
+    fn from(fraction: Fraction) -> (i32,i32){
+        (fraction.numerator, fraction.denominator)
+    }
+}
+
+impl Neg for Fraction{
+    type Output = Fraction;
+    fn neg(self) -> Fraction{
+        Fraction{
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl Add for Fraction{
+    type Output = Fraction;
+    fn add(self, other: Fraction) -> Fraction{
+        let (a, b, c, d) = self.wide(other);
+        saturate(a * d + c * b, b * d)
+    }
+}
+
+impl Sub for Fraction{
+    type Output = Fraction;
+    fn sub(self, other: Fraction) -> Fraction{
+        let (a, b, c, d) = self.wide(other);
+        saturate(a * d - c * b, b * d)
+    }
+}
+
+impl Mul for Fraction{
+    type Output = Fraction;
+    fn mul(self, other: Fraction) -> Fraction{
+        let (a, b, c, d) = self.wide(other);
+        saturate(a * c, b * d)
+    }
+}
+
+impl Div for Fraction{
+    type Output = Fraction;
+    fn div(self, other: Fraction) -> Fraction{
+        let (a, b, c, d) = self.wide(other);
+        saturate(a * d, b * c)
+    }
+}
+
+impl PartialOrd for Fraction{
+    fn partial_cmp(&self, other: &Fraction) -> Option<Ordering>{
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Fraction{
+    fn cmp(&self, other: &Fraction) -> Ordering{
+        // denominators are always positive
+        (self.numerator as i64 * other.denominator as i64).cmp(&(other.numerator as i64 * self.denominator as i64))
+    }
+}
+
+impl Display for Fraction{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+impl Debug for Fraction{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "Fraction({}/{})", self.numerator, self.denominator)
+    }
+}
+
+impl ToValue for Fraction{
+    fn to_value(&self) -> Value{
+        unsafe{
+            let mut value = Value::new(gst_fraction_get_type());
+            gst_value_set_fraction(value.gst_value_mut(), self.numerator, self.denominator);
+            value
+        }
+    }
+}
+
+impl FromValue for Fraction{
+    fn from_value(value: &Value) -> Option<Fraction>{
+        unsafe{
+            if value.holds(gst_fraction_get_type()){
+                let denominator = gst_value_get_fraction_denominator(value.gst_value());
+                if denominator != 0{
+                    return Some(Fraction::new(gst_value_get_fraction_numerator(value.gst_value()), denominator));
+                }
+            }
+            None
+        }
+    }
+}
+
+impl From<Fraction> for Value{
+    fn from(fraction: Fraction) -> Value{
+        fraction.to_value()
+    }
+}
+
+
+#[cfg(test)]
+mod tests{
+    use super::Fraction;
+    use std::i32;
+
+    #[test]
+    fn reduces(){
+        assert_eq!(Fraction::new(60000, 2002), Fraction::new(30000, 1001));
+        assert_eq!(Fraction::new(2, -4), Fraction::new(-1, 2));
+        assert_eq!(Fraction::new(0, -5), Fraction::new(0, 1));
+    }
+
+    #[test]
+    fn checked_overflow(){
+        let max = Fraction::new(i32::MAX, 1);
+        assert_eq!(max.checked_add(Fraction::new(1, 1)), None);
+        assert_eq!((-max).checked_sub(Fraction::new(1, 1)), None);
+        assert_eq!(max.checked_mul(Fraction::new(2, 1)), None);
+        assert_eq!(Fraction::new(1, i32::MAX).checked_div(Fraction::new(i32::MAX, 1)), None);
+        assert_eq!(max.checked_div(Fraction::new(0, 1)), None);
+        // intermediate products overflow i32 but the result fits
+        assert_eq!(max.checked_mul(Fraction::new(1, i32::MAX)), Some(Fraction::new(1, 1)));
+        assert_eq!(max.checked_sub(max), Some(Fraction::new(0, 1)));
+    }
+
+    #[test]
+    fn operators_saturate(){
+        let max = Fraction::new(i32::MAX, 1);
+        assert_eq!(max + Fraction::new(1, 1), max);
+        assert_eq!(-max - Fraction::new(1, 1), -max);
+        assert_eq!(max * max, max);
+        assert_eq!(max * -max, -max);
+        assert_eq!(Fraction::new(1, 2) / Fraction::new(0, 1), max);
+        assert_eq!(Fraction::new(-1, 2) / Fraction::new(0, 1), -max);
+        assert_eq!(Fraction::new(0, 1) / Fraction::new(0, 1), Fraction::new(0, 1));
+        assert_eq!(Fraction::new(1, i32::MAX) * Fraction::new(1, 2), Fraction::new(1, i32::MAX));
+        assert_eq!(Fraction::new(i32::MIN, 1), -max);
+    }
+
+    #[test]
+    fn saturates_to_closest(){
+        // 1/3 + 1/(MAX - 2) doesn't fit, the result is scaled to a
+        // MAX denominator
+        let sum = Fraction::new(1, 3) + Fraction::new(1, i32::MAX - 2);
+        assert_eq!(Fraction::new(1, 3).checked_add(Fraction::new(1, i32::MAX - 2)), None);
+        assert!((sum.to_f64() - (1. / 3. + 1. / (i32::MAX - 2) as f64)).abs() < 1e-9);
+        assert_eq!(sum.denominator(), i32::MAX);
+    }
+}
@@ -0,0 +1,228 @@
+use ffi::*;
+use util::*;
+
+use appsrc::AppSrc;
+use element::Element;
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use handle::ProbeHandle;
+use reference::Reference;
+
+use std::sync::{Arc, Mutex, Condvar};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// A gap event, sent downstream instead of buffers to tell the elements
+/// after it that there's no data for a period of time, so muxers and
+/// aggregators don't wait for that stream, eg: a subtitle track between
+/// two subtitles
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct GapEvent{
+    /// Start of the gap in stream time, nanoseconds
+    pub timestamp: u64,
+    /// Length of the gap, None if it's unknown
+    pub duration: Option<u64>,
+}
+
+impl GapEvent{
+    pub fn new(timestamp: u64, duration: Option<u64>) -> GapEvent{
+        GapEvent{
+            timestamp: timestamp,
+            duration: duration,
+        }
+    }
+
+    /// Parses a gap event, None for any other event
+    pub unsafe fn new_from_event(event: *mut GstEvent) -> Option<GapEvent>{
+        if event == ptr::null_mut() || (*event)._type != GST_EVENT_GAP{
+            return None;
+        }
+        let mut timestamp: GstClockTime = 0;
+        let mut duration: GstClockTime = GST_CLOCK_TIME_NONE;
+        gst_event_parse_gap(event, &mut timestamp, &mut duration);
+        Some(GapEvent{
+            timestamp: timestamp,
+            duration: if duration != GST_CLOCK_TIME_NONE { Some(duration) } else { None },
+        })
+    }
+
+    /// Creates a new gap event, eg: to push it from a src pad or send it
+    /// to an appsrc with Element::send_event
+    pub fn to_event(&self) -> *mut GstEvent{
+        unsafe{ gst_event_new_gap(self.timestamp, self.duration.unwrap_or(GST_CLOCK_TIME_NONE)) }
+    }
+}
+
+/// Calls callback with every gap event that goes through pad, eg: the
+/// sink pad of a muxer to check a sparse stream keeps advancing. The
+/// probe is removed when the returned handle is dropped
+pub fn watch_gap_events<F>(pad: &mut Pad, mut callback: F) -> Option<ProbeHandle>
+    where F: FnMut(&GapEvent) + Send + 'static{
+    pad.add_probe(GST_PAD_PROBE_TYPE_EVENT_DOWNSTREAM, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+        if let Some(event) = info.event(){
+            if let Some(gap) = unsafe{ GapEvent::new_from_event(event.gst_event() as *mut GstEvent) }{
+                callback(&gap);
+            }
+        }
+        PadProbeReturn::Ok
+    })
+}
+
+struct Inner{
+    // end of the last buffer or gap, where the next gap starts
+    position: Option<u64>,
+    last_data: Option<Instant>,
+    timeout: Duration,
+    gaps: u64,
+    stop: bool,
+}
+
+struct Shared{
+    inner: Mutex<Inner>,
+    cond: Condvar,
+}
+
+/// Sends gap events from an appsrc when no buffer has been pushed for
+/// a while, so a sparse stream, eg: subtitles or KLV metadata, doesn't
+/// stall the muxer or compositor it's linked to:
+///
+/// ```ignore
+/// let mut klvsrc = gst::AppSrc::new("klvsrc").unwrap();
+/// let filler = gst::gap::GapFiller::attach(&mut klvsrc, Duration::from_millis(500)).unwrap();
+/// // push buffers from the application as usual
+/// klvsrc.push_buffer(buffer);
+/// ```
+///
+/// Every time timeout passes without a buffer, a gap of timeout is sent
+/// starting where the last buffer or gap ended, so the buffers have to
+/// be timestamped. It starts with the first timestamped buffer, or when
+/// arm() is called, and stops on EOS or when the filler is dropped.
+///
+/// The events are queued by appsrc in order with the buffers, which
+/// needs GStreamer 1.18 or later.
+pub struct GapFiller{
+    shared: Arc<Shared>,
+    _probe: ProbeHandle,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl GapFiller{
+    pub fn attach(appsrc: &mut AppSrc, timeout: Duration) -> Option<GapFiller>{
+        let shared = Arc::new(Shared{
+            inner: Mutex::new(Inner{
+                position: None,
+                last_data: None,
+                timeout: timeout,
+                gaps: 0,
+                stop: false,
+            }),
+            cond: Condvar::new(),
+        });
+        let probe_shared = shared.clone();
+        let mask = GST_PAD_PROBE_TYPE_BUFFER | GST_PAD_PROBE_TYPE_BUFFER_LIST | GST_PAD_PROBE_TYPE_EVENT_DOWNSTREAM;
+        let probe = appsrc.static_pad("src")?.add_probe(mask, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+            let mut inner = probe_shared.inner.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(buffer) = info.buffer(){
+                if let Some(pts) = buffer.pts(){
+                    let end = pts + buffer.duration().unwrap_or(0);
+                    inner.position = Some(inner.position.map_or(end, |position| position.max(end)));
+                }
+                if inner.position.is_some(){
+                    inner.last_data = Some(Instant::now());
+                    probe_shared.cond.notify_all();
+                }
+            }else if info.is_buffer_list(){
+                if inner.position.is_some(){
+                    inner.last_data = Some(Instant::now());
+                }
+            }else if let Some(event) = info.event(){
+                match event.event_type(){
+                    GST_EVENT_EOS => {
+                        inner.last_data = None;
+                    }
+                    GST_EVENT_FLUSH_STOP => {
+                        inner.position = None;
+                        inner.last_data = None;
+                    }
+                    _ => ()
+                }
+            }
+            PadProbeReturn::Ok
+        })?;
+        let element = appsrc.reference();
+        let thread_shared = shared.clone();
+        let thread = thread::spawn(move || fill(thread_shared, element.into()));
+        Some(GapFiller{
+            shared: shared,
+            _probe: probe,
+            thread: Some(thread),
+        })
+    }
+
+    /// Starts sending gaps from position, in nanoseconds, without
+    /// waiting for the first buffer, eg: for a stream that may have no
+    /// data at the beginning. The caps and segment have to be already
+    /// sent, the first gap is sent after timeout
+    pub fn arm(&self, position: u64){
+        let mut inner = self.shared.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.position = Some(position);
+        inner.last_data = Some(Instant::now());
+        self.shared.cond.notify_all();
+    }
+
+    /// Stops sending gaps until the next buffer or arm()
+    pub fn disarm(&self){
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).last_data = None;
+        self.shared.cond.notify_all();
+    }
+
+    /// Time without buffers before a gap is sent, also the length of
+    /// each gap
+    pub fn set_timeout(&self, timeout: Duration){
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).timeout = timeout;
+        self.shared.cond.notify_all();
+    }
+
+    /// Number of gap events sent so far
+    pub fn gaps(&self) -> u64{
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).gaps
+    }
+}
+
+impl Drop for GapFiller{
+    fn drop(&mut self){
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).stop = true;
+        self.shared.cond.notify_all();
+        if let Some(thread) = self.thread.take(){
+            let _ = thread.join();
+        }
+    }
+}
+
+// runs in the filler thread until the GapFiller is dropped
+fn fill(shared: Arc<Shared>, mut appsrc: Element){
+    let mut inner = shared.inner.lock().unwrap_or_else(|e| e.into_inner());
+    while !inner.stop{
+        let (elapsed, position) = match (inner.last_data, inner.position){
+            (Some(last_data), Some(position)) => (last_data.elapsed(), position),
+            _ => {
+                inner = shared.cond.wait(inner).unwrap_or_else(|e| e.into_inner());
+                continue;
+            }
+        };
+        if elapsed < inner.timeout{
+            let wait = inner.timeout - elapsed;
+            inner = shared.cond.wait_timeout(inner, wait).unwrap_or_else(|e| e.into_inner()).0;
+            continue;
+        }
+        let duration = inner.timeout.as_secs() * 1_000_000_000 + inner.timeout.subsec_nanos() as u64;
+        inner.position = Some(position + duration);
+        inner.last_data = Some(Instant::now());
+        inner.gaps += 1;
+        drop(inner);
+        // without the lock, the probe runs when appsrc pushes the event
+        unsafe{
+            appsrc.send_event(GapEvent::new(position, Some(duration)).to_event());
+        }
+        inner = shared.inner.lock().unwrap_or_else(|e| e.into_inner());
+    }
+}
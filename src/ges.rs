@@ -0,0 +1,586 @@
+use ffi::*;
+use util::*;
+
+use element::Element;
+use encoding_profile::EncodingProfile;
+use error::{Error, Result};
+use gobject::GObjectRef;
+use pipeline::Pipeline;
+use reference::Reference;
+
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+/// Initializes GStreamer Editing Services, call it after gst::init and
+/// before using any of the types in this module
+pub fn init() -> bool{
+    unsafe{
+        ges_init() != 0
+    }
+}
+
+/// Kind of tracks a clip has streams in, combine them with |
+pub type TrackType = GESTrackType;
+
+/// What a TimelinePipeline does with the timeline
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum PipelineMode{
+    /// Plays audio and video, eg: for the preview of an editor
+    Preview = GES_PIPELINE_MODE_PREVIEW,
+    PreviewAudio = GES_PIPELINE_MODE_PREVIEW_AUDIO,
+    PreviewVideo = GES_PIPELINE_MODE_PREVIEW_VIDEO,
+    /// Encodes the timeline to the uri set with set_render_settings
+    Render = GES_PIPELINE_MODE_RENDER,
+    /// Same as Render but copies the parts of the sources that don't
+    /// need to be reencoded
+    SmartRender = GES_PIPELINE_MODE_SMART_RENDER,
+}
+
+/// A non-linear editing timeline: layers of clips placed at some
+/// position, that can be cut, trimmed and moved, and rendered as a
+/// single stream:
+///
+/// ```ignore
+/// gst::ges::init();
+/// let mut timeline = gst::ges::Timeline::new_audio_video().unwrap();
+/// let mut layer = timeline.append_layer().unwrap();
+/// layer.set_auto_transition(true);
+/// layer.add_uri("file:///tmp/intro.mp4", 0, 0, 5_000_000_000).unwrap();
+/// layer.add_uri("file:///tmp/talk.mp4", 4_000_000_000, 0, 60_000_000_000).unwrap();
+/// timeline.commit();
+///
+/// let mut pipeline = gst::ges::TimelinePipeline::new(&timeline).unwrap();
+/// pipeline.set_render_settings("file:///tmp/out.webm", &profile);
+/// pipeline.set_mode(gst::ges::PipelineMode::Render);
+/// pipeline.play();
+/// ```
+///
+/// Changes to the clips are only applied to the tracks of the timeline
+/// after commit is called.
+///
+/// The timeline, its layers and clips aren't thread safe so they can't
+/// be sent to other threads, use them from the thread that created
+/// them, usually the main one.
+pub struct Timeline{
+    timeline: Element,
+    thread: PhantomData<*const ()>,
+}
+
+impl Timeline{
+    /// An empty timeline without tracks
+    pub fn new() -> Option<Timeline>{
+        unsafe{
+            Timeline::new_from_floating(ges_timeline_new())
+        }
+    }
+
+    /// A timeline with an audio and a video track, the usual one for
+    /// editing video
+    pub fn new_audio_video() -> Option<Timeline>{
+        unsafe{
+            Timeline::new_from_floating(ges_timeline_new_audio_video())
+        }
+    }
+
+    /// Loads a project saved with save_to_uri
+    pub fn new_from_uri(uri: &str) -> Result<Timeline>{
+        let curi = CString::new(uri)?;
+        unsafe{
+            let mut error = ptr::null_mut();
+            let timeline = ges_timeline_new_from_uri(curi.as_ptr(), &mut error);
+            if error != ptr::null_mut(){
+                if timeline != ptr::null_mut(){
+                    gst_object_unref(gst_object_ref_sink(timeline as gpointer));
+                }
+                return Err(Error::new_from_g_error(error));
+            }
+            Timeline::new_from_floating(timeline).ok_or(Error::new(0, 0, "couldn't load timeline"))
+        }
+    }
+
+    unsafe fn new_from_floating(timeline: *mut GESTimeline) -> Option<Timeline>{
+        if timeline == ptr::null_mut(){
+            return None;
+        }
+        gst_object_ref_sink(timeline as gpointer);
+        Element::new_from_gst_element(timeline as *mut GstElement).map(Timeline::new_from_element)
+    }
+
+    pub fn new_from_element(element: Element) -> Timeline{
+        Timeline{ timeline: element, thread: PhantomData }
+    }
+
+    /// Saves the timeline as a project, by default in the xges format,
+    /// so it can be loaded with new_from_uri
+    pub fn save_to_uri(&mut self, uri: &str, overwrite: bool) -> Result<()>{
+        let curi = CString::new(uri)?;
+        unsafe{
+            let mut error = ptr::null_mut();
+            if ges_timeline_save_to_uri(self.gst_timeline_mut(), curi.as_ptr(), ptr::null_mut(), overwrite as gboolean, &mut error) != 0{
+                Ok(())
+            }else if error != ptr::null_mut(){
+                Err(Error::new_from_g_error(error))
+            }else{
+                Err(Error::new(0, 0, "couldn't save timeline"))
+            }
+        }
+    }
+
+    /// Adds a layer below the existing ones, clips in the layers above
+    /// cover the ones below
+    pub fn append_layer(&mut self) -> Option<Layer>{
+        unsafe{
+            Layer::new_ref(ges_timeline_append_layer(self.gst_timeline_mut()))
+        }
+    }
+
+    pub fn remove_layer(&mut self, layer: &Layer) -> bool{
+        unsafe{
+            ges_timeline_remove_layer(self.gst_timeline_mut(), layer.gst_layer()) != 0
+        }
+    }
+
+    /// The layers sorted by priority, the topmost first
+    pub fn layers(&self) -> Vec<Layer>{
+        unsafe{
+            let list = ges_timeline_get_layers(self.gst_timeline() as *mut GESTimeline);
+            let layers = list_to_vec(list, |layer| Layer::new(layer as *mut GESLayer));
+            g_list_free(list);
+            layers
+        }
+    }
+
+    pub fn layer(&self, priority: u32) -> Option<Layer>{
+        unsafe{
+            Layer::new(ges_timeline_get_layer(self.gst_timeline() as *mut GESTimeline, priority))
+        }
+    }
+
+    /// Applies the changes made to the clips and layers since the last
+    /// commit. Returns false if there was nothing to commit
+    pub fn commit(&mut self) -> bool{
+        unsafe{
+            ges_timeline_commit(self.gst_timeline_mut()) != 0
+        }
+    }
+
+    /// Same as commit but waits until the changes are applied to a
+    /// playing pipeline
+    pub fn commit_sync(&mut self) -> bool{
+        unsafe{
+            ges_timeline_commit_sync(self.gst_timeline_mut()) != 0
+        }
+    }
+
+    /// End of the last clip in nanoseconds
+    pub fn duration(&self) -> u64{
+        unsafe{
+            ges_timeline_get_duration(self.gst_timeline() as *mut GESTimeline)
+        }
+    }
+
+    pub unsafe fn gst_timeline(&self) -> *const GESTimeline{
+        self.timeline.gst_element() as *const GESTimeline
+    }
+
+    pub unsafe fn gst_timeline_mut(&mut self) -> *mut GESTimeline{
+        self.timeline.gst_element_mut() as *mut GESTimeline
+    }
+}
+
+impl Reference for Timeline{
+    fn reference(&self) -> Timeline{
+        Timeline{ timeline: self.timeline.reference(), thread: PhantomData }
+    }
+}
+
+impl AsRef<Element> for Timeline{
+    fn as_ref(&self) -> &Element{
+        &self.timeline
+    }
+}
+
+impl AsMut<Element> for Timeline{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.timeline
+    }
+}
+
+impl From<Timeline> for Element{
+    fn from(t: Timeline) -> Element{
+        t.timeline
+    }
+}
+
+impl Deref for Timeline{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.timeline
+    }
+}
+
+impl DerefMut for Timeline{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.timeline
+    }
+}
+
+/// A layer of a timeline, clips in the same layer can overlap to make
+/// transitions
+#[derive(Clone)]
+pub struct Layer{
+    layer: GObjectRef,
+    thread: PhantomData<*const ()>,
+}
+
+impl Layer{
+    // takes ownership of layer
+    unsafe fn new(layer: *mut GESLayer) -> Option<Layer>{
+        GObjectRef::new(layer as gpointer).map(|layer| Layer{ layer: layer, thread: PhantomData })
+    }
+
+    unsafe fn new_ref(layer: *mut GESLayer) -> Option<Layer>{
+        GObjectRef::new_ref(layer as gpointer).map(|layer| Layer{ layer: layer, thread: PhantomData })
+    }
+
+    /// Adds clip to the layer at the start position of the clip
+    pub fn add_clip(&mut self, clip: &Clip) -> bool{
+        unsafe{
+            ges_layer_add_clip(self.gst_layer(), clip.gst_clip()) != 0
+        }
+    }
+
+    pub fn remove_clip(&mut self, clip: &Clip) -> bool{
+        unsafe{
+            ges_layer_remove_clip(self.gst_layer(), clip.gst_clip()) != 0
+        }
+    }
+
+    /// Adds the media file at uri, placed at start in the timeline,
+    /// from inpoint in the file and lasting duration. The file is
+    /// discovered synchronously, which can take a while for remote
+    /// files. Durations of GST_CLOCK_TIME_NONE use the whole file
+    pub fn add_uri(&mut self, uri: &str, start: u64, inpoint: u64, duration: u64) -> Result<Clip>{
+        let curi = CString::new(uri)?;
+        unsafe{
+            let mut error = ptr::null_mut();
+            let asset = ges_uri_clip_asset_request_sync(curi.as_ptr(), &mut error);
+            if asset == ptr::null_mut(){
+                return if error != ptr::null_mut(){
+                    Err(Error::new_from_g_error(error))
+                }else{
+                    Err(Error::new(0, 0, &format!("couldn't load {}", uri)))
+                };
+            }
+            let clip = ges_layer_add_asset(self.gst_layer(), asset as *mut GESAsset, start, inpoint, duration, GES_TRACK_TYPE_UNKNOWN);
+            g_object_unref(asset as gpointer);
+            Clip::new_ref(clip).ok_or(Error::new(0, 0, &format!("couldn't add {} to the layer", uri)))
+        }
+    }
+
+    /// The clips of the layer sorted by start
+    pub fn clips(&self) -> Vec<Clip>{
+        unsafe{
+            let list = ges_layer_get_clips(self.gst_layer());
+            let clips = list_to_vec(list, |clip| Clip::new(clip as *mut GESClip));
+            g_list_free(list);
+            clips
+        }
+    }
+
+    /// Position of the layer in the timeline, 0 is the topmost
+    pub fn priority(&self) -> u32{
+        unsafe{
+            ges_layer_get_priority(self.gst_layer())
+        }
+    }
+
+    /// When enabled overlapping clips get a crossfade between them
+    /// automatically
+    pub fn set_auto_transition(&mut self, auto_transition: bool){
+        unsafe{
+            ges_layer_set_auto_transition(self.gst_layer(), auto_transition as gboolean);
+        }
+    }
+
+    pub fn auto_transition(&self) -> bool{
+        unsafe{
+            ges_layer_get_auto_transition(self.gst_layer()) != 0
+        }
+    }
+
+    pub fn duration(&self) -> u64{
+        unsafe{
+            ges_layer_get_duration(self.gst_layer())
+        }
+    }
+
+    pub unsafe fn gst_layer(&self) -> *mut GESLayer{
+        self.layer.object as *mut GESLayer
+    }
+}
+
+/// A clip of a timeline, a media file, a test pattern, a title or a
+/// transition, placed at start in its layer and playing from inpoint in
+/// its source for duration. Times are in nanoseconds
+#[derive(Clone)]
+pub struct Clip{
+    clip: GObjectRef,
+    thread: PhantomData<*const ()>,
+}
+
+impl Clip{
+    // takes ownership of clip
+    unsafe fn new(clip: *mut GESClip) -> Option<Clip>{
+        GObjectRef::new(clip as gpointer).map(|clip| Clip{ clip: clip, thread: PhantomData })
+    }
+
+    unsafe fn new_ref(clip: *mut GESClip) -> Option<Clip>{
+        GObjectRef::new_ref(clip as gpointer).map(|clip| Clip{ clip: clip, thread: PhantomData })
+    }
+
+    unsafe fn new_from_floating(clip: *mut GESClip) -> Option<Clip>{
+        if clip != ptr::null_mut(){
+            g_object_ref_sink(clip as gpointer);
+        }
+        Clip::new(clip)
+    }
+
+    /// A clip for the media file at uri, the file is discovered
+    /// synchronously. None if it can't be played
+    pub fn new_uri(uri: &str) -> Option<Clip>{
+        let curi = CString::new(uri).ok()?;
+        unsafe{
+            Clip::new_from_floating(ges_uri_clip_new(curi.as_ptr()) as *mut GESClip)
+        }
+    }
+
+    /// A clip with a test pattern and a test tone, eg: as a placeholder
+    pub fn new_test() -> Option<Clip>{
+        unsafe{
+            Clip::new_from_floating(ges_test_clip_new() as *mut GESClip)
+        }
+    }
+
+    /// A clip rendering text over a black background
+    pub fn new_title(text: &str) -> Option<Clip>{
+        let ctext = CString::new(text).ok()?;
+        let cname = CString::new("text").unwrap();
+        unsafe{
+            let clip = Clip::new_from_floating(ges_title_clip_new() as *mut GESClip)?;
+            g_object_set(clip.gst_clip() as gpointer, cname.as_ptr(), ctext.as_ptr(), ptr::null::<gchar>());
+            Some(clip)
+        }
+    }
+
+    /// A transition to place between two overlapping clips, nick is
+    /// the name of the effect, eg: "crossfade" or "bar-wipe-lr"
+    pub fn new_transition(nick: &str) -> Option<Clip>{
+        let cnick = CString::new(nick).ok()?;
+        unsafe{
+            Clip::new_from_floating(ges_transition_clip_new_for_nick(cnick.as_ptr() as *mut gchar) as *mut GESClip)
+        }
+    }
+
+    /// Uri of the media file if it's a clip from a file
+    pub fn uri(&self) -> Option<String>{
+        unsafe{
+            if g_type_check_instance_is_a(self.clip.object as *mut GTypeInstance, ges_uri_clip_get_type()) == 0{
+                return None;
+            }
+            let uri = ges_uri_clip_get_uri(self.gst_clip() as *mut GESUriClip);
+            if uri != ptr::null(){
+                Some(from_c_str!(uri).to_string())
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn name(&self) -> Option<String>{
+        unsafe{
+            let name = ges_timeline_element_get_name(self.gst_timeline_element());
+            if name != ptr::null_mut(){
+                let ret = from_c_str!(name).to_string();
+                g_free(name as gpointer);
+                Some(ret)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Position of the clip in the timeline
+    pub fn start(&self) -> u64{
+        self.get_u64("start")
+    }
+
+    pub fn set_start(&mut self, start: u64){
+        unsafe{
+            ges_timeline_element_set_start(self.gst_timeline_element(), start);
+        }
+    }
+
+    /// Position in the source the clip starts playing from
+    pub fn inpoint(&self) -> u64{
+        self.get_u64("in-point")
+    }
+
+    pub fn set_inpoint(&mut self, inpoint: u64){
+        unsafe{
+            ges_timeline_element_set_inpoint(self.gst_timeline_element(), inpoint);
+        }
+    }
+
+    pub fn duration(&self) -> u64{
+        self.get_u64("duration")
+    }
+
+    pub fn set_duration(&mut self, duration: u64){
+        unsafe{
+            ges_timeline_element_set_duration(self.gst_timeline_element(), duration);
+        }
+    }
+
+    /// Position in the timeline where the clip ends, None if the
+    /// duration isn't known
+    pub fn end(&self) -> Option<u64>{
+        match self.duration(){
+            GST_CLOCK_TIME_NONE => None,
+            duration => self.start().checked_add(duration),
+        }
+    }
+
+    /// Moves the start of the clip to start keeping its end in place,
+    /// the inpoint and the duration change accordingly
+    pub fn trim(&mut self, start: u64) -> bool{
+        unsafe{
+            ges_timeline_element_trim(self.gst_timeline_element(), start) != 0
+        }
+    }
+
+    /// Cuts the clip at position, in timeline time, the clip ends there
+    /// and the returned one, added to the same layer, plays the rest
+    pub fn split(&mut self, position: u64) -> Option<Clip>{
+        unsafe{
+            Clip::new_ref(ges_clip_split(self.gst_clip(), position))
+        }
+    }
+
+    /// The layer the clip was added to
+    pub fn layer(&self) -> Option<Layer>{
+        unsafe{
+            Layer::new(ges_clip_get_layer(self.gst_clip()))
+        }
+    }
+
+    pub fn move_to_layer(&mut self, layer: &Layer) -> bool{
+        unsafe{
+            ges_clip_move_to_layer(self.gst_clip(), layer.gst_layer()) != 0
+        }
+    }
+
+    fn get_u64(&self, name: &str) -> u64{
+        let cname = CString::new(name).unwrap();
+        let mut value: u64 = 0;
+        unsafe{
+            g_object_get(self.clip.object, cname.as_ptr(), &mut value, ptr::null::<gchar>());
+        }
+        value
+    }
+
+    pub unsafe fn gst_clip(&self) -> *mut GESClip{
+        self.clip.object as *mut GESClip
+    }
+
+    pub unsafe fn gst_timeline_element(&self) -> *mut GESTimelineElement{
+        self.clip.object as *mut GESTimelineElement
+    }
+}
+
+/// Pipeline that plays or renders a Timeline
+pub struct TimelinePipeline{
+    pipeline: Pipeline
+}
+
+impl TimelinePipeline{
+    /// Creates a pipeline playing timeline in preview mode
+    pub fn new(timeline: &Timeline) -> Option<TimelinePipeline>{
+        unsafe{
+            let pipeline = ges_pipeline_new();
+            if pipeline == ptr::null_mut(){
+                return None;
+            }
+            gst_object_ref_sink(pipeline as gpointer);
+            let pipeline = Pipeline::new_from_gst_pipeline(pipeline as *mut GstPipeline)
+                .map(|pipeline| TimelinePipeline{ pipeline: pipeline })?;
+            if ges_pipeline_set_timeline(pipeline.gst_ges_pipeline(), timeline.gst_timeline() as *mut GESTimeline) == 0{
+                return None;
+            }
+            Some(pipeline)
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: PipelineMode) -> bool{
+        unsafe{
+            ges_pipeline_set_mode(self.gst_ges_pipeline(), mode as GESPipelineFlags) != 0
+        }
+    }
+
+    /// Where and how to encode the timeline in Render modes, eg: a webm
+    /// container profile built with EncodingContainerProfile
+    pub fn set_render_settings(&mut self, output_uri: &str, profile: &EncodingProfile) -> bool{
+        let curi = CString::new(output_uri).unwrap();
+        unsafe{
+            ges_pipeline_set_render_settings(self.gst_ges_pipeline(), curi.as_ptr(), profile.gst_encoding_profile()) != 0
+        }
+    }
+
+    pub fn pipeline(&self) -> &Pipeline{
+        &self.pipeline
+    }
+
+    pub unsafe fn gst_ges_pipeline(&self) -> *mut GESPipeline{
+        self.pipeline.gst_pipeline() as *mut GESPipeline
+    }
+}
+
+impl Reference for TimelinePipeline{
+    fn reference(&self) -> TimelinePipeline{
+        TimelinePipeline{ pipeline: self.pipeline.reference() }
+    }
+}
+
+impl From<TimelinePipeline> for Pipeline{
+    fn from(p: TimelinePipeline) -> Pipeline{
+        p.pipeline
+    }
+}
+
+impl Deref for TimelinePipeline{
+    type Target = Pipeline;
+    fn deref(&self) -> &Pipeline{
+        &self.pipeline
+    }
+}
+
+impl DerefMut for TimelinePipeline{
+    fn deref_mut(&mut self) -> &mut Pipeline{
+        &mut self.pipeline
+    }
+}
+
+// takes the references in the list but not the list itself
+unsafe fn list_to_vec<T, F: Fn(gpointer) -> Option<T>>(list: *mut GList, f: F) -> Vec<T>{
+    let mut ret = vec![];
+    let mut node = list;
+    while node != ptr::null_mut(){
+        if let Some(item) = f((*node).data){
+            ret.push(item);
+        }
+        node = (*node).next;
+    }
+    ret
+}
+
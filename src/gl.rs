@@ -0,0 +1,273 @@
+use ffi::*;
+use util::*;
+use object::Object;
+use reference::Reference;
+use context::Context;
+use buffer::Buffer;
+
+use std::ops::{Deref, DerefMut};
+
+/// Context type of the GLDisplay shared by the GL elements
+pub const GL_DISPLAY_CONTEXT_TYPE: &'static str = "gst.gl.GLDisplay";
+
+/// Context type of the GL context of the application, the GL elements
+/// create their contexts shared with it
+pub const GL_APP_CONTEXT_TYPE: &'static str = "gst.gl.app_context";
+
+/// The connection to the windowing system the GL elements create their
+/// contexts with, eg: an X11, wayland or EGL display
+pub struct GLDisplay{
+    display: Object<GstGLDisplay>
+}
+
+impl GLDisplay{
+    pub unsafe fn new_from_gst_gl_display(display: *mut GstGLDisplay) -> Option<GLDisplay>{
+        Object::new(display).map(|obj| GLDisplay{ display: obj })
+    }
+
+    /// Creates a display for the default windowing system of the
+    /// platform
+    pub fn new() -> Option<GLDisplay>{
+        unsafe{
+            let display = gst_gl_display_new();
+            if display != ptr::null_mut(){
+                gst_object_ref_sink(display as gpointer);
+            }
+            GLDisplay::new_from_gst_gl_display(display)
+        }
+    }
+
+    /// The display of a context of type GL_DISPLAY_CONTEXT_TYPE, eg:
+    /// posted by a GL element in a HaveContext message
+    pub fn from_context(context: &Context) -> Option<GLDisplay>{
+        unsafe{
+            let mut display: *mut GstGLDisplay = ptr::null_mut();
+            if gst_context_get_gl_display(context.gst_context() as *mut GstContext, &mut display) != 0{
+                GLDisplay::new_from_gst_gl_display(display)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// A context to set on the GL elements, usually in answer to a
+    /// NeedContext message of type GL_DISPLAY_CONTEXT_TYPE, so they all
+    /// use this display
+    pub fn to_context(&self) -> Context{
+        // libgstgl needs a GStreamer with contexts
+        let mut context = Context::new(GL_DISPLAY_CONTEXT_TYPE, true).unwrap();
+        unsafe{
+            gst_context_set_gl_display(context.gst_context_mut(), self.gst_gl_display() as *mut GstGLDisplay);
+        }
+        context
+    }
+
+    /// GstGLDisplayType of the display, eg: GST_GL_DISPLAY_TYPE_X11
+    pub fn handle_type(&self) -> GstGLDisplayType{
+        unsafe{
+            gst_gl_display_get_handle_type(self.gst_gl_display() as *mut GstGLDisplay)
+        }
+    }
+
+    /// The native handle of the display, eg: the X11 Display*
+    pub fn handle(&self) -> usize{
+        unsafe{
+            gst_gl_display_get_handle(self.gst_gl_display() as *mut GstGLDisplay) as usize
+        }
+    }
+
+    pub unsafe fn gst_gl_display(&self) -> *const GstGLDisplay{
+        self.display.as_ptr()
+    }
+
+    pub unsafe fn gst_gl_display_mut(&mut self) -> *mut GstGLDisplay{
+        self.display.as_mut_ptr()
+    }
+}
+
+/// A GL context, either created by the GL elements or wrapping one of
+/// the application so the textures of the pipeline can be used from it:
+///
+/// ```ignore
+/// let display = gst::gl::GLDisplay::new().unwrap();
+/// let handle = gst::gl::GLContext::current_handle(GST_GL_PLATFORM_GLX);
+/// let app_context = gst::gl::GLContext::new_wrapped(&display, handle, GST_GL_PLATFORM_GLX, GST_GL_API_OPENGL3).unwrap();
+/// bus.set_sync_handler(move |msg: &gst::Message| {
+///     match msg.context_type().as_ref().map(|t| t.as_str()){
+///         Some(gst::gl::GL_DISPLAY_CONTEXT_TYPE) => msg.src_element().unwrap().set_context(&display.to_context()).unwrap(),
+///         Some(gst::gl::GL_APP_CONTEXT_TYPE) => msg.src_element().unwrap().set_context(&app_context.to_context()).unwrap(),
+///         _ => ()
+///     }
+///     gst::BusSyncReply::Pass
+/// });
+/// ```
+pub struct GLContext{
+    context: Object<GstGLContext>
+}
+
+impl GLContext{
+    pub unsafe fn new_from_gst_gl_context(context: *mut GstGLContext) -> Option<GLContext>{
+        Object::new(context).map(|obj| GLContext{ context: obj })
+    }
+
+    /// Wraps a GL context created by the application from its native
+    /// handle, eg: the GLXContext or EGLContext
+    pub fn new_wrapped(display: &GLDisplay, handle: usize, platform: GstGLPlatform, api: GstGLAPI) -> Option<GLContext>{
+        unsafe{
+            let context = gst_gl_context_new_wrapped(display.gst_gl_display() as *mut GstGLDisplay, handle as guintptr, platform, api);
+            if context != ptr::null_mut(){
+                gst_object_ref_sink(context as gpointer);
+            }
+            GLContext::new_from_gst_gl_context(context)
+        }
+    }
+
+    /// Native handle of the GL context current in the calling thread for
+    /// platform, 0 if there's none
+    pub fn current_handle(platform: GstGLPlatform) -> usize{
+        unsafe{
+            gst_gl_context_get_current_gl_context(platform) as usize
+        }
+    }
+
+    /// A context to set on the GL elements in answer to a NeedContext
+    /// message of type GL_APP_CONTEXT_TYPE, so the contexts they create
+    /// share their textures with this one
+    pub fn to_context(&self) -> Context{
+        let mut context = Context::new(GL_APP_CONTEXT_TYPE, true).unwrap();
+        context.structure_mut().unwrap().set("context", self.context.upcast_ref());
+        context
+    }
+
+    /// Makes the context current in the calling thread, or releases it
+    pub fn activate(&mut self, activate: bool) -> bool{
+        unsafe{
+            gst_gl_context_activate(self.gst_gl_context_mut(), activate as gboolean) != 0
+        }
+    }
+
+    pub fn display(&self) -> Option<GLDisplay>{
+        unsafe{
+            GLDisplay::new_from_gst_gl_display(gst_gl_context_get_display(self.gst_gl_context() as *mut GstGLContext))
+        }
+    }
+
+    pub fn gl_api(&self) -> GstGLAPI{
+        unsafe{
+            gst_gl_context_get_gl_api(self.gst_gl_context() as *mut GstGLContext)
+        }
+    }
+
+    pub fn platform(&self) -> GstGLPlatform{
+        unsafe{
+            gst_gl_context_get_gl_platform(self.gst_gl_context() as *mut GstGLContext)
+        }
+    }
+
+    /// The native handle of the context
+    pub fn handle(&self) -> usize{
+        unsafe{
+            gst_gl_context_get_gl_context(self.gst_gl_context() as *mut GstGLContext) as usize
+        }
+    }
+
+    pub unsafe fn gst_gl_context(&self) -> *const GstGLContext{
+        self.context.as_ptr()
+    }
+
+    pub unsafe fn gst_gl_context_mut(&mut self) -> *mut GstGLContext{
+        self.context.as_mut_ptr()
+    }
+}
+
+/// Makes context wait, on the GPU, until the GL commands that produced
+/// buffer finished, before using its textures from context. Returns
+/// false if the buffer has no sync meta, in which case it can be used
+/// directly
+pub fn wait_sync(buffer: &Buffer, context: &GLContext) -> bool{
+    unsafe{
+        let meta = gst_buffer_get_meta(buffer.gst_buffer() as *mut GstBuffer, gst_gl_sync_meta_api_get_type());
+        if meta == ptr::null_mut(){
+            return false;
+        }
+        gst_gl_sync_meta_wait(meta as *mut GstGLSyncMeta, context.gst_gl_context() as *mut GstGLContext);
+        true
+    }
+}
+
+/// Blocks the calling thread until the GL commands that produced buffer
+/// finished, eg: before reading its textures from a context that isn't
+/// shared with the pipeline. Returns false if the buffer has no sync
+/// meta
+pub fn wait_sync_cpu(buffer: &Buffer, context: &GLContext) -> bool{
+    unsafe{
+        let meta = gst_buffer_get_meta(buffer.gst_buffer() as *mut GstBuffer, gst_gl_sync_meta_api_get_type());
+        if meta == ptr::null_mut(){
+            return false;
+        }
+        gst_gl_sync_meta_wait_cpu(meta as *mut GstGLSyncMeta, context.gst_gl_context() as *mut GstGLContext);
+        true
+    }
+}
+
+impl Reference for GLDisplay{
+    fn reference(&self) -> GLDisplay{
+        GLDisplay{ display: self.display.reference() }
+    }
+}
+
+impl AsRef<Object> for GLDisplay{
+    fn as_ref(&self) -> &Object{
+        self.display.upcast_ref()
+    }
+}
+
+impl AsMut<Object> for GLDisplay{
+    fn as_mut(&mut self) -> &mut Object{
+        self.display.upcast_mut()
+    }
+}
+
+impl Deref for GLDisplay{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.display.upcast_ref()
+    }
+}
+
+impl DerefMut for GLDisplay{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.display.upcast_mut()
+    }
+}
+
+impl Reference for GLContext{
+    fn reference(&self) -> GLContext{
+        GLContext{ context: self.context.reference() }
+    }
+}
+
+impl AsRef<Object> for GLContext{
+    fn as_ref(&self) -> &Object{
+        self.context.upcast_ref()
+    }
+}
+
+impl AsMut<Object> for GLContext{
+    fn as_mut(&mut self) -> &mut Object{
+        self.context.upcast_mut()
+    }
+}
+
+impl Deref for GLContext{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.context.upcast_ref()
+    }
+}
+
+impl DerefMut for GLContext{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.context.upcast_mut()
+    }
+}
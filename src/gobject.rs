@@ -0,0 +1,50 @@
+use ffi::*;
+use util::*;
+
+/// Strong reference to a plain GObject. The types of the discoverer or
+/// the player libraries aren't GstObjects so they can't be wrapped in
+/// an Object.
+pub struct GObjectRef{
+    pub object: gpointer
+}
+
+// Not Send or Sync, GObject reference counting is atomic but not every
+// GObject is thread safe, eg: the GES timeline objects. The wrappers of
+// the ones that are implement them themselves
+
+impl GObjectRef{
+    pub unsafe fn new(object: gpointer) -> Option<GObjectRef>{
+        if object != ptr::null_mut(){
+            Some(GObjectRef{ object: object })
+        }else{
+            None
+        }
+    }
+
+    pub unsafe fn new_ref(object: gpointer) -> Option<GObjectRef>{
+        if object != ptr::null_mut(){
+            g_object_ref(object);
+        }
+        GObjectRef::new(object)
+    }
+
+    pub unsafe fn is_a(&self, gtype: GType) -> bool{
+        g_type_check_instance_is_a(self.object as *mut GTypeInstance, gtype) != 0
+    }
+}
+
+impl Clone for GObjectRef{
+    fn clone(&self) -> GObjectRef{
+        unsafe{
+            GObjectRef::new_ref(self.object).unwrap()
+        }
+    }
+}
+
+impl Drop for GObjectRef{
+    fn drop(&mut self){
+        unsafe{
+            g_object_unref(self.object);
+        }
+    }
+}
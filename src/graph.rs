@@ -0,0 +1,190 @@
+use ffi::*;
+use util::*;
+
+use bin::Bin;
+use pad::Pad;
+use caps::Caps;
+use registration::factory_name;
+
+use std::fmt::{self, Display, Formatter};
+
+/// An element of a PipelineGraph
+#[derive(Clone,Debug,PartialEq)]
+pub struct GraphElement{
+    /// Path of the element in the pipeline, eg: "/pipeline0/decodebin0/h264parse0"
+    pub path: String,
+    /// Name of the factory that created the element, eg: "x264enc"
+    pub factory: Option<String>,
+}
+
+/// A link between two pads of a PipelineGraph, the pads are identified
+/// by their path, eg: "/pipeline0/videoconvert0:src"
+#[derive(Clone,PartialEq)]
+pub struct GraphLink{
+    pub src: String,
+    pub sink: String,
+    /// The caps negotiated on the link, None if not negotiated yet
+    pub caps: Option<Caps>,
+}
+
+/// The caps of a link that changed between two graphs
+#[derive(Clone)]
+pub struct CapsChange{
+    pub src: String,
+    pub sink: String,
+    pub before: Option<Caps>,
+    pub after: Option<Caps>,
+}
+
+/// The topology of a pipeline at some point in time: its elements,
+/// the links between their pads and the caps negotiated on them.
+/// Capturing it before and after a renegotiation or a dynamic change
+/// and diffing both shows what changed:
+///
+/// ```ignore
+/// let before = gst::graph::PipelineGraph::capture(&pipeline);
+/// switch_camera(&mut pipeline);
+/// let after = gst::graph::PipelineGraph::capture(&pipeline);
+/// println!("{}", before.diff(&after));
+/// ```
+#[derive(Clone)]
+pub struct PipelineGraph{
+    pub elements: Vec<GraphElement>,
+    pub links: Vec<GraphLink>,
+}
+
+/// Differences between two PipelineGraphs, see PipelineGraph::diff
+#[derive(Clone)]
+pub struct GraphDiff{
+    pub added_elements: Vec<GraphElement>,
+    pub removed_elements: Vec<GraphElement>,
+    pub added_links: Vec<GraphLink>,
+    pub removed_links: Vec<GraphLink>,
+    pub changed_caps: Vec<CapsChange>,
+}
+
+impl PipelineGraph{
+    /// Captures the elements of bin, recursing into child bins, and the
+    /// links of their source pads
+    pub fn capture(bin: &Bin) -> PipelineGraph{
+        let mut elements = vec![];
+        let mut links = vec![];
+        for element in bin.iter_recurse().filter_map(|element| element.ok()){
+            elements.push(GraphElement{
+                path: unsafe{ object_path(element.gst_element() as *mut GstObject) },
+                factory: factory_name(&element),
+            });
+            for pad in element.src_pads(){
+                if let Some(peer) = pad.peer(){
+                    links.push(GraphLink{
+                        src: pad_path(&pad),
+                        sink: pad_path(&peer),
+                        caps: pad.current_caps(),
+                    });
+                }
+            }
+        }
+        PipelineGraph{
+            elements: elements,
+            links: links,
+        }
+    }
+
+    pub fn element(&self, path: &str) -> Option<&GraphElement>{
+        self.elements.iter().find(|element| element.path == path)
+    }
+
+    /// The link from the src pad with path src, if it's linked
+    pub fn link(&self, src: &str) -> Option<&GraphLink>{
+        self.links.iter().find(|link| link.src == src)
+    }
+
+    /// What changed from this graph to later. A link whose pads didn't
+    /// change but its caps did is reported in changed_caps, a link
+    /// that changed any of its pads as a removed and an added link
+    pub fn diff(&self, later: &PipelineGraph) -> GraphDiff{
+        let added_elements = later.elements.iter()
+            .filter(|element| !self.elements.contains(element))
+            .cloned().collect();
+        let removed_elements = self.elements.iter()
+            .filter(|element| !later.elements.contains(element))
+            .cloned().collect();
+        let same_pads = |a: &GraphLink, b: &GraphLink| a.src == b.src && a.sink == b.sink;
+        let added_links = later.links.iter()
+            .filter(|link| !self.links.iter().any(|other| same_pads(link, other)))
+            .cloned().collect();
+        let removed_links = self.links.iter()
+            .filter(|link| !later.links.iter().any(|other| same_pads(link, other)))
+            .cloned().collect();
+        let changed_caps = self.links.iter()
+            .filter_map(|before| later.links.iter()
+                .find(|after| same_pads(before, after) && after.caps != before.caps)
+                .map(|after| CapsChange{
+                    src: before.src.clone(),
+                    sink: before.sink.clone(),
+                    before: before.caps.clone(),
+                    after: after.caps.clone(),
+                }))
+            .collect();
+        GraphDiff{
+            added_elements: added_elements,
+            removed_elements: removed_elements,
+            added_links: added_links,
+            removed_links: removed_links,
+            changed_caps: changed_caps,
+        }
+    }
+}
+
+impl GraphDiff{
+    /// True if both graphs had the same topology and caps
+    pub fn is_empty(&self) -> bool{
+        self.added_elements.is_empty() && self.removed_elements.is_empty() &&
+            self.added_links.is_empty() && self.removed_links.is_empty() &&
+            self.changed_caps.is_empty()
+    }
+}
+
+impl Display for GraphDiff{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        for element in &self.removed_elements{
+            writeln!(fmt, "- element {} ({})", element.path, element.factory.as_ref().map(|f| &f[..]).unwrap_or("-"))?;
+        }
+        for element in &self.added_elements{
+            writeln!(fmt, "+ element {} ({})", element.path, element.factory.as_ref().map(|f| &f[..]).unwrap_or("-"))?;
+        }
+        for link in &self.removed_links{
+            writeln!(fmt, "- link {} -> {} {}", link.src, link.sink, caps_string(&link.caps))?;
+        }
+        for link in &self.added_links{
+            writeln!(fmt, "+ link {} -> {} {}", link.src, link.sink, caps_string(&link.caps))?;
+        }
+        for change in &self.changed_caps{
+            writeln!(fmt, "~ caps {} -> {}", change.src, change.sink)?;
+            writeln!(fmt, "    - {}", caps_string(&change.before))?;
+            writeln!(fmt, "    + {}", caps_string(&change.after))?;
+        }
+        Ok(())
+    }
+}
+
+fn caps_string(caps: &Option<Caps>) -> String{
+    caps.as_ref().map(|caps| caps.to_string()).unwrap_or("no caps".to_string())
+}
+
+fn pad_path(pad: &Pad) -> String{
+    unsafe{
+        object_path(pad.gst_pad() as *mut GstObject)
+    }
+}
+
+unsafe fn object_path(object: *mut GstObject) -> String{
+    let path = gst_object_get_path_string(object);
+    if path == ptr::null_mut(){
+        return String::new();
+    }
+    let ret = from_c_str!(path).to_string();
+    g_free(path as gpointer);
+    ret
+}
+
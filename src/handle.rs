@@ -0,0 +1,199 @@
+use ffi::*;
+use util::*;
+
+/// Weak reference to a GObject, doesn't keep the object alive and
+/// returns null once it's been destroyed. Boxed since GLib keeps track
+/// of the address of the GWeakRef.
+struct WeakRef{
+    weak_ref: Box<GWeakRef>
+}
+
+// GWeakRef is thread safe
+unsafe impl Send for WeakRef {}
+unsafe impl Sync for WeakRef {}
+
+impl WeakRef{
+    unsafe fn new(object: gpointer) -> WeakRef{
+        let mut weak_ref: Box<GWeakRef> = Box::new(mem::zeroed());
+        g_weak_ref_init(&mut *weak_ref, object);
+        WeakRef{ weak_ref: weak_ref }
+    }
+
+    /// Calls f with a strong reference to the object if it's still alive
+    fn with_object<F: FnOnce(gpointer) -> U, U>(&mut self, f: F) -> Option<U>{
+        unsafe{
+            let object = g_weak_ref_get(&mut *self.weak_ref);
+            if object != ptr::null_mut(){
+                let ret = f(object);
+                g_object_unref(object);
+                Some(ret)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+impl Drop for WeakRef{
+    fn drop(&mut self){
+        unsafe{
+            g_weak_ref_clear(&mut *self.weak_ref);
+        }
+    }
+}
+
+/// Returned when connecting to a signal, the handler is disconnected
+/// when the handle is dropped.
+///
+/// Handlers own their data, which is dropped once they are
+/// disconnected, so leaking the handle only keeps the handler
+/// connected. The handle only keeps a weak reference to the object,
+/// dropping it after the object was destroyed is fine.
+#[must_use]
+pub struct SignalHandle{
+    object: WeakRef,
+    id: gulong,
+}
+
+impl SignalHandle{
+    pub unsafe fn new(object: gpointer, id: gulong) -> SignalHandle{
+        SignalHandle{
+            object: WeakRef::new(object),
+            id: id,
+        }
+    }
+
+    pub fn id(&self) -> u64{
+        self.id as u64
+    }
+
+    /// Returns true while the object is alive and the handler connected
+    pub fn is_connected(&mut self) -> bool{
+        let id = self.id;
+        self.object.with_object(|object| unsafe{ g_signal_handler_is_connected(object, id) != 0 })
+            .unwrap_or(false)
+    }
+
+    /// Disconnects the handler, same as dropping the handle
+    pub fn disconnect(self){
+    }
+
+    /// Keeps the handler connected for the lifetime of the object
+    pub fn forget(mut self){
+        self.id = 0;
+    }
+}
+
+impl Drop for SignalHandle{
+    fn drop(&mut self){
+        let id = self.id;
+        if id != 0{
+            self.object.with_object(|object| unsafe{
+                if g_signal_handler_is_connected(object, id) != 0{
+                    g_signal_handler_disconnect(object, id);
+                }
+            });
+        }
+    }
+}
+
+/// Returned when adding a probe to a pad, the probe is removed when the
+/// handle is dropped.
+///
+/// The handle only keeps a weak reference to the pad, dropping it after
+/// the pad was destroyed is fine. Probes that remove themselves by
+/// returning PadProbeReturn::Remove should have their handle forgotten
+/// instead, GStreamer warns about removing an unknown probe.
+#[must_use]
+pub struct ProbeHandle{
+    pad: WeakRef,
+    id: gulong,
+}
+
+impl ProbeHandle{
+    pub unsafe fn new(pad: *mut GstPad, id: gulong) -> ProbeHandle{
+        ProbeHandle{
+            pad: WeakRef::new(pad as gpointer),
+            id: id,
+        }
+    }
+
+    pub fn id(&self) -> u64{
+        self.id as u64
+    }
+
+    /// Removes the probe, same as dropping the handle. If it was a
+    /// blocking probe the dataflow will resume
+    pub fn remove(self){
+    }
+
+    /// Keeps the probe installed until it returns PadProbeReturn::Remove
+    /// or the pad is destroyed
+    pub fn forget(mut self){
+        self.id = 0;
+    }
+}
+
+impl Drop for ProbeHandle{
+    fn drop(&mut self){
+        let id = self.id;
+        if id != 0{
+            self.pad.with_object(|pad| unsafe{
+                gst_pad_remove_probe(pad as *mut GstPad, id);
+            });
+        }
+    }
+}
+
+/// Returned when adding a watch to a bus, the watch is removed when the
+/// handle is dropped. Watches that return false are removed right away,
+/// dropping their handle afterwards is fine.
+#[must_use]
+pub struct WatchHandle{
+    context: *mut GMainContext,
+    id: guint,
+}
+
+// GMainContext is thread safe and sources can be destroyed from any
+// thread
+unsafe impl Send for WatchHandle {}
+unsafe impl Sync for WatchHandle {}
+
+impl WatchHandle{
+    /// Takes a new reference to context, the one the source with id was
+    /// attached to
+    pub unsafe fn new(context: *mut GMainContext, id: guint) -> WatchHandle{
+        WatchHandle{
+            context: g_main_context_ref(context),
+            id: id,
+        }
+    }
+
+    pub fn id(&self) -> u32{
+        self.id as u32
+    }
+
+    /// Removes the watch, same as dropping the handle
+    pub fn remove(self){
+    }
+
+    /// Keeps the watch until it returns false or Bus::remove_watch is
+    /// called
+    pub fn forget(mut self){
+        self.id = 0;
+    }
+}
+
+impl Drop for WatchHandle{
+    fn drop(&mut self){
+        unsafe{
+            if self.id != 0{
+                let source = g_main_context_find_source_by_id(self.context, self.id);
+                if source != ptr::null_mut(){
+                    g_source_destroy(source);
+                }
+            }
+            g_main_context_unref(self.context);
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use ffi::*;
+use util::*;
+
+use buffer::Buffer;
+use element::Element;
+use ::Transfer;
+
+/// Wraps GstHarness from libgstcheck, to unit test an element, or a
+/// gst-launch fragment, by pushing buffers and events into its sink pad
+/// and pulling what comes out of its src pad, without a pipeline:
+///
+/// ```ignore
+/// let mut h = gst::harness::Harness::new("identity").unwrap();
+/// h.set_src_caps_str("video/x-raw,format=RGB,width=2,height=2,framerate=30/1");
+/// h.push(gst::Buffer::from_slice_zero_copy(vec![0; 12]).unwrap());
+/// let buffer = h.pull().unwrap();
+/// assert_eq!(buffer.size(), 12);
+/// ```
+///
+/// The element is set to playing when created. By default it uses a
+/// test clock that only advances when cranked, so elements that wait on
+/// the clock, like sources or sinks that sync, can be stepped
+/// deterministically with crank_single_clock_wait.
+pub struct Harness{
+    harness: *mut GstHarness
+}
+
+unsafe impl Send for Harness{}
+
+impl Harness{
+    /// Harness for a new element from factory with its "sink" and "src"
+    /// pads
+    pub fn new(factory: &str) -> Option<Harness>{
+        let cfactory = CString::new(factory).unwrap();
+        unsafe{
+            Harness::new_from_gst_harness(gst_harness_new(cfactory.as_ptr()))
+        }
+    }
+
+    /// Same as new for elements with pads that aren't named "sink" and
+    /// "src", None for elements without one of them, eg: a source
+    pub fn new_with_padnames(factory: &str, sink_pad: Option<&str>, src_pad: Option<&str>) -> Option<Harness>{
+        let cfactory = CString::new(factory).unwrap();
+        let csink_pad = sink_pad.map(|pad| CString::new(pad).unwrap());
+        let csrc_pad = src_pad.map(|pad| CString::new(pad).unwrap());
+        unsafe{
+            Harness::new_from_gst_harness(gst_harness_new_with_padnames(cfactory.as_ptr(),
+                csink_pad.as_ref().map(|pad| pad.as_ptr()).unwrap_or(ptr::null()),
+                csrc_pad.as_ref().map(|pad| pad.as_ptr()).unwrap_or(ptr::null())))
+        }
+    }
+
+    /// Harness for a bin created from a gst-launch fragment, its
+    /// unlinked pads are the ones used, eg: "videoconvert ! videoscale"
+    pub fn new_parse(launchline: &str) -> Option<Harness>{
+        let claunchline = CString::new(launchline).unwrap();
+        unsafe{
+            Harness::new_from_gst_harness(gst_harness_new_parse(claunchline.as_ptr()))
+        }
+    }
+
+    pub unsafe fn new_from_gst_harness(harness: *mut GstHarness) -> Option<Harness>{
+        if harness != ptr::null_mut(){
+            Some(Harness{ harness: harness })
+        }else{
+            None
+        }
+    }
+
+    /// The element being tested
+    pub fn element(&self) -> Option<Element>{
+        unsafe{
+            let element = (*self.harness).element;
+            if element != ptr::null_mut(){
+                gst_object_ref(element as gpointer);
+            }
+            Element::new_from_gst_element(element)
+        }
+    }
+
+    /// Caps of the buffers pushed into the element
+    pub fn set_src_caps_str(&mut self, caps: &str){
+        let ccaps = CString::new(caps).unwrap();
+        unsafe{
+            gst_harness_set_src_caps_str(self.harness, ccaps.as_ptr());
+        }
+    }
+
+    /// Caps accepted from the element
+    pub fn set_sink_caps_str(&mut self, caps: &str){
+        let ccaps = CString::new(caps).unwrap();
+        unsafe{
+            gst_harness_set_sink_caps_str(self.harness, ccaps.as_ptr());
+        }
+    }
+
+    /// Sets both the caps pushed into and accepted from the element
+    pub fn set_caps_str(&mut self, input: &str, output: &str){
+        let cinput = CString::new(input).unwrap();
+        let coutput = CString::new(output).unwrap();
+        unsafe{
+            gst_harness_set_caps_str(self.harness, cinput.as_ptr(), coutput.as_ptr());
+        }
+    }
+
+    /// Pushes buffer into the element, returns the flow return of the
+    /// push
+    pub fn push(&mut self, buffer: Buffer) -> GstFlowReturn{
+        unsafe{
+            gst_harness_push(self.harness, buffer.transfer())
+        }
+    }
+
+    /// Waits up to 60 seconds for a buffer from the element
+    pub fn pull(&mut self) -> Option<Buffer>{
+        unsafe{
+            Buffer::new(gst_harness_pull(self.harness))
+        }
+    }
+
+    /// The next buffer from the element if there's one, without waiting
+    pub fn try_pull(&mut self) -> Option<Buffer>{
+        unsafe{
+            Buffer::new(gst_harness_try_pull(self.harness))
+        }
+    }
+
+    /// Pushes buffer and waits for the buffer it produces, for elements
+    /// that output a buffer for each input
+    pub fn push_and_pull(&mut self, buffer: Buffer) -> Option<Buffer>{
+        unsafe{
+            Buffer::new(gst_harness_push_and_pull(self.harness, buffer.transfer()))
+        }
+    }
+
+    /// Buffers received from the element so far, pulled or not
+    pub fn buffers_received(&self) -> u32{
+        unsafe{
+            gst_harness_buffers_received(self.harness)
+        }
+    }
+
+    /// Buffers received from the element and not pulled yet
+    pub fn buffers_in_queue(&self) -> u32{
+        unsafe{
+            gst_harness_buffers_in_queue(self.harness)
+        }
+    }
+
+    /// Pushes event downstream into the element, taking ownership of it
+    pub unsafe fn push_event(&mut self, event: *mut GstEvent) -> bool{
+        gst_harness_push_event(self.harness, event) != 0
+    }
+
+    /// Pushes event upstream into the src pad of the element, taking
+    /// ownership of it
+    pub unsafe fn push_upstream_event(&mut self, event: *mut GstEvent) -> bool{
+        gst_harness_push_upstream_event(self.harness, event) != 0
+    }
+
+    /// Waits up to 60 seconds for an event from the element, the caller
+    /// owns the returned event
+    pub unsafe fn pull_event(&mut self) -> *mut GstEvent{
+        gst_harness_pull_event(self.harness)
+    }
+
+    /// The next event from the element if there's one, without waiting.
+    /// The caller owns the returned event
+    pub unsafe fn try_pull_event(&mut self) -> *mut GstEvent{
+        gst_harness_try_pull_event(self.harness)
+    }
+
+    pub fn events_received(&self) -> u32{
+        unsafe{
+            gst_harness_events_received(self.harness)
+        }
+    }
+
+    pub fn events_in_queue(&self) -> u32{
+        unsafe{
+            gst_harness_events_in_queue(self.harness)
+        }
+    }
+
+    /// Uses the system clock instead of the test clock, so the element
+    /// runs in real time
+    pub fn use_systemclock(&mut self){
+        unsafe{
+            gst_harness_use_systemclock(self.harness);
+        }
+    }
+
+    pub fn use_testclock(&mut self){
+        unsafe{
+            gst_harness_use_testclock(self.harness);
+        }
+    }
+
+    /// Advances the test clock to time, in nanoseconds
+    pub fn set_time(&mut self, time: GstClockTime) -> bool{
+        unsafe{
+            gst_harness_set_time(self.harness, time) != 0
+        }
+    }
+
+    /// Waits for the element to wait on the test clock and advances the
+    /// clock to release it. Returns false if the element didn't wait
+    pub fn crank_single_clock_wait(&mut self) -> bool{
+        unsafe{
+            gst_harness_crank_single_clock_wait(self.harness) != 0
+        }
+    }
+
+    /// Same as crank_single_clock_wait for a number of simultaneous waits
+    pub fn crank_multiple_clock_waits(&mut self, waits: u32) -> bool{
+        unsafe{
+            gst_harness_crank_multiple_clock_waits(self.harness, waits) != 0
+        }
+    }
+
+    /// Waits up to timeout seconds for the element to be waiting on the
+    /// test clock waits times
+    pub fn wait_for_clock_id_waits(&mut self, waits: u32, timeout: u32) -> bool{
+        unsafe{
+            gst_harness_wait_for_clock_id_waits(self.harness, waits, timeout) != 0
+        }
+    }
+
+    /// Latency reported to the element by latency queries from upstream
+    pub fn set_upstream_latency(&mut self, latency: GstClockTime){
+        unsafe{
+            gst_harness_set_upstream_latency(self.harness, latency);
+        }
+    }
+
+    /// Feeds the element from a source created from a gst-launch
+    /// fragment, eg: "videotestsrc num-buffers=10", instead of pushing
+    /// buffers by hand. has_clock_wait has to be true for sources that
+    /// wait on the clock, like live sources
+    pub fn add_src_parse(&mut self, launchline: &str, has_clock_wait: bool){
+        let claunchline = CString::new(launchline).unwrap();
+        unsafe{
+            gst_harness_add_src_parse(self.harness, claunchline.as_ptr(), has_clock_wait as gboolean);
+        }
+    }
+
+    /// Makes the source added with add_src_parse produce a buffer and
+    /// pushes it into the element
+    pub fn push_from_src(&mut self) -> GstFlowReturn{
+        unsafe{
+            gst_harness_push_from_src(self.harness)
+        }
+    }
+
+    /// Sends the output of the element to a sink created from a
+    /// gst-launch fragment, eg: "fakesink sync=true"
+    pub fn add_sink_parse(&mut self, launchline: &str){
+        let claunchline = CString::new(launchline).unwrap();
+        unsafe{
+            gst_harness_add_sink_parse(self.harness, claunchline.as_ptr());
+        }
+    }
+
+    /// Pulls a buffer from the element and pushes it to the sink added
+    /// with add_sink_parse
+    pub fn push_to_sink(&mut self) -> GstFlowReturn{
+        unsafe{
+            gst_harness_push_to_sink(self.harness)
+        }
+    }
+
+    pub unsafe fn gst_harness(&self) -> *const GstHarness{
+        self.harness
+    }
+
+    pub unsafe fn gst_harness_mut(&mut self) -> *mut GstHarness{
+        self.harness
+    }
+}
+
+impl Drop for Harness{
+    fn drop(&mut self){
+        unsafe{
+            gst_harness_teardown(self.harness);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+use caps::Caps;
+use sample::Sample;
+use videoframe::VideoFrame;
+use error::{Error, Result};
+
+// gst_video_convert_sample runs a pipeline and waits this long, in
+// nanoseconds, for it to produce the converted image
+const CONVERT_TIMEOUT: GstClockTime = 10_000_000_000;
+
+/// Decodes a still image in any format GStreamer has a decoder for, like
+/// JPEG, PNG or WebP, to an RGBA frame:
+///
+/// ```ignore
+/// let data = std::fs::read("photo.jpg").unwrap();
+/// let frame = gst::image::decode_image(&data).unwrap();
+/// let pixels: &[u8] = frame.plane(0).unwrap().data();
+/// ```
+pub fn decode_image(data: &[u8]) -> Result<VideoFrame>{
+    decode_image_to(data, "RGBA")
+}
+
+/// Decodes a still image to a frame in format, eg: "RGB" or "I420"
+pub fn decode_image_to(data: &[u8], format: &str) -> Result<VideoFrame>{
+    let caps = unsafe{
+        Caps::new(gst_type_find_helper_for_data(ptr::null_mut(), data.as_ptr(), data.len() as gsize, ptr::null_mut()))
+    }.ok_or(Error::new(0, 0, "unknown image format"))?;
+    let buffer = Buffer::from_slice_zero_copy(data.to_vec()).ok_or(Error::new(0, 0, "couldn't create buffer"))?;
+    let to_caps = Caps::from_string(&format!("video/x-raw,format={}", format)).ok_or(Error::new(0, 0, "invalid format"))?;
+    convert(&buffer, &caps, &to_caps)?
+        .video_frame()
+        .ok_or(Error::new(0, 0, "couldn't map decoded image"))
+}
+
+/// Formats a Sample can be encoded to with Sample::encode
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum ImageFormat{
+    Png,
+    Jpeg,
+    WebP,
+}
+
+impl ImageFormat{
+    pub fn media_type(self) -> &'static str{
+        match self{
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::WebP => "image/webp",
+        }
+    }
+}
+
+impl Sample{
+    /// Encodes the video frame of the sample, eg: pulled from an appsink
+    /// or the "sample" property of playbin, to a still image:
+    ///
+    /// ```ignore
+    /// let png = sample.encode(gst::image::ImageFormat::Png).unwrap();
+    /// std::fs::write("thumbnail.png", png).unwrap();
+    /// ```
+    pub fn encode(&self, format: ImageFormat) -> Result<Vec<u8>>{
+        self.encode_to_caps(format.media_type())
+    }
+
+    /// Same as encode but scaling the frame to fit in width x height
+    /// keeping its aspect ratio, eg: for thumbnails. Frames already
+    /// smaller are not scaled up
+    pub fn encode_scaled(&self, format: ImageFormat, width: u32, height: u32) -> Result<Vec<u8>>{
+        let caps = self.caps().ok_or(Error::new(0, 0, "sample has no caps"))?;
+        let info = caps.video_info().ok_or(Error::new(0, 0, "sample is not raw video"))?;
+        // display size, so non square pixels come out with the right shape
+        let (par_n, par_d) = (info.par_n().max(1) as u64, info.par_d().max(1) as u64);
+        let (src_width, src_height) = (info.width() as u64 * par_n / par_d, info.height() as u64);
+        if src_width == 0 || src_height == 0{
+            return Err(Error::new(0, 0, "invalid frame size"));
+        }
+        let (mut dst_width, mut dst_height) = (src_width.min(width as u64), src_height.min(height as u64));
+        if dst_width * src_height > dst_height * src_width{
+            dst_width = (dst_height * src_width / src_height).max(1);
+        }else{
+            dst_height = (dst_width * src_height / src_width).max(1);
+        }
+        self.encode_to_caps(&format!("{},width={},height={},pixel-aspect-ratio=1/1",
+            format.media_type(), dst_width, dst_height))
+    }
+
+    fn encode_to_caps(&self, caps: &str) -> Result<Vec<u8>>{
+        let to_caps = Caps::from_string(caps).ok_or(Error::new(0, 0, "invalid media type"))?;
+        let sample = self.convert_video(&to_caps, CONVERT_TIMEOUT)?;
+        let buffer = sample.buffer().ok_or(Error::new(0, 0, "no encoded image"))?;
+        buffer.map_read(|mapinfo| mapinfo.data::<u8>().to_vec())
+            .map_err(|_| Error::new(0, 0, "couldn't map encoded image"))
+    }
+}
+
+/// Encodes frame to a still image of the given media type, eg:
+/// "image/png", "image/jpeg" or "image/webp"
+///
+/// ```ignore
+/// let png = gst::image::encode_image(&frame, "image/png").unwrap();
+/// std::fs::write("frame.png", png).unwrap();
+/// ```
+pub fn encode_image(frame: &VideoFrame, media_type: &str) -> Result<Vec<u8>>{
+    let caps = frame.info().to_caps().ok_or(Error::new(0, 0, "invalid frame info"))?;
+    let to_caps = Caps::from_string(media_type).ok_or(Error::new(0, 0, "invalid media type"))?;
+    let sample = convert(frame.buffer(), &caps, &to_caps)?;
+    let buffer = sample.buffer().ok_or(Error::new(0, 0, "no encoded image"))?;
+    buffer.map_read(|mapinfo| mapinfo.data::<u8>().to_vec())
+        .map_err(|_| Error::new(0, 0, "couldn't map encoded image"))
+}
+
+fn convert(buffer: &Buffer, caps: &Caps, to_caps: &Caps) -> Result<Sample>{
+    unsafe{
+        let sample = gst_sample_new(buffer.gst_buffer() as *mut GstBuffer, caps.gst_caps() as *mut GstCaps, ptr::null(), ptr::null_mut());
+        let mut err: *mut GError = ptr::null_mut();
+        let converted = gst_video_convert_sample(sample, to_caps.gst_caps(), CONVERT_TIMEOUT, &mut err);
+        gst_mini_object_unref(sample as *mut GstMiniObject);
+        match Sample::new(converted){
+            Some(sample) => Ok(sample),
+            None => if err != ptr::null_mut(){
+                Err(Error::new_from_g_error(err))
+            }else{
+                Err(Error::new(0, 0, "couldn't convert image"))
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use ffi::*;
+use util::*;
+
+use bin::Bin;
+use caps::Caps;
+use element::Element;
+use reference::Reference;
+
+/// Builds a source bin that plays a numbered sequence of images as a
+/// video, eg: to encode the frames rendered by an animation tool:
+///
+/// ```ignore
+/// let src = gst::image_sequence::ImageSequenceSrc::new("/tmp/render/frame%04d.png")
+///     .framerate(24, 1)
+///     .start_index(1)
+///     .build("frames")
+///     .unwrap();
+/// pipeline.add_and_link(src.into(), encoder);
+/// ```
+///
+/// The bin reads the files with multifilesrc, decodes them and has a
+/// single "src" pad with raw video timestamped from the framerate.
+/// PNG, JPEG and WebP sequences are supported.
+#[derive(Clone,Debug)]
+pub struct ImageSequenceSrc{
+    location: String,
+    media_type: Option<String>,
+    framerate: (i32, i32),
+    start_index: i32,
+    stop_index: i32,
+    looping: bool,
+}
+
+impl ImageSequenceSrc{
+    /// location is a printf style pattern with the index of the file,
+    /// eg: "frame%04d.png"
+    pub fn new(location: &str) -> ImageSequenceSrc{
+        ImageSequenceSrc{
+            location: location.to_string(),
+            media_type: None,
+            framerate: (25, 1),
+            start_index: 0,
+            stop_index: -1,
+            looping: false,
+        }
+    }
+
+    /// Frames per second as a fraction, 25/1 by default
+    pub fn framerate(mut self, numerator: i32, denominator: i32) -> ImageSequenceSrc{
+        self.framerate = (numerator, denominator);
+        self
+    }
+
+    /// Index of the first file, 0 by default
+    pub fn start_index(mut self, index: i32) -> ImageSequenceSrc{
+        self.start_index = index;
+        self
+    }
+
+    /// Index of the last file, by default the sequence ends at the
+    /// first missing file
+    pub fn stop_index(mut self, index: i32) -> ImageSequenceSrc{
+        self.stop_index = index;
+        self
+    }
+
+    /// Starts again from start_index after the last file instead of
+    /// sending EOS
+    pub fn looping(mut self, looping: bool) -> ImageSequenceSrc{
+        self.looping = looping;
+        self
+    }
+
+    /// Media type of the files when it can't be guessed from the
+    /// extension, eg: "image/jpeg" for files named "frame%04d.jfif"
+    pub fn media_type(mut self, media_type: &str) -> ImageSequenceSrc{
+        self.media_type = Some(media_type.to_string());
+        self
+    }
+
+    /// Creates the source bin called name. None if the format of the
+    /// files is unknown or its decoder isn't installed
+    pub fn build(&self, name: &str) -> Option<Bin>{
+        let media_type = self.media_type.clone().or_else(|| media_type_from_location(&self.location))?;
+        let decoder = match &media_type[..]{
+            "image/png" => Element::new("pngdec", ""),
+            "image/jpeg" => Element::new("jpegdec", ""),
+            "image/webp" => Element::new("webpdec", ""),
+            _ => None,
+        }?;
+        let caps = Caps::from_string(&format!("{},framerate={}/{}", media_type, self.framerate.0, self.framerate.1))?;
+        let mut src = Element::new("multifilesrc", "")?;
+        src.set("location", &self.location[..]);
+        src.set("index", self.start_index);
+        src.set("start-index", self.start_index);
+        src.set("stop-index", self.stop_index);
+        src.set("loop", self.looping as gboolean);
+        src.set("caps", &caps);
+        let convert = Element::new("videoconvert", "")?;
+
+        let mut bin = Bin::new(name)?;
+        if !bin.add_and_link_many(vec![src, decoder, convert.reference()]){
+            return None;
+        }
+        let mut target = convert.reference().static_pad("src")?;
+        let cname = CString::new("src").unwrap();
+        unsafe{
+            let pad = gst_ghost_pad_new(cname.as_ptr(), target.gst_pad_mut());
+            if pad == ptr::null_mut(){
+                return None;
+            }
+            gst_pad_set_active(pad, 1);
+            if gst_element_add_pad(bin.gst_element_mut(), pad) == 0{
+                return None;
+            }
+        }
+        Some(bin)
+    }
+}
+
+fn media_type_from_location(location: &str) -> Option<String>{
+    let extension = location.rsplit('.').next()?.to_lowercase();
+    let media_type = match &extension[..]{
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        _ => return None,
+    };
+    Some(media_type.to_string())
+}
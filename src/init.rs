@@ -0,0 +1,127 @@
+use ffi::*;
+use error::Result;
+
+use std::env;
+
+/// Initializes GStreamer with options set from the application instead
+/// of environment variables, eg: for packaged applications that ship
+/// their own plugins:
+///
+/// ```ignore
+/// gst::InitBuilder::new()
+///     .system_plugin_path(&app_dir.join("gstreamer-1.0").to_string_lossy())
+///     .registry(&cache_dir.join("registry.bin").to_string_lossy())
+///     .debug("*:2,myapp:5")
+///     .init()?;
+/// ```
+///
+/// Like gst::init it has no effect if GStreamer is already initialized.
+#[derive(Clone,Debug)]
+pub struct InitBuilder{
+    plugin_paths: Vec<String>,
+    system_plugin_paths: Vec<String>,
+    registry: Option<String>,
+    registry_update: bool,
+    registry_fork: bool,
+    debug: Option<String>,
+    debug_no_color: bool,
+}
+
+impl InitBuilder{
+    pub fn new() -> InitBuilder{
+        InitBuilder{
+            plugin_paths: vec![],
+            system_plugin_paths: vec![],
+            registry: None,
+            registry_update: true,
+            registry_fork: true,
+            debug: None,
+            debug_no_color: false,
+        }
+    }
+
+    /// Adds a directory to look for plugins in, on top of the system ones
+    pub fn plugin_path(mut self, path: &str) -> InitBuilder{
+        self.plugin_paths.push(path.to_string());
+        self
+    }
+
+    /// Adds a directory to look for plugins in instead of the system
+    /// ones, so the plugins installed in the system are never loaded
+    pub fn system_plugin_path(mut self, path: &str) -> InitBuilder{
+        self.system_plugin_paths.push(path.to_string());
+        self
+    }
+
+    /// File the registry is cached in, eg: in the application's cache
+    /// directory when the default one isn't writable
+    pub fn registry(mut self, path: &str) -> InitBuilder{
+        self.registry = Some(path.to_string());
+        self
+    }
+
+    /// Whether to rescan the plugins on init if they changed, true by
+    /// default. Disabling it makes init faster when the plugins never
+    /// change but new ones won't be found until the registry is removed
+    pub fn registry_update(mut self, update: bool) -> InitBuilder{
+        self.registry_update = update;
+        self
+    }
+
+    /// Whether to scan the plugins in a separate process so a crashing
+    /// plugin doesn't take down the application, true by default
+    pub fn registry_fork(mut self, fork: bool) -> InitBuilder{
+        self.registry_fork = fork;
+        self
+    }
+
+    /// Debug thresholds with the same syntax as GST_DEBUG, eg:
+    /// "*:2,rtpjitterbuffer:6"
+    pub fn debug(mut self, list: &str) -> InitBuilder{
+        self.debug = Some(list.to_string());
+        self
+    }
+
+    /// Logs without colors, eg: when the output goes to a file
+    pub fn debug_no_color(mut self, no_color: bool) -> InitBuilder{
+        self.debug_no_color = no_color;
+        self
+    }
+
+    /// Initializes GStreamer with these options
+    pub fn init(self) -> Result<()>{
+        // there are no command line options for these, they are only read
+        // from the environment of this process during init
+        if let Some(ref registry) = self.registry{
+            env::set_var("GST_REGISTRY_1_0", registry);
+        }
+        if !self.system_plugin_paths.is_empty(){
+            env::set_var("GST_PLUGIN_SYSTEM_PATH_1_0", env::join_paths(&self.system_plugin_paths).unwrap_or_default());
+        }
+        unsafe{
+            gst_registry_fork_set_enabled(self.registry_fork as gboolean);
+        }
+        let mut args = vec!["gst-rs".to_string()];
+        if !self.plugin_paths.is_empty(){
+            let paths = env::join_paths(&self.plugin_paths).unwrap_or_default();
+            args.push(format!("--gst-plugin-path={}", paths.to_string_lossy()));
+        }
+        if !self.registry_update{
+            args.push("--gst-disable-registry-update".to_string());
+        }
+        if let Some(ref debug) = self.debug{
+            args.push(format!("--gst-debug={}", debug));
+        }
+        if self.debug_no_color{
+            args.push("--gst-debug-no-color".to_string());
+        }
+        let args: Vec<&str> = args.iter().map(|arg| &arg[..]).collect();
+        ::init_with_args(&args).map(|_| ())
+    }
+}
+
+impl Default for InitBuilder{
+    fn default() -> InitBuilder{
+        InitBuilder::new()
+    }
+}
@@ -0,0 +1,65 @@
+use ffi::*;
+use registry::Registry;
+use element_factory::ElementFactory;
+
+use std::env;
+use std::path::PathBuf;
+
+// Defined in gst_ios_init.m, which the GStreamer iOS project templates
+// add to the application and where the static plugins to register are
+// selected
+extern "C" {
+    fn gst_ios_init();
+}
+
+/// Initializes GStreamer on iOS, to be called instead of gst::init.
+///
+/// Apart from gst_init this points the registry, cache and fontconfig
+/// to the application's sandbox, sets up the CA certificates and
+/// registers the statically linked plugins, including applemedia which
+/// provides the VideoToolbox hardware codecs. Returns true if GStreamer
+/// is initialized.
+///
+/// Pipelines have to be paused when the app goes to the background,
+/// see lifecycle::Lifecycle.
+pub fn init() -> bool{
+    unsafe{
+        gst_ios_init();
+        gst_is_initialized() != 0
+    }
+}
+
+/// Hardware encoders and decoders provided by VideoToolbox through the
+/// applemedia plugin. Empty if the plugin wasn't registered in
+/// gst_ios_init.m
+pub fn hardware_codecs() -> Vec<ElementFactory>{
+    Registry::get().features_by_plugin("applemedia").into_iter()
+        .filter_map(|feature| feature.element_factory())
+        .filter(|factory| factory.is_type(GST_ELEMENT_FACTORY_TYPE_DECODER | GST_ELEMENT_FACTORY_TYPE_ENCODER))
+        .collect()
+}
+
+/// Path of a file shipped in the application bundle, eg: a video added
+/// to the Xcode project as a resource. None if it doesn't exist
+pub fn bundle_path(name: &str) -> Option<PathBuf>{
+    // the executable is at the root of the .app bundle on iOS
+    let path = env::current_exe().ok()?.parent()?.join(name);
+    if path.exists(){
+        Some(path)
+    }else{
+        None
+    }
+}
+
+/// URI of a file shipped in the application bundle to play it with
+/// playbin or uridecodebin:
+///
+/// ```ignore
+/// playbin.set_uri(&gst::ios::bundle_uri("intro.mp4").unwrap());
+/// ```
+///
+/// Media from the user's library, eg: an ipod-library:// URI from
+/// MPMediaItem, can be passed to playbin as is, avassetsrc reads it
+pub fn bundle_uri(name: &str) -> Option<String>{
+    ::filename_to_uri(bundle_path(name)?.to_str()?).ok()
+}
@@ -0,0 +1,331 @@
+use ffi::*;
+use util::*;
+
+use sample::Sample;
+use buffer::Buffer;
+use caps::Caps;
+#[cfg(target_os="linux")]
+use allocator::FdAllocator;
+
+use std::convert::TryFrom;
+#[cfg(target_os="linux")]
+use std::fs::File;
+#[cfg(target_os="linux")]
+use std::io::Write;
+#[cfg(target_os="linux")]
+use std::os::raw::{c_char, c_int, c_uint};
+#[cfg(target_os="linux")]
+use std::os::unix::io::{FromRawFd, IntoRawFd};
+
+const MAGIC: &'static [u8; 4] = b"GSTS";
+const VERSION: u32 = 1;
+/// The data of the buffer is in a file descriptor sent separately
+/// instead of after the header
+const PAYLOAD_IN_FD: u32 = 1;
+/// The flags below are the mini object ones, they don't describe the
+/// buffer
+const BUFFER_FLAGS_MASK: u32 = !(GST_MINI_OBJECT_FLAG_LAST - 1);
+
+/// Everything in a serialized sample but the data of the buffer
+struct Header{
+    flags: u32,
+    caps: Option<String>,
+    pts: u64,
+    dts: u64,
+    duration: u64,
+    offset: u64,
+    offset_end: u64,
+    buffer_flags: u32,
+    payload_offset: u64,
+    payload_size: u64,
+}
+
+impl Header{
+    fn new(sample: &Sample, buffer: &Buffer, flags: u32, payload_offset: u64, payload_size: u64) -> Header{
+        unsafe{
+            let gst_buffer = buffer.gst_buffer();
+            Header{
+                flags: flags,
+                caps: sample.caps().map(|caps| caps.to_string()),
+                pts: (*gst_buffer).pts,
+                dts: (*gst_buffer).dts,
+                duration: (*gst_buffer).duration,
+                offset: (*gst_buffer).offset,
+                offset_end: (*gst_buffer).offset_end,
+                buffer_flags: buffer.flags() & BUFFER_FLAGS_MASK,
+                payload_offset: payload_offset,
+                payload_size: payload_size,
+            }
+        }
+    }
+
+    fn write(&self, out: &mut Vec<u8>){
+        let caps = self.caps.as_ref().map(|caps| caps.as_bytes()).unwrap_or(&[]);
+        out.extend_from_slice(MAGIC);
+        write_u32(out, VERSION);
+        write_u32(out, self.flags);
+        write_u32(out, caps.len() as u32);
+        out.extend_from_slice(caps);
+        for &value in &[self.pts, self.dts, self.duration, self.offset, self.offset_end]{
+            write_u64(out, value);
+        }
+        write_u32(out, self.buffer_flags);
+        write_u64(out, self.payload_offset);
+        write_u64(out, self.payload_size);
+    }
+
+    /// Parses the header at the start of data, returns it with its
+    /// length. None if data is too short or not a serialized sample
+    fn read(data: &[u8]) -> Option<(Header, usize)>{
+        let mut reader = Reader{ data: data, pos: 0 };
+        if reader.bytes(MAGIC.len()) != Some(&MAGIC[..]) || reader.u32() != Some(VERSION){
+            return None;
+        }
+        let flags = reader.u32()?;
+        let caps_len = reader.u32()? as usize;
+        let caps = reader.bytes(caps_len)?;
+        let caps = if caps.is_empty(){
+            None
+        }else{
+            Some(str::from_utf8(caps).ok()?.to_string())
+        };
+        let header = Header{
+            flags: flags,
+            caps: caps,
+            pts: reader.u64()?,
+            dts: reader.u64()?,
+            duration: reader.u64()?,
+            offset: reader.u64()?,
+            offset_end: reader.u64()?,
+            buffer_flags: reader.u32()?,
+            payload_offset: reader.u64()?,
+            payload_size: reader.u64()?,
+        };
+        Some((header, reader.pos))
+    }
+
+    /// Sets the timestamps and flags on buffer and wraps it in a sample
+    /// with the caps
+    fn to_sample(&self, mut buffer: Buffer) -> Option<Sample>{
+        let caps = match self.caps{
+            Some(ref caps) => Some(Caps::from_string(caps)?),
+            None => None,
+        };
+        unsafe{
+            let gst_buffer = buffer.gst_buffer_mut();
+            (*gst_buffer).pts = self.pts;
+            (*gst_buffer).dts = self.dts;
+            (*gst_buffer).duration = self.duration;
+            (*gst_buffer).offset = self.offset;
+            (*gst_buffer).offset_end = self.offset_end;
+            (*gst_buffer).mini_object.flags |= self.buffer_flags & BUFFER_FLAGS_MASK;
+            let caps = caps.as_ref().map(|caps| caps.gst_caps() as *mut GstCaps).unwrap_or(ptr::null_mut());
+            Sample::new(gst_sample_new(gst_buffer, caps, ptr::null(), ptr::null_mut()))
+        }
+    }
+}
+
+struct Reader<'a>{
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a>{
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]>{
+        if self.data.len() - self.pos >= len{
+            let bytes = &self.data[self.pos .. self.pos + len];
+            self.pos += len;
+            Some(bytes)
+        }else{
+            None
+        }
+    }
+
+    fn u32(&mut self) -> Option<u32>{
+        self.bytes(4).map(|bytes| bytes.iter().rev().fold(0, |value, &byte| value << 8 | byte as u32))
+    }
+
+    fn u64(&mut self) -> Option<u64>{
+        self.bytes(8).map(|bytes| bytes.iter().rev().fold(0, |value, &byte| value << 8 | byte as u64))
+    }
+}
+
+fn write_u32(out: &mut Vec<u8>, value: u32){
+    out.extend((0..4).map(|i| (value >> (i * 8)) as u8));
+}
+
+fn write_u64(out: &mut Vec<u8>, value: u64){
+    out.extend((0..8).map(|i| (value >> (i * 8)) as u8));
+}
+
+/// Serializes the caps, timestamps, flags and data of the buffer of
+/// sample into a message that can be sent to another process through a
+/// pipe, a socket... and turned back into a sample with deserialize.
+/// The segment and the metas of the buffer are not included.
+///
+/// ```ignore
+/// // producer
+/// stream.write_all(&gst::ipc::serialize(&sample).unwrap()).unwrap();
+///
+/// // consumer
+/// let mut data = vec![];
+/// loop{
+///     data.extend_from_slice(&read_some(&mut stream));
+///     while let Some(len) = gst::ipc::message_len(&data){
+///         let sample = gst::ipc::deserialize(&data[..len]).unwrap();
+///         appsrc.push_buffer(sample.buffer().unwrap());
+///         data.drain(..len);
+///     }
+/// }
+/// ```
+pub fn serialize(sample: &Sample) -> Option<Vec<u8>>{
+    let buffer = sample.buffer()?;
+    let size = buffer.size();
+    let mut out = vec![];
+    Header::new(sample, &buffer, 0, 0, size).write(&mut out);
+    buffer.map_read(|map| out.extend_from_slice(map.data::<u8>())).ok()?;
+    Some(out)
+}
+
+/// Reconstructs a sample from a message created with serialize, the
+/// data is copied into a new buffer. None if data is not a complete
+/// message
+pub fn deserialize(data: &[u8]) -> Option<Sample>{
+    let (header, header_len) = Header::read(data)?;
+    if header.flags & PAYLOAD_IN_FD != 0 || ((data.len() - header_len) as u64) < header.payload_size{
+        return None;
+    }
+    let payload = data[header_len .. header_len + header.payload_size as usize].to_vec();
+    header.to_sample(Buffer::from_slice_zero_copy(payload)?)
+}
+
+/// Length of the message at the start of data, None until the whole
+/// message has been received. Useful to split messages read from a
+/// stream
+pub fn message_len(data: &[u8]) -> Option<usize>{
+    let (header, header_len) = Header::read(data)?;
+    // payload_size comes from the data, a length that doesn't fit in
+    // usize can't be received anyway
+    let len = if header.flags & PAYLOAD_IN_FD != 0{
+        header_len
+    }else{
+        header_len.checked_add(usize::try_from(header.payload_size).ok()?)?
+    };
+    if data.len() >= len{
+        Some(len)
+    }else{
+        None
+    }
+}
+
+#[cfg(target_os="linux")]
+extern "C" {
+    fn memfd_create(name: *const c_char, flags: c_uint) -> c_int;
+}
+
+#[cfg(target_os="linux")]
+const MFD_CLOEXEC: c_uint = 1;
+
+/// Like serialize but the data of the buffer goes in a file descriptor,
+/// to be sent with the header over a unix socket as an SCM_RIGHTS
+/// control message. If the buffer is already backed by a single fd, eg:
+/// from a memfd or dmabuf allocator, the fd is shared without copying
+/// the data, otherwise the data is copied once into a memfd.
+///
+/// The other process reconstructs the sample with deserialize_shared
+/// which maps the fd without copying the data.
+#[cfg(target_os="linux")]
+pub fn serialize_shared(sample: &Sample) -> Option<(Vec<u8>, File)>{
+    let buffer = sample.buffer()?;
+    let memory = if buffer.n_memory() == 1 { buffer.memory(0) } else { None };
+    let (file, offset, size) = match memory{
+        Some(ref memory) if memory.is_fd_memory() => {
+            unsafe{
+                // the fd belongs to the memory, it's duplicated so the
+                // caller can close it independently
+                let fd = File::from_raw_fd(memory.fd()?);
+                let file = fd.try_clone();
+                let _ = fd.into_raw_fd();
+                (file.ok()?, memory.offset() as u64, memory.size() as u64)
+            }
+        }
+        _ => {
+            let cname = CString::new("gst-sample").unwrap();
+            let mut file = unsafe{
+                let fd = memfd_create(cname.as_ptr(), MFD_CLOEXEC);
+                if fd < 0{
+                    return None;
+                }
+                File::from_raw_fd(fd)
+            };
+            buffer.map_read(|map| file.write_all(map.data::<u8>())).ok()?.ok()?;
+            (file, 0, buffer.size())
+        }
+    };
+    let mut header = vec![];
+    Header::new(sample, &buffer, PAYLOAD_IN_FD, offset, size).write(&mut header);
+    Some((header, file))
+}
+
+/// Reconstructs a sample from a header and fd created with
+/// serialize_shared. The fd is owned by the buffer of the sample from
+/// then on and mapped when the buffer is read
+#[cfg(target_os="linux")]
+pub fn deserialize_shared(header: &[u8], fd: File) -> Option<Sample>{
+    let (header, _) = Header::read(header)?;
+    if header.flags & PAYLOAD_IN_FD == 0{
+        return None;
+    }
+    let mut allocator = FdAllocator::new()?;
+    let offset = isize::try_from(header.payload_offset).ok()?;
+    let size = usize::try_from(header.payload_size).ok()?;
+    let total = (offset as usize).checked_add(size)?;
+    let mut memory = allocator.alloc_fd(fd, total, GST_FD_MEMORY_FLAG_NONE)?;
+    memory.resize(offset, size);
+    let mut buffer = Buffer::new_empty();
+    buffer.append_memory(memory);
+    header.to_sample(buffer)
+}
+
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn header(flags: u32, payload_size: u64) -> Vec<u8>{
+        let header = Header{
+            flags: flags,
+            caps: Some("audio/x-raw".to_string()),
+            pts: 0,
+            dts: GST_CLOCK_TIME_NONE,
+            duration: GST_CLOCK_TIME_NONE,
+            offset: 0,
+            offset_end: 0,
+            buffer_flags: 0,
+            payload_offset: 0,
+            payload_size: payload_size,
+        };
+        let mut out = vec![];
+        header.write(&mut out);
+        out
+    }
+
+    #[test]
+    fn message_len_waits_for_payload(){
+        let mut data = header(0, 4);
+        let header_len = data.len();
+        assert_eq!(message_len(&data[..header_len - 1]), None);
+        assert_eq!(message_len(&data), None);
+        data.extend_from_slice(&[1, 2, 3, 4, 5]);
+        assert_eq!(message_len(&data), Some(header_len + 4));
+        // the payload isn't in data
+        assert_eq!(message_len(&header(PAYLOAD_IN_FD, 4)), Some(header_len));
+    }
+
+    #[test]
+    fn message_len_overflow(){
+        let mut data = header(0, u64::max_value());
+        data.extend_from_slice(&[0; 16]);
+        assert_eq!(message_len(&data), None);
+    }
+}
@@ -3,6 +3,7 @@ use std::ptr;
 use std::marker;
 use std::mem;
 
+/// Not Send, an iterator has to be used from the thread that created it
 pub struct Iter<Item>{
     it: *mut GstIterator,
     marker: marker::PhantomData<Item>
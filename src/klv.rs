@@ -0,0 +1,285 @@
+use ffi::*;
+use util::*;
+
+use appsrc::AppSrc;
+use buffer::Buffer;
+use caps::Caps;
+use element::Element;
+use reference::Reference;
+
+use std::ops::{Deref, DerefMut};
+
+/// Universal label of the MISB ST 0601 UAS Datalink Local Set
+pub static UAS_DATALINK_LS: [u8; 16] = [0x06, 0x0e, 0x2b, 0x34, 0x02, 0x0b, 0x01, 0x01,
+                                        0x0e, 0x01, 0x03, 0x01, 0x01, 0x00, 0x00, 0x00];
+
+/// Caps of a KLV metadata stream, parsed when every buffer holds whole
+/// KLV packets, as produced by KlvSrc and expected by mpegtsmux
+pub fn caps(parsed: bool) -> Caps{
+    Caps::from_string(&format!("meta/x-klv, parsed=(boolean){}", parsed)).unwrap()
+}
+
+/// Whether caps are for a KLV metadata stream, eg: the caps of a pad
+/// added by tsdemux
+pub fn is_klv(caps: &Caps) -> bool{
+    caps.structure(0).map(|structure| structure.name() == "meta/x-klv").unwrap_or(false)
+}
+
+/// A KLV packet: a 16 byte universal label key, and a value whose
+/// length is BER encoded when serialized
+#[derive(Clone,Debug,PartialEq)]
+pub struct KlvPacket{
+    pub key: [u8; 16],
+    pub value: Vec<u8>,
+}
+
+impl KlvPacket{
+    pub fn new(key: [u8; 16], value: Vec<u8>) -> KlvPacket{
+        KlvPacket{
+            key: key,
+            value: value,
+        }
+    }
+
+    /// Parses the packets in data, eg: the payload of a buffer. The
+    /// parsing stops at the first truncated packet
+    pub fn parse_all(data: &[u8]) -> Vec<KlvPacket>{
+        let mut packets = vec![];
+        let mut data = data;
+        while let Some((packet, len)) = KlvPacket::parse(data){
+            packets.push(packet);
+            data = &data[len..];
+        }
+        packets
+    }
+
+    /// Parses the packet at the start of data, returning it and the
+    /// number of bytes it takes. None if data doesn't hold a whole packet
+    pub fn parse(data: &[u8]) -> Option<(KlvPacket, usize)>{
+        if data.len() < 17{
+            return None;
+        }
+        let mut key = [0u8; 16];
+        key.copy_from_slice(&data[..16]);
+        let (value_len, header_len) = if data[16] & 0x80 == 0{
+            (data[16] as usize, 17)
+        }else{
+            // long form, the low bits are the number of length bytes
+            let n = (data[16] & 0x7f) as usize;
+            if n == 0 || n > 8 || data.len() < 17 + n{
+                return None;
+            }
+            let len = data[17..17 + n].iter().fold(0u64, |len, byte| (len << 8) | *byte as u64);
+            (len as usize, 17 + n)
+        };
+        if data.len() - header_len < value_len{
+            return None;
+        }
+        let value = data[header_len..header_len + value_len].to_vec();
+        Some((KlvPacket::new(key, value), header_len + value_len))
+    }
+
+    /// The packet serialized with a BER encoded length
+    pub fn to_bytes(&self) -> Vec<u8>{
+        let mut bytes = Vec::with_capacity(16 + 9 + self.value.len());
+        bytes.extend_from_slice(&self.key);
+        let len = self.value.len() as u64;
+        if len < 0x80{
+            bytes.push(len as u8);
+        }else{
+            let n = 8 - (len.leading_zeros() / 8) as usize;
+            bytes.push(0x80 | n as u8);
+            for i in (0..n).rev(){
+                bytes.push((len >> (i * 8)) as u8);
+            }
+        }
+        bytes.extend_from_slice(&self.value);
+        bytes
+    }
+
+    /// Parses the packets in the payload of buffer
+    pub fn from_buffer(buffer: &Buffer) -> Vec<KlvPacket>{
+        buffer.map_read(|mapinfo| KlvPacket::parse_all(mapinfo.data::<u8>())).unwrap_or(vec![])
+    }
+
+    /// A buffer with packets as payload
+    pub fn to_buffer(packets: &[KlvPacket]) -> Option<Buffer>{
+        let data = packets.iter().fold(vec![], |mut data, packet| {
+            data.extend_from_slice(&packet.to_bytes());
+            data
+        });
+        Buffer::from_slice_zero_copy(data)
+    }
+}
+
+/// An appsrc producing a live meta/x-klv stream, to mux metadata
+/// synchronized with a video, eg: telemetry of a drone with mpegtsmux:
+///
+/// ```ignore
+/// let mut klvsrc = gst::klv::KlvSrc::new("klvsrc").unwrap();
+/// pipeline.add(klvsrc.reference());
+/// klvsrc.link(&mut mux);
+/// // from the thread receiving the telemetry
+/// klvsrc.push(&[gst::klv::KlvPacket::new(gst::klv::UAS_DATALINK_LS, local_set)]);
+/// ```
+///
+/// Packets pushed without a timestamp get the running time of the
+/// pipeline, so they're synchronized with a live video source in the
+/// same pipeline. Metadata is sparse, a gap::GapFiller keeps the muxer
+/// from waiting for it between packets
+pub struct KlvSrc{
+    appsrc: AppSrc,
+}
+
+impl KlvSrc{
+    pub fn new(name: &str) -> Option<KlvSrc>{
+        let mut appsrc = AppSrc::new(name)?;
+        appsrc.set_from_str("format", "time");
+        appsrc.set("is-live", true as gboolean);
+        appsrc.set_caps(&caps(true));
+        Some(KlvSrc{ appsrc: appsrc })
+    }
+
+    /// Pushes packets in one buffer timestamped with the current
+    /// running time of the pipeline. Returns GST_FLOW_FLUSHING if the
+    /// pipeline isn't playing yet
+    pub fn push(&mut self, packets: &[KlvPacket]) -> GstFlowReturn{
+        match self.running_time(){
+            Some(running_time) => self.push_at(packets, running_time, None),
+            None => GST_FLOW_FLUSHING,
+        }
+    }
+
+    /// Pushes packets in one buffer with pts, in nanoseconds of running
+    /// time, eg: the timestamp of the video frame they describe
+    pub fn push_at(&mut self, packets: &[KlvPacket], pts: u64, duration: Option<u64>) -> GstFlowReturn{
+        let mut buffer = match KlvPacket::to_buffer(packets){
+            Some(buffer) => buffer,
+            None => return GST_FLOW_ERROR,
+        };
+        buffer.set_pts(Some(pts));
+        buffer.set_dts(Some(pts));
+        buffer.set_duration(duration);
+        self.appsrc.push_buffer(buffer)
+    }
+
+    /// Current running time of the pipeline the source is in, None if
+    /// it has no clock yet
+    pub fn running_time(&self) -> Option<u64>{
+        unsafe{
+            let element = self.appsrc.gst_element() as *mut GstElement;
+            let clock = gst_element_get_clock(element);
+            if clock == ptr::null_mut(){
+                return None;
+            }
+            let now = gst_clock_get_time(clock);
+            gst_object_unref(clock as gpointer);
+            Some(now.saturating_sub(gst_element_get_base_time(element)))
+        }
+    }
+
+    pub fn appsrc(&mut self) -> &mut AppSrc{
+        &mut self.appsrc
+    }
+}
+
+impl AsRef<Element> for KlvSrc{
+    fn as_ref(&self) -> &Element{
+        &self.appsrc
+    }
+}
+
+impl AsMut<Element> for KlvSrc{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.appsrc
+    }
+}
+
+impl From<KlvSrc> for Element{
+    fn from(b: KlvSrc) -> Element{
+        b.appsrc.into()
+    }
+}
+
+impl Deref for KlvSrc{
+    type Target = AppSrc;
+    fn deref(&self) -> &AppSrc{
+        &self.appsrc
+    }
+}
+
+impl DerefMut for KlvSrc{
+    fn deref_mut(&mut self) -> &mut AppSrc{
+        &mut self.appsrc
+    }
+}
+
+impl ::Transfer for KlvSrc{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.appsrc.transfer()
+    }
+}
+
+impl Reference for KlvSrc{
+    fn reference(&self) -> KlvSrc{
+        KlvSrc{ appsrc: self.appsrc.reference() }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn serialized(len: &[u8], value_len: usize) -> Vec<u8>{
+        let mut data = UAS_DATALINK_LS.to_vec();
+        data.extend_from_slice(len);
+        data.extend(vec![0xab; value_len]);
+        data
+    }
+
+    #[test]
+    fn short_form_length(){
+        let data = serialized(&[3], 3);
+        let (packet, len) = KlvPacket::parse(&data).unwrap();
+        assert_eq!(len, 20);
+        assert_eq!(packet, KlvPacket::new(UAS_DATALINK_LS, vec![0xab; 3]));
+        assert_eq!(packet.to_bytes(), data);
+    }
+
+    #[test]
+    fn long_form_length(){
+        let data = serialized(&[0x82, 0x01, 0x00], 256);
+        let (packet, len) = KlvPacket::parse(&data).unwrap();
+        assert_eq!(len, 16 + 3 + 256);
+        assert_eq!(packet.value.len(), 256);
+        assert_eq!(packet.to_bytes(), data);
+
+        // long form isn't required to be minimal
+        let data = serialized(&[0x81, 0x05], 5);
+        assert_eq!(KlvPacket::parse(&data).map(|(packet, len)| (packet.value.len(), len)), Some((5, 23)));
+    }
+
+    #[test]
+    fn truncated(){
+        // missing key, length and value bytes
+        assert_eq!(KlvPacket::parse(&UAS_DATALINK_LS), None);
+        assert_eq!(KlvPacket::parse(&serialized(&[4], 3)), None);
+        assert_eq!(KlvPacket::parse(&serialized(&[0x82, 0x01], 0)), None);
+        assert_eq!(KlvPacket::parse(&serialized(&[0x82, 0x01, 0x00], 255)), None);
+        // invalid number of length bytes
+        assert_eq!(KlvPacket::parse(&serialized(&[0x80], 0)), None);
+        assert_eq!(KlvPacket::parse(&serialized(&[0x89, 0, 0, 0, 0, 0, 0, 0, 0, 0], 0)), None);
+        // a length that doesn't fit in the data
+        assert_eq!(KlvPacket::parse(&serialized(&[0x88, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff], 1)), None);
+    }
+
+    #[test]
+    fn parse_all_stops_at_truncated(){
+        let mut data = serialized(&[1], 1);
+        data.extend(serialized(&[0x81, 2], 2));
+        data.extend(serialized(&[5], 1));
+        let packets = KlvPacket::parse_all(&data);
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[1].value, vec![0xab; 2]);
+    }
+}
@@ -0,0 +1,163 @@
+use ffi::*;
+
+use buffer::Buffer;
+use caps::Caps;
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use handle::ProbeHandle;
+use panic::catch_panic;
+
+use std::ptr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Media type of the reference caps of the timestamps added by stamp
+/// and LatencyMeter, read with Buffer::reference_timestamp_meta
+pub static LATENCY_REFERENCE: &'static str = "timestamp/x-gst-rs-latency";
+
+/// Time from the monotonic clock the stamps are taken from
+fn now() -> GstClockTime{
+    unsafe{ gst_util_get_timestamp() }
+}
+
+fn reference() -> Caps{
+    Caps::new_empty_simple(LATENCY_REFERENCE)
+}
+
+/// Attaches the current time to buffer as a reference timestamp, to be
+/// read later with latency(). Returns false if buffer isn't writable
+pub fn stamp(buffer: &mut Buffer) -> bool{
+    buffer.add_reference_timestamp_meta(&reference(), now(), GST_CLOCK_TIME_NONE).is_some()
+}
+
+/// Time since buffer was stamped, None if it wasn't. The stamps are
+/// only comparable in the same process
+pub fn latency(buffer: &Buffer) -> Option<Duration>{
+    let stamped = buffer.reference_timestamp_meta(Some(&reference()))?.timestamp();
+    let now = now();
+    if now < stamped{
+        return None;
+    }
+    let elapsed = now - stamped;
+    Some(Duration::new(elapsed / 1_000_000_000, (elapsed % 1_000_000_000) as u32))
+}
+
+/// Latency measured by a LatencyMeter
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub struct LatencyStats{
+    /// Latency of the last buffer
+    pub last: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub average: Duration,
+    /// Number of buffers measured
+    pub count: u32,
+}
+
+struct Inner{
+    stats: Option<LatencyStats>,
+    total: Duration,
+    on_measured: Option<Box<dyn FnMut(Duration) + Send>>,
+}
+
+/// Measures the time buffers take from one pad to another, eg: from
+/// the src pad of a camera source to the sink pad of the video sink for
+/// the glass to glass latency of a live pipeline:
+///
+/// ```ignore
+/// let mut src = camera.static_pad("src").unwrap();
+/// let mut sink = videosink.static_pad("sink").unwrap();
+/// let meter = gst::latency::LatencyMeter::install(&mut src, &mut sink).unwrap();
+/// // later
+/// if let Some(stats) = meter.stats(){
+///     println!("latency {:?} (max {:?})", stats.average, stats.max);
+/// }
+/// ```
+///
+/// Buffers are stamped with a reference timestamp meta when they go
+/// through src, which is read back at sink. The meta is kept by
+/// elements that copy the metas of their input, including encoders and
+/// decoders, but not across the network, payloaders drop it.
+pub struct LatencyMeter{
+    inner: Arc<Mutex<Inner>>,
+    _src_probe: ProbeHandle,
+    _sink_probe: ProbeHandle,
+}
+
+impl LatencyMeter{
+    pub fn install(src: &mut Pad, sink: &mut Pad) -> Option<LatencyMeter>{
+        let inner = Arc::new(Mutex::new(Inner{
+            stats: None,
+            total: Duration::from_secs(0),
+            on_measured: None,
+        }));
+        let src_probe = src.add_probe(GST_PAD_PROBE_TYPE_BUFFER, |_pad: &mut Pad, info: &mut PadProbeInfo| {
+            if let Some(buffer) = info.buffer_mut(){
+                stamp(buffer);
+            }
+            PadProbeReturn::Ok
+        })?;
+        let sink_inner = inner.clone();
+        let sink_probe = sink.add_probe(GST_PAD_PROBE_TYPE_BUFFER, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+            let latency = match info.buffer().and_then(latency){
+                Some(latency) => latency,
+                None => return PadProbeReturn::Ok
+            };
+            let mut on_measured = {
+                let mut inner = sink_inner.lock().unwrap_or_else(|e| e.into_inner());
+                let count = inner.stats.map(|stats| stats.count).unwrap_or(0) + 1;
+                inner.total += latency;
+                let average = inner.total / count;
+                inner.stats = Some(match inner.stats{
+                    Some(stats) => LatencyStats{
+                        last: latency,
+                        min: stats.min.min(latency),
+                        max: stats.max.max(latency),
+                        average: average,
+                        count: count,
+                    },
+                    None => LatencyStats{
+                        last: latency,
+                        min: latency,
+                        max: latency,
+                        average: latency,
+                        count: count,
+                    }
+                });
+                inner.on_measured.take()
+            };
+            // without the lock so the handler can use the meter
+            if let Some(ref mut handler) = on_measured{
+                catch_panic(ptr::null_mut(), (), || handler(latency));
+            }
+            let mut inner = sink_inner.lock().unwrap_or_else(|e| e.into_inner());
+            if inner.on_measured.is_none(){
+                inner.on_measured = on_measured;
+            }
+            PadProbeReturn::Ok
+        })?;
+        Some(LatencyMeter{
+            inner: inner,
+            _src_probe: src_probe,
+            _sink_probe: sink_probe,
+        })
+    }
+
+    /// The latency measured so far, None until a stamped buffer arrives
+    /// at the sink pad
+    pub fn stats(&self) -> Option<LatencyStats>{
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).stats
+    }
+
+    /// Starts measuring from zero, eg: after changing the pipeline
+    pub fn reset(&self){
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.stats = None;
+        inner.total = Duration::from_secs(0);
+    }
+
+    /// Calls handler with the latency of every buffer arriving at the
+    /// sink pad, from the streaming thread
+    pub fn connect_measured<F: FnMut(Duration) + Send + 'static>(&self, handler: F){
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).on_measured = Some(Box::new(handler));
+    }
+}
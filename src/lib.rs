@@ -1,37 +1,110 @@
 #![crate_type = "lib"]
 #![crate_name = "gst"]
 
+#[cfg(feature = "futures")]
+extern crate futures;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "raw-window-handle")]
+extern crate raw_window_handle;
+
 pub use self::appsink::AppSink;
 pub use self::appsrc::AppSrc;
 pub use self::sample::Sample;
+pub use self::segment::Segment;
 pub use self::caps::Caps;
-pub use self::buffer::Buffer;
+pub use self::buffer::{Buffer, BufferFlags, FrameKind};
+pub use self::buffer_list::BufferList;
+pub use self::event::Event;
+pub use self::query::Query;
 pub use self::mapinfo::MapInfo;
 pub use self::mapinfo::Map;
-pub use self::element::Element;
+pub use self::element::{Element, URIType};
+pub use self::pad_template::PadTemplate;
 pub use self::bus::Bus;
-pub use self::bin::Bin;
+pub use self::bin::{Bin, PendingChange};
 pub use self::pipeline::Pipeline;
+pub use self::clock::Clock;
 pub use self::playbin::PlayBin;
+pub use self::media_controller::MediaController;
+pub use self::pipeline_controller::{PipelineController, PipelineDispatcher};
 pub use self::message::Message;
+pub use self::context::Context;
 pub use self::mainloop::MainLoop;
+pub use self::mainloop::MainContext;
 pub use self::error::Error;
 pub use self::error::Result;
+pub use self::error::ParseError;
+pub use self::error::{ErrorDomain, ErrorCode, CoreError, LibraryError, ResourceError, StreamError};
 pub use self::videoframe::VideoFrame;
 pub use self::videoframe::VideoPlane;
 pub use self::videoframe::VideoComponent;
 pub use self::videoinfo::VideoInfo;
+pub use self::audioinfo::AudioInfo;
+pub use self::audio_converter::AudioConverter;
 pub use self::buffer_pool::BufferPool;
+pub use self::memory::Memory;
+pub use self::allocator::{Allocator, AllocationParams};
+#[cfg(target_os="linux")]
+pub use self::allocator::{FdAllocator, DmaBufAllocator};
+pub use self::meta::{Meta, VideoMeta, VideoCropMeta, ReferenceTimestampMeta, NetControlMessageMeta, ProtectionMeta, CustomMeta, MetaTransform};
 pub use self::pad::Pad;
-pub use self::structure::Structure;
+pub use self::pad::{PadProbe, PadProbeInfo, PadProbeReturn};
+pub use self::handle::{SignalHandle, ProbeHandle, WatchHandle};
+pub use self::registry::{Registry, Plugin, PluginFeature};
+pub use self::init::InitBuilder;
+pub use self::version::{version, version_string, check_version, ApiAvailability};
+#[cfg(feature = "static-plugins")]
+pub use self::static_plugins::register_static_plugins;
+pub use self::element_factory::ElementFactory;
+pub use self::panic::{set_panic_handler, reset_panic_handler, post_panic_error};
+pub use self::subclass::{BaseTransform, BaseTransformImpl, ElementMetadata, InPlaceBuffer};
+pub use self::v4l2src::V4l2Src;
+pub use self::videooverlay::VideoOverlay;
+#[cfg(feature = "raw-window-handle")]
+pub use self::videooverlay::NativeVideoSink;
+pub use self::navigation::Navigation;
+pub use self::colorbalance::ColorBalance;
+pub use self::stream_volume::{StreamVolume, VolumeFormat};
+pub use self::structure::{Structure, StructureRef, StructureRefMut};
 pub use self::iterator::Iter;
-pub use self::reference::Ref;
+pub use self::reference::{Ref, WeakRef, Downgrade};
 pub use self::miniobject::MiniObject;
 pub use self::object::Object;
+pub use self::value::{Value, ToValue, FromValue};
+pub use self::fraction::Fraction;
+pub use self::datetime::DateTime;
+pub use self::textoverlay::TextOverlay;
+pub use self::subtitles::SubtitleOverlay;
+pub use self::camerabin::CameraBin;
+#[cfg(feature = "webrtc")]
+pub use self::webrtcbin::WebRtcBin;
+#[cfg(feature = "webrtc")]
+pub use self::sdp::{SdpMessage, SdpMedia};
+pub use self::rtp_header_extension::RtpHeaderExtension;
+pub use self::promise::{Promise, PromiseResult};
+pub use self::decodebin::{DecodeBin, AutoplugSelectResult};
+pub use self::encodebin::EncodeBin;
+pub use self::concat::Concat;
+pub use self::rtspsrc::RtspSrc;
+pub use self::shm::{ShmSink, ShmSrc};
+pub use self::encoding_profile::{EncodingProfile, EncodingContainerProfile, EncodingVideoProfile, EncodingAudioProfile};
+pub use self::device_monitor::{Device, DeviceMonitor};
+pub use self::taglist::{TagList, TagScope, MergeMode};
+pub use self::toc::{Toc, TocEntry, TocSetter};
+pub use self::stream::{Stream, StreamCollection};
+pub use self::discoverer::{Discoverer, DiscovererInfo, DiscovererResult};
+pub use self::debug::{DebugCategory, DebugLevel, DebugRecord, LogFunctionId};
+pub use self::debug::{debug_add_log_function, debug_remove_log_function, debug_remove_default_log_function};
+pub use self::debug::{debug_set_default_threshold, debug_default_threshold, debug_set_threshold_from_string};
+pub use self::debug::explain_link_failure;
+#[cfg(feature = "photography")]
+pub use self::photography::Photography;
+#[cfg(feature = "player")]
+pub use self::player::Player;
 
 use ffi::*;
 use std::ptr;
-use std::mem;
 use std::ffi::CString;
 use std::str;
 use std::ffi::CStr;
@@ -39,6 +112,8 @@ use std::os::raw::c_char;
 
 #[macro_use] mod util;
 pub mod ffi;
+/// Logging to and from the GStreamer debug system.
+#[macro_use] mod debug;
 
 /// Easy way for applications to extract samples from a pipeline.
 pub mod appsink;
@@ -46,26 +121,218 @@ pub mod appsink;
 /// Easy way for applications to inject buffers into a pipeline.
 mod appsrc;
 mod sample;
+mod segment;
 mod caps;
 mod buffer;
+mod buffer_list;
+mod event;
+mod query;
 mod element;
 pub mod bus;
 mod bin;
 mod pipeline;
+mod clock;
 mod playbin;
 mod message;
 pub mod mainloop;
 mod error;
 mod videoframe;
 mod videoinfo;
+mod audioinfo;
+mod audio_converter;
+mod videooverlay;
 mod mapinfo;
 mod buffer_pool;
+mod memory;
+mod allocator;
+mod meta;
 mod pad;
+mod pad_template;
 mod structure;
 mod iterator;
 mod reference;
 mod miniobject;
 mod object;
+mod value;
+mod fraction;
+mod datetime;
+mod promise;
+mod taglist;
+mod toc;
+mod encoding_profile;
+mod handle;
+mod context;
+mod panic;
+mod registration;
+mod registry;
+mod init;
+mod version;
+#[cfg(feature = "static-plugins")]
+mod static_plugins;
+mod element_factory;
+#[cfg(feature = "serde")]
+mod serialize;
+/// Implementing GStreamer elements and plugins in rust.
+pub mod subclass;
+pub mod textoverlay;
+/// Rendering subtitles and loading external subtitle files into playbin.
+pub mod subtitles;
+/// Gapless playback of a queue of URIs with playbin.
+pub mod playlist;
+pub mod camerabin;
+pub mod decodebin;
+pub mod encodebin;
+pub mod concat;
+pub mod rtspsrc;
+/// Streaming between processes on the same machine through shared memory.
+pub mod shm;
+/// Animating properties of elements over time.
+pub mod controller;
+/// Saving and restoring the properties of the elements of a bin.
+pub mod snapshot;
+/// Network clocks to synchronize pipelines across devices.
+pub mod net;
+/// Moving buffers between pipelines that can be restarted independently.
+pub mod bridge;
+/// Serializing samples to hand them to other processes.
+pub mod ipc;
+/// Suspending and resuming pipelines with the lifecycle of mobile apps.
+pub mod lifecycle;
+/// Dropping buffers that arrive too late and parsing QoS messages and events.
+pub mod qos;
+/// Buffering stats from messages and queries for network playback.
+pub mod buffering;
+/// Bitrate limits and variant selection for the HLS and DASH demuxers.
+pub mod adaptive;
+/// Init data and protection systems of encrypted (cenc) streams.
+pub mod protection;
+/// Typed videotestsrc, audiotestsrc, imagefreeze, fakesrc and fakesink for synthetic pipelines.
+pub mod testsrc;
+/// Synthetic sources and a measuring sink to benchmark pipelines.
+pub mod bench;
+/// Stopping and dropping the data flowing through part of a pipeline.
+pub mod valve;
+/// Recording to a series of files split by duration or size with splitmuxsink.
+pub mod splitmuxsink;
+/// Streaming threads: tasks and the thread pools they run in.
+pub mod task;
+/// Stream-status notifications and priority and affinity of the streaming threads.
+pub mod threads;
+/// Identifying the media type of in-memory data with the registered typefinders.
+pub mod typefind;
+/// Sequence numbers to match seeks with the messages that complete them.
+pub mod seqnum;
+/// Recent messages of a bus kept for components that attach late.
+pub mod message_history;
+/// Adding and removing tee branches while the pipeline is running.
+pub mod branch;
+/// Building pipelines from structured descriptions, eg: configuration files.
+pub mod description;
+/// Grabbing decoded frames from running pipelines.
+pub mod capture;
+/// Named caps and encoding profiles for common formats.
+pub mod caps_presets;
+/// Building linear pipelines with chained calls instead of launch strings.
+pub mod builder;
+/// Injecting errors, delays and corrupted buffers to test error handling.
+pub mod fault;
+/// Capturing the topology of a pipeline and diffing it between two points in time.
+pub mod graph;
+/// Detecting pads that stop receiving buffers and restarting their pipeline.
+pub mod watchdog;
+/// Gap events and filling sparse streams from appsrc with them.
+pub mod gap;
+/// KLV metadata streams (meta/x-klv) for MISB pipelines.
+pub mod klv;
+/// Frame stepping, keyframe seeks and playback rate changes.
+pub mod trickplay;
+/// Shifting and rescaling the timestamps of the buffers going through a pad.
+pub mod retimestamp;
+/// Measuring the skew between the audio and video of a pipeline.
+pub mod avsync;
+/// Typed levels and spectrum bands posted by the level and spectrum elements.
+pub mod meter;
+/// Listing and selecting the streams of playbin3 and decodebin3.
+pub mod stream;
+/// Collecting the tags and tocs of a pipeline from events and messages.
+pub mod metadata;
+/// Reports of the state of every element and pad to debug stuck pipelines.
+pub mod status;
+/// Buffers, bytes per second and latency of every element of a pipeline.
+pub mod stats;
+/// Measuring the time buffers take between two pads with reference timestamps.
+pub mod latency;
+/// Reading and writing bitstream headers like SPS/PPS or ADTS.
+pub mod bitstream;
+/// Decoding and encoding still images like JPEG, PNG or WebP.
+pub mod image;
+/// Playing numbered image files as a video.
+pub mod image_sequence;
+/// Decoding audio files to raw samples in a chosen format.
+pub mod audio;
+/// A single object to control playback from media player UIs.
+pub mod media_controller;
+/// Controlling a pipeline from other threads through a command queue.
+pub mod pipeline_controller;
+/// Running several pipelines from one bus thread and restarting the failed ones.
+pub mod pipeline_manager;
+/// Tracers implemented in rust, tracer records like latency and CPU usage and leak tracking for tests.
+pub mod tracer;
+#[cfg(feature = "webrtc")]
+pub mod webrtcbin;
+/// Session descriptions exchanged by WebRTC and RTSP signaling.
+#[cfg(feature = "webrtc")]
+pub mod sdp;
+/// Mapping RTP packets and naming the pads of rtpbin.
+pub mod rtp;
+/// Reading and writing RTP header extensions from payloaders.
+pub mod rtp_header_extension;
+/// Key management for the srtpenc and srtpdec elements.
+pub mod srtp;
+/// Sending and parsing the mouse and keyboard input of video sinks.
+pub mod navigation;
+/// Brightness, contrast, hue and saturation of sinks and sources.
+pub mod colorbalance;
+/// Volume and mute of playbin and audio sinks in linear, cubic or dB scales.
+pub mod stream_volume;
+/// Equalizer, pitch and tempo of audio playback.
+pub mod audio_effects;
+/// Helpers to discover and configure video4linux capture devices.
+pub mod v4l2src;
+mod device_monitor;
+/// Device providers implemented in rust, to list devices in a DeviceMonitor.
+pub mod device_provider;
+mod gobject;
+/// Inspecting media files and streams without playing them.
+pub mod discoverer;
+/// Helpers to choose the audio output device across platforms.
+pub mod audio_device;
+#[cfg(feature = "photography")]
+pub mod photography;
+/// High level playback API for applications that just play media.
+#[cfg(feature = "player")]
+pub mod player;
+/// Splitting H.264 and H.265 streams into NAL units and reading their SPS and PPS.
+#[cfg(feature = "codecparsers")]
+pub mod codecparsers;
+/// Reading and injecting the PAT, PMT and SDT tables of MPEG transport streams.
+#[cfg(feature = "mpegts")]
+pub mod mpegts;
+/// Non-linear editing with timelines of layers and clips.
+#[cfg(feature = "ges")]
+pub mod ges;
+/// Sharing GL displays, contexts and textures with the GL elements.
+#[cfg(feature = "gl")]
+pub mod gl;
+/// Testing elements in isolation by pushing and pulling buffers with GstHarness.
+#[cfg(feature = "check")]
+pub mod harness;
+/// Initialization on Android, replaces init() in apps.
+#[cfg(all(target_os="android", feature = "android"))]
+pub mod android;
+/// Initialization on iOS, replaces init() in apps.
+#[cfg(all(target_os="ios", feature = "ios"))]
+pub mod ios;
 
 #[cfg(target_os="linux")]
 mod link_linux;
@@ -73,6 +340,86 @@ mod link_linux;
 mod link_osx;
 #[cfg(target_os="windows")]
 mod link_windows;
+#[cfg(target_os="android")]
+mod link_android;
+#[cfg(target_os="ios")]
+mod link_ios;
+
+// Wrappers are Send and Sync through Object and MiniObject, this breaks
+// the build if a field that isn't thread safe is added to one of them
+#[allow(dead_code)]
+fn assert_thread_safety(){
+    fn send_sync<T: Send + Sync>(){}
+    fn send<T: Send>(){}
+    send_sync::<Element>();
+    send_sync::<Bin>();
+    send_sync::<Pipeline>();
+    send_sync::<WeakRef<Pipeline>>();
+    send_sync::<PlayBin>();
+    send_sync::<Clock>();
+    send_sync::<net::NetTimeProvider>();
+    send_sync::<task::Task>();
+    send_sync::<task::TaskPool>();
+    send_sync::<Bus>();
+    send_sync::<Pad>();
+    send_sync::<AppSrc>();
+    send_sync::<klv::KlvSrc>();
+    send_sync::<BufferPool>();
+    send_sync::<Allocator>();
+    send_sync::<Registry>();
+    send_sync::<ElementFactory>();
+    send_sync::<DeviceMonitor>();
+    send_sync::<device_provider::DeviceProvider>();
+    send_sync::<RtpHeaderExtension>();
+    send_sync::<TextOverlay>();
+    send_sync::<SubtitleOverlay>();
+    #[cfg(feature = "raw-window-handle")]
+    send_sync::<NativeVideoSink>();
+    send_sync::<Buffer>();
+    send_sync::<BufferList>();
+    send_sync::<Event>();
+    send_sync::<Caps>();
+    send_sync::<Sample>();
+    send_sync::<Memory>();
+    send_sync::<Promise>();
+    #[cfg(feature = "webrtc")]
+    send_sync::<SdpMessage>();
+    #[cfg(feature = "webrtc")]
+    send_sync::<SdpMedia>();
+    send_sync::<TagList>();
+    send_sync::<DateTime>();
+    send_sync::<Toc>();
+    send_sync::<Context>();
+    send_sync::<TocEntry>();
+    send_sync::<Stream>();
+    send_sync::<StreamCollection>();
+    send_sync::<Discoverer>();
+    send_sync::<EncodingProfile>();
+    send_sync::<DiscovererInfo>();
+    send_sync::<MainLoop>();
+    send_sync::<Error>();
+    send_sync::<DebugCategory>();
+    #[cfg(feature = "player")]
+    send_sync::<Player>();
+    #[cfg(feature = "mpegts")]
+    send_sync::<mpegts::Section>();
+    send::<Message>();
+    send::<AppSink>();
+    send::<AudioConverter>();
+    send::<appsink::BoundedAppSink>();
+    #[cfg(feature = "check")]
+    send::<harness::Harness>();
+    send::<SignalHandle>();
+    send::<ProbeHandle>();
+    send::<WatchHandle>();
+    send::<MediaController>();
+    send::<PipelineController>();
+    send::<pipeline_manager::PipelineManager>();
+    #[cfg(feature = "futures")]
+    send::<bus::BusStream>();
+    #[cfg(feature = "futures")]
+    send::<appsink::SampleStream>();
+}
 
 pub fn init(){
     unsafe{
@@ -80,19 +427,51 @@ pub fn init(){
     }
 }
 
+/// Initializes GStreamer parsing the GStreamer options in args, eg:
+/// "--gst-plugin-path=plugins" or "--gst-debug=*:3". The first arg is
+/// the program name as in std::env::args. Returns the args that weren't
+/// GStreamer options or an error if initialization failed. The options
+/// are ignored if GStreamer is already initialized
+pub fn init_with_args(args: &[&str]) -> Result<Vec<String>>{
+    let cargs: Vec<CString> = args.iter().map(|arg| CString::new(*arg).unwrap()).collect();
+    let mut argv: Vec<*mut c_char> = cargs.iter().map(|arg| arg.as_ptr() as *mut c_char).collect();
+    argv.push(ptr::null_mut());
+    let mut argc = args.len() as i32;
+    let mut argv_ptr = argv.as_mut_ptr();
+    unsafe{
+        let mut err: *mut GError = ptr::null_mut();
+        if gst_init_check(&mut argc, &mut argv_ptr, &mut err) == 0{
+            if err != ptr::null_mut(){
+                return Err(Error::new_from_g_error(err));
+            }else{
+                return Err(Error::new(0,0,"couldn't initialize GStreamer"));
+            }
+        }
+        // gst_init_check only reorders the pointers, the strings are still cargs
+        Ok((0..argc as isize).map(|i| from_c_str!(*argv_ptr.offset(i)).to_string()).collect())
+    }
+}
+
+/// Frees all the resources GStreamer allocated, eg: so leak checkers
+/// only report leaks of the application. Every object has to be
+/// dropped before and GStreamer can't be initialized again after
+pub unsafe fn deinit(){
+    gst_deinit();
+}
+
 pub fn filename_to_uri(filename: &str) -> Result<String>{
     let cfilename = CString::new(filename).unwrap();
     unsafe{
         if gst_uri_is_valid(cfilename.as_ptr())==1{
             return Ok(filename.to_string())
         }
-        let err: *mut GError = ptr::null_mut();
-        let c_uri = gst_filename_to_uri(cfilename.as_ptr(), mem::transmute(&err));
+        let mut err: *mut GError = ptr::null_mut();
+        let c_uri = gst_filename_to_uri(cfilename.as_ptr(), &mut err);
         if err != ptr::null_mut(){
-            Err(Error::new(0, 0, from_c_str!(mem::transmute((*err).message))))
+            Err(Error::new_from_g_error(err))
         }else{
-            let uri = from_c_str!(mem::transmute(c_uri)).to_string();
-            g_free(mem::transmute(c_uri));
+            let uri = from_c_str!(c_uri).to_string();
+            g_free(c_uri as gpointer);
             Ok(uri)
         }
     }
@@ -102,13 +481,82 @@ pub fn uri_get_protocol(uri: &str) -> Result<String>{
     let curi = CString::new(uri).unwrap();
     unsafe{
         if gst_uri_is_valid(curi.as_ptr())==1{
-            Ok(from_c_str!(mem::transmute(gst_uri_get_protocol(curi.as_ptr()))).to_string())
+            Ok({
+                let protocol = gst_uri_get_protocol(curi.as_ptr());
+                let ret = from_c_str!(protocol).to_string();
+                g_free(protocol as gpointer);
+                ret
+            })
         }else{
             Err(Error::new(0,0,"not a valid URI"))
         }
     }
 }
 
+pub fn uri_is_valid(uri: &str) -> bool{
+    let curi = CString::new(uri).unwrap();
+    unsafe{
+        gst_uri_is_valid(curi.as_ptr()) != 0
+    }
+}
+
+/// The part of uri after the protocol, eg: "/home/user/video.mp4" for
+/// "file:///home/user/video.mp4", still escaped
+pub fn uri_get_location(uri: &str) -> Result<String>{
+    let curi = CString::new(uri).unwrap();
+    unsafe{
+        let location = gst_uri_get_location(curi.as_ptr());
+        if location != ptr::null_mut(){
+            let ret = from_c_str!(location).to_string();
+            g_free(location as gpointer);
+            Ok(ret)
+        }else{
+            Err(Error::new(0,0,"not a valid URI"))
+        }
+    }
+}
+
+/// Whether there's an element that can read from, URIType::Src, or
+/// write to, URIType::Sink, URIs with protocol, eg: "rtsp"
+pub fn uri_protocol_is_supported(uri_type: URIType, protocol: &str) -> bool{
+    let cprotocol = CString::new(protocol).unwrap();
+    unsafe{
+        gst_uri_protocol_is_supported(uri_type as GstURIType, cprotocol.as_ptr()) != 0
+    }
+}
+
+/// The local filename of a file:// URI, the inverse of filename_to_uri
+pub fn uri_to_filename(uri: &str) -> Result<String>{
+    let curi = CString::new(uri).unwrap();
+    unsafe{
+        let mut err: *mut GError = ptr::null_mut();
+        let filename = g_filename_from_uri(curi.as_ptr(), ptr::null_mut(), &mut err);
+        if err != ptr::null_mut(){
+            Err(Error::new_from_g_error(err))
+        }else{
+            let ret = from_c_str!(filename).to_string();
+            g_free(filename as gpointer);
+            Ok(ret)
+        }
+    }
+}
+
+/// Decodes the %XX escapes of uri, eg: "my%20video.mp4" is
+/// "my video.mp4". Fails if an escape is invalid or decodes to a \0
+pub fn uri_decode(uri: &str) -> Result<String>{
+    let curi = CString::new(uri).unwrap();
+    unsafe{
+        let decoded = g_uri_unescape_string(curi.as_ptr(), ptr::null());
+        if decoded != ptr::null_mut(){
+            let ret = String::from_utf8_lossy(CStr::from_ptr(decoded).to_bytes()).into_owned();
+            g_free(decoded as gpointer);
+            Ok(ret)
+        }else{
+            Err(Error::new(0,0,"invalid escape in URI"))
+        }
+    }
+}
+
 pub trait Transfer<PtrType=GstElement>{
     /// Consumes the current object and transfers ownership of the raw pointer
     /// Used to transfer ownership to ffi functions, should be used when an ffi
@@ -0,0 +1,124 @@
+use ffi::*;
+use pipeline::Pipeline;
+
+/// What a pipeline was doing when it was suspended
+#[derive(Copy,Clone,Debug)]
+pub struct SuspendedState{
+    /// State, or pending state, of the pipeline before suspending
+    pub state: GstState,
+    /// Position in nanoseconds, None for live pipelines or if unknown
+    pub position: Option<i64>,
+    pub live: bool,
+}
+
+/// Handles the background / foreground transitions of mobile apps.
+///
+/// Call suspend() when the app goes to the background, eg: from
+/// Activity.onPause on Android or applicationDidEnterBackground on iOS,
+/// and resume() when it comes back. Live pipelines, like a camera
+/// preview or a network stream, go to NULL so the devices and
+/// connections are released and are restarted from scratch. Other
+/// pipelines go to PAUSED, or to NULL if release_resources is set,
+/// and continue from the same position.
+///
+/// ```ignore
+/// let mut lifecycle = gst::lifecycle::Lifecycle::new(pipeline);
+/// // onPause
+/// lifecycle.suspend();
+/// // onResume
+/// lifecycle.resume();
+/// ```
+pub struct Lifecycle{
+    pipeline: Pipeline,
+    release_resources: bool,
+    suspended: Option<SuspendedState>,
+}
+
+impl Lifecycle{
+    pub fn new(pipeline: Pipeline) -> Lifecycle{
+        Lifecycle{
+            pipeline: pipeline,
+            release_resources: false,
+            suspended: None,
+        }
+    }
+
+    /// Take non live pipelines to NULL too when suspending, releasing
+    /// decoders and video surfaces. Needed on platforms that reclaim
+    /// them from background apps. Resuming seeks back to the position
+    /// the pipeline was at.
+    pub fn set_release_resources(&mut self, release: bool){
+        self.release_resources = release;
+    }
+
+    pub fn is_suspended(&self) -> bool{
+        self.suspended.is_some()
+    }
+
+    pub fn suspended_state(&self) -> Option<&SuspendedState>{
+        self.suspended.as_ref()
+    }
+
+    /// Suspends the pipeline remembering its state and position. Does
+    /// nothing if it's already suspended
+    pub fn suspend(&mut self) -> bool{
+        if self.suspended.is_some(){
+            return true;
+        }
+        let (current, pending, _) = self.pipeline.get_state(0);
+        let state = match pending{
+            GST_STATE_VOID_PENDING => current,
+            pending => pending,
+        };
+        let live = self.pipeline.query_latency().map(|(live, _, _)| live).unwrap_or(false);
+        let position = if live { None } else { self.pipeline.position_ns() };
+        let target = if live || self.release_resources { GST_STATE_NULL } else { GST_STATE_PAUSED };
+        let target = if (state as i32) < (target as i32) { state } else { target };
+        if self.pipeline.set_state(target) == GST_STATE_CHANGE_FAILURE{
+            return false;
+        }
+        self.suspended = Some(SuspendedState{
+            state: state,
+            position: position,
+            live: live,
+        });
+        true
+    }
+
+    /// Brings the pipeline back to the state it was in before suspend,
+    /// seeking back to the previous position if it had to be restarted
+    pub fn resume(&mut self) -> bool{
+        let suspended = match self.suspended.take(){
+            Some(suspended) => suspended,
+            None => return true,
+        };
+        let (current, _, _) = self.pipeline.get_state(0);
+        if (current as i32) < (GST_STATE_PAUSED as i32) && suspended.state as i32 >= GST_STATE_PAUSED as i32 && !suspended.live{
+            if self.pipeline.set_state(GST_STATE_PAUSED) == GST_STATE_CHANGE_FAILURE{
+                return false;
+            }
+            // Wait for preroll so the seek can be performed
+            let (_, _, ret) = self.pipeline.get_state(GST_CLOCK_TIME_NONE);
+            if ret == GST_STATE_CHANGE_FAILURE{
+                return false;
+            }
+            if let Some(position) = suspended.position{
+                self.pipeline.seek_simple(GST_FORMAT_TIME, GST_SEEK_FLAG_FLUSH | GST_SEEK_FLAG_KEY_UNIT, position);
+            }
+        }
+        self.pipeline.set_state(suspended.state) != GST_STATE_CHANGE_FAILURE
+    }
+
+    pub fn pipeline(&self) -> &Pipeline{
+        &self.pipeline
+    }
+
+    pub fn pipeline_mut(&mut self) -> &mut Pipeline{
+        &mut self.pipeline
+    }
+
+    /// Returns the pipeline, forgetting any suspended state
+    pub fn into_pipeline(self) -> Pipeline{
+        self.pipeline
+    }
+}
@@ -0,0 +1,4 @@
+// All of GStreamer, the plugins and their dependencies are linked into
+// a single library by the ndk-build scripts of the Android binaries
+#[link(name = "gstreamer_android")]
+extern {}
@@ -0,0 +1,2 @@
+#[link(name = "GStreamer", kind = "framework")]
+extern {}
@@ -1,2 +1,6 @@
 #[link(name = "GStreamer", kind = "framework")]
 extern {}
+
+#[cfg(feature = "check")]
+#[link(name = "gstcheck-1.0")]
+extern {}
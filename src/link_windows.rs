@@ -1,9 +1,48 @@
 #[link(name = "gstvideo-1.0")]
+#[link(name = "gstaudio-1.0")]
 #[link(name = "gstapp-1.0")]
+#[link(name = "gstrtp-1.0")]
+#[link(name = "gstpbutils-1.0")]
+#[link(name = "gstnet-1.0")]
+#[link(name = "gstcontroller-1.0")]
+#[link(name = "gstallocators-1.0")]
 #[link(name = "gstbase-1.0")]
 #[link(name = "gstreamer-1.0")]
+#[link(name = "gio-2.0")]
+#[link(name = "gmodule-2.0")]
 #[link(name = "gobject-2.0")]
 #[link(name = "glib-2.0")]
 extern {}
 
+#[cfg(feature = "webrtc")]
+#[link(name = "gstsdp-1.0")]
+#[link(name = "gstwebrtc-1.0")]
+extern {}
+
+#[cfg(feature = "photography")]
+#[link(name = "gstphotography-1.0")]
+extern {}
+
+#[cfg(feature = "player")]
+#[link(name = "gstplayer-1.0")]
+extern {}
+
+#[cfg(feature = "codecparsers")]
+#[link(name = "gstcodecparsers-1.0")]
+extern {}
 
+#[cfg(feature = "mpegts")]
+#[link(name = "gstmpegts-1.0")]
+extern {}
+
+#[cfg(feature = "ges")]
+#[link(name = "ges-1.0")]
+extern {}
+
+#[cfg(feature = "gl")]
+#[link(name = "gstgl-1.0")]
+extern {}
+
+#[cfg(feature = "check")]
+#[link(name = "gstcheck-1.0")]
+extern {}
@@ -1,12 +1,119 @@
 use ffi::*;
 use std::ptr;
 use std::thread;
-use std::mem;
 use std::cell::RefCell;
-use std::os::raw::c_void;
 
 unsafe impl Sync for MainLoop {}
 unsafe impl Send for MainLoop {}
+unsafe impl Sync for MainContext {}
+unsafe impl Send for MainContext {}
+
+/// Wrapper for GMainContext, the set of sources a main loop dispatches.
+///
+/// Every thread can have its own context so bus watches, timeouts
+/// and idle callbacks are dispatched in that thread instead of the
+/// global default context.
+pub struct MainContext{
+	context: *mut GMainContext
+}
+
+impl Drop for MainContext{
+	fn drop(&mut self){
+		unsafe{
+			g_main_context_unref(self.context);
+		}
+	}
+}
+
+impl Clone for MainContext{
+	fn clone(&self) -> MainContext{
+		unsafe{
+			MainContext{ context: g_main_context_ref(self.context) }
+		}
+	}
+}
+
+/// The global default context, the one used by add_watch and the
+/// MainLoop created with MainLoop::new()
+impl Default for MainContext{
+	fn default() -> MainContext{
+		unsafe{
+			MainContext{ context: g_main_context_ref(g_main_context_default()) }
+		}
+	}
+}
+
+impl MainContext{
+	/// Creates a new context, sources need to be explicitly attached to it
+	pub fn new() -> MainContext{
+		unsafe{
+			MainContext{ context: g_main_context_new() }
+		}
+	}
+
+	/// Returns the context that was made default for the current thread
+	/// with push_thread_default or the global default context
+	pub fn thread_default() -> MainContext{
+		unsafe{
+			MainContext{ context: g_main_context_ref_thread_default() }
+		}
+	}
+
+	pub unsafe fn new_from_gmaincontext(context: *mut GMainContext) -> Option<MainContext>{
+		if context != ptr::null_mut(){
+			Some(MainContext{ context: context })
+		}else{
+			None
+		}
+	}
+
+	/// Runs a single iteration of the context, if may_block is true
+	/// it waits for a source to become ready when there's none.
+	///
+	/// Returns true if any event was dispatched.
+	pub fn iteration(&self, may_block: bool) -> bool{
+		unsafe{
+			g_main_context_iteration(self.context, may_block as gboolean) != 0
+		}
+	}
+
+	/// Checks if there's any source pending to be dispatched
+	pub fn pending(&self) -> bool{
+		unsafe{
+			g_main_context_pending(self.context) != 0
+		}
+	}
+
+	/// Wakes up the context if it's currently blocked in iteration
+	pub fn wakeup(&self){
+		unsafe{
+			g_main_context_wakeup(self.context)
+		}
+	}
+
+	/// Makes this context the default for the calling thread, any
+	/// element or library that uses the thread default context will
+	/// attach its sources to it until pop_thread_default is called
+	pub fn push_thread_default(&self){
+		unsafe{
+			g_main_context_push_thread_default(self.context)
+		}
+	}
+
+	pub fn pop_thread_default(&self){
+		unsafe{
+			g_main_context_pop_thread_default(self.context)
+		}
+	}
+
+	pub unsafe fn gst_maincontext(&self) -> *const GMainContext{
+		self.context
+	}
+
+	pub unsafe fn gst_maincontext_mut(&mut self) -> *mut GMainContext{
+		self.context
+	}
+}
 
 pub struct MainLoop{
 	gst_loop: *mut GMainLoop,
@@ -19,7 +126,7 @@ impl Drop for MainLoop{
 			if self.running {
 				self.quit();
 			}
-			g_main_loop_unref(mem::transmute(self.gst_loop));
+			g_main_loop_unref(self.gst_loop);
 		}
 	}
 }
@@ -27,17 +134,44 @@ impl Drop for MainLoop{
 impl MainLoop{
 	pub fn new() -> MainLoop{
 		unsafe{
-			MainLoop{ gst_loop: g_main_loop_new(mem::transmute(ptr::null::<c_void>()), 0), running: false }
+			MainLoop{ gst_loop: g_main_loop_new(ptr::null_mut(), 0), running: false }
 		}
 	}
 
+	/// Creates a main loop that dispatches the sources of context
+	/// instead of the default one
+	pub fn new_with_context(context: &MainContext) -> MainLoop{
+		unsafe{
+			MainLoop{ gst_loop: g_main_loop_new(context.context, 0), running: false }
+		}
+	}
+
+	/// Returns the context this loop is running
+	pub fn context(&self) -> MainContext{
+		unsafe{
+			MainContext{ context: g_main_context_ref(g_main_loop_get_context(self.gst_loop)) }
+		}
+	}
+
+	pub fn is_running(&self) -> bool{
+		unsafe{
+			g_main_loop_is_running(self.gst_loop) != 0
+		}
+	}
+
+	/// Runs a single iteration of the loop's context, useful to integrate
+	/// with an application's own loop instead of blocking in run()
+	pub fn iteration(&self, may_block: bool) -> bool{
+		self.context().iteration(may_block)
+	}
+
 	pub fn spawn(&mut self){
 		if !self.running {
 			self.running = true;
-			let gst_loop: usize = unsafe{ mem::transmute(self.gst_loop) };
+			let gst_loop = self.gst_loop as usize;
 			thread::spawn( move|| {
 				unsafe{
-					g_main_loop_run ( mem::transmute(gst_loop) );
+					g_main_loop_run ( gst_loop as *mut GMainLoop );
 				}
 				/*loop{
 					g_main_context_iteration(gst_loop,1);
@@ -50,8 +184,7 @@ impl MainLoop{
 		unsafe{
 			if !self.running {
 				self.running = true;
-				let gst_loop = self.gst_loop.clone();
-				g_main_loop_run ( mem::transmute(gst_loop) );
+				g_main_loop_run ( self.gst_loop );
 			}
 		}
 	}
@@ -60,7 +193,7 @@ impl MainLoop{
 		unsafe{
 			if self.running{
 				self.running = false;
-				g_main_loop_quit(mem::transmute(self.gst_loop));
+				g_main_loop_quit(self.gst_loop);
 			}
 		}
 	}
@@ -2,6 +2,8 @@ use ffi::*;
 use std::mem;
 use std::slice::{self,from_raw_parts,from_raw_parts_mut};
 
+/// Mapped memory of a buffer, only valid while the buffer is mapped so
+/// it's not Send
 pub type MapInfo = GstMapInfo;
 
 impl MapInfo{
@@ -12,12 +14,12 @@ impl MapInfo{
 
     #[inline]
     pub fn data<'a,T>(&self) -> &'a [T]{
-        unsafe{ from_raw_parts( mem::transmute(self.data), self.len::<T>() ) }
+        unsafe{ from_raw_parts( self.data as *const T, self.len::<T>() ) }
     }
 
     #[inline]
     pub fn data_mut<'a,T>(&mut self) -> &'a mut [T]{
-        unsafe{ from_raw_parts_mut( mem::transmute(self.data), self.len::<T>() ) }
+        unsafe{ from_raw_parts_mut( self.data as *mut T, self.len::<T>() ) }
     }
 
     #[inline]
@@ -0,0 +1,288 @@
+use ffi::*;
+use util::*;
+use bus::Bus;
+use error::Error;
+use playbin::PlayBin;
+
+/// What happened to the media of a MediaController
+pub enum MediaEvent{
+    StateChanged{ old: GstState, new: GstState },
+    /// The playback position in nanoseconds, sent when it changes
+    PositionChanged(u64),
+    /// The duration in nanoseconds, None if not known yet
+    DurationChanged(Option<u64>),
+    /// Percent of the buffer filled while streaming, playback is paused
+    /// until it reaches 100
+    Buffering(i32),
+    EndOfStream,
+    Error(Error),
+}
+
+/// Everything a media player needs in one object: playback state,
+/// seeking, rate, volume, track selection and events from the pipeline.
+///
+/// Events are dispatched from poll, which GUIs call from their own
+/// timer or frame loop, so no main loop is needed:
+///
+/// ```ignore
+/// let mut player = gst::MediaController::new("file:///tmp/movie.mkv").unwrap();
+/// player.on_event(|event| match *event{
+///     gst::controller::MediaEvent::PositionChanged(pos) => println!("at {}s", pos / 1_000_000_000),
+///     gst::controller::MediaEvent::EndOfStream => println!("done"),
+///     _ => ()
+/// });
+/// player.play();
+/// loop{
+///     player.poll();
+///     // draw the UI...
+/// }
+/// ```
+pub struct MediaController{
+    playbin: PlayBin,
+    bus: Bus,
+    callback: Option<Box<dyn FnMut(&MediaEvent) + Send>>,
+    target_state: GstState,
+    buffering: bool,
+    rate: f64,
+    last_position: Option<u64>,
+}
+
+impl MediaController{
+    pub fn new(uri: &str) -> Option<MediaController>{
+        let mut playbin = PlayBin::new("player")?;
+        playbin.set_uri(uri);
+        let bus = playbin.bus()?;
+        Some(MediaController{
+            playbin: playbin,
+            bus: bus,
+            callback: None,
+            target_state: GST_STATE_NULL,
+            buffering: false,
+            rate: 1.0,
+            last_position: None,
+        })
+    }
+
+    /// Sets the function poll calls with each event, replacing any
+    /// previous one
+    pub fn on_event<F: FnMut(&MediaEvent) + Send + 'static>(&mut self, f: F){
+        self.callback = Some(Box::new(f));
+    }
+
+    /// Stops and changes the media to play
+    pub fn set_uri(&mut self, uri: &str){
+        self.stop();
+        self.playbin.set_uri(uri);
+    }
+
+    pub fn play(&mut self) -> bool{
+        self.set_target_state(GST_STATE_PLAYING)
+    }
+
+    pub fn pause(&mut self) -> bool{
+        self.set_target_state(GST_STATE_PAUSED)
+    }
+
+    /// Stops playback and releases the media, play starts again from the
+    /// beginning
+    pub fn stop(&mut self) -> bool{
+        self.last_position = None;
+        self.rate = 1.0;
+        self.set_target_state(GST_STATE_NULL)
+    }
+
+    fn set_target_state(&mut self, state: GstState) -> bool{
+        self.target_state = state;
+        // while buffering the pipeline stays paused until the buffer is full
+        let state = if self.buffering && state == GST_STATE_PLAYING { GST_STATE_PAUSED } else { state };
+        self.playbin.set_state(state) != GST_STATE_CHANGE_FAILURE
+    }
+
+    /// The state the pipeline is in, without waiting for a state change
+    /// in progress
+    pub fn state(&self) -> GstState{
+        self.playbin.get_state(0).0
+    }
+
+    /// Whether play was called last, even if the pipeline is still
+    /// prerolling or buffering
+    pub fn is_playing(&self) -> bool{
+        self.target_state == GST_STATE_PLAYING
+    }
+
+    /// Position in nanoseconds
+    pub fn position(&self) -> Option<u64>{
+        self.playbin.position_ns().map(|pos| pos as u64)
+    }
+
+    /// Duration in nanoseconds, None for live streams or until the media
+    /// is prerolled
+    pub fn duration(&self) -> Option<u64>{
+        self.playbin.duration_ns().map(|duration| duration as u64)
+    }
+
+    /// Seeks to position in nanoseconds keeping the current rate
+    pub fn seek(&mut self, position: u64) -> bool{
+        let (start, stop) = if self.rate > 0.0 { (position as i64, -1) } else { (0, position as i64) };
+        self.playbin.seek(self.rate, GST_FORMAT_TIME, GST_SEEK_FLAG_FLUSH | GST_SEEK_FLAG_ACCURATE,
+            GST_SEEK_TYPE_SET, start, GST_SEEK_TYPE_SET, stop)
+    }
+
+    /// Changes the playback rate from the current position, 2.0 to play
+    /// twice as fast, negative to play backwards
+    pub fn set_rate(&mut self, rate: f64) -> bool{
+        if rate == 0.0{
+            return false;
+        }
+        let position = match self.position(){
+            Some(position) => position,
+            None => return false,
+        };
+        self.rate = rate;
+        self.seek(position)
+    }
+
+    pub fn rate(&self) -> f64{
+        self.rate
+    }
+
+    /// Volume, 1.0 being 100%
+    pub fn set_volume(&mut self, volume: f64){
+        self.playbin.set_volume(volume);
+    }
+
+    pub fn volume(&self) -> f64{
+        self.playbin.get::<f64>("volume")
+    }
+
+    pub fn set_muted(&mut self, muted: bool){
+        self.playbin.set("mute", muted);
+    }
+
+    pub fn is_muted(&self) -> bool{
+        self.playbin.get::<gboolean>("mute") != 0
+    }
+
+    pub fn n_audio_tracks(&self) -> i32{
+        self.playbin.get::<i32>("n-audio")
+    }
+
+    pub fn audio_track(&self) -> i32{
+        self.playbin.get::<i32>("current-audio")
+    }
+
+    pub fn set_audio_track(&mut self, track: i32){
+        self.playbin.set_current_audio(track);
+    }
+
+    pub fn n_video_tracks(&self) -> i32{
+        self.playbin.get::<i32>("n-video")
+    }
+
+    pub fn video_track(&self) -> i32{
+        self.playbin.get::<i32>("current-video")
+    }
+
+    pub fn set_video_track(&mut self, track: i32){
+        self.playbin.set("current-video", track);
+    }
+
+    pub fn n_subtitle_tracks(&self) -> i32{
+        self.playbin.get::<i32>("n-text")
+    }
+
+    /// The subtitle track shown, -1 for none
+    pub fn subtitle_track(&self) -> i32{
+        self.playbin.get::<i32>("current-text")
+    }
+
+    pub fn set_subtitle_track(&mut self, track: i32){
+        self.playbin.set_current_text(track);
+    }
+
+    /// Handles the messages posted by the pipeline since the last call
+    /// and calls the event function with the resulting events, followed
+    /// by a PositionChanged if the position moved
+    pub fn poll(&mut self){
+        unsafe{
+            loop{
+                let msg = gst_bus_pop(self.bus.gst_bus_mut());
+                if msg == ptr::null_mut(){
+                    break;
+                }
+                if let Some(event) = self.handle_message(msg){
+                    self.dispatch(&event);
+                }
+                gst_mini_object_unref(msg as *mut GstMiniObject);
+            }
+        }
+        let position = self.position();
+        if position.is_some() && position != self.last_position{
+            self.last_position = position;
+            self.dispatch(&MediaEvent::PositionChanged(position.unwrap()));
+        }
+    }
+
+    unsafe fn handle_message(&mut self, msg: *mut GstMessage) -> Option<MediaEvent>{
+        match (*msg)._type{
+            GST_MESSAGE_EOS => Some(MediaEvent::EndOfStream),
+            GST_MESSAGE_ERROR => {
+                let mut error: *mut GError = ptr::null_mut();
+                let mut debug: *mut gchar = ptr::null_mut();
+                gst_message_parse_error(msg, &mut error, &mut debug);
+                let error = Error::new_from_message(error, (*msg).src, debug);
+                g_free(debug as gpointer);
+                Some(MediaEvent::Error(error))
+            }
+            GST_MESSAGE_STATE_CHANGED if (*msg).src == self.playbin.gst_element() as *mut GstObject => {
+                let mut old = GST_STATE_NULL;
+                let mut new = GST_STATE_NULL;
+                let mut pending = GST_STATE_NULL;
+                gst_message_parse_state_changed(msg, &mut old, &mut new, &mut pending);
+                Some(MediaEvent::StateChanged{ old: old, new: new })
+            }
+            GST_MESSAGE_DURATION_CHANGED => Some(MediaEvent::DurationChanged(self.duration())),
+            GST_MESSAGE_ASYNC_DONE => Some(MediaEvent::DurationChanged(self.duration())),
+            GST_MESSAGE_BUFFERING => {
+                let mut percent = 0;
+                gst_message_parse_buffering(msg, &mut percent);
+                // live streams can't be paused to buffer
+                if self.playbin.get_state(0).2 != GST_STATE_CHANGE_NO_PREROLL{
+                    if percent < 100 && !self.buffering{
+                        self.buffering = true;
+                        if self.target_state == GST_STATE_PLAYING{
+                            self.playbin.set_state(GST_STATE_PAUSED);
+                        }
+                    }else if percent == 100 && self.buffering{
+                        self.buffering = false;
+                        if self.target_state == GST_STATE_PLAYING{
+                            self.playbin.set_state(GST_STATE_PLAYING);
+                        }
+                    }
+                }
+                Some(MediaEvent::Buffering(percent))
+            }
+            _ => None
+        }
+    }
+
+    fn dispatch(&mut self, event: &MediaEvent){
+        if let Some(ref mut callback) = self.callback{
+            callback(event);
+        }
+    }
+
+    pub fn playbin(&self) -> &PlayBin{
+        &self.playbin
+    }
+
+    pub fn playbin_mut(&mut self) -> &mut PlayBin{
+        &mut self.playbin
+    }
+}
+
+impl Drop for MediaController{
+    fn drop(&mut self){
+        self.playbin.set_null_state();
+    }
+}
@@ -0,0 +1,257 @@
+use ffi::*;
+use util::*;
+use reference::Reference;
+use miniobject::MiniObject;
+use allocator::{self, Allocator};
+use version::ApiAvailability;
+use panic::catch_panic;
+
+use std::ops::{Deref, DerefMut};
+#[cfg(target_os="linux")]
+use std::os::unix::io::RawFd;
+
+const IS_TYPE: ApiAvailability = ApiAvailability::new("Memory::is_type", 1, 2, 0);
+
+/// A chunk of memory, a buffer is made of one or more of them.
+///
+/// Memories can wrap data owned by rust without copying it, the data is
+/// released once the last buffer using the memory is freed:
+///
+/// ```ignore
+/// let mut buffer = gst::Buffer::new_empty();
+/// buffer.append_memory(gst::Memory::from_vec(header).unwrap());
+/// buffer.append_memory(gst::Memory::from_vec(payload).unwrap());
+/// appsrc.push_buffer(buffer);
+/// ```
+pub struct Memory{
+    memory: MiniObject<GstMemory>
+}
+
+impl Memory{
+    pub unsafe fn new(memory: *mut GstMemory) -> Option<Memory>{
+        MiniObject::new(memory)
+            .map(|miniobject| Memory{ memory: miniobject })
+    }
+
+    /// Wraps the contents of data without copying them, the vector is
+    /// dropped once the memory isn't used anymore
+    pub fn from_vec(data: Vec<u8>) -> Option<Memory>{
+        let mut data = Box::new(data);
+        let ptr = data.as_mut_ptr();
+        let len = data.len();
+        unsafe{
+            Memory::new(gst_memory_new_wrapped(0, ptr as gpointer, len as gsize, 0, len as gsize,
+                Box::into_raw(data) as gpointer, Some(drop_boxed::<Vec<u8>>)))
+        }
+    }
+
+    /// Wraps len bytes starting at data without copying them. drop_fn is
+    /// called once the memory isn't used anymore so the data can be
+    /// released.
+    ///
+    /// Unsafe because data has to stay valid until drop_fn is called,
+    /// possibly from a different thread
+    pub unsafe fn wrap_with_destructor<F: FnOnce() + Send + 'static>(data: *mut u8, len: usize, drop_fn: F) -> Option<Memory>{
+        let drop_fn = Box::into_raw(Box::new(drop_fn));
+        Memory::new(gst_memory_new_wrapped(0, data as gpointer, len as gsize, 0, len as gsize,
+            drop_fn as gpointer, Some(call_boxed::<F>)))
+    }
+
+    /// Size of the memory in bytes
+    pub fn size(&self) -> usize{
+        unsafe{ (*self.gst_memory()).size as usize }
+    }
+
+    /// Offset of the data from the start of the allocated memory
+    pub fn offset(&self) -> usize{
+        unsafe{ (*self.gst_memory()).offset as usize }
+    }
+
+    /// Total size of the allocated memory
+    pub fn maxsize(&self) -> usize{
+        unsafe{ (*self.gst_memory()).maxsize as usize }
+    }
+
+    /// Changes the visible part of the memory, offset is relative to the
+    /// current offset and offset + size can't go past maxsize
+    pub fn resize(&mut self, offset: isize, size: usize){
+        unsafe{
+            gst_memory_resize(self.gst_memory_mut(), offset as gssize, size as gsize)
+        }
+    }
+
+    /// Returns a new memory sharing the same data starting at offset,
+    /// with size bytes or up to the end if size is None
+    pub fn share(&self, offset: usize, size: Option<usize>) -> Option<Memory>{
+        unsafe{
+            let size = size.map(|size| size as gssize).unwrap_or(-1);
+            Memory::new(gst_memory_share(self.gst_memory() as *mut GstMemory, offset as gssize, size))
+        }
+    }
+
+    /// Copies the data to a new memory, reference() shares it instead
+    pub fn copy(&self) -> Option<Memory>{
+        unsafe{
+            Memory::new(gst_memory_copy(self.gst_memory() as *mut GstMemory, 0, -1))
+        }
+    }
+
+    /// Returns true if the memory was allocated by an allocator of type
+    /// mem_type, eg: "SystemMemory"
+    pub fn is_type(&self, mem_type: &str) -> bool{
+        let ctype = CString::new(mem_type).unwrap();
+        unsafe{
+            match IS_TYPE.function::<unsafe extern "C" fn(*mut GstMemory, *const gchar) -> gboolean>("gst_memory_is_type"){
+                Ok(is_type) => is_type(self.gst_memory() as *mut GstMemory, ctype.as_ptr()) != 0,
+                Err(_) => {
+                    // what gst_memory_is_type does
+                    let allocator = (*self.gst_memory()).allocator;
+                    allocator != ptr::null_mut() && (*allocator).mem_type != ptr::null()
+                        && CStr::from_ptr((*allocator).mem_type) == ctype.as_c_str()
+                }
+            }
+        }
+    }
+
+    /// Whether the memory is backed by a file descriptor, eg: a memfd
+    /// or a dmabuf. Always false with GStreamer older than 1.6
+    pub fn is_fd_memory(&self) -> bool{
+        unsafe{
+            match allocator::FD_MEMORY.function::<unsafe extern "C" fn(*mut GstMemory) -> gboolean>("gst_is_fd_memory"){
+                Ok(is_fd_memory) => is_fd_memory(self.gst_memory() as *mut GstMemory) != 0,
+                Err(_) => false,
+            }
+        }
+    }
+
+    /// Whether the memory is backed by a DMA-BUF, that can be passed to
+    /// a device or GPU API without copying. Always false with GStreamer
+    /// older than 1.2
+    pub fn is_dmabuf(&self) -> bool{
+        unsafe{
+            match allocator::DMABUF_MEMORY.function::<unsafe extern "C" fn(*mut GstMemory) -> gboolean>("gst_is_dmabuf_memory"){
+                Ok(is_dmabuf_memory) => is_dmabuf_memory(self.gst_memory() as *mut GstMemory) != 0,
+                Err(_) => false,
+            }
+        }
+    }
+
+    /// The file descriptor backing the memory, None if it's not fd
+    /// memory. The fd still belongs to the memory, dup it to keep it
+    /// after the memory is freed. The data starts at offset() in it
+    #[cfg(target_os="linux")]
+    pub fn fd(&self) -> Option<RawFd>{
+        if self.is_fd_memory(){
+            unsafe{
+                let get_fd: unsafe extern "C" fn(*mut GstMemory) -> gint = allocator::FD_MEMORY.function("gst_fd_memory_get_fd").ok()?;
+                Some(get_fd(self.gst_memory() as *mut GstMemory))
+            }
+        }else{
+            None
+        }
+    }
+
+    /// The allocator this memory was allocated with, None for wrapped
+    /// memories
+    pub fn allocator(&self) -> Option<Allocator>{
+        unsafe{
+            let allocator = (*self.gst_memory()).allocator;
+            if allocator != ptr::null_mut(){
+                gst_object_ref(allocator as gpointer);
+            }
+            Allocator::new(allocator)
+        }
+    }
+
+    pub fn map_read<'a,F:FnMut(&::MapInfo)->U,U>(&'a self, mut f: F ) -> Result<U,()>{
+        unsafe{
+	        let mut mapinfo = mem::zeroed();
+	        if gst_memory_map(self.gst_memory() as *mut GstMemory, &mut mapinfo, GST_MAP_READ) != 0{
+	        	let ret = f(&mapinfo);
+        		gst_memory_unmap(self.gst_memory() as *mut GstMemory, &mut mapinfo);
+        		Ok(ret)
+        	}else{
+        	    Err(())
+        	}
+	    }
+    }
+
+    /// Copies the memory first if it's shared
+    pub fn map_write<'a,F:FnMut(&mut ::MapInfo)->U,U>(&'a mut self, mut f: F ) -> Result<U,()>{
+        self.memory.make_mut();
+        unsafe{
+	        let mut mapinfo = mem::zeroed();
+	        if gst_memory_map(self.gst_memory_mut(), &mut mapinfo, GST_MAP_WRITE) != 0{
+	        	let ret = f(&mut mapinfo);
+        		gst_memory_unmap(self.gst_memory_mut(), &mut mapinfo);
+        		Ok(ret)
+        	}else{
+        	    Err(())
+        	}
+	    }
+    }
+
+    pub unsafe fn gst_memory(&self) -> *const GstMemory{
+        self.memory.as_ptr()
+    }
+
+    pub unsafe fn gst_memory_mut(&mut self) -> *mut GstMemory{
+        self.memory.as_mut_ptr()
+    }
+}
+
+extern "C" fn drop_boxed<T>(data: gpointer){
+    unsafe{
+        drop(Box::from_raw(data as *mut T));
+    }
+}
+
+extern "C" fn call_boxed<F: FnOnce()>(data: gpointer){
+    unsafe{
+        let drop_fn = Box::from_raw(data as *mut F);
+        catch_panic(ptr::null_mut(), (), drop_fn);
+    }
+}
+
+impl ::Transfer<GstMemory> for Memory{
+    unsafe fn transfer(self) -> *mut GstMemory{
+        self.memory.transfer()
+    }
+}
+
+impl Reference for Memory{
+    fn reference(&self) -> Memory{
+        Memory{ memory: self.memory.reference() }
+    }
+}
+
+impl AsRef<MiniObject> for Memory{
+    fn as_ref(&self) -> &MiniObject{
+        self.memory.upcast_ref()
+    }
+}
+
+impl AsMut<MiniObject> for Memory{
+    fn as_mut(&mut self) -> &mut MiniObject{
+        self.memory.upcast_mut()
+    }
+}
+
+impl From<Memory> for MiniObject{
+    fn from(m: Memory) -> MiniObject{
+        m.memory.upcast()
+    }
+}
+
+impl Deref for Memory{
+    type Target = MiniObject;
+    fn deref(&self) -> &MiniObject{
+        self.memory.upcast_ref()
+    }
+}
+
+impl DerefMut for Memory{
+    fn deref_mut(&mut self) -> &mut MiniObject{
+        self.memory.upcast_mut()
+    }
+}
@@ -1,18 +1,24 @@
 use ffi::*;
 use util::*;
 use error::Error;
+use device_monitor::{Device, DEVICES};
+use taglist::TagList;
+use element::Element;
+use object::Object;
+use structure::Structure;
+use context::{Context, CONTEXT};
 use std::os::raw;
 use reference::Reference;
 
-unsafe impl Send for GstMessage {}
-unsafe impl Send for GstTagList {}
+// Messages are immutable once posted and refcounted atomically, the
+// parsed variants only add owned rust data
 unsafe impl Send for Message {}
 
 pub type MessagePrivate = *mut GstMessage;
 
 
 unsafe fn gst_message_ref(msg: *mut GstMessage) -> *mut GstMessage{
-	gst_mini_object_ref(mem::transmute(msg)) as *mut GstMessage
+	gst_mini_object_ref(msg as *mut GstMiniObject) as *mut GstMessage
 }
 
 pub enum Message{
@@ -36,6 +42,9 @@ pub enum Message{
     ClockLost(MessagePrivate),
     NewClock(MessagePrivate),
     StructureChange(MessagePrivate),
+    /// A pad of owner is being linked or unlinked, busy is false once
+    /// it's done
+    StructureChangeParsed{msg: MessagePrivate, change_type: GstStructureChangeType, owner: Option<Element>, busy: bool},
     StreamStatus(MessagePrivate),
     Application(MessagePrivate),
     Element(MessagePrivate),
@@ -49,7 +58,14 @@ pub enum Message{
     StepStart(MessagePrivate),
     Qos(MessagePrivate),
     Progress(MessagePrivate),
+    /// Progress of a long operation, like rtspsrc connecting to a
+    /// server. code identifies the step, eg: "open" or "request", and
+    /// text is a translated description for the user
+    ProgressParsed{msg: MessagePrivate, progress_type: GstProgressType, code: String, text: String},
     Toc(MessagePrivate),
+    /// The table of contents of the stream, updated is true when it
+    /// changes an already posted one
+    TocParsed{msg: MessagePrivate, toc: ::toc::Toc, updated: bool},
     ResetTime(MessagePrivate),
     StreamStart(MessagePrivate),
     NeedContext(MessagePrivate),
@@ -57,6 +73,10 @@ pub enum Message{
     Extended(MessagePrivate),
     DeviceAdded(MessagePrivate),
     DeviceRemoved(MessagePrivate),
+    /// The streams available, see stream_collection
+    StreamCollection(MessagePrivate),
+    /// The streams being played changed, see selected_streams
+    StreamsSelected(MessagePrivate),
     Any(MessagePrivate),
 }
 
@@ -108,6 +128,8 @@ impl Message{
                  GST_MESSAGE_EXTENDED => Some(Message::Extended(gst_message)),
                  GST_MESSAGE_DEVICE_ADDED => Some(Message::DeviceAdded(gst_message)),
                  GST_MESSAGE_DEVICE_REMOVED => Some(Message::DeviceRemoved(gst_message)),
+                 GST_MESSAGE_STREAM_COLLECTION => Some(Message::StreamCollection(gst_message)),
+                 GST_MESSAGE_STREAMS_SELECTED => Some(Message::StreamsSelected(gst_message)),
                  GST_MESSAGE_ANY => Some(Message::Any(gst_message)),
                  _ => None
             }
@@ -122,17 +144,17 @@ impl Message{
 
     pub unsafe fn new_error(src: *mut GstObject, error: *mut GError, debug: &str) -> Option<Message>{
         let cdebug = CString::new(debug).unwrap();
-        Message::new(gst_message_new_error(src,error,mem::transmute(cdebug.as_ptr())))
+        Message::new(gst_message_new_error(src,error,cdebug.as_ptr()))
     }
 
     pub unsafe fn new_warning(src: *mut GstObject, error: *mut GError, debug: &str) -> Option<Message>{
         let cdebug = CString::new(debug).unwrap();
-        Message::new(gst_message_new_warning(src,error,mem::transmute(cdebug.as_ptr())))
+        Message::new(gst_message_new_warning(src,error,cdebug.as_ptr()))
     }
 
     pub unsafe fn new_info(src: *mut GstObject, error: *mut GError, debug: &str) -> Option<Message>{
 		let cdebug = CString::new(debug).unwrap();
-        Message::new(gst_message_new_info(src,error,mem::transmute(cdebug.as_ptr())))
+        Message::new(gst_message_new_info(src,error,cdebug.as_ptr()))
     }
 
     pub unsafe fn new_tag(src: *mut GstObject, tag_list: *mut GstTagList) -> Option<Message>{
@@ -170,6 +192,16 @@ impl Message{
         Message::new(gst_message_new_new_clock(src,clock))
     }
 
+    pub unsafe fn new_structure_change(src: *mut GstObject, change_type: GstStructureChangeType, owner: *mut GstElement, busy: bool) -> Option<Message>{
+        Message::new(gst_message_new_structure_change(src,change_type,owner,busy as i32))
+    }
+
+    pub unsafe fn new_progress(src: *mut GstObject, progress_type: GstProgressType, code: &str, text: &str) -> Option<Message>{
+        let ccode = CString::new(code).unwrap();
+        let ctext = CString::new(text).unwrap();
+        Message::new(gst_message_new_progress(src,progress_type,ccode.as_ptr(),ctext.as_ptr()))
+    }
+
     pub unsafe fn new_application(src: *mut GstObject, structure: *mut GstStructure) -> Option<Message>{
         Message::new(gst_message_new_application(src,structure))
     }
@@ -178,6 +210,53 @@ impl Message{
         Message::new(gst_message_new_element(src,structure))
     }
 
+    /// Creates an application message carrying structure, to send data
+    /// from application threads to the bus watch:
+    ///
+    /// ```ignore
+    /// let mut structure = gst::Structure::new("download-progress");
+    /// structure.set("percent", &50i32);
+    /// pipeline.post_message(gst::Message::application(None, &structure).unwrap());
+    /// ```
+    pub fn application(src: Option<&Object>, structure: &Structure) -> Option<Message>{
+        unsafe{
+            let src = src.map(|src| src.gst_object() as *mut GstObject).unwrap_or(ptr::null_mut());
+            let msg = gst_message_new_application(src, gst_structure_copy(structure.gst_structure()));
+            Message::new_take(msg)
+        }
+    }
+
+    /// Creates an element message carrying structure, the kind of message
+    /// elements like level or spectrum post their results with
+    pub fn element(src: Option<&Object>, structure: &Structure) -> Option<Message>{
+        unsafe{
+            let src = src.map(|src| src.gst_object() as *mut GstObject).unwrap_or(ptr::null_mut());
+            let msg = gst_message_new_element(src, gst_structure_copy(structure.gst_structure()));
+            Message::new_take(msg)
+        }
+    }
+
+    /// Creates a HaveContext message to tell the other elements of the
+    /// pipeline about context
+    pub fn have_context(src: Option<&Object>, context: &Context) -> Option<Message>{
+        unsafe{
+            let src = src.map(|src| src.gst_object() as *mut GstObject).unwrap_or(ptr::null_mut());
+            let new_have_context: unsafe extern "C" fn(*mut GstObject, *mut GstContext) -> *mut GstMessage =
+                CONTEXT.function("gst_message_new_have_context").ok()?;
+            let msg = new_have_context(src, ::Transfer::transfer(context.reference()));
+            Message::new_take(msg)
+        }
+    }
+
+    // wraps a newly created message, Message::new takes its own reference
+    unsafe fn new_take(msg: *mut GstMessage) -> Option<Message>{
+        let ret = Message::new(msg);
+        if msg != ptr::null_mut(){
+            gst_mini_object_unref(msg as *mut GstMiniObject);
+        }
+        ret
+    }
+
     pub unsafe fn new_custom(ty: GstMessageType, src: *mut GstObject, structure: *mut GstStructure) -> Option<Message>{
         Message::new(gst_message_new_custom(ty,src,structure))
     }
@@ -205,6 +284,7 @@ impl Message{
             Message::ClockLost(msg) => msg,
             Message::NewClock(msg) => msg,
             Message::StructureChange(msg) => msg,
+            Message::StructureChangeParsed{msg, ref change_type, ref owner, ref busy} => msg,
             Message::StreamStatus(msg) => msg,
             Message::Application(msg) => msg,
             Message::Element(msg) => msg,
@@ -218,7 +298,9 @@ impl Message{
             Message::StepStart(msg) => msg,
             Message::Qos(msg) => msg,
             Message::Progress(msg) => msg,
+            Message::ProgressParsed{msg, ref progress_type, ref code, ref text} => msg,
             Message::Toc(msg) => msg,
+            Message::TocParsed{msg, ref toc, ref updated} => msg,
             Message::ResetTime(msg) => msg,
             Message::StreamStart(msg) => msg,
             Message::NeedContext(msg) => msg,
@@ -226,6 +308,8 @@ impl Message{
             Message::Extended(msg) => msg,
             Message::DeviceAdded(msg) => msg,
             Message::DeviceRemoved(msg) => msg,
+            Message::StreamCollection(msg) => msg,
+            Message::StreamsSelected(msg) => msg,
             Message::Any(msg) => msg,
         }
     }
@@ -253,6 +337,7 @@ impl Message{
             Message::ClockLost(msg) => msg,
             Message::NewClock(msg) => msg,
             Message::StructureChange(msg) => msg,
+            Message::StructureChangeParsed{msg, ref change_type, ref owner, ref busy} => msg,
             Message::StreamStatus(msg) => msg,
             Message::Application(msg) => msg,
             Message::Element(msg) => msg,
@@ -266,7 +351,9 @@ impl Message{
             Message::StepStart(msg) => msg,
             Message::Qos(msg) => msg,
             Message::Progress(msg) => msg,
+            Message::ProgressParsed{msg, ref progress_type, ref code, ref text} => msg,
             Message::Toc(msg) => msg,
+            Message::TocParsed{msg, ref toc, ref updated} => msg,
             Message::ResetTime(msg) => msg,
             Message::StreamStart(msg) => msg,
             Message::NeedContext(msg) => msg,
@@ -274,6 +361,8 @@ impl Message{
             Message::Extended(msg) => msg,
             Message::DeviceAdded(msg) => msg,
             Message::DeviceRemoved(msg) => msg,
+            Message::StreamCollection(msg) => msg,
+            Message::StreamsSelected(msg) => msg,
             Message::Any(msg) => msg,
         }
     }
@@ -292,11 +381,14 @@ impl Message{
 
     pub fn seqnum(&self) -> u32{
         unsafe{
-            gst_message_get_seqnum(mem::transmute(self.gst_message()))
+            gst_message_get_seqnum(self.gst_message() as *mut GstMessage)
         }
     }
 
+    /// Sets the seqnum, copying the message first if it's shared, see
+    /// make_mut
     pub fn set_seqnum(&mut self, seqnum: u32){
+        self.make_mut();
         unsafe{
             gst_message_set_seqnum(self.gst_message_mut(),seqnum)
         }
@@ -312,19 +404,232 @@ impl Message{
         (*self.gst_message()).src
     }
 
+    /// Name of the object that posted the message, eg: "recsink", empty
+    /// if the message has no source
     pub fn src_name(&self) -> String{
         unsafe{
-            from_c_str!(mem::transmute((*self.src()).name)).to_string()
+            let src = self.src();
+            if src == ptr::null_mut() || (*src).name == ptr::null_mut(){
+                return String::new();
+            }
+            from_c_str!((*src).name).to_string()
+        }
+    }
+
+    /// Path of the object that posted the message in its pipeline, eg:
+    /// "/GstPipeline:pipeline0/GstBin:recording/GstFileSink:recsink",
+    /// empty if the message has no source
+    pub fn src_path(&self) -> String{
+        unsafe{
+            let src = self.src();
+            if src == ptr::null_mut(){
+                return String::new();
+            }
+            let c_path = gst_object_get_path_string(src);
+            let path = from_c_str!(c_path).to_string();
+            g_free(c_path as gpointer);
+            path
+        }
+    }
+
+    /// Whether the message was posted by object or by any object inside
+    /// it, eg: to only handle the errors of one branch of a pipeline
+    pub fn is_from(&self, object: &Object) -> bool{
+        unsafe{
+            let src = self.src();
+            src != ptr::null_mut() && gst_object_has_ancestor(src, object.gst_object() as *mut GstObject) != 0
+        }
+    }
+
+    /// The element that posted the message, None if it was posted by
+    /// another kind of object, eg: a bus or a device monitor
+    pub fn src_element(&self) -> Option<Element>{
+        unsafe{
+            let src = self.src();
+            if src == ptr::null_mut() || g_type_check_instance_is_a(src as *mut GTypeInstance, gst_element_get_type()) == 0{
+                return None;
+            }
+            gst_object_ref(src as gpointer);
+            Element::new_from_gst_element(src as *mut GstElement)
+        }
+    }
+
+    /// The type of the context an element needs for NeedContext
+    /// messages, eg: "gst.gl.GLDisplay", None for any other message
+    pub fn context_type(&self) -> Option<String>{
+        unsafe{
+            if (*self.gst_message())._type != GST_MESSAGE_NEED_CONTEXT{
+                return None;
+            }
+            let mut context_type: *const gchar = ptr::null();
+            let parse_context_type: unsafe extern "C" fn(*mut GstMessage, *mut *const gchar) -> gboolean =
+                CONTEXT.function("gst_message_parse_context_type").ok()?;
+            if parse_context_type(self.gst_message() as *mut GstMessage, &mut context_type) != 0{
+                Some(from_c_str!(context_type).to_string())
+            }else{
+                None
+            }
+        }
+    }
+
+    /// The context an element created for HaveContext messages, it can
+    /// be set on elements of other pipelines to share it
+    pub fn context(&self) -> Option<Context>{
+        unsafe{
+            if (*self.gst_message())._type != GST_MESSAGE_HAVE_CONTEXT{
+                return None;
+            }
+            let mut context: *mut GstContext = ptr::null_mut();
+            let parse_have_context: unsafe extern "C" fn(*mut GstMessage, *mut *mut GstContext) =
+                CONTEXT.function("gst_message_parse_have_context").ok()?;
+            parse_have_context(self.gst_message() as *mut GstMessage, &mut context);
+            Context::new_from_gst_context(context)
+        }
+    }
+
+    /// The device that was added or removed for DeviceAdded and
+    /// DeviceRemoved messages posted by a DeviceMonitor
+    pub fn device(&self) -> Option<Device>{
+        unsafe{
+            let mut device: *mut GstDevice = ptr::null_mut();
+            let parse: unsafe extern "C" fn(*mut GstMessage, *mut *mut GstDevice) = match *self{
+                Message::DeviceAdded(_) => DEVICES.function("gst_message_parse_device_added").ok()?,
+                Message::DeviceRemoved(_) => DEVICES.function("gst_message_parse_device_removed").ok()?,
+                _ => return None,
+            };
+            parse(self.gst_message() as *mut GstMessage, &mut device);
+            Device::new_from_gst_device(device)
         }
     }
 
     pub unsafe fn structure(&self) -> *const GstStructure{
-        gst_message_get_structure(mem::transmute(self.gst_message()))
+        gst_message_get_structure(self.gst_message() as *mut GstMessage)
+    }
+
+    /// A copy of the structure of application and element messages, like
+    /// the "level" results of the level element with their "rms" and
+    /// "peak" fields, or of any other message that carries one
+    ///
+    /// ```ignore
+    /// if let gst::Message::Element(_) = msg{
+    ///     if let Some(structure) = msg.get_structure(){
+    ///         if structure.name() == "level"{
+    ///             println!("{:?}", structure.get_value("rms"));
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn get_structure(&self) -> Option<Structure>{
+        unsafe{
+            let structure = self.structure();
+            if structure == ptr::null(){
+                None
+            }else{
+                Structure::new_owned(gst_structure_copy(structure))
+            }
+        }
+    }
+
+    /// The name of the structure of the message, eg: "level" or
+    /// "spectrum" for element messages, None if it has none
+    pub fn structure_name(&self) -> Option<String>{
+        unsafe{
+            let structure = self.structure();
+            if structure == ptr::null(){
+                None
+            }else{
+                Some(from_c_str!(gst_structure_get_name(structure)).to_string())
+            }
+        }
+    }
+
+    /// The streams of a StreamCollection message, or the whole
+    /// collection the selected streams belong to for StreamsSelected
+    pub fn stream_collection(&self) -> Option<::stream::StreamCollection>{
+        unsafe{
+            let mut collection: *mut GstStreamCollection = ptr::null_mut();
+            let parse: unsafe extern "C" fn(*mut GstMessage, *mut *mut GstStreamCollection) = match *self{
+                Message::StreamCollection(_) => ::stream::STREAMS.function("gst_message_parse_stream_collection").ok()?,
+                Message::StreamsSelected(_) => ::stream::STREAMS.function("gst_message_parse_streams_selected").ok()?,
+                _ => return None,
+            };
+            parse(self.gst_message() as *mut GstMessage, &mut collection);
+            ::stream::StreamCollection::new(collection)
+        }
+    }
+
+    /// The streams being played after a StreamsSelected message, empty
+    /// for any other message
+    pub fn selected_streams(&self) -> Vec<::stream::Stream>{
+        unsafe{
+            match *self{
+                Message::StreamsSelected(msg) => {
+                    let get_size = ::stream::STREAMS.function::<unsafe extern "C" fn(*mut GstMessage) -> guint>("gst_message_streams_selected_get_size");
+                    let get_stream = ::stream::STREAMS.function::<unsafe extern "C" fn(*mut GstMessage, guint) -> *mut GstStream>("gst_message_streams_selected_get_stream");
+                    match (get_size, get_stream){
+                        (Ok(get_size), Ok(get_stream)) => (0..get_size(msg)).filter_map(|i| ::stream::Stream::new(get_stream(msg, i))).collect(),
+                        _ => vec![],
+                    }
+                }
+                _ => vec![],
+            }
+        }
+    }
+
+    /// The tags of a Tag message, also if it was already parsed
+    pub fn tags(&self) -> Option<TagList>{
+        unsafe{
+            if (*self.gst_message())._type != GST_MESSAGE_TAG{
+                return None;
+            }
+            let mut tags: *mut GstTagList = ptr::null_mut();
+            gst_message_parse_tag(self.gst_message() as *mut GstMessage, &mut tags);
+            TagList::new_from_gst_taglist(tags)
+        }
+    }
+
+    /// The toc of a Toc message and whether it's an update of a previous
+    /// one, also if it was already parsed
+    pub fn toc(&self) -> Option<(::toc::Toc, bool)>{
+        unsafe{
+            if (*self.gst_message())._type != GST_MESSAGE_TOC{
+                return None;
+            }
+            let mut toc: *mut GstToc = ptr::null_mut();
+            let mut updated: gboolean = 0;
+            gst_message_parse_toc(self.gst_message() as *mut GstMessage, &mut toc, &mut updated);
+            ::toc::Toc::new_from_gst_toc(toc).map(|toc| (toc, updated != 0))
+        }
+    }
+
+    /// The percent and stats of a Buffering message, also if it was
+    /// already parsed
+    pub fn buffering(&self) -> Option<::buffering::BufferingMessage>{
+        unsafe{
+            ::buffering::BufferingMessage::new_from_gst_message(self.gst_message() as *mut GstMessage)
+        }
+    }
+
+    /// The values and stats of a Qos message, to see how many buffers
+    /// an element is dropping and how late they arrive
+    pub fn qos(&self) -> Option<::qos::QosMessage>{
+        unsafe{
+            ::qos::QosMessage::new_from_gst_message(self.gst_message() as *mut GstMessage)
+        }
+    }
+
+    /// The type, owner and task of a StreamStatus message
+    pub fn stream_status(&self) -> Option<::threads::StreamStatus>{
+        unsafe{
+            ::threads::StreamStatus::new_from_gst_message(self.gst_message() as *mut GstMessage)
+        }
     }
 
     pub fn make_writable(&self) -> Option<Message>{
         unsafe{
-            Message::new(gst_mini_object_make_writable(self.gst_message() as *mut GstMiniObject) as *mut GstMessage)
+            // make_writable takes ownership of the passed reference
+            let message = gst_mini_object_ref(self.gst_message() as *mut GstMiniObject);
+            Message::new(gst_mini_object_make_writable(message) as *mut GstMessage)
         }
     }
 
@@ -334,6 +639,17 @@ impl Message{
         }
     }
 
+    /// Makes the message writable in place, copying it only if it's
+    /// shared
+    pub fn make_mut(&mut self) -> &mut Message{
+        if !self.is_writable(){
+            if let Some(message) = self.make_writable(){
+                *self = message;
+            }
+        }
+        self
+    }
+
     pub fn parse(&self) -> Message{
         unsafe{
 			let ret = Message::new(gst_mini_object_copy(self.gst_message() as *mut GstMiniObject) as *const GstMessage).unwrap();
@@ -342,28 +658,31 @@ impl Message{
                     let mut error: *mut GError = ptr::null_mut();
                     let mut debug: *mut raw::c_char = ptr::null_mut();
                     gst_message_parse_error(message,&mut error,&mut debug);
-                    let str_error = from_c_str!(mem::transmute(debug)).to_string();
-                    g_free(mem::transmute(debug));
+                    let error = Error::new_from_message(error, (*message).src, debug);
+                    let str_error = error.debug().unwrap_or("").to_string();
+                    g_free(debug as gpointer);
                     let message = gst_message_ref(message);
-                    Message::ErrorParsed{msg: message, error: Error::new_from_g_error(error), debug: str_error}
+                    Message::ErrorParsed{msg: message, error: error, debug: str_error}
                 }
                 Message::Warning(message) => {
                     let mut error: *mut GError = ptr::null_mut();
                     let mut debug: *mut raw::c_char = ptr::null_mut();
                     gst_message_parse_warning(message,&mut error,&mut debug);
-                    let str_error = from_c_str!(mem::transmute(debug)).to_string();
-                    g_free(mem::transmute(debug));
+                    let error = Error::new_from_message(error, (*message).src, debug);
+                    let str_error = error.debug().unwrap_or("").to_string();
+                    g_free(debug as gpointer);
                     let message = gst_message_ref(message);
-                    Message::WarningParsed{msg: message, error: Error::new_from_g_error(error), debug: str_error}
+                    Message::WarningParsed{msg: message, error: error, debug: str_error}
                 }
                 Message::Info(message) => {
                     let mut error: *mut GError = ptr::null_mut();
                     let mut debug: *mut raw::c_char = ptr::null_mut();
                     gst_message_parse_info(message,&mut error,&mut debug);
-                    let str_error = from_c_str!(mem::transmute(debug)).to_string();
-                    g_free(mem::transmute(debug));
+                    let error = Error::new_from_message(error, (*message).src, debug);
+                    let str_error = error.debug().unwrap_or("").to_string();
+                    g_free(debug as gpointer);
                     let message = gst_message_ref(message);
-                    Message::InfoParsed{msg: message, error: Error::new_from_g_error(error), debug: str_error}
+                    Message::InfoParsed{msg: message, error: error, debug: str_error}
                 }
                 Message::Tag(message) => {
                     let mut tags: *mut GstTagList = ptr::null_mut();
@@ -385,6 +704,38 @@ impl Message{
                     let message = gst_message_ref(message);
                     Message::StateChangedParsed{msg: message, old: old, new: new, pending: pending}
                 }
+                Message::StructureChange(message) => {
+                    let mut change_type: GstStructureChangeType = GST_STRUCTURE_CHANGE_TYPE_PAD_LINK;
+                    let mut owner: *mut GstElement = ptr::null_mut();
+                    let mut busy: gboolean = 0;
+                    gst_message_parse_structure_change(message,&mut change_type,&mut owner,&mut busy);
+                    if owner != ptr::null_mut(){
+                        gst_object_ref(owner as gpointer);
+                    }
+                    let owner = Element::new_from_gst_element(owner);
+                    let message = gst_message_ref(message);
+                    Message::StructureChangeParsed{msg: message, change_type: change_type, owner: owner, busy: busy != 0}
+                }
+                Message::Progress(message) => {
+                    let mut progress_type: GstProgressType = GST_PROGRESS_TYPE_START;
+                    let mut code: *mut gchar = ptr::null_mut();
+                    let mut text: *mut gchar = ptr::null_mut();
+                    gst_message_parse_progress(message,&mut progress_type,&mut code,&mut text);
+                    let str_code = if code != ptr::null_mut() { from_c_str!(code).to_string() } else { String::new() };
+                    let str_text = if text != ptr::null_mut() { from_c_str!(text).to_string() } else { String::new() };
+                    g_free(code as gpointer);
+                    g_free(text as gpointer);
+                    let message = gst_message_ref(message);
+                    Message::ProgressParsed{msg: message, progress_type: progress_type, code: str_code, text: str_text}
+                }
+                Message::Toc(message) => {
+                    let mut toc: *mut GstToc = ptr::null_mut();
+                    let mut updated: gboolean = 0;
+                    gst_message_parse_toc(message,&mut toc,&mut updated);
+                    let toc = ::toc::Toc::new_from_gst_toc(toc).unwrap();
+                    let message = gst_message_ref(message);
+                    Message::TocParsed{msg: message, toc: toc, updated: updated != 0}
+                }
                 _ => {
                     ret
                 }
@@ -483,7 +834,7 @@ impl Reference for Message{
 
     fn src_name(&self) -> String{
         unsafe{
-            from_c_str!(mem::transmute((*self.src()).name)).to_string()
+            from_c_str!((*self.src()).name).to_string()
         }
     }
 
@@ -533,6 +884,8 @@ pub struct HaveContext(MessagePrivate);
 pub struct Extended(MessagePrivate);
 pub struct DeviceAdded(MessagePrivate);
 pub struct DeviceRemoved(MessagePrivate);
+pub struct StreamCollection(MessagePrivate);
+pub struct StreamsSelected(MessagePrivate);
 pub struct Any(MessagePrivate);
 
 
@@ -606,6 +959,8 @@ msg_impl!(HaveContext,GST_MESSAGE_HAVE_CONTEXT);
 msg_impl!(Extended,GST_MESSAGE_EXTENDED);
 msg_impl!(DeviceAdded,GST_MESSAGE_DEVICE_ADDED);
 msg_impl!(DeviceRemoved,GST_MESSAGE_DEVICE_REMOVED);
+msg_impl!(StreamCollection,GST_MESSAGE_STREAM_COLLECTION);
+msg_impl!(StreamsSelected,GST_MESSAGE_STREAMS_SELECTED);
 msg_impl!(Any,GST_MESSAGE_ANY);
 
 impl Eos{
@@ -0,0 +1,108 @@
+use ffi::*;
+use bus::Bus;
+use message::Message;
+use handle::SignalHandle;
+use reference::Reference;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Keeps the last messages posted on a bus so components attached
+/// later, eg: a debug UI opened after the pipeline started, can see the
+/// recent errors, state changes or tags:
+///
+/// ```ignore
+/// let history = gst::message_history::MessageHistory::attach(&mut pipeline.bus().unwrap(),
+///     100, GST_MESSAGE_ERROR | GST_MESSAGE_WARNING | GST_MESSAGE_STATE_CHANGED | GST_MESSAGE_TAG);
+/// // later
+/// for msg in history.messages(){
+///     println!("{} from {}", msg.type_name(), msg.src_name());
+/// }
+/// ```
+///
+/// The messages are recorded from the thread that posts them using the
+/// sync-message signal of the bus, so they are recorded even if nobody
+/// is handling the bus, and watches and sync handlers still receive
+/// them. Recording stops when the history is dropped.
+pub struct MessageHistory{
+    messages: Arc<Mutex<VecDeque<Message>>>,
+    bus: Bus,
+    _handle: Option<SignalHandle>,
+}
+
+impl MessageHistory{
+    /// Records up to capacity messages of types, a combination of
+    /// GST_MESSAGE_* types, GST_MESSAGE_ANY for all of them. Older
+    /// messages are discarded when it's full
+    pub fn attach(bus: &mut Bus, capacity: usize, types: GstMessageType) -> MessageHistory{
+        let messages = Arc::new(Mutex::new(VecDeque::with_capacity(capacity)));
+        let handle = {
+            let messages = messages.clone();
+            unsafe{
+                gst_bus_enable_sync_message_emission(bus.gst_bus_mut());
+            }
+            bus.connect_values("sync-message", move |args| {
+                let msg = match args.get(1){
+                    Some(arg) => unsafe{ Message::new(g_value_get_boxed(arg.gst_value()) as *const GstMessage) },
+                    None => None,
+                };
+                if let Some(msg) = msg{
+                    if msg.ty() & types != 0 && capacity > 0{
+                        let mut messages = messages.lock().unwrap_or_else(|e| e.into_inner());
+                        if messages.len() == capacity{
+                            messages.pop_front();
+                        }
+                        messages.push_back(msg);
+                    }
+                }
+                None
+            })
+        };
+        MessageHistory{
+            messages: messages,
+            bus: bus.reference(),
+            _handle: handle,
+        }
+    }
+
+    /// The recorded messages, oldest first
+    pub fn messages(&self) -> Vec<Message>{
+        self.messages.lock().unwrap_or_else(|e| e.into_inner()).iter().map(|msg| msg.reference()).collect()
+    }
+
+    /// The recorded messages of types, oldest first
+    pub fn messages_of_type(&self, types: GstMessageType) -> Vec<Message>{
+        self.messages.lock().unwrap_or_else(|e| e.into_inner()).iter()
+            .filter(|msg| msg.ty() & types != 0)
+            .map(|msg| msg.reference())
+            .collect()
+    }
+
+    /// The most recent error message, if any was recorded
+    pub fn last_error(&self) -> Option<Message>{
+        self.messages.lock().unwrap_or_else(|e| e.into_inner()).iter().rev()
+            .find(|msg| msg.ty() == GST_MESSAGE_ERROR)
+            .map(|msg| msg.reference())
+    }
+
+    pub fn len(&self) -> usize{
+        self.messages.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+
+    pub fn clear(&self){
+        self.messages.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+impl Drop for MessageHistory{
+    fn drop(&mut self){
+        self._handle.take();
+        unsafe{
+            gst_bus_disable_sync_message_emission(self.bus.gst_bus_mut());
+        }
+    }
+}
@@ -0,0 +1,407 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use structure::Structure;
+use videoinfo::VideoInfo;
+use panic::catch_panic;
+use version::ApiAvailability;
+
+use std::any::TypeId;
+#[cfg(unix)]
+use std::os::unix::io::RawFd;
+use std::sync::Mutex;
+
+/// Metadata attached to a buffer, like the strides of a video frame or
+/// a timestamp from a reference clock
+pub type Meta = GstMeta;
+
+/// Layout of the planes of a video frame, elements that produce frames
+/// with padding add it so downstream can find the data of each plane
+pub type VideoMeta = GstVideoMeta;
+
+/// Region of a video frame that should be displayed
+pub type VideoCropMeta = GstVideoCropMeta;
+
+/// A timestamp from a clock other than the pipeline's, like the NTP
+/// time at which a frame was captured. Needs GStreamer 1.14, with older
+/// versions buffers never have it and it can't be added
+pub type ReferenceTimestampMeta = GstReferenceTimestampMeta;
+
+pub const REFERENCE_TIMESTAMP_META: ApiAvailability = ApiAvailability::new("ReferenceTimestampMeta", 1, 14, 0);
+
+/// A socket control message received along with the data, like file
+/// descriptors passed over a unix socket or packet info from udpsrc.
+/// Needs GStreamer 1.4
+pub type NetControlMessageMeta = GstNetControlMessageMeta;
+
+pub const NET_CONTROL_MESSAGE_META: ApiAvailability = ApiAvailability::new("NetControlMessageMeta", 1, 4, 0);
+
+/// How a buffer of an encrypted stream was encrypted, eg: the key id
+/// and IV of a cenc sample, added by demuxers for the decryptors.
+/// Needs GStreamer 1.6
+pub type ProtectionMeta = GstProtectionMeta;
+
+pub const PROTECTION_META: ApiAvailability = ApiAvailability::new("ProtectionMeta", 1, 6, 0);
+
+impl Meta{
+    /// Type of the API this meta implements
+    pub fn api_type(&self) -> GType{
+        unsafe{ (*self.info).api }
+    }
+
+    /// Name of the API this meta implements, eg: "GstVideoMetaAPI"
+    pub fn api_name(&self) -> &str{
+        unsafe{ from_c_str!(g_type_name(self.api_type())) }
+    }
+
+    pub fn video_meta(&self) -> Option<&VideoMeta>{
+        unsafe{
+            if self.api_type() == gst_video_meta_api_get_type(){
+                Some(&*(self as *const Meta as *const VideoMeta))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn video_crop_meta(&self) -> Option<&VideoCropMeta>{
+        unsafe{
+            if self.api_type() == gst_video_crop_meta_api_get_type(){
+                Some(&*(self as *const Meta as *const VideoCropMeta))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn reference_timestamp_meta(&self) -> Option<&ReferenceTimestampMeta>{
+        unsafe{
+            let api_get_type = REFERENCE_TIMESTAMP_META.function::<unsafe extern "C" fn() -> GType>("gst_reference_timestamp_meta_api_get_type");
+            if api_get_type.map(|api_get_type| self.api_type() == api_get_type()).unwrap_or(false){
+                Some(&*(self as *const Meta as *const ReferenceTimestampMeta))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn net_control_message_meta(&self) -> Option<&NetControlMessageMeta>{
+        unsafe{
+            let api_get_type = NET_CONTROL_MESSAGE_META.function::<unsafe extern "C" fn() -> GType>("gst_net_control_message_meta_api_get_type");
+            if api_get_type.map(|api_get_type| self.api_type() == api_get_type()).unwrap_or(false){
+                Some(&*(self as *const Meta as *const NetControlMessageMeta))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn protection_meta(&self) -> Option<&ProtectionMeta>{
+        unsafe{
+            let api_get_type = PROTECTION_META.function::<unsafe extern "C" fn() -> GType>("gst_protection_meta_api_get_type");
+            if api_get_type.map(|api_get_type| self.api_type() == api_get_type()).unwrap_or(false){
+                Some(&*(self as *const Meta as *const ProtectionMeta))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns the data of a meta added with Buffer::add_custom_meta
+    pub fn custom_meta<T: CustomMeta>(&self) -> Option<&T>{
+        unsafe{
+            let info = custom_meta_info::<T>();
+            if info != ptr::null() && self.api_type() == (*info).api{
+                Some(&(*(self as *const Meta as *const RustMeta<T>)).data)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+impl VideoMeta{
+    pub fn format(&self) -> GstVideoFormat{
+        self.format
+    }
+
+    pub fn width(&self) -> u32{
+        self.width
+    }
+
+    pub fn height(&self) -> u32{
+        self.height
+    }
+
+    pub fn n_planes(&self) -> u32{
+        self.n_planes
+    }
+
+    /// Bytes from the start of the buffer to the first line of each plane
+    pub fn offsets(&self) -> &[gsize]{
+        &self.offset[..self.n_planes as usize]
+    }
+
+    /// Bytes between the start of two consecutive lines of each plane
+    pub fn strides(&self) -> &[i32]{
+        &self.stride[..self.n_planes as usize]
+    }
+}
+
+impl ReferenceTimestampMeta{
+    /// Caps describing the reference clock, eg:
+    /// timestamp/x-ntp, host=pool.ntp.org, port=123
+    pub fn reference(&self) -> Option<Caps>{
+        unsafe{
+            if self.reference != ptr::null_mut(){
+                gst_mini_object_ref(self.reference as *mut GstMiniObject);
+            }
+            Caps::new(self.reference)
+        }
+    }
+
+    pub fn timestamp(&self) -> GstClockTime{
+        self.timestamp
+    }
+
+    pub fn duration(&self) -> GstClockTime{
+        self.duration
+    }
+}
+
+impl ProtectionMeta{
+    /// Copy of the encryption parameters, for cenc a structure named
+    /// "application/x-cenc" with the "kid", "iv_size", "iv" and
+    /// "subsamples" fields among others
+    pub fn info(&self) -> Option<Structure>{
+        unsafe{
+            Structure::new_from_gst_structure(self.info).map(|info| info.copy())
+        }
+    }
+}
+
+impl NetControlMessageMeta{
+    /// Protocol level of the message, eg: SOL_SOCKET or IPPROTO_IP
+    pub fn level(&self) -> i32{
+        unsafe{ g_socket_control_message_get_level(self.message) }
+    }
+
+    /// Protocol specific type of the message, eg: SCM_RIGHTS
+    pub fn msg_type(&self) -> i32{
+        unsafe{ g_socket_control_message_get_msg_type(self.message) }
+    }
+
+    /// Payload of the message as it's sent over the socket
+    pub fn data(&self) -> Vec<u8>{
+        unsafe{
+            let mut data = vec![0u8; g_socket_control_message_get_size(self.message) as usize];
+            g_socket_control_message_serialize(self.message, data.as_mut_ptr() as gpointer);
+            data
+        }
+    }
+
+    /// True if the message carries file descriptors
+    #[cfg(unix)]
+    pub fn has_fds(&self) -> bool{
+        unsafe{
+            g_type_check_instance_is_a(self.message as *mut GTypeInstance, g_unix_fd_message_get_type()) != 0
+        }
+    }
+
+    /// Duplicates of the file descriptors passed in the message, the
+    /// caller owns them and has to close them. Empty if the message
+    /// doesn't carry file descriptors
+    #[cfg(unix)]
+    pub fn fds(&self) -> Vec<RawFd>{
+        if !self.has_fds(){
+            return vec![];
+        }
+        unsafe{
+            let list = g_unix_fd_message_get_fd_list(self.message);
+            (0..g_unix_fd_list_get_length(list)).filter_map(|i| {
+                let fd = g_unix_fd_list_get(list, i, ptr::null_mut());
+                if fd >= 0{ Some(fd) }else{ None }
+            }).collect()
+        }
+    }
+}
+
+/// Implemented by types that can be attached to buffers as metadata,
+/// the meta is registered the first time a value of the type is added
+/// to a buffer.
+///
+/// The data is dropped when the buffer is freed. When elements copy
+/// or transform the buffer, eg: a scaler, transform decides what the
+/// data becomes in the new buffer, by default it's cloned on copies and
+/// dropped by any other transformation.
+///
+/// Buffers are shared between threads and any of them can read the
+/// data through a shared reference, so it has to be Sync as well.
+///
+/// ```ignore
+/// #[derive(Clone)]
+/// struct FrameId(u64);
+///
+/// impl gst::CustomMeta for FrameId{
+///     fn name() -> &'static str{ "FrameIdMeta" }
+/// }
+///
+/// buffer.add_custom_meta(FrameId(42));
+/// let id = buffer.custom_meta::<FrameId>().map(|id| id.0);
+/// ```
+pub trait CustomMeta: Clone + Send + Sync + 'static{
+    /// Unique name used to register the meta in the GType system
+    fn name() -> &'static str;
+
+    /// Aspects of the buffer the data depends on, eg: "video" and
+    /// "size" for detection boxes in pixel coordinates. Elements that
+    /// change some of those aspects drop the metas tagged with them
+    /// unless they know how to transform them
+    fn tags() -> &'static [&'static str]{
+        &[]
+    }
+
+    /// The data for the buffer produced by transform, None to not copy
+    /// the meta to it
+    ///
+    /// ```ignore
+    /// impl gst::CustomMeta for Detection{
+    ///     fn name() -> &'static str{ "DetectionMeta" }
+    ///     fn tags() -> &'static [&'static str]{ &["video", "size"] }
+    ///     fn transform(&self, transform: gst::MetaTransform) -> Option<Detection>{
+    ///         match transform{
+    ///             gst::MetaTransform::Copy{..} => Some(self.clone()),
+    ///             gst::MetaTransform::VideoScale{input, output} => Some(self.scaled(
+    ///                 output.width() as f64 / input.width() as f64,
+    ///                 output.height() as f64 / input.height() as f64)),
+    ///             _ => None,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    fn transform(&self, transform: MetaTransform) -> Option<Self>{
+        match transform{
+            MetaTransform::Copy{..} => Some(self.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// How a buffer with a custom meta is being transformed, see
+/// CustomMeta::transform
+pub enum MetaTransform<'a>{
+    /// The buffer is copied, region is true if only size bytes from
+    /// offset are copied
+    Copy{region: bool, offset: usize, size: usize},
+    /// The video frame of the buffer is scaled from input to output
+    VideoScale{input: &'a VideoInfo, output: &'a VideoInfo},
+    /// Any other transformation, with its name, eg: a custom one of some
+    /// element
+    Other(&'a str),
+}
+
+#[repr(C)]
+struct RustMeta<T>{
+    meta: GstMeta,
+    data: T,
+}
+
+static CUSTOM_METAS: Mutex<Vec<(TypeId, usize)>> = Mutex::new(Vec::new());
+
+/// Returns the meta info registered for T or null if it wasn't
+/// registered yet
+pub fn custom_meta_info<T: CustomMeta>() -> *const GstMetaInfo{
+    CUSTOM_METAS.lock().unwrap().iter()
+        .find(|&&(id, _)| id == TypeId::of::<T>())
+        .map(|&(_, info)| info as *const GstMetaInfo)
+        .unwrap_or(ptr::null())
+}
+
+/// Returns the meta info for T, registering it if needed
+pub fn register_custom_meta<T: CustomMeta>() -> *const GstMetaInfo{
+    let mut metas = CUSTOM_METAS.lock().unwrap();
+    if let Some(&(_, info)) = metas.iter().find(|&&(id, _)| id == TypeId::of::<T>()){
+        return info as *const GstMetaInfo;
+    }
+    let api_name = CString::new(format!("{}API", T::name())).unwrap();
+    let impl_name = CString::new(T::name()).unwrap();
+    unsafe{
+        let ctags: Vec<CString> = T::tags().iter().map(|tag| CString::new(*tag).unwrap()).collect();
+        let mut tags: Vec<*const gchar> = ctags.iter().map(|tag| tag.as_ptr()).collect();
+        tags.push(ptr::null());
+        let api = gst_meta_api_type_register(api_name.as_ptr(), tags.as_mut_ptr());
+        let info = gst_meta_register(api, impl_name.as_ptr(), mem::size_of::<RustMeta<T>>() as gsize,
+            Some(custom_meta_init::<T>), Some(custom_meta_free::<T>), Some(custom_meta_transform::<T>));
+        if info != ptr::null(){
+            metas.push((TypeId::of::<T>(), info as usize));
+        }
+        info
+    }
+}
+
+/// Adds a meta for T to buffer moving data into it
+pub unsafe fn add_custom_meta<T: CustomMeta>(buffer: *mut GstBuffer, data: T) -> bool{
+    let info = register_custom_meta::<T>();
+    if info == ptr::null(){
+        return false;
+    }
+    let mut data = Some(data);
+    gst_buffer_add_meta(buffer, info, &mut data as *mut Option<T> as gpointer) != ptr::null_mut()
+}
+
+/// Returns the data of the meta for T in buffer if there's any
+pub unsafe fn get_custom_meta<'a, T: CustomMeta>(buffer: *mut GstBuffer) -> Option<&'a mut T>{
+    let info = custom_meta_info::<T>();
+    if info == ptr::null(){
+        return None;
+    }
+    let meta = gst_buffer_get_meta(buffer, (*info).api) as *mut RustMeta<T>;
+    if meta != ptr::null_mut(){
+        Some(&mut (*meta).data)
+    }else{
+        None
+    }
+}
+
+extern "C" fn custom_meta_init<T: CustomMeta>(meta: *mut GstMeta, params: gpointer, _buffer: *mut GstBuffer) -> gboolean{
+    unsafe{
+        // params is NULL if the meta is added from C with
+        // gst_buffer_add_meta, there's no data to initialize it with then
+        if params == ptr::null_mut(){
+            return 0;
+        }
+        let meta = meta as *mut RustMeta<T>;
+        match (*(params as *mut Option<T>)).take(){
+            Some(data) => {
+                ptr::write(&mut (*meta).data, data);
+                1
+            }
+            None => 0
+        }
+    }
+}
+
+extern "C" fn custom_meta_free<T: CustomMeta>(meta: *mut GstMeta, _buffer: *mut GstBuffer){
+    unsafe{
+        catch_panic(ptr::null_mut(), (), || ptr::drop_in_place(&mut (*(meta as *mut RustMeta<T>)).data));
+    }
+}
+
+extern "C" fn custom_meta_transform<T: CustomMeta>(dest: *mut GstBuffer, meta: *mut GstMeta, _buffer: *mut GstBuffer, transform: GQuark, data: gpointer) -> gboolean{
+    unsafe{
+        let copy = CString::new("gst-copy").unwrap();
+        let transform = if transform == g_quark_from_string(copy.as_ptr()){
+            let copy = &*(data as *const GstMetaTransformCopy);
+            MetaTransform::Copy{ region: copy.region != 0, offset: copy.offset as usize, size: copy.size as usize }
+        }else if transform == gst_video_meta_transform_scale_get_quark(){
+            let scale = &*(data as *const GstVideoMetaTransform);
+            MetaTransform::VideoScale{ input: &*scale.in_info, output: &*scale.out_info }
+        }else{
+            MetaTransform::Other(from_c_str!(g_quark_to_string(transform)))
+        };
+        match catch_panic(ptr::null_mut(), None, || (*(meta as *mut RustMeta<T>)).data.transform(transform)){
+            Some(data) => add_custom_meta(dest, data) as gboolean,
+            None => 0
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use pad::Pad;
+use message::Message;
+use taglist::{TagList, TagScope, MergeMode};
+use toc::Toc;
+use handle::ProbeHandle;
+use reference::Reference;
+
+use std::sync::{Arc, Mutex};
+
+struct MetadataState{
+    global: TagList,
+    streams: Vec<(String, TagList)>,
+    toc: Option<Toc>,
+}
+
+impl MetadataState{
+    fn add_tags(&mut self, source: &str, tags: &TagList){
+        // merged into new lists so the ones already handed out stay
+        // unchanged
+        if tags.scope() == TagScope::Global{
+            self.global = self.global.merge(tags, MergeMode::Replace);
+            return;
+        }
+        match self.streams.iter_mut().find(|&&mut (ref name, _)| name == source){
+            Some(&mut (_, ref mut stream)) => *stream = stream.merge(tags, MergeMode::Replace),
+            None => self.streams.push((source.to_string(), tags.reference())),
+        }
+    }
+}
+
+/// Collects the tags and toc of a pipeline whether the elements post
+/// them on the bus or only send them downstream as events, so the
+/// application sees the same metadata whatever the demuxers and decoders
+/// in the pipeline do.
+///
+/// Global tags, eg: the title, are merged into a single list. Stream
+/// tags, eg: the codec or the language, are kept per source: the name
+/// of the watched pad or of the element that posted the message.
+///
+/// ```ignore
+/// let mut metadata = gst::metadata::MetadataCollector::new();
+/// metadata.watch_pad(&mut video_sink_pad);
+/// metadata.watch_pad(&mut audio_sink_pad);
+/// let _watch = bus.add_watch(move |msg| {
+///     metadata.handle_message(&msg);
+///     if let Some(title) = metadata.tags().get::<String>("title"){
+///         window.set_title(&title);
+///     }
+///     true
+/// });
+/// ```
+pub struct MetadataCollector{
+    state: Arc<Mutex<MetadataState>>,
+    probes: Vec<ProbeHandle>,
+}
+
+impl MetadataCollector{
+    pub fn new() -> MetadataCollector{
+        MetadataCollector{
+            state: Arc::new(Mutex::new(MetadataState{
+                global: TagList::new(),
+                streams: vec![],
+                toc: None,
+            })),
+            probes: vec![],
+        }
+    }
+
+    /// Collects the tag and toc events going through pad, until the
+    /// collector is dropped
+    pub fn watch_pad(&mut self, pad: &mut Pad) -> bool{
+        let name = pad.name();
+        let state = self.state.clone();
+        let tags_probe = pad.connect_tags(move |tags| {
+            state.lock().unwrap_or_else(|e| e.into_inner()).add_tags(&name, &tags);
+        });
+        let state = self.state.clone();
+        let toc_probe = pad.connect_toc(move |toc, _updated| {
+            state.lock().unwrap_or_else(|e| e.into_inner()).toc = Some(toc);
+        });
+        match (tags_probe, toc_probe){
+            (Some(tags_probe), Some(toc_probe)) => {
+                self.probes.push(tags_probe);
+                self.probes.push(toc_probe);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Collects the tags or toc of msg, returns false if it's neither a
+    /// Tag nor a Toc message
+    pub fn handle_message(&self, msg: &Message) -> bool{
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(tags) = msg.tags(){
+            state.add_tags(&msg.src_name(), &tags);
+            true
+        }else if let Some((toc, _updated)) = msg.toc(){
+            state.toc = Some(toc);
+            true
+        }else{
+            false
+        }
+    }
+
+    /// The global tags merged with the tags of every stream, the global
+    /// values taking precedence
+    pub fn tags(&self) -> TagList{
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.streams.iter().fold(state.global.reference(), |tags, &(_, ref stream)| tags.merge(stream, MergeMode::Keep))
+    }
+
+    pub fn global_tags(&self) -> TagList{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).global.reference()
+    }
+
+    /// Tags of the stream from source, the name of a watched pad or of
+    /// the element that posted them
+    pub fn stream_tags(&self, source: &str) -> Option<TagList>{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).streams.iter()
+            .find(|&&(ref name, _)| name == source)
+            .map(|&(_, ref tags)| tags.reference())
+    }
+
+    /// Names of the sources of the stream tags received so far
+    pub fn stream_sources(&self) -> Vec<String>{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).streams.iter().map(|&(ref name, _)| name.clone()).collect()
+    }
+
+    /// The latest toc received
+    pub fn toc(&self) -> Option<Toc>{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).toc.as_ref().map(|toc| toc.reference())
+    }
+
+    /// Forgets everything collected, eg: when a new uri is played
+    pub fn clear(&self){
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.global = TagList::new();
+        state.streams.clear();
+        state.toc = None;
+    }
+}
@@ -0,0 +1,171 @@
+use ffi::*;
+use util::*;
+
+use message::Message;
+use structure::Structure;
+use value::Value;
+
+/// Timing of the interval a level or spectrum message was measured over,
+/// all in nanoseconds
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct Interval{
+    pub timestamp: u64,
+    pub stream_time: u64,
+    pub running_time: u64,
+    pub duration: u64,
+    pub endtime: u64,
+}
+
+impl Interval{
+    fn from_structure(structure: &Structure) -> Interval{
+        Interval{
+            timestamp: structure.get("timestamp").unwrap_or(0),
+            stream_time: structure.get("stream-time").unwrap_or(0),
+            running_time: structure.get("running-time").unwrap_or(0),
+            duration: structure.get("duration").unwrap_or(0),
+            endtime: structure.get("endtime").unwrap_or(0),
+        }
+    }
+}
+
+/// Levels posted by the level element every interval, with one value per
+/// channel in dB, 0 being full scale:
+///
+/// ```ignore
+/// let _watch = bus.add_watch(|msg| {
+///     if let Some(level) = gst::meter::Level::from_message(&msg){
+///         meter.set(level.peak.iter().map(|&db| gst::meter::db_to_linear(db)).collect());
+///     }
+///     true
+/// });
+/// ```
+#[derive(Clone,Debug,PartialEq)]
+pub struct Level{
+    pub interval: Interval,
+    pub rms: Vec<f64>,
+    pub peak: Vec<f64>,
+    /// Peak falling back at the peak-falloff rate, what meters usually
+    /// draw as the peak hold
+    pub decay: Vec<f64>,
+}
+
+impl Level{
+    /// Parses an element message posted by level, None for any other
+    /// message
+    pub fn from_message(msg: &Message) -> Option<Level>{
+        match *msg{
+            Message::Element(_) => Level::from_structure(&msg.get_structure()?),
+            _ => None
+        }
+    }
+
+    pub fn from_structure(structure: &Structure) -> Option<Level>{
+        if structure.name() != "level"{
+            return None;
+        }
+        Some(Level{
+            interval: Interval::from_structure(structure),
+            rms: structure.get_list("rms"),
+            peak: structure.get_list("peak"),
+            decay: structure.get_list("decay"),
+        })
+    }
+
+    pub fn channels(&self) -> usize{
+        self.rms.len()
+    }
+}
+
+/// Bands posted by the spectrum element every interval. Magnitudes are in
+/// dB relative to the threshold property and phases in radians, with
+/// one vector per channel if multi-channel is enabled or a single one
+/// for the mix of all of them otherwise
+#[derive(Clone,Debug,PartialEq)]
+pub struct Spectrum{
+    pub interval: Interval,
+    pub magnitude: Vec<Vec<f32>>,
+    /// Only posted if message-phase is enabled
+    pub phase: Vec<Vec<f32>>,
+}
+
+impl Spectrum{
+    /// Parses an element message posted by spectrum, None for any other
+    /// message
+    pub fn from_message(msg: &Message) -> Option<Spectrum>{
+        match *msg{
+            Message::Element(_) => Spectrum::from_structure(&msg.get_structure()?),
+            _ => None
+        }
+    }
+
+    pub fn from_structure(structure: &Structure) -> Option<Spectrum>{
+        if structure.name() != "spectrum"{
+            return None;
+        }
+        Some(Spectrum{
+            interval: Interval::from_structure(structure),
+            magnitude: channel_bands(structure, "magnitude"),
+            phase: channel_bands(structure, "phase"),
+        })
+    }
+
+    pub fn bands(&self) -> usize{
+        self.magnitude.first().map(|bands| bands.len()).unwrap_or(0)
+    }
+
+    /// Center frequency in Hz of band for audio at rate
+    pub fn band_frequency(&self, band: usize, rate: u32) -> f64{
+        let bands = self.bands();
+        if bands == 0{
+            return 0.;
+        }
+        (rate as f64 / 2. * band as f64 + rate as f64 / 4.) / bands as f64
+    }
+}
+
+/// Converts a level in dB, as in Level and Spectrum, to a linear
+/// amplitude between 0 and 1
+pub fn db_to_linear(db: f64) -> f64{
+    10f64.powf(db / 20.)
+}
+
+// spectrum posts a list of bands or, with multi-channel, an array with a
+// list of bands per channel
+fn channel_bands(structure: &Structure, name: &str) -> Vec<Vec<f32>>{
+    let cname = CString::new(name).unwrap();
+    unsafe{
+        let value = gst_structure_get_value(structure.gst_structure(), cname.as_ptr());
+        if value == ptr::null(){
+            return vec![];
+        }
+        let per_channel = g_type_check_value_holds(value as *mut GValue, gst_value_array_get_type()) != 0
+            && gst_value_array_get_size(value) > 0
+            && is_list(gst_value_array_get_value(value, 0));
+        if per_channel{
+            (0..gst_value_array_get_size(value))
+                .map(|i| bands(gst_value_array_get_value(value, i)))
+                .collect()
+        }else{
+            vec![bands(value)]
+        }
+    }
+}
+
+unsafe fn is_list(value: *const GValue) -> bool{
+    g_type_check_value_holds(value as *mut GValue, gst_value_list_get_type()) != 0
+        || g_type_check_value_holds(value as *mut GValue, gst_value_array_get_type()) != 0
+}
+
+unsafe fn bands(value: *const GValue) -> Vec<f32>{
+    if g_type_check_value_holds(value as *mut GValue, gst_value_list_get_type()) != 0{
+        (0..gst_value_list_get_size(value))
+            .filter_map(|i| Value::new_from_gvalue(gst_value_list_get_value(value, i)).get::<f32>())
+            .collect()
+    }else if g_type_check_value_holds(value as *mut GValue, gst_value_array_get_type()) != 0{
+        (0..gst_value_array_get_size(value))
+            .filter_map(|i| Value::new_from_gvalue(gst_value_array_get_value(value, i)).get::<f32>())
+            .collect()
+    }else{
+        vec![]
+    }
+}
@@ -1,14 +1,27 @@
 use ffi::*;
 use std::mem;
 use std::ptr;
+use std::marker::PhantomData;
 
 use reference::Reference;
 
-pub struct MiniObject{
+/// A reference to a GstMiniObject, T is the C struct it points to, eg:
+/// MiniObject<GstBuffer> in Buffer. MiniObject without a parameter is the
+/// untyped base all the wrappers deref to.
+#[repr(C)]
+pub struct MiniObject<T = GstMiniObject>{
     miniobject: *mut GstMiniObject,
+    marker: PhantomData<T>,
 }
 
-impl Drop for MiniObject{
+// Mini objects are refcounted atomically and the wrappers only modify
+// them through &mut once they are writable, making a copy first if they
+// are shared, so shared references are read only. Mapped memory and
+// other views borrowing from a mini object aren't Send.
+unsafe impl<T> Send for MiniObject<T> {}
+unsafe impl<T> Sync for MiniObject<T> {}
+
+impl<T> Drop for MiniObject<T>{
 	fn drop(&mut self){
         unsafe{
 			gst_mini_object_unref(self.miniobject);
@@ -27,16 +40,24 @@ pub enum LockFlags{
 
 impl MiniObject{
     pub unsafe fn new_from_gst_miniobject(miniobject: *mut GstMiniObject) -> Option<MiniObject>{
+        MiniObject::new(miniobject)
+    }
+}
+
+impl<T> MiniObject<T>{
+    /// Takes ownership of the passed reference, None if it's null
+    pub unsafe fn new(miniobject: *mut T) -> Option<MiniObject<T>>{
         if miniobject != ptr::null_mut(){
             Some(MiniObject{
-                miniobject: miniobject
+                miniobject: miniobject as *mut GstMiniObject,
+                marker: PhantomData,
             })
         }else{
             None
         }
     }
 
-    pub fn lock<F:FnMut(&mut MiniObject)>(&mut self, flags: &[LockFlags], mut f: F) -> bool{
+    pub fn lock<F:FnMut(&mut MiniObject<T>)>(&mut self, flags: &[LockFlags], mut f: F) -> bool{
         let flags = flags.iter().fold(0,|ret, flag| ret | *flag as u32);
         unsafe{
             if gst_mini_object_lock(self.miniobject, flags) != 0{
@@ -49,12 +70,43 @@ impl MiniObject{
         }
     }
 
-    pub fn make_writable(self) -> MiniObject{
+    /// True if this is the only reference, so it can be modified
+    /// without affecting anyone else
+    pub fn is_writable(&self) -> bool{
+        unsafe{
+            gst_mini_object_is_writable(self.miniobject) != 0
+        }
+    }
+
+    pub fn refcount(&self) -> usize{
         unsafe{
+            (*self.miniobject).refcount as usize
+        }
+    }
+
+    /// Returns this object if it's writable or a copy of it otherwise
+    pub fn make_writable(self) -> MiniObject<T>{
+        unsafe{
+            // make_writable takes ownership of the passed reference
+            let miniobject = ::Transfer::transfer(self.upcast());
             MiniObject{
-                miniobject: gst_mini_object_make_writable(self.miniobject)
+                miniobject: gst_mini_object_make_writable(miniobject),
+                marker: PhantomData,
+            }
+        }
+    }
+
+    /// Makes this object writable in place, copying it only if it's
+    /// shared. The wrappers call it before modifying the object so the
+    /// changes never reach other references
+    pub fn make_mut(&mut self) -> &mut MiniObject<T>{
+        if !self.is_writable(){
+            unsafe{
+                // make_writable takes ownership of the passed reference
+                self.miniobject = gst_mini_object_make_writable(self.miniobject);
             }
         }
+        self
     }
 
     pub unsafe fn gst_miniobject(&self) -> *const GstMiniObject{
@@ -64,28 +116,52 @@ impl MiniObject{
     pub unsafe fn gst_miniobject_mut(&mut self) -> *mut GstMiniObject{
         self.miniobject
     }
+
+    pub unsafe fn as_ptr(&self) -> *const T{
+        self.miniobject as *const T
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut T{
+        self.miniobject as *mut T
+    }
+
+    /// The untyped mini object, keeping the reference
+    pub fn upcast(self) -> MiniObject{
+        let miniobject = self.miniobject;
+        mem::forget(self);
+        MiniObject{ miniobject: miniobject, marker: PhantomData }
+    }
+
+    pub fn upcast_ref(&self) -> &MiniObject{
+        // same layout, only the marker differs
+        unsafe{ &*(self as *const MiniObject<T> as *const MiniObject) }
+    }
+
+    pub fn upcast_mut(&mut self) -> &mut MiniObject{
+        unsafe{ &mut *(self as *mut MiniObject<T> as *mut MiniObject) }
+    }
 }
 
-impl Clone for MiniObject{
-	fn clone(&self) -> MiniObject{
-		unsafe{
-			MiniObject{ miniobject: gst_mini_object_copy(self.miniobject as *mut GstMiniObject) }
-		}
+// Clone, same as Reference, shares the same object by increasing its
+// refcount, the object is copied once one of them is modified
+impl<T> Clone for MiniObject<T>{
+	fn clone(&self) -> MiniObject<T>{
+		self.reference()
 	}
 }
 
-impl Reference for MiniObject{
-    fn reference(&self) -> MiniObject{
+impl<T> Reference for MiniObject<T>{
+    fn reference(&self) -> MiniObject<T>{
         unsafe{
-			MiniObject{ miniobject: gst_mini_object_ref(self.miniobject) }
+			MiniObject{ miniobject: gst_mini_object_ref(self.miniobject), marker: PhantomData }
 		}
     }
 }
 
-impl ::Transfer<GstMiniObject> for MiniObject{
-    unsafe fn transfer(self) ->  *mut GstMiniObject{
+impl<T> ::Transfer<T> for MiniObject<T>{
+    unsafe fn transfer(self) ->  *mut T{
         let miniobject = self.miniobject;
 		mem::forget(self);
-        miniobject
+        miniobject as *mut T
     }
 }
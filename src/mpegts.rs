@@ -0,0 +1,374 @@
+use ffi::*;
+use util::*;
+use element::Element;
+use message::Message;
+use reference::Reference;
+use miniobject::MiniObject;
+
+use std::slice;
+use std::sync::Once;
+use std::ops::{Deref, DerefMut};
+
+static INIT: Once = Once::new();
+
+/// Registers the mpegts types, called by every function of this module
+/// that needs them
+pub fn init(){
+    INIT.call_once(|| unsafe{ gst_mpegts_initialize() });
+}
+
+/// Kind of table in a Section, mirrors GstMpegtsSectionType
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum SectionType{
+    Unknown,
+    /// Program Association Table
+    Pat,
+    /// Program Map Table
+    Pmt,
+    /// Conditional Access Table
+    Cat,
+    /// Transport Stream Description Table
+    Tsdt,
+    /// Event Information Table
+    Eit,
+    /// Network Information Table
+    Nit,
+    /// Bouquet Association Table
+    Bat,
+    /// Service Description Table
+    Sdt,
+    /// Time and Date Table
+    Tdt,
+    /// Time Offset Table
+    Tot,
+}
+
+impl SectionType{
+    fn from_gst_type(ty: GstMpegtsSectionType) -> SectionType{
+        match ty{
+            GST_MPEGTS_SECTION_PAT => SectionType::Pat,
+            GST_MPEGTS_SECTION_PMT => SectionType::Pmt,
+            GST_MPEGTS_SECTION_CAT => SectionType::Cat,
+            GST_MPEGTS_SECTION_TSDT => SectionType::Tsdt,
+            GST_MPEGTS_SECTION_EIT => SectionType::Eit,
+            GST_MPEGTS_SECTION_NIT => SectionType::Nit,
+            GST_MPEGTS_SECTION_BAT => SectionType::Bat,
+            GST_MPEGTS_SECTION_SDT => SectionType::Sdt,
+            GST_MPEGTS_SECTION_TDT => SectionType::Tdt,
+            GST_MPEGTS_SECTION_TOT => SectionType::Tot,
+            _ => SectionType::Unknown,
+        }
+    }
+}
+
+/// A program of a PAT and the pid of its PMT
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct PatProgram{
+    /// 0 for the pid of the NIT
+    pub program_number: u16,
+    pub pmt_pid: u16,
+}
+
+/// An elementary stream of a program
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct PmtStream{
+    /// eg: 0x1b for H.264, 0x0f for AAC
+    pub stream_type: u8,
+    pub pid: u16,
+}
+
+/// The streams of a program
+#[derive(Clone,Debug,PartialEq)]
+pub struct Pmt{
+    pub program_number: u16,
+    pub pcr_pid: u16,
+    pub streams: Vec<PmtStream>,
+}
+
+/// A service, usually a channel, of an SDT
+#[derive(Clone,Debug,PartialEq)]
+pub struct SdtService{
+    pub service_id: u16,
+    pub running_status: GstMpegtsRunningStatus,
+    pub free_ca_mode: bool,
+    /// From the DVB service descriptor, if present
+    pub name: Option<String>,
+    pub provider: Option<String>,
+}
+
+/// The services of a transport stream
+#[derive(Clone,Debug,PartialEq)]
+pub struct Sdt{
+    pub transport_stream_id: u16,
+    pub original_network_id: u16,
+    /// Whether it describes this transport stream or another one
+    pub actual_ts: bool,
+    pub services: Vec<SdtService>,
+}
+
+/// A table of a transport stream as posted on the bus by tsparse and
+/// tsdemux, or to inject in tsmux:
+///
+/// ```ignore
+/// match msg{
+///     gst::Message::Element(_) => if let Some(section) = gst::mpegts::Section::from_message(&msg){
+///         if let Some(pmt) = section.pmt(){
+///             for stream in pmt.streams{
+///                 println!("program {} pid {} type {:x}", pmt.program_number, stream.pid, stream.stream_type);
+///             }
+///         }
+///     },
+///     _ => {}
+/// }
+/// ```
+///
+/// tsmux writes the sections sent to it with send_event along with the
+/// tables it generates itself.
+#[derive(Clone)]
+pub struct Section{
+    section: MiniObject<GstMpegtsSection>
+}
+
+impl Section{
+    pub unsafe fn new_from_gst_section(section: *mut GstMpegtsSection) -> Option<Section>{
+        MiniObject::new(section)
+            .map(|miniobject| Section{ section: miniobject })
+    }
+
+    /// The section of an element message posted by tsparse or tsdemux,
+    /// None if it's not a section message
+    pub fn from_message(msg: &Message) -> Option<Section>{
+        init();
+        unsafe{
+            Section::new_from_gst_section(gst_message_parse_mpegts_section(msg.gst_message() as *mut GstMessage))
+        }
+    }
+
+    /// Parses a section from its raw bytes, from the table_id to the
+    /// CRC
+    pub fn from_data(pid: u16, data: &[u8]) -> Option<Section>{
+        init();
+        unsafe{
+            // the section takes ownership of the data
+            let copy = g_malloc(data.len() as gsize) as *mut u8;
+            ptr::copy_nonoverlapping(data.as_ptr(), copy, data.len());
+            Section::new_from_gst_section(gst_mpegts_section_new(pid, copy, data.len() as gsize))
+        }
+    }
+
+    /// Creates a PAT listing programs
+    pub fn from_pat(programs: &[PatProgram], ts_id: u16) -> Option<Section>{
+        init();
+        unsafe{
+            let array = gst_mpegts_pat_new();
+            for program in programs{
+                let gst_program = gst_mpegts_pat_program_new();
+                (*gst_program).program_number = program.program_number;
+                (*gst_program).network_or_program_map_PID = program.pmt_pid;
+                g_ptr_array_add(array, gst_program as gpointer);
+            }
+            Section::new_from_gst_section(gst_mpegts_section_from_pat(array, ts_id))
+        }
+    }
+
+    /// Creates a PMT to send on pid, the pid listed for the program in
+    /// the PAT
+    pub fn from_pmt(pmt: &Pmt, pid: u16) -> Option<Section>{
+        init();
+        unsafe{
+            let gst_pmt = gst_mpegts_pmt_new();
+            (*gst_pmt).program_number = pmt.program_number;
+            (*gst_pmt).pcr_pid = pmt.pcr_pid;
+            for stream in &pmt.streams{
+                let gst_stream = gst_mpegts_pmt_stream_new();
+                (*gst_stream).stream_type = stream.stream_type;
+                (*gst_stream).pid = stream.pid;
+                g_ptr_array_add((*gst_pmt).streams, gst_stream as gpointer);
+            }
+            Section::new_from_gst_section(gst_mpegts_section_from_pmt(gst_pmt, pid))
+        }
+    }
+
+    pub fn section_type(&self) -> SectionType{
+        unsafe{
+            SectionType::from_gst_type((*self.gst_section()).section_type)
+        }
+    }
+
+    pub fn pid(&self) -> u16{
+        unsafe{
+            (*self.gst_section()).pid
+        }
+    }
+
+    pub fn table_id(&self) -> u8{
+        unsafe{
+            (*self.gst_section()).table_id
+        }
+    }
+
+    /// The program number of a PMT, the transport stream id of a PAT...
+    pub fn subtable_extension(&self) -> u16{
+        unsafe{
+            (*self.gst_section()).subtable_extension
+        }
+    }
+
+    /// Changes every time the table changes
+    pub fn version_number(&self) -> u8{
+        unsafe{
+            (*self.gst_section()).version_number
+        }
+    }
+
+    pub fn crc(&self) -> u32{
+        unsafe{
+            (*self.gst_section()).crc
+        }
+    }
+
+    /// The programs of a PAT, None if it's not a PAT
+    pub fn pat(&self) -> Option<Vec<PatProgram>>{
+        unsafe{
+            let array = gst_mpegts_section_get_pat(self.gst_section() as *mut GstMpegtsSection);
+            if array == ptr::null_mut(){
+                return None;
+            }
+            let programs = ptr_array(array).iter().map(|&program| {
+                let program = program as *const GstMpegtsPatProgram;
+                PatProgram{
+                    program_number: (*program).program_number,
+                    pmt_pid: (*program).network_or_program_map_PID,
+                }
+            }).collect();
+            g_ptr_array_unref(array);
+            Some(programs)
+        }
+    }
+
+    /// The program described by a PMT, None if it's not a PMT
+    pub fn pmt(&self) -> Option<Pmt>{
+        unsafe{
+            let pmt = gst_mpegts_section_get_pmt(self.gst_section() as *mut GstMpegtsSection);
+            if pmt == ptr::null(){
+                return None;
+            }
+            Some(Pmt{
+                program_number: (*pmt).program_number,
+                pcr_pid: (*pmt).pcr_pid,
+                streams: ptr_array((*pmt).streams).iter().map(|&stream| {
+                    let stream = stream as *const GstMpegtsPMTStream;
+                    PmtStream{
+                        stream_type: (*stream).stream_type,
+                        pid: (*stream).pid,
+                    }
+                }).collect(),
+            })
+        }
+    }
+
+    /// The services described by an SDT, None if it's not an SDT
+    pub fn sdt(&self) -> Option<Sdt>{
+        unsafe{
+            let sdt = gst_mpegts_section_get_sdt(self.gst_section() as *mut GstMpegtsSection);
+            if sdt == ptr::null(){
+                return None;
+            }
+            Some(Sdt{
+                transport_stream_id: (*sdt).transport_stream_id,
+                original_network_id: (*sdt).original_network_id,
+                actual_ts: (*sdt).actual_ts != 0,
+                services: ptr_array((*sdt).services).iter().map(|&service| {
+                    let service = service as *const GstMpegtsSDTService;
+                    let (name, provider) = service_names((*service).descriptors);
+                    SdtService{
+                        service_id: (*service).service_id,
+                        running_status: (*service).running_status,
+                        free_ca_mode: (*service).free_CA_mode != 0,
+                        name: name,
+                        provider: provider,
+                    }
+                }).collect(),
+            })
+        }
+    }
+
+    /// The section as sent in the transport stream, from the table_id
+    /// to the CRC
+    pub fn data(&self) -> Vec<u8>{
+        unsafe{
+            let mut size = 0;
+            let data = gst_mpegts_section_packetize(self.gst_section() as *mut GstMpegtsSection, &mut size);
+            if data == ptr::null_mut(){
+                vec![]
+            }else{
+                // owned by the section
+                slice::from_raw_parts(data, size as usize).to_vec()
+            }
+        }
+    }
+
+    /// Sends the section to element, usually tsmux, to write it in the
+    /// stream
+    pub fn send_event(&self, element: &mut Element) -> bool{
+        unsafe{
+            gst_mpegts_section_send_event(self.gst_section() as *mut GstMpegtsSection, element.gst_element_mut()) != 0
+        }
+    }
+
+    pub unsafe fn gst_section(&self) -> *const GstMpegtsSection{
+        self.section.as_ptr()
+    }
+
+    pub unsafe fn gst_section_mut(&mut self) -> *mut GstMpegtsSection{
+        self.section.as_mut_ptr()
+    }
+}
+
+impl Reference for Section{
+    fn reference(&self) -> Section{
+        Section{ section: self.section.reference() }
+    }
+}
+
+impl Deref for Section{
+    type Target = MiniObject;
+    fn deref(&self) -> &MiniObject{
+        self.section.upcast_ref()
+    }
+}
+
+impl DerefMut for Section{
+    fn deref_mut(&mut self) -> &mut MiniObject{
+        self.section.upcast_mut()
+    }
+}
+
+unsafe fn ptr_array<'a>(array: *const GPtrArray) -> &'a [gpointer]{
+    if array == ptr::null() || (*array).len == 0{
+        &[]
+    }else{
+        slice::from_raw_parts((*array).pdata, (*array).len as usize)
+    }
+}
+
+unsafe fn service_names(descriptors: *mut GPtrArray) -> (Option<String>, Option<String>){
+    let descriptor = gst_mpegts_find_descriptor(descriptors, GST_MTS_DESC_DVB_SERVICE as guint8);
+    if descriptor == ptr::null(){
+        return (None, None);
+    }
+    let mut service_type = 0;
+    let mut name: *mut gchar = ptr::null_mut();
+    let mut provider: *mut gchar = ptr::null_mut();
+    if gst_mpegts_descriptor_parse_dvb_service(descriptor, &mut service_type, &mut name, &mut provider) == 0{
+        return (None, None);
+    }
+    let to_string = |s: *mut gchar| if s != ptr::null_mut(){
+        let string = from_c_str!(s).to_string();
+        g_free(s as gpointer);
+        Some(string)
+    }else{
+        None
+    };
+    (to_string(name), to_string(provider))
+}
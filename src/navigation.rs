@@ -0,0 +1,250 @@
+use ffi::*;
+use util::*;
+use element::Element;
+use message::Message;
+use reference::Reference;
+
+/// DVD-menu style commands, mirrors GstNavigationCommand
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum Command{
+    Invalid = GST_NAVIGATION_COMMAND_INVALID,
+    Menu1 = GST_NAVIGATION_COMMAND_MENU1,
+    Menu2 = GST_NAVIGATION_COMMAND_MENU2,
+    Menu3 = GST_NAVIGATION_COMMAND_MENU3,
+    Menu4 = GST_NAVIGATION_COMMAND_MENU4,
+    Menu5 = GST_NAVIGATION_COMMAND_MENU5,
+    Menu6 = GST_NAVIGATION_COMMAND_MENU6,
+    Menu7 = GST_NAVIGATION_COMMAND_MENU7,
+    Left = GST_NAVIGATION_COMMAND_LEFT,
+    Right = GST_NAVIGATION_COMMAND_RIGHT,
+    Up = GST_NAVIGATION_COMMAND_UP,
+    Down = GST_NAVIGATION_COMMAND_DOWN,
+    Activate = GST_NAVIGATION_COMMAND_ACTIVATE,
+    PrevAngle = GST_NAVIGATION_COMMAND_PREV_ANGLE,
+    NextAngle = GST_NAVIGATION_COMMAND_NEXT_ANGLE,
+}
+
+impl Command{
+    fn from_gst_command(command: GstNavigationCommand) -> Command{
+        match command{
+            GST_NAVIGATION_COMMAND_MENU1 => Command::Menu1,
+            GST_NAVIGATION_COMMAND_MENU2 => Command::Menu2,
+            GST_NAVIGATION_COMMAND_MENU3 => Command::Menu3,
+            GST_NAVIGATION_COMMAND_MENU4 => Command::Menu4,
+            GST_NAVIGATION_COMMAND_MENU5 => Command::Menu5,
+            GST_NAVIGATION_COMMAND_MENU6 => Command::Menu6,
+            GST_NAVIGATION_COMMAND_MENU7 => Command::Menu7,
+            GST_NAVIGATION_COMMAND_LEFT => Command::Left,
+            GST_NAVIGATION_COMMAND_RIGHT => Command::Right,
+            GST_NAVIGATION_COMMAND_UP => Command::Up,
+            GST_NAVIGATION_COMMAND_DOWN => Command::Down,
+            GST_NAVIGATION_COMMAND_ACTIVATE => Command::Activate,
+            GST_NAVIGATION_COMMAND_PREV_ANGLE => Command::PrevAngle,
+            GST_NAVIGATION_COMMAND_NEXT_ANGLE => Command::NextAngle,
+            _ => Command::Invalid,
+        }
+    }
+}
+
+/// Input travelling upstream from a sink in a navigation event. Mouse
+/// coordinates are in the coordinates of the video, the sinks translate
+/// them from the window
+#[derive(Clone,Debug,PartialEq)]
+pub enum NavigationEvent{
+    /// Key name as in X11 keysyms, eg: "Left", "Return", "a"
+    KeyPress(String),
+    KeyRelease(String),
+    MouseButtonPress{ button: i32, x: f64, y: f64 },
+    MouseButtonRelease{ button: i32, x: f64, y: f64 },
+    MouseMove{ x: f64, y: f64 },
+    Command(Command),
+}
+
+impl NavigationEvent{
+    /// Parses a navigation event, eg: received in an event probe. None
+    /// if it's not a navigation event
+    pub unsafe fn from_event(event: *mut GstEvent) -> Option<NavigationEvent>{
+        if event == ptr::null_mut() || (*event)._type != GST_EVENT_NAVIGATION{
+            return None;
+        }
+        let (mut x, mut y, mut button) = (0., 0., 0);
+        match gst_navigation_event_get_type(event){
+            ty @ GST_NAVIGATION_EVENT_KEY_PRESS | ty @ GST_NAVIGATION_EVENT_KEY_RELEASE => {
+                let mut key: *const gchar = ptr::null();
+                if gst_navigation_event_parse_key_event(event, &mut key) == 0 || key == ptr::null(){
+                    return None;
+                }
+                let key = from_c_str!(key).to_string();
+                if ty == GST_NAVIGATION_EVENT_KEY_PRESS{
+                    Some(NavigationEvent::KeyPress(key))
+                }else{
+                    Some(NavigationEvent::KeyRelease(key))
+                }
+            }
+            ty @ GST_NAVIGATION_EVENT_MOUSE_BUTTON_PRESS | ty @ GST_NAVIGATION_EVENT_MOUSE_BUTTON_RELEASE => {
+                if gst_navigation_event_parse_mouse_button_event(event, &mut button, &mut x, &mut y) == 0{
+                    return None;
+                }
+                if ty == GST_NAVIGATION_EVENT_MOUSE_BUTTON_PRESS{
+                    Some(NavigationEvent::MouseButtonPress{ button: button, x: x, y: y })
+                }else{
+                    Some(NavigationEvent::MouseButtonRelease{ button: button, x: x, y: y })
+                }
+            }
+            GST_NAVIGATION_EVENT_MOUSE_MOVE => {
+                if gst_navigation_event_parse_mouse_move_event(event, &mut x, &mut y) == 0{
+                    return None;
+                }
+                Some(NavigationEvent::MouseMove{ x: x, y: y })
+            }
+            GST_NAVIGATION_EVENT_COMMAND => {
+                let mut command = GST_NAVIGATION_COMMAND_INVALID;
+                if gst_navigation_event_parse_command(event, &mut command) == 0{
+                    return None;
+                }
+                Some(NavigationEvent::Command(Command::from_gst_command(command)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Notifications posted on the bus by elements handling navigation,
+/// eg: a DVD source telling the cursor is over a button
+#[derive(Clone,Debug,PartialEq)]
+pub enum NavigationMessage{
+    /// The mouse entered or left a clickable area
+    MouseOver(bool),
+    /// The commands available changed, eg: a menu was entered
+    CommandsChanged,
+    /// The current angle and number of angles
+    AnglesChanged{ current: u32, count: u32 },
+}
+
+impl NavigationMessage{
+    /// Parses msg, None if it's not a navigation message
+    pub fn from_message(msg: &Message) -> Option<NavigationMessage>{
+        unsafe{
+            let gst_message = msg.gst_message() as *mut GstMessage;
+            match gst_navigation_message_get_type(gst_message){
+                GST_NAVIGATION_MESSAGE_MOUSE_OVER => {
+                    let mut active = 0;
+                    if gst_navigation_message_parse_mouse_over(gst_message, &mut active) != 0{
+                        Some(NavigationMessage::MouseOver(active != 0))
+                    }else{
+                        None
+                    }
+                }
+                GST_NAVIGATION_MESSAGE_COMMANDS_CHANGED => Some(NavigationMessage::CommandsChanged),
+                GST_NAVIGATION_MESSAGE_ANGLES_CHANGED => {
+                    let (mut current, mut count) = (0, 0);
+                    if gst_navigation_message_parse_angles_changed(gst_message, &mut current, &mut count) != 0{
+                        Some(NavigationMessage::AnglesChanged{ current: current, count: count })
+                    }else{
+                        None
+                    }
+                }
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Access to the GstNavigation interface implemented by video sinks,
+/// to inject input into the pipeline as if it came from the sink's
+/// window, eg: when the application draws the video itself or receives
+/// the input remotely:
+///
+/// ```ignore
+/// let mut navigation = gst::Navigation::new(&sink).unwrap();
+/// navigation.send_mouse_button_press(1, 320., 240.);
+/// navigation.send_mouse_button_release(1, 320., 240.);
+/// navigation.send_key_press("Return");
+/// ```
+///
+/// The events travel upstream from the sink so elements like dvdspu,
+/// navseek or videobox can react to them, or can be caught with an
+/// event probe and parsed with NavigationEvent::from_event.
+pub struct Navigation{
+    element: Element
+}
+
+impl Navigation{
+    /// Returns the navigation interface of element. If the element is a
+    /// bin the first child implementing the interface is returned
+    pub fn new(element: &Element) -> Option<Navigation>{
+        unsafe{
+            let instance = element.gst_element() as *mut GTypeInstance;
+            if g_type_check_instance_is_a(instance, gst_navigation_get_type()) != 0{
+                Some(Navigation{ element: element.reference() })
+            }else if g_type_check_instance_is_a(instance, gst_bin_get_type()) != 0{
+                let element = gst_bin_get_by_interface(element.gst_element() as *mut GstBin, gst_navigation_get_type());
+                Element::new_from_gst_element(element).map(|element| Navigation{ element: element })
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn send_key_press(&mut self, key: &str){
+        self.send_key_event("key-press", key)
+    }
+
+    pub fn send_key_release(&mut self, key: &str){
+        self.send_key_event("key-release", key)
+    }
+
+    /// button starts at 1 for the left button, x and y are in window
+    /// coordinates
+    pub fn send_mouse_button_press(&mut self, button: i32, x: f64, y: f64){
+        self.send_mouse_event("mouse-button-press", button, x, y)
+    }
+
+    pub fn send_mouse_button_release(&mut self, button: i32, x: f64, y: f64){
+        self.send_mouse_event("mouse-button-release", button, x, y)
+    }
+
+    pub fn send_mouse_move(&mut self, x: f64, y: f64){
+        self.send_mouse_event("mouse-move", 0, x, y)
+    }
+
+    pub fn send_command(&mut self, command: Command){
+        unsafe{
+            gst_navigation_send_command(self.gst_navigation_mut(), command as GstNavigationCommand);
+        }
+    }
+
+    fn send_key_event(&mut self, event: &str, key: &str){
+        let cevent = CString::new(event).unwrap();
+        let ckey = CString::new(key).unwrap();
+        unsafe{
+            gst_navigation_send_key_event(self.gst_navigation_mut(), cevent.as_ptr(), ckey.as_ptr());
+        }
+    }
+
+    fn send_mouse_event(&mut self, event: &str, button: i32, x: f64, y: f64){
+        let cevent = CString::new(event).unwrap();
+        unsafe{
+            gst_navigation_send_mouse_event(self.gst_navigation_mut(), cevent.as_ptr(), button, x, y);
+        }
+    }
+
+    pub fn element(&self) -> &Element{
+        &self.element
+    }
+
+    pub unsafe fn gst_navigation(&self) -> *const GstNavigation{
+        self.element.gst_element() as *const GstNavigation
+    }
+
+    pub unsafe fn gst_navigation_mut(&mut self) -> *mut GstNavigation{
+        self.element.gst_element_mut() as *mut GstNavigation
+    }
+}
+
+impl Reference for Navigation{
+    fn reference(&self) -> Navigation{
+        Navigation{ element: self.element.reference() }
+    }
+}
@@ -0,0 +1,317 @@
+use ffi::*;
+use util::*;
+
+use clock::Clock;
+use object::Object;
+use reference::Reference;
+use version::ApiAvailability;
+
+use std::ops::{Deref, DerefMut};
+
+const NTP_CLOCK: ApiAvailability = ApiAvailability::new("NtpClock", 1, 6, 0);
+const PTP: ApiAvailability = ApiAvailability::new("PtpClock", 1, 6, 0);
+
+/// A clock that follows the clock of a NetTimeProvider running on
+/// another device, so pipelines on several devices can render in sync,
+/// eg: the screens of a video wall.
+///
+/// The server exposes the clock of its pipeline and distributes the
+/// base time, by whatever means the application already has:
+///
+/// ```ignore
+/// let clock = pipeline.clock().unwrap();
+/// let provider = gst::net::NetTimeProvider::new(&clock, None, 5637).unwrap();
+/// let base_time = clock.time();
+/// pipeline.use_clock(&clock);
+/// pipeline.set_start_time(None);
+/// pipeline.set_base_time(base_time);
+/// pipeline.play();
+/// // send base_time to the clients
+/// ```
+///
+/// Each client slaves its pipeline to the server's clock and uses the
+/// same base time:
+///
+/// ```ignore
+/// let clock = gst::net::NetClientClock::new("net-clock", "192.168.1.10", 5637, 0).unwrap();
+/// clock.wait_for_sync(Some(5 * 1_000_000_000));
+/// pipeline.use_clock(&clock);
+/// pipeline.set_start_time(None);
+/// pipeline.set_base_time(base_time);
+/// pipeline.set("latency", 500_000_000u64);
+/// pipeline.play();
+/// ```
+pub struct NetClientClock{
+    clock: Clock
+}
+
+impl NetClientClock{
+    /// Creates a clock synchronized to the NetTimeProvider at
+    /// address:port. base_time is the initial time of the clock until
+    /// the first synchronization
+    pub fn new(name: &str, address: &str, port: u16, base_time: u64) -> Option<NetClientClock>{
+        let cname = CString::new(name).unwrap();
+        let caddress = CString::new(address).unwrap();
+        unsafe{
+            Clock::new(gst_net_client_clock_new(cname.as_ptr(), caddress.as_ptr(), port as gint, base_time))
+                .map(|clock| NetClientClock{ clock: clock })
+        }
+    }
+
+    pub fn address(&self) -> Option<String>{
+        self.get_string("address")
+    }
+
+    pub fn port(&self) -> u16{
+        self.get::<i32>("port") as u16
+    }
+
+    /// Maximum round trip in nanoseconds of the observations used to
+    /// correct the clock, slower replies are discarded
+    pub fn round_trip_limit(&self) -> u64{
+        self.get::<u64>("round-trip-limit")
+    }
+
+    pub fn set_round_trip_limit(&mut self, limit: u64){
+        self.set("round-trip-limit", limit);
+    }
+}
+
+impl Reference for NetClientClock{
+    fn reference(&self) -> NetClientClock{
+        NetClientClock{ clock: self.clock.reference() }
+    }
+}
+
+impl From<NetClientClock> for Clock{
+    fn from(c: NetClientClock) -> Clock{
+        c.clock
+    }
+}
+
+impl Deref for NetClientClock{
+    type Target = Clock;
+    fn deref(&self) -> &Clock{
+        &self.clock
+    }
+}
+
+impl DerefMut for NetClientClock{
+    fn deref_mut(&mut self) -> &mut Clock{
+        &mut self.clock
+    }
+}
+
+/// A clock synchronized to an NTPv4 server, for devices that can't
+/// reach a NetTimeProvider but share an NTP server
+pub struct NtpClock{
+    clock: NetClientClock
+}
+
+impl NtpClock{
+    /// Needs GStreamer 1.6, returns None with older versions
+    pub fn new(name: &str, address: &str, port: u16, base_time: u64) -> Option<NtpClock>{
+        let cname = CString::new(name).unwrap();
+        let caddress = CString::new(address).unwrap();
+        unsafe{
+            let ntp_clock_new: unsafe extern "C" fn(*const gchar, *const gchar, gint, GstClockTime) -> *mut GstClock
+                = NTP_CLOCK.function("gst_ntp_clock_new").ok()?;
+            Clock::new(ntp_clock_new(cname.as_ptr(), caddress.as_ptr(), port as gint, base_time))
+                .map(|clock| NtpClock{ clock: NetClientClock{ clock: clock } })
+        }
+    }
+}
+
+impl Reference for NtpClock{
+    fn reference(&self) -> NtpClock{
+        NtpClock{ clock: self.clock.reference() }
+    }
+}
+
+impl From<NtpClock> for Clock{
+    fn from(c: NtpClock) -> Clock{
+        c.clock.clock
+    }
+}
+
+impl Deref for NtpClock{
+    type Target = NetClientClock;
+    fn deref(&self) -> &NetClientClock{
+        &self.clock
+    }
+}
+
+impl DerefMut for NtpClock{
+    fn deref_mut(&mut self) -> &mut NetClientClock{
+        &mut self.clock
+    }
+}
+
+/// Whether PTP is supported on this platform, always false before
+/// GStreamer 1.6
+pub fn ptp_is_supported() -> bool{
+    unsafe{
+        match PTP.function::<unsafe extern "C" fn() -> gboolean>("gst_ptp_is_supported"){
+            Ok(is_supported) => is_supported() != 0,
+            Err(_) => false,
+        }
+    }
+}
+
+pub fn ptp_is_initialized() -> bool{
+    unsafe{
+        match PTP.function::<unsafe extern "C" fn() -> gboolean>("gst_ptp_is_initialized"){
+            Ok(is_initialized) => is_initialized() != 0,
+            Err(_) => false,
+        }
+    }
+}
+
+/// Starts the PTP helper process, has to be called before creating a
+/// PtpClock. clock_id None derives it from a MAC address, interfaces
+/// empty listens on all of them. Needs GStreamer 1.6, returns false
+/// with older versions
+pub fn ptp_init(clock_id: Option<u64>, interfaces: &[&str]) -> bool{
+    let cinterfaces: Vec<CString> = interfaces.iter().map(|i| CString::new(*i).unwrap()).collect();
+    let mut pinterfaces: Vec<*mut gchar> = cinterfaces.iter().map(|i| i.as_ptr() as *mut gchar).collect();
+    pinterfaces.push(ptr::null_mut());
+    unsafe{
+        let init: unsafe extern "C" fn(guint64, *mut *mut gchar) -> gboolean = match PTP.function("gst_ptp_init"){
+            Ok(init) => init,
+            Err(_) => return false,
+        };
+        let interfaces = if interfaces.is_empty(){
+            ptr::null_mut()
+        }else{
+            pinterfaces.as_mut_ptr()
+        };
+        init(clock_id.unwrap_or(GST_PTP_CLOCK_ID_NONE), interfaces) != 0
+    }
+}
+
+/// Stops the PTP helper process, clocks already created stop being
+/// synchronized
+pub fn ptp_deinit(){
+    unsafe{
+        if let Ok(deinit) = PTP.function::<unsafe extern "C" fn()>("gst_ptp_deinit"){
+            deinit();
+        }
+    }
+}
+
+/// A clock following the grandmaster of a PTP (IEEE 1588) domain, the
+/// usual choice in professional AV networks like AES67 or SMPTE 2110.
+/// ptp_init has to be called first
+pub struct PtpClock{
+    clock: Clock
+}
+
+impl PtpClock{
+    /// Needs GStreamer 1.6, returns None with older versions
+    pub fn new(name: &str, domain: u32) -> Option<PtpClock>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let ptp_clock_new: unsafe extern "C" fn(*const gchar, guint) -> *mut GstClock
+                = PTP.function("gst_ptp_clock_new").ok()?;
+            Clock::new(ptp_clock_new(cname.as_ptr(), domain))
+                .map(|clock| PtpClock{ clock: clock })
+        }
+    }
+
+    pub fn domain(&self) -> u32{
+        self.get::<u32>("domain")
+    }
+
+    /// Id of the current grandmaster, 0 until one is selected
+    pub fn grandmaster_clock_id(&self) -> u64{
+        self.get::<u64>("grandmaster-clock-id")
+    }
+
+    /// Id of the clock the local one synchronizes to, 0 until one is
+    /// selected
+    pub fn master_clock_id(&self) -> u64{
+        self.get::<u64>("master-clock-id")
+    }
+}
+
+impl Reference for PtpClock{
+    fn reference(&self) -> PtpClock{
+        PtpClock{ clock: self.clock.reference() }
+    }
+}
+
+impl From<PtpClock> for Clock{
+    fn from(c: PtpClock) -> Clock{
+        c.clock
+    }
+}
+
+impl Deref for PtpClock{
+    type Target = Clock;
+    fn deref(&self) -> &Clock{
+        &self.clock
+    }
+}
+
+impl DerefMut for PtpClock{
+    fn deref_mut(&mut self) -> &mut Clock{
+        &mut self.clock
+    }
+}
+
+/// Exposes a clock on the network so NetClientClocks on other devices
+/// can follow it. The clock keeps being exposed while the provider is
+/// alive
+pub struct NetTimeProvider{
+    provider: Object<GstNetTimeProvider>
+}
+
+impl NetTimeProvider{
+    /// Listens on address, all the interfaces if None, and port, a
+    /// random free one if 0
+    pub fn new(clock: &Clock, address: Option<&str>, port: u16) -> Option<NetTimeProvider>{
+        let caddress = address.map(|address| CString::new(address).unwrap());
+        unsafe{
+            let provider = gst_net_time_provider_new(clock.gst_clock() as *mut GstClock,
+                caddress.as_ref().map(|address| address.as_ptr()).unwrap_or(ptr::null()), port as gint);
+            Object::new(provider).map(|obj| NetTimeProvider{ provider: obj })
+        }
+    }
+
+    /// The port the provider listens on, useful when created with 0
+    pub fn port(&self) -> u16{
+        self.get::<i32>("port") as u16
+    }
+
+    pub fn is_active(&self) -> bool{
+        self.get::<gboolean>("active") != 0
+    }
+
+    /// Stops or resumes answering clients without closing the socket
+    pub fn set_active(&mut self, active: bool){
+        self.set("active", active as gboolean);
+    }
+
+    pub unsafe fn gst_net_time_provider(&self) -> *const GstNetTimeProvider{
+        self.provider.as_ptr()
+    }
+}
+
+impl Reference for NetTimeProvider{
+    fn reference(&self) -> NetTimeProvider{
+        NetTimeProvider{ provider: self.provider.reference() }
+    }
+}
+
+impl Deref for NetTimeProvider{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.provider.upcast_ref()
+    }
+}
+
+impl DerefMut for NetTimeProvider{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.provider.upcast_mut()
+    }
+}
@@ -1,15 +1,34 @@
 use ffi::*;
 use util::*;
-use reference::{Reference, Ref};
+use reference::{Reference, Ref, Downgrade};
+use handle::SignalHandle;
+use panic::catch_panic;
+use value::{Value, ToValue, G_TYPE_ENUM, G_TYPE_NONE, G_SIGNAL_TYPE_STATIC_SCOPE};
+use ::Transfer;
 
 use std::os::raw::{c_void, c_char};
-
-pub struct Object{
+use std::slice;
+use std::sync::Mutex;
+use std::marker::PhantomData;
+
+/// A reference to a GstObject, T is the C struct it points to, eg:
+/// Object<GstElement> in Element. Object without a parameter is the
+/// untyped base all the wrappers deref to.
+#[repr(C)]
+pub struct Object<T = GstObject>{
     object: *mut GstObject,
+    marker: PhantomData<T>,
 }
 
+// GObject reference counting is atomic and GstObject protects its state
+// with the object lock so objects can be shared across threads. Types
+// wrapping an Object inherit these unless they hold something that isn't
+// thread safe themselves.
+unsafe impl<T> Send for Object<T> {}
+unsafe impl<T> Sync for Object<T> {}
+
 
-impl Drop for Object{
+impl<T> Drop for Object<T>{
 	fn drop(&mut self){
 		unsafe{
 			gst_object_unref(self.object as *mut c_void);
@@ -17,15 +36,45 @@ impl Drop for Object{
 	}
 }
 
-impl Object{
-    pub unsafe fn new(object: *mut GstObject) -> Option<Object>{
+impl<T> Object<T>{
+    /// Takes ownership of the passed reference, None if it's null
+    pub unsafe fn new(object: *mut T) -> Option<Object<T>>{
         if object != ptr::null_mut(){
-            Some(Object{ object: object })
+            Some(Object{ object: object as *mut GstObject, marker: PhantomData })
         }else{
             None
         }
     }
 
+    pub unsafe fn as_ptr(&self) -> *const T{
+        self.object as *const T
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut T{
+        self.object as *mut T
+    }
+
+    /// The untyped object, keeping the reference
+    pub fn upcast(self) -> Object{
+        let object = self.object;
+        mem::forget(self);
+        Object{ object: object, marker: PhantomData }
+    }
+
+    pub fn upcast_ref(&self) -> &Object{
+        // same layout, only the marker differs
+        unsafe{ &*(self as *const Object<T> as *const Object) }
+    }
+
+    pub fn upcast_mut(&mut self) -> &mut Object{
+        unsafe{ &mut *(self as *mut Object<T> as *mut Object) }
+    }
+}
+
+impl Object{
+
+    /// Renames the object, fails if it already has a parent since names
+    /// have to be unique among siblings
     pub fn set_name(&mut self, name: &str) -> bool{
         let cname = CString::new(name).unwrap();
         unsafe{
@@ -33,18 +82,23 @@ impl Object{
         }
     }
 
-    /// Returns the name of the object
+    /// Returns the name of the object, available on elements, bins,
+    /// pipelines and pads too
     pub fn name(&self) -> String{
         unsafe{
             let c_str_name = gst_object_get_name(self.object);
-            from_c_str!(c_str_name).to_string()
+            if c_str_name == ptr::null_mut(){
+                return String::new();
+            }
+            let name = from_c_str!(c_str_name).to_string();
+            g_free(c_str_name as gpointer);
+            name
         }
     }
 
     pub fn flags(&self) -> u32{
         unsafe{
-            let object: &mut GstObject = mem::transmute(self.object);
-            object.flags
+            (*self.object).flags
         }
     }
 
@@ -54,31 +108,27 @@ impl Object{
 
     pub fn set_flag(&mut self, flag: u32){
         unsafe{
-            let object: &mut GstObject = mem::transmute(self.object);
-            object.flags |= flag
+            (*self.object).flags |= flag
         }
     }
 
     pub fn unset_flag(&mut self, flag: u32){
         unsafe{
-            let object: &mut GstObject = mem::transmute(self.object);
-            object.flags |= !flag
+            (*self.object).flags &= !flag
         }
     }
 
     pub fn refcount(&self) -> usize{
         unsafe{
-            let object: &mut GObject = mem::transmute(self.object);
-            object.ref_count as usize
+            (*(self.object as *mut GObject)).ref_count as usize
         }
     }
 
     pub fn lock<F: FnMut(&mut Object)>(&mut self, mut f: F){
         unsafe{
-            let object: &mut GstObject = mem::transmute(self.object);
-            g_mutex_lock(&mut object.lock);
+            g_mutex_lock(&mut (*self.object).lock);
             f(self);
-            g_mutex_unlock(&mut object.lock);
+            g_mutex_unlock(&mut (*self.object).lock);
         }
     }
 
@@ -94,12 +144,14 @@ impl Object{
         }
     }
 
+    /// The bin containing an element or the element owning a pad, None
+    /// for top level objects
     pub fn parent(&self) -> Option<Ref<Object>>{
         let parent = unsafe{ gst_object_get_parent(self.object) };
         if parent == ptr::null_mut(){
             None
         }else{
-            Some(Ref::new(&Object{ object: parent }))
+            Some(Ref::new(&Object{ object: parent, marker: PhantomData }))
         }
     }
 
@@ -109,15 +161,29 @@ impl Object{
         }
     }
 
-    pub fn has_as_ancestor(&self, ancestor: &Object) -> bool{
+    /// Whether ancestor is this object or any of its parents, eg: to
+    /// check that an element posting a message is inside a bin
+    pub fn has_ancestor(&self, ancestor: &Object) -> bool{
         unsafe{
             gst_object_has_ancestor(self.object, ancestor.object) != 0
         }
     }
 
-    pub fn path_string(&self) -> &str{
+    pub fn has_as_ancestor(&self, ancestor: &Object) -> bool{
+        self.has_ancestor(ancestor)
+    }
+
+    /// The names of the object and all its parents, eg:
+    /// "/GstPipeline:pipeline0/GstBin:bin0/GstFileSrc:src.GstPad:src"
+    pub fn path_string(&self) -> String{
         unsafe{
-            from_c_str!(gst_object_get_path_string(self.object))
+            let c_path = gst_object_get_path_string(self.object);
+            if c_path == ptr::null_mut(){
+                return String::new();
+            }
+            let path = from_c_str!(c_path).to_string();
+            g_free(c_path as gpointer);
+            path
         }
     }
 
@@ -153,6 +219,12 @@ impl Object{
         }
     }
 
+    /// The properties that can be driven by a control binding, with
+    /// their ranges
+    pub fn controllable_properties(&self) -> Vec<::controller::ControllableProperty>{
+        ::controller::ControllableProperty::list(self)
+    }
+
     pub fn set<T>(&mut self, name: &str, value: T)
     	where T: Property {
         value.set_to(name, self)
@@ -168,10 +240,251 @@ impl Object{
         }
     }
 
+    /// Returns the value of a string property or None if it's not set
+    pub fn get_string(&self, name: &str) -> Option<String>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let mut value: *mut gchar = ptr::null_mut();
+            g_object_get(self.object as *mut c_void, cname.as_ptr(), &mut value, ptr::null::<gchar>());
+            if value != ptr::null_mut(){
+                let ret = from_c_str!(value).to_string();
+                g_free(value as gpointer);
+                Some(ret)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Sets a property of any type supported by Value, eg: a buffer or a
+    /// structure. The value is converted to the type of the property when
+    /// possible, returns false if the property doesn't exist or the value
+    /// can't be converted
+    pub fn set_value(&mut self, name: &str, value: &Value) -> bool{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let class = (*(self.object as *mut GTypeInstance)).g_class as *mut GObjectClass;
+            let pspec = g_object_class_find_property(class, cname.as_ptr());
+            if pspec == ptr::null_mut(){
+                return false;
+            }
+            match value.convert((*pspec).value_type){
+                Some(value) => {
+                    g_object_set_property(self.object as *mut GObject, cname.as_ptr(), value.gst_value());
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    /// Returns the value of a property of any type or None if the
+    /// property doesn't exist
+    pub fn get_value(&self, name: &str) -> Option<Value>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let class = (*(self.object as *mut GTypeInstance)).g_class as *mut GObjectClass;
+            let pspec = g_object_class_find_property(class, cname.as_ptr());
+            if pspec == ptr::null_mut(){
+                return None;
+            }
+            let mut value = Value::new((*pspec).value_type);
+            g_object_get_property(self.object as *mut GObject, cname.as_ptr(), value.gst_value_mut());
+            Some(value)
+        }
+    }
+
+    /// Sets a property from its string representation, enums and flags
+    /// can be set using their nicks, eg: set_from_str("io-mode", "dmabuf")
+    pub fn set_from_str(&mut self, name: &str, value: &str){
+        let cname = CString::new(name).unwrap();
+        let cvalue = CString::new(value).unwrap();
+        unsafe{
+            gst_util_set_object_arg(self.gst_object_mut() as *mut GObject, cname.as_ptr(), cvalue.as_ptr());
+        }
+    }
+
+    /// Returns the nick of the current value of an enum property or None
+    /// if the property doesn't exist or is not an enum
+    pub fn get_enum_nick(&self, name: &str) -> Option<String>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let class = (*(self.object as *mut GTypeInstance)).g_class as *mut GObjectClass;
+            let pspec = g_object_class_find_property(class, cname.as_ptr());
+            if pspec == ptr::null_mut() || g_type_fundamental((*pspec).value_type) != G_TYPE_ENUM{
+                return None;
+            }
+            let mut value: gint = 0;
+            g_object_get(self.object as *mut c_void, cname.as_ptr(), &mut value, ptr::null::<gchar>());
+            let enum_class = g_type_class_ref((*pspec).value_type) as *mut GEnumClass;
+            let enum_value = g_enum_get_value(enum_class, value);
+            let nick = if enum_value != ptr::null_mut(){
+                Some(from_c_str!((*enum_value).value_nick).to_string())
+            }else{
+                None
+            };
+            g_type_class_unref(enum_class as gpointer);
+            nick
+        }
+    }
+
+    /// Connects callback to signal passing data as its last argument,
+    /// data has to outlive the object. Prefer connect_owned which disconnects
+    /// the handler once the returned handle goes out of scope
     pub unsafe fn signal_connect<T>(&mut self, signal: &str, callback: GCallback, data: &mut T)
         where Self:Sized{
         let csignal = CString::new(signal).unwrap();
-        g_signal_connect_data(self.gst_object() as *mut c_void, csignal.as_ptr(), callback, mem::transmute(data), None, 0);
+        g_signal_connect_data(self.gst_object() as *mut c_void, csignal.as_ptr(), callback, data as *mut T as gpointer, None, 0);
+    }
+
+    /// Connects callback to signal passing data as its last argument.
+    /// data is owned by the handler and dropped once it's disconnected,
+    /// when the returned handle is dropped, or the object is destroyed,
+    /// so the handle can be forgotten to keep the handler connected for
+    /// the object's lifetime. Unsafe since the signature of callback has
+    /// to match the signal's
+    pub unsafe fn connect_owned<T: Send + 'static>(&mut self, signal: &str, callback: GCallback, data: T) -> Option<SignalHandle>{
+        let csignal = CString::new(signal).unwrap();
+        let data = Box::into_raw(Box::new(data));
+        let id = g_signal_connect_data(self.gst_object() as *mut c_void, csignal.as_ptr(), callback, data as gpointer, Some(drop_signal_data::<T>), 0);
+        if id != 0{
+            Some(SignalHandle::new(self.gst_object_mut() as gpointer, id))
+        }else{
+            None
+        }
+    }
+
+    /// Emits a signal that has no arguments nor return value, mostly
+    /// useful for action signals like camerabin's "start-capture"
+    pub fn emit(&mut self, signal: &str){
+        let csignal = CString::new(signal).unwrap();
+        unsafe{
+            g_signal_emit_by_name(self.gst_object() as *mut c_void, csignal.as_ptr());
+        }
+    }
+
+    /// Emits signal with args, returning the value returned by the
+    /// handlers if the signal has a return value. Useful for action
+    /// signals that take arguments or return objects, eg: rtpbin's
+    /// "get-session". The arguments are converted to the types the
+    /// signal expects when possible, None is returned if the signal
+    /// doesn't exist or the arguments don't match.
+    ///
+    /// ```ignore
+    /// let session = rtpbin.emit_with_values("get-session", &[0u32.into()])
+    ///     .and_then(|value| value.get::<gst::Object>());
+    /// ```
+    pub fn emit_with_values(&mut self, signal: &str, args: &[Value]) -> Option<Value>{
+        let csignal = CString::new(signal).unwrap();
+        unsafe{
+            let gtype = (*(*(self.object as *mut GTypeInstance)).g_class).g_type;
+            let mut signal_id = 0;
+            let mut detail = 0;
+            if g_signal_parse_name(csignal.as_ptr(), gtype, &mut signal_id, &mut detail, 1) == 0{
+                return None;
+            }
+            let mut query = mem::zeroed();
+            g_signal_query(signal_id, &mut query);
+            if query.n_params as usize != args.len(){
+                return None;
+            }
+            // GLib leaves param_types null for signals without parameters
+            let param_types = if args.is_empty(){
+                &[]
+            }else{
+                slice::from_raw_parts(query.param_types, args.len())
+            };
+            let mut params = vec![self.to_value()];
+            for (arg, param_type) in args.iter().zip(param_types){
+                match arg.convert(*param_type){
+                    Some(param) => params.push(param),
+                    None => return None,
+                }
+            }
+            // Value has the same layout as GValue
+            let params = params.as_ptr() as *const GValue;
+            if query.return_type & !G_SIGNAL_TYPE_STATIC_SCOPE != G_TYPE_NONE{
+                let mut ret = Value::new(query.return_type);
+                g_signal_emitv(params, signal_id, detail, ret.gst_value_mut());
+                Some(ret)
+            }else{
+                g_signal_emitv(params, signal_id, detail, ptr::null_mut());
+                None
+            }
+        }
+    }
+
+    /// Connects handler to signal, it receives the arguments of the signal,
+    /// the first one being the object that emitted it, and returns the
+    /// value for signals that expect one. The returned value is converted
+    /// to the signal's return type, so enums can be returned as integers
+    /// or using Value::from_enum_nick. Returning None leaves the default
+    /// value.
+    ///
+    /// ```ignore
+    /// decodebin.connect_values("autoplug-select", |args| {
+    ///     let factory = args[3].get::<gst::Object>().unwrap();
+    ///     if factory.name() == "vaapidecodebin"{
+    ///         gst::Value::from_enum_nick("GstAutoplugSelectResult", "skip")
+    ///     }else{
+    ///         gst::Value::from_enum_nick("GstAutoplugSelectResult", "try")
+    ///     }
+    /// }).unwrap().forget();
+    /// ```
+    ///
+    /// Signals like "pad-added" or "handoff" can be emitted from several
+    /// streaming threads at once, the handler is called from one of them
+    /// at a time so it mustn't emit the same signal itself.
+    pub fn connect_values<F>(&mut self, signal: &str, handler: F) -> Option<SignalHandle>
+        where F: FnMut(&[Value]) -> Option<Value> + Send + 'static{
+        let csignal = CString::new(signal).unwrap();
+        unsafe{
+            let handler: SignalHandler<F> = Mutex::new(Guarded::new(Box::new(handler)));
+            let data = Box::into_raw(Box::new(handler)) as gpointer;
+            let closure = g_closure_new_simple(mem::size_of::<GClosure>() as guint, ptr::null_mut());
+            g_closure_add_finalize_notifier(closure, data, Some(drop_signal_data::<SignalHandler<F>>));
+            // GClosure's data field isn't accessible from the bindings, the
+            // meta marshal gets the handler as marshal_data instead
+            g_closure_set_meta_marshal(closure, data, Some(marshal_values::<F>));
+            let id = g_signal_connect_closure(self.gst_object() as *mut c_void, csignal.as_ptr(), closure, 0);
+            if id != 0{
+                Some(SignalHandle::new(self.gst_object_mut() as gpointer, id))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Calls f with the object and the name of the property every time
+    /// property changes, eg: "caps" on a pad or "volume" on playbin. An
+    /// empty property name notifies the changes of every property.
+    ///
+    /// ```ignore
+    /// playbin.connect_notify("volume", |playbin, _property| {
+    ///     println!("volume changed to {}", playbin.get::<f64>("volume"));
+    /// }).unwrap().forget();
+    /// ```
+    ///
+    /// f is called from the thread that changed the property, often a
+    /// streaming thread for pad caps.
+    pub fn connect_notify<F>(&mut self, property: &str, mut f: F) -> Option<SignalHandle>
+        where F: FnMut(&Object, &str) + Send + 'static{
+        let signal = if property.is_empty(){
+            "notify".to_string()
+        }else{
+            format!("notify::{}", property)
+        };
+        self.connect_values(&signal, move |args| {
+            if let (Some(object), Some(pspec)) = (args.get(0).and_then(|arg| arg.get::<Object>()), args.get(1)){
+                unsafe{
+                    let pspec = g_value_get_param(pspec.gst_value());
+                    if pspec != ptr::null_mut(){
+                        f(&object, from_c_str!((*pspec).name));
+                    }
+                }
+            }
+            None
+        })
     }
 
     pub unsafe fn gst_object(&self) -> *const GstObject{
@@ -183,18 +496,28 @@ impl Object{
     }
 }
 
-impl Reference for Object{
-    fn reference(&self) -> Object{
+impl<T> Reference for Object<T>{
+    fn reference(&self) -> Object<T>{
         unsafe{ gst_object_ref(self.object as *mut c_void) };
-        Object{ object: self.object }
+        Object{ object: self.object, marker: PhantomData }
     }
 }
 
-impl ::Transfer<GstObject> for Object{
-    unsafe fn transfer(self) -> *mut GstObject{
+impl Downgrade for Object{
+    unsafe fn gst_object_ptr(&self) -> *mut GstObject{
+        self.gst_object() as *mut GstObject
+    }
+
+    unsafe fn from_gst_object_ptr(object: *mut GstObject) -> Option<Object>{
+        Object::new(object)
+    }
+}
+
+impl<T> ::Transfer<T> for Object<T>{
+    unsafe fn transfer(self) -> *mut T{
         let object = self.object;
         mem::forget(self);
-        object
+        object as *mut T
     }
 }
 
@@ -259,3 +582,36 @@ impl RawProperty for u64{}
 impl RawProperty for f32{}
 impl RawProperty for f64{}
 impl RawProperty for bool{}
+
+// called from every thread emitting the signal
+type SignalHandler<F> = Mutex<Guarded<F>>;
+
+extern "C" fn marshal_values<F>(_closure: *mut GClosure, return_value: *mut GValue, n_param_values: guint,
+    param_values: *const GValue, _invocation_hint: gpointer, marshal_data: gpointer)
+    where F: FnMut(&[Value]) -> Option<Value> + Send + 'static{
+    unsafe{
+        let handler = &*(marshal_data as *const SignalHandler<F>);
+        // borrowed from the emission, Value has the same layout as GValue
+        let args = slice::from_raw_parts(param_values as *const Value, n_param_values as usize);
+        let element = || match args.first().and_then(|instance| instance.get::<::Element>()){
+            Some(element) => element.transfer(),
+            None => ptr::null_mut(),
+        };
+        let mut handler = handler.lock().unwrap_or_else(|e| e.into_inner());
+        let ret = handler.call_with(element, None, |handler| handler(args));
+        drop(handler);
+        if let Some(ret) = ret{
+            if return_value != ptr::null_mut() && (*return_value).g_type != 0{
+                if let Some(ret) = ret.convert((*return_value).g_type){
+                    g_value_copy(ret.gst_value(), return_value);
+                }
+            }
+        }
+    }
+}
+
+extern "C" fn drop_signal_data<T>(data: gpointer, _closure: *mut GClosure){
+    unsafe{
+        catch_panic(ptr::null_mut(), (), || drop(Box::from_raw(data as *mut T)));
+    }
+}
@@ -1,14 +1,24 @@
 use ffi::*;
 use caps::Caps;
+use buffer::Buffer;
+use buffer_list::BufferList;
+use event::Event;
+use query::Query;
+use taglist::TagList;
+use toc::Toc;
 use reference::Reference;
 use object::Object;
+use handle::ProbeHandle;
+use panic::catch_panic;
+use util::Guarded;
+use ::Transfer;
 
 use std::ptr;
-use std::mem;
 use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
 
 pub struct Pad{
-    pad: Object
+    pad: Object<GstPad>
 }
 
 #[derive(Debug)]
@@ -22,9 +32,150 @@ pub enum LinkReturn{
     Refused = GST_PAD_LINK_REFUSED as isize,
 }
 
+/// What a probe callback found in the pad, see Pad::add_probe. The data
+/// can be modified or replaced through the accessors but not taken out,
+/// the pad gets it back once the callback returns
+pub struct PadProbeInfo<'a>{
+    data: PadProbeData<'a>,
+}
+
+enum PadProbeData<'a>{
+    Buffer(Buffer),
+    BufferList(BufferList),
+    Event(Event),
+    Query(Query<'a>),
+    Idle,
+    Block,
+}
+
+impl<'a> PadProbeInfo<'a>{
+    /// Returns the buffer if this is a buffer probe
+    pub fn buffer(&self) -> Option<&Buffer>{
+        match self.data{
+            PadProbeData::Buffer(ref buffer) => Some(buffer),
+            _ => None
+        }
+    }
+
+    /// Returns the buffer if this is a buffer probe making it writable
+    /// first, which will copy it if someone else holds a reference.
+    pub fn buffer_mut(&mut self) -> Option<&mut Buffer>{
+        match self.data{
+            PadProbeData::Buffer(ref mut buffer) => Some(buffer.make_mut()),
+            _ => None
+        }
+    }
+
+    /// Returns the buffer list if this is a buffer list probe
+    pub fn buffer_list(&self) -> Option<&BufferList>{
+        match self.data{
+            PadProbeData::BufferList(ref list) => Some(list),
+            _ => None
+        }
+    }
+
+    /// Returns the buffer list if this is a buffer list probe making it
+    /// writable first, which will copy it if someone else holds a
+    /// reference.
+    pub fn buffer_list_mut(&mut self) -> Option<&mut BufferList>{
+        match self.data{
+            PadProbeData::BufferList(ref mut list) => Some(list.make_mut()),
+            _ => None
+        }
+    }
+
+    /// Returns the event if this is an event probe
+    pub fn event(&self) -> Option<&Event>{
+        match self.data{
+            PadProbeData::Event(ref event) => Some(event),
+            _ => None
+        }
+    }
+
+    /// Returns the event if this is an event probe so it can be replaced
+    pub fn event_mut(&mut self) -> Option<&mut Event>{
+        match self.data{
+            PadProbeData::Event(ref mut event) => Some(event),
+            _ => None
+        }
+    }
+
+    /// Returns the query if this is a query probe
+    pub fn query(&self) -> Option<&Query<'a>>{
+        match self.data{
+            PadProbeData::Query(ref query) => Some(query),
+            _ => None
+        }
+    }
+
+    /// Returns the query if this is a query probe so it can be answered
+    pub fn query_mut(&mut self) -> Option<&mut Query<'a>>{
+        match self.data{
+            PadProbeData::Query(ref mut query) => Some(query),
+            _ => None
+        }
+    }
+
+    pub fn is_buffer(&self) -> bool{
+        self.buffer().is_some()
+    }
+
+    pub fn is_buffer_list(&self) -> bool{
+        self.buffer_list().is_some()
+    }
+
+    pub fn is_event(&self) -> bool{
+        self.event().is_some()
+    }
+
+    pub fn is_query(&self) -> bool{
+        self.query().is_some()
+    }
+
+    /// The pad is idle, no data is flowing through it
+    pub fn is_idle(&self) -> bool{
+        match self.data{
+            PadProbeData::Idle => true,
+            _ => false
+        }
+    }
+
+    /// The pad is blocked, no data will flow until the probe is removed
+    pub fn is_blocked(&self) -> bool{
+        match self.data{
+            PadProbeData::Block => true,
+            _ => false
+        }
+    }
+}
+
+/// What to do with the data after a probe callback, mirrors GstPadProbeReturn
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum PadProbeReturn{
+    /// Drop the data, for blocking probes the pad stays blocked
+    Drop = GST_PAD_PROBE_DROP,
+    /// Let the data pass, for blocking probes the pad stays blocked
+    Ok = GST_PAD_PROBE_OK,
+    /// Remove the probe and let the data pass
+    Remove = GST_PAD_PROBE_REMOVE,
+    /// Let the data pass even if it's a blocking probe
+    Pass = GST_PAD_PROBE_PASS,
+}
+
+pub trait PadProbe: Send{
+    fn call(&mut self, pad: &mut Pad, info: &mut PadProbeInfo) -> PadProbeReturn;
+}
+
+impl<F: FnMut(&mut Pad, &mut PadProbeInfo) -> PadProbeReturn + Send> PadProbe for F{
+    fn call(&mut self, pad: &mut Pad, info: &mut PadProbeInfo) -> PadProbeReturn{
+        self(pad, info)
+    }
+}
+
 impl Pad{
     pub unsafe fn new(pad: *mut GstPad) -> Option<Pad>{
-		Object::new(pad as *mut GstObject).map(|obj| Pad{ pad: obj })
+		Object::new(pad).map(|obj| Pad{ pad: obj })
     }
 
     pub fn link(&mut self, sink: &mut Pad) -> Result<(), LinkReturn>{
@@ -33,15 +184,59 @@ impl Pad{
             if ret == GST_PAD_LINK_OK{
                 Ok(())
             }else{
-                Err(mem::transmute(ret as isize))
+                Err(match ret{
+                    GST_PAD_LINK_WRONG_HIERARCHY => LinkReturn::WrongHierarchy,
+                    GST_PAD_LINK_WAS_LINKED => LinkReturn::WasLinked,
+                    GST_PAD_LINK_WRONG_DIRECTION => LinkReturn::WrongDirection,
+                    GST_PAD_LINK_NOFORMAT => LinkReturn::NoFormat,
+                    GST_PAD_LINK_NOSCHED => LinkReturn::NoSched,
+                    _ => LinkReturn::Refused,
+                })
             }
         }
     }
 
+    /// Unlinks this source pad from the sink pad
+    pub fn unlink(&mut self, sink: &mut Pad) -> bool{
+        unsafe{
+            gst_pad_unlink(self.gst_pad_mut(), sink.gst_pad_mut()) != 0
+        }
+    }
+
+    /// Returns the pad this pad is linked to
+    pub fn peer(&self) -> Option<Pad>{
+        unsafe{
+            Pad::new(gst_pad_get_peer(self.gst_pad() as *mut GstPad))
+        }
+    }
+
+    /// Sends an event to this pad, the event will go in the direction
+    /// of the pad, ie: upstream events should be sent to source pads and
+    /// downstream events to sink pads.
+    ///
+    /// This function takes ownership of the event
+    pub unsafe fn send_event(&mut self, event: *mut GstEvent) -> bool{
+        gst_pad_send_event(self.gst_pad_mut(), event) != 0
+    }
+
+    /// Sends an event to the peer of this pad
+    ///
+    /// This function takes ownership of the event
+    pub unsafe fn push_event(&mut self, event: *mut GstEvent) -> bool{
+        gst_pad_push_event(self.gst_pad_mut(), event) != 0
+    }
+
+    /// Pushes the buffers of list to the peer of this pad in a single
+    /// call, elements that don't handle lists receive them one by one
+    pub fn push_list(&mut self, list: BufferList) -> GstFlowReturn{
+        unsafe{
+            gst_pad_push_list(self.gst_pad_mut(), list.transfer())
+        }
+    }
+
     pub fn is_linked(&self) -> bool{
         unsafe{
-            let pad: &mut GstPad = mem::transmute(self.gst_pad());
-            pad.peer != ptr::null_mut()
+            (*self.gst_pad()).peer != ptr::null_mut()
         }
     }
 
@@ -52,18 +247,224 @@ impl Pad{
         }
     }
 
+    /// Caps negotiated on the pad, None if they aren't known yet
+    pub fn current_caps(&self) -> Option<Caps>{
+        unsafe{
+            Caps::new(gst_pad_get_current_caps(self.gst_pad() as *mut GstPad))
+        }
+    }
+
+    /// Installs a probe on the pad that will be called for every
+    /// data item or state that matches mask, a combination of
+    /// GST_PAD_PROBE_TYPE_* flags.
+    ///
+    /// The probe is called from the streaming thread, it can inspect or
+    /// modify buffers going through the pad, drop them or, if mask
+    /// contains GST_PAD_PROBE_TYPE_BLOCK, block the dataflow until the
+    /// probe is removed which is the basis for dynamically relinking
+    /// elements in a running pipeline.
+    ///
+    /// A probe for both buffers and upstream events or queries can be
+    /// triggered from several threads at once, it's called from one of
+    /// them at a time so it mustn't trigger itself, eg: by pushing an
+    /// event through the pad it's probing.
+    ///
+    /// Returns a handle that removes the probe when dropped, so any
+    /// state captured by the probe is released as soon as it's not
+    /// needed anymore, or None if the probe couldn't be installed or
+    /// was an idle probe that was already called.
+    pub fn add_probe<P: PadProbe + 'static>(&mut self, mask: GstPadProbeType, probe: P) -> Option<ProbeHandle>{
+        unsafe{
+            let probe: Probe = Mutex::new(Guarded::new(Box::new(probe)));
+            let probe: *mut Probe = Box::into_raw(Box::new(probe));
+            let id = gst_pad_add_probe(self.gst_pad_mut(), mask, Some(pad_probe_callback), probe as gpointer, Some(pad_probe_destroy));
+            if id != 0{
+                Some(ProbeHandle::new(self.gst_pad_mut(), id))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Calls handler with the previous and the new caps every time the
+    /// caps going through the pad change, eg: when the resolution of a
+    /// stream changes mid-stream, so buffers, textures... sized for the
+    /// previous caps can be reallocated before the first buffer with the
+    /// new ones arrives:
+    ///
+    /// ```ignore
+    /// let handle = pad.connect_caps_changed(|_old, new| {
+    ///     if let Some(info) = new.video_info(){
+    ///         resize_textures(info.width(), info.height());
+    ///     }
+    /// }).unwrap();
+    /// ```
+    ///
+    /// Caps events with the same caps as the previous ones don't call
+    /// the handler. It's called from the streaming thread and the probe
+    /// is removed when the returned handle is dropped.
+    pub fn connect_caps_changed<F>(&mut self, mut handler: F) -> Option<ProbeHandle>
+        where F: FnMut(Option<&Caps>, &Caps) + Send + 'static{
+        let mut current = self.current_caps();
+        self.add_probe(GST_PAD_PROBE_TYPE_EVENT_DOWNSTREAM, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+            if let Some(event) = info.event(){
+                if event.event_type() != GST_EVENT_CAPS{
+                    return PadProbeReturn::Ok;
+                }
+                let caps = unsafe{
+                    let mut caps: *mut GstCaps = ptr::null_mut();
+                    gst_event_parse_caps(event.gst_event() as *mut GstEvent, &mut caps);
+                    if caps != ptr::null_mut(){
+                        gst_mini_object_ref(caps as *mut GstMiniObject);
+                    }
+                    Caps::new(caps)
+                };
+                if let Some(caps) = caps{
+                    if current.as_ref() != Some(&caps){
+                        handler(current.as_ref(), &caps);
+                        current = Some(caps);
+                    }
+                }
+            }
+            PadProbeReturn::Ok
+        })
+    }
+
+    /// Calls handler with the tags of every tag event going downstream
+    /// through the pad. Unlike the Tag messages on the bus, which are
+    /// only posted by some elements, the events carry the tags of each
+    /// stream, so this is the way to know which stream they belong to.
+    /// The probe is removed when the returned handle is dropped
+    pub fn connect_tags<F>(&mut self, mut handler: F) -> Option<ProbeHandle>
+        where F: FnMut(TagList) + Send + 'static{
+        self.add_probe(GST_PAD_PROBE_TYPE_EVENT_DOWNSTREAM, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+            if let Some(event) = info.event(){
+                if let Some(tags) = unsafe{ TagList::from_event(event.gst_event() as *mut GstEvent) }{
+                    handler(tags);
+                }
+            }
+            PadProbeReturn::Ok
+        })
+    }
+
+    /// Calls handler with the toc of every toc event going downstream
+    /// through the pad and whether it updates a previous one. The probe
+    /// is removed when the returned handle is dropped
+    pub fn connect_toc<F>(&mut self, mut handler: F) -> Option<ProbeHandle>
+        where F: FnMut(Toc, bool) + Send + 'static{
+        self.add_probe(GST_PAD_PROBE_TYPE_EVENT_DOWNSTREAM, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+            if let Some(event) = info.event(){
+                if let Some((toc, updated)) = unsafe{ Toc::from_event(event.gst_event() as *mut GstEvent) }{
+                    handler(toc, updated);
+                }
+            }
+            PadProbeReturn::Ok
+        })
+    }
+
+    /// The template the pad was created from, None for pads created
+    /// without one
+    pub fn pad_template(&self) -> Option<::PadTemplate>{
+        unsafe{
+            ::PadTemplate::new_from_gst_pad_template(gst_pad_get_pad_template(self.gst_pad() as *mut GstPad))
+        }
+    }
+
+    /// Whether the pad is a GstProxyPad, like ghost pads and their
+    /// internal pads
+    pub fn is_proxy_pad(&self) -> bool{
+        unsafe{
+            g_type_check_instance_is_a(self.gst_pad() as *mut GTypeInstance, gst_proxy_pad_get_type()) != 0
+        }
+    }
+
+    /// For a proxy pad the pad on the other side of the bin boundary,
+    /// eg: the internal pad of a ghost pad, linked to the pad of the
+    /// child element it targets
+    pub fn internal(&self) -> Option<Pad>{
+        if !self.is_proxy_pad(){
+            return None;
+        }
+        unsafe{
+            Pad::new(gst_proxy_pad_get_internal(self.gst_pad() as *mut GstProxyPad) as *mut GstPad)
+        }
+    }
+
+    pub fn is_blocked(&self) -> bool{
+        unsafe{
+            gst_pad_is_blocked(self.gst_pad() as *mut GstPad) != 0
+        }
+    }
+
     pub unsafe fn gst_pad(&self) -> *const GstPad{
-        self.pad.gst_object() as *const GstPad
+        self.pad.as_ptr()
     }
 
     pub unsafe fn gst_pad_mut(&mut self) -> *mut GstPad{
-        self.pad.gst_object_mut() as *mut GstPad
+        self.pad.as_mut_ptr()
+    }
+}
+
+// buffer probes and upstream event or query probes can be called from
+// different threads at once
+type Probe = Mutex<Guarded<dyn PadProbe>>;
+
+extern "C" fn pad_probe_callback(pad: *mut GstPad, info: *mut GstPadProbeInfo, data: gpointer) -> GstPadProbeReturn{
+    unsafe{
+        let mut probe = (*(data as *const Probe)).lock().unwrap_or_else(|e| e.into_inner());
+        // a probe that panicked before is removed without calling it again
+        if probe.is_poisoned(){
+            return GST_PAD_PROBE_REMOVE;
+        }
+        gst_object_ref(pad as gpointer);
+        let mut pad = Pad::new(pad).unwrap();
+        let ty = (*info)._type;
+        let data = if ty & GST_PAD_PROBE_TYPE_BUFFER != 0 && (*info).data != ptr::null_mut(){
+            let buffer = (*info).data as *mut GstBuffer;
+            (*info).data = ptr::null_mut();
+            PadProbeData::Buffer(Buffer::new(buffer).unwrap())
+        }else if ty & GST_PAD_PROBE_TYPE_BUFFER_LIST != 0 && (*info).data != ptr::null_mut(){
+            let list = (*info).data as *mut GstBufferList;
+            (*info).data = ptr::null_mut();
+            PadProbeData::BufferList(BufferList::new_from_gst_buffer_list(list).unwrap())
+        }else if ty & GST_PAD_PROBE_TYPE_EVENT_BOTH != 0 && (*info).data != ptr::null_mut(){
+            let event = (*info).data as *mut GstEvent;
+            (*info).data = ptr::null_mut();
+            PadProbeData::Event(Event::new(event).unwrap())
+        }else if ty & GST_PAD_PROBE_TYPE_QUERY_BOTH != 0 && (*info).data != ptr::null_mut(){
+            PadProbeData::Query(Query::new(gst_pad_probe_info_get_query(info)).unwrap())
+        }else if ty & GST_PAD_PROBE_TYPE_IDLE != 0{
+            PadProbeData::Idle
+        }else{
+            PadProbeData::Block
+        };
+        let mut probe_info = PadProbeInfo{ data: data };
+
+        let gst_pad = pad.gst_pad_mut();
+        let ret = probe.call_with(|| gst_pad_get_parent_element(gst_pad), PadProbeReturn::Drop,
+            |probe| probe.call(&mut pad, &mut probe_info));
+
+        // the data might have been replaced by a writable copy, give the
+        // ownership back to the pad
+        match probe_info.data{
+            PadProbeData::Buffer(buffer) => (*info).data = buffer.transfer() as gpointer,
+            PadProbeData::BufferList(list) => (*info).data = list.transfer() as gpointer,
+            PadProbeData::Event(event) => (*info).data = event.transfer() as gpointer,
+            _ => ()
+        }
+        ret as GstPadProbeReturn
+    }
+}
+
+extern "C" fn pad_probe_destroy(data: gpointer){
+    unsafe{
+        catch_panic(ptr::null_mut(), (), || drop(Box::from_raw(data as *mut Probe)));
     }
 }
 
 impl ::Transfer<GstPad> for Pad{
     unsafe fn transfer(self) -> *mut GstPad{
-        self.pad.transfer() as *mut GstPad
+        self.pad.transfer()
     }
 }
 
@@ -75,31 +476,31 @@ impl Reference for Pad{
 
 impl AsRef<Object> for Pad{
     fn as_ref(&self) -> &Object{
-        &self.pad
+        self.pad.upcast_ref()
     }
 }
 
 impl AsMut<Object> for Pad{
     fn as_mut(&mut self) -> &mut Object{
-        &mut self.pad
+        self.pad.upcast_mut()
     }
 }
 
 impl From<Pad> for Object{
     fn from(b: Pad) -> Object{
-        b.pad
+        b.pad.upcast()
     }
 }
 
 impl Deref for Pad{
     type Target = Object;
     fn deref(&self) -> &Object{
-        &self.pad
+        self.pad.upcast_ref()
     }
 }
 
 impl DerefMut for Pad{
     fn deref_mut(&mut self) -> &mut Object{
-        &mut self.pad
+        self.pad.upcast_mut()
     }
 }
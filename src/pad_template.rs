@@ -0,0 +1,113 @@
+use ffi::*;
+use util::*;
+use object::Object;
+use caps::Caps;
+use reference::Reference;
+
+use std::ops::{Deref, DerefMut};
+
+/// Describes the pads an element can have: their name, eg: "src_%u"
+/// for the source pads of a tee, direction, when they exist and the
+/// caps they can handle. Request pads are created from the templates
+/// with GST_PAD_REQUEST presence:
+///
+/// ```ignore
+/// let template = tee.pad_template("src_%u").unwrap();
+/// let mut src = tee.request_pad_from_template(&template, None, None).unwrap();
+/// src.link(&mut queue_sink);
+/// ```
+pub struct PadTemplate{
+    template: Object<GstPadTemplate>
+}
+
+impl PadTemplate{
+    pub unsafe fn new_from_gst_pad_template(template: *mut GstPadTemplate) -> Option<PadTemplate>{
+        Object::new(template).map(|obj| PadTemplate{ template: obj })
+    }
+
+    /// Creates a template, presence is GST_PAD_ALWAYS, GST_PAD_SOMETIMES
+    /// or GST_PAD_REQUEST
+    pub fn new(name_template: &str, direction: GstPadDirection, presence: GstPadPresence, caps: &Caps) -> Option<PadTemplate>{
+        let cname = CString::new(name_template).unwrap();
+        unsafe{
+            let template = gst_pad_template_new(cname.as_ptr(), direction, presence, caps.gst_caps() as *mut GstCaps);
+            if template != ptr::null_mut(){
+                gst_object_ref_sink(template as gpointer);
+            }
+            PadTemplate::new_from_gst_pad_template(template)
+        }
+    }
+
+    /// Name of the pads created from the template, with %u, %d or %s
+    /// for request and sometimes pads, eg: "sink_%u"
+    pub fn name_template(&self) -> String{
+        unsafe{
+            from_c_str!((*self.gst_pad_template()).name_template).to_string()
+        }
+    }
+
+    pub fn direction(&self) -> GstPadDirection{
+        unsafe{
+            (*self.gst_pad_template()).direction
+        }
+    }
+
+    /// GST_PAD_ALWAYS, GST_PAD_SOMETIMES or GST_PAD_REQUEST
+    pub fn presence(&self) -> GstPadPresence{
+        unsafe{
+            (*self.gst_pad_template()).presence
+        }
+    }
+
+    /// Whether pads have to be requested from the element with this
+    /// template
+    pub fn is_request(&self) -> bool{
+        self.presence() == GST_PAD_REQUEST
+    }
+
+    /// The caps the pads created from the template can handle
+    pub fn caps(&self) -> Option<Caps>{
+        unsafe{
+            Caps::new(gst_pad_template_get_caps(self.gst_pad_template() as *mut GstPadTemplate))
+        }
+    }
+
+    pub unsafe fn gst_pad_template(&self) -> *const GstPadTemplate{
+        self.template.as_ptr()
+    }
+
+    pub unsafe fn gst_pad_template_mut(&mut self) -> *mut GstPadTemplate{
+        self.template.as_mut_ptr()
+    }
+}
+
+impl Reference for PadTemplate{
+    fn reference(&self) -> PadTemplate{
+        PadTemplate{ template: self.template.reference() }
+    }
+}
+
+impl AsRef<Object> for PadTemplate{
+    fn as_ref(&self) -> &Object{
+        self.template.upcast_ref()
+    }
+}
+
+impl AsMut<Object> for PadTemplate{
+    fn as_mut(&mut self) -> &mut Object{
+        self.template.upcast_mut()
+    }
+}
+
+impl Deref for PadTemplate{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.template.upcast_ref()
+    }
+}
+
+impl DerefMut for PadTemplate{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.template.upcast_mut()
+    }
+}
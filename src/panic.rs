@@ -0,0 +1,93 @@
+use ffi::*;
+use util::*;
+use element::Element;
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+
+type PanicHandler = Arc<dyn Fn(Option<&Element>, &str) + Send + Sync>;
+
+static PANIC_HANDLER: Mutex<Option<PanicHandler>> = Mutex::new(None);
+
+/// Sets a function to be called when a rust callback called from
+/// GStreamer, like a pad probe or a bus watch, panics. It receives the
+/// element the callback was running for, if known, and the panic
+/// message.
+///
+/// Unwinding into C code is undefined behaviour so panics are always
+/// caught at the boundary and the callback returns a safe default:
+/// probes drop the data, bus watches are removed... Callbacks that keep
+/// being called, like probes, signal handlers, sync handlers or appsink
+/// handlers, are poisoned after a panic and never called again, probes
+/// are removed the next time they'd run.
+///
+/// By default an error message is posted on the bus for the element, so
+/// the application sees it like any other error, or the panic is printed
+/// to stderr if there's no element.
+pub fn set_panic_handler<F: Fn(Option<&Element>, &str) + Send + Sync + 'static>(handler: F){
+    *PANIC_HANDLER.lock().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(handler));
+}
+
+/// Goes back to the default behaviour of posting an error message
+pub fn reset_panic_handler(){
+    *PANIC_HANDLER.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Posts an error message on behalf of element with the panic message,
+/// the default behaviour when no handler is set
+pub fn post_panic_error(element: &Element, msg: &str){
+    unsafe{
+        post_element_error(element.gst_element() as *mut GstElement, gst_core_error_quark(), GST_CORE_ERROR_FAILED as gint,
+            &format!("Panic in callback: {}", msg), None, file!(), "post_panic_error", line!());
+    }
+}
+
+/// Runs f catching any panic, in which case the panic handler is called
+/// and fallback returned. element is the element the callback runs for,
+/// can be null.
+pub fn catch_panic<R, F: FnOnce() -> R>(element: *mut GstElement, fallback: R, f: F) -> R{
+    catch_panic_with(|| unsafe{
+        if element != ptr::null_mut(){
+            gst_object_ref(element as gpointer);
+        }
+        element
+    }, fallback, f)
+}
+
+/// Same as catch_panic but the element is only looked up if f panics,
+/// for callbacks on hot paths like pad probes. element returns a new
+/// reference to the element or null
+pub fn catch_panic_with<R, E: FnOnce() -> *mut GstElement, F: FnOnce() -> R>(element: E, fallback: R, f: F) -> R{
+    match panic::catch_unwind(AssertUnwindSafe(f)){
+        Ok(ret) => ret,
+        Err(payload) => {
+            let msg = panic_message(&payload);
+            // a panicking handler mustn't unwind into C either
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+                let element = unsafe{ Element::new_from_gst_element(element()) };
+                // called without holding the lock so the handler can
+                // replace itself
+                let handler = PANIC_HANDLER.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                match handler{
+                    Some(handler) => handler(element.as_ref(), &msg),
+                    None => match element{
+                        Some(ref element) => post_panic_error(element, &msg),
+                        None => eprintln!("Panic in GStreamer callback: {}", msg),
+                    }
+                }
+            }));
+            fallback
+        }
+    }
+}
+
+fn panic_message(payload: &Box<dyn Any + Send>) -> String{
+    if let Some(msg) = payload.downcast_ref::<&str>(){
+        msg.to_string()
+    }else if let Some(msg) = payload.downcast_ref::<String>(){
+        msg.clone()
+    }else{
+        "unknown panic".to_string()
+    }
+}
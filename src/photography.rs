@@ -0,0 +1,240 @@
+use ffi::*;
+use element::Element;
+use reference::Reference;
+
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum FlashMode{
+    Auto = GST_PHOTOGRAPHY_FLASH_MODE_AUTO,
+    Off = GST_PHOTOGRAPHY_FLASH_MODE_OFF,
+    On = GST_PHOTOGRAPHY_FLASH_MODE_ON,
+    FillIn = GST_PHOTOGRAPHY_FLASH_MODE_FILL_IN,
+    RedEye = GST_PHOTOGRAPHY_FLASH_MODE_RED_EYE,
+}
+
+impl FlashMode{
+    fn from_gst(mode: GstPhotographyFlashMode) -> Option<FlashMode>{
+        match mode{
+            GST_PHOTOGRAPHY_FLASH_MODE_AUTO => Some(FlashMode::Auto),
+            GST_PHOTOGRAPHY_FLASH_MODE_OFF => Some(FlashMode::Off),
+            GST_PHOTOGRAPHY_FLASH_MODE_ON => Some(FlashMode::On),
+            GST_PHOTOGRAPHY_FLASH_MODE_FILL_IN => Some(FlashMode::FillIn),
+            GST_PHOTOGRAPHY_FLASH_MODE_RED_EYE => Some(FlashMode::RedEye),
+            _ => None
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum FocusMode{
+    Auto = GST_PHOTOGRAPHY_FOCUS_MODE_AUTO,
+    Macro = GST_PHOTOGRAPHY_FOCUS_MODE_MACRO,
+    Portrait = GST_PHOTOGRAPHY_FOCUS_MODE_PORTRAIT,
+    Infinity = GST_PHOTOGRAPHY_FOCUS_MODE_INFINITY,
+    Hyperfocal = GST_PHOTOGRAPHY_FOCUS_MODE_HYPERFOCAL,
+    Extended = GST_PHOTOGRAPHY_FOCUS_MODE_EXTENDED,
+    ContinuousNormal = GST_PHOTOGRAPHY_FOCUS_MODE_CONTINUOUS_NORMAL,
+    ContinuousExtended = GST_PHOTOGRAPHY_FOCUS_MODE_CONTINUOUS_EXTENDED,
+    Manual = GST_PHOTOGRAPHY_FOCUS_MODE_MANUAL,
+}
+
+impl FocusMode{
+    fn from_gst(mode: GstPhotographyFocusMode) -> Option<FocusMode>{
+        match mode{
+            GST_PHOTOGRAPHY_FOCUS_MODE_AUTO => Some(FocusMode::Auto),
+            GST_PHOTOGRAPHY_FOCUS_MODE_MACRO => Some(FocusMode::Macro),
+            GST_PHOTOGRAPHY_FOCUS_MODE_PORTRAIT => Some(FocusMode::Portrait),
+            GST_PHOTOGRAPHY_FOCUS_MODE_INFINITY => Some(FocusMode::Infinity),
+            GST_PHOTOGRAPHY_FOCUS_MODE_HYPERFOCAL => Some(FocusMode::Hyperfocal),
+            GST_PHOTOGRAPHY_FOCUS_MODE_EXTENDED => Some(FocusMode::Extended),
+            GST_PHOTOGRAPHY_FOCUS_MODE_CONTINUOUS_NORMAL => Some(FocusMode::ContinuousNormal),
+            GST_PHOTOGRAPHY_FOCUS_MODE_CONTINUOUS_EXTENDED => Some(FocusMode::ContinuousExtended),
+            GST_PHOTOGRAPHY_FOCUS_MODE_MANUAL => Some(FocusMode::Manual),
+            _ => None
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum WhiteBalanceMode{
+    Auto = GST_PHOTOGRAPHY_WB_MODE_AUTO,
+    Daylight = GST_PHOTOGRAPHY_WB_MODE_DAYLIGHT,
+    Cloudy = GST_PHOTOGRAPHY_WB_MODE_CLOUDY,
+    Sunset = GST_PHOTOGRAPHY_WB_MODE_SUNSET,
+    Tungsten = GST_PHOTOGRAPHY_WB_MODE_TUNGSTEN,
+    Fluorescent = GST_PHOTOGRAPHY_WB_MODE_FLUORESCENT,
+    Manual = GST_PHOTOGRAPHY_WB_MODE_MANUAL,
+    WarmFluorescent = GST_PHOTOGRAPHY_WB_MODE_WARM_FLUORESCENT,
+    Shade = GST_PHOTOGRAPHY_WB_MODE_SHADE,
+}
+
+impl WhiteBalanceMode{
+    fn from_gst(mode: GstPhotographyWhiteBalanceMode) -> Option<WhiteBalanceMode>{
+        match mode{
+            GST_PHOTOGRAPHY_WB_MODE_AUTO => Some(WhiteBalanceMode::Auto),
+            GST_PHOTOGRAPHY_WB_MODE_DAYLIGHT => Some(WhiteBalanceMode::Daylight),
+            GST_PHOTOGRAPHY_WB_MODE_CLOUDY => Some(WhiteBalanceMode::Cloudy),
+            GST_PHOTOGRAPHY_WB_MODE_SUNSET => Some(WhiteBalanceMode::Sunset),
+            GST_PHOTOGRAPHY_WB_MODE_TUNGSTEN => Some(WhiteBalanceMode::Tungsten),
+            GST_PHOTOGRAPHY_WB_MODE_FLUORESCENT => Some(WhiteBalanceMode::Fluorescent),
+            GST_PHOTOGRAPHY_WB_MODE_MANUAL => Some(WhiteBalanceMode::Manual),
+            GST_PHOTOGRAPHY_WB_MODE_WARM_FLUORESCENT => Some(WhiteBalanceMode::WarmFluorescent),
+            GST_PHOTOGRAPHY_WB_MODE_SHADE => Some(WhiteBalanceMode::Shade),
+            _ => None
+        }
+    }
+}
+
+/// Access to the GstPhotography interface implemented by some camera
+/// sources to control flash, focus, exposure...
+///
+/// Every setter returns false if the driver doesn't support the
+/// requested setting.
+pub struct Photography{
+    element: Element
+}
+
+impl Photography{
+    /// Returns the photography interface of element or None if the
+    /// element doesn't implement it
+    pub fn new(element: &Element) -> Option<Photography>{
+        unsafe{
+            if g_type_check_instance_is_a(element.gst_element() as *mut GTypeInstance, gst_photography_get_type()) != 0{
+                Some(Photography{ element: element.reference() })
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn set_flash_mode(&mut self, mode: FlashMode) -> bool{
+        unsafe{
+            gst_photography_set_flash_mode(self.gst_photography_mut(), mode as GstPhotographyFlashMode) != 0
+        }
+    }
+
+    pub fn flash_mode(&self) -> Option<FlashMode>{
+        unsafe{
+            let mut mode = 0;
+            if gst_photography_get_flash_mode(self.gst_photography() as *mut GstPhotography, &mut mode) != 0{
+                FlashMode::from_gst(mode)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn set_focus_mode(&mut self, mode: FocusMode) -> bool{
+        unsafe{
+            gst_photography_set_focus_mode(self.gst_photography_mut(), mode as GstPhotographyFocusMode) != 0
+        }
+    }
+
+    pub fn focus_mode(&self) -> Option<FocusMode>{
+        unsafe{
+            let mut mode = 0;
+            if gst_photography_get_focus_mode(self.gst_photography() as *mut GstPhotography, &mut mode) != 0{
+                FocusMode::from_gst(mode)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn set_white_balance_mode(&mut self, mode: WhiteBalanceMode) -> bool{
+        unsafe{
+            gst_photography_set_white_balance_mode(self.gst_photography_mut(), mode as GstPhotographyWhiteBalanceMode) != 0
+        }
+    }
+
+    pub fn white_balance_mode(&self) -> Option<WhiteBalanceMode>{
+        unsafe{
+            let mut mode = 0;
+            if gst_photography_get_white_balance_mode(self.gst_photography() as *mut GstPhotography, &mut mode) != 0{
+                WhiteBalanceMode::from_gst(mode)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Exposure compensation in EV steps
+    pub fn set_ev_compensation(&mut self, ev: f32) -> bool{
+        unsafe{
+            gst_photography_set_ev_compensation(self.gst_photography_mut(), ev) != 0
+        }
+    }
+
+    pub fn ev_compensation(&self) -> Option<f32>{
+        unsafe{
+            let mut ev = 0.;
+            if gst_photography_get_ev_compensation(self.gst_photography() as *mut GstPhotography, &mut ev) != 0{
+                Some(ev)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn set_iso_speed(&mut self, iso: u32) -> bool{
+        unsafe{
+            gst_photography_set_iso_speed(self.gst_photography_mut(), iso) != 0
+        }
+    }
+
+    pub fn iso_speed(&self) -> Option<u32>{
+        unsafe{
+            let mut iso = 0;
+            if gst_photography_get_iso_speed(self.gst_photography() as *mut GstPhotography, &mut iso) != 0{
+                Some(iso)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn set_zoom(&mut self, zoom: f32) -> bool{
+        unsafe{
+            gst_photography_set_zoom(self.gst_photography_mut(), zoom) != 0
+        }
+    }
+
+    pub fn zoom(&self) -> Option<f32>{
+        unsafe{
+            let mut zoom = 0.;
+            if gst_photography_get_zoom(self.gst_photography() as *mut GstPhotography, &mut zoom) != 0{
+                Some(zoom)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Starts or stops autofocusing. The result is posted on the bus as an
+    /// element message with a structure named "photo-capture-focus-done"
+    pub fn set_autofocus(&mut self, on: bool){
+        unsafe{
+            gst_photography_set_autofocus(self.gst_photography_mut(), on as gboolean)
+        }
+    }
+
+    pub fn element(&self) -> &Element{
+        &self.element
+    }
+
+    pub unsafe fn gst_photography(&self) -> *const GstPhotography{
+        self.element.gst_element() as *const GstPhotography
+    }
+
+    pub unsafe fn gst_photography_mut(&mut self) -> *mut GstPhotography{
+        self.element.gst_element_mut() as *mut GstPhotography
+    }
+}
+
+impl Reference for Photography{
+    fn reference(&self) -> Photography{
+        Photography{ element: self.element.reference() }
+    }
+}
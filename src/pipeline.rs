@@ -1,12 +1,22 @@
 use ffi::*;
 use bin::Bin;
 use bus::Bus;
+use clock::Clock;
+use element::Element;
+use message::Message;
 use error::Error;
 use error::Result;
+use error::{ParseError, parse_result};
 use util::*;
-use reference::Reference;
+use reference::{Reference, Downgrade};
+use status::{PipelineStatus, PtsTracker};
+use stats::{PipelineStats, StatsCollector};
+use version::ApiAvailability;
 
 use std::ops::{Deref, DerefMut};
+use std::result;
+
+const PIPELINE_LATENCY: ApiAvailability = ApiAvailability::new("Pipeline::set_latency", 1, 6, 0);
 
 
 /** A GstPipeline is a special GstBin used as the toplevel container for the filter graph. The GstPipeline will manage the selection and distribution of a global GstClock as well as provide a GstBus to the application.
@@ -28,9 +38,6 @@ pub struct Pipeline{
     pipeline: Bin
 }
 
-unsafe impl Sync for Pipeline {}
-unsafe impl Send for Pipeline {}
-
 impl Pipeline{
     /// Create a new pipeline with the given name.
     pub fn new(name: &str) -> Option<Pipeline>{
@@ -38,7 +45,7 @@ impl Pipeline{
         unsafe{
             let pipeline = gst_pipeline_new(cname.as_ptr());
             if pipeline != ptr::null_mut(){
-		        gst_object_ref_sink(mem::transmute(pipeline));
+		        gst_object_ref_sink(pipeline as gpointer);
 	            match Bin::new_from_gst_bin(pipeline as *mut GstBin){
 	                Some(bin) => Some(Pipeline{ pipeline: bin }),
 	                None => None
@@ -54,9 +61,9 @@ impl Pipeline{
         let mut error = ptr::null_mut::<GError>();
         let cstring = CString::new(string).unwrap();
         unsafe{
-            let pipeline = gst_parse_launch (mem::transmute(cstring.as_ptr()), &mut error);
+            let pipeline = gst_parse_launch (cstring.as_ptr(), &mut error);
             if error == ptr::null_mut(){
-	            gst_object_ref_sink(mem::transmute(pipeline));
+	            gst_object_ref_sink(pipeline as gpointer);
 				match Bin::new_from_gst_bin(pipeline as *mut GstBin){
 					Some(bin) => Ok(Pipeline{ pipeline: bin }),
 					None => Err(Error::new(0,0,"Couldn't create bin"))
@@ -67,6 +74,68 @@ impl Pipeline{
         }
     }
 
+    /// Creates a new pipeline from a description split in arguments, as
+    /// gst-launch receives it from the command line, eg:
+    /// &["videotestsrc", "!", "autovideosink"]
+    pub fn new_from_args(args: &[&str]) -> Result<Pipeline>{
+        Pipeline::new_from_args_full(args, GST_PARSE_FLAG_NONE).map_err(|err| err.error)
+    }
+
+    /// Same as new_from_str with GST_PARSE_FLAG_* flags. Unless
+    /// GST_PARSE_FLAG_FATAL_ERRORS is passed, errors like an unknown
+    /// property or a missing plugin still build the rest of the pipeline,
+    /// returned in the error's partial:
+    ///
+    /// ```ignore
+    /// match gst::Pipeline::new_from_str_full("videotestsrc ! x265enc ! fakesink", gst::ffi::GST_PARSE_FLAG_NONE){
+    ///     Ok(pipeline) => pipeline,
+    ///     Err(err) => {
+    ///         println!("missing {:?}", err.missing_elements);
+    ///         err.partial.unwrap()
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Descriptions of a single element are put in a pipeline.
+    pub fn new_from_str_full(string: &str, flags: GstParseFlags) -> result::Result<Pipeline, ParseError<Pipeline>>{
+        let cstring = CString::new(string).unwrap();
+        unsafe{
+            let context = gst_parse_context_new();
+            let mut error = ptr::null_mut();
+            let element = gst_parse_launch_full(cstring.as_ptr(), context, flags, &mut error);
+            parse_result(element, error, context, |element| Pipeline::from_parsed(element))
+        }
+    }
+
+    /// Same as new_from_args with GST_PARSE_FLAG_* flags, see
+    /// new_from_str_full
+    pub fn new_from_args_full(args: &[&str], flags: GstParseFlags) -> result::Result<Pipeline, ParseError<Pipeline>>{
+        let cargs: Vec<CString> = args.iter().map(|arg| CString::new(*arg).unwrap()).collect();
+        let mut argv: Vec<*const gchar> = cargs.iter().map(|arg| arg.as_ptr()).collect();
+        argv.push(ptr::null());
+        unsafe{
+            let context = gst_parse_context_new();
+            let mut error = ptr::null_mut();
+            let element = gst_parse_launchv_full(argv.as_mut_ptr(), context, flags, &mut error);
+            parse_result(element, error, context, |element| Pipeline::from_parsed(element))
+        }
+    }
+
+    // takes ownership of element
+    unsafe fn from_parsed(element: *mut GstElement) -> Option<Pipeline>{
+        if g_type_check_instance_is_a(element as *mut GTypeInstance, gst_pipeline_get_type()) != 0{
+            Pipeline::new_from_gst_pipeline(element as *mut GstPipeline)
+        }else{
+            let element = Element::new_from_gst_element(element)?;
+            let mut pipeline = Pipeline::new("pipeline")?;
+            if pipeline.add(element){
+                Some(pipeline)
+            }else{
+                None
+            }
+        }
+    }
+
     pub unsafe fn new_from_gst_pipeline(pipeline: *mut GstPipeline) -> Option<Pipeline>{
         match Bin::new_from_gst_bin(pipeline as *mut GstBin){
             Some(pipeline) => Some( Pipeline{ pipeline: pipeline } ),
@@ -102,6 +171,94 @@ impl Pipeline{
         }
     }
 
+    /// The latency configured with set_latency, GST_CLOCK_TIME_NONE if
+    /// the pipeline computes it from its elements. Needs GStreamer 1.6
+    pub fn latency(&self) -> Result<GstClockTime>{
+        PIPELINE_LATENCY.check()?;
+        Ok(self.get::<u64>("latency"))
+    }
+
+    /// Overrides the latency the pipeline computes from its elements,
+    /// eg: to give a live network source more time to deliver its
+    /// buffers. GST_CLOCK_TIME_NONE goes back to the computed latency.
+    /// Needs GStreamer 1.6, returns an unsupported error with older
+    /// versions
+    pub fn set_latency(&mut self, latency: GstClockTime) -> Result<()>{
+        PIPELINE_LATENCY.check()?;
+        self.set("latency", latency);
+        Ok(())
+    }
+
+    /// Forces the pipeline to use clock instead of selecting one from
+    /// its elements, eg: a network clock shared with other devices
+    pub fn use_clock(&mut self, clock: &Clock){
+        unsafe{
+            gst_pipeline_use_clock(self.gst_pipeline_mut(), clock.gst_clock() as *mut GstClock);
+        }
+    }
+
+    /// Restores the default clock selection after use_clock
+    pub fn auto_clock(&mut self){
+        unsafe{
+            gst_pipeline_auto_clock(self.gst_pipeline_mut());
+        }
+    }
+
+    /// The clock the pipeline uses or would use if it was set to
+    /// PLAYING
+    pub fn clock(&self) -> Option<Clock>{
+        unsafe{
+            Clock::new(gst_pipeline_get_clock(self.gst_pipeline() as *mut GstPipeline))
+        }
+    }
+
+    /// Pauses the pipeline while buffering and resumes it once the buffer
+    /// is full, call it with every message from the bus when playing from
+    /// the network. target is the state the application wants the
+    /// pipeline in, the pipeline is only resumed if it's PLAYING:
+    ///
+    /// ```ignore
+    /// for msg in bus.receiver().iter(){
+    ///     if let Some(percent) = pipeline.handle_buffering(&msg, gst::GST_STATE_PLAYING){
+    ///         println!("buffering {}%", percent);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Live pipelines can't be paused to buffer so they are left alone.
+    /// Returns the buffering percent if msg is a Buffering message
+    pub fn handle_buffering(&mut self, msg: &Message, target: GstState) -> Option<i32>{
+        let buffering = msg.buffering()?;
+        let is_live = self.get_state(0).2 == GST_STATE_CHANGE_NO_PREROLL;
+        if !is_live && target == GST_STATE_PLAYING{
+            if buffering.percent < 100{
+                self.set_state(GST_STATE_PAUSED);
+            }else{
+                self.set_state(GST_STATE_PLAYING);
+            }
+        }
+        Some(buffering.percent)
+    }
+
+    /// The state of every element and the caps of every pad of the
+    /// pipeline, to debug pipelines that are stuck. Printing it gives a
+    /// readable report
+    pub fn dump_status(&self) -> PipelineStatus{
+        PipelineStatus::collect(self, None)
+    }
+
+    /// Like dump_status, also with the PTS of the last buffer that went
+    /// through each pad as recorded by tracker
+    pub fn dump_status_with_pts(&self, tracker: &PtsTracker) -> PipelineStatus{
+        PipelineStatus::collect(self, Some(tracker))
+    }
+
+    /// Throughput and latency of every element of the pipeline, as
+    /// measured by collector since it was installed or reset
+    pub fn stats(&self, collector: &StatsCollector) -> PipelineStats{
+        PipelineStats::collect(self, collector)
+    }
+
     /// Returns a const raw pointer to the internal GstElement
     pub unsafe fn gst_pipeline(&self) -> *const GstPipeline{
         self.pipeline.gst_element() as *const GstPipeline
@@ -125,6 +282,16 @@ impl Reference for Pipeline{
     }
 }
 
+impl Downgrade for Pipeline{
+    unsafe fn gst_object_ptr(&self) -> *mut GstObject{
+        self.gst_pipeline() as *mut GstObject
+    }
+
+    unsafe fn from_gst_object_ptr(object: *mut GstObject) -> Option<Pipeline>{
+        Pipeline::new_from_gst_pipeline(object as *mut GstPipeline)
+    }
+}
+
 
 impl AsRef<Bin> for Pipeline{
     fn as_ref(&self) -> &Bin{
@@ -0,0 +1,311 @@
+use ffi::*;
+use bin::Bin;
+use pipeline::Pipeline;
+use mainloop::MainContext;
+use panic::catch_panic;
+use util::Guarded;
+
+use std::mem;
+use std::ptr;
+use std::sync::mpsc::{self, Sender, Receiver, TryRecvError};
+
+/// A command sent to the pipeline of a PipelineController
+pub enum Command{
+    Play,
+    Pause,
+    /// Sets the pipeline to NULL
+    Stop,
+    /// Flushing seek to a position in nanoseconds
+    Seek(u64),
+    /// Sets a property of the element with that name from its string
+    /// representation, as in a gst-launch description
+    SetProperty{
+        element: String,
+        property: String,
+        value: String,
+    },
+    /// Adds a branch to the tee with that name from a bin description,
+    /// see Pipeline::add_branch
+    AddBranch{
+        tee: String,
+        description: String,
+    },
+    /// Runs any code with the pipeline
+    Call(Box<dyn FnMut(&mut Pipeline) + Send>),
+}
+
+struct Executor{
+    pipeline: Pipeline,
+    commands: Receiver<Command>,
+    // command received while checking if the source is ready
+    pending: Option<Command>,
+    disconnected: bool,
+}
+
+/// A handle to control a pipeline from any thread. The commands are
+/// queued and run on the thread that owns the pipeline: the thread
+/// running the MainContext the controller was attached to, or the one
+/// calling PipelineDispatcher::dispatch, eg: from a GUI frame loop.
+///
+/// ```ignore
+/// let mut main_loop = gst::MainLoop::new();
+/// let controller = gst::PipelineController::attach(pipeline, &main_loop.context());
+/// let remote = controller.clone();
+/// thread::spawn(move || {
+///     remote.set_property("encoder", "bitrate", "4096");
+///     remote.play();
+/// });
+/// main_loop.run();
+/// ```
+///
+/// The handle only holds the sending side of the command queue, the
+/// pipeline stays with the source attached to the context or with the
+/// PipelineDispatcher, and is dropped there once every handle is gone.
+///
+/// Commands don't return a result, errors are reported on the bus of
+/// the pipeline as usual.
+#[derive(Clone)]
+pub struct PipelineController{
+    // only None while dropping
+    sender: Option<Sender<Command>>,
+    // woken up when a command is sent to a controller that was attached
+    context: Option<MainContext>,
+}
+
+/// The owning side of a PipelineController created with new, runs the
+/// commands on the thread calling dispatch
+pub struct PipelineDispatcher{
+    executor: Executor,
+}
+
+impl PipelineController{
+    /// Controls pipeline, running the commands when dispatch is called
+    /// on the returned PipelineDispatcher
+    pub fn new(pipeline: Pipeline) -> (PipelineController, PipelineDispatcher){
+        let (controller, executor) = PipelineController::with_executor(pipeline, None);
+        (controller, PipelineDispatcher{ executor: executor })
+    }
+
+    /// Controls pipeline, running the commands from context as soon as
+    /// they are sent. The pipeline is owned by a source attached to
+    /// context until every handle is dropped
+    pub fn attach(pipeline: Pipeline, context: &MainContext) -> PipelineController{
+        let (controller, executor) = PipelineController::with_executor(pipeline, Some(context.clone()));
+        unsafe{
+            let source = g_source_new(&EXECUTOR_SOURCE_FUNCS as *const GSourceFuncs as *mut GSourceFuncs,
+                mem::size_of::<ExecutorSource>() as guint);
+            (*(source as *mut ExecutorSource)).executor = Box::into_raw(Box::new(executor));
+            g_source_attach(source, context.gst_maincontext() as *mut GMainContext);
+            g_source_unref(source);
+        }
+        controller
+    }
+
+    fn with_executor(pipeline: Pipeline, context: Option<MainContext>) -> (PipelineController, Executor){
+        let (sender, commands) = mpsc::channel();
+        let controller = PipelineController{
+            sender: Some(sender),
+            context: context,
+        };
+        let executor = Executor{
+            pipeline: pipeline,
+            commands: commands,
+            pending: None,
+            disconnected: false,
+        };
+        (controller, executor)
+    }
+
+    /// Queues command, returns false if it couldn't be sent
+    pub fn send(&self, command: Command) -> bool{
+        match self.sender{
+            Some(ref sender) if sender.send(command).is_ok() => (),
+            _ => return false,
+        }
+        self.wakeup();
+        true
+    }
+
+    pub fn play(&self) -> bool{
+        self.send(Command::Play)
+    }
+
+    pub fn pause(&self) -> bool{
+        self.send(Command::Pause)
+    }
+
+    pub fn stop(&self) -> bool{
+        self.send(Command::Stop)
+    }
+
+    /// Seeks to position in nanoseconds
+    pub fn seek(&self, position: u64) -> bool{
+        self.send(Command::Seek(position))
+    }
+
+    /// Sets a property of the element called element from a string, eg:
+    /// set_property("encoder", "bitrate", "4096")
+    pub fn set_property(&self, element: &str, property: &str, value: &str) -> bool{
+        self.send(Command::SetProperty{
+            element: element.to_string(),
+            property: property.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    /// Adds a branch described as in a gst-launch line to the tee called
+    /// tee, eg: add_branch("tee", "x264enc ! mp4mux ! filesink location=rec.mp4")
+    pub fn add_branch(&self, tee: &str, description: &str) -> bool{
+        self.send(Command::AddBranch{
+            tee: tee.to_string(),
+            description: description.to_string(),
+        })
+    }
+
+    /// Runs f with the pipeline on the thread that owns it
+    pub fn call<F: FnMut(&mut Pipeline) + Send + 'static>(&self, f: F) -> bool{
+        self.send(Command::Call(Box::new(f)))
+    }
+
+    fn wakeup(&self){
+        if let Some(ref context) = self.context{
+            unsafe{
+                g_main_context_wakeup(context.gst_maincontext() as *mut GMainContext);
+            }
+        }
+    }
+}
+
+impl Drop for PipelineController{
+    fn drop(&mut self){
+        // the source removes itself once the last sender is gone, it has
+        // to be dropped before waking up the context
+        drop(self.sender.take());
+        self.wakeup();
+    }
+}
+
+impl PipelineDispatcher{
+    /// Runs the commands sent so far on the calling thread
+    pub fn dispatch(&mut self){
+        self.executor.run_pending();
+    }
+
+    pub fn pipeline(&self) -> &Pipeline{
+        &self.executor.pipeline
+    }
+}
+
+impl Executor{
+    // whether there are commands to run or every sender is gone, keeps
+    // the command received to check it
+    fn ready(&mut self) -> bool{
+        if self.pending.is_none() && !self.disconnected{
+            match self.commands.try_recv(){
+                Ok(command) => self.pending = Some(command),
+                Err(TryRecvError::Disconnected) => self.disconnected = true,
+                Err(TryRecvError::Empty) => (),
+            }
+        }
+        self.pending.is_some() || self.disconnected
+    }
+
+    fn run_pending(&mut self){
+        if let Some(command) = self.pending.take(){
+            self.run(command);
+        }
+        loop{
+            match self.commands.try_recv(){
+                Ok(command) => self.run(command),
+                Err(TryRecvError::Disconnected) => {
+                    self.disconnected = true;
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+    }
+
+    fn run(&mut self, command: Command){
+        let pipeline = &mut self.pipeline;
+        match command{
+            Command::Play => { pipeline.play(); }
+            Command::Pause => { pipeline.pause(); }
+            Command::Stop => { pipeline.set_state(GST_STATE_NULL); }
+            Command::Seek(position) => {
+                pipeline.seek_simple(GST_FORMAT_TIME, GST_SEEK_FLAG_FLUSH | GST_SEEK_FLAG_KEY_UNIT, position as i64);
+            }
+            Command::SetProperty{element, property, value} => {
+                if let Some(mut element) = pipeline.get_by_name(&element){
+                    element.set_from_str(&property, &value);
+                }
+            }
+            Command::AddBranch{tee, description} => {
+                if let (Some(tee), Ok(bin)) = (pipeline.get_by_name(&tee), Bin::from_description(&description, true)){
+                    // the branch stays in the pipeline until it's removed
+                    // with a Call command
+                    pipeline.add_branch(&tee, bin);
+                }
+            }
+            Command::Call(f) => {
+                let element = unsafe{ pipeline.gst_element() as *mut GstElement };
+                Guarded::new(f).call(element, (), |f| f(pipeline));
+            }
+        }
+    }
+}
+
+/// A source that owns the executor and runs the commands as they
+/// arrive
+#[repr(C)]
+struct ExecutorSource{
+    source: GSource,
+    executor: *mut Executor,
+}
+
+static EXECUTOR_SOURCE_FUNCS: GSourceFuncs = GSourceFuncs{
+    prepare: Some(source_prepare),
+    check: Some(source_check),
+    dispatch: Some(source_dispatch),
+    finalize: Some(source_finalize),
+    closure_callback: None,
+    closure_marshal: None,
+};
+
+unsafe fn source_executor<'a>(source: *mut GSource) -> &'a mut Executor{
+    &mut *(*(source as *mut ExecutorSource)).executor
+}
+
+extern "C" fn source_prepare(source: *mut GSource, timeout: *mut gint) -> gboolean{
+    unsafe{
+        // no timeout, the controllers wake up the context
+        *timeout = -1;
+        source_executor(source).ready() as gboolean
+    }
+}
+
+extern "C" fn source_check(source: *mut GSource) -> gboolean{
+    unsafe{
+        source_executor(source).ready() as gboolean
+    }
+}
+
+extern "C" fn source_dispatch(source: *mut GSource, _callback: GSourceFunc, _data: gpointer) -> gboolean{
+    unsafe{
+        let executor = source_executor(source);
+        catch_panic(ptr::null_mut(), (), || executor.run_pending());
+        // G_SOURCE_REMOVE once every controller was dropped
+        (!executor.disconnected) as gboolean
+    }
+}
+
+extern "C" fn source_finalize(source: *mut GSource){
+    unsafe{
+        let source = source as *mut ExecutorSource;
+        if (*source).executor != ptr::null_mut(){
+            let executor = Box::from_raw((*source).executor);
+            (*source).executor = ptr::null_mut();
+            catch_panic(ptr::null_mut(), (), move || drop(executor));
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use ffi::*;
+use util::*;
+
+use pipeline::Pipeline;
+use message::Message;
+use bus::BusSyncReply;
+use reference::Reference;
+use panic::catch_panic;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{self, Sender, Receiver, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Status of a pipeline of a PipelineManager
+#[derive(Clone,Debug,PartialEq)]
+pub struct ManagedStatus{
+    /// Current state of the pipeline
+    pub state: GstState,
+    /// Whether the pipeline should be playing, false once stopped or
+    /// when it ran out of restarts
+    pub running: bool,
+    /// Times the pipeline was restarted after an error
+    pub restarts: u32,
+    /// Message of the last error, cleared when it plays again
+    pub last_error: Option<String>,
+    /// When the next restart is due, if one is scheduled
+    pub restart_at: Option<Instant>,
+}
+
+struct Managed{
+    pipeline: Pipeline,
+    status: ManagedStatus,
+    // failures since the pipeline last played for longer than the
+    // maximum backoff, the delay of the next restart grows with them
+    failures: u32,
+    playing_since: Option<Instant>,
+}
+
+struct Inner{
+    pipelines: HashMap<String, Managed>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_restarts: Option<u32>,
+    restart_on_eos: bool,
+    on_message: Option<Box<dyn FnMut(&str, &Message) + Send>>,
+}
+
+enum Event{
+    Message(String, Message),
+    Wake,
+    Stop,
+}
+
+/// Runs several pipelines, eg: one per camera of an ingest service,
+/// handling all their buses from a single thread and restarting the
+/// ones that fail with an exponential backoff:
+///
+/// ```ignore
+/// let manager = gst::pipeline_manager::PipelineManager::new();
+/// for (i, url) in cameras.iter().enumerate(){
+///     let pipeline = gst::Pipeline::new_from_str(&format!("rtspsrc location={} ! ...", url)).unwrap();
+///     manager.add(&format!("camera{}", i), pipeline);
+/// }
+/// manager.start_all();
+/// for (name, status) in manager.statuses(){
+///     println!("{}: {:?} restarts {}", name, status.state, status.restarts);
+/// }
+/// ```
+///
+/// Messages are taken from the buses with a sync handler, which
+/// replaces any other one, so the application gets them through
+/// connect_message instead of a bus watch.
+pub struct PipelineManager{
+    inner: Arc<Mutex<Inner>>,
+    events: Sender<Event>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PipelineManager{
+    pub fn new() -> PipelineManager{
+        let inner = Arc::new(Mutex::new(Inner{
+            pipelines: HashMap::new(),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            max_restarts: None,
+            restart_on_eos: false,
+            on_message: None,
+        }));
+        let (events, receiver) = mpsc::channel();
+        let thread_inner = inner.clone();
+        let thread = thread::spawn(move || watch(thread_inner, receiver));
+        PipelineManager{
+            inner: inner,
+            events: events,
+            thread: Some(thread),
+        }
+    }
+
+    /// Adds pipeline with name, stopped until start is called. Returns
+    /// false if there's already a pipeline with that name or it has no
+    /// bus
+    pub fn add(&self, name: &str, pipeline: Pipeline) -> bool{
+        let mut inner = self.lock();
+        if inner.pipelines.contains_key(name){
+            return false;
+        }
+        let mut bus = match pipeline.bus(){
+            Some(bus) => bus,
+            None => return false,
+        };
+        let events = self.events.clone();
+        let pipeline_name = name.to_string();
+        bus.set_sync_handler(move |msg: &Message| {
+            let _ = events.send(Event::Message(pipeline_name.clone(), msg.reference()));
+            BusSyncReply::Drop
+        });
+        inner.pipelines.insert(name.to_string(), Managed{
+            pipeline: pipeline,
+            status: ManagedStatus{
+                state: GST_STATE_NULL,
+                running: false,
+                restarts: 0,
+                last_error: None,
+                restart_at: None,
+            },
+            failures: 0,
+            playing_since: None,
+        });
+        true
+    }
+
+    /// Stops the pipeline with name and returns it, its bus is left
+    /// without sync handler
+    pub fn remove(&self, name: &str) -> Option<Pipeline>{
+        let mut managed = self.lock().pipelines.remove(name)?;
+        managed.pipeline.set_state(GST_STATE_NULL);
+        if let Some(mut bus) = managed.pipeline.bus(){
+            bus.unset_sync_handler();
+        }
+        Some(managed.pipeline)
+    }
+
+    /// Sets the pipeline with name to playing, it's restarted from now
+    /// on if it fails. Returns false if there's no such pipeline
+    pub fn start(&self, name: &str) -> bool{
+        let mut inner = self.lock();
+        match inner.pipelines.get_mut(name){
+            Some(managed) => {
+                managed.status.running = true;
+                managed.status.restart_at = None;
+                managed.failures = 0;
+                managed.pipeline.play();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the pipeline with name to NULL and cancels any pending
+    /// restart. Returns false if there's no such pipeline
+    pub fn stop(&self, name: &str) -> bool{
+        let mut inner = self.lock();
+        match inner.pipelines.get_mut(name){
+            Some(managed) => {
+                managed.status.running = false;
+                managed.status.restart_at = None;
+                managed.pipeline.set_state(GST_STATE_NULL);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn start_all(&self){
+        for name in self.names(){
+            self.start(&name);
+        }
+    }
+
+    pub fn stop_all(&self){
+        for name in self.names(){
+            self.stop(&name);
+        }
+    }
+
+    pub fn names(&self) -> Vec<String>{
+        self.lock().pipelines.keys().cloned().collect()
+    }
+
+    /// A new reference to the pipeline with name
+    pub fn pipeline(&self, name: &str) -> Option<Pipeline>{
+        self.lock().pipelines.get(name).map(|managed| managed.pipeline.reference())
+    }
+
+    pub fn status(&self, name: &str) -> Option<ManagedStatus>{
+        self.lock().pipelines.get(name).map(|managed| managed.status.clone())
+    }
+
+    /// The status of every pipeline, sorted by name
+    pub fn statuses(&self) -> Vec<(String, ManagedStatus)>{
+        let mut statuses: Vec<(String, ManagedStatus)> = self.lock().pipelines.iter()
+            .map(|(name, managed)| (name.clone(), managed.status.clone()))
+            .collect();
+        statuses.sort_by(|a, b| a.0.cmp(&b.0));
+        statuses
+    }
+
+    /// Delay before the first restart after an error, doubled on every
+    /// consecutive failure up to max. 1s and 60s by default
+    pub fn set_backoff(&self, initial: Duration, max: Duration){
+        let mut inner = self.lock();
+        inner.initial_backoff = initial;
+        inner.max_backoff = max.max(initial);
+    }
+
+    /// Gives up restarting a pipeline after max consecutive failures,
+    /// None, the default, to retry forever
+    pub fn set_max_restarts(&self, max: Option<u32>){
+        self.lock().max_restarts = max;
+    }
+
+    /// Restarts pipelines that reach EOS too, eg: live sources that
+    /// send EOS when the connection is closed. False by default
+    pub fn set_restart_on_eos(&self, restart: bool){
+        self.lock().restart_on_eos = restart;
+    }
+
+    /// Calls handler with the name of the pipeline and every message
+    /// posted on its bus, from the manager thread
+    pub fn connect_message<F: FnMut(&str, &Message) + Send + 'static>(&self, handler: F){
+        self.lock().on_message = Some(Box::new(handler));
+        let _ = self.events.send(Event::Wake);
+    }
+
+    fn lock<'a>(&'a self) -> ::std::sync::MutexGuard<'a, Inner>{
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for PipelineManager{
+    fn default() -> PipelineManager{
+        PipelineManager::new()
+    }
+}
+
+impl Drop for PipelineManager{
+    fn drop(&mut self){
+        let _ = self.events.send(Event::Stop);
+        if let Some(thread) = self.thread.take(){
+            let _ = thread.join();
+        }
+        for name in self.names(){
+            self.remove(&name);
+        }
+    }
+}
+
+// runs in the manager thread until the PipelineManager is dropped
+fn watch(inner: Arc<Mutex<Inner>>, events: Receiver<Event>){
+    loop{
+        let next_restart = inner.lock().unwrap_or_else(|e| e.into_inner()).pipelines.values()
+            .filter_map(|managed| managed.status.restart_at)
+            .min();
+        let event = match next_restart{
+            Some(at) => {
+                let now = Instant::now();
+                let wait = if at > now { at - now } else { Duration::from_secs(0) };
+                match events.recv_timeout(wait){
+                    Ok(event) => Some(event),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            None => match events.recv(){
+                Ok(event) => Some(event),
+                Err(_) => return,
+            },
+        };
+        match event{
+            Some(Event::Message(name, msg)) => handle_message(&inner, &name, msg),
+            Some(Event::Stop) => return,
+            Some(Event::Wake) | None => (),
+        }
+        restart_due(&inner);
+    }
+}
+
+fn handle_message(inner: &Arc<Mutex<Inner>>, name: &str, msg: Message){
+    let mut on_message = {
+        let mut inner = inner.lock().unwrap_or_else(|e| e.into_inner());
+        let (initial_backoff, max_backoff, max_restarts, restart_on_eos) =
+            (inner.initial_backoff, inner.max_backoff, inner.max_restarts, inner.restart_on_eos);
+        if let Some(managed) = inner.pipelines.get_mut(name){
+            update_status(managed, &msg, initial_backoff, max_backoff, max_restarts, restart_on_eos);
+        }
+        inner.on_message.take()
+    };
+    // without the lock so the handler can use the manager
+    if let Some(ref mut handler) = on_message{
+        catch_panic(ptr::null_mut(), (), || handler(name, &msg));
+    }
+    let mut inner = inner.lock().unwrap_or_else(|e| e.into_inner());
+    if inner.on_message.is_none(){
+        inner.on_message = on_message;
+    }
+}
+
+fn update_status(managed: &mut Managed, msg: &Message, initial_backoff: Duration, max_backoff: Duration, max_restarts: Option<u32>, restart_on_eos: bool){
+    let from_pipeline = unsafe{ msg.src() == managed.pipeline.gst_element() as *mut GstObject };
+    let error = match msg.ty(){
+        GST_MESSAGE_STATE_CHANGED if from_pipeline => {
+            let mut new_state = GST_STATE_VOID_PENDING;
+            unsafe{
+                gst_message_parse_state_changed(msg.gst_message() as *mut GstMessage, ptr::null_mut(), &mut new_state, ptr::null_mut());
+            }
+            managed.status.state = new_state;
+            if new_state == GST_STATE_PLAYING{
+                managed.playing_since = Some(Instant::now());
+                managed.status.last_error = None;
+            }
+            return;
+        }
+        GST_MESSAGE_ERROR => {
+            match msg.parse(){
+                Message::ErrorParsed{ref error, ..} => error.message(),
+                _ => "unknown error".to_string(),
+            }
+        }
+        GST_MESSAGE_EOS if restart_on_eos => "end of stream".to_string(),
+        _ => return,
+    };
+    if !managed.status.running || managed.status.restart_at.is_some(){
+        return;
+    }
+    // a pipeline that played for a while before failing starts again
+    // with the shortest delay
+    if managed.playing_since.map(|since| since.elapsed() > max_backoff).unwrap_or(false){
+        managed.failures = 0;
+    }
+    managed.playing_since = None;
+    managed.status.last_error = Some(error);
+    managed.pipeline.set_state(GST_STATE_NULL);
+    managed.status.state = GST_STATE_NULL;
+    if max_restarts.map(|max| managed.failures >= max).unwrap_or(false){
+        managed.status.running = false;
+        return;
+    }
+    let backoff = initial_backoff.checked_mul(1 << managed.failures.min(16)).unwrap_or(max_backoff).min(max_backoff);
+    managed.failures += 1;
+    managed.status.restart_at = Some(Instant::now() + backoff);
+}
+
+fn restart_due(inner: &Arc<Mutex<Inner>>){
+    let now = Instant::now();
+    let mut inner = inner.lock().unwrap_or_else(|e| e.into_inner());
+    for managed in inner.pipelines.values_mut(){
+        match managed.status.restart_at{
+            Some(at) if at <= now => {
+                managed.status.restart_at = None;
+                managed.status.restarts += 1;
+                managed.pipeline.play();
+            }
+            _ => (),
+        }
+    }
+}
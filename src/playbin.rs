@@ -1,14 +1,15 @@
 use ffi::*;
+use util::*;
 
 use pipeline::Pipeline;
 use element::Element;
 use ::Transfer;
 use reference::Reference;
+use toc::{Toc, TocEntry};
 
 use std::ops::{Deref, DerefMut};
 
-unsafe impl Sync for PlayBin {}
-unsafe impl Send for PlayBin {}
+const CHAPTER_RESTART_THRESHOLD: u64 = 3_000_000_000;
 
 pub struct PlayBin{
     playbin: Pipeline
@@ -123,6 +124,67 @@ impl PlayBin{
     pub fn set_flags(&mut self, flags: i32){
         self.set("flags", flags);
     }
+
+    /// Seeks to the start of a chapter of the stream's Toc. Demuxers
+    /// like matroskademux handle the TOC select event for the uid of
+    /// entry themselves, otherwise this falls back to a seek to its
+    /// start time
+    pub fn seek_to_chapter(&mut self, entry: &TocEntry) -> bool{
+        let cuid = CString::new(entry.uid()).unwrap();
+        unsafe{
+            if gst_element_send_event(self.gst_element_mut(), gst_event_new_toc_select(cuid.as_ptr())) != 0{
+                return true;
+            }
+        }
+        match entry.start(){
+            Some(start) => self.seek_simple(GST_FORMAT_TIME, GST_SEEK_FLAG_FLUSH | GST_SEEK_FLAG_KEY_UNIT, start as i64),
+            None => false
+        }
+    }
+
+    /// The chapter of toc being played
+    pub fn current_chapter(&self, toc: &Toc) -> Option<TocEntry>{
+        let position = match self.position_ns(){
+            Some(position) => position as u64,
+            None => return None
+        };
+        toc.chapters().into_iter()
+            .take_while(|chapter| chapter.start().unwrap() <= position)
+            .last()
+    }
+
+    /// Seeks to the chapter of toc after the current one, false if this
+    /// is the last one
+    pub fn next_chapter(&mut self, toc: &Toc) -> bool{
+        let position = match self.position_ns(){
+            Some(position) => position as u64,
+            None => return false
+        };
+        match toc.chapters().into_iter().find(|chapter| chapter.start().unwrap() > position){
+            Some(chapter) => self.seek_to_chapter(&chapter),
+            None => false
+        }
+    }
+
+    /// Like the previous button of most players, seeks to the start of
+    /// the current chapter of toc if it's been playing for more than 3
+    /// seconds, to the previous chapter otherwise
+    pub fn previous_chapter(&mut self, toc: &Toc) -> bool{
+        let position = match self.position_ns(){
+            Some(position) => position as u64,
+            None => return false
+        };
+        let chapters = toc.chapters();
+        let current = match chapters.iter().rposition(|chapter| chapter.start().unwrap() <= position){
+            Some(current) => current,
+            None => return false
+        };
+        if current > 0 && position - chapters[current].start().unwrap() < CHAPTER_RESTART_THRESHOLD{
+            self.seek_to_chapter(&chapters[current - 1])
+        }else{
+            self.seek_to_chapter(&chapters[current])
+        }
+    }
 }
 
 impl ::Transfer for PlayBin{
@@ -0,0 +1,743 @@
+use ffi::*;
+use util::*;
+use object::Object;
+use element::Element;
+use caps::Caps;
+use taglist::TagList;
+use error::Error;
+use mainloop::MainContext;
+use gobject::GObjectRef;
+use handle::SignalHandle;
+use reference::Reference;
+use value::Value;
+
+use std::ops::{Deref, DerefMut};
+
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum PlayerState{
+    Stopped = GST_PLAYER_STATE_STOPPED,
+    Buffering = GST_PLAYER_STATE_BUFFERING,
+    Paused = GST_PLAYER_STATE_PAUSED,
+    Playing = GST_PLAYER_STATE_PLAYING,
+}
+
+impl PlayerState{
+    fn from_gst_state(state: GstPlayerState) -> PlayerState{
+        match state{
+            GST_PLAYER_STATE_BUFFERING => PlayerState::Buffering,
+            GST_PLAYER_STATE_PAUSED => PlayerState::Paused,
+            GST_PLAYER_STATE_PLAYING => PlayerState::Playing,
+            _ => PlayerState::Stopped,
+        }
+    }
+}
+
+/// Where the signals of a player are delivered
+pub enum SignalDispatcher{
+    /// From the internal thread of the player, handlers have to forward
+    /// what they need to the UI thread themselves
+    PlayerThread,
+    /// From the given context, usually the one the UI main loop runs,
+    /// so handlers can update widgets directly
+    MainContext(MainContext),
+}
+
+/// Renders the video of a player into a native window through the
+/// VideoOverlay interface of the video sink
+#[derive(Clone)]
+pub struct VideoOverlayRenderer{
+    renderer: GObjectRef
+}
+
+impl VideoOverlayRenderer{
+    /// window_handle is the platform specific handle of the window, eg:
+    /// an XID, HWND or NSView, see VideoOverlay::set_window_handle. It
+    /// can be 0 and set later
+    pub fn new(window_handle: usize) -> Option<VideoOverlayRenderer>{
+        unsafe{
+            GObjectRef::new(gst_player_video_overlay_video_renderer_new(window_handle as gpointer) as gpointer)
+                .map(|renderer| VideoOverlayRenderer{ renderer: renderer })
+        }
+    }
+
+    /// Same as new but renders with video_sink instead of the default
+    /// sink for the platform
+    pub fn new_with_sink(window_handle: usize, video_sink: &Element) -> Option<VideoOverlayRenderer>{
+        unsafe{
+            let sink = gst_object_ref(video_sink.gst_element() as gpointer) as *mut GstElement;
+            GObjectRef::new(gst_player_video_overlay_video_renderer_new_with_sink(window_handle as gpointer, sink) as gpointer)
+                .map(|renderer| VideoOverlayRenderer{ renderer: renderer })
+        }
+    }
+
+    pub fn window_handle(&self) -> usize{
+        unsafe{
+            gst_player_video_overlay_video_renderer_get_window_handle(self.gst_renderer()) as usize
+        }
+    }
+
+    pub fn set_window_handle(&mut self, window_handle: usize){
+        unsafe{
+            gst_player_video_overlay_video_renderer_set_window_handle(self.gst_renderer(), window_handle as gpointer);
+        }
+    }
+
+    /// Redraws the last frame, call it when the window is exposed
+    pub fn expose(&mut self){
+        unsafe{
+            gst_player_video_overlay_video_renderer_expose(self.gst_renderer());
+        }
+    }
+
+    /// Area of the window the video is drawn in, -1 for all of them
+    /// uses the whole window
+    pub fn set_render_rectangle(&mut self, x: i32, y: i32, width: i32, height: i32){
+        unsafe{
+            gst_player_video_overlay_video_renderer_set_render_rectangle(self.gst_renderer(), x, y, width, height);
+        }
+    }
+
+    pub unsafe fn gst_renderer(&self) -> *mut GstPlayerVideoOverlayVideoRenderer{
+        self.renderer.object as *mut GstPlayerVideoOverlayVideoRenderer
+    }
+}
+
+/// A ready made playback backend built on playbin, for applications
+/// that need to play media without building pipelines. Progress is
+/// reported through signals:
+///
+/// ```ignore
+/// use gst::player::{Player, SignalDispatcher, VideoOverlayRenderer};
+/// let renderer = VideoOverlayRenderer::new(window_handle).unwrap();
+/// let mut player = Player::new(Some(&renderer), SignalDispatcher::MainContext(gst::MainContext::default())).unwrap();
+/// player.connect_position_updated(|position| println!("{}s", position / 1_000_000_000)).unwrap().forget();
+/// player.connect_end_of_stream(|| println!("done")).unwrap().forget();
+/// player.set_uri("file:///tmp/video.mp4");
+/// player.play();
+/// gst::mainloop::run();
+/// ```
+pub struct Player{
+    player: Object<GstPlayer>
+}
+
+impl Player{
+    /// Creates a player, with renderer as the video output or the
+    /// default video sink if None
+    pub fn new(renderer: Option<&VideoOverlayRenderer>, dispatcher: SignalDispatcher) -> Option<Player>{
+        unsafe{
+            // the player takes ownership of the renderer and dispatcher
+            let renderer = match renderer{
+                Some(renderer) => g_object_ref(renderer.renderer.object) as *mut GstPlayerVideoRenderer,
+                None => ptr::null_mut(),
+            };
+            let dispatcher = match dispatcher{
+                SignalDispatcher::PlayerThread => ptr::null_mut(),
+                SignalDispatcher::MainContext(mut context) =>
+                    gst_player_g_main_context_signal_dispatcher_new(context.gst_maincontext_mut()),
+            };
+            Object::new(gst_player_new(renderer, dispatcher))
+                .map(|obj| Player{ player: obj })
+        }
+    }
+
+    pub fn play(&mut self){
+        unsafe{
+            gst_player_play(self.gst_player_mut());
+        }
+    }
+
+    pub fn pause(&mut self){
+        unsafe{
+            gst_player_pause(self.gst_player_mut());
+        }
+    }
+
+    /// Stops playback and releases the resources, play restarts from
+    /// the beginning
+    pub fn stop(&mut self){
+        unsafe{
+            gst_player_stop(self.gst_player_mut());
+        }
+    }
+
+    /// Seeks to position in nanoseconds, "seek-done" is emitted once
+    /// it's done
+    pub fn seek(&mut self, position: u64){
+        unsafe{
+            gst_player_seek(self.gst_player_mut(), position);
+        }
+    }
+
+    pub fn rate(&self) -> f64{
+        unsafe{
+            gst_player_get_rate(self.gst_player() as *mut GstPlayer)
+        }
+    }
+
+    pub fn set_rate(&mut self, rate: f64){
+        unsafe{
+            gst_player_set_rate(self.gst_player_mut(), rate);
+        }
+    }
+
+    pub fn uri(&self) -> Option<String>{
+        unsafe{
+            owned_string(gst_player_get_uri(self.gst_player() as *mut GstPlayer))
+        }
+    }
+
+    pub fn set_uri(&mut self, uri: &str){
+        let curi = CString::new(uri).unwrap();
+        unsafe{
+            gst_player_set_uri(self.gst_player_mut(), curi.as_ptr());
+        }
+    }
+
+    pub fn subtitle_uri(&self) -> Option<String>{
+        unsafe{
+            owned_string(gst_player_get_subtitle_uri(self.gst_player() as *mut GstPlayer))
+        }
+    }
+
+    /// Sets an external subtitle file for the current uri
+    pub fn set_subtitle_uri(&mut self, uri: &str){
+        let curi = CString::new(uri).unwrap();
+        unsafe{
+            gst_player_set_subtitle_uri(self.gst_player_mut(), curi.as_ptr());
+        }
+    }
+
+    /// Position in nanoseconds, None if unknown
+    pub fn position(&self) -> Option<u64>{
+        unsafe{
+            time_to_option(gst_player_get_position(self.gst_player() as *mut GstPlayer))
+        }
+    }
+
+    /// Duration in nanoseconds, None if unknown
+    pub fn duration(&self) -> Option<u64>{
+        unsafe{
+            time_to_option(gst_player_get_duration(self.gst_player() as *mut GstPlayer))
+        }
+    }
+
+    /// Linear volume, 1.0 is 100%
+    pub fn volume(&self) -> f64{
+        unsafe{
+            gst_player_get_volume(self.gst_player() as *mut GstPlayer)
+        }
+    }
+
+    pub fn set_volume(&mut self, volume: f64){
+        unsafe{
+            gst_player_set_volume(self.gst_player_mut(), volume);
+        }
+    }
+
+    pub fn is_muted(&self) -> bool{
+        unsafe{
+            gst_player_get_mute(self.gst_player() as *mut GstPlayer) != 0
+        }
+    }
+
+    pub fn set_mute(&mut self, mute: bool){
+        unsafe{
+            gst_player_set_mute(self.gst_player_mut(), mute as gboolean);
+        }
+    }
+
+    /// Streams and metadata of the current uri, None until it's loaded
+    pub fn media_info(&self) -> Option<PlayerMediaInfo>{
+        unsafe{
+            PlayerMediaInfo::new(gst_player_get_media_info(self.gst_player() as *mut GstPlayer))
+        }
+    }
+
+    /// Selects the audio stream with the index of its PlayerStreamInfo
+    pub fn set_audio_track(&mut self, index: i32) -> bool{
+        unsafe{
+            gst_player_set_audio_track(self.gst_player_mut(), index) != 0
+        }
+    }
+
+    pub fn set_video_track(&mut self, index: i32) -> bool{
+        unsafe{
+            gst_player_set_video_track(self.gst_player_mut(), index) != 0
+        }
+    }
+
+    pub fn set_subtitle_track(&mut self, index: i32) -> bool{
+        unsafe{
+            gst_player_set_subtitle_track(self.gst_player_mut(), index) != 0
+        }
+    }
+
+    pub fn set_audio_track_enabled(&mut self, enabled: bool){
+        unsafe{
+            gst_player_set_audio_track_enabled(self.gst_player_mut(), enabled as gboolean);
+        }
+    }
+
+    pub fn set_video_track_enabled(&mut self, enabled: bool){
+        unsafe{
+            gst_player_set_video_track_enabled(self.gst_player_mut(), enabled as gboolean);
+        }
+    }
+
+    pub fn set_subtitle_track_enabled(&mut self, enabled: bool){
+        unsafe{
+            gst_player_set_subtitle_track_enabled(self.gst_player_mut(), enabled as gboolean);
+        }
+    }
+
+    /// How often "position-updated" is emitted, in milliseconds, 0
+    /// disables it. Defaults to 100ms
+    pub fn set_position_update_interval(&mut self, interval: u32) -> bool{
+        unsafe{
+            let config = gst_player_get_config(self.gst_player_mut());
+            gst_player_config_set_position_update_interval(config, interval);
+            gst_player_set_config(self.gst_player_mut(), config) != 0
+        }
+    }
+
+    /// The playbin the player uses, to tweak it beyond what the player
+    /// exposes
+    pub fn pipeline(&self) -> Option<Element>{
+        unsafe{
+            Element::new_from_gst_element(gst_player_get_pipeline(self.gst_player() as *mut GstPlayer))
+        }
+    }
+
+    /// Emitted when a new uri starts loading
+    pub fn connect_uri_loaded<F: FnMut(&str) + Send + 'static>(&mut self, mut f: F) -> Option<SignalHandle>{
+        self.connect_values("uri-loaded", move |args| {
+            if let Some(uri) = args.get(1).and_then(|uri| uri.get::<String>()){
+                f(&uri);
+            }
+            None
+        })
+    }
+
+    /// Emitted periodically with the position in nanoseconds, see
+    /// set_position_update_interval
+    pub fn connect_position_updated<F: FnMut(u64) + Send + 'static>(&mut self, f: F) -> Option<SignalHandle>{
+        self.connect_time("position-updated", f)
+    }
+
+    /// Emitted with the new duration in nanoseconds
+    pub fn connect_duration_changed<F: FnMut(u64) + Send + 'static>(&mut self, f: F) -> Option<SignalHandle>{
+        self.connect_time("duration-changed", f)
+    }
+
+    /// Emitted with the position reached after a seek
+    pub fn connect_seek_done<F: FnMut(u64) + Send + 'static>(&mut self, f: F) -> Option<SignalHandle>{
+        self.connect_time("seek-done", f)
+    }
+
+    pub fn connect_state_changed<F: FnMut(PlayerState) + Send + 'static>(&mut self, mut f: F) -> Option<SignalHandle>{
+        self.connect_values("state-changed", move |args| {
+            if let Some(state) = args.get(1).and_then(|state| state.enum_value()){
+                f(PlayerState::from_gst_state(state as GstPlayerState));
+            }
+            None
+        })
+    }
+
+    /// Emitted with the buffering percentage while buffering network
+    /// streams
+    pub fn connect_buffering<F: FnMut(i32) + Send + 'static>(&mut self, mut f: F) -> Option<SignalHandle>{
+        self.connect_values("buffering", move |args| {
+            if let Some(percent) = args.get(1).and_then(|percent| percent.get::<i32>()){
+                f(percent);
+            }
+            None
+        })
+    }
+
+    pub fn connect_end_of_stream<F: FnMut() + Send + 'static>(&mut self, mut f: F) -> Option<SignalHandle>{
+        self.connect_values("end-of-stream", move |_args| {
+            f();
+            None
+        })
+    }
+
+    /// Emitted on errors, playback stops
+    pub fn connect_error<F: FnMut(Error) + Send + 'static>(&mut self, f: F) -> Option<SignalHandle>{
+        self.connect_error_signal("error", f)
+    }
+
+    pub fn connect_warning<F: FnMut(Error) + Send + 'static>(&mut self, f: F) -> Option<SignalHandle>{
+        self.connect_error_signal("warning", f)
+    }
+
+    /// Emitted with the width and height of the video when they change
+    pub fn connect_video_dimensions_changed<F: FnMut(i32, i32) + Send + 'static>(&mut self, mut f: F) -> Option<SignalHandle>{
+        self.connect_values("video-dimensions-changed", move |args| {
+            let width = args.get(1).and_then(|width| width.get::<i32>());
+            let height = args.get(2).and_then(|height| height.get::<i32>());
+            if let (Some(width), Some(height)) = (width, height){
+                f(width, height);
+            }
+            None
+        })
+    }
+
+    /// Emitted when the streams or metadata of the uri change
+    pub fn connect_media_info_updated<F: FnMut(PlayerMediaInfo) + Send + 'static>(&mut self, mut f: F) -> Option<SignalHandle>{
+        self.connect_values("media-info-updated", move |args| {
+            let info = args.get(1).and_then(|info| unsafe{
+                PlayerMediaInfo::new_ref(g_value_get_object(info.gst_value()) as *mut GstPlayerMediaInfo)
+            });
+            if let Some(info) = info{
+                f(info);
+            }
+            None
+        })
+    }
+
+    fn connect_time<F: FnMut(u64) + Send + 'static>(&mut self, signal: &str, mut f: F) -> Option<SignalHandle>{
+        self.connect_values(signal, move |args| {
+            if let Some(time) = args.get(1).and_then(|time| time.get::<u64>()){
+                f(time);
+            }
+            None
+        })
+    }
+
+    fn connect_error_signal<F: FnMut(Error) + Send + 'static>(&mut self, signal: &str, mut f: F) -> Option<SignalHandle>{
+        self.connect_values(signal, move |args| {
+            if let Some(error) = args.get(1).and_then(error_from_value){
+                f(error);
+            }
+            None
+        })
+    }
+
+    pub unsafe fn gst_player(&self) -> *const GstPlayer{
+        self.player.as_ptr()
+    }
+
+    pub unsafe fn gst_player_mut(&mut self) -> *mut GstPlayer{
+        self.player.as_mut_ptr()
+    }
+}
+
+impl Reference for Player{
+    fn reference(&self) -> Player{
+        Player{ player: self.player.reference() }
+    }
+}
+
+impl AsRef<Object> for Player{
+    fn as_ref(&self) -> &Object{
+        self.player.upcast_ref()
+    }
+}
+
+impl AsMut<Object> for Player{
+    fn as_mut(&mut self) -> &mut Object{
+        self.player.upcast_mut()
+    }
+}
+
+impl Deref for Player{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.player.upcast_ref()
+    }
+}
+
+impl DerefMut for Player{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.player.upcast_mut()
+    }
+}
+
+/// Streams and metadata of the uri being played
+#[derive(Clone)]
+pub struct PlayerMediaInfo{
+    info: GObjectRef
+}
+
+impl PlayerMediaInfo{
+    pub unsafe fn new(info: *mut GstPlayerMediaInfo) -> Option<PlayerMediaInfo>{
+        GObjectRef::new(info as gpointer).map(|info| PlayerMediaInfo{ info: info })
+    }
+
+    pub unsafe fn new_ref(info: *mut GstPlayerMediaInfo) -> Option<PlayerMediaInfo>{
+        GObjectRef::new_ref(info as gpointer).map(|info| PlayerMediaInfo{ info: info })
+    }
+
+    pub fn uri(&self) -> &str{
+        unsafe{
+            from_c_str!(gst_player_media_info_get_uri(self.gst_media_info()))
+        }
+    }
+
+    pub fn title(&self) -> Option<&str>{
+        unsafe{
+            optional_str(gst_player_media_info_get_title(self.gst_media_info()))
+        }
+    }
+
+    pub fn container_format(&self) -> Option<&str>{
+        unsafe{
+            optional_str(gst_player_media_info_get_container_format(self.gst_media_info()))
+        }
+    }
+
+    /// Duration in nanoseconds, None if unknown
+    pub fn duration(&self) -> Option<u64>{
+        unsafe{
+            time_to_option(gst_player_media_info_get_duration(self.gst_media_info()))
+        }
+    }
+
+    pub fn is_seekable(&self) -> bool{
+        unsafe{
+            gst_player_media_info_is_seekable(self.gst_media_info()) != 0
+        }
+    }
+
+    pub fn is_live(&self) -> bool{
+        unsafe{
+            gst_player_media_info_is_live(self.gst_media_info()) != 0
+        }
+    }
+
+    pub fn tags(&self) -> Option<TagList>{
+        unsafe{
+            taglist_from_borrowed(gst_player_media_info_get_tags(self.gst_media_info()))
+        }
+    }
+
+    pub fn video_streams(&self) -> Vec<PlayerVideoInfo>{
+        unsafe{
+            stream_infos_from_list(gst_player_media_info_get_video_streams(self.gst_media_info()))
+                .into_iter().map(|info| PlayerVideoInfo{ info: info }).collect()
+        }
+    }
+
+    pub fn audio_streams(&self) -> Vec<PlayerAudioInfo>{
+        unsafe{
+            stream_infos_from_list(gst_player_media_info_get_audio_streams(self.gst_media_info()))
+                .into_iter().map(|info| PlayerAudioInfo{ info: info }).collect()
+        }
+    }
+
+    pub fn subtitle_streams(&self) -> Vec<PlayerSubtitleInfo>{
+        unsafe{
+            stream_infos_from_list(gst_player_media_info_get_subtitle_streams(self.gst_media_info()))
+                .into_iter().map(|info| PlayerSubtitleInfo{ info: info }).collect()
+        }
+    }
+
+    pub unsafe fn gst_media_info(&self) -> *const GstPlayerMediaInfo{
+        self.info.object as *const GstPlayerMediaInfo
+    }
+}
+
+/// Information common to all the streams of a PlayerMediaInfo
+#[derive(Clone)]
+pub struct PlayerStreamInfo{
+    info: GObjectRef
+}
+
+impl PlayerStreamInfo{
+    /// Index to select the stream with set_audio_track,
+    /// set_video_track or set_subtitle_track
+    pub fn index(&self) -> i32{
+        unsafe{
+            gst_player_stream_info_get_index(self.gst_stream_info())
+        }
+    }
+
+    /// "video", "audio" or "subtitle"
+    pub fn stream_type(&self) -> &str{
+        unsafe{
+            from_c_str!(gst_player_stream_info_get_stream_type(self.gst_stream_info()))
+        }
+    }
+
+    pub fn codec(&self) -> Option<&str>{
+        unsafe{
+            optional_str(gst_player_stream_info_get_codec(self.gst_stream_info()))
+        }
+    }
+
+    pub fn caps(&self) -> Option<Caps>{
+        unsafe{
+            let caps = gst_player_stream_info_get_caps(self.gst_stream_info());
+            if caps != ptr::null_mut(){
+                gst_mini_object_ref(caps as *mut GstMiniObject);
+            }
+            Caps::new(caps)
+        }
+    }
+
+    pub fn tags(&self) -> Option<TagList>{
+        unsafe{
+            taglist_from_borrowed(gst_player_stream_info_get_tags(self.gst_stream_info()))
+        }
+    }
+
+    pub unsafe fn gst_stream_info(&self) -> *const GstPlayerStreamInfo{
+        self.info.object as *const GstPlayerStreamInfo
+    }
+}
+
+#[derive(Clone)]
+pub struct PlayerVideoInfo{
+    info: PlayerStreamInfo
+}
+
+impl PlayerVideoInfo{
+    pub fn width(&self) -> i32{
+        unsafe{ gst_player_video_info_get_width(self.gst_video_info()) }
+    }
+
+    pub fn height(&self) -> i32{
+        unsafe{ gst_player_video_info_get_height(self.gst_video_info()) }
+    }
+
+    /// Framerate as numerator, denominator
+    pub fn framerate(&self) -> (i32, i32){
+        let (mut fps_n, mut fps_d) = (0, 1);
+        unsafe{
+            gst_player_video_info_get_framerate(self.gst_video_info(), &mut fps_n, &mut fps_d);
+        }
+        (fps_n, fps_d)
+    }
+
+    /// Bitrate in bits per second, -1 if unknown
+    pub fn bitrate(&self) -> i32{
+        unsafe{ gst_player_video_info_get_bitrate(self.gst_video_info()) }
+    }
+
+    unsafe fn gst_video_info(&self) -> *const GstPlayerVideoInfo{
+        self.gst_stream_info() as *const GstPlayerVideoInfo
+    }
+}
+
+#[derive(Clone)]
+pub struct PlayerAudioInfo{
+    info: PlayerStreamInfo
+}
+
+impl PlayerAudioInfo{
+    pub fn channels(&self) -> i32{
+        unsafe{ gst_player_audio_info_get_channels(self.gst_audio_info()) }
+    }
+
+    pub fn sample_rate(&self) -> i32{
+        unsafe{ gst_player_audio_info_get_sample_rate(self.gst_audio_info()) }
+    }
+
+    /// Bitrate in bits per second, -1 if unknown
+    pub fn bitrate(&self) -> i32{
+        unsafe{ gst_player_audio_info_get_bitrate(self.gst_audio_info()) }
+    }
+
+    /// ISO-639 language code, if known
+    pub fn language(&self) -> Option<&str>{
+        unsafe{ optional_str(gst_player_audio_info_get_language(self.gst_audio_info())) }
+    }
+
+    unsafe fn gst_audio_info(&self) -> *const GstPlayerAudioInfo{
+        self.gst_stream_info() as *const GstPlayerAudioInfo
+    }
+}
+
+#[derive(Clone)]
+pub struct PlayerSubtitleInfo{
+    info: PlayerStreamInfo
+}
+
+impl PlayerSubtitleInfo{
+    /// ISO-639 language code, if known
+    pub fn language(&self) -> Option<&str>{
+        unsafe{ optional_str(gst_player_subtitle_info_get_language(self.gst_stream_info() as *const GstPlayerSubtitleInfo)) }
+    }
+}
+
+impl Deref for PlayerVideoInfo{
+    type Target = PlayerStreamInfo;
+    fn deref(&self) -> &PlayerStreamInfo{
+        &self.info
+    }
+}
+
+impl Deref for PlayerAudioInfo{
+    type Target = PlayerStreamInfo;
+    fn deref(&self) -> &PlayerStreamInfo{
+        &self.info
+    }
+}
+
+impl Deref for PlayerSubtitleInfo{
+    type Target = PlayerStreamInfo;
+    fn deref(&self) -> &PlayerStreamInfo{
+        &self.info
+    }
+}
+
+#[inline]
+fn time_to_option(time: GstClockTime) -> Option<u64>{
+    if time != GST_CLOCK_TIME_NONE{
+        Some(time)
+    }else{
+        None
+    }
+}
+
+unsafe fn owned_string(s: *mut gchar) -> Option<String>{
+    if s != ptr::null_mut(){
+        let ret = from_c_str!(s).to_string();
+        g_free(s as gpointer);
+        Some(ret)
+    }else{
+        None
+    }
+}
+
+unsafe fn optional_str<'a>(s: *const gchar) -> Option<&'a str>{
+    if s != ptr::null(){
+        Some(from_c_str!(s))
+    }else{
+        None
+    }
+}
+
+unsafe fn taglist_from_borrowed(tags: *mut GstTagList) -> Option<TagList>{
+    if tags != ptr::null_mut(){
+        gst_mini_object_ref(tags as *mut GstMiniObject);
+    }
+    TagList::new_from_gst_taglist(tags)
+}
+
+/// The stream lists are owned by the media info, each info is reffed
+unsafe fn stream_infos_from_list(mut node: *mut GList) -> Vec<PlayerStreamInfo>{
+    let mut infos = vec![];
+    while node != ptr::null_mut(){
+        if let Some(info) = GObjectRef::new_ref((*node).data){
+            infos.push(PlayerStreamInfo{ info: info });
+        }
+        node = (*node).next;
+    }
+    infos
+}
+
+fn error_from_value(value: &Value) -> Option<Error>{
+    unsafe{
+        if !value.holds(g_error_get_type()){
+            return None;
+        }
+        let error = g_value_get_boxed(value.gst_value()) as *const GError;
+        if error != ptr::null(){
+            Some(Error::new_from_g_error(g_error_copy(error)))
+        }else{
+            None
+        }
+    }
+}
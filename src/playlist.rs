@@ -0,0 +1,522 @@
+use ffi::*;
+
+use bus::{Bus, BusSyncReply};
+use handle::SignalHandle;
+use mainloop::MainContext;
+use message::Message;
+use object::Object;
+use panic::catch_panic;
+use playbin::PlayBin;
+use reference::Reference;
+use structure::Structure;
+
+use std::ptr;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Name of the application message posted on the bus of the playbin
+/// when a new track starts playing, see Playlist::track_changed
+pub const TRACK_CHANGED: &'static str = "playlist-track-changed";
+
+struct Inner{
+    uris: Vec<String>,
+    // track playing now
+    current: Option<usize>,
+    // track set on playbin that becomes current once it starts
+    pending: Option<usize>,
+    repeat: bool,
+}
+
+impl Inner{
+    fn new() -> Inner{
+        Inner{
+            uris: vec![],
+            current: None,
+            pending: None,
+            repeat: false,
+        }
+    }
+
+    fn next_index(&self, from: Option<usize>) -> Option<usize>{
+        let next = from.map(|index| index + 1).unwrap_or(0);
+        if next < self.uris.len(){
+            Some(next)
+        }else if self.repeat && !self.uris.is_empty(){
+            Some(0)
+        }else{
+            None
+        }
+    }
+
+    fn previous_index(&self) -> Option<usize>{
+        match self.current{
+            Some(current) if current > 0 => Some(current - 1),
+            Some(_) if self.repeat && !self.uris.is_empty() => Some(self.uris.len() - 1),
+            _ => None,
+        }
+    }
+
+    // the track to set on playbin when the one playing or pending is
+    // about to finish, it becomes pending
+    fn queue_next(&mut self) -> Option<usize>{
+        let from = self.pending.or(self.current);
+        let next = self.next_index(from);
+        if next.is_some(){
+            self.pending = next;
+        }
+        next
+    }
+
+    // the pending track started playing, returns its index and uri
+    fn start_pending(&mut self) -> Option<(usize, String)>{
+        match self.pending.take(){
+            Some(index) if index < self.uris.len() => {
+                self.current = Some(index);
+                Some((index, self.uris[index].clone()))
+            }
+            _ => None,
+        }
+    }
+
+    fn insert(&mut self, index: usize, uri: &str){
+        let index = index.min(self.uris.len());
+        self.uris.insert(index, uri.to_string());
+        if let Some(ref mut current) = self.current{
+            if *current >= index{
+                *current += 1;
+            }
+        }
+        if let Some(ref mut pending) = self.pending{
+            if *pending >= index{
+                *pending += 1;
+            }
+        }
+    }
+
+    fn remove(&mut self, index: usize) -> Option<String>{
+        if index >= self.uris.len(){
+            return None;
+        }
+        let uri = self.uris.remove(index);
+        self.current = match self.current{
+            Some(current) if current == index => None,
+            Some(current) if current > index => Some(current - 1),
+            current => current,
+        };
+        self.pending = match self.pending{
+            Some(pending) if pending == index => None,
+            Some(pending) if pending > index => Some(pending - 1),
+            pending => pending,
+        };
+        Some(uri)
+    }
+}
+
+/// A queue of URIs played one after the other by a playbin without
+/// gaps between them, the next URI is set on playbin when it's about to
+/// finish the current one so it's prerolled in time:
+///
+/// ```ignore
+/// let mut playlist = gst::playlist::Playlist::new(gst::PlayBin::new("player").unwrap()).unwrap();
+/// playlist.add("file:///music/01.flac");
+/// playlist.add("file:///music/02.flac");
+/// playlist.play();
+/// for msg in playlist.playbin().bus().unwrap().receiver().iter(){
+///     if let Some((index, uri)) = gst::playlist::Playlist::track_changed(&msg){
+///         println!("playing {}: {}", index, uri);
+///     }
+/// }
+/// ```
+///
+/// Track changes are detected with a sync handler on the bus of the
+/// playbin, which replaces any other one while the playlist exists, and
+/// posted as TRACK_CHANGED application messages from the thread-default
+/// main context of the thread that created the playlist, so it needs to
+/// be running like for the watches of the bus.
+pub struct Playlist{
+    playbin: PlayBin,
+    bus: Bus,
+    inner: Arc<Mutex<Inner>>,
+    _about_to_finish: SignalHandle,
+}
+
+impl Playlist{
+    /// Plays the playlist with playbin, None if it has no bus
+    pub fn new(mut playbin: PlayBin) -> Option<Playlist>{
+        let inner = Arc::new(Mutex::new(Inner::new()));
+
+        // called from the streaming thread when the current uri is
+        // almost over, setting the uri here plays it without a gap
+        let finish_inner = inner.clone();
+        let about_to_finish = playbin.connect_values("about-to-finish", move |args| {
+            if let Some(mut playbin) = args.get(0).and_then(|arg| arg.get::<Object>()){
+                let mut inner = lock(&finish_inner);
+                if let Some(next) = inner.queue_next(){
+                    playbin.set("uri", &inner.uris[next][..]);
+                }
+            }
+            None
+        })?;
+
+        let mut bus = playbin.bus()?;
+        let sync_inner = inner.clone();
+        let post_bus = bus.reference();
+        let src = {
+            let object: &Object = &playbin;
+            object.reference()
+        };
+        // the watches of the bus are dispatched from the context of
+        // the thread creating the playlist
+        let context = MainContext::thread_default();
+        bus.set_sync_handler(move |msg: &Message| {
+            if msg.ty() == GST_MESSAGE_STREAM_START{
+                let changed = lock(&sync_inner).start_pending();
+                if let Some((index, uri)) = changed{
+                    // posting from the sync handler would call it again
+                    // from inside itself, the message is posted from the
+                    // main context instead
+                    post_track_changed(&context, TrackChanged{
+                        bus: post_bus.reference(),
+                        src: src.reference(),
+                        index: index,
+                        uri: uri,
+                    });
+                }
+            }
+            BusSyncReply::Pass
+        });
+
+        Some(Playlist{
+            playbin: playbin,
+            bus: bus,
+            inner: inner,
+            _about_to_finish: about_to_finish,
+        })
+    }
+
+    /// The index and uri of the new track if msg is a TRACK_CHANGED
+    /// message
+    pub fn track_changed(msg: &Message) -> Option<(usize, String)>{
+        let structure = msg.get_structure()?;
+        if structure.name() != TRACK_CHANGED{
+            return None;
+        }
+        let index = structure.get::<u32>("index")?;
+        let uri = structure.get_string("uri")?.to_string();
+        Some((index as usize, uri))
+    }
+
+    /// Adds uri at the end of the playlist
+    pub fn add(&mut self, uri: &str){
+        self.lock().uris.push(uri.to_string());
+    }
+
+    /// Inserts uri before the track at index, or at the end if index is
+    /// past it
+    pub fn insert(&mut self, index: usize, uri: &str){
+        self.lock().insert(index, uri);
+    }
+
+    /// Removes the track at index, the current track keeps playing if
+    /// it's the one removed. Returns its uri or None if there's no such
+    /// track
+    pub fn remove(&mut self, index: usize) -> Option<String>{
+        self.lock().remove(index)
+    }
+
+    /// Stops playback and empties the playlist
+    pub fn clear(&mut self){
+        self.playbin.set_state(GST_STATE_NULL);
+        let mut inner = self.lock();
+        inner.uris.clear();
+        inner.current = None;
+        inner.pending = None;
+    }
+
+    pub fn uris(&self) -> Vec<String>{
+        self.lock().uris.clone()
+    }
+
+    pub fn len(&self) -> usize{
+        self.lock().uris.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.lock().uris.is_empty()
+    }
+
+    /// Index of the track playing now
+    pub fn current(&self) -> Option<usize>{
+        self.lock().current
+    }
+
+    pub fn current_uri(&self) -> Option<String>{
+        let inner = self.lock();
+        inner.current.map(|current| inner.uris[current].clone())
+    }
+
+    /// Starts again from the first track after the last one instead of
+    /// ending with EOS. False by default
+    pub fn set_repeat(&mut self, repeat: bool){
+        self.lock().repeat = repeat;
+    }
+
+    pub fn repeat(&self) -> bool{
+        self.lock().repeat
+    }
+
+    /// Plays the track at index straight away, returns false if there's
+    /// no such track
+    pub fn play_index(&mut self, index: usize) -> bool{
+        let uri = {
+            let mut inner = self.lock();
+            match inner.uris.get(index).cloned(){
+                Some(uri) => {
+                    inner.pending = Some(index);
+                    uri
+                }
+                None => return false,
+            }
+        };
+        self.playbin.set_state(GST_STATE_READY);
+        self.playbin.set_uri(&uri);
+        self.playbin.play();
+        true
+    }
+
+    /// Starts or resumes playback, from the first track if none was
+    /// played yet
+    pub fn play(&mut self) -> bool{
+        let current = self.lock().current;
+        match current{
+            Some(_) => {
+                self.playbin.play();
+                true
+            }
+            None => self.play_index(0),
+        }
+    }
+
+    /// Skips to the next track, returns false if the current one is the
+    /// last and repeat is disabled
+    pub fn next(&mut self) -> bool{
+        let next = {
+            let inner = self.lock();
+            inner.next_index(inner.current)
+        };
+        match next{
+            Some(next) => self.play_index(next),
+            None => false,
+        }
+    }
+
+    /// Goes back to the previous track, or to the last one from the
+    /// first if repeat is enabled
+    pub fn previous(&mut self) -> bool{
+        let previous = self.lock().previous_index();
+        match previous{
+            Some(previous) => self.play_index(previous),
+            None => false,
+        }
+    }
+
+    pub fn playbin(&self) -> &PlayBin{
+        &self.playbin
+    }
+
+    pub fn playbin_mut(&mut self) -> &mut PlayBin{
+        &mut self.playbin
+    }
+
+    fn lock<'a>(&'a self) -> MutexGuard<'a, Inner>{
+        lock(&self.inner)
+    }
+}
+
+impl Drop for Playlist{
+    fn drop(&mut self){
+        self.bus.unset_sync_handler();
+    }
+}
+
+fn lock<'a>(inner: &'a Arc<Mutex<Inner>>) -> MutexGuard<'a, Inner>{
+    inner.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+struct TrackChanged{
+    bus: Bus,
+    src: Object,
+    index: usize,
+    uri: String,
+}
+
+// posts changed on its bus from an idle source of context, the sync
+// handler runs from the streaming thread which could also acquire the
+// context so g_main_context_invoke isn't enough
+fn post_track_changed(context: &MainContext, changed: TrackChanged){
+    unsafe{
+        let changed = Box::into_raw(Box::new(changed));
+        let source = g_idle_source_new();
+        g_source_set_callback(source, Some(track_changed_callback), changed as gpointer, Some(track_changed_destroy));
+        g_source_attach(source, context.gst_maincontext() as *mut GMainContext);
+        g_source_unref(source);
+    }
+}
+
+extern "C" fn track_changed_callback(data: gpointer) -> gboolean{
+    unsafe{
+        catch_panic(ptr::null_mut(), (), || {
+            let changed = &mut *(data as *mut TrackChanged);
+            let mut structure = Structure::new(TRACK_CHANGED);
+            structure.set("index", &(changed.index as u32));
+            structure.set("uri", &changed.uri);
+            if let Some(msg) = Message::application(Some(&changed.src), &structure){
+                changed.bus.post(msg);
+            }
+        });
+    }
+    0
+}
+
+extern "C" fn track_changed_destroy(data: gpointer){
+    unsafe{
+        catch_panic(ptr::null_mut(), (), || drop(Box::from_raw(data as *mut TrackChanged)));
+    }
+}
+
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use element::Element;
+    use std::fs::File;
+    use std::io::Write;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    fn inner(n: usize) -> Inner{
+        let mut inner = Inner::new();
+        for i in 0..n{
+            inner.uris.push(format!("file:///{}", i));
+        }
+        inner
+    }
+
+    #[test]
+    fn insert_shifts_current_and_pending(){
+        let mut inner = inner(3);
+        inner.current = Some(1);
+        inner.pending = Some(2);
+        inner.insert(1, "file:///new");
+        assert_eq!(inner.uris[1], "file:///new");
+        assert_eq!((inner.current, inner.pending), (Some(2), Some(3)));
+        // after both and past the end
+        inner.insert(10, "file:///last");
+        assert_eq!(inner.uris.last().unwrap(), "file:///last");
+        assert_eq!((inner.current, inner.pending), (Some(2), Some(3)));
+    }
+
+    #[test]
+    fn remove_shifts_current_and_pending(){
+        let mut inner = inner(4);
+        inner.current = Some(2);
+        inner.pending = Some(3);
+        assert_eq!(inner.remove(0), Some("file:///0".to_string()));
+        assert_eq!((inner.current, inner.pending), (Some(1), Some(2)));
+        assert_eq!(inner.remove(3), None);
+        assert_eq!(inner.remove(1), Some("file:///2".to_string()));
+        assert_eq!((inner.current, inner.pending), (None, Some(1)));
+        assert_eq!(inner.remove(1), Some("file:///3".to_string()));
+        assert_eq!((inner.current, inner.pending), (None, None));
+    }
+
+    #[test]
+    fn queue_and_start(){
+        let mut inner = inner(2);
+        assert_eq!(inner.queue_next(), Some(0));
+        assert_eq!(inner.start_pending(), Some((0, "file:///0".to_string())));
+        assert_eq!(inner.start_pending(), None);
+        assert_eq!(inner.queue_next(), Some(1));
+        // a pending track removed before it starts isn't reported
+        inner.remove(1);
+        assert_eq!(inner.start_pending(), None);
+        assert_eq!(inner.current, Some(0));
+        assert_eq!(inner.queue_next(), None);
+    }
+
+    #[test]
+    fn repeat(){
+        let mut inner = inner(2);
+        inner.current = Some(1);
+        assert_eq!(inner.next_index(inner.current), None);
+        inner.current = Some(0);
+        assert_eq!(inner.previous_index(), None);
+        inner.repeat = true;
+        assert_eq!(inner.previous_index(), Some(1));
+        inner.current = Some(1);
+        assert_eq!(inner.next_index(inner.current), Some(0));
+        assert_eq!(Inner::new().next_index(None), None);
+    }
+
+    // 100ms of silence as 8kHz mono 16 bit pcm
+    fn write_wav(path: &::std::path::Path){
+        let data_len = 1600u32;
+        let mut wav = vec![];
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&8000u32.to_le_bytes());
+        wav.extend_from_slice(&16000u32.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        wav.extend(vec![0; data_len as usize]);
+        File::create(path).unwrap().write_all(&wav).unwrap();
+    }
+
+    #[test]
+    fn posts_track_changed(){
+        ::init();
+        let dir = ::std::env::temp_dir();
+        let uris: Vec<String> = (0..2).map(|i| {
+            let path = dir.join(format!("gstreamer1.0-rs-playlist-{}-{}.wav", ::std::process::id(), i));
+            write_wav(&path);
+            format!("file://{}", path.display())
+        }).collect();
+
+        let mut playbin = PlayBin::new("playlist-test").unwrap();
+        let mut sink = Element::new("fakesink", "playlist-test-sink").unwrap();
+        sink.set("sync", false as gboolean);
+        playbin.set_audio_sink(&sink);
+        let mut playlist = Playlist::new(playbin).unwrap();
+        for uri in &uris{
+            playlist.add(uri);
+        }
+        let messages = playlist.playbin().bus().unwrap().receiver();
+        assert!(playlist.play());
+
+        let context = MainContext::default();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut changes = vec![];
+        'playing: while Instant::now() < deadline{
+            while context.iteration(false){}
+            while let Ok(msg) = messages.try_recv(){
+                match msg{
+                    Message::Eos(_) => break 'playing,
+                    Message::Error(_) => panic!("error playing the playlist"),
+                    msg => changes.extend(Playlist::track_changed(&msg)),
+                }
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        playlist.clear();
+        for uri in &uris{
+            let _ = ::std::fs::remove_file(&uri["file://".len()..]);
+        }
+
+        assert_eq!(changes, vec![(0, uris[0].clone()), (1, uris[1].clone())]);
+    }
+}
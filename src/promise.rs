@@ -0,0 +1,199 @@
+use ffi::*;
+use util::*;
+use structure::Structure;
+use value::FromValue;
+use reference::Reference;
+use miniobject::MiniObject;
+use panic::catch_panic;
+use version::ApiAvailability;
+use error::Result;
+
+use std::ops::Deref;
+
+const PROMISE: ApiAvailability = ApiAvailability::new("Promise", 1, 14, 0);
+
+// the functions used once there's a promise, a promise can only be
+// created if they are available but one might come from a GValue of a
+// library built against a newer version
+unsafe fn promise_fn<F: Copy>(symbol: &str) -> Result<F>{
+    PROMISE.function(symbol)
+}
+
+/// The GType of GstPromise, None with GStreamer older than 1.14
+pub fn promise_type() -> Option<GType>{
+    unsafe{
+        PROMISE.function::<unsafe extern "C" fn() -> GType>("gst_promise_get_type").ok().map(|get_type| get_type())
+    }
+}
+
+/// State of a promise, mirrors GstPromiseResult
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum PromiseResult{
+    Pending = GST_PROMISE_RESULT_PENDING,
+    Interrupted = GST_PROMISE_RESULT_INTERRUPTED,
+    Replied = GST_PROMISE_RESULT_REPLIED,
+    Expired = GST_PROMISE_RESULT_EXPIRED,
+}
+
+impl PromiseResult{
+    fn from_gst_result(result: GstPromiseResult) -> PromiseResult{
+        match result{
+            GST_PROMISE_RESULT_INTERRUPTED => PromiseResult::Interrupted,
+            GST_PROMISE_RESULT_REPLIED => PromiseResult::Replied,
+            GST_PROMISE_RESULT_EXPIRED => PromiseResult::Expired,
+            _ => PromiseResult::Pending,
+        }
+    }
+}
+
+/// A value that will be replied to asynchronously with a structure,
+/// passed to action signals like webrtcbin's "get-stats" or
+/// "create-offer" which answer from their own thread.
+pub struct Promise{
+    promise: MiniObject<GstPromise>
+}
+
+type ChangeFunc = Guarded<dyn FnMut(&Promise) + Send>;
+
+impl Promise{
+    pub unsafe fn new_from_gst_promise(promise: *mut GstPromise) -> Option<Promise>{
+        MiniObject::new(promise)
+            .map(|miniobject| Promise{ promise: miniobject })
+    }
+
+    /// Creates a promise to be waited on with wait(). Needs GStreamer
+    /// 1.14, returns an unsupported error with older versions
+    pub fn new() -> Result<Promise>{
+        unsafe{
+            let promise_new: unsafe extern "C" fn() -> *mut GstPromise = PROMISE.function("gst_promise_new")?;
+            Ok(Promise::new_from_gst_promise(promise_new()).unwrap())
+        }
+    }
+
+    /// Creates a promise that calls f once it's replied, interrupted or
+    /// expired. f is called from whichever thread changed the promise so
+    /// it mustn't block. Same errors as new
+    pub fn new_with_change_func<F: FnMut(&Promise) + Send + 'static>(f: F) -> Result<Promise>{
+        unsafe{
+            let promise_new: unsafe extern "C" fn(GstPromiseChangeFunc, gpointer, GDestroyNotify) -> *mut GstPromise =
+                PROMISE.function("gst_promise_new_with_change_func")?;
+            let f: ChangeFunc = Guarded::new(Box::new(f));
+            let f: *mut ChangeFunc = Box::into_raw(Box::new(f));
+            Ok(Promise::new_from_gst_promise(promise_new(Some(promise_changed), f as gpointer, Some(free_change_func))).unwrap())
+        }
+    }
+
+    /// Blocks until the promise is replied, interrupted or expired. The
+    /// promise functions are looked up at runtime, an unsupported error
+    /// is returned if they can't be found, same for the other methods
+    pub fn wait(&self) -> Result<PromiseResult>{
+        unsafe{
+            let wait: unsafe extern "C" fn(*mut GstPromise) -> GstPromiseResult = promise_fn("gst_promise_wait")?;
+            Ok(PromiseResult::from_gst_result(wait(self.gst_promise() as *mut GstPromise)))
+        }
+    }
+
+    /// Replies with structure, any waiters are woken up
+    pub fn reply(&self, structure: Option<&Structure>) -> Result<()>{
+        unsafe{
+            let reply: unsafe extern "C" fn(*mut GstPromise, *mut GstStructure) = promise_fn("gst_promise_reply")?;
+            let structure = match structure{
+                Some(structure) => gst_structure_copy(structure.gst_structure()),
+                None => ptr::null_mut(),
+            };
+            reply(self.gst_promise() as *mut GstPromise, structure);
+            Ok(())
+        }
+    }
+
+    /// Tells the replier that the result isn't needed anymore
+    pub fn interrupt(&self) -> Result<()>{
+        unsafe{
+            let interrupt: unsafe extern "C" fn(*mut GstPromise) = promise_fn("gst_promise_interrupt")?;
+            interrupt(self.gst_promise() as *mut GstPromise);
+            Ok(())
+        }
+    }
+
+    /// Tells the waiters that no reply will arrive
+    pub fn expire(&self) -> Result<()>{
+        unsafe{
+            let expire: unsafe extern "C" fn(*mut GstPromise) = promise_fn("gst_promise_expire")?;
+            expire(self.gst_promise() as *mut GstPromise);
+            Ok(())
+        }
+    }
+
+    /// Returns a copy of the reply, None if the promise hasn't been
+    /// replied yet or was replied with no structure
+    pub fn get_reply(&self) -> Option<Structure>{
+        unsafe{
+            let reply = self.reply_structure().ok()?;
+            Structure::new_from_gst_structure(reply as *mut GstStructure).map(|reply| reply.copy())
+        }
+    }
+
+    /// Blocks until the promise changes and returns the reply, None if
+    /// it was interrupted, expired or replied with no structure
+    pub fn wait_reply(&self) -> Option<Structure>{
+        match self.wait(){
+            Ok(PromiseResult::Replied) => self.get_reply(),
+            _ => None,
+        }
+    }
+
+    /// Returns the field name of the reply converted to T, None if the
+    /// promise hasn't been replied, the field doesn't exist or has a
+    /// different type.
+    ///
+    /// ```ignore
+    /// let offer = promise.get_reply_field::<gst::webrtcbin::SessionDescription>("offer");
+    /// ```
+    pub fn get_reply_field<T: FromValue>(&self, name: &str) -> Option<T>{
+        unsafe{
+            let reply = self.reply_structure().ok()?;
+            // the reply is owned by the promise, no need to copy it
+            Structure::new_from_gst_structure(reply as *mut GstStructure)
+                .and_then(|reply| reply.get::<T>(name))
+        }
+    }
+
+    unsafe fn reply_structure(&self) -> Result<*const GstStructure>{
+        let get_reply: unsafe extern "C" fn(*mut GstPromise) -> *const GstStructure = promise_fn("gst_promise_get_reply")?;
+        Ok(get_reply(self.gst_promise() as *mut GstPromise))
+    }
+
+    pub unsafe fn gst_promise(&self) -> *const GstPromise{
+        self.promise.as_ptr()
+    }
+}
+
+extern "C" fn promise_changed(promise: *mut GstPromise, data: gpointer){
+    unsafe{
+        let f = &mut *(data as *mut ChangeFunc);
+        gst_mini_object_ref(promise as *mut GstMiniObject);
+        if let Some(promise) = Promise::new_from_gst_promise(promise){
+            f.call(ptr::null_mut(), (), |f| f(&promise));
+        }
+    }
+}
+
+extern "C" fn free_change_func(data: gpointer){
+    unsafe{
+        catch_panic(ptr::null_mut(), (), || drop(Box::from_raw(data as *mut ChangeFunc)));
+    }
+}
+
+impl Reference for Promise{
+    fn reference(&self) -> Promise{
+        Promise{ promise: self.promise.reference() }
+    }
+}
+
+impl Deref for Promise{
+    type Target = MiniObject;
+    fn deref(&self) -> &MiniObject{
+        self.promise.upcast_ref()
+    }
+}
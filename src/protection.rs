@@ -0,0 +1,150 @@
+use ffi::*;
+use util::*;
+
+use buffer::Buffer;
+use caps::Caps;
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use handle::ProbeHandle;
+use version::ApiAvailability;
+
+const PROTECTION: ApiAvailability = ApiAvailability::new("ProtectionEvent", 1, 6, 0);
+const AVAILABLE_SYSTEMS: ApiAvailability = ApiAvailability::new("available_systems", 1, 14, 0);
+
+/// System id of Widevine
+pub static WIDEVINE: &'static str = "edef8ba9-79d6-4ace-a3c8-27dcd51d21ed";
+/// System id of PlayReady
+pub static PLAYREADY: &'static str = "9a04f079-9840-4286-ab92-e65be0885f95";
+/// System id of FairPlay
+pub static FAIRPLAY: &'static str = "94ce86fb-07ff-4f43-adb8-93d2fa968ca2";
+/// System id of the W3C common PSSH box, used by ClearKey
+pub static CLEARKEY: &'static str = "1077efec-c0b2-4d02-ace3-3c1e52e2fb4b";
+
+/// Init data for a protection system, eg: a PSSH box found by qtdemux or
+/// a ContentProtection element found by dashdemux, sent downstream as a
+/// protection event before the encrypted buffers. It's what a CDM
+/// needs to request the keys from a license server
+#[derive(Clone)]
+pub struct ProtectionEvent{
+    /// UUID of the protection system, eg: WIDEVINE
+    pub system_id: String,
+    /// The init data, for PSSH boxes the whole box
+    pub data: Buffer,
+    /// Where the data was found, eg: "isobmff/moov" or "dash/mpd"
+    pub origin: Option<String>,
+}
+
+impl ProtectionEvent{
+    /// Copies the init data of a protection event, eg: received in an
+    /// event probe. None if it's not a protection event
+    pub unsafe fn from_event(event: *mut GstEvent) -> Option<ProtectionEvent>{
+        if event == ptr::null_mut() || (*event)._type != GST_EVENT_PROTECTION{
+            return None;
+        }
+        let mut system_id: *const gchar = ptr::null();
+        let mut data: *mut GstBuffer = ptr::null_mut();
+        let mut origin: *const gchar = ptr::null();
+        let parse_protection: unsafe extern "C" fn(*mut GstEvent, *mut *const gchar, *mut *mut GstBuffer, *mut *const gchar) =
+            PROTECTION.function("gst_event_parse_protection").ok()?;
+        parse_protection(event, &mut system_id, &mut data, &mut origin);
+        if system_id == ptr::null() || data == ptr::null_mut(){
+            return None;
+        }
+        gst_mini_object_ref(data as *mut GstMiniObject);
+        Some(ProtectionEvent{
+            system_id: from_c_str!(system_id).to_string(),
+            data: Buffer::new(data)?,
+            origin: if origin != ptr::null() { Some(from_c_str!(origin).to_string()) } else { None },
+        })
+    }
+
+    /// Creates a protection event with the same data, eg: to send the
+    /// init data to a decryptor implemented in rust. Null with GStreamer
+    /// older than 1.6
+    pub fn to_event(&self) -> *mut GstEvent{
+        let csystem_id = CString::new(self.system_id.clone()).unwrap();
+        let corigin = self.origin.as_ref().map(|origin| CString::new(origin.clone()).unwrap());
+        unsafe{
+            let new_protection: unsafe extern "C" fn(*const gchar, *mut GstBuffer, *const gchar) -> *mut GstEvent =
+                match PROTECTION.function("gst_event_new_protection"){
+                    Ok(new_protection) => new_protection,
+                    Err(_) => return ptr::null_mut(),
+                };
+            new_protection(csystem_id.as_ptr(),
+                self.data.gst_buffer() as *mut GstBuffer,
+                corigin.as_ref().map(|origin| origin.as_ptr()).unwrap_or(ptr::null()))
+        }
+    }
+
+    /// Calls handler with the init data of every protection event going
+    /// through pad, eg: the src pads of a demuxer, from the streaming
+    /// thread. The events are left going downstream
+    pub fn watch<F>(pad: &mut Pad, mut handler: F) -> Option<ProbeHandle>
+        where F: FnMut(&ProtectionEvent) + Send + 'static{
+        pad.add_probe(GST_PAD_PROBE_TYPE_EVENT_DOWNSTREAM, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+            if let Some(event) = info.event(){
+                if let Some(protection) = unsafe{ ProtectionEvent::from_event(event.gst_event() as *mut GstEvent) }{
+                    handler(&protection);
+                }
+            }
+            PadProbeReturn::Ok
+        })
+    }
+}
+
+/// The protection system caps of an encrypted stream are for, from the
+/// "protection-system" field demuxers add, eg: on
+/// application/x-cenc caps. None if the caps aren't encrypted
+pub fn system_id_from_caps(caps: &Caps) -> Option<String>{
+    caps.structure(0)?.get_string("protection-system").map(|id| id.to_string())
+}
+
+/// The media type the stream had before being encrypted, eg:
+/// "video/x-h264" for application/x-cenc caps
+pub fn original_media_type(caps: &Caps) -> Option<String>{
+    caps.structure(0)?.get_string("original-media-type").map(|media_type| media_type.to_string())
+}
+
+/// The first of system_ids for which there's a decryptor element
+/// installed, in the order given. None with GStreamer older than 1.6
+pub fn select_system(system_ids: &[&str]) -> Option<String>{
+    let cids: Vec<CString> = system_ids.iter().map(|id| CString::new(*id).unwrap()).collect();
+    let mut ptrs: Vec<*const gchar> = cids.iter().map(|id| id.as_ptr()).collect();
+    ptrs.push(ptr::null());
+    unsafe{
+        let select_system: unsafe extern "C" fn(*mut *const gchar) -> *const gchar =
+            PROTECTION.function("gst_protection_select_system").ok()?;
+        let selected = select_system(ptrs.as_mut_ptr());
+        if selected != ptr::null(){
+            Some(from_c_str!(selected).to_string())
+        }else{
+            None
+        }
+    }
+}
+
+/// The system_ids for which there's a decryptor element installed.
+/// Always empty with GStreamer older than 1.14
+pub fn available_systems(system_ids: &[&str]) -> Vec<String>{
+    let cids: Vec<CString> = system_ids.iter().map(|id| CString::new(*id).unwrap()).collect();
+    let mut ptrs: Vec<*const gchar> = cids.iter().map(|id| id.as_ptr()).collect();
+    ptrs.push(ptr::null());
+    unsafe{
+        let filter_systems: unsafe extern "C" fn(*mut *const gchar) -> *mut *mut gchar =
+            match AVAILABLE_SYSTEMS.function("gst_protection_filter_systems_by_available_decryptors"){
+                Ok(filter_systems) => filter_systems,
+                Err(_) => return vec![],
+            };
+        let available = filter_systems(ptrs.as_mut_ptr());
+        if available == ptr::null_mut(){
+            return vec![];
+        }
+        let mut ret = vec![];
+        let mut id = available;
+        while *id != ptr::null_mut(){
+            ret.push(from_c_str!(*id).to_string());
+            id = id.offset(1);
+        }
+        g_strfreev(available);
+        ret
+    }
+}
@@ -0,0 +1,294 @@
+use ffi::*;
+use util::*;
+
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use segment::Segment;
+use handle::ProbeHandle;
+use meta::CustomMeta;
+
+use std::sync::{Arc, Mutex};
+
+/// What to do with the buffers that miss the deadline
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum DeadlineAction{
+    /// Drop them before they reach the next element
+    Drop,
+    /// Let them through with a Late meta so the next elements can skip
+    /// the expensive parts of the processing
+    Flag,
+}
+
+/// Meta added to the buffers that missed the deadline with
+/// DeadlineAction::Flag, with how late they were in nanoseconds
+#[derive(Clone,Copy,Debug)]
+pub struct Late(pub u64);
+
+impl CustomMeta for Late{
+    fn name() -> &'static str{ "GstRsDeadlineLateMeta" }
+}
+
+/// Counters of a Deadline since it was installed or reset
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct DeadlineStats{
+    /// Buffers that reached the probe
+    pub processed: u64,
+    /// Buffers dropped for missing the deadline
+    pub dropped: u64,
+    /// Buffers let through with a Late meta
+    pub flagged: u64,
+    /// Lateness in nanoseconds of the latest buffer that missed the
+    /// deadline
+    pub last_lateness: u64,
+    /// Highest lateness in nanoseconds seen
+    pub max_lateness: u64,
+}
+
+struct DeadlineState{
+    deadline: u64,
+    action: DeadlineAction,
+    stats: DeadlineStats,
+}
+
+/// Drops or flags the buffers going through a pad when their running
+/// time is older than a deadline relative to the current time of the
+/// pipeline clock, so a live pipeline that can't keep up skips frames
+/// instead of accumulating delay, eg: in front of an inference element:
+///
+/// ```ignore
+/// let mut pad = detector.static_pad("sink").unwrap();
+/// let deadline = gst::qos::Deadline::install(&mut pad, 50_000_000, gst::qos::DeadlineAction::Drop).unwrap();
+/// // later
+/// let stats = deadline.stats();
+/// println!("dropped {} of {} frames", stats.dropped, stats.processed);
+/// ```
+///
+/// Buffers without timestamps and buffers that go through before the
+/// element has a clock, eg: while prerolling, are always let through.
+/// The probe is removed when the Deadline is dropped.
+pub struct Deadline{
+    state: Arc<Mutex<DeadlineState>>,
+    _probe: ProbeHandle,
+}
+
+impl Deadline{
+    /// Installs the probe on pad, deadline is the maximum lateness in
+    /// nanoseconds a buffer can have when it goes through the pad
+    pub fn install(pad: &mut Pad, deadline: u64, action: DeadlineAction) -> Option<Deadline>{
+        let state = Arc::new(Mutex::new(DeadlineState{
+            deadline: deadline,
+            action: action,
+            stats: DeadlineStats::default(),
+        }));
+        let probe_state = state.clone();
+        let probe = pad.add_probe(GST_PAD_PROBE_TYPE_BUFFER, move |pad: &mut Pad, info: &mut PadProbeInfo| {
+            let lateness = match info.buffer().and_then(|buffer| lateness(pad, buffer)){
+                Some(lateness) => lateness,
+                None => return PadProbeReturn::Ok,
+            };
+            let mut state = probe_state.lock().unwrap_or_else(|e| e.into_inner());
+            state.stats.processed += 1;
+            if lateness <= state.deadline{
+                return PadProbeReturn::Ok;
+            }
+            state.stats.last_lateness = lateness;
+            state.stats.max_lateness = state.stats.max_lateness.max(lateness);
+            match state.action{
+                DeadlineAction::Drop => {
+                    state.stats.dropped += 1;
+                    PadProbeReturn::Drop
+                }
+                DeadlineAction::Flag => {
+                    state.stats.flagged += 1;
+                    if let Some(buffer) = info.buffer_mut(){
+                        buffer.add_custom_meta(Late(lateness));
+                    }
+                    PadProbeReturn::Ok
+                }
+            }
+        })?;
+        Some(Deadline{
+            state: state,
+            _probe: probe,
+        })
+    }
+
+    pub fn deadline(&self) -> u64{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).deadline
+    }
+
+    /// Changes the deadline, eg: to adapt it to the load of the system
+    pub fn set_deadline(&self, deadline: u64){
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).deadline = deadline;
+    }
+
+    pub fn action(&self) -> DeadlineAction{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).action
+    }
+
+    pub fn set_action(&self, action: DeadlineAction){
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).action = action;
+    }
+
+    pub fn stats(&self) -> DeadlineStats{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).stats
+    }
+
+    /// Returns the counters and starts counting again from 0
+    pub fn reset_stats(&self) -> DeadlineStats{
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = state.stats;
+        state.stats = DeadlineStats::default();
+        stats
+    }
+}
+
+/// How late the buffer is with respect to the clock of the element of
+/// pad, 0 if it's early. None if it can't be known
+fn lateness(pad: &mut Pad, buffer: &::Buffer) -> Option<u64>{
+    let pts = buffer.pts()?;
+    unsafe{
+        let event = gst_pad_get_sticky_event(pad.gst_pad_mut(), GST_EVENT_SEGMENT, 0);
+        let segment = Segment::new_from_event(event);
+        if event != ptr::null_mut(){
+            gst_mini_object_unref(event as *mut GstMiniObject);
+        }
+        let running_time = segment?.to_running_time(GST_FORMAT_TIME, pts)?;
+
+        let element = gst_pad_get_parent_element(pad.gst_pad_mut());
+        if element == ptr::null_mut(){
+            return None;
+        }
+        let clock = gst_element_get_clock(element);
+        let base_time = gst_element_get_base_time(element);
+        gst_object_unref(element as gpointer);
+        if clock == ptr::null_mut(){
+            return None;
+        }
+        let now = gst_clock_get_time(clock).saturating_sub(base_time);
+        gst_object_unref(clock as gpointer);
+        Some(now.saturating_sub(running_time))
+    }
+}
+
+/// The contents of a Qos message, posted by elements that dropped or
+/// changed the quality of a buffer because of QoS, see Message::qos. All
+/// times in nanoseconds, None if the element didn't set them
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct QosMessage{
+    /// Whether the element is live, a live element drops buffers that
+    /// are too late while non live ones can still process them
+    pub live: bool,
+    /// Running time of the buffer that caused the message
+    pub running_time: Option<u64>,
+    pub stream_time: Option<u64>,
+    pub timestamp: Option<u64>,
+    pub duration: Option<u64>,
+    /// Difference between the running time of the buffer and the clock
+    /// when it was processed, positive when late
+    pub jitter: i64,
+    /// Long term proportion of processing speed to real time, greater
+    /// than 1.0 when the pipeline can't keep up
+    pub proportion: f64,
+    /// Quality of the element, 1000000 being the best
+    pub quality: i32,
+    /// Format of processed and dropped, usually buffers
+    pub format: GstFormat,
+    /// Units processed since the last flush
+    pub processed: Option<u64>,
+    /// Units dropped since the last flush
+    pub dropped: Option<u64>,
+}
+
+impl QosMessage{
+    pub unsafe fn new_from_gst_message(message: *mut GstMessage) -> Option<QosMessage>{
+        if message == ptr::null_mut() || (*message)._type != GST_MESSAGE_QOS{
+            return None;
+        }
+        let mut live: gboolean = 0;
+        let mut running_time: guint64 = 0;
+        let mut stream_time: guint64 = 0;
+        let mut timestamp: guint64 = 0;
+        let mut duration: guint64 = 0;
+        let mut jitter: gint64 = 0;
+        let mut proportion: gdouble = 0.;
+        let mut quality: gint = 0;
+        let mut format: GstFormat = GST_FORMAT_UNDEFINED;
+        let mut processed: guint64 = 0;
+        let mut dropped: guint64 = 0;
+        gst_message_parse_qos(message, &mut live, &mut running_time, &mut stream_time, &mut timestamp, &mut duration);
+        gst_message_parse_qos_values(message, &mut jitter, &mut proportion, &mut quality);
+        gst_message_parse_qos_stats(message, &mut format, &mut processed, &mut dropped);
+        Some(QosMessage{
+            live: live != 0,
+            running_time: time_to_option(running_time),
+            stream_time: time_to_option(stream_time),
+            timestamp: time_to_option(timestamp),
+            duration: time_to_option(duration),
+            jitter: jitter,
+            proportion: proportion,
+            quality: quality,
+            format: format,
+            processed: time_to_option(processed),
+            dropped: time_to_option(dropped),
+        })
+    }
+}
+
+/// A Qos event, sent upstream by sinks to tell the elements before them
+/// how late buffers arrive so they can drop frames or lower the quality
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct QosEvent{
+    /// GST_QOS_TYPE_OVERFLOW, GST_QOS_TYPE_UNDERFLOW or
+    /// GST_QOS_TYPE_THROTTLE
+    pub qos_type: GstQOSType,
+    /// Proportion of processing speed to real time, greater than 1.0
+    /// when upstream should go faster
+    pub proportion: f64,
+    /// How late, positive, or early, negative, the buffer at timestamp
+    /// was in nanoseconds
+    pub diff: i64,
+    /// Running time of the buffer the event refers to
+    pub timestamp: u64,
+}
+
+impl QosEvent{
+    /// Parses a Qos event, None for any other event
+    pub unsafe fn new_from_event(event: *mut GstEvent) -> Option<QosEvent>{
+        if event == ptr::null_mut() || (*event)._type != GST_EVENT_QOS{
+            return None;
+        }
+        let mut qos = QosEvent{ qos_type: GST_QOS_TYPE_OVERFLOW, proportion: 0., diff: 0, timestamp: 0 };
+        gst_event_parse_qos(event, &mut qos.qos_type, &mut qos.proportion, &mut qos.diff, &mut qos.timestamp);
+        Some(qos)
+    }
+
+    /// Creates a new Qos event to be sent upstream, eg: with
+    /// Pad::push_event from a sink pad
+    pub fn to_event(&self) -> *mut GstEvent{
+        unsafe{ gst_event_new_qos(self.qos_type, self.proportion, self.diff, self.timestamp) }
+    }
+}
+
+/// Calls callback with every Qos event that goes through pad, eg: the
+/// sink pad of a sink to follow how late it's receiving buffers. The
+/// probe is removed when the returned handle is dropped
+pub fn watch_qos_events<F>(pad: &mut Pad, mut callback: F) -> Option<ProbeHandle>
+    where F: FnMut(&QosEvent) + Send + 'static{
+    pad.add_probe(GST_PAD_PROBE_TYPE_EVENT_UPSTREAM, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+        if let Some(event) = info.event(){
+            if let Some(qos) = unsafe{ QosEvent::new_from_event(event.gst_event() as *mut GstEvent) }{
+                callback(&qos);
+            }
+        }
+        PadProbeReturn::Ok
+    })
+}
+
+#[inline]
+fn time_to_option(time: guint64) -> Option<u64>{
+    if time != GST_CLOCK_TIME_NONE{
+        Some(time as u64)
+    }else{
+        None
+    }
+}
@@ -0,0 +1,40 @@
+use ffi::*;
+use std::marker::PhantomData;
+
+/// A query going through a pad, as seen by a query probe. The query is
+/// owned by whoever sent it and the probe can answer it in place, so
+/// this only borrows it for the duration of the probe callback
+pub struct Query<'a>{
+    query: *mut GstQuery,
+    query_type: GstQueryType,
+    marker: PhantomData<&'a mut GstQuery>,
+}
+
+impl<'a> Query<'a>{
+    /// The caller has to make sure query outlives 'a
+    pub unsafe fn new(query: *mut GstQuery) -> Option<Query<'a>>{
+        if query.is_null(){
+            None
+        }else{
+            Some(Query{
+                query: query,
+                query_type: (*query)._type,
+                marker: PhantomData,
+            })
+        }
+    }
+
+    pub fn query_type(&self) -> GstQueryType{
+        self.query_type
+    }
+
+    /// The query is only valid until the probe callback returns
+    pub unsafe fn gst_query(&self) -> *const GstQuery{
+        self.query
+    }
+
+    /// The query is only valid until the probe callback returns
+    pub unsafe fn gst_query_mut(&mut self) -> *mut GstQuery{
+        self.query
+    }
+}
@@ -1,4 +1,7 @@
+use ffi::*;
 use std::ops::{Deref, DerefMut};
+use std::marker::PhantomData;
+use std::mem;
 
 pub trait Reference{
     fn reference(&self) -> Self where Self:Sized;
@@ -53,3 +56,84 @@ impl<T> AsMut<T> for Ref<T>{
         &mut self.value
     }
 }
+
+/// Objects that can be referenced with a WeakRef
+pub trait Downgrade: Reference + Sized{
+    /// The GObject that is referenced weakly
+    unsafe fn gst_object_ptr(&self) -> *mut GstObject;
+
+    /// Wraps object taking ownership of the passed reference
+    unsafe fn from_gst_object_ptr(object: *mut GstObject) -> Option<Self>;
+
+    fn downgrade(&self) -> WeakRef<Self>{
+        WeakRef::new(self)
+    }
+}
+
+/// A reference to an object that doesn't keep it alive, equivalent to
+/// GWeakRef. Useful to use a pipeline from its own callbacks without
+/// creating a cycle that never releases it:
+///
+/// ```ignore
+/// let weak = pipeline.downgrade();
+/// bus.add_watch(move |msg| {
+///     match weak.upgrade(){
+///         Some(mut pipeline) => { pipeline.set_null_state(); false }
+///         None => false
+///     }
+/// }).unwrap().forget();
+/// ```
+pub struct WeakRef<T>{
+    // GWeakRef can't move once initialized, the object keeps its address
+    weak: Box<GWeakRef>,
+    marker: PhantomData<T>,
+}
+
+// GWeakRef is thread safe, upgrading and dropping are done with the
+// object's weak locations lock held
+unsafe impl<T: Send> Send for WeakRef<T>{}
+unsafe impl<T: Send + Sync> Sync for WeakRef<T>{}
+
+impl<T: Downgrade> WeakRef<T>{
+    pub fn new(t: &T) -> WeakRef<T>{
+        unsafe{
+            WeakRef::new_from_gst_object(t.gst_object_ptr())
+        }
+    }
+
+    unsafe fn new_from_gst_object(object: *mut GstObject) -> WeakRef<T>{
+        let mut weak: Box<GWeakRef> = Box::new(mem::zeroed());
+        g_weak_ref_init(&mut *weak, object as gpointer);
+        WeakRef{ weak: weak, marker: PhantomData }
+    }
+
+    /// A new strong reference to the object, None if it was already
+    /// destroyed
+    pub fn upgrade(&self) -> Option<T>{
+        unsafe{
+            let object = g_weak_ref_get(&*self.weak as *const GWeakRef as *mut GWeakRef);
+            T::from_gst_object_ptr(object as *mut GstObject)
+        }
+    }
+}
+
+impl<T: Downgrade> Clone for WeakRef<T>{
+    fn clone(&self) -> WeakRef<T>{
+        unsafe{
+            let object = g_weak_ref_get(&*self.weak as *const GWeakRef as *mut GWeakRef);
+            let weak = WeakRef::new_from_gst_object(object as *mut GstObject);
+            if object != ::std::ptr::null_mut(){
+                gst_object_unref(object);
+            }
+            weak
+        }
+    }
+}
+
+impl<T> Drop for WeakRef<T>{
+    fn drop(&mut self){
+        unsafe{
+            g_weak_ref_clear(&mut *self.weak);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use ffi::*;
+use util::*;
+use element::Element;
+use panic::catch_panic;
+
+use std::any::TypeId;
+use std::convert::TryFrom;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Prefix of the GTypes of elements implemented in rust
+pub const ELEMENT_PREFIX: &'static str = "GstRsElement";
+/// Prefix of the GTypes of device providers implemented in rust
+pub const DEVICE_PROVIDER_PREFIX: &'static str = "GstRsDeviceProvider";
+
+// rust type implementing each type registered so far, held while
+// looking up and registering a type so two threads don't try to
+// register the same one
+static REGISTERED: Mutex<Option<HashMap<String, TypeId>>> = Mutex::new(None);
+
+/// Name of the GType registered for the factory called name, with
+/// prefix depending on the kind of factory so an element and a device
+/// provider with the same name don't clash, eg: "rs-invert" becomes
+/// "GstRsElementRsInvert"
+pub fn type_name(prefix: &str, name: &str) -> String{
+    let camel: String = name.split(|c: char| !c.is_alphanumeric())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next(){
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        }).collect();
+    format!("{}{}", prefix, camel)
+}
+
+/// Registers a static type implemented by T deriving from parent, or
+/// returns the one already registered with the same name for T. 0 if
+/// the name is taken by a type implemented by something else, eg: two
+/// factory names that give the same type name, or if the class or the
+/// instance struct is bigger than the 64KB GLib allows
+pub unsafe fn register_type<T: 'static>(parent: GType, type_name: &str, class_size: usize, instance_size: usize,
+    class_init: GClassInitFunc, instance_init: GInstanceInitFunc) -> GType{
+
+    let mut registered = REGISTERED.lock().unwrap_or_else(|e| e.into_inner());
+    let registered = registered.get_or_insert_with(HashMap::new);
+    let ctype_name = CString::new(type_name).unwrap();
+    let gtype = g_type_from_name(ctype_name.as_ptr());
+    if gtype != 0{
+        return match registered.get(type_name){
+            Some(&imp) if imp == TypeId::of::<T>() => gtype,
+            _ => 0,
+        };
+    }
+    let (class_size, instance_size) = match (guint16::try_from(class_size), guint16::try_from(instance_size)){
+        (Ok(class_size), Ok(instance_size)) => (class_size, instance_size),
+        _ => return 0,
+    };
+    let info = GTypeInfo{
+        class_size: class_size,
+        base_init: None,
+        base_finalize: None,
+        class_init: class_init,
+        class_finalize: None,
+        class_data: ptr::null(),
+        instance_size: instance_size,
+        n_preallocs: 0,
+        instance_init: instance_init,
+        value_table: ptr::null(),
+    };
+    let gtype = g_type_register_static(parent, ctype_name.as_ptr(), &info, 0);
+    if gtype != 0{
+        registered.insert(type_name.to_string(), TypeId::of::<T>());
+    }
+    gtype
+}
+
+/// Instance struct of a type implemented by T deriving from P, the rust
+/// state is allocated separately so its size doesn't depend on T
+#[repr(C)]
+pub struct Instance<P, T>{
+    parent: P,
+    imp: *mut Mutex<Guarded<T>>,
+}
+
+/// Stores the state created in instance_init, None if creating it
+/// panicked
+pub unsafe fn set_imp<P, T>(instance: *mut P, imp: Option<T>){
+    let instance = instance as *mut Instance<P, T>;
+    (*instance).imp = match imp{
+        Some(imp) => Box::into_raw(Box::new(Mutex::new(Guarded::new(Box::new(imp))))),
+        None => ptr::null_mut(),
+    };
+}
+
+/// Runs f with the rust state of instance, fallback is returned if the
+/// state couldn't be created or f panics, and for every later call once
+/// it panicked. element is passed to the panic handler and can be null
+pub unsafe fn with_imp<P, T, R, F: FnOnce(&mut T) -> R>(instance: *mut P, element: *mut GstElement, fallback: R, f: F) -> R{
+    let imp = (*(instance as *mut Instance<P, T>)).imp;
+    if imp == ptr::null_mut(){
+        return fallback;
+    }
+    let mut imp = (*imp).lock().unwrap_or_else(|e| e.into_inner());
+    imp.call(element, fallback, f)
+}
+
+/// Drops the rust state and chains up to the parent class. Only the
+/// direct parent is chained up to as these types can't be subclassed
+pub extern "C" fn finalize<P, T>(object: *mut GObject){
+    unsafe{
+        let instance = object as *mut Instance<P, T>;
+        if (*instance).imp != ptr::null_mut(){
+            let imp = Box::from_raw((*instance).imp);
+            (*instance).imp = ptr::null_mut();
+            catch_panic(ptr::null_mut(), (), move || drop(imp));
+        }
+        let klass = (*(object as *mut GTypeInstance)).g_class;
+        let parent_class = g_type_class_peek_parent(klass as gpointer) as *mut GObjectClass;
+        if let Some(parent_finalize) = (*parent_class).finalize{
+            parent_finalize(object);
+        }
+    }
+}
+
+/// Name of the factory element was created from, None if it wasn't
+/// created from a factory
+pub fn factory_name(element: &Element) -> Option<String>{
+    unsafe{
+        let factory = gst_element_get_factory(element.gst_element() as *mut GstElement);
+        if factory == ptr::null_mut(){
+            return None;
+        }
+        let name = gst_object_get_name(factory as *mut GstObject);
+        if name == ptr::null_mut(){
+            return None;
+        }
+        let ret = from_c_str!(name).to_string();
+        g_free(name as gpointer);
+        Some(ret)
+    }
+}
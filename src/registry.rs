@@ -0,0 +1,383 @@
+use ffi::*;
+use util::*;
+use object::Object;
+use element_factory::ElementFactory;
+use reference::Reference;
+use version::ApiAvailability;
+
+use std::os::raw::c_void;
+use std::ops::{Deref, DerefMut};
+use std::cmp;
+
+const FEATURE_PLUGIN: ApiAvailability = ApiAvailability::new("PluginFeature::plugin", 1, 2, 0);
+
+/// The list of plugins and features GStreamer knows about
+pub struct Registry{
+    registry: Object<GstRegistry>
+}
+
+impl Registry{
+    /// Returns the default registry
+    pub fn get() -> Registry{
+        unsafe{
+            let registry = gst_registry_get();
+            gst_object_ref(registry as *mut c_void);
+            Registry{ registry: Object::new(registry).unwrap() }
+        }
+    }
+
+    /// All the plugins in the registry, loaded or not
+    pub fn plugins(&self) -> Vec<Plugin>{
+        unsafe{
+            let list = gst_registry_get_plugin_list(self.gst_registry() as *mut GstRegistry);
+            let plugins = objects_from_list(list, |plugin| Plugin::new(plugin as *mut GstPlugin));
+            g_list_free(list);
+            plugins
+        }
+    }
+
+    /// All the features of the given type, eg: gst_element_factory_get_type()
+    pub fn features(&self, feature_type: GType) -> Vec<PluginFeature>{
+        unsafe{
+            let list = gst_registry_get_feature_list(self.gst_registry() as *mut GstRegistry, feature_type);
+            let features = objects_from_list(list, |feature| PluginFeature::new(feature as *mut GstPluginFeature));
+            g_list_free(list);
+            features
+        }
+    }
+
+    /// Same as features but sorted from highest to lowest rank, features
+    /// with the same rank are sorted by name
+    pub fn features_by_rank(&self, feature_type: GType) -> Vec<PluginFeature>{
+        let mut features = self.features(feature_type);
+        features.sort_by(|a, b| b.rank().cmp(&a.rank()).then_with(|| a.name().cmp(&b.name())));
+        features
+    }
+
+    /// Features provided by the plugin with the given name
+    pub fn features_by_plugin(&self, plugin_name: &str) -> Vec<PluginFeature>{
+        let cname = CString::new(plugin_name).unwrap();
+        unsafe{
+            let list = gst_registry_get_feature_list_by_plugin(self.gst_registry() as *mut GstRegistry, cname.as_ptr());
+            let features = objects_from_list(list, |feature| PluginFeature::new(feature as *mut GstPluginFeature));
+            g_list_free(list);
+            features
+        }
+    }
+
+    /// Finds a feature by name, eg: "x264enc", returns None if the plugin
+    /// providing it isn't installed
+    pub fn lookup_feature(&self, name: &str) -> Option<PluginFeature>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            PluginFeature::new(gst_registry_lookup_feature(self.gst_registry() as *mut GstRegistry, cname.as_ptr()))
+        }
+    }
+
+    /// Finds a plugin by name, eg: "coreelements"
+    pub fn find_plugin(&self, name: &str) -> Option<Plugin>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            Plugin::new(gst_registry_find_plugin(self.gst_registry() as *mut GstRegistry, cname.as_ptr()))
+        }
+    }
+
+    /// Returns true if the feature exists and its plugin version is at
+    /// least major.minor.micro
+    pub fn check_feature_version(&self, name: &str, major: u32, minor: u32, micro: u32) -> bool{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            gst_registry_check_feature_version(self.gst_registry() as *mut GstRegistry, cname.as_ptr(), major, minor, micro) != 0
+        }
+    }
+
+    /// Makes decodebin and playbin choose the decoder factory name over
+    /// any other decoder for the same format, eg: "avdec_h264" to always
+    /// decode in software, by raising its rank above all of them. Only
+    /// affects this process. Returns false if it isn't installed or
+    /// isn't a decoder
+    pub fn prefer_decoder(&mut self, name: &str) -> bool{
+        let mut feature = match self.lookup_feature(name){
+            Some(feature) => feature,
+            None => return false
+        };
+        let is_decoder = feature.element_factory()
+            .map(|factory| factory.is_type(GST_ELEMENT_FACTORY_TYPE_DECODER))
+            .unwrap_or(false);
+        if !is_decoder{
+            return false;
+        }
+        let highest = ElementFactory::list_get_elements(GST_ELEMENT_FACTORY_TYPE_DECODER, GST_RANK_NONE).iter()
+            .filter(|factory| factory.name() != name)
+            .map(|factory| factory.rank())
+            .max()
+            .unwrap_or(GST_RANK_NONE);
+        feature.set_rank(cmp::max(highest + 1, GST_RANK_PRIMARY));
+        true
+    }
+
+    /// Stops autoplugging from ever choosing the factory name, eg: a
+    /// hardware decoder like "vaapih264dec" that's broken with some
+    /// drivers, by setting its rank to GST_RANK_NONE. It can still be
+    /// created by name. Returns false if it isn't installed
+    pub fn disable_element(&mut self, name: &str) -> bool{
+        match self.lookup_feature(name){
+            Some(mut feature) => {
+                feature.set_rank(GST_RANK_NONE);
+                true
+            }
+            None => false
+        }
+    }
+
+    /// Scans path for plugins, returns true if the registry changed
+    pub fn scan_path(&mut self, path: &str) -> bool{
+        let cpath = CString::new(path).unwrap();
+        unsafe{
+            gst_registry_scan_path(self.gst_registry_mut(), cpath.as_ptr()) != 0
+        }
+    }
+
+    pub unsafe fn gst_registry(&self) -> *const GstRegistry{
+        self.registry.as_ptr()
+    }
+
+    pub unsafe fn gst_registry_mut(&mut self) -> *mut GstRegistry{
+        self.registry.as_mut_ptr()
+    }
+}
+
+impl Reference for Registry{
+    fn reference(&self) -> Registry{
+        Registry{ registry: self.registry.reference() }
+    }
+}
+
+impl Deref for Registry{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.registry.upcast_ref()
+    }
+}
+
+impl DerefMut for Registry{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.registry.upcast_mut()
+    }
+}
+
+/// A loadable module providing elements and other features
+pub struct Plugin{
+    plugin: Object<GstPlugin>
+}
+
+impl Plugin{
+    pub unsafe fn new(plugin: *mut GstPlugin) -> Option<Plugin>{
+        Object::new(plugin).map(|obj| Plugin{ plugin: obj })
+    }
+
+    pub fn description(&self) -> String{
+        self.get_str(gst_plugin_get_description).unwrap_or(String::new())
+    }
+
+    /// Path of the plugin's library, None for static plugins
+    pub fn filename(&self) -> Option<String>{
+        self.get_str(gst_plugin_get_filename)
+    }
+
+    pub fn version(&self) -> String{
+        self.get_str(gst_plugin_get_version).unwrap_or(String::new())
+    }
+
+    pub fn license(&self) -> String{
+        self.get_str(gst_plugin_get_license).unwrap_or(String::new())
+    }
+
+    pub fn source(&self) -> String{
+        self.get_str(gst_plugin_get_source).unwrap_or(String::new())
+    }
+
+    /// Package the plugin belongs to, eg: "GStreamer Good Plug-ins"
+    pub fn package(&self) -> String{
+        self.get_str(gst_plugin_get_package).unwrap_or(String::new())
+    }
+
+    pub fn origin(&self) -> String{
+        self.get_str(gst_plugin_get_origin).unwrap_or(String::new())
+    }
+
+    pub fn is_loaded(&self) -> bool{
+        unsafe{
+            gst_plugin_is_loaded(self.gst_plugin() as *mut GstPlugin) != 0
+        }
+    }
+
+    /// Loads the plugin, returns the loaded plugin or None if it failed
+    pub fn load(&self) -> Option<Plugin>{
+        unsafe{
+            Plugin::new(gst_plugin_load(self.gst_plugin() as *mut GstPlugin))
+        }
+    }
+
+    /// Features provided by this plugin
+    pub fn features(&self) -> Vec<PluginFeature>{
+        Registry::get().features_by_plugin(&self.name())
+    }
+
+    fn get_str(&self, f: unsafe extern "C" fn(*mut GstPlugin) -> *const gchar) -> Option<String>{
+        unsafe{
+            let value = f(self.gst_plugin() as *mut GstPlugin);
+            if value != ptr::null(){
+                Some(from_c_str!(value).to_string())
+            }else{
+                None
+            }
+        }
+    }
+
+    pub unsafe fn gst_plugin(&self) -> *const GstPlugin{
+        self.plugin.as_ptr()
+    }
+
+    pub unsafe fn gst_plugin_mut(&mut self) -> *mut GstPlugin{
+        self.plugin.as_mut_ptr()
+    }
+}
+
+impl Reference for Plugin{
+    fn reference(&self) -> Plugin{
+        Plugin{ plugin: self.plugin.reference() }
+    }
+}
+
+impl Deref for Plugin{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.plugin.upcast_ref()
+    }
+}
+
+impl DerefMut for Plugin{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.plugin.upcast_mut()
+    }
+}
+
+/// Something provided by a plugin: an element factory, a typefinder, a
+/// device provider...
+pub struct PluginFeature{
+    feature: Object<GstPluginFeature>
+}
+
+impl PluginFeature{
+    pub unsafe fn new(feature: *mut GstPluginFeature) -> Option<PluginFeature>{
+        Object::new(feature).map(|obj| PluginFeature{ feature: obj })
+    }
+
+    /// Rank used when autoplugging, see the GST_RANK_* constants
+    pub fn rank(&self) -> u32{
+        unsafe{
+            gst_plugin_feature_get_rank(self.gst_pluginfeature() as *mut GstPluginFeature)
+        }
+    }
+
+    /// Changes the rank of the feature, eg: to make decodebin prefer a
+    /// hardware decoder or to stop it from using a broken one by setting
+    /// GST_RANK_NONE
+    pub fn set_rank(&mut self, rank: u32){
+        unsafe{
+            gst_plugin_feature_set_rank(self.gst_pluginfeature_mut(), rank)
+        }
+    }
+
+    /// Needs GStreamer 1.2, returns None with older versions
+    pub fn plugin_name(&self) -> Option<String>{
+        unsafe{
+            let get_plugin_name: unsafe extern "C" fn(*mut GstPluginFeature) -> *const gchar
+                = FEATURE_PLUGIN.function("gst_plugin_feature_get_plugin_name").ok()?;
+            let name = get_plugin_name(self.gst_pluginfeature() as *mut GstPluginFeature);
+            if name != ptr::null(){
+                Some(from_c_str!(name).to_string())
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Needs GStreamer 1.2, returns None with older versions
+    pub fn plugin(&self) -> Option<Plugin>{
+        unsafe{
+            let get_plugin: unsafe extern "C" fn(*mut GstPluginFeature) -> *mut GstPlugin
+                = FEATURE_PLUGIN.function("gst_plugin_feature_get_plugin").ok()?;
+            Plugin::new(get_plugin(self.gst_pluginfeature() as *mut GstPluginFeature))
+        }
+    }
+
+    /// Returns true if the plugin providing this feature is at least
+    /// version major.minor.micro
+    pub fn check_version(&self, major: u32, minor: u32, micro: u32) -> bool{
+        unsafe{
+            gst_plugin_feature_check_version(self.gst_pluginfeature() as *mut GstPluginFeature, major, minor, micro) != 0
+        }
+    }
+
+    /// Loads the plugin providing this feature, returns the loaded
+    /// feature or None if it failed
+    pub fn load(&self) -> Option<PluginFeature>{
+        unsafe{
+            PluginFeature::new(gst_plugin_feature_load(self.gst_pluginfeature() as *mut GstPluginFeature))
+        }
+    }
+
+    /// Returns the feature as an element factory if it is one
+    pub fn element_factory(&self) -> Option<ElementFactory>{
+        unsafe{
+            if g_type_check_instance_is_a(self.gst_pluginfeature() as *mut GTypeInstance, gst_element_factory_get_type()) != 0{
+                Some(ElementFactory::new_from_feature(self.reference()))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub unsafe fn gst_pluginfeature(&self) -> *const GstPluginFeature{
+        self.feature.as_ptr()
+    }
+
+    pub unsafe fn gst_pluginfeature_mut(&mut self) -> *mut GstPluginFeature{
+        self.feature.as_mut_ptr()
+    }
+}
+
+impl Reference for PluginFeature{
+    fn reference(&self) -> PluginFeature{
+        PluginFeature{ feature: self.feature.reference() }
+    }
+}
+
+impl Deref for PluginFeature{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.feature.upcast_ref()
+    }
+}
+
+impl DerefMut for PluginFeature{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.feature.upcast_mut()
+    }
+}
+
+/// Wraps every object in list with new taking ownership of the
+/// references held by the list, the list itself has to be freed after
+pub unsafe fn objects_from_list<T, F: Fn(gpointer) -> Option<T>>(list: *mut GList, new: F) -> Vec<T>{
+    let mut objects = vec![];
+    let mut node = list;
+    while node != ptr::null_mut(){
+        if let Some(object) = new((*node).data){
+            objects.push(object);
+        }
+        node = (*node).next;
+    }
+    objects
+}
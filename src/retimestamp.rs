@@ -0,0 +1,116 @@
+use ffi::*;
+
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use segment::Segment;
+use event::Event;
+use handle::ProbeHandle;
+
+use std::sync::{Arc, Mutex};
+
+/// How a Retimestamper changes the timestamps going through a pad
+pub enum Retiming{
+    /// Adds an offset in nanoseconds, timestamps that would become
+    /// negative are clamped to 0
+    Offset(i64),
+    /// Multiplies timestamps and durations by a factor, eg: 2.0 to play
+    /// a stream at half speed
+    Rate(f64),
+    /// Maps every timestamp with a function, timestamps it returns None
+    /// for are left unset
+    Custom(Box<dyn FnMut(u64) -> Option<u64> + Send>),
+}
+
+impl Retiming{
+    fn map(&mut self, time: u64) -> Option<u64>{
+        match *self{
+            Retiming::Offset(offset) => if offset < 0{
+                Some(time.saturating_sub((-offset) as u64))
+            }else{
+                Some(time.saturating_add(offset as u64))
+            },
+            Retiming::Rate(rate) => Some((time as f64 * rate) as u64),
+            Retiming::Custom(ref mut map) => map(time),
+        }
+    }
+
+    fn map_duration(&self, duration: u64) -> u64{
+        match *self{
+            Retiming::Rate(rate) => (duration as f64 * rate) as u64,
+            _ => duration,
+        }
+    }
+}
+
+/// Rewrites the pts and dts of the buffers going through a pad, and the
+/// segment events so the running time of the buffers changes the same
+/// way, eg: to concatenate live sources or fix the timestamps of a
+/// source that gets them wrong:
+///
+/// ```ignore
+/// let mut pad = source.static_pad("src").unwrap();
+/// // continue where the previous source stopped
+/// let retimestamper = gst::retimestamp::Retimestamper::install(&mut pad, gst::retimestamp::Retiming::Offset(previous_end as i64)).unwrap();
+/// ```
+///
+/// Only segments in time format are modified, the stream time of the
+/// segment is kept so positions reported to the user don't change. The
+/// probe is removed when the Retimestamper is dropped.
+pub struct Retimestamper{
+    retiming: Arc<Mutex<Retiming>>,
+    _probe: ProbeHandle,
+}
+
+impl Retimestamper{
+    pub fn install(pad: &mut Pad, retiming: Retiming) -> Option<Retimestamper>{
+        let retiming = Arc::new(Mutex::new(retiming));
+        let probe_retiming = retiming.clone();
+        let mask = GST_PAD_PROBE_TYPE_BUFFER | GST_PAD_PROBE_TYPE_EVENT_DOWNSTREAM;
+        let probe = pad.add_probe(mask, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+            let mut retiming = probe_retiming.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(buffer) = info.buffer_mut(){
+                let pts = buffer.pts().and_then(|pts| retiming.map(pts));
+                let dts = buffer.dts().and_then(|dts| retiming.map(dts));
+                let duration = buffer.duration().map(|duration| retiming.map_duration(duration));
+                buffer.set_pts(pts);
+                buffer.set_dts(dts);
+                buffer.set_duration(duration);
+            }else if let Some(event) = info.event_mut(){
+                let segment = unsafe{ retime_segment(event.gst_event() as *mut GstEvent, &mut retiming) };
+                if let Some(segment) = segment{
+                    if let Some(mut new_event) = unsafe{ Event::new(segment.to_event()) }{
+                        new_event.set_seqnum(event.seqnum());
+                        *event = new_event;
+                    }
+                }
+            }
+            PadProbeReturn::Ok
+        })?;
+        Some(Retimestamper{
+            retiming: retiming,
+            _probe: probe,
+        })
+    }
+
+    /// Changes the retiming for the next buffers, it doesn't modify the
+    /// current segment so a new one has to be sent if the running time
+    /// needs to change accordingly
+    pub fn set_retiming(&self, retiming: Retiming){
+        *self.retiming.lock().unwrap_or_else(|e| e.into_inner()) = retiming;
+    }
+}
+
+// the segment of event with start, stop and position retimed, None if
+// it's not a time segment event
+unsafe fn retime_segment(event: *mut GstEvent, retiming: &mut Retiming) -> Option<Segment>{
+    let mut segment = Segment::new_from_event(event)?;
+    if segment.format() != GST_FORMAT_TIME{
+        return None;
+    }
+    let start = segment.start();
+    segment.set_start(retiming.map(start).unwrap_or(start));
+    let stop = segment.stop().map(|stop| retiming.map(stop).unwrap_or(stop));
+    segment.set_stop(stop);
+    let position = segment.position();
+    segment.set_position(retiming.map(position).unwrap_or(position));
+    Some(segment)
+}
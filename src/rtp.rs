@@ -0,0 +1,317 @@
+use ffi::*;
+use util::*;
+use buffer::Buffer;
+
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::slice;
+
+/// Read access to the header and payload of an RTP packet. The buffer
+/// stays mapped, and borrowed, until this is dropped:
+///
+/// ```ignore
+/// if let Some(rtp) = gst::rtp::RtpBuffer::map(&buffer){
+///     println!("ssrc {:x} seq {} ts {}", rtp.ssrc(), rtp.seq(), rtp.timestamp());
+/// }
+/// ```
+///
+/// Not Send, the mapping belongs to the thread that mapped it
+pub struct RtpBuffer<'a>{
+    rtp: GstRTPBuffer,
+    buffer: PhantomData<&'a Buffer>
+}
+
+impl<'a> RtpBuffer<'a>{
+    /// Maps buffer for reading, None if it isn't a valid RTP packet
+    pub fn map(buffer: &'a Buffer) -> Option<RtpBuffer<'a>>{
+        unsafe{ RtpBuffer::map_flags(buffer.gst_buffer() as *mut GstBuffer, GST_MAP_READ) }
+    }
+
+    unsafe fn map_flags(buffer: *mut GstBuffer, flags: GstMapFlags) -> Option<RtpBuffer<'a>>{
+        let mut rtp: GstRTPBuffer = mem::zeroed();
+        if gst_rtp_buffer_map(buffer, flags, &mut rtp) != 0{
+            Some(RtpBuffer{ rtp: rtp, buffer: PhantomData })
+        }else{
+            None
+        }
+    }
+
+    /// Allocates a buffer for an RTP packet with a payload of
+    /// payload_len bytes and room for csrc_count contributing sources.
+    /// The header is initialized with version 2, the rest has to be
+    /// filled through RtpBufferMut
+    pub fn new_buffer(payload_len: u32, csrc_count: u8) -> Buffer{
+        unsafe{
+            Buffer::new(gst_rtp_buffer_new_allocate(payload_len, 0, csrc_count)).unwrap()
+        }
+    }
+
+    fn rtp(&self) -> *mut GstRTPBuffer{
+        &self.rtp as *const GstRTPBuffer as *mut GstRTPBuffer
+    }
+
+    pub fn version(&self) -> u8{
+        unsafe{ gst_rtp_buffer_get_version(self.rtp()) }
+    }
+
+    pub fn has_padding(&self) -> bool{
+        unsafe{ gst_rtp_buffer_get_padding(self.rtp()) != 0 }
+    }
+
+    /// Whether the packet has header extensions
+    pub fn has_extension(&self) -> bool{
+        unsafe{ gst_rtp_buffer_get_extension(self.rtp()) != 0 }
+    }
+
+    /// Size of the header including the csrcs and extensions
+    pub fn header_len(&self) -> u32{
+        unsafe{ gst_rtp_buffer_get_header_len(self.rtp()) }
+    }
+
+    pub fn ssrc(&self) -> u32{
+        unsafe{ gst_rtp_buffer_get_ssrc(self.rtp()) }
+    }
+
+    /// The contributing sources of a mixed packet
+    pub fn csrcs(&self) -> Vec<u32>{
+        unsafe{
+            (0..gst_rtp_buffer_get_csrc_count(self.rtp()))
+                .map(|idx| gst_rtp_buffer_get_csrc(self.rtp(), idx))
+                .collect()
+        }
+    }
+
+    /// Usually set on the last packet of a video frame
+    pub fn marker(&self) -> bool{
+        unsafe{ gst_rtp_buffer_get_marker(self.rtp()) != 0 }
+    }
+
+    pub fn payload_type(&self) -> u8{
+        unsafe{ gst_rtp_buffer_get_payload_type(self.rtp()) }
+    }
+
+    /// Sequence number, wraps around at 65535
+    pub fn seq(&self) -> u16{
+        unsafe{ gst_rtp_buffer_get_seq(self.rtp()) }
+    }
+
+    /// Timestamp in units of the clock rate of the payload
+    pub fn timestamp(&self) -> u32{
+        unsafe{ gst_rtp_buffer_get_timestamp(self.rtp()) }
+    }
+
+    pub fn payload(&self) -> &[u8]{
+        unsafe{
+            let len = gst_rtp_buffer_get_payload_len(self.rtp()) as usize;
+            let payload = gst_rtp_buffer_get_payload(self.rtp()) as *const u8;
+            if len == 0 || payload == ptr::null(){
+                &[]
+            }else{
+                slice::from_raw_parts(payload, len)
+            }
+        }
+    }
+
+    /// Data of the nth one byte header extension with the given id, as
+    /// used by most WebRTC extensions
+    pub fn extension_onebyte(&self, id: u8, nth: u32) -> Option<&[u8]>{
+        unsafe{
+            let mut data = ptr::null_mut();
+            let mut size = 0;
+            if gst_rtp_buffer_get_extension_onebyte_header(self.rtp(), id, nth, &mut data, &mut size) != 0{
+                Some(slice::from_raw_parts(data as *const u8, size as usize))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Data of the nth two bytes header extension with the given id,
+    /// with the application specific bits of the header
+    pub fn extension_twobytes(&self, id: u8, nth: u32) -> Option<(u8, &[u8])>{
+        unsafe{
+            let mut appbits = 0;
+            let mut data = ptr::null_mut();
+            let mut size = 0;
+            if gst_rtp_buffer_get_extension_twobytes_header(self.rtp(), &mut appbits, id, nth, &mut data, &mut size) != 0{
+                Some((appbits, slice::from_raw_parts(data as *const u8, size as usize)))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub unsafe fn gst_rtp_buffer(&self) -> *const GstRTPBuffer{
+        &self.rtp
+    }
+}
+
+impl<'a> Drop for RtpBuffer<'a>{
+    fn drop(&mut self){
+        unsafe{
+            gst_rtp_buffer_unmap(&mut self.rtp);
+        }
+    }
+}
+
+/// Read and write access to an RTP packet, to forge packets or rewrite
+/// the headers of received ones:
+///
+/// ```ignore
+/// let mut buffer = gst::rtp::RtpBuffer::new_buffer(payload.len() as u32, 0);
+/// {
+///     let mut rtp = gst::rtp::RtpBufferMut::map(&mut buffer).unwrap();
+///     rtp.set_payload_type(96);
+///     rtp.set_seq(seq);
+///     rtp.set_timestamp(ts);
+///     rtp.set_ssrc(0x1234abcd);
+///     rtp.payload_mut().copy_from_slice(&payload);
+/// }
+/// appsrc.push_buffer(buffer);
+/// ```
+pub struct RtpBufferMut<'a>{
+    rtp: RtpBuffer<'a>
+}
+
+impl<'a> RtpBufferMut<'a>{
+    /// Maps buffer for reading and writing, None if it isn't a valid
+    /// RTP packet or it's not writable, see Buffer::make_writable
+    pub fn map(buffer: &'a mut Buffer) -> Option<RtpBufferMut<'a>>{
+        unsafe{
+            RtpBuffer::map_flags(buffer.gst_buffer_mut(), GST_MAP_READ | GST_MAP_WRITE)
+                .map(|rtp| RtpBufferMut{ rtp: rtp })
+        }
+    }
+
+    pub fn set_ssrc(&mut self, ssrc: u32){
+        unsafe{ gst_rtp_buffer_set_ssrc(self.rtp.rtp(), ssrc) }
+    }
+
+    /// Sets the csrc at idx, the packet has to have been allocated with
+    /// room for it
+    pub fn set_csrc(&mut self, idx: u8, csrc: u32){
+        unsafe{ gst_rtp_buffer_set_csrc(self.rtp.rtp(), idx, csrc) }
+    }
+
+    pub fn set_marker(&mut self, marker: bool){
+        unsafe{ gst_rtp_buffer_set_marker(self.rtp.rtp(), marker as gboolean) }
+    }
+
+    pub fn set_payload_type(&mut self, payload_type: u8){
+        unsafe{ gst_rtp_buffer_set_payload_type(self.rtp.rtp(), payload_type) }
+    }
+
+    pub fn set_seq(&mut self, seq: u16){
+        unsafe{ gst_rtp_buffer_set_seq(self.rtp.rtp(), seq) }
+    }
+
+    pub fn set_timestamp(&mut self, timestamp: u32){
+        unsafe{ gst_rtp_buffer_set_timestamp(self.rtp.rtp(), timestamp) }
+    }
+
+    pub fn payload_mut(&mut self) -> &mut [u8]{
+        unsafe{
+            let len = gst_rtp_buffer_get_payload_len(self.rtp.rtp()) as usize;
+            let payload = gst_rtp_buffer_get_payload(self.rtp.rtp()) as *mut u8;
+            if len == 0 || payload == ptr::null_mut(){
+                &mut []
+            }else{
+                slice::from_raw_parts_mut(payload, len)
+            }
+        }
+    }
+
+    /// Adds a one byte header extension, id has to be between 1 and 14
+    /// and data at most 16 bytes long. Returns false if it doesn't fit
+    /// or the packet already has two bytes extensions
+    pub fn add_extension_onebyte(&mut self, id: u8, data: &[u8]) -> bool{
+        unsafe{
+            gst_rtp_buffer_add_extension_onebyte_header(self.rtp.rtp(), id, data.as_ptr() as gconstpointer, data.len() as guint) != 0
+        }
+    }
+
+    /// Adds a two bytes header extension, data can be up to 255 bytes
+    pub fn add_extension_twobytes(&mut self, appbits: u8, id: u8, data: &[u8]) -> bool{
+        unsafe{
+            gst_rtp_buffer_add_extension_twobytes_header(self.rtp.rtp(), appbits, id, data.as_ptr() as gconstpointer, data.len() as guint) != 0
+        }
+    }
+}
+
+impl<'a> Deref for RtpBufferMut<'a>{
+    type Target = RtpBuffer<'a>;
+    fn deref(&self) -> &RtpBuffer<'a>{
+        &self.rtp
+    }
+}
+
+/// The pads of rtpbin, and of elements exposing rtpbin's pads like
+/// rtspsrc or webrtcbin, with the session, ssrc and payload type parsed
+/// out of their names:
+///
+/// ```ignore
+/// let mut sink = rtpbin.request_pad(&gst::rtp::RtpBinPad::SendRtpSink(0).name()).unwrap();
+/// rtpbin.connect_values("pad-added", |args| {
+///     let pad = args[1].get::<gst::Pad>().unwrap();
+///     if let Some(gst::rtp::RtpBinPad::RecvRtpSrc(session, ssrc, pt)) = gst::rtp::RtpBinPad::parse(&pad.name()){
+///         // link to a depayloader
+///     }
+///     None
+/// }).unwrap().forget();
+/// ```
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum RtpBinPad{
+    /// send_rtp_sink_<session>, request pad for the RTP to send
+    SendRtpSink(u32),
+    /// send_rtp_src_<session>, the RTP to send to the network
+    SendRtpSrc(u32),
+    /// send_rtcp_src_<session>, request pad for the RTCP to send
+    SendRtcpSrc(u32),
+    /// recv_rtp_sink_<session>, request pad for the RTP received
+    RecvRtpSink(u32),
+    /// recv_rtcp_sink_<session>, request pad for the RTCP received
+    RecvRtcpSink(u32),
+    /// recv_rtp_src_<session>_<ssrc>_<pt>, a received stream, added
+    /// once its first packet arrives
+    RecvRtpSrc(u32, u32, u8),
+}
+
+impl RtpBinPad{
+    pub fn name(&self) -> String{
+        match *self{
+            RtpBinPad::SendRtpSink(session) => format!("send_rtp_sink_{}", session),
+            RtpBinPad::SendRtpSrc(session) => format!("send_rtp_src_{}", session),
+            RtpBinPad::SendRtcpSrc(session) => format!("send_rtcp_src_{}", session),
+            RtpBinPad::RecvRtpSink(session) => format!("recv_rtp_sink_{}", session),
+            RtpBinPad::RecvRtcpSink(session) => format!("recv_rtcp_sink_{}", session),
+            RtpBinPad::RecvRtpSrc(session, ssrc, pt) => format!("recv_rtp_src_{}_{}_{}", session, ssrc, pt),
+        }
+    }
+
+    /// Parses a pad name, None if it's not one of rtpbin's
+    pub fn parse(name: &str) -> Option<RtpBinPad>{
+        let (prefix, ids) = match name.rfind(|c: char| !c.is_digit(10) && c != '_'){
+            Some(pos) => name.split_at(pos + 1),
+            None => return None,
+        };
+        let ids: Vec<&str> = ids.split('_').filter(|id| !id.is_empty()).collect();
+        let session = match ids.first().and_then(|id| id.parse::<u32>().ok()){
+            Some(session) => session,
+            None => return None,
+        };
+        match (prefix, ids.len()){
+            ("send_rtp_sink", 1) => Some(RtpBinPad::SendRtpSink(session)),
+            ("send_rtp_src", 1) => Some(RtpBinPad::SendRtpSrc(session)),
+            ("send_rtcp_src", 1) => Some(RtpBinPad::SendRtcpSrc(session)),
+            ("recv_rtp_sink", 1) => Some(RtpBinPad::RecvRtpSink(session)),
+            ("recv_rtcp_sink", 1) => Some(RtpBinPad::RecvRtcpSink(session)),
+            ("recv_rtp_src", 3) => {
+                match (ids[1].parse::<u32>(), ids[2].parse::<u8>()){
+                    (Ok(ssrc), Ok(pt)) => Some(RtpBinPad::RecvRtpSrc(session, ssrc, pt)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
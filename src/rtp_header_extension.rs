@@ -0,0 +1,262 @@
+use ffi::*;
+use util::*;
+use object::Object;
+use element::Element;
+use buffer::Buffer;
+use caps::Caps;
+use reference::Reference;
+use handle::SignalHandle;
+use value::{Value, ToValue, FromValue};
+use version::ApiAvailability;
+
+use std::ops::{Deref, DerefMut};
+
+const RTP_HEADER_EXTENSION: ApiAvailability = ApiAvailability::new("RtpHeaderExtension", 1, 20, 0);
+
+// the functions used once there's an extension, which can only exist if
+// they are available
+unsafe fn ext_fn<F: Copy>(symbol: &str) -> F{
+    RTP_HEADER_EXTENSION.function(symbol).expect("RtpHeaderExtension without GStreamer 1.20")
+}
+
+/// Uri of the client-to-mixer audio level extension, RFC 6464
+pub const AUDIO_LEVEL_URI: &'static str = "urn:ietf:params:rtp-hdrext:ssrc-audio-level";
+/// Uri of the transport-wide congestion control sequence number
+pub const TRANSPORT_CC_URI: &'static str = "http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01";
+/// Uri of the media identification extension used with BUNDLE, RFC 8843
+pub const MID_URI: &'static str = "urn:ietf:params:rtp-hdrext:sdes:mid";
+
+/// Header formats an extension can be read from or written to, mirrors
+/// GstRTPHeaderExtensionFlags
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum HeaderFlags{
+    OneByte = GST_RTP_HEADER_EXTENSION_ONE_BYTE,
+    TwoByte = GST_RTP_HEADER_EXTENSION_TWO_BYTE,
+}
+
+/// An RTP header extension implementation, as registered by plugins
+/// for an uri. Payloaders write it into the packets they produce and
+/// depayloaders parse it back into buffer metas:
+///
+/// ```ignore
+/// let mut ext = gst::RtpHeaderExtension::create_from_uri(gst::rtp_header_extension::AUDIO_LEVEL_URI).unwrap();
+/// ext.set_id(1);
+/// gst::rtp_header_extension::add_extension(&mut payloader, &ext);
+/// ```
+///
+/// Needs GStreamer 1.20, with older versions extensions can't be created
+pub struct RtpHeaderExtension{
+    ext: Object
+}
+
+impl RtpHeaderExtension{
+    pub unsafe fn new(ext: *mut GstRTPHeaderExtension) -> Option<RtpHeaderExtension>{
+        Object::new(ext as *mut GstObject).map(|obj| RtpHeaderExtension{ ext: obj })
+    }
+
+    /// Creates the extension registered for uri, None if no installed
+    /// plugin implements it or with GStreamer older than 1.20
+    pub fn create_from_uri(uri: &str) -> Option<RtpHeaderExtension>{
+        let curi = CString::new(uri).unwrap();
+        unsafe{
+            let create_from_uri: unsafe extern "C" fn(*const gchar) -> *mut GstRTPHeaderExtension =
+                RTP_HEADER_EXTENSION.function("gst_rtp_header_extension_create_from_uri").ok()?;
+            let ext = create_from_uri(curi.as_ptr());
+            if ext == ptr::null_mut(){
+                return None;
+            }
+            // the new extension can be floating, or already owned by
+            // the caller if the plugin sank it
+            if g_object_is_floating(ext as gpointer) != 0{
+                gst_object_ref_sink(ext as gpointer);
+            }
+            RtpHeaderExtension::new(ext)
+        }
+    }
+
+    pub fn uri(&self) -> Option<&str>{
+        unsafe{
+            let get_uri: unsafe extern "C" fn(*mut GstRTPHeaderExtension) -> *const gchar = ext_fn("gst_rtp_header_extension_get_uri");
+            let uri = get_uri(self.gst_rtp_header_extension());
+            if uri != ptr::null(){
+                Some(from_c_str!(uri))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Id of the extension in the packets, negotiated through the
+    /// extmap attribute in SDP
+    pub fn id(&self) -> u32{
+        unsafe{
+            let get_id: unsafe extern "C" fn(*mut GstRTPHeaderExtension) -> guint = ext_fn("gst_rtp_header_extension_get_id");
+            get_id(self.gst_rtp_header_extension())
+        }
+    }
+
+    pub fn set_id(&mut self, id: u32){
+        unsafe{
+            let set_id: unsafe extern "C" fn(*mut GstRTPHeaderExtension, guint) = ext_fn("gst_rtp_header_extension_set_id");
+            set_id(self.gst_rtp_header_extension(), id);
+        }
+    }
+
+    pub fn supports(&self, flags: HeaderFlags) -> bool{
+        unsafe{
+            let get_supported_flags: unsafe extern "C" fn(*mut GstRTPHeaderExtension) -> GstRTPHeaderExtensionFlags =
+                ext_fn("gst_rtp_header_extension_get_supported_flags");
+            get_supported_flags(self.gst_rtp_header_extension()) & flags as u32 != 0
+        }
+    }
+
+    /// Maximum size the extension data can take when written for
+    /// input_meta, the buffer metas are written from
+    pub fn max_size(&self, input_meta: &Buffer) -> usize{
+        unsafe{
+            let get_max_size: unsafe extern "C" fn(*mut GstRTPHeaderExtension, *const GstBuffer) -> gsize =
+                ext_fn("gst_rtp_header_extension_get_max_size");
+            get_max_size(self.gst_rtp_header_extension(), input_meta.gst_buffer()) as usize
+        }
+    }
+
+    /// Writes the extension data for input_meta into data, returns the
+    /// number of bytes written or None on error. output is the RTP
+    /// packet the data will be part of.
+    pub fn write(&self, input_meta: &Buffer, flags: HeaderFlags, output: &mut Buffer, data: &mut [u8]) -> Option<usize>{
+        unsafe{
+            let write: unsafe extern "C" fn(*mut GstRTPHeaderExtension, *const GstBuffer, GstRTPHeaderExtensionFlags, *mut GstBuffer, *mut guint8, gsize) -> gssize =
+                ext_fn("gst_rtp_header_extension_write");
+            let written = write(self.gst_rtp_header_extension(), input_meta.gst_buffer(),
+                flags as GstRTPHeaderExtensionFlags, output.gst_buffer_mut(), data.as_mut_ptr(), data.len() as gsize);
+            if written >= 0{
+                Some(written as usize)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Parses the extension data, usually adding a meta to buffer
+    pub fn read(&self, flags: HeaderFlags, data: &[u8], buffer: &mut Buffer) -> bool{
+        unsafe{
+            let read: unsafe extern "C" fn(*mut GstRTPHeaderExtension, GstRTPHeaderExtensionFlags, *const guint8, gsize, *mut GstBuffer) -> gboolean =
+                ext_fn("gst_rtp_header_extension_read");
+            read(self.gst_rtp_header_extension(), flags as GstRTPHeaderExtensionFlags,
+                data.as_ptr(), data.len() as gsize, buffer.gst_buffer_mut()) != 0
+        }
+    }
+
+    /// Configures the extension from the extmap-N fields of RTP caps
+    pub fn set_attributes_from_caps(&mut self, caps: &Caps) -> bool{
+        unsafe{
+            let set_attributes_from_caps: unsafe extern "C" fn(*mut GstRTPHeaderExtension, *const GstCaps) -> gboolean =
+                ext_fn("gst_rtp_header_extension_set_attributes_from_caps");
+            set_attributes_from_caps(self.gst_rtp_header_extension(), caps.gst_caps()) != 0
+        }
+    }
+
+    /// Adds the extmap-N field for this extension to caps, which have to
+    /// be writable
+    pub fn set_caps_from_attributes(&self, caps: &mut Caps) -> bool{
+        unsafe{
+            let set_caps_from_attributes: unsafe extern "C" fn(*mut GstRTPHeaderExtension, *mut GstCaps) -> gboolean =
+                ext_fn("gst_rtp_header_extension_set_caps_from_attributes");
+            set_caps_from_attributes(self.gst_rtp_header_extension(), caps.gst_caps_mut()) != 0
+        }
+    }
+
+    pub unsafe fn gst_rtp_header_extension(&self) -> *mut GstRTPHeaderExtension{
+        self.ext.gst_object() as *mut GstRTPHeaderExtension
+    }
+}
+
+/// Adds ext to an RTP payloader or depayloader so it's written to or
+/// read from every packet
+pub fn add_extension(element: &mut Element, ext: &RtpHeaderExtension){
+    element.emit_with_values("add-extension", &[ext.to_value()]);
+}
+
+/// Removes all the extensions from an RTP payloader or depayloader
+pub fn clear_extensions(element: &mut Element){
+    element.emit("clear-extensions");
+}
+
+/// Extensions currently in use by an RTP payloader or depayloader
+pub fn extensions(element: &Element) -> Vec<RtpHeaderExtension>{
+    let cname = CString::new("extensions").unwrap();
+    unsafe{
+        let mut value = Value::new(gst_value_array_get_type());
+        g_object_get_property(element.gst_element() as *mut GObject, cname.as_ptr(), value.gst_value_mut());
+        (0..gst_value_array_get_size(value.gst_value()))
+            .filter_map(|i| Value::new_from_gvalue(gst_value_array_get_value(value.gst_value(), i)).get::<RtpHeaderExtension>())
+            .collect()
+    }
+}
+
+/// Connects handler to the "request-extension" signal of an RTP
+/// payloader or depayloader, emitted when the caps reference an
+/// extension by id and uri that hasn't been added. Returning None lets
+/// the element create it automatically if possible.
+pub fn connect_request_extension<F>(element: &mut Element, mut handler: F) -> Option<SignalHandle>
+    where F: FnMut(u32, &str) -> Option<RtpHeaderExtension> + Send + 'static{
+    element.connect_values("request-extension", move |args| {
+        let id = args.get(1).and_then(|id| id.get::<u32>());
+        let uri = args.get(2).and_then(|uri| uri.get::<String>());
+        match (id, uri){
+            (Some(id), Some(uri)) => handler(id, &uri).map(|ext| ext.to_value()),
+            _ => None,
+        }
+    })
+}
+
+impl ToValue for RtpHeaderExtension{
+    fn to_value(&self) -> Value{
+        self.ext.to_value()
+    }
+}
+
+impl FromValue for RtpHeaderExtension{
+    fn from_value(value: &Value) -> Option<RtpHeaderExtension>{
+        unsafe{
+            let get_type = RTP_HEADER_EXTENSION.function::<unsafe extern "C" fn() -> GType>("gst_rtp_header_extension_get_type");
+            if get_type.map(|get_type| value.holds(get_type())).unwrap_or(false){
+                RtpHeaderExtension::new(g_value_dup_object(value.gst_value()) as *mut GstRTPHeaderExtension)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+impl Reference for RtpHeaderExtension{
+    fn reference(&self) -> RtpHeaderExtension{
+        RtpHeaderExtension{ ext: self.ext.reference() }
+    }
+}
+
+impl AsRef<Object> for RtpHeaderExtension{
+    fn as_ref(&self) -> &Object{
+        &self.ext
+    }
+}
+
+impl AsMut<Object> for RtpHeaderExtension{
+    fn as_mut(&mut self) -> &mut Object{
+        &mut self.ext
+    }
+}
+
+impl Deref for RtpHeaderExtension{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        &self.ext
+    }
+}
+
+impl DerefMut for RtpHeaderExtension{
+    fn deref_mut(&mut self) -> &mut Object{
+        &mut self.ext
+    }
+}
@@ -2,28 +2,28 @@ use ffi::*;
 use caps::Caps;
 use buffer::Buffer;
 use videoframe::VideoFrame;
-use std::mem;
+use segment::Segment;
 use std::ptr;
 use reference::Reference;
 use miniobject::MiniObject;
-
-unsafe impl Send for Sample {}
+use error::{Error, Result};
+use panic::catch_panic;
 
 #[derive(Clone)]
 pub struct Sample{
-	sample: MiniObject
+	sample: MiniObject<GstSample>
 }
 
 impl Sample{
 	pub unsafe fn new(sample: *mut GstSample) -> Option<Sample>{
-	    MiniObject::new_from_gst_miniobject(sample as *mut GstMiniObject)
+	    MiniObject::new(sample)
 			.map(|miniobject| Sample{ sample: miniobject })
 	}
 
 	/// Get the buffer associated with sample or None when there is no buffer.
     pub fn buffer(&self) -> Option<Buffer>{
         unsafe{
-        	let buffer = gst_sample_get_buffer(mem::transmute(self.gst_sample()));
+        	let buffer = gst_sample_get_buffer(self.gst_sample() as *mut GstSample);
         	if buffer != ptr::null_mut(){
 	            Buffer::new(gst_mini_object_ref(buffer as *mut GstMiniObject) as *mut GstBuffer)
 	        }else{
@@ -35,7 +35,7 @@ impl Sample{
 	/// Get the caps associated with sample or None when there's no caps
     pub fn caps(&self) -> Option<Caps>{
 		unsafe{
-			let caps = gst_sample_get_caps(mem::transmute(self.gst_sample()));
+			let caps = gst_sample_get_caps(self.gst_sample() as *mut GstSample);
         	if caps != ptr::null_mut(){
 	            Caps::new(gst_mini_object_ref(caps as *mut GstMiniObject) as *mut GstCaps)
 	        }else{
@@ -45,9 +45,9 @@ impl Sample{
 	}
 
     /// Get the segment associated with sample
-    pub fn segment(&self) -> GstSegment{
+    pub fn segment(&self) -> Segment{
         unsafe{
-            (*gst_sample_get_segment(mem::transmute(self.gst_sample())))
+            *gst_sample_get_segment(self.gst_sample() as *mut GstSample)
         }
     }
 
@@ -69,18 +69,70 @@ impl Sample{
         unsafe{ VideoFrame::new(vi, buffer) }
     }
 
+    /// Converts the video in this sample to to_caps, eg:
+    /// "video/x-raw,format=RGB" to encode it as PNG or "image/jpeg" if
+    /// a jpeg encoder is installed. Fields not in to_caps, like the
+    /// size, keep the values of the sample. Blocks up to timeout
+    /// nanoseconds while the conversion runs
+    pub fn convert_video(&self, to_caps: &Caps, timeout: GstClockTime) -> Result<Sample>{
+        unsafe{
+            let mut error: *mut GError = ptr::null_mut();
+            let converted = gst_video_convert_sample(self.gst_sample() as *mut GstSample, to_caps.gst_caps(), timeout, &mut error);
+            if error != ptr::null_mut(){
+                Err(Error::new_from_g_error(error))
+            }else{
+                Sample::new(converted).ok_or(Error::new(0, 0, "couldn't convert the sample"))
+            }
+        }
+    }
+
+    /// Same as convert_video but returns right away and calls callback
+    /// with the result once the conversion is done. The callback is
+    /// dispatched from the default main context, so a MainLoop has to
+    /// be running it
+    pub fn convert_video_async<F: FnOnce(Result<Sample>) + Send + 'static>(&self, to_caps: &Caps, timeout: GstClockTime, callback: F){
+        unsafe{
+            let callback: Box<Option<Box<dyn FnOnce(Result<Sample>) + Send>>> = Box::new(Some(Box::new(callback)));
+            gst_video_convert_sample_async(self.gst_sample() as *mut GstSample, to_caps.gst_caps(), timeout,
+                Some(convert_callback), Box::into_raw(callback) as gpointer, Some(convert_destroy));
+        }
+    }
+
     pub unsafe fn gst_sample(&self) -> *const GstSample{
-		self.sample.gst_miniobject() as *const GstSample
+		self.sample.as_ptr()
 	}
 
     pub unsafe fn gst_sample_mut(&mut self) -> *mut GstSample{
-		self.sample.gst_miniobject_mut() as *mut GstSample
+		self.sample.as_mut_ptr()
 	}
 }
 
+extern "C" fn convert_callback(sample: *mut GstSample, error: *mut GError, data: gpointer){
+    unsafe{
+        let callback = &mut *(data as *mut Option<Box<dyn FnOnce(Result<Sample>) + Send>>);
+        let result = if error != ptr::null_mut(){
+            if sample != ptr::null_mut(){
+                gst_mini_object_unref(sample as *mut GstMiniObject);
+            }
+            Err(Error::new_from_g_error(error))
+        }else{
+            Sample::new(sample).ok_or(Error::new(0, 0, "couldn't convert the sample"))
+        };
+        if let Some(callback) = callback.take(){
+            catch_panic(ptr::null_mut(), (), move || callback(result));
+        }
+    }
+}
+
+extern "C" fn convert_destroy(data: gpointer){
+    unsafe{
+        drop(Box::from_raw(data as *mut Option<Box<dyn FnOnce(Result<Sample>) + Send>>));
+    }
+}
+
 impl ::Transfer<GstSample> for Sample{
     unsafe fn transfer(self) ->  *mut GstSample{
-        self.sample.transfer() as *mut GstSample
+        self.sample.transfer()
     }
 }
 
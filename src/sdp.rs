@@ -0,0 +1,462 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+
+use std::fmt;
+
+/// An a= line of a session description or of one of its medias, value
+/// is None for flags like "a=sendrecv"
+#[derive(Clone,Debug,PartialEq)]
+pub struct SdpAttribute{
+    pub key: String,
+    pub value: Option<String>,
+}
+
+impl SdpAttribute{
+    unsafe fn new_from_gst_attribute(attr: *const GstSDPAttribute) -> Option<SdpAttribute>{
+        if attr == ptr::null() || (*attr).key == ptr::null_mut(){
+            return None;
+        }
+        Some(SdpAttribute{
+            key: from_c_str!((*attr).key).to_string(),
+            value: to_option_string((*attr).value),
+        })
+    }
+}
+
+unsafe fn to_option_string(s: *const gchar) -> Option<String>{
+    if s != ptr::null(){
+        Some(from_c_str!(s).to_string())
+    }else{
+        None
+    }
+}
+
+fn attribute_value(attributes: Vec<SdpAttribute>, key: &str) -> Option<String>{
+    attributes.into_iter()
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.unwrap_or_default())
+}
+
+/// A session description as exchanged during WebRTC or RTSP signaling,
+/// wraps GstSDPMessage.
+///
+/// Received descriptions are parsed with parse, to build one from
+/// scratch fill the session fields and add a media per stream:
+///
+/// ```ignore
+/// let mut sdp = gst::SdpMessage::new();
+/// sdp.set_version("0");
+/// sdp.set_origin("-", "1188340656180883", "1", "IN", "IP4", "127.0.0.1");
+/// sdp.set_session_name("stream");
+/// sdp.add_time("0", "0");
+/// let mut media = gst::SdpMedia::new();
+/// media.set_media("video");
+/// media.set_port(5000, 1);
+/// media.set_proto("RTP/AVP");
+/// media.add_format("96");
+/// media.add_attribute("rtpmap", Some("96 H264/90000"));
+/// sdp.add_media(&media);
+/// println!("{}", sdp.as_text());
+/// ```
+pub struct SdpMessage{
+    msg: *mut GstSDPMessage
+}
+
+unsafe impl Send for SdpMessage {}
+unsafe impl Sync for SdpMessage {}
+
+impl SdpMessage{
+    /// Takes ownership of msg, which will be freed on drop
+    pub unsafe fn new_from_gst_sdp_message(msg: *mut GstSDPMessage) -> Option<SdpMessage>{
+        if msg != ptr::null_mut(){
+            Some(SdpMessage{ msg: msg })
+        }else{
+            None
+        }
+    }
+
+    /// Creates an empty message
+    pub fn new() -> SdpMessage{
+        unsafe{
+            let mut msg = ptr::null_mut();
+            gst_sdp_message_new(&mut msg);
+            SdpMessage::new_from_gst_sdp_message(msg).unwrap()
+        }
+    }
+
+    /// Parses an SDP as received from the remote peer, None if it's not
+    /// valid
+    pub fn parse(text: &str) -> Option<SdpMessage>{
+        let msg = SdpMessage::new();
+        unsafe{
+            if gst_sdp_message_parse_buffer(text.as_ptr(), text.len() as guint, msg.msg) == GST_SDP_OK{
+                Some(msg)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Serializes the message to be sent to the remote peer
+    pub fn as_text(&self) -> String{
+        unsafe{
+            let text = gst_sdp_message_as_text(self.msg);
+            let ret = from_c_str!(text).to_string();
+            g_free(text as gpointer);
+            ret
+        }
+    }
+
+    /// The v= line, "0" for every SDP in use
+    pub fn version(&self) -> Option<String>{
+        unsafe{ to_option_string(gst_sdp_message_get_version(self.msg)) }
+    }
+
+    pub fn set_version(&mut self, version: &str){
+        let cversion = CString::new(version).unwrap();
+        unsafe{
+            gst_sdp_message_set_version(self.msg, cversion.as_ptr());
+        }
+    }
+
+    /// Sets the o= line, nettype is "IN" and addrtype "IP4" or "IP6"
+    pub fn set_origin(&mut self, username: &str, session_id: &str, session_version: &str, nettype: &str, addrtype: &str, address: &str){
+        let cusername = CString::new(username).unwrap();
+        let csession_id = CString::new(session_id).unwrap();
+        let csession_version = CString::new(session_version).unwrap();
+        let cnettype = CString::new(nettype).unwrap();
+        let caddrtype = CString::new(addrtype).unwrap();
+        let caddress = CString::new(address).unwrap();
+        unsafe{
+            gst_sdp_message_set_origin(self.msg, cusername.as_ptr(), csession_id.as_ptr(), csession_version.as_ptr(),
+                cnettype.as_ptr(), caddrtype.as_ptr(), caddress.as_ptr());
+        }
+    }
+
+    /// The s= line
+    pub fn session_name(&self) -> Option<String>{
+        unsafe{ to_option_string(gst_sdp_message_get_session_name(self.msg)) }
+    }
+
+    pub fn set_session_name(&mut self, name: &str){
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            gst_sdp_message_set_session_name(self.msg, cname.as_ptr());
+        }
+    }
+
+    /// Sets the c= line of the session, used by the medias that don't
+    /// have their own
+    pub fn set_connection(&mut self, nettype: &str, addrtype: &str, address: &str){
+        let cnettype = CString::new(nettype).unwrap();
+        let caddrtype = CString::new(addrtype).unwrap();
+        let caddress = CString::new(address).unwrap();
+        unsafe{
+            gst_sdp_message_set_connection(self.msg, cnettype.as_ptr(), caddrtype.as_ptr(), caddress.as_ptr(), 0, 0);
+        }
+    }
+
+    /// Adds a t= line, "0" "0" for a session that is always active
+    pub fn add_time(&mut self, start: &str, stop: &str){
+        let cstart = CString::new(start).unwrap();
+        let cstop = CString::new(stop).unwrap();
+        let mut repeat = [ptr::null()];
+        unsafe{
+            gst_sdp_message_add_time(self.msg, cstart.as_ptr(), cstop.as_ptr(), repeat.as_mut_ptr());
+        }
+    }
+
+    /// The session level attributes, in order
+    pub fn attributes(&self) -> Vec<SdpAttribute>{
+        unsafe{
+            (0..gst_sdp_message_attributes_len(self.msg))
+                .filter_map(|idx| SdpAttribute::new_from_gst_attribute(gst_sdp_message_get_attribute(self.msg, idx)))
+                .collect()
+        }
+    }
+
+    /// Value of the first session attribute called key, an empty string
+    /// for flags
+    pub fn attribute(&self, key: &str) -> Option<String>{
+        attribute_value(self.attributes(), key)
+    }
+
+    pub fn add_attribute(&mut self, key: &str, value: Option<&str>){
+        let ckey = CString::new(key).unwrap();
+        let cvalue = value.map(|value| CString::new(value).unwrap());
+        unsafe{
+            gst_sdp_message_add_attribute(self.msg, ckey.as_ptr(), cvalue.as_ref().map(|v| v.as_ptr()).unwrap_or(ptr::null()));
+        }
+    }
+
+    /// Removes every session attribute called key, returns false if
+    /// there was none
+    pub fn remove_attribute(&mut self, key: &str) -> bool{
+        let mut removed = false;
+        while let Some(idx) = self.attributes().iter().position(|attr| attr.key == key){
+            unsafe{
+                gst_sdp_message_remove_attribute(self.msg, idx as guint);
+            }
+            removed = true;
+        }
+        removed
+    }
+
+    pub fn medias_len(&self) -> u32{
+        unsafe{ gst_sdp_message_medias_len(self.msg) }
+    }
+
+    /// Returns a copy of the m= section at idx
+    pub fn media(&self, idx: u32) -> Option<SdpMedia>{
+        unsafe{
+            let media = gst_sdp_message_get_media(self.msg, idx);
+            if media == ptr::null(){
+                return None;
+            }
+            let mut copy = ptr::null_mut();
+            gst_sdp_media_copy(media, &mut copy);
+            SdpMedia::new_from_gst_sdp_media(copy)
+        }
+    }
+
+    /// Copies of all the m= sections, in order
+    pub fn medias(&self) -> Vec<SdpMedia>{
+        (0..self.medias_len()).filter_map(|idx| self.media(idx)).collect()
+    }
+
+    /// Appends a copy of media
+    pub fn add_media(&mut self, media: &SdpMedia){
+        unsafe{
+            gst_sdp_message_add_media(self.msg, media.media);
+        }
+    }
+
+    pub unsafe fn gst_sdp_message(&self) -> *const GstSDPMessage{
+        self.msg
+    }
+
+    pub unsafe fn gst_sdp_message_mut(&mut self) -> *mut GstSDPMessage{
+        self.msg
+    }
+
+    /// Gives up ownership of the message, the caller has to free it
+    pub unsafe fn into_gst_sdp_message(self) -> *mut GstSDPMessage{
+        let msg = self.msg;
+        mem::forget(self);
+        msg
+    }
+}
+
+impl Clone for SdpMessage{
+    fn clone(&self) -> SdpMessage{
+        unsafe{
+            let mut copy = ptr::null_mut();
+            gst_sdp_message_copy(self.msg, &mut copy);
+            SdpMessage::new_from_gst_sdp_message(copy).unwrap()
+        }
+    }
+}
+
+impl fmt::Display for SdpMessage{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        f.write_str(&self.as_text())
+    }
+}
+
+impl Drop for SdpMessage{
+    fn drop(&mut self){
+        unsafe{
+            gst_sdp_message_free(self.msg);
+        }
+    }
+}
+
+/// An m= section of a session description with its attributes, wraps
+/// GstSDPMedia
+pub struct SdpMedia{
+    media: *mut GstSDPMedia
+}
+
+unsafe impl Send for SdpMedia {}
+unsafe impl Sync for SdpMedia {}
+
+impl SdpMedia{
+    /// Takes ownership of media, which will be freed on drop
+    pub unsafe fn new_from_gst_sdp_media(media: *mut GstSDPMedia) -> Option<SdpMedia>{
+        if media != ptr::null_mut(){
+            Some(SdpMedia{ media: media })
+        }else{
+            None
+        }
+    }
+
+    pub fn new() -> SdpMedia{
+        unsafe{
+            let mut media = ptr::null_mut();
+            gst_sdp_media_new(&mut media);
+            SdpMedia::new_from_gst_sdp_media(media).unwrap()
+        }
+    }
+
+    /// Creates a media from the caps of an RTP stream, application/x-rtp,
+    /// filling the format, rtpmap and fmtp. The port and proto still
+    /// have to be set
+    pub fn from_caps(caps: &Caps) -> Option<SdpMedia>{
+        let media = SdpMedia::new();
+        unsafe{
+            if gst_sdp_media_set_media_from_caps(caps.gst_caps(), media.media) == GST_SDP_OK{
+                Some(media)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Caps of the RTP stream with payload type pt, as described by the
+    /// rtpmap and fmtp attributes
+    pub fn caps(&self, pt: i32) -> Option<Caps>{
+        unsafe{
+            Caps::new(gst_sdp_media_get_caps_from_media(self.media, pt))
+        }
+    }
+
+    pub fn as_text(&self) -> String{
+        unsafe{
+            let text = gst_sdp_media_as_text(self.media);
+            let ret = from_c_str!(text).to_string();
+            g_free(text as gpointer);
+            ret
+        }
+    }
+
+    /// "audio", "video", "application"...
+    pub fn media(&self) -> Option<String>{
+        unsafe{ to_option_string(gst_sdp_media_get_media(self.media)) }
+    }
+
+    pub fn set_media(&mut self, media: &str){
+        let cmedia = CString::new(media).unwrap();
+        unsafe{
+            gst_sdp_media_set_media(self.media, cmedia.as_ptr());
+        }
+    }
+
+    pub fn port(&self) -> u32{
+        unsafe{ gst_sdp_media_get_port(self.media) }
+    }
+
+    pub fn num_ports(&self) -> u32{
+        unsafe{ gst_sdp_media_get_num_ports(self.media) }
+    }
+
+    pub fn set_port(&mut self, port: u32, num_ports: u32){
+        unsafe{
+            gst_sdp_media_set_port_info(self.media, port, num_ports);
+        }
+    }
+
+    /// Transport protocol, eg: "RTP/AVP" or "UDP/TLS/RTP/SAVPF"
+    pub fn proto(&self) -> Option<String>{
+        unsafe{ to_option_string(gst_sdp_media_get_proto(self.media)) }
+    }
+
+    pub fn set_proto(&mut self, proto: &str){
+        let cproto = CString::new(proto).unwrap();
+        unsafe{
+            gst_sdp_media_set_proto(self.media, cproto.as_ptr());
+        }
+    }
+
+    /// The formats of the m= line, the payload types for RTP
+    pub fn formats(&self) -> Vec<String>{
+        unsafe{
+            (0..gst_sdp_media_formats_len(self.media))
+                .filter_map(|idx| to_option_string(gst_sdp_media_get_format(self.media, idx)))
+                .collect()
+        }
+    }
+
+    pub fn add_format(&mut self, format: &str){
+        let cformat = CString::new(format).unwrap();
+        unsafe{
+            gst_sdp_media_add_format(self.media, cformat.as_ptr());
+        }
+    }
+
+    /// The attributes of the media, in order
+    pub fn attributes(&self) -> Vec<SdpAttribute>{
+        unsafe{
+            (0..gst_sdp_media_attributes_len(self.media))
+                .filter_map(|idx| SdpAttribute::new_from_gst_attribute(gst_sdp_media_get_attribute(self.media, idx)))
+                .collect()
+        }
+    }
+
+    /// Value of the first attribute called key, an empty string for
+    /// flags
+    pub fn attribute(&self, key: &str) -> Option<String>{
+        attribute_value(self.attributes(), key)
+    }
+
+    /// Values of all the attributes called key, eg: every "rtpmap"
+    pub fn attribute_values(&self, key: &str) -> Vec<String>{
+        self.attributes().into_iter()
+            .filter(|attr| attr.key == key)
+            .map(|attr| attr.value.unwrap_or_default())
+            .collect()
+    }
+
+    pub fn add_attribute(&mut self, key: &str, value: Option<&str>){
+        let ckey = CString::new(key).unwrap();
+        let cvalue = value.map(|value| CString::new(value).unwrap());
+        unsafe{
+            gst_sdp_media_add_attribute(self.media, ckey.as_ptr(), cvalue.as_ref().map(|v| v.as_ptr()).unwrap_or(ptr::null()));
+        }
+    }
+
+    /// Removes every attribute called key, returns false if there was
+    /// none
+    pub fn remove_attribute(&mut self, key: &str) -> bool{
+        let mut removed = false;
+        while let Some(idx) = self.attributes().iter().position(|attr| attr.key == key){
+            unsafe{
+                gst_sdp_media_remove_attribute(self.media, idx as guint);
+            }
+            removed = true;
+        }
+        removed
+    }
+
+    pub unsafe fn gst_sdp_media(&self) -> *const GstSDPMedia{
+        self.media
+    }
+
+    pub unsafe fn gst_sdp_media_mut(&mut self) -> *mut GstSDPMedia{
+        self.media
+    }
+}
+
+impl Clone for SdpMedia{
+    fn clone(&self) -> SdpMedia{
+        unsafe{
+            let mut copy = ptr::null_mut();
+            gst_sdp_media_copy(self.media, &mut copy);
+            SdpMedia::new_from_gst_sdp_media(copy).unwrap()
+        }
+    }
+}
+
+impl fmt::Display for SdpMedia{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        f.write_str(&self.as_text())
+    }
+}
+
+impl Drop for SdpMedia{
+    fn drop(&mut self){
+        unsafe{
+            gst_sdp_media_free(self.media);
+        }
+    }
+}
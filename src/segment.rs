@@ -0,0 +1,190 @@
+use ffi::*;
+use util::*;
+
+/// Part of a stream to be played, as sent downstream in segment events
+/// and returned by Sample::segment. Used to convert buffer timestamps to
+/// running time, which is what's synchronized against the clock, or to
+/// stream time, the position reported to the user.
+pub type Segment = GstSegment;
+
+#[inline]
+fn time_to_option(time: guint64) -> Option<u64>{
+    if time != GST_CLOCK_TIME_NONE{
+        Some(time as u64)
+    }else{
+        None
+    }
+}
+
+impl Segment{
+    /// Creates a segment in format that covers the whole stream at
+    /// normal rate
+    pub fn new(format: GstFormat) -> Segment{
+        unsafe{
+            let mut segment: Segment = mem::zeroed();
+            gst_segment_init(&mut segment, format);
+            segment
+        }
+    }
+
+    /// Copies the segment from a segment event, for example the one
+    /// received in a pad probe
+    pub unsafe fn new_from_event(event: *mut GstEvent) -> Option<Segment>{
+        if event != ptr::null_mut() && (*event)._type == GST_EVENT_SEGMENT{
+            let mut segment: Segment = mem::zeroed();
+            gst_event_copy_segment(event, &mut segment);
+            Some(segment)
+        }else{
+            None
+        }
+    }
+
+    /// Creates a new segment event to be pushed downstream
+    pub fn to_event(&self) -> *mut GstEvent{
+        unsafe{ gst_event_new_segment(self) }
+    }
+
+    #[inline]
+    pub fn format(&self) -> GstFormat{
+        self.format
+    }
+
+    #[inline]
+    pub fn flags(&self) -> GstSegmentFlags{
+        self.flags
+    }
+
+    /// Playback rate, negative for reverse playback
+    #[inline]
+    pub fn rate(&self) -> f64{
+        self.rate
+    }
+
+    #[inline]
+    pub fn set_rate(&mut self, rate: f64){
+        self.rate = rate
+    }
+
+    /// Rate already applied to the stream upstream, eg. by an element
+    /// doing the trick mode itself
+    #[inline]
+    pub fn applied_rate(&self) -> f64{
+        self.applied_rate
+    }
+
+    #[inline]
+    pub fn set_applied_rate(&mut self, applied_rate: f64){
+        self.applied_rate = applied_rate
+    }
+
+    /// Running time accumulated by previous segments
+    #[inline]
+    pub fn base(&self) -> u64{
+        self.base as u64
+    }
+
+    #[inline]
+    pub fn set_base(&mut self, base: u64){
+        self.base = base as guint64
+    }
+
+    #[inline]
+    pub fn offset(&self) -> u64{
+        self.offset as u64
+    }
+
+    #[inline]
+    pub fn start(&self) -> u64{
+        self.start as u64
+    }
+
+    #[inline]
+    pub fn set_start(&mut self, start: u64){
+        self.start = start as guint64
+    }
+
+    /// None if the segment extends until the end of the stream
+    #[inline]
+    pub fn stop(&self) -> Option<u64>{
+        time_to_option(self.stop)
+    }
+
+    #[inline]
+    pub fn set_stop(&mut self, stop: Option<u64>){
+        self.stop = stop.map(|stop| stop as guint64).unwrap_or(GST_CLOCK_TIME_NONE)
+    }
+
+    /// Stream time corresponding to start
+    #[inline]
+    pub fn time(&self) -> u64{
+        self.time as u64
+    }
+
+    #[inline]
+    pub fn set_time(&mut self, time: u64){
+        self.time = time as guint64
+    }
+
+    /// Last known position in the segment
+    #[inline]
+    pub fn position(&self) -> u64{
+        self.position as u64
+    }
+
+    #[inline]
+    pub fn set_position(&mut self, position: u64){
+        self.position = position as guint64
+    }
+
+    #[inline]
+    pub fn duration(&self) -> Option<u64>{
+        time_to_option(self.duration)
+    }
+
+    /// Converts position, in format, to running time. Returns None if the
+    /// position is outside of the segment or format doesn't match
+    pub fn to_running_time(&self, format: GstFormat, position: u64) -> Option<u64>{
+        unsafe{
+            time_to_option(gst_segment_to_running_time(self, format, position as guint64))
+        }
+    }
+
+    /// Converts position, in format, to stream time. Returns None if the
+    /// position is outside of the segment or format doesn't match
+    pub fn to_stream_time(&self, format: GstFormat, position: u64) -> Option<u64>{
+        unsafe{
+            time_to_option(gst_segment_to_stream_time(self, format, position as guint64))
+        }
+    }
+
+    /// Converts running_time back to a position in format, the inverse
+    /// of to_running_time
+    pub fn to_position(&self, format: GstFormat, running_time: u64) -> Option<u64>{
+        unsafe{
+            time_to_option(gst_segment_to_position(self, format, running_time as guint64))
+        }
+    }
+
+    /// Adjusts the segment so position corresponds to running_time
+    pub fn set_running_time(&mut self, format: GstFormat, running_time: u64) -> bool{
+        unsafe{
+            gst_segment_set_running_time(self, format, running_time as guint64) != 0
+        }
+    }
+
+    /// Clips the range start-stop, in format, to the segment. Returns
+    /// None if it's completely outside, otherwise the clipped start and
+    /// stop.
+    pub fn clip(&self, format: GstFormat, start: u64, stop: Option<u64>) -> Option<(u64, Option<u64>)>{
+        unsafe{
+            let mut clip_start: guint64 = 0;
+            let mut clip_stop: guint64 = 0;
+            let stop = stop.map(|stop| stop as guint64).unwrap_or(GST_CLOCK_TIME_NONE);
+            if gst_segment_clip(self, format, start as guint64, stop, &mut clip_start, &mut clip_stop) != 0{
+                Some((clip_start as u64, time_to_option(clip_stop)))
+            }else{
+                None
+            }
+        }
+    }
+}
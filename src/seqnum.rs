@@ -0,0 +1,132 @@
+use ffi::*;
+use util::*;
+use element::Element;
+use message::Message;
+
+use std::cmp::Ordering;
+
+/// A new sequence number, different from every other one returned
+/// before, to mark events and the messages they cause
+pub fn next() -> u32{
+    unsafe{
+        gst_util_seqnum_next()
+    }
+}
+
+/// Compares two sequence numbers handling wraparound, an older seqnum
+/// is Less than a newer one
+pub fn compare(s1: u32, s2: u32) -> Ordering{
+    unsafe{
+        gst_util_seqnum_compare(s1, s2).cmp(&0)
+    }
+}
+
+pub unsafe fn event_seqnum(event: *mut GstEvent) -> u32{
+    gst_event_get_seqnum(event)
+}
+
+/// Marks event with seqnum, the messages and events elements produce
+/// in response to it carry the same seqnum. The event has to be
+/// writable
+pub unsafe fn set_event_seqnum(event: *mut GstEvent, seqnum: u32){
+    gst_event_set_seqnum(event, seqnum);
+}
+
+/// Message that completes a seek
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum SeekCompletion{
+    /// The pipeline prerolled again after a flushing seek
+    AsyncDone,
+    /// A segment seek reached the end of the segment
+    SegmentDone,
+}
+
+impl Element{
+    /// Same as seek but returns the seqnum of the seek event, None if
+    /// the seek failed. The AsyncDone and SegmentDone messages caused
+    /// by the seek have the same seqnum, see PendingSeeks
+    pub fn seek_with_seqnum(&mut self, rate: f64, format: GstFormat, flags: GstSeekFlags, start_type: GstSeekType, start: i64, stop_type: GstSeekType, stop: i64) -> Option<u32>{
+        unsafe{
+            let event = gst_event_new_seek(rate, format, flags, start_type, start, stop_type, stop);
+            if event == ptr::null_mut(){
+                return None;
+            }
+            let seqnum = next();
+            set_event_seqnum(event, seqnum);
+            if self.send_event(event){
+                Some(seqnum)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+impl Message{
+    /// If the message completes the seek with seqnum, how
+    pub fn seek_completion(&self, seqnum: u32) -> Option<SeekCompletion>{
+        if self.seqnum() != seqnum{
+            return None;
+        }
+        match self.ty(){
+            GST_MESSAGE_ASYNC_DONE => Some(SeekCompletion::AsyncDone),
+            GST_MESSAGE_SEGMENT_DONE => Some(SeekCompletion::SegmentDone),
+            _ => None,
+        }
+    }
+}
+
+/// Seeks waiting for completion, to match the messages on the bus with
+/// the seek that caused them when the application seeks again before
+/// the previous seek finished, eg: while the user drags a slider:
+///
+/// ```ignore
+/// let mut seeks = gst::seqnum::PendingSeeks::new();
+/// if let Some(seqnum) = pipeline.seek_with_seqnum(1.0, GST_FORMAT_TIME, GST_SEEK_FLAG_FLUSH,
+///         GST_SEEK_TYPE_SET, position, GST_SEEK_TYPE_NONE, -1){
+///     seeks.push(seqnum);
+/// }
+/// // in the bus watch
+/// if let Some((seqnum, _)) = seeks.complete(&msg){
+///     if seeks.is_empty(){
+///         update_position_ui();
+///     }
+/// }
+/// ```
+pub struct PendingSeeks{
+    seqnums: Vec<u32>,
+}
+
+impl PendingSeeks{
+    pub fn new() -> PendingSeeks{
+        PendingSeeks{ seqnums: vec![] }
+    }
+
+    pub fn push(&mut self, seqnum: u32){
+        self.seqnums.push(seqnum);
+    }
+
+    /// If msg completes one of the pending seeks returns its seqnum and
+    /// how it completed. That seek and the older ones, which were
+    /// interrupted by it if it was a flushing seek, are removed
+    pub fn complete(&mut self, msg: &Message) -> Option<(u32, SeekCompletion)>{
+        let (seqnum, completion) = self.seqnums.iter()
+            .filter_map(|&seqnum| msg.seek_completion(seqnum).map(|completion| (seqnum, completion)))
+            .next()?;
+        self.seqnums.retain(|&pending| compare(pending, seqnum) == Ordering::Greater);
+        Some((seqnum, completion))
+    }
+
+    /// The most recent seek still waiting for completion
+    pub fn last(&self) -> Option<u32>{
+        self.seqnums.last().cloned()
+    }
+
+    pub fn len(&self) -> usize{
+        self.seqnums.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.seqnums.is_empty()
+    }
+}
@@ -0,0 +1,92 @@
+//! Serde support for the types that carry pipeline metadata, so it can
+//! be logged as JSON or sent to another process. Caps, Structure and
+//! TagList are serialized as their string representation, the same one
+//! used by gst-launch, which keeps the types of the fields and can be
+//! parsed back. Messages are only serialized, as their type, source and
+//! structure.
+
+use ffi::*;
+use util::*;
+
+use caps::Caps;
+use structure::Structure;
+use taglist::TagList;
+use message::Message;
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::Error as DeError;
+use serde::ser::SerializeStruct;
+
+impl Serialize for Caps{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>{
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Caps{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Caps, D::Error>{
+        let desc = String::deserialize(deserializer)?;
+        parse(&desc, Caps::from_string).ok_or(D::Error::custom(format!("invalid caps {}", desc)))
+    }
+}
+
+impl Serialize for Structure{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>{
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Structure{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Structure, D::Error>{
+        let desc = String::deserialize(deserializer)?;
+        parse(&desc, Structure::from_string).ok_or(D::Error::custom(format!("invalid structure {}", desc)))
+    }
+}
+
+impl Serialize for TagList{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>{
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for TagList{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<TagList, D::Error>{
+        let desc = String::deserialize(deserializer)?;
+        parse(&desc, TagList::from_string).ok_or(D::Error::custom(format!("invalid tag list {}", desc)))
+    }
+}
+
+// from_string panics with nul bytes, which can come from any input
+fn parse<T, F: Fn(&str) -> Option<T>>(desc: &str, from_string: F) -> Option<T>{
+    if desc.contains('\0'){
+        None
+    }else{
+        from_string(desc)
+    }
+}
+
+/// Serialized as a struct with the type name, eg: "error" or "element",
+/// the seqnum, the timestamp, the path of the object that posted it, if
+/// any, and its structure, if it has one
+impl Serialize for Message{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>{
+        let source = unsafe{
+            let src = self.src();
+            if src == ptr::null_mut(){
+                None
+            }else{
+                let path = gst_object_get_path_string(src);
+                let ret = from_c_str!(path).to_string();
+                g_free(path as gpointer);
+                Some(ret)
+            }
+        };
+        let mut state = serializer.serialize_struct("Message", 5)?;
+        state.serialize_field("type", &self.type_name())?;
+        state.serialize_field("seqnum", &self.seqnum())?;
+        state.serialize_field("timestamp", &self.timestamp())?;
+        state.serialize_field("source", &source)?;
+        state.serialize_field("structure", &self.get_structure())?;
+        state.end()
+    }
+}
@@ -0,0 +1,239 @@
+use ffi::*;
+use element::Element;
+use handle::SignalHandle;
+use reference::Reference;
+
+use std::ops::{Deref, DerefMut};
+
+/// Wrapper for shmsink, which writes buffers to a shared memory area
+/// and announces them to the shmsrc elements connected to its control
+/// socket, so other processes on the same machine can read them without
+/// copying the data through a pipe.
+///
+/// shmsrc doesn't receive the caps so the reading process has to know
+/// them and set them with a capsfilter:
+///
+/// ```ignore
+/// // producer
+/// let mut sink = gst::ShmSink::new("shm").unwrap();
+/// sink.set_socket_path("/tmp/camera.sock");
+/// sink.set_shm_size(20 * 1024 * 1024);
+/// sink.set_wait_for_connection(false);
+/// sink.connect_client_connected(|id| println!("client {} connected", id)).unwrap().forget();
+/// // v4l2src ! video/x-raw,format=I420,width=1280,height=720 ! shmsink
+///
+/// // consumer
+/// let mut src = gst::ShmSrc::new("shm").unwrap();
+/// src.set_socket_path("/tmp/camera.sock");
+/// src.set_is_live(true);
+/// // shmsrc ! video/x-raw,format=I420,width=1280,height=720,framerate=30/1 ! queue ! ...
+/// ```
+///
+/// The shared memory has to be big enough for the buffers not yet
+/// released by the readers, a few frames at least. Buffers written
+/// while it's full block the sink until the readers release some.
+pub struct ShmSink{
+    shmsink: Element
+}
+
+impl ShmSink{
+    pub fn new(name: &str) -> Option<ShmSink>{
+        Element::new("shmsink", name).map(|e| ShmSink{ shmsink: e })
+    }
+
+    pub fn new_from_element(element: Element) -> ShmSink{
+        ShmSink{ shmsink: element }
+    }
+
+    /// Path of the unix socket the readers connect to, it's created by
+    /// the sink and has to be set before going to READY
+    pub fn set_socket_path(&mut self, path: &str){
+        self.set("socket-path", path);
+    }
+
+    pub fn socket_path(&self) -> Option<String>{
+        self.get_string("socket-path")
+    }
+
+    /// Size in bytes of the shared memory area
+    pub fn set_shm_size(&mut self, size: u32){
+        self.set("shm-size", size);
+    }
+
+    pub fn shm_size(&self) -> u32{
+        self.get::<u32>("shm-size")
+    }
+
+    /// Whether to block the pipeline until a reader connects, true by
+    /// default. Set it to false for producers that keep running without
+    /// readers
+    pub fn set_wait_for_connection(&mut self, wait: bool){
+        self.set("wait-for-connection", wait as gboolean);
+    }
+
+    pub fn wait_for_connection(&self) -> bool{
+        self.get::<gboolean>("wait-for-connection") != 0
+    }
+
+    /// Permissions of the shared memory area, eg: 0o660 to allow
+    /// readers from other users of the group
+    pub fn set_perms(&mut self, perms: u32){
+        self.set("perms", perms);
+    }
+
+    /// Maximum time of data in nanoseconds kept in the shared memory
+    /// waiting for the readers, -1 for no limit
+    pub fn set_buffer_time(&mut self, time: i64){
+        self.set("buffer-time", time);
+    }
+
+    /// Calls handler with the id of every reader that connects
+    pub fn connect_client_connected<F>(&mut self, handler: F) -> Option<SignalHandle>
+        where F: FnMut(i32) + Send + 'static{
+        self.connect_client_signal("client-connected", handler)
+    }
+
+    /// Calls handler with the id of every reader that disconnects
+    pub fn connect_client_disconnected<F>(&mut self, handler: F) -> Option<SignalHandle>
+        where F: FnMut(i32) + Send + 'static{
+        self.connect_client_signal("client-disconnected", handler)
+    }
+
+    fn connect_client_signal<F>(&mut self, signal: &str, mut handler: F) -> Option<SignalHandle>
+        where F: FnMut(i32) + Send + 'static{
+        self.connect_values(signal, move |args| {
+            if let Some(id) = args.get(1).and_then(|id| id.get::<i32>()){
+                handler(id);
+            }
+            None
+        })
+    }
+}
+
+impl Reference for ShmSink{
+    fn reference(&self) -> ShmSink{
+        ShmSink{ shmsink: self.shmsink.reference() }
+    }
+}
+
+impl AsRef<Element> for ShmSink{
+    fn as_ref(&self) -> &Element{
+        &self.shmsink
+    }
+}
+
+impl AsMut<Element> for ShmSink{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.shmsink
+    }
+}
+
+impl From<ShmSink> for Element{
+    fn from(s: ShmSink) -> Element{
+        s.shmsink
+    }
+}
+
+impl Deref for ShmSink{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.shmsink
+    }
+}
+
+impl DerefMut for ShmSink{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.shmsink
+    }
+}
+
+impl ::Transfer for ShmSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.shmsink.transfer()
+    }
+}
+
+/// Wrapper for shmsrc, which reads the buffers written by a shmsink in
+/// another process, see ShmSink
+pub struct ShmSrc{
+    shmsrc: Element
+}
+
+impl ShmSrc{
+    pub fn new(name: &str) -> Option<ShmSrc>{
+        Element::new("shmsrc", name).map(|e| ShmSrc{ shmsrc: e })
+    }
+
+    pub fn new_from_element(element: Element) -> ShmSrc{
+        ShmSrc{ shmsrc: element }
+    }
+
+    /// Path of the socket of the shmsink to read from
+    pub fn set_socket_path(&mut self, path: &str){
+        self.set("socket-path", path);
+    }
+
+    pub fn socket_path(&self) -> Option<String>{
+        self.get_string("socket-path")
+    }
+
+    /// Whether to behave as a live source, timestamping the buffers with
+    /// the running time they are received at. Set it when the producer
+    /// is live, eg: a camera
+    pub fn set_is_live(&mut self, is_live: bool){
+        self.set("is-live", is_live as gboolean);
+    }
+
+    pub fn is_live(&self) -> bool{
+        self.get::<gboolean>("is-live") != 0
+    }
+
+    /// Name of the shared memory area, available once connected to the
+    /// sink
+    pub fn shm_area_name(&self) -> Option<String>{
+        self.get_string("shm-area-name")
+    }
+}
+
+impl Reference for ShmSrc{
+    fn reference(&self) -> ShmSrc{
+        ShmSrc{ shmsrc: self.shmsrc.reference() }
+    }
+}
+
+impl AsRef<Element> for ShmSrc{
+    fn as_ref(&self) -> &Element{
+        &self.shmsrc
+    }
+}
+
+impl AsMut<Element> for ShmSrc{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.shmsrc
+    }
+}
+
+impl From<ShmSrc> for Element{
+    fn from(s: ShmSrc) -> Element{
+        s.shmsrc
+    }
+}
+
+impl Deref for ShmSrc{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.shmsrc
+    }
+}
+
+impl DerefMut for ShmSrc{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.shmsrc
+    }
+}
+
+impl ::Transfer for ShmSrc{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.shmsrc.transfer()
+    }
+}
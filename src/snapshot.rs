@@ -0,0 +1,326 @@
+use ffi::*;
+use util::*;
+
+use bin::Bin;
+use element::Element;
+use pipeline::Pipeline;
+use structure::Structure;
+use value::Value;
+use error::Result;
+use registration::factory_name;
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::slice;
+
+/// The values of the writable properties of the elements of a bin, eg:
+/// the bitrate of an encoder or the latency of a jitterbuffer, to be
+/// saved and applied again later.
+///
+/// Each element is stored as a structure named "element" with its name
+/// in the "name" field and a field per property. Serialized, there's a
+/// structure per line so it can be edited by hand:
+///
+/// ```text
+/// element, name=(string)encoder, bitrate=(uint)2048, speed-preset=(GstX264EncPreset)veryfast;
+/// ```
+///
+/// ```ignore
+/// let snapshot = gst::snapshot::PropertySnapshot::take_changed(&pipeline);
+/// snapshot.save("tuning.txt").unwrap();
+/// // on the next run, before starting the pipeline
+/// let snapshot = gst::snapshot::PropertySnapshot::load("tuning.txt").unwrap();
+/// snapshot.apply(&mut pipeline);
+/// ```
+pub struct PropertySnapshot{
+    elements: Vec<Structure>
+}
+
+impl Clone for PropertySnapshot{
+    fn clone(&self) -> PropertySnapshot{
+        PropertySnapshot{
+            elements: self.elements.iter().map(|element| element.copy()).collect()
+        }
+    }
+}
+
+impl PropertySnapshot{
+    /// Snapshots every readable and writable property of every element
+    /// in bin, recursing into child bins
+    pub fn take(bin: &Bin) -> PropertySnapshot{
+        PropertySnapshot::take_filtered(bin, false)
+    }
+
+    /// Snapshots only the properties that don't have their default
+    /// value, which keeps tuning profiles small and independent from
+    /// the defaults of future versions of the elements
+    pub fn take_changed(bin: &Bin) -> PropertySnapshot{
+        PropertySnapshot::take_filtered(bin, true)
+    }
+
+    fn take_filtered(bin: &Bin, only_changed: bool) -> PropertySnapshot{
+        let elements = bin.iter_recurse()
+            .filter_map(|element| element.ok())
+            .map(|element| element_properties(&element, only_changed))
+            .filter(|properties| properties.n_fields() > 1)
+            .collect();
+        PropertySnapshot{ elements: elements }
+    }
+
+    /// Parses a snapshot serialized with to_string, None if any of the
+    /// lines is not a valid structure
+    pub fn from_string(desc: &str) -> Option<PropertySnapshot>{
+        let mut elements = vec![];
+        for line in desc.lines().map(|line| line.trim()).filter(|line| !line.is_empty() && !line.starts_with('#')){
+            match Structure::from_string(line){
+                Some(element) => elements.push(element),
+                None => return None,
+            }
+        }
+        Some(PropertySnapshot{ elements: elements })
+    }
+
+    pub fn to_string(&self) -> String{
+        self.elements.iter()
+            .map(|element| element.to_string() + "\n")
+            .collect()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<PropertySnapshot>{
+        let mut desc = String::new();
+        File::open(path).and_then(|mut file| file.read_to_string(&mut desc))?;
+        PropertySnapshot::from_string(&desc)
+            .ok_or(io::Error::new(io::ErrorKind::InvalidData, "not a valid property snapshot"))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()>{
+        File::create(path).and_then(|mut file| file.write_all(self.to_string().as_bytes()))
+    }
+
+    /// The snapshot of every element
+    pub fn elements(&self) -> &[Structure]{
+        &self.elements
+    }
+
+    /// The properties of the element with the given name
+    pub fn element(&self, name: &str) -> Option<&Structure>{
+        self.elements.iter().find(|element| element.get_string("name") == Some(name))
+    }
+
+    /// Sets the properties in the snapshot on the elements of bin with
+    /// the same names. Returns the ones that couldn't be set, as
+    /// "element.property", because the element or the property don't
+    /// exist or the value has the wrong type.
+    ///
+    /// Some properties can only be changed before the pipeline starts,
+    /// apply the snapshot in the NULL or READY states
+    pub fn apply(&self, bin: &mut Bin) -> Vec<String>{
+        let mut failed = vec![];
+        for properties in self.elements.iter(){
+            let name = match properties.get_string("name"){
+                Some(name) => name,
+                None => continue,
+            };
+            let mut element = bin.get_by_name(name);
+            for property in properties.field_names().into_iter().filter(|property| property != "name"){
+                let set = match (element.as_mut(), properties.get_value(&property)){
+                    (Some(element), Some(value)) => element.set_value(&property, &value),
+                    _ => false,
+                };
+                if !set{
+                    failed.push(format!("{}.{}", name, property));
+                }
+            }
+        }
+        failed
+    }
+}
+
+impl Pipeline{
+    /// A gst-launch description of the pipeline as it is now, with its
+    /// elements, the properties that don't have their default value and
+    /// the links between them, so a pipeline built or changed at runtime
+    /// can be saved and created again with new_from_description:
+    ///
+    /// ```text
+    /// videotestsrc name=src pattern=ball x264enc name=enc bitrate=2048 src.src ! enc.sink
+    /// ```
+    ///
+    /// Only the direct children of the pipeline are described, bins are
+    /// recreated from their factory with the properties set on them, so
+    /// elements added by hand to a plain bin are lost
+    pub fn describe(&self) -> String{
+        let elements: Vec<Element> = self.iter().filter_map(|element| element.ok()).collect();
+        let names: Vec<String> = elements.iter().map(|element| element.name()).collect();
+        let mut description = vec![];
+        // the iterator returns the last added element first
+        for element in elements.iter().rev(){
+            let factory = match factory_name(element){
+                Some(factory) => factory,
+                None => continue,
+            };
+            let mut desc = factory;
+            desc.push_str(&format!(" name={}", launch_value(&element.name())));
+            let properties = element_properties(element, true);
+            for property in properties.field_names().into_iter().filter(|property| property != "name"){
+                if let Some(value) = properties.get_value(&property).as_ref().and_then(serialize){
+                    desc.push_str(&format!(" {}={}", property, launch_value(&value)));
+                }
+            }
+            description.push(desc);
+        }
+        for element in elements.iter().rev(){
+            for pad in element.src_pads(){
+                let peer = match pad.peer(){
+                    Some(peer) => peer,
+                    None => continue,
+                };
+                let peer_element = match peer.parent(){
+                    Some(peer_element) => peer_element.name(),
+                    None => continue,
+                };
+                // links to pads of elements inside child bins can't be
+                // described from here
+                if !names.contains(&peer_element){
+                    continue;
+                }
+                description.push(format!("{}.{} ! {}.{}",
+                    launch_value(&element.name()), launch_value(&pad.name()),
+                    launch_value(&peer_element), launch_value(&peer.name())));
+            }
+        }
+        description.join(" ")
+    }
+
+    /// Creates a pipeline from a description returned by describe
+    pub fn new_from_description(description: &str) -> Result<Pipeline>{
+        Pipeline::new_from_str(description)
+    }
+}
+
+/// Quotes a name or serialized value if it has characters with a
+/// meaning in gst-launch syntax, eg: the spaces and commas of caps
+fn launch_value(value: &str) -> String{
+    if is_quoted(value){
+        return value.to_string();
+    }
+    let plain = !value.is_empty() && value.chars().all(|c| c.is_alphanumeric() || "-_+:/%@".contains(c));
+    if plain{
+        value.to_string()
+    }else{
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+// whether value is already a quoted string, as serialized strings are,
+// with any quote and backslash inside escaped
+fn is_quoted(value: &str) -> bool{
+    if value.len() < 2 || !value.starts_with('"') || !value.ends_with('"'){
+        return false;
+    }
+    let mut escaped = false;
+    for c in value[1..value.len() - 1].chars(){
+        match c{
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return false,
+            _ => {}
+        }
+    }
+    !escaped
+}
+
+/// Returns a structure with the name of element and the serializable
+/// properties that can be read and written after construction
+fn element_properties(element: &Element, only_changed: bool) -> Structure{
+    let mut properties = Structure::new("element");
+    properties.set_value("name", Value::from(&element.name()[..]));
+    unsafe{
+        let class = (*(element.gst_element() as *mut GTypeInstance)).g_class as *mut GObjectClass;
+        let mut n_pspecs = 0;
+        let pspecs = g_object_class_list_properties(class, &mut n_pspecs);
+        for &pspec in slice::from_raw_parts(pspecs, n_pspecs as usize){
+            let flags = (*pspec).flags;
+            let rw = G_PARAM_READABLE | G_PARAM_WRITABLE;
+            if flags & rw != rw || flags & G_PARAM_CONSTRUCT_ONLY != 0 || flags & G_PARAM_DEPRECATED != 0{
+                continue;
+            }
+            let name = from_c_str!((*pspec).name);
+            if name == "name" || name == "parent"{
+                continue;
+            }
+            let value = match element.get_value(name){
+                Some(value) => value,
+                None => continue,
+            };
+            let serialized = match serialize(&value){
+                Some(serialized) => serialized,
+                None => continue,
+            };
+            if only_changed{
+                let mut default = Value::new((*pspec).value_type);
+                g_param_value_set_default(pspec, default.gst_value_mut());
+                if serialize(&default).as_ref() == Some(&serialized){
+                    continue;
+                }
+            }
+            properties.set_value(name, value);
+        }
+        g_free(pspecs as gpointer);
+    }
+    properties
+}
+
+/// Objects and pointers can't be serialized, those properties are
+/// skipped
+fn serialize(value: &Value) -> Option<String>{
+    unsafe{
+        let serialized = gst_value_serialize(value.gst_value());
+        if serialized != ptr::null_mut(){
+            let ret = from_c_str!(serialized).to_string();
+            g_free(serialized as gpointer);
+            Some(ret)
+        }else{
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::launch_value;
+
+    #[test]
+    fn plain_values(){
+        assert_eq!(launch_value("videotestsrc0"), "videotestsrc0");
+        assert_eq!(launch_value("file:///tmp/a%20b"), "file:///tmp/a%20b");
+        assert_eq!(launch_value("-1"), "-1");
+    }
+
+    #[test]
+    fn quotes_special_characters(){
+        assert_eq!(launch_value(""), "\"\"");
+        assert_eq!(launch_value("my src"), "\"my src\"");
+        assert_eq!(launch_value("video/x-raw, width=(int)320"), "\"video/x-raw, width=(int)320\"");
+        assert_eq!(launch_value("a!b"), "\"a!b\"");
+        // dots separate element and pad names in links
+        assert_eq!(launch_value("src.mp4"), "\"src.mp4\"");
+    }
+
+    #[test]
+    fn escapes_quotes_and_backslashes(){
+        assert_eq!(launch_value("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(launch_value("C:\\dir"), "\"C:\\\\dir\"");
+    }
+
+    #[test]
+    fn keeps_quoted_strings(){
+        assert_eq!(launch_value("\"already quoted\""), "\"already quoted\"");
+        assert_eq!(launch_value("\"with \\\"escape\\\"\""), "\"with \\\"escape\\\"\"");
+        // only starting with a quote isn't enough
+        assert_eq!(launch_value("\"half"), "\"\\\"half\"");
+        assert_eq!(launch_value("\"a\" b \"c\""), "\"\\\"a\\\" b \\\"c\\\"\"");
+        assert_eq!(launch_value("\"ends with\\\""), "\"\\\"ends with\\\\\\\"\"");
+    }
+}
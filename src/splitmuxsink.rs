@@ -0,0 +1,201 @@
+use ffi::*;
+use element::Element;
+use message::Message;
+use sample::Sample;
+use structure::Structure;
+use value::Value;
+use handle::SignalHandle;
+use reference::Reference;
+
+use std::ops::{Deref, DerefMut};
+
+/// Wrapper for splitmuxsink, which muxes its input into a series of
+/// files, starting a new one at a keyframe once the current one
+/// reaches a maximum duration or size, eg: for a DVR that records in
+/// segments of 10 minutes:
+///
+/// ```ignore
+/// let mut sink = gst::splitmuxsink::SplitMuxSink::new("recorder").unwrap();
+/// sink.set_muxer_factory("mp4mux");
+/// sink.set_max_size_time(10 * 60 * 1_000_000_000);
+/// sink.set_max_files(144);
+/// sink.connect_format_location_full(|fragment, sample| {
+///     let pts = sample.buffer().and_then(|buffer| buffer.pts()).unwrap_or(0);
+///     format!("/recordings/{:05}-{}.mp4", fragment, pts / 1_000_000_000)
+/// }).unwrap().forget();
+/// ```
+///
+/// Each file is finalized when it's closed so it can be played while
+/// the next ones are being recorded, see Fragment::from_message to know
+/// when.
+pub struct SplitMuxSink{
+    sink: Element
+}
+
+impl SplitMuxSink{
+    pub fn new(name: &str) -> Option<SplitMuxSink>{
+        Element::new("splitmuxsink", name).map(SplitMuxSink::new_from_element)
+    }
+
+    pub fn new_from_element(element: Element) -> SplitMuxSink{
+        SplitMuxSink{ sink: element }
+    }
+
+    /// Location of the files with a printf style %d for the fragment
+    /// number, eg: "video%05d.mp4". Ignored if a format location handler
+    /// is connected
+    pub fn set_location(&mut self, location: &str){
+        self.set("location", location);
+    }
+
+    /// Maximum duration of each file in nanoseconds, 0 for no limit
+    pub fn set_max_size_time(&mut self, max_size_time: u64){
+        self.set("max-size-time", max_size_time);
+    }
+
+    /// Maximum size of each file in bytes, 0 for no limit
+    pub fn set_max_size_bytes(&mut self, max_size_bytes: u64){
+        self.set("max-size-bytes", max_size_bytes);
+    }
+
+    /// Reuses the fragment numbers after max_files files so the oldest
+    /// ones get overwritten, 0 for no limit
+    pub fn set_max_files(&mut self, max_files: u32){
+        self.set("max-files", max_files);
+    }
+
+    /// Asks the encoder for a keyframe when a file reaches its limit so
+    /// files don't go over it until the next natural keyframe
+    pub fn set_send_keyframe_requests(&mut self, send: bool){
+        self.set("send-keyframe-requests", send as gboolean);
+    }
+
+    /// Factory of the muxer to create, eg: "mp4mux" or "matroskamux",
+    /// mp4mux by default
+    pub fn set_muxer_factory(&mut self, factory: &str){
+        self.set("muxer-factory", factory);
+    }
+
+    /// Uses muxer instead of creating one, eg: to configure it first
+    pub fn set_muxer(&mut self, muxer: &Element){
+        self.set("muxer", muxer);
+    }
+
+    /// Factory of the sink each file is written with, filesink by
+    /// default. It has to be seekable for muxers like mp4mux that
+    /// rewrite the headers when the file is closed
+    pub fn set_sink_factory(&mut self, factory: &str){
+        self.set("sink-factory", factory);
+    }
+
+    /// Closes the current file and starts a new one at the next
+    /// keyframe, eg: when an event to record starts
+    pub fn split_now(&mut self){
+        self.emit("split-now");
+    }
+
+    /// Calls handler to get the location of each file from its fragment
+    /// number, from the streaming thread. Overrides set_location
+    pub fn connect_format_location<F>(&mut self, mut handler: F) -> Option<SignalHandle>
+        where F: FnMut(u32) -> String + Send + 'static{
+        self.connect_values("format-location", move |args| {
+            let fragment = args[1].get::<u32>()?;
+            Some(Value::from(&handler(fragment)))
+        })
+    }
+
+    /// Same as connect_format_location also passing the first sample
+    /// of the file, eg: to name the files after its timestamp
+    pub fn connect_format_location_full<F>(&mut self, mut handler: F) -> Option<SignalHandle>
+        where F: FnMut(u32, &Sample) -> String + Send + 'static{
+        self.connect_values("format-location-full", move |args| {
+            let fragment = args[1].get::<u32>()?;
+            let sample = args[2].get::<Sample>()?;
+            Some(Value::from(&handler(fragment, &sample)))
+        })
+    }
+}
+
+/// Whether a fragment was started or finished
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum FragmentState{
+    Opened,
+    Closed,
+}
+
+/// A file opened or closed by splitmuxsink, posted as an element message
+#[derive(Clone,Debug,PartialEq)]
+pub struct Fragment{
+    pub state: FragmentState,
+    pub location: String,
+    /// Running time in nanoseconds of the start of the file when opened
+    /// or of its end when closed
+    pub running_time: u64,
+}
+
+impl Fragment{
+    /// Parses an element message posted by splitmuxsink, None for any
+    /// other message
+    pub fn from_message(msg: &Message) -> Option<Fragment>{
+        match *msg{
+            Message::Element(_) => Fragment::from_structure(&msg.get_structure()?),
+            _ => None
+        }
+    }
+
+    pub fn from_structure(structure: &Structure) -> Option<Fragment>{
+        let state = match structure.name(){
+            "splitmuxsink-fragment-opened" => FragmentState::Opened,
+            "splitmuxsink-fragment-closed" => FragmentState::Closed,
+            _ => return None
+        };
+        Some(Fragment{
+            state: state,
+            location: structure.get_string("location")?.to_string(),
+            running_time: structure.get::<u64>("running-time").unwrap_or(0),
+        })
+    }
+}
+
+impl Reference for SplitMuxSink{
+    fn reference(&self) -> SplitMuxSink{
+        SplitMuxSink{ sink: self.sink.reference() }
+    }
+}
+
+impl AsRef<Element> for SplitMuxSink{
+    fn as_ref(&self) -> &Element{
+        &self.sink
+    }
+}
+
+impl AsMut<Element> for SplitMuxSink{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.sink
+    }
+}
+
+impl From<SplitMuxSink> for Element{
+    fn from(s: SplitMuxSink) -> Element{
+        s.sink
+    }
+}
+
+impl Deref for SplitMuxSink{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.sink
+    }
+}
+
+impl DerefMut for SplitMuxSink{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.sink
+    }
+}
+
+impl ::Transfer for SplitMuxSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.sink.transfer()
+    }
+}
@@ -0,0 +1,179 @@
+use element::Element;
+use buffer::Buffer;
+use caps::Caps;
+use handle::SignalHandle;
+use value::{Value, ToValue};
+
+/// Cipher used to encrypt the packets, mirrors GstSrtpCipherType
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum Cipher{
+    Null,
+    Aes128Icm,
+    Aes256Icm,
+    Aes128Gcm,
+    Aes256Gcm,
+}
+
+impl Cipher{
+    pub fn nick(self) -> &'static str{
+        match self{
+            Cipher::Null => "null",
+            Cipher::Aes128Icm => "aes-128-icm",
+            Cipher::Aes256Icm => "aes-256-icm",
+            Cipher::Aes128Gcm => "aes-128-gcm",
+            Cipher::Aes256Gcm => "aes-256-gcm",
+        }
+    }
+
+    /// Length of the master key plus the master salt this cipher needs,
+    /// the size the key buffer has to be
+    pub fn key_len(self) -> usize{
+        match self{
+            Cipher::Null => 0,
+            Cipher::Aes128Icm => 30,
+            Cipher::Aes256Icm => 46,
+            Cipher::Aes128Gcm => 28,
+            Cipher::Aes256Gcm => 44,
+        }
+    }
+}
+
+/// Authentication of the packets, mirrors GstSrtpAuthType. Has to be
+/// Null for the GCM ciphers which authenticate by themselves
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum Auth{
+    Null,
+    HmacSha1_32,
+    HmacSha1_80,
+}
+
+impl Auth{
+    pub fn nick(self) -> &'static str{
+        match self{
+            Auth::Null => "null",
+            Auth::HmacSha1_32 => "hmac-sha1-32",
+            Auth::HmacSha1_80 => "hmac-sha1-80",
+        }
+    }
+}
+
+/// Master key and crypto suites of an SRTP stream, used to configure
+/// srtpenc and answer srtpdec's key requests:
+///
+/// ```ignore
+/// let key = gst::srtp::Key::new(&master_key, gst::srtp::Cipher::Aes128Icm, gst::srtp::Auth::HmacSha1_80);
+/// gst::srtp::configure_encoder(&mut srtpenc, &key);
+/// gst::srtp::connect_request_key(&mut srtpdec, move |_ssrc| Some(key.clone())).unwrap().forget();
+/// ```
+#[derive(Clone,Debug)]
+pub struct Key{
+    pub key: Vec<u8>,
+    pub rtp_cipher: Cipher,
+    pub rtp_auth: Auth,
+    pub rtcp_cipher: Cipher,
+    pub rtcp_auth: Auth,
+    /// Master key identifier sent in every packet, if any
+    pub mki: Option<Vec<u8>>,
+}
+
+impl Key{
+    /// Key using the same suite for RTP and RTCP, the most common setup
+    pub fn new(key: &[u8], cipher: Cipher, auth: Auth) -> Key{
+        Key{
+            key: key.to_vec(),
+            rtp_cipher: cipher,
+            rtp_auth: auth,
+            rtcp_cipher: cipher,
+            rtcp_auth: auth,
+            mki: None,
+        }
+    }
+
+    /// True if the key has the length the ciphers need
+    pub fn is_valid(&self) -> bool{
+        self.key.len() == self.rtp_cipher.key_len() && self.key.len() == self.rtcp_cipher.key_len()
+    }
+
+    /// Caps srtpdec understands, either returned from "request-key" or
+    /// set on its sink pad. ssrc is only needed when setting the caps
+    /// directly
+    pub fn to_caps(&self, ssrc: Option<u32>) -> Caps{
+        let mut caps = Caps::new_empty_simple("application/x-srtp");
+        {
+            let mut s = caps.structure_mut(0).unwrap();
+            if let Some(ssrc) = ssrc{
+                s.set("ssrc", &ssrc);
+            }
+            s.set("srtp-key", &Buffer::from_slice_zero_copy(self.key.clone()).unwrap());
+            s.set_value("srtp-cipher", Value::from(self.rtp_cipher.nick()));
+            s.set_value("srtp-auth", Value::from(self.rtp_auth.nick()));
+            s.set_value("srtcp-cipher", Value::from(self.rtcp_cipher.nick()));
+            s.set_value("srtcp-auth", Value::from(self.rtcp_auth.nick()));
+            if let Some(ref mki) = self.mki{
+                s.set("mki", &Buffer::from_slice_zero_copy(mki.clone()).unwrap());
+            }
+        }
+        caps
+    }
+}
+
+/// Sets the key and crypto suites srtpenc encrypts with
+pub fn configure_encoder(srtpenc: &mut Element, key: &Key){
+    srtpenc.set_value("key", &Buffer::from_slice_zero_copy(key.key.clone()).unwrap().to_value());
+    srtpenc.set_from_str("rtp-cipher", key.rtp_cipher.nick());
+    srtpenc.set_from_str("rtp-auth", key.rtp_auth.nick());
+    srtpenc.set_from_str("rtcp-cipher", key.rtcp_cipher.nick());
+    srtpenc.set_from_str("rtcp-auth", key.rtcp_auth.nick());
+    if let Some(ref mki) = key.mki{
+        srtpenc.set_value("mki", &Buffer::from_slice_zero_copy(mki.clone()).unwrap().to_value());
+    }
+}
+
+/// Connects handler to srtpdec's "request-key" signal, emitted with the
+/// ssrc of every new stream. Returning None drops the stream
+pub fn connect_request_key<F>(srtpdec: &mut Element, handler: F) -> Option<SignalHandle>
+    where F: FnMut(u32) -> Option<Key> + Send + 'static{
+    connect_key_signal(srtpdec, "request-key", handler)
+}
+
+/// Connects handler to srtpdec's "soft-limit" signal, emitted when a
+/// key is close to being exhausted so a new one can be provided.
+/// Returning None keeps using the current key
+pub fn connect_soft_limit<F>(srtpdec: &mut Element, handler: F) -> Option<SignalHandle>
+    where F: FnMut(u32) -> Option<Key> + Send + 'static{
+    connect_key_signal(srtpdec, "soft-limit", handler)
+}
+
+/// Connects handler to srtpdec's "hard-limit" signal, emitted when a key
+/// can't be used anymore. Returning None drops the stream
+pub fn connect_hard_limit<F>(srtpdec: &mut Element, handler: F) -> Option<SignalHandle>
+    where F: FnMut(u32) -> Option<Key> + Send + 'static{
+    connect_key_signal(srtpdec, "hard-limit", handler)
+}
+
+fn connect_key_signal<F>(srtpdec: &mut Element, signal: &str, mut handler: F) -> Option<SignalHandle>
+    where F: FnMut(u32) -> Option<Key> + Send + 'static{
+    srtpdec.connect_values(signal, move |args| {
+        args.get(1).and_then(|ssrc| ssrc.get::<u32>())
+            .and_then(|ssrc| handler(ssrc))
+            .map(|key| key.to_caps(None).to_value())
+    })
+}
+
+/// Forgets the key of a stream in srtpdec, the next packet of that ssrc
+/// will trigger a new "request-key"
+pub fn remove_key(srtpdec: &mut Element, ssrc: u32){
+    srtpdec.emit_with_values("remove-key", &[Value::from(ssrc)]);
+}
+
+/// Forgets all the keys in srtpdec
+pub fn clear_keys(srtpdec: &mut Element){
+    srtpdec.emit("clear-keys");
+}
+
+/// Current rollover counter of a stream in srtpenc, needed by the
+/// receiver to join a stream in progress
+pub fn rollover_counter(srtpenc: &mut Element, ssrc: u32) -> Option<u32>{
+    srtpenc.emit_with_values("get-rollover-counter", &[Value::from(ssrc)])
+        .and_then(|roc| roc.get::<u32>())
+}
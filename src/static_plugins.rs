@@ -0,0 +1,56 @@
+use ffi::*;
+use subclass::PluginDesc;
+
+/// Registers plugins linked into the application, for static builds
+/// where GStreamer can't load plugins from shared libraries, eg: on iOS
+/// or in embedded firmware. Has to be called after init and before
+/// creating any of their elements.
+///
+/// The descriptions of plugins implemented in rust are created with
+/// plugin_desc!:
+///
+/// ```ignore
+/// static RSINVERT: gst::subclass::PluginDesc = plugin_desc!("rsinvert", "Inverts video",
+///     plugin_init, "0.1.0", "MIT", "rsinvert", "rsinvert", "https://example.com");
+///
+/// gst::init();
+/// gst::register_static_plugins(&[&RSINVERT]);
+/// ```
+///
+/// Returns false if any of them failed to register, the rest are still
+/// registered
+pub fn register_static_plugins(plugins: &[&PluginDesc]) -> bool{
+    plugins.iter().fold(true, |ok, plugin| {
+        let desc = &plugin.0;
+        let registered = unsafe{
+            gst_plugin_register_static(desc.major_version, desc.minor_version, desc.name,
+                desc.description, desc.plugin_init, desc.version, desc.license, desc.source,
+                desc.package, desc.origin) != 0
+        };
+        ok && registered
+    })
+}
+
+/// Registers C plugins linked statically into the application by
+/// calling the gst_plugin_<name>_register function each of them
+/// exports, eg: for plugins built with -Ddefault_library=static.
+/// Has to be called after init:
+///
+/// ```ignore
+/// gst::init();
+/// register_linked_plugins!(gst_plugin_coreelements_register, gst_plugin_playback_register);
+/// ```
+///
+/// The static libraries of the plugins and their dependencies have to
+/// be linked by the application's build script
+#[macro_export]
+macro_rules! register_linked_plugins(
+    ($($register:ident),*) => ({
+        extern "C" {
+            $(fn $register();)*
+        }
+        unsafe{
+            $($register();)*
+        }
+    })
+);
@@ -0,0 +1,271 @@
+use ffi::*;
+use util::*;
+
+use bin::Bin;
+use element::Element;
+use buffer_list::BufferList;
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use handle::ProbeHandle;
+use registration::factory_name;
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// buffers waiting to leave an element, enough for the usual queues
+// without growing forever in elements that change the timestamps
+const MAX_PENDING: usize = 64;
+
+/// Data that went through a pad since the StatsCollector was installed
+#[derive(Clone,Debug,PartialEq)]
+pub struct PadStats{
+    pub name: String,
+    pub direction: GstPadDirection,
+    pub buffers: u64,
+    pub bytes: u64,
+    /// Average rate between the first and the last buffer
+    pub buffers_per_sec: f64,
+    pub bytes_per_sec: f64,
+}
+
+/// Throughput and latency of an element, its throughput is the one of
+/// its src pads, or of its sink pads for sinks
+#[derive(Clone,Debug,PartialEq)]
+pub struct ElementStats{
+    pub name: String,
+    /// Name of the factory that created the element, eg: "x264enc"
+    pub factory: Option<String>,
+    pub buffers: u64,
+    pub bytes: u64,
+    pub buffers_per_sec: f64,
+    pub bytes_per_sec: f64,
+    /// Average time between a buffer arriving on a sink pad and a
+    /// buffer with the same PTS leaving a src pad. None for sources,
+    /// sinks and elements that change the timestamps, eg: encoders
+    /// that reorder frames
+    pub latency: Option<Duration>,
+    pub pads: Vec<PadStats>,
+}
+
+/// Throughput and latency of every element of a pipeline, to export to
+/// a monitoring system:
+///
+/// ```ignore
+/// let collector = gst::stats::StatsCollector::install(&pipeline);
+/// // every few seconds
+/// for element in pipeline.stats(&collector).elements{
+///     metrics.gauge(&format!("{}.bytes_per_sec", element.name), element.bytes_per_sec);
+/// }
+/// ```
+#[derive(Clone,Debug)]
+pub struct PipelineStats{
+    /// Time since the collector was installed or reset
+    pub elapsed: Duration,
+    pub elements: Vec<ElementStats>,
+}
+
+impl PipelineStats{
+    /// Collects the stats of every element in bin, recursing into child
+    /// bins, from what collector measured
+    pub fn collect(bin: &Bin, collector: &StatsCollector) -> PipelineStats{
+        let counters = collector.counters.lock().unwrap_or_else(|e| e.into_inner());
+        PipelineStats{
+            elapsed: counters.since.elapsed(),
+            elements: bin.iter_recurse()
+                .filter_map(|element| element.ok())
+                .map(|element| element_stats(&element, &counters))
+                .collect(),
+        }
+    }
+
+    pub fn element(&self, name: &str) -> Option<&ElementStats>{
+        self.elements.iter().find(|element| element.name == name)
+    }
+}
+
+impl Display for PipelineStats{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        for element in &self.elements{
+            write!(fmt, "{} ({}): {} buffers, {:.1} buffers/s, {:.0} bytes/s", element.name,
+                element.factory.as_ref().map(|f| &f[..]).unwrap_or("-"),
+                element.buffers, element.buffers_per_sec, element.bytes_per_sec)?;
+            if let Some(latency) = element.latency{
+                write!(fmt, ", latency {:.3}ms", duration_secs(latency) * 1000.)?;
+            }
+            writeln!(fmt, "")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct PadCounters{
+    buffers: u64,
+    bytes: u64,
+    first: Option<Instant>,
+    last: Option<Instant>,
+}
+
+#[derive(Default)]
+struct LatencyCounters{
+    pending: VecDeque<(u64, Instant)>,
+    total: Duration,
+    samples: u32,
+}
+
+struct Counters{
+    since: Instant,
+    pads: HashMap<String, PadCounters>,
+    latencies: HashMap<String, LatencyCounters>,
+}
+
+/// Counts the buffers and bytes going through every pad of a bin and
+/// the time they spend in each element, for PipelineStats. Only the
+/// pads present when it's installed are measured, the probes are
+/// removed when it's dropped
+pub struct StatsCollector{
+    counters: Arc<Mutex<Counters>>,
+    _probes: Vec<ProbeHandle>,
+}
+
+impl StatsCollector{
+    pub fn install(bin: &Bin) -> StatsCollector{
+        let counters = Arc::new(Mutex::new(Counters{
+            since: Instant::now(),
+            pads: HashMap::new(),
+            latencies: HashMap::new(),
+        }));
+        let mut probes = vec![];
+        let mask = GST_PAD_PROBE_TYPE_BUFFER | GST_PAD_PROBE_TYPE_BUFFER_LIST;
+        for element in bin.iter_recurse().filter_map(|element| element.ok()){
+            let element_name = element.name();
+            // latency is only measured in elements that have both
+            let filter = !element.sink_pads().is_empty() && !element.src_pads().is_empty();
+            for (mut pad, is_src) in element.sink_pads().into_iter().map(|pad| (pad, false))
+                    .chain(element.src_pads().into_iter().map(|pad| (pad, true))){
+                let key = pad_key(&element_name, &pad.name());
+                let element_name = element_name.clone();
+                let counters = counters.clone();
+                let probe = pad.add_probe(mask, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+                    let (buffers, bytes, pts) = if let Some(buffer) = info.buffer(){
+                        (1, buffer.size(), buffer.pts())
+                    }else if let Some(list) = info.buffer_list(){
+                        list_size(list)
+                    }else{
+                        return PadProbeReturn::Ok
+                    };
+                    let now = Instant::now();
+                    let mut counters = counters.lock().unwrap_or_else(|e| e.into_inner());
+                    {
+                        let pad = counters.pads.entry(key.clone()).or_insert_with(PadCounters::default);
+                        pad.buffers += buffers;
+                        pad.bytes += bytes;
+                        pad.first = pad.first.or(Some(now));
+                        pad.last = Some(now);
+                    }
+                    if let (true, Some(pts)) = (filter, pts){
+                        let latency = counters.latencies.entry(element_name.clone()).or_insert_with(LatencyCounters::default);
+                        if is_src{
+                            if let Some(idx) = latency.pending.iter().position(|&(p, _)| p == pts){
+                                let (_, arrived) = latency.pending[idx];
+                                latency.total += now.duration_since(arrived);
+                                latency.samples += 1;
+                                latency.pending.drain(..idx + 1);
+                            }
+                        }else{
+                            if latency.pending.len() == MAX_PENDING{
+                                latency.pending.pop_front();
+                            }
+                            latency.pending.push_back((pts, now));
+                        }
+                    }
+                    PadProbeReturn::Ok
+                });
+                if let Some(probe) = probe{
+                    probes.push(probe);
+                }
+            }
+        }
+        StatsCollector{
+            counters: counters,
+            _probes: probes,
+        }
+    }
+
+    /// Starts counting from zero, eg: after a seek or to measure
+    /// intervals instead of averages since the start
+    pub fn reset(&self){
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        counters.since = Instant::now();
+        counters.pads.clear();
+        counters.latencies.clear();
+    }
+}
+
+fn element_stats(element: &Element, counters: &Counters) -> ElementStats{
+    let name = element.name();
+    let pads = element.sink_pads().into_iter().chain(element.src_pads()).map(|pad| {
+        let pad_name = pad.name();
+        let direction = unsafe{ gst_pad_get_direction(pad.gst_pad() as *mut GstPad) };
+        match counters.pads.get(&pad_key(&name, &pad_name)){
+            Some(pad) => {
+                let secs = match (pad.first, pad.last){
+                    (Some(first), Some(last)) => duration_secs(last.duration_since(first)),
+                    _ => 0.
+                };
+                PadStats{
+                    name: pad_name,
+                    direction: direction,
+                    buffers: pad.buffers,
+                    bytes: pad.bytes,
+                    buffers_per_sec: if secs > 0. { pad.buffers as f64 / secs } else { 0. },
+                    bytes_per_sec: if secs > 0. { pad.bytes as f64 / secs } else { 0. },
+                }
+            }
+            None => PadStats{
+                name: pad_name,
+                direction: direction,
+                buffers: 0,
+                bytes: 0,
+                buffers_per_sec: 0.,
+                bytes_per_sec: 0.,
+            }
+        }
+    }).collect::<Vec<_>>();
+    let has_src = pads.iter().any(|pad| pad.direction == GST_PAD_SRC);
+    let direction = if has_src { GST_PAD_SRC } else { GST_PAD_SINK };
+    let mut stats = ElementStats{
+        factory: factory_name(element),
+        buffers: 0,
+        bytes: 0,
+        buffers_per_sec: 0.,
+        bytes_per_sec: 0.,
+        latency: counters.latencies.get(&name)
+            .and_then(|latency| if latency.samples > 0 { Some(latency.total / latency.samples) } else { None }),
+        pads: vec![],
+        name: name,
+    };
+    for pad in pads.iter().filter(|pad| pad.direction == direction){
+        stats.buffers += pad.buffers;
+        stats.bytes += pad.bytes;
+        stats.buffers_per_sec += pad.buffers_per_sec;
+        stats.bytes_per_sec += pad.bytes_per_sec;
+    }
+    stats.pads = pads;
+    stats
+}
+
+// number of buffers, bytes and PTS of the first buffer of list
+fn list_size(list: &BufferList) -> (u64, u64, Option<u64>){
+    (list.len() as u64, list.size(), list.get(0).and_then(|buffer| buffer.pts()))
+}
+
+fn pad_key(element: &str, pad: &str) -> String{
+    format!("{}:{}", element, pad)
+}
+
+fn duration_secs(duration: Duration) -> f64{
+    duration.as_secs() as f64 + duration.subsec_nanos() as f64 / 1e9
+}
+
@@ -0,0 +1,181 @@
+use ffi::*;
+use util::*;
+
+use bin::Bin;
+use element::Element;
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use caps::Caps;
+use handle::ProbeHandle;
+use registration::factory_name;
+
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::sync::{Arc, Mutex};
+
+/// State of a pad when the status was collected
+#[derive(Clone)]
+pub struct PadStatus{
+    pub name: String,
+    pub direction: GstPadDirection,
+    pub linked: bool,
+    pub blocked: bool,
+    pub caps: Option<Caps>,
+    /// PTS of the last buffer that went through the pad, only known if
+    /// a PtsTracker was installed
+    pub last_pts: Option<u64>,
+}
+
+/// State of an element when the status was collected
+#[derive(Clone)]
+pub struct ElementStatus{
+    pub name: String,
+    /// Name of the factory that created the element, eg: "x264enc"
+    pub factory: Option<String>,
+    pub state: GstState,
+    /// The state the element is going to, GST_STATE_VOID_PENDING if
+    /// it's not changing state
+    pub pending: GstState,
+    /// GST_STATE_CHANGE_ASYNC if the element is still changing state,
+    /// the usual culprit of a stuck pipeline
+    pub state_change: GstStateChangeReturn,
+    pub pads: Vec<PadStatus>,
+}
+
+/// Snapshot of the state of every element and pad of a pipeline to
+/// debug pipelines that don't preroll or stop producing data:
+///
+/// ```ignore
+/// let tracker = gst::status::PtsTracker::install(&pipeline);
+/// // when the pipeline seems stuck
+/// println!("{}", pipeline.dump_status_with_pts(&tracker));
+/// ```
+///
+/// The states are queried without waiting so collecting the status
+/// never blocks, even if some element is stuck in a state change.
+#[derive(Clone)]
+pub struct PipelineStatus{
+    pub elements: Vec<ElementStatus>,
+}
+
+impl PipelineStatus{
+    /// Collects the status of bin and every element in it, recursing
+    /// into child bins. The last PTS of each pad comes from tracker if
+    /// passed
+    pub fn collect(bin: &Bin, tracker: Option<&PtsTracker>) -> PipelineStatus{
+        let mut elements = vec![element_status(bin, tracker)];
+        elements.extend(bin.iter_recurse()
+            .filter_map(|element| element.ok())
+            .map(|element| element_status(&element, tracker)));
+        PipelineStatus{ elements: elements }
+    }
+
+    pub fn element(&self, name: &str) -> Option<&ElementStatus>{
+        self.elements.iter().find(|element| element.name == name)
+    }
+
+    /// Elements still changing state, eg: a sink waiting for a buffer
+    /// to preroll
+    pub fn pending_elements(&self) -> Vec<&ElementStatus>{
+        self.elements.iter().filter(|element| element.state_change == GST_STATE_CHANGE_ASYNC).collect()
+    }
+}
+
+impl Display for PipelineStatus{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        for element in &self.elements{
+            write!(fmt, "{} ({}): {}", element.name, element.factory.as_ref().map(|f| &f[..]).unwrap_or("-"), state_name(element.state))?;
+            if element.pending != GST_STATE_VOID_PENDING{
+                write!(fmt, " -> {}", state_name(element.pending))?;
+            }
+            writeln!(fmt, "")?;
+            for pad in &element.pads{
+                write!(fmt, "    {}{}{}", pad.name, if pad.linked { "" } else { " (unlinked)" }, if pad.blocked { " (blocked)" } else { "" })?;
+                if let Some(pts) = pad.last_pts{
+                    write!(fmt, " last pts {}", format_time(pts))?;
+                }
+                match pad.caps{
+                    Some(ref caps) => writeln!(fmt, " {}", caps.to_string())?,
+                    None => writeln!(fmt, " no caps")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Records the PTS of the last buffer going through every pad of a bin,
+/// for PipelineStatus. Only the pads present when it's installed are
+/// tracked, the probes are removed when it's dropped
+pub struct PtsTracker{
+    last_pts: Arc<Mutex<HashMap<String, u64>>>,
+    _probes: Vec<ProbeHandle>,
+}
+
+impl PtsTracker{
+    pub fn install(bin: &Bin) -> PtsTracker{
+        let last_pts = Arc::new(Mutex::new(HashMap::new()));
+        let mut probes = vec![];
+        for element in bin.iter_recurse().filter_map(|element| element.ok()){
+            for mut pad in element.src_pads().into_iter().chain(element.sink_pads()){
+                let key = pad_key(&element.name(), &pad.name());
+                let last_pts = last_pts.clone();
+                let probe = pad.add_probe(GST_PAD_PROBE_TYPE_BUFFER, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+                    if let Some(pts) = info.buffer().and_then(|buffer| buffer.pts()){
+                        last_pts.lock().unwrap_or_else(|e| e.into_inner()).insert(key.clone(), pts);
+                    }
+                    PadProbeReturn::Ok
+                });
+                if let Some(probe) = probe{
+                    probes.push(probe);
+                }
+            }
+        }
+        PtsTracker{
+            last_pts: last_pts,
+            _probes: probes,
+        }
+    }
+
+    /// PTS of the last buffer that went through the pad of element
+    pub fn last_pts(&self, element: &str, pad: &str) -> Option<u64>{
+        self.last_pts.lock().unwrap_or_else(|e| e.into_inner()).get(&pad_key(element, pad)).cloned()
+    }
+}
+
+fn pad_key(element: &str, pad: &str) -> String{
+    format!("{}:{}", element, pad)
+}
+
+fn element_status(element: &Element, tracker: Option<&PtsTracker>) -> ElementStatus{
+    let name = element.name();
+    let (state, pending, state_change) = element.get_state(0);
+    let pads = element.sink_pads().into_iter().chain(element.src_pads()).map(|pad| {
+        let pad_name = pad.name();
+        PadStatus{
+            direction: unsafe{ gst_pad_get_direction(pad.gst_pad() as *mut GstPad) },
+            linked: pad.is_linked(),
+            blocked: pad.is_blocked(),
+            caps: pad.current_caps(),
+            last_pts: tracker.and_then(|tracker| tracker.last_pts(&name, &pad_name)),
+            name: pad_name,
+        }
+    }).collect();
+    ElementStatus{
+        factory: factory_name(element),
+        name: name,
+        state: state,
+        pending: pending,
+        state_change: state_change,
+        pads: pads,
+    }
+}
+
+fn state_name(state: GstState) -> &'static str{
+    unsafe{
+        from_c_str!(gst_element_state_get_name(state))
+    }
+}
+
+fn format_time(ns: u64) -> String{
+    format!("{}:{:02}:{:02}.{:09}", ns / 3_600_000_000_000, ns / 60_000_000_000 % 60, ns / 1_000_000_000 % 60, ns % 1_000_000_000)
+}
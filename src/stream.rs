@@ -0,0 +1,277 @@
+use ffi::*;
+use util::*;
+use object::Object;
+use element::Element;
+use caps::Caps;
+use taglist::TagList;
+use reference::Reference;
+use version::ApiAvailability;
+
+use std::ops::{Deref, DerefMut};
+
+pub const STREAMS: ApiAvailability = ApiAvailability::new("StreamCollection", 1, 10, 0);
+
+// the functions used once there's a stream, a collection or one of
+// their messages and events, which can only exist if they are available
+unsafe fn stream_fn<F: Copy>(symbol: &str) -> F{
+    STREAMS.function(symbol).expect("StreamCollection without GStreamer 1.10")
+}
+
+/// Kind of a Stream, mirrors GstStreamType
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum StreamType{
+    Unknown,
+    Audio,
+    Video,
+    /// A stream containing other streams, eg: before demuxing
+    Container,
+    /// Subtitles
+    Text,
+}
+
+impl StreamType{
+    fn from_gst_type(ty: GstStreamType) -> StreamType{
+        if ty & GST_STREAM_TYPE_VIDEO != 0{
+            StreamType::Video
+        }else if ty & GST_STREAM_TYPE_AUDIO != 0{
+            StreamType::Audio
+        }else if ty & GST_STREAM_TYPE_TEXT != 0{
+            StreamType::Text
+        }else if ty & GST_STREAM_TYPE_CONTAINER != 0{
+            StreamType::Container
+        }else{
+            StreamType::Unknown
+        }
+    }
+}
+
+/// One of the streams of a StreamCollection, eg: an audio track or a
+/// subtitle in a given language
+pub struct Stream{
+    stream: Object<GstStream>
+}
+
+impl Stream{
+    pub unsafe fn new(stream: *mut GstStream) -> Option<Stream>{
+        Object::new(stream).map(|obj| Stream{ stream: obj })
+    }
+
+    /// Id unique in the pipeline, the one to pass to select_streams
+    pub fn stream_id(&self) -> Option<&str>{
+        unsafe{
+            let get_stream_id: unsafe extern "C" fn(*mut GstStream) -> *const gchar = stream_fn("gst_stream_get_stream_id");
+            let id = get_stream_id(self.gst_stream() as *mut GstStream);
+            if id != ptr::null(){
+                Some(from_c_str!(id))
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn stream_type(&self) -> StreamType{
+        unsafe{
+            let get_stream_type: unsafe extern "C" fn(*mut GstStream) -> GstStreamType = stream_fn("gst_stream_get_stream_type");
+            StreamType::from_gst_type(get_stream_type(self.gst_stream() as *mut GstStream))
+        }
+    }
+
+    /// Combination of GST_STREAM_FLAG_*, eg: GST_STREAM_FLAG_SELECT
+    /// for the streams selected by default
+    pub fn flags(&self) -> GstStreamFlags{
+        unsafe{
+            let get_stream_flags: unsafe extern "C" fn(*mut GstStream) -> GstStreamFlags = stream_fn("gst_stream_get_stream_flags");
+            get_stream_flags(self.gst_stream() as *mut GstStream)
+        }
+    }
+
+    pub fn caps(&self) -> Option<Caps>{
+        unsafe{
+            let get_caps: unsafe extern "C" fn(*mut GstStream) -> *mut GstCaps = stream_fn("gst_stream_get_caps");
+            Caps::new(get_caps(self.gst_stream() as *mut GstStream))
+        }
+    }
+
+    pub fn tags(&self) -> Option<TagList>{
+        unsafe{
+            let get_tags: unsafe extern "C" fn(*mut GstStream) -> *mut GstTagList = stream_fn("gst_stream_get_tags");
+            TagList::new_from_gst_taglist(get_tags(self.gst_stream() as *mut GstStream))
+        }
+    }
+
+    /// The language code of the stream from its tags, eg: for audio and
+    /// subtitle selection menus
+    pub fn language(&self) -> Option<String>{
+        self.tags().and_then(|tags| tags.get::<String>("language-code"))
+    }
+
+    pub unsafe fn gst_stream(&self) -> *const GstStream{
+        self.stream.as_ptr()
+    }
+
+    pub unsafe fn gst_stream_mut(&mut self) -> *mut GstStream{
+        self.stream.as_mut_ptr()
+    }
+}
+
+impl Reference for Stream{
+    fn reference(&self) -> Stream{
+        Stream{ stream: self.stream.reference() }
+    }
+}
+
+impl Deref for Stream{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.stream.upcast_ref()
+    }
+}
+
+impl DerefMut for Stream{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.stream.upcast_mut()
+    }
+}
+
+/// The streams available in a source, posted by playbin3, decodebin3
+/// and the demuxers in a StreamCollection message. The application
+/// chooses which ones to play sending their ids with select_streams:
+///
+/// ```ignore
+/// match msg{
+///     gst::Message::StreamCollection(_) => {
+///         let collection = msg.stream_collection().unwrap();
+///         let ids: Vec<String> = collection.streams().iter()
+///             .filter(|s| s.stream_type() == StreamType::Video || s.language() == Some("fr".to_string()))
+///             .filter_map(|s| s.stream_id().map(|id| id.to_string()))
+///             .collect();
+///         let ids: Vec<&str> = ids.iter().map(|id| &id[..]).collect();
+///         gst::stream::select_streams(&mut playbin, &ids);
+///     }
+///     gst::Message::StreamsSelected(_) => println!("now playing {} streams", msg.selected_streams().len()),
+///     _ => {}
+/// }
+/// ```
+pub struct StreamCollection{
+    collection: Object<GstStreamCollection>
+}
+
+impl StreamCollection{
+    pub unsafe fn new(collection: *mut GstStreamCollection) -> Option<StreamCollection>{
+        Object::new(collection).map(|obj| StreamCollection{ collection: obj })
+    }
+
+    /// Stream id of the stream the collection comes from
+    pub fn upstream_id(&self) -> Option<&str>{
+        unsafe{
+            let get_upstream_id: unsafe extern "C" fn(*mut GstStreamCollection) -> *const gchar = stream_fn("gst_stream_collection_get_upstream_id");
+            let id = get_upstream_id(self.gst_stream_collection() as *mut GstStreamCollection);
+            if id != ptr::null(){
+                Some(from_c_str!(id))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Number of streams
+    pub fn len(&self) -> u32{
+        unsafe{
+            let get_size: unsafe extern "C" fn(*mut GstStreamCollection) -> guint = stream_fn("gst_stream_collection_get_size");
+            get_size(self.gst_stream_collection() as *mut GstStreamCollection)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+
+    pub fn stream(&self, index: u32) -> Option<Stream>{
+        unsafe{
+            let get_stream: unsafe extern "C" fn(*mut GstStreamCollection, guint) -> *mut GstStream = stream_fn("gst_stream_collection_get_stream");
+            let stream = get_stream(self.gst_stream_collection() as *mut GstStreamCollection, index);
+            if stream != ptr::null_mut(){
+                gst_object_ref(stream as gpointer);
+            }
+            Stream::new(stream)
+        }
+    }
+
+    pub fn streams(&self) -> Vec<Stream>{
+        (0..self.len()).filter_map(|i| self.stream(i)).collect()
+    }
+
+    pub fn streams_of_type(&self, stream_type: StreamType) -> Vec<Stream>{
+        self.streams().into_iter().filter(|stream| stream.stream_type() == stream_type).collect()
+    }
+
+    pub unsafe fn gst_stream_collection(&self) -> *const GstStreamCollection{
+        self.collection.as_ptr()
+    }
+
+    pub unsafe fn gst_stream_collection_mut(&mut self) -> *mut GstStreamCollection{
+        self.collection.as_mut_ptr()
+    }
+}
+
+impl Reference for StreamCollection{
+    fn reference(&self) -> StreamCollection{
+        StreamCollection{ collection: self.collection.reference() }
+    }
+}
+
+impl Deref for StreamCollection{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.collection.upcast_ref()
+    }
+}
+
+impl DerefMut for StreamCollection{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.collection.upcast_mut()
+    }
+}
+
+/// Sends a select-streams event to element, usually playbin3 or
+/// decodebin3, to play the streams with the passed ids and stop playing
+/// any other. The change is confirmed with a StreamsSelected message.
+/// Returns false with GStreamer older than 1.10
+pub fn select_streams(element: &mut Element, stream_ids: &[&str]) -> bool{
+    let cids: Vec<CString> = stream_ids.iter().map(|id| CString::new(*id).unwrap()).collect();
+    unsafe{
+        let new_select_streams: unsafe extern "C" fn(*mut GList) -> *mut GstEvent =
+            match STREAMS.function("gst_event_new_select_streams"){
+                Ok(new_select_streams) => new_select_streams,
+                Err(_) => return false,
+            };
+        // the event copies the ids
+        let mut list: *mut GList = ptr::null_mut();
+        for id in &cids{
+            list = g_list_append(list, id.as_ptr() as gpointer);
+        }
+        let event = new_select_streams(list);
+        g_list_free(list);
+        element.send_event(event)
+    }
+}
+
+/// The stream ids of a select-streams event, eg: received in an event
+/// probe. None if it's not a select-streams event
+pub unsafe fn parse_select_streams(event: *mut GstEvent) -> Option<Vec<String>>{
+    if event == ptr::null_mut() || (*event)._type != GST_EVENT_SELECT_STREAMS{
+        return None;
+    }
+    let mut list: *mut GList = ptr::null_mut();
+    let parse_select_streams: unsafe extern "C" fn(*mut GstEvent, *mut *mut GList) = stream_fn("gst_event_parse_select_streams");
+    parse_select_streams(event, &mut list);
+    let mut ids = vec![];
+    let mut node = list;
+    while node != ptr::null_mut(){
+        ids.push(from_c_str!((*node).data as *const gchar).to_string());
+        g_free((*node).data);
+        node = (*node).next;
+    }
+    g_list_free(list);
+    Some(ids)
+}
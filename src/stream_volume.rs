@@ -0,0 +1,109 @@
+use ffi::*;
+use element::Element;
+use reference::Reference;
+
+/// Scale a volume is expressed in
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum VolumeFormat{
+    /// Multiplier of the amplitude, 1.0 is the original volume. What
+    /// the volume properties of the elements use
+    Linear,
+    /// Cubic scale, which matches how loud it sounds, so it's the one
+    /// to use for volume sliders
+    Cubic,
+    /// Decibels, 0.0 is the original volume
+    Db,
+}
+
+impl VolumeFormat{
+    fn to_gst(self) -> GstStreamVolumeFormat{
+        match self{
+            VolumeFormat::Linear => GST_STREAM_VOLUME_FORMAT_LINEAR,
+            VolumeFormat::Cubic => GST_STREAM_VOLUME_FORMAT_CUBIC,
+            VolumeFormat::Db => GST_STREAM_VOLUME_FORMAT_DB,
+        }
+    }
+}
+
+/// Converts volume from one scale to another, eg: the position of a
+/// cubic slider to the linear value of a volume property
+pub fn convert_volume(from: VolumeFormat, to: VolumeFormat, volume: f64) -> f64{
+    unsafe{
+        gst_stream_volume_convert_volume(from.to_gst(), to.to_gst(), volume)
+    }
+}
+
+/// Access to the GstStreamVolume interface implemented by playbin, the
+/// volume element and sinks with their own volume control like
+/// pulsesink, so a volume control works the same with any of them:
+///
+/// ```ignore
+/// let mut volume = gst::StreamVolume::new(&playbin).unwrap();
+/// volume.set_volume(gst::VolumeFormat::Cubic, 0.8);
+/// volume.set_mute(false);
+/// ```
+pub struct StreamVolume{
+    element: Element
+}
+
+impl StreamVolume{
+    /// Returns the stream volume interface of element. If the element
+    /// is a bin that doesn't implement it, the first child implementing
+    /// it is returned
+    pub fn new(element: &Element) -> Option<StreamVolume>{
+        unsafe{
+            let instance = element.gst_element() as *mut GTypeInstance;
+            if g_type_check_instance_is_a(instance, gst_stream_volume_get_type()) != 0{
+                Some(StreamVolume{ element: element.reference() })
+            }else if g_type_check_instance_is_a(instance, gst_bin_get_type()) != 0{
+                let element = gst_bin_get_by_interface(element.gst_element() as *mut GstBin, gst_stream_volume_get_type());
+                Element::new_from_gst_element(element).map(|element| StreamVolume{ element: element })
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn volume(&self, format: VolumeFormat) -> f64{
+        unsafe{
+            gst_stream_volume_get_volume(self.gst_stream_volume() as *mut GstStreamVolume, format.to_gst())
+        }
+    }
+
+    /// Sets the volume in format, eg: set_volume(VolumeFormat::Cubic, 0.8)
+    pub fn set_volume(&mut self, format: VolumeFormat, volume: f64){
+        unsafe{
+            gst_stream_volume_set_volume(self.gst_stream_volume_mut(), format.to_gst(), volume);
+        }
+    }
+
+    pub fn is_muted(&self) -> bool{
+        unsafe{
+            gst_stream_volume_get_mute(self.gst_stream_volume() as *mut GstStreamVolume) != 0
+        }
+    }
+
+    pub fn set_mute(&mut self, mute: bool){
+        unsafe{
+            gst_stream_volume_set_mute(self.gst_stream_volume_mut(), mute as gboolean);
+        }
+    }
+
+    pub fn element(&self) -> &Element{
+        &self.element
+    }
+
+    pub unsafe fn gst_stream_volume(&self) -> *const GstStreamVolume{
+        self.element.gst_element() as *const GstStreamVolume
+    }
+
+    pub unsafe fn gst_stream_volume_mut(&mut self) -> *mut GstStreamVolume{
+        self.element.gst_element_mut() as *mut GstStreamVolume
+    }
+}
+
+impl Reference for StreamVolume{
+    fn reference(&self) -> StreamVolume{
+        StreamVolume{ element: self.element.reference() }
+    }
+}
@@ -1,25 +1,408 @@
 use ffi::*;
 use util::*;
+use caps::Caps;
+use buffer::Buffer;
+use value::{Value, ToValue};
 
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+/// Structures are usually borrowed from caps, messages or events so they
+/// aren't Send, copy the values out to use them in another thread
 pub struct Structure{
     structure: *mut GstStructure,
+    owned: bool,
+}
+
+impl Drop for Structure{
+    fn drop(&mut self){
+        if self.owned{
+            unsafe{
+                gst_structure_free(self.structure);
+            }
+        }
+    }
 }
 
 impl Structure{
+    /// Wraps a structure owned by someone else, like the caps or message
+    /// that contains it, so it's not freed when dropped
     pub unsafe fn new_from_gst_structure(structure: *mut GstStructure) -> Option<Structure>{
         if structure!=ptr::null_mut(){
             Some(Structure{
-                structure: structure
+                structure: structure,
+                owned: false,
             })
         }else{
             None
         }
     }
 
+    /// Wraps a structure taking ownership of it, it's freed when dropped
+    pub unsafe fn new_owned(structure: *mut GstStructure) -> Option<Structure>{
+        Structure::new_from_gst_structure(structure).map(|mut structure| {
+            structure.owned = true;
+            structure
+        })
+    }
+
+    /// Creates a new empty structure
+    pub fn new(name: &str) -> Structure{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            Structure::new_owned(gst_structure_new_empty(cname.as_ptr())).unwrap()
+        }
+    }
+
+    /// Parses a structure from its string representation, eg:
+    /// "stats, bytes=(guint64)100"
+    pub fn from_string(desc: &str) -> Option<Structure>{
+        let cdesc = CString::new(desc).unwrap();
+        unsafe{
+            Structure::new_owned(gst_structure_from_string(cdesc.as_ptr(), ptr::null_mut()))
+        }
+    }
+
+    /// Returns an owned copy of the structure that can outlive the caps
+    /// or message it came from
+    pub fn copy(&self) -> Structure{
+        unsafe{
+            Structure::new_owned(gst_structure_copy(self.structure)).unwrap()
+        }
+    }
+
+    pub fn to_string(&self) -> String{
+        unsafe{
+            let desc = gst_structure_to_string(self.structure);
+            let ret = from_c_str!(desc).to_string();
+            g_free(desc as gpointer);
+            ret
+        }
+    }
+
     pub fn name(&self) -> &str{
         unsafe{
             let cname = gst_structure_get_name(self.structure);
             from_c_str!(cname)
         }
     }
+
+    pub fn n_fields(&self) -> u32{
+        unsafe{
+            gst_structure_n_fields(self.structure) as u32
+        }
+    }
+
+    /// Names of all the fields in the structure, eg: the ids of the
+    /// stats in webrtcbin's get-stats result
+    pub fn field_names(&self) -> Vec<String>{
+        unsafe{
+            (0..gst_structure_n_fields(self.structure)).map(|i|
+                from_c_str!(gst_structure_nth_field_name(self.structure, i as guint)).to_string()
+            ).collect()
+        }
+    }
+
+    pub fn has_field(&self, name: &str) -> bool{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            gst_structure_has_field(self.structure, cname.as_ptr()) != 0
+        }
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let mut value = 0;
+            if gst_structure_get_boolean(self.structure, cname.as_ptr(), &mut value) != 0{
+                Some(value != 0)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn get_int(&self, name: &str) -> Option<i32>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let mut value = 0;
+            if gst_structure_get_int(self.structure, cname.as_ptr(), &mut value) != 0{
+                Some(value)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn get_uint(&self, name: &str) -> Option<u32>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let mut value = 0;
+            if gst_structure_get_uint(self.structure, cname.as_ptr(), &mut value) != 0{
+                Some(value)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn get_double(&self, name: &str) -> Option<f64>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let mut value = 0.;
+            if gst_structure_get_double(self.structure, cname.as_ptr(), &mut value) != 0{
+                Some(value)
+            }else{
+                None
+            }
+        }
+    }
+
+    pub fn get_string(&self, name: &str) -> Option<&str>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let value = gst_structure_get_string(self.structure, cname.as_ptr());
+            if value != ptr::null(){
+                Some(from_c_str!(value))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns a fraction field as (numerator, denominator)
+    pub fn get_fraction(&self, name: &str) -> Option<(i32,i32)>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let mut num = 0;
+            let mut den = 0;
+            if gst_structure_get_fraction(self.structure, cname.as_ptr(), &mut num, &mut den) != 0{
+                Some((num, den))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns all the fractions in a field that can be either a single
+    /// fraction, a list of fractions or a fraction range in which case
+    /// the minimum and maximum are returned. Mostly useful to query
+    /// the supported framerates of a device from its caps.
+    pub fn get_fraction_list(&self, name: &str) -> Vec<(i32,i32)>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let value = gst_structure_get_value(self.structure, cname.as_ptr());
+            let mut fractions = vec![];
+            if value != ptr::null(){
+                collect_fractions(value, &mut fractions);
+            }
+            fractions
+        }
+    }
+
+    /// Returns a copy of the value of any field
+    pub fn get_value(&self, name: &str) -> Option<Value>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let value = gst_structure_get_value(self.structure, cname.as_ptr());
+            if value != ptr::null(){
+                Some(Value::new_from_gvalue(value))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns a field of any type supported by Value, eg:
+    /// get::<u64>("bytes-received")
+    pub fn get<T: ::FromValue>(&self, name: &str) -> Option<T>{
+        self.get_value(name).and_then(|value| value.get::<T>())
+    }
+
+    /// Returns a copy of a field that contains a structure
+    pub fn get_structure(&self, name: &str) -> Option<Structure>{
+        self.get_value(name).and_then(|value| value.get::<Structure>())
+    }
+
+    pub fn get_caps(&self, name: &str) -> Option<Caps>{
+        self.get_value(name).and_then(|value| value.get::<Caps>())
+    }
+
+    /// Returns the structures in a field that can be either a single
+    /// structure or an array or list of them
+    pub fn get_structure_list(&self, name: &str) -> Vec<Structure>{
+        self.get_list(name)
+    }
+
+    /// Returns the caps in a field that can be either a single caps or an
+    /// array or list of them
+    pub fn get_caps_list(&self, name: &str) -> Vec<Caps>{
+        self.get_list(name)
+    }
+
+    /// Returns the buffers in a field that can be either a single buffer
+    /// or an array or list of them, like the "streamheader" of caps
+    pub fn get_buffer_list(&self, name: &str) -> Vec<Buffer>{
+        self.get_list(name)
+    }
+
+    /// Returns the values of type T in a field that can be either a
+    /// single value or an array or list of them, eg: get_list::<f64>("rms")
+    /// for the per channel levels of level messages
+    pub fn get_list<T: ::FromValue>(&self, name: &str) -> Vec<T>{
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            let value = gst_structure_get_value(self.structure, cname.as_ptr());
+            let mut values = vec![];
+            if value != ptr::null(){
+                collect_values(value, &mut values);
+            }
+            values
+        }
+    }
+
+    /// Sets a field to a value of any type supported by Value, eg:
+    /// set("width", &640) or set("caps", &caps)
+    pub fn set<T: ToValue>(&mut self, name: &str, value: &T){
+        self.set_value(name, value.to_value());
+    }
+
+    pub fn set_value(&mut self, name: &str, mut value: Value){
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            gst_structure_set_value(self.structure, cname.as_ptr(), value.gst_value_mut());
+        }
+    }
+
+    pub fn set_structure(&mut self, name: &str, structure: &Structure){
+        self.set(name, structure);
+    }
+
+    pub fn set_caps(&mut self, name: &str, caps: &Caps){
+        self.set(name, caps);
+    }
+
+    /// Sets a field to an array of structures
+    pub fn set_structure_list(&mut self, name: &str, structures: &[Structure]){
+        self.set_array(name, structures);
+    }
+
+    /// Sets a field to an array of caps
+    pub fn set_caps_list(&mut self, name: &str, caps: &[Caps]){
+        self.set_array(name, caps);
+    }
+
+    /// Sets a field to an array of buffers
+    pub fn set_buffer_list(&mut self, name: &str, buffers: &[Buffer]){
+        self.set_array(name, buffers);
+    }
+
+    fn set_array<T: ToValue>(&mut self, name: &str, values: &[T]){
+        let mut array = Value::new(unsafe{ gst_value_array_get_type() });
+        for value in values{
+            unsafe{
+                gst_value_array_append_value(array.gst_value_mut(), value.to_value().gst_value());
+            }
+        }
+        self.set_value(name, array);
+    }
+
+    pub fn remove_field(&mut self, name: &str){
+        let cname = CString::new(name).unwrap();
+        unsafe{
+            gst_structure_remove_field(self.structure, cname.as_ptr());
+        }
+    }
+
+    pub unsafe fn gst_structure(&self) -> *const GstStructure{
+        self.structure
+    }
+
+    pub unsafe fn gst_structure_mut(&mut self) -> *mut GstStructure{
+        self.structure
+    }
+}
+
+/// A structure borrowed from the caps or context that contains it, it
+/// can only be read and can't outlive them
+pub struct StructureRef<'a>{
+    structure: Structure,
+    parent: PhantomData<&'a ()>,
+}
+
+impl<'a> StructureRef<'a>{
+    pub unsafe fn new(structure: *const GstStructure) -> Option<StructureRef<'a>>{
+        Structure::new_from_gst_structure(structure as *mut GstStructure)
+            .map(|structure| StructureRef{ structure: structure, parent: PhantomData })
+    }
+}
+
+impl<'a> Deref for StructureRef<'a>{
+    type Target = Structure;
+    fn deref(&self) -> &Structure{
+        &self.structure
+    }
+}
+
+/// A structure mutably borrowed from a writable caps or context
+pub struct StructureRefMut<'a>{
+    structure: Structure,
+    parent: PhantomData<&'a mut ()>,
+}
+
+impl<'a> StructureRefMut<'a>{
+    pub unsafe fn new(structure: *mut GstStructure) -> Option<StructureRefMut<'a>>{
+        Structure::new_from_gst_structure(structure)
+            .map(|structure| StructureRefMut{ structure: structure, parent: PhantomData })
+    }
+}
+
+impl<'a> Deref for StructureRefMut<'a>{
+    type Target = Structure;
+    fn deref(&self) -> &Structure{
+        &self.structure
+    }
+}
+
+impl<'a> DerefMut for StructureRefMut<'a>{
+    fn deref_mut(&mut self) -> &mut Structure{
+        &mut self.structure
+    }
+}
+
+/// Collects the values of type T in value if it holds a T or in the
+/// array or list it holds
+unsafe fn collect_values<T: ::FromValue>(value: *const GValue, values: &mut Vec<T>){
+    if g_type_check_value_holds(value as *mut GValue, gst_value_array_get_type()) != 0{
+        for i in 0..gst_value_array_get_size(value){
+            collect_values(gst_value_array_get_value(value, i), values);
+        }
+    }else if g_type_check_value_holds(value as *mut GValue, gst_value_list_get_type()) != 0{
+        for i in 0..gst_value_list_get_size(value){
+            collect_values(gst_value_list_get_value(value, i), values);
+        }
+    }else if g_type_check_value_holds(value as *mut GValue, g_value_array_get_type()) != 0{
+        // GValueArray, still used by some elements like level
+        let array = g_value_get_boxed(value) as *mut GValueArray;
+        if array != ptr::null_mut(){
+            for i in 0..(*array).n_values{
+                collect_values(g_value_array_get_nth(array, i), values);
+            }
+        }
+    }else if let Some(t) = Value::new_from_gvalue(value).get::<T>(){
+        values.push(t);
+    }
+}
+
+unsafe fn collect_fractions(value: *const GValue, fractions: &mut Vec<(i32,i32)>){
+    if g_type_check_value_holds(value as *mut GValue, gst_fraction_get_type()) != 0{
+        fractions.push((gst_value_get_fraction_numerator(value), gst_value_get_fraction_denominator(value)));
+    }else if g_type_check_value_holds(value as *mut GValue, gst_fraction_range_get_type()) != 0{
+        collect_fractions(gst_value_get_fraction_range_min(value), fractions);
+        collect_fractions(gst_value_get_fraction_range_max(value), fractions);
+    }else if g_type_check_value_holds(value as *mut GValue, gst_value_list_get_type()) != 0{
+        for i in 0..gst_value_list_get_size(value){
+            collect_fractions(gst_value_list_get_value(value, i), fractions);
+        }
+    }
 }
@@ -0,0 +1,430 @@
+use ffi::*;
+use util::*;
+use element::Element;
+use buffer::{Buffer, BufferFlags};
+use meta::CustomMeta;
+use mapinfo::MapInfo;
+use caps::Caps;
+use registry::Plugin;
+use reference::Reference;
+use panic::catch_panic;
+use registration::{type_name, register_type, set_imp, with_imp, finalize, Instance, ELEMENT_PREFIX};
+use ::Transfer;
+
+use std::os::raw::c_void;
+use std::ops::{Deref, DerefMut};
+
+/// Metadata shown by gst-inspect for an element implemented in rust
+pub struct ElementMetadata{
+    pub long_name: &'static str,
+    /// Class of the element, eg: "Filter/Effect/Video"
+    pub klass: &'static str,
+    pub description: &'static str,
+    pub author: &'static str,
+}
+
+/// An element implemented in rust with one sink and one source pad that
+/// modifies the buffers going through it, backed by GstBaseTransform.
+///
+/// The methods are called from the streaming thread and from the thread
+/// changing the state of the element, the implementation is kept behind
+/// a mutex so they never run at the same time. A panic in any of them is
+/// caught and handled like an error in the element, see
+/// set_panic_handler, and the implementation isn't called anymore
+/// afterwards.
+///
+/// ```ignore
+/// struct Invert;
+///
+/// impl gst::BaseTransformImpl for Invert{
+///     fn new(transform: &mut gst::BaseTransform) -> Invert{
+///         transform.set_in_place(true);
+///         Invert
+///     }
+///
+///     fn metadata() -> gst::ElementMetadata{
+///         gst::ElementMetadata{
+///             long_name: "Invert",
+///             klass: "Filter/Effect/Video",
+///             description: "Inverts gray video",
+///             author: "Someone <someone@example.com>",
+///         }
+///     }
+///
+///     fn pad_caps() -> gst::Caps{
+///         gst::Caps::from_string("video/x-raw,format=GRAY8").unwrap()
+///     }
+///
+///     fn transform_ip(&mut self, buffer: &mut gst::InPlaceBuffer) -> gst::ffi::GstFlowReturn{
+///         let mapped = buffer.map_write(|mapping| for pixel in mapping.data_mut::<u8>(){
+///             *pixel = 255 - *pixel;
+///         });
+///         if mapped.is_ok() { gst::ffi::GST_FLOW_OK } else { gst::ffi::GST_FLOW_ERROR }
+///     }
+/// }
+///
+/// gst::subclass::register_base_transform::<Invert>(None, "rsinvert", gst::ffi::GST_RANK_NONE);
+/// ```
+pub trait BaseTransformImpl: Send + Sized + 'static{
+    /// Creates the state of a new instance of the element
+    fn new(transform: &mut BaseTransform) -> Self;
+
+    fn metadata() -> ElementMetadata;
+
+    /// Caps accepted by the sink pad and produced by the source pad
+    fn pad_caps() -> Caps;
+
+    /// Called when the caps are negotiated, returning false refuses them
+    fn set_caps(&mut self, _incaps: &Caps, _outcaps: &Caps) -> bool{
+        true
+    }
+
+    /// Called when the element goes to PAUSED, before any buffer
+    fn start(&mut self) -> bool{
+        true
+    }
+
+    /// Called when the element goes back to READY
+    fn stop(&mut self) -> bool{
+        true
+    }
+
+    /// Modifies buffer in place. The buffer belongs to the element and
+    /// is already writable, it can be mapped and modified but not
+    /// replaced with a different buffer
+    fn transform_ip(&mut self, buffer: &mut InPlaceBuffer) -> GstFlowReturn;
+}
+
+/// The buffer passed to BaseTransformImpl::transform_ip. It derefs to
+/// the Buffer to read it and has the methods of Buffer that modify it in
+/// place, but never gives out a &mut Buffer since the buffer is borrowed
+/// from the base class and replacing it would free it. For the same
+/// reason it's never copied, if a reference to the buffer was taken it
+/// becomes read only and the setters return false without changing it
+pub struct InPlaceBuffer<'a>{
+    buffer: &'a mut Buffer
+}
+
+impl<'a> InPlaceBuffer<'a>{
+    fn writable(&mut self) -> Option<&mut Buffer>{
+        if self.buffer.is_writable(){
+            Some(self.buffer)
+        }else{
+            None
+        }
+    }
+
+    /// Err if the buffer is read only
+    pub fn map_write<F: FnMut(&mut MapInfo) -> U, U>(&mut self, f: F) -> Result<U,()>{
+        self.writable().ok_or(())?.map_write(f)
+    }
+
+    /// Err if flags include Write and the buffer is read only
+    pub fn map<F: FnMut(&mut MapInfo) -> U, U>(&mut self, flags: ::Map, f: F) -> Result<U,()>{
+        if flags as u32 & GST_MAP_WRITE != 0 && !self.buffer.is_writable(){
+            return Err(());
+        }
+        self.buffer.map(flags, f)
+    }
+
+    pub fn set_pts(&mut self, pts: Option<u64>) -> bool{
+        self.writable().map(|buffer| buffer.set_pts(pts)).is_some()
+    }
+
+    pub fn set_dts(&mut self, dts: Option<u64>) -> bool{
+        self.writable().map(|buffer| buffer.set_dts(dts)).is_some()
+    }
+
+    pub fn set_duration(&mut self, duration: Option<u64>) -> bool{
+        self.writable().map(|buffer| buffer.set_duration(duration)).is_some()
+    }
+
+    pub fn set_flags(&mut self, flags: BufferFlags) -> bool{
+        self.writable().map(|buffer| buffer.set_flags(flags)).is_some()
+    }
+
+    pub fn unset_flags(&mut self, flags: BufferFlags) -> bool{
+        self.writable().map(|buffer| buffer.unset_flags(flags)).is_some()
+    }
+
+    pub fn add_custom_meta<T: CustomMeta>(&mut self, data: T) -> bool{
+        self.buffer.add_custom_meta(data)
+    }
+
+    pub fn custom_meta_mut<T: CustomMeta>(&mut self) -> Option<&mut T>{
+        self.buffer.custom_meta_mut()
+    }
+
+    pub fn remove_meta(&mut self, api: GType) -> bool{
+        self.buffer.remove_meta(api)
+    }
+
+    pub unsafe fn gst_buffer_mut(&mut self) -> *mut GstBuffer{
+        self.buffer.gst_buffer_mut()
+    }
+}
+
+impl<'a> Deref for InPlaceBuffer<'a>{
+    type Target = Buffer;
+    fn deref(&self) -> &Buffer{
+        self.buffer
+    }
+}
+
+/// The GstBaseTransform part of an element implemented in rust, gives
+/// access to the behaviour of the base class
+pub struct BaseTransform{
+    transform: Element
+}
+
+impl BaseTransform{
+    pub unsafe fn new_from_gst_basetransform(transform: *mut GstBaseTransform) -> Option<BaseTransform>{
+        Element::new_from_gst_element(transform as *mut GstElement)
+            .map(|element| BaseTransform{ transform: element })
+    }
+
+    /// Sets if the element modifies the buffers in place, an in place
+    /// element gets the input buffer made writable in transform_ip
+    pub fn set_in_place(&mut self, in_place: bool){
+        unsafe{
+            gst_base_transform_set_in_place(self.gst_basetransform_mut(), in_place as gboolean);
+        }
+    }
+
+    pub fn is_in_place(&self) -> bool{
+        unsafe{
+            gst_base_transform_is_in_place(self.gst_basetransform() as *mut GstBaseTransform) != 0
+        }
+    }
+
+    /// In passthrough mode buffers are pushed downstream without calling
+    /// transform_ip
+    pub fn set_passthrough(&mut self, passthrough: bool){
+        unsafe{
+            gst_base_transform_set_passthrough(self.gst_basetransform_mut(), passthrough as gboolean);
+        }
+    }
+
+    pub fn is_passthrough(&self) -> bool{
+        unsafe{
+            gst_base_transform_is_passthrough(self.gst_basetransform() as *mut GstBaseTransform) != 0
+        }
+    }
+
+    pub unsafe fn gst_basetransform(&self) -> *const GstBaseTransform{
+        self.transform.gst_element() as *const GstBaseTransform
+    }
+
+    pub unsafe fn gst_basetransform_mut(&mut self) -> *mut GstBaseTransform{
+        self.transform.gst_element_mut() as *mut GstBaseTransform
+    }
+}
+
+impl Reference for BaseTransform{
+    fn reference(&self) -> BaseTransform{
+        BaseTransform{ transform: self.transform.reference() }
+    }
+}
+
+impl AsRef<Element> for BaseTransform{
+    fn as_ref(&self) -> &Element{
+        &self.transform
+    }
+}
+
+impl AsMut<Element> for BaseTransform{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.transform
+    }
+}
+
+impl From<BaseTransform> for Element{
+    fn from(t: BaseTransform) -> Element{
+        t.transform
+    }
+}
+
+impl Deref for BaseTransform{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.transform
+    }
+}
+
+impl DerefMut for BaseTransform{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.transform
+    }
+}
+
+/// Registers T as an element factory called name, eg: "rsinvert", so it
+/// can be created with Element::new or used in a pipeline description.
+///
+/// plugin is the plugin being loaded when called from the plugin's init
+/// function or None to register the element only for this application.
+/// rank is used when autoplugging, see the GST_RANK_* constants.
+///
+/// Returns false if name was already registered for a different T,
+/// also with a name that only differs in punctuation, eg: "rs-invert"
+/// and "rs_invert".
+pub fn register_base_transform<T: BaseTransformImpl>(plugin: Option<&Plugin>, name: &str, rank: u32) -> bool{
+    let cname = CString::new(name).unwrap();
+    unsafe{
+        let gtype = base_transform_type::<T>(name);
+        let plugin = plugin.map(|plugin| plugin.gst_plugin() as *mut GstPlugin).unwrap_or(ptr::null_mut());
+        gtype != 0 && gst_element_register(plugin, cname.as_ptr(), rank, gtype) != 0
+    }
+}
+
+unsafe fn base_transform_type<T: BaseTransformImpl>(name: &str) -> GType{
+    register_type::<T>(gst_base_transform_get_type(), &type_name(ELEMENT_PREFIX, name),
+        mem::size_of::<GstBaseTransformClass>(), mem::size_of::<Instance<GstBaseTransform, T>>(),
+        Some(class_init::<T>), Some(instance_init::<T>))
+}
+
+extern "C" fn class_init<T: BaseTransformImpl>(klass: gpointer, _data: gpointer){
+    catch_panic(ptr::null_mut(), (), || unsafe{
+        let object_class = klass as *mut GObjectClass;
+        (*object_class).finalize = Some(finalize::<GstBaseTransform, T>);
+
+        let element_class = klass as *mut GstElementClass;
+        let metadata = T::metadata();
+        let long_name = CString::new(metadata.long_name).unwrap();
+        let klass_name = CString::new(metadata.klass).unwrap();
+        let description = CString::new(metadata.description).unwrap();
+        let author = CString::new(metadata.author).unwrap();
+        gst_element_class_set_metadata(element_class, long_name.as_ptr(), klass_name.as_ptr(),
+            description.as_ptr(), author.as_ptr());
+
+        let caps = T::pad_caps();
+        for &(name, direction) in [("sink", GST_PAD_SINK), ("src", GST_PAD_SRC)].iter(){
+            let cname = CString::new(name).unwrap();
+            let templ = gst_pad_template_new(cname.as_ptr(), direction, GST_PAD_ALWAYS, caps.gst_caps() as *mut GstCaps);
+            gst_element_class_add_pad_template(element_class, templ);
+        }
+
+        let transform_class = klass as *mut GstBaseTransformClass;
+        (*transform_class).set_caps = Some(set_caps::<T>);
+        (*transform_class).start = Some(start::<T>);
+        (*transform_class).stop = Some(stop::<T>);
+        (*transform_class).transform_ip = Some(transform_ip::<T>);
+    })
+}
+
+extern "C" fn instance_init<T: BaseTransformImpl>(instance: *mut GTypeInstance, _klass: gpointer){
+    unsafe{
+        let trans = instance as *mut GstBaseTransform;
+        let element = instance as *mut GstElement;
+        gst_object_ref(element as *mut c_void);
+        let mut transform = BaseTransform::new_from_gst_basetransform(trans).unwrap();
+        let imp = catch_panic(element, None, || Some(T::new(&mut transform)));
+        set_imp(trans, imp);
+    }
+}
+
+extern "C" fn set_caps<T: BaseTransformImpl>(trans: *mut GstBaseTransform, incaps: *mut GstCaps, outcaps: *mut GstCaps) -> gboolean{
+    unsafe{
+        let incaps = Caps::new(gst_mini_object_ref(incaps as *mut GstMiniObject) as *mut GstCaps);
+        let outcaps = Caps::new(gst_mini_object_ref(outcaps as *mut GstMiniObject) as *mut GstCaps);
+        match (incaps, outcaps){
+            (Some(incaps), Some(outcaps)) => with_imp(trans, trans as *mut GstElement, false, |imp: &mut T| imp.set_caps(&incaps, &outcaps)) as gboolean,
+            _ => 0,
+        }
+    }
+}
+
+extern "C" fn start<T: BaseTransformImpl>(trans: *mut GstBaseTransform) -> gboolean{
+    unsafe{
+        with_imp(trans, trans as *mut GstElement, false, |imp: &mut T| imp.start()) as gboolean
+    }
+}
+
+extern "C" fn stop<T: BaseTransformImpl>(trans: *mut GstBaseTransform) -> gboolean{
+    unsafe{
+        with_imp(trans, trans as *mut GstElement, false, |imp: &mut T| imp.stop()) as gboolean
+    }
+}
+
+extern "C" fn transform_ip<T: BaseTransformImpl>(trans: *mut GstBaseTransform, buf: *mut GstBuffer) -> GstFlowReturn{
+    unsafe{
+        // the buffer is borrowed from the base class, it's wrapped outside
+        // of the closure so it's never dropped, even if transform_ip panics
+        let mut buffer = match Buffer::new(buf){
+            Some(buffer) => buffer,
+            None => return GST_FLOW_ERROR,
+        };
+        let ret = with_imp(trans, trans as *mut GstElement, GST_FLOW_ERROR, |imp: &mut T| imp.transform_ip(&mut InPlaceBuffer{ buffer: &mut buffer }));
+        buffer.transfer();
+        ret
+    }
+}
+
+/// Plugin description exported by plugin_define!
+pub struct PluginDesc(pub GstPluginDesc);
+
+// only contains pointers to static strings and functions
+unsafe impl Sync for PluginDesc {}
+
+/// Calls the plugin's init function from the plugin_init of the
+/// description created by plugin_define!
+pub unsafe fn plugin_init<F: FnOnce(&Plugin) -> bool>(plugin: *mut GstPlugin, init: F) -> gboolean{
+    gst_object_ref(plugin as *mut c_void);
+    match Plugin::new(plugin){
+        Some(plugin) => catch_panic(ptr::null_mut(), false, || init(&plugin)) as gboolean,
+        None => 0,
+    }
+}
+
+/// Defines the entry point of a plugin so GStreamer can load it, the
+/// crate has to be built as a cdylib named libgst<name>.so (or the
+/// equivalent in other platforms) and be in the plugin path.
+///
+/// init is a fn(&Plugin) -> bool that registers the elements of the
+/// plugin.
+///
+/// ```ignore
+/// fn plugin_init(plugin: &gst::Plugin) -> bool{
+///     gst::subclass::register_base_transform::<Invert>(Some(plugin), "rsinvert", gst::ffi::GST_RANK_NONE)
+/// }
+///
+/// plugin_define!("rsinvert", "Inverts video", plugin_init, "0.1.0", "MIT",
+///     "rsinvert", "rsinvert", "https://example.com");
+/// ```
+#[macro_export]
+macro_rules! plugin_define(
+    ($name:expr, $description:expr, $init:expr, $version:expr, $license:expr, $source:expr, $package:expr, $origin:expr) => (
+        #[no_mangle]
+        #[allow(non_upper_case_globals)]
+        pub static gst_plugin_desc: $crate::subclass::PluginDesc =
+            $crate::plugin_desc!($name, $description, $init, $version, $license, $source, $package, $origin);
+    )
+);
+
+/// The description of a plugin as a PluginDesc value, same arguments
+/// as plugin_define!. Used to register plugins linked into the
+/// application with register_static_plugins instead of
+/// loading them from a shared library
+#[macro_export]
+macro_rules! plugin_desc(
+    ($name:expr, $description:expr, $init:expr, $version:expr, $license:expr, $source:expr, $package:expr, $origin:expr) => (
+        $crate::subclass::PluginDesc($crate::ffi::GstPluginDesc{
+            major_version: 1,
+            minor_version: 0,
+            name: concat!($name, "\0").as_ptr() as *const $crate::ffi::gchar,
+            description: concat!($description, "\0").as_ptr() as *const $crate::ffi::gchar,
+            plugin_init: {
+                extern "C" fn plugin_init_trampoline(plugin: *mut $crate::ffi::GstPlugin) -> $crate::ffi::gboolean{
+                    unsafe{ $crate::subclass::plugin_init(plugin, $init) }
+                }
+                Some(plugin_init_trampoline)
+            },
+            version: concat!($version, "\0").as_ptr() as *const $crate::ffi::gchar,
+            license: concat!($license, "\0").as_ptr() as *const $crate::ffi::gchar,
+            source: concat!($source, "\0").as_ptr() as *const $crate::ffi::gchar,
+            package: concat!($package, "\0").as_ptr() as *const $crate::ffi::gchar,
+            origin: concat!($origin, "\0").as_ptr() as *const $crate::ffi::gchar,
+            release_datetime: ::std::ptr::null(),
+            _gst_reserved: [::std::ptr::null_mut(); 4],
+        })
+    )
+);
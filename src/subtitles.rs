@@ -0,0 +1,145 @@
+use ffi::*;
+use element::Element;
+use playbin::PlayBin;
+use reference::Reference;
+use error::Result;
+
+use std::ops::{Deref, DerefMut};
+
+// GST_PLAY_FLAG_TEXT, playbin's GstPlayFlags aren't in the headers
+const PLAY_FLAG_TEXT: i32 = 1 << 2;
+
+/// Wrapper for subtitleoverlay, which renders a subtitle stream of any
+/// format on top of a video, or for a textoverlay used the same way
+/// with text/x-raw subtitles linked to its "text_sink" pad:
+///
+/// ```ignore
+/// let mut overlay = gst::SubtitleOverlay::new("subs").unwrap();
+/// overlay.set_font_desc("Sans 18");
+/// pipeline.add(overlay.reference());
+/// // later, from a key press
+/// overlay.set_visible(false);
+/// ```
+pub struct SubtitleOverlay{
+    overlay: Element,
+}
+
+impl SubtitleOverlay{
+    /// Creates a new subtitleoverlay element with the given name
+    pub fn new(name: &str) -> Option<SubtitleOverlay>{
+        Element::new("subtitleoverlay", name).map(SubtitleOverlay::new_from_element)
+    }
+
+    /// Creates a new textoverlay element with the given name, for
+    /// subtitles already parsed to text
+    pub fn new_text(name: &str) -> Option<SubtitleOverlay>{
+        Element::new("textoverlay", name).map(SubtitleOverlay::new_from_element)
+    }
+
+    /// Wraps an existing subtitleoverlay or textoverlay, eg: retrieved
+    /// by name from a pipeline
+    pub fn new_from_element(element: Element) -> SubtitleOverlay{
+        SubtitleOverlay{ overlay: element }
+    }
+
+    /// Pango font description of the font to be used for rendering,
+    /// eg: "Sans Bold 24"
+    pub fn set_font_desc(&mut self, font: &str){
+        self.set("font-desc", font);
+    }
+
+    /// Character encoding of subtitles that aren't UTF-8, eg:
+    /// "Windows-1252". Only used by subtitleoverlay
+    pub fn set_encoding(&mut self, encoding: &str){
+        self.set("subtitle-encoding", encoding);
+    }
+
+    /// Shifts the subtitles by offset nanoseconds, positive to show
+    /// them later. Only used by subtitleoverlay
+    pub fn set_offset(&mut self, offset: i64){
+        self.set("subtitle-ts-offset", offset);
+    }
+
+    /// Shows or hides the subtitles while playing, the stream keeps
+    /// being parsed so they're back in sync when shown again
+    pub fn set_visible(&mut self, visible: bool){
+        self.set("silent", !visible as gboolean);
+    }
+
+    pub fn is_visible(&self) -> bool{
+        self.get::<gboolean>("silent") == 0
+    }
+}
+
+impl PlayBin{
+    /// Plays the subtitles at uri, eg: an SRT or ASS file, along with
+    /// the current uri. Has to be set before going to PAUSED, encoding
+    /// is only needed for files that aren't UTF-8
+    pub fn load_subtitles(&mut self, uri: &str, encoding: Option<&str>){
+        self.set_suburi(uri);
+        if let Some(encoding) = encoding{
+            self.set_subtitle_encoding(encoding);
+        }
+    }
+
+    /// Same as load_subtitles with a path instead of an uri
+    pub fn load_subtitle_file(&mut self, path: &str, encoding: Option<&str>) -> Result<()>{
+        let uri = ::filename_to_uri(path)?;
+        self.load_subtitles(&uri, encoding);
+        Ok(())
+    }
+
+    /// Shows or hides the subtitles at runtime by toggling the text
+    /// flag of playbin
+    pub fn set_subtitles_visible(&mut self, visible: bool){
+        let flags = self.get::<i32>("flags");
+        self.set_flags(if visible { flags | PLAY_FLAG_TEXT } else { flags & !PLAY_FLAG_TEXT });
+    }
+
+    pub fn subtitles_visible(&self) -> bool{
+        self.get::<i32>("flags") & PLAY_FLAG_TEXT != 0
+    }
+}
+
+impl Reference for SubtitleOverlay{
+    fn reference(&self) -> SubtitleOverlay{
+        SubtitleOverlay{ overlay: self.overlay.reference() }
+    }
+}
+
+impl AsRef<Element> for SubtitleOverlay{
+    fn as_ref(&self) -> &Element{
+        &self.overlay
+    }
+}
+
+impl AsMut<Element> for SubtitleOverlay{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.overlay
+    }
+}
+
+impl From<SubtitleOverlay> for Element{
+    fn from(s: SubtitleOverlay) -> Element{
+        s.overlay
+    }
+}
+
+impl Deref for SubtitleOverlay{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.overlay
+    }
+}
+
+impl DerefMut for SubtitleOverlay{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.overlay
+    }
+}
+
+impl ::Transfer for SubtitleOverlay{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.overlay.transfer()
+    }
+}
@@ -0,0 +1,245 @@
+use ffi::*;
+use util::*;
+use reference::Reference;
+use miniobject::MiniObject;
+use value::{Value, ToValue, FromValue};
+
+use std::ops::{Deref, DerefMut};
+
+/// How the values of two tag lists are combined, mirrors
+/// GstTagMergeMode. "Old" values are the ones of the list merged into
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum MergeMode{
+    /// Only the new values, the old ones are removed
+    ReplaceAll = GST_TAG_MERGE_REPLACE_ALL,
+    /// New values replace old values of the same tag
+    Replace = GST_TAG_MERGE_REPLACE,
+    /// New values are added after the old values of the same tag
+    Append = GST_TAG_MERGE_APPEND,
+    /// New values are added before the old values of the same tag
+    Prepend = GST_TAG_MERGE_PREPEND,
+    /// New values are only used for tags without values
+    Keep = GST_TAG_MERGE_KEEP,
+    /// Only the old values, the new ones are ignored
+    KeepAll = GST_TAG_MERGE_KEEP_ALL,
+}
+
+/// Whether tags apply to a single stream, eg: its codec or bitrate, or
+/// to the whole media, eg: the title of a movie. Mirrors GstTagScope
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum TagScope{
+    Stream,
+    Global,
+}
+
+/// Metadata of a stream like its title, codec or bitrate. Tag names are
+/// the ones defined by GStreamer, eg: "title", "video-codec" or
+/// "bitrate", and each tag can have several values.
+#[derive(Clone)]
+pub struct TagList{
+    tags: MiniObject<GstTagList>
+}
+
+impl TagList{
+    pub unsafe fn new_from_gst_taglist(tags: *mut GstTagList) -> Option<TagList>{
+        MiniObject::new(tags)
+            .map(|miniobject| TagList{ tags: miniobject })
+    }
+
+    pub fn new() -> TagList{
+        unsafe{
+            TagList::new_from_gst_taglist(gst_tag_list_new_empty()).unwrap()
+        }
+    }
+
+    /// Parses a tag list from its string representation, eg:
+    /// "taglist, title=(string)Intro"
+    pub fn from_string(desc: &str) -> Option<TagList>{
+        let cdesc = CString::new(desc).unwrap();
+        unsafe{
+            TagList::new_from_gst_taglist(gst_tag_list_new_from_string(cdesc.as_ptr()))
+        }
+    }
+
+    pub fn to_string(&self) -> String{
+        unsafe{
+            let desc = gst_tag_list_to_string(self.gst_taglist());
+            let ret = from_c_str!(desc).to_string();
+            g_free(desc as gpointer);
+            ret
+        }
+    }
+
+    pub fn is_empty(&self) -> bool{
+        unsafe{
+            gst_tag_list_is_empty(self.gst_taglist()) != 0
+        }
+    }
+
+    /// Names of the tags in the list
+    pub fn tag_names(&self) -> Vec<String>{
+        unsafe{
+            (0..gst_tag_list_n_tags(self.gst_taglist())).map(|i|
+                from_c_str!(gst_tag_list_nth_tag_name(self.gst_taglist(), i as guint)).to_string()
+            ).collect()
+        }
+    }
+
+    /// Number of values for tag
+    pub fn size(&self, tag: &str) -> u32{
+        let ctag = CString::new(tag).unwrap();
+        unsafe{
+            gst_tag_list_get_tag_size(self.gst_taglist(), ctag.as_ptr())
+        }
+    }
+
+    /// Returns a copy of the index-th value of tag
+    pub fn get_value(&self, tag: &str, index: u32) -> Option<Value>{
+        let ctag = CString::new(tag).unwrap();
+        unsafe{
+            let value = gst_tag_list_get_value_index(self.gst_taglist(), ctag.as_ptr(), index);
+            if value != ptr::null(){
+                Some(Value::new_from_gvalue(value))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns the first value of tag if it's of type T, eg:
+    /// get::<u32>("bitrate") or get::<String>("title")
+    pub fn get<T: FromValue>(&self, tag: &str) -> Option<T>{
+        self.get_value(tag, 0).and_then(|value| value.get::<T>())
+    }
+
+    /// Returns all the values of tag that are of type T
+    pub fn get_all<T: FromValue>(&self, tag: &str) -> Vec<T>{
+        (0..self.size(tag)).filter_map(|i| self.get_value(tag, i).and_then(|value| value.get::<T>())).collect()
+    }
+
+    /// Sets tag to value replacing any previous values, the list has to
+    /// be writable
+    pub fn set<T: ToValue + ?Sized>(&mut self, tag: &str, value: &T){
+        let ctag = CString::new(tag).unwrap();
+        unsafe{
+            gst_tag_list_add_value(self.gst_taglist_mut(), GST_TAG_MERGE_REPLACE, ctag.as_ptr(), value.to_value().gst_value());
+        }
+    }
+
+    /// Adds value to the values of tag, the list has to be writable
+    pub fn append<T: ToValue + ?Sized>(&mut self, tag: &str, value: &T){
+        let ctag = CString::new(tag).unwrap();
+        unsafe{
+            gst_tag_list_add_value(self.gst_taglist_mut(), GST_TAG_MERGE_APPEND, ctag.as_ptr(), value.to_value().gst_value());
+        }
+    }
+
+    pub fn remove(&mut self, tag: &str){
+        let ctag = CString::new(tag).unwrap();
+        unsafe{
+            gst_tag_list_remove_tag(self.gst_taglist_mut(), ctag.as_ptr());
+        }
+    }
+
+    /// Copies the tags of a tag event, eg: received in an event probe.
+    /// None if it's not a tag event
+    pub unsafe fn from_event(event: *mut GstEvent) -> Option<TagList>{
+        if event == ptr::null_mut() || (*event)._type != GST_EVENT_TAG{
+            return None;
+        }
+        let mut tags: *mut GstTagList = ptr::null_mut();
+        gst_event_parse_tag(event, &mut tags);
+        if tags != ptr::null_mut(){
+            gst_mini_object_ref(tags as *mut GstMiniObject);
+        }
+        TagList::new_from_gst_taglist(tags)
+    }
+
+    pub fn scope(&self) -> TagScope{
+        unsafe{
+            match gst_tag_list_get_scope(self.gst_taglist()){
+                GST_TAG_SCOPE_GLOBAL => TagScope::Global,
+                _ => TagScope::Stream,
+            }
+        }
+    }
+
+    /// Sets the scope, the list has to be writable
+    pub fn set_scope(&mut self, scope: TagScope){
+        let scope = match scope{
+            TagScope::Stream => GST_TAG_SCOPE_STREAM,
+            TagScope::Global => GST_TAG_SCOPE_GLOBAL,
+        };
+        unsafe{
+            gst_tag_list_set_scope(self.gst_taglist_mut(), scope);
+        }
+    }
+
+    /// A new list with the tags of self and other, other's values being
+    /// the new ones for mode
+    pub fn merge(&self, other: &TagList, mode: MergeMode) -> TagList{
+        unsafe{
+            TagList::new_from_gst_taglist(gst_tag_list_merge(self.gst_taglist(), other.gst_taglist(), mode as GstTagMergeMode))
+                .unwrap_or_else(TagList::new)
+        }
+    }
+
+    /// Adds the tags of other to self, the list has to be writable
+    pub fn insert(&mut self, other: &TagList, mode: MergeMode){
+        unsafe{
+            gst_tag_list_insert(self.gst_taglist_mut(), other.gst_taglist(), mode as GstTagMergeMode);
+        }
+    }
+
+    pub fn make_writable(self) -> TagList{
+        TagList{ tags: self.tags.make_writable() }
+    }
+
+    pub unsafe fn gst_taglist(&self) -> *const GstTagList{
+        self.tags.as_ptr()
+    }
+
+    pub unsafe fn gst_taglist_mut(&mut self) -> *mut GstTagList{
+        self.tags.as_mut_ptr()
+    }
+}
+
+impl PartialEq for TagList{
+    fn eq(&self, other: &TagList) -> bool{
+        unsafe{
+            gst_tag_list_is_equal(self.gst_taglist(), other.gst_taglist()) != 0
+        }
+    }
+}
+
+impl Reference for TagList{
+    fn reference(&self) -> TagList{
+        TagList{ tags: self.tags.reference() }
+    }
+}
+
+impl AsRef<MiniObject> for TagList{
+    fn as_ref(&self) -> &MiniObject{
+        self.tags.upcast_ref()
+    }
+}
+
+impl AsMut<MiniObject> for TagList{
+    fn as_mut(&mut self) -> &mut MiniObject{
+        self.tags.upcast_mut()
+    }
+}
+
+impl Deref for TagList{
+    type Target = MiniObject;
+    fn deref(&self) -> &MiniObject{
+        self.tags.upcast_ref()
+    }
+}
+
+impl DerefMut for TagList{
+    fn deref_mut(&mut self) -> &mut MiniObject{
+        self.tags.upcast_mut()
+    }
+}
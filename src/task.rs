@@ -0,0 +1,472 @@
+use ffi::*;
+use util::*;
+use object::Object;
+use reference::Reference;
+use error::{Error, Result};
+use panic::catch_panic;
+use registration::register_type;
+
+use std::ops::{Deref, DerefMut};
+use std::os::raw::c_void;
+use std::sync::{Mutex, MutexGuard};
+
+/// A thread running a function in a loop, what pads use to run the
+/// streaming loop of sources and demuxers. Tasks can also be used by
+/// applications that need a loop they can start, pause and stop:
+///
+/// ```ignore
+/// let mut task = gst::task::Task::new(move || {
+///     let frame = camera.capture();
+///     appsrc.push_buffer(frame);
+/// }).unwrap();
+/// task.set_enter_callback(|| pin_current_thread_to_core(2));
+/// task.start();
+/// ```
+///
+/// The function is called again as soon as it returns until the task
+/// is paused or stopped.
+pub struct Task{
+    task: Object<GstTask>
+}
+
+// the function of a task and the lock the task holds while calling it,
+// freed by the task when finalized
+struct TaskData{
+    func: Guarded<dyn FnMut() + Send>,
+    lock: GRecMutex,
+    // not a reference, the task owns this data
+    task: *mut GstTask,
+}
+
+type ThreadFunc = Guarded<dyn FnMut() + Send>;
+
+impl Drop for TaskData{
+    fn drop(&mut self){
+        unsafe{
+            g_rec_mutex_clear(&mut self.lock);
+        }
+    }
+}
+
+impl Task{
+    pub unsafe fn new_from_gst_task(task: *mut GstTask) -> Option<Task>{
+        Object::new(task).map(|obj| Task{ task: obj })
+    }
+
+    pub fn new<F: FnMut() + Send + 'static>(func: F) -> Option<Task>{
+        unsafe{
+            let data = Box::into_raw(Box::new(TaskData{
+                func: Guarded::new(Box::new(func)),
+                lock: mem::zeroed(),
+                task: ptr::null_mut(),
+            }));
+            g_rec_mutex_init(&mut (*data).lock);
+            let task = gst_task_new(Some(task_func), data as gpointer, Some(task_data_destroy));
+            if task == ptr::null_mut(){
+                drop(Box::from_raw(data));
+                return None;
+            }
+            (*data).task = task;
+            gst_task_set_lock(task, &mut (*data).lock);
+            Task::new_from_gst_task(task)
+        }
+    }
+
+    /// Starts or resumes calling the function from a thread of the pool
+    pub fn start(&mut self) -> bool{
+        unsafe{
+            gst_task_start(self.gst_task_mut()) != 0
+        }
+    }
+
+    /// Stops calling the function after the current call, join waits
+    /// for it
+    pub fn stop(&mut self) -> bool{
+        unsafe{
+            gst_task_stop(self.gst_task_mut()) != 0
+        }
+    }
+
+    /// Pauses the task, keeping its thread, until start is called again
+    pub fn pause(&mut self) -> bool{
+        unsafe{
+            gst_task_pause(self.gst_task_mut()) != 0
+        }
+    }
+
+    /// Stops the task and waits for the function to return. Can't be
+    /// called from the function itself
+    pub fn join(&mut self) -> bool{
+        unsafe{
+            gst_task_join(self.gst_task_mut()) != 0
+        }
+    }
+
+    /// GST_TASK_STARTED, GST_TASK_STOPPED or GST_TASK_PAUSED
+    pub fn state(&self) -> GstTaskState{
+        unsafe{
+            gst_task_get_state(self.gst_task() as *mut GstTask)
+        }
+    }
+
+    /// The pool the thread of the task comes from
+    pub fn pool(&self) -> Option<TaskPool>{
+        unsafe{
+            TaskPool::new_from_gst_task_pool(gst_task_get_pool(self.gst_task() as *mut GstTask))
+        }
+    }
+
+    /// Changes the pool the thread of the task comes from, only while
+    /// the task is stopped
+    pub fn set_pool(&mut self, pool: &TaskPool){
+        unsafe{
+            gst_task_set_pool(self.gst_task_mut(), pool.gst_task_pool() as *mut GstTaskPool);
+        }
+    }
+
+    /// Calls callback from the thread of the task when it starts running
+    /// the task, eg: to change the priority of the thread or pin it to a
+    /// CPU core
+    pub fn set_enter_callback<F: FnMut() + Send + 'static>(&mut self, callback: F){
+        unsafe{
+            let callback: Box<ThreadFunc> = Box::new(Guarded::new(Box::new(callback)));
+            gst_task_set_enter_callback(self.gst_task_mut(), Some(task_thread_func),
+                Box::into_raw(callback) as gpointer, Some(thread_func_destroy));
+        }
+    }
+
+    /// Calls callback from the thread of the task when it stops running
+    /// the task
+    pub fn set_leave_callback<F: FnMut() + Send + 'static>(&mut self, callback: F){
+        unsafe{
+            let callback: Box<ThreadFunc> = Box::new(Guarded::new(Box::new(callback)));
+            gst_task_set_leave_callback(self.gst_task_mut(), Some(task_thread_func),
+                Box::into_raw(callback) as gpointer, Some(thread_func_destroy));
+        }
+    }
+
+    pub unsafe fn gst_task(&self) -> *const GstTask{
+        self.task.as_ptr()
+    }
+
+    pub unsafe fn gst_task_mut(&mut self) -> *mut GstTask{
+        self.task.as_mut_ptr()
+    }
+}
+
+extern "C" fn task_func(data: gpointer){
+    unsafe{
+        let data = &mut *(data as *mut TaskData);
+        data.func.call(ptr::null_mut(), (), |func| func());
+        // the function would be called again right away otherwise
+        if data.func.is_poisoned(){
+            gst_task_stop(data.task);
+        }
+    }
+}
+
+extern "C" fn task_data_destroy(data: gpointer){
+    unsafe{
+        catch_panic(ptr::null_mut(), (), || drop(Box::from_raw(data as *mut TaskData)));
+    }
+}
+
+extern "C" fn task_thread_func(_task: *mut GstTask, _thread: *mut GThread, data: gpointer){
+    unsafe{
+        let callback = &mut *(data as *mut ThreadFunc);
+        callback.call(ptr::null_mut(), (), |callback| callback());
+    }
+}
+
+extern "C" fn thread_func_destroy(data: gpointer){
+    unsafe{
+        catch_panic(ptr::null_mut(), (), || drop(Box::from_raw(data as *mut ThreadFunc)));
+    }
+}
+
+/// A job pushed to a TaskPool, usually the loop of a Task. It has to be
+/// run once from the thread chosen by the pool
+pub struct TaskPoolJob{
+    func: GstTaskPoolFunction,
+    data: gpointer,
+}
+
+unsafe impl Send for TaskPoolJob{}
+
+impl TaskPoolJob{
+    pub fn run(self){
+        if let Some(func) = self.func{
+            func(self.data as *mut c_void);
+        }
+    }
+}
+
+/// Implementation of a custom TaskPool, to control which threads run
+/// the streaming loops, eg: threads pinned to a CPU core:
+///
+/// ```ignore
+/// struct PinnedPool{ threads: HashMap<usize, thread::JoinHandle<()>>, next: usize }
+///
+/// impl gst::task::TaskPoolImpl for PinnedPool{
+///     fn push(&mut self, job: gst::task::TaskPoolJob) -> gst::Result<usize>{
+///         self.next += 1;
+///         self.threads.insert(self.next, thread::spawn(move || {
+///             pin_current_thread_to_core(2);
+///             job.run();
+///         }));
+///         Ok(self.next)
+///     }
+///
+///     fn join(&mut self, id: usize){
+///         if let Some(thread) = self.threads.remove(&id){
+///             let _ = thread.join();
+///         }
+///     }
+/// }
+///
+/// let pool = gst::task::TaskPool::new_custom(PinnedPool{ threads: HashMap::new(), next: 0 }).unwrap();
+/// ```
+pub trait TaskPoolImpl: Send + 'static{
+    /// Called before the pool is used
+    fn prepare(&mut self) -> Result<()>{
+        Ok(())
+    }
+
+    /// Called once the pool isn't used anymore
+    fn cleanup(&mut self){}
+
+    /// Runs job in a thread, returns an id to pass to join
+    fn push(&mut self, job: TaskPoolJob) -> Result<usize>;
+
+    /// Waits until the job identified by id returns
+    fn join(&mut self, id: usize);
+}
+
+/// A pool of threads tasks get their thread from, by default a pool of
+/// GThreads. Set on the pads of an element, with Task::set_pool or
+/// answering the stream-status messages, to choose the threads that run
+/// its streaming loop
+pub struct TaskPool{
+    pool: Object<GstTaskPool>
+}
+
+impl TaskPool{
+    pub unsafe fn new_from_gst_task_pool(pool: *mut GstTaskPool) -> Option<TaskPool>{
+        Object::new(pool).map(|obj| TaskPool{ pool: obj })
+    }
+
+    /// Creates a pool of GThreads
+    pub fn new() -> Option<TaskPool>{
+        unsafe{
+            TaskPool::new_from_gst_task_pool(gst_task_pool_new())
+        }
+    }
+
+    /// Creates a pool that runs the jobs with imp
+    pub fn new_custom<T: TaskPoolImpl>(imp: T) -> Option<TaskPool>{
+        unsafe{
+            let gtype = rust_task_pool_type();
+            if gtype == 0{
+                return None;
+            }
+            let pool = g_object_new(gtype, ptr::null::<gchar>()) as *mut RustTaskPool;
+            if pool == ptr::null_mut(){
+                return None;
+            }
+            gst_object_ref_sink(pool as gpointer);
+            let imp: Guarded<dyn TaskPoolImpl> = Guarded::new(Box::new(imp));
+            (*pool).imp = Box::into_raw(Box::new(Mutex::new(imp)));
+            TaskPool::new_from_gst_task_pool(pool as *mut GstTaskPool)
+        }
+    }
+
+    /// Prepares the pool to run jobs, tasks call it when they start
+    pub fn prepare(&mut self) -> Result<()>{
+        unsafe{
+            let mut error: *mut GError = ptr::null_mut();
+            gst_task_pool_prepare(self.gst_task_pool_mut(), &mut error);
+            if error != ptr::null_mut(){
+                Err(Error::new_from_g_error(error))
+            }else{
+                Ok(())
+            }
+        }
+    }
+
+    /// Releases the threads of the pool
+    pub fn cleanup(&mut self){
+        unsafe{
+            gst_task_pool_cleanup(self.gst_task_pool_mut());
+        }
+    }
+
+    pub unsafe fn gst_task_pool(&self) -> *const GstTaskPool{
+        self.pool.as_ptr()
+    }
+
+    pub unsafe fn gst_task_pool_mut(&mut self) -> *mut GstTaskPool{
+        self.pool.as_mut_ptr()
+    }
+}
+
+/// Instance struct of the task pool type used for TaskPoolImpl
+#[repr(C)]
+struct RustTaskPool{
+    parent: GstTaskPool,
+    imp: *mut Mutex<Guarded<dyn TaskPoolImpl>>,
+}
+
+unsafe fn rust_task_pool_type() -> GType{
+    register_type::<RustTaskPool>(gst_task_pool_get_type(), "GstRsTaskPool", mem::size_of::<GstTaskPoolClass>(),
+        mem::size_of::<RustTaskPool>(), Some(pool_class_init), None)
+}
+
+// the pool isn't called anymore once it panicked
+unsafe fn pool_imp<'a>(pool: *mut GstTaskPool) -> Option<MutexGuard<'a, Guarded<dyn TaskPoolImpl>>>{
+    let imp = (*(pool as *mut RustTaskPool)).imp;
+    if imp != ptr::null_mut() { Some((*imp).lock().unwrap_or_else(|e| e.into_inner())) } else { None }
+}
+
+unsafe fn set_error(error: *mut *mut GError, err: &Error){
+    let message = CString::new(err.message().replace('\0', "")).unwrap();
+    g_set_error_literal(error, err.domain(), err.code(), message.as_ptr());
+}
+
+extern "C" fn pool_class_init(klass: gpointer, _data: gpointer){
+    catch_panic(ptr::null_mut(), (), || unsafe{
+        let object_class = klass as *mut GObjectClass;
+        (*object_class).finalize = Some(pool_finalize);
+
+        let pool_class = klass as *mut GstTaskPoolClass;
+        (*pool_class).prepare = Some(pool_prepare);
+        (*pool_class).cleanup = Some(pool_cleanup);
+        (*pool_class).push = Some(pool_push);
+        (*pool_class).join = Some(pool_join);
+    })
+}
+
+extern "C" fn pool_prepare(pool: *mut GstTaskPool, error: *mut *mut GError){
+    unsafe{
+        if let Some(mut imp) = pool_imp(pool){
+            if let Err(err) = imp.call(ptr::null_mut(), Ok(()), |imp| imp.prepare()){
+                set_error(error, &err);
+            }
+        }
+    }
+}
+
+extern "C" fn pool_cleanup(pool: *mut GstTaskPool){
+    unsafe{
+        if let Some(mut imp) = pool_imp(pool){
+            imp.call(ptr::null_mut(), (), |imp| imp.cleanup());
+        }
+    }
+}
+
+// ids are offset by one so the first job isn't returned as NULL, which
+// means failure
+extern "C" fn pool_push(pool: *mut GstTaskPool, func: GstTaskPoolFunction, data: gpointer, error: *mut *mut GError) -> gpointer{
+    unsafe{
+        let mut imp = match pool_imp(pool){
+            Some(imp) => imp,
+            None => return ptr::null_mut(),
+        };
+        let job = TaskPoolJob{ func: func, data: data };
+        match imp.call(ptr::null_mut(), None, |imp| Some(imp.push(job))){
+            Some(Ok(id)) => (id + 1) as gpointer,
+            Some(Err(err)) => {
+                set_error(error, &err);
+                ptr::null_mut()
+            }
+            None => ptr::null_mut(),
+        }
+    }
+}
+
+extern "C" fn pool_join(pool: *mut GstTaskPool, id: gpointer){
+    unsafe{
+        if let Some(mut imp) = pool_imp(pool){
+            if id != ptr::null_mut(){
+                imp.call(ptr::null_mut(), (), |imp| imp.join(id as usize - 1));
+            }
+        }
+    }
+}
+
+extern "C" fn pool_finalize(object: *mut GObject){
+    unsafe{
+        let pool = object as *mut RustTaskPool;
+        if (*pool).imp != ptr::null_mut(){
+            let imp = Box::from_raw((*pool).imp);
+            (*pool).imp = ptr::null_mut();
+            catch_panic(ptr::null_mut(), (), move || drop(imp));
+        }
+        let klass = (*(object as *mut GTypeInstance)).g_class;
+        let parent_class = g_type_class_peek_parent(klass as gpointer) as *mut GObjectClass;
+        if let Some(parent_finalize) = (*parent_class).finalize{
+            parent_finalize(object);
+        }
+    }
+}
+
+impl Reference for Task{
+    fn reference(&self) -> Task{
+        Task{ task: self.task.reference() }
+    }
+}
+
+impl AsRef<Object> for Task{
+    fn as_ref(&self) -> &Object{
+        self.task.upcast_ref()
+    }
+}
+
+impl AsMut<Object> for Task{
+    fn as_mut(&mut self) -> &mut Object{
+        self.task.upcast_mut()
+    }
+}
+
+impl Deref for Task{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.task.upcast_ref()
+    }
+}
+
+impl DerefMut for Task{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.task.upcast_mut()
+    }
+}
+
+impl Reference for TaskPool{
+    fn reference(&self) -> TaskPool{
+        TaskPool{ pool: self.pool.reference() }
+    }
+}
+
+impl AsRef<Object> for TaskPool{
+    fn as_ref(&self) -> &Object{
+        self.pool.upcast_ref()
+    }
+}
+
+impl AsMut<Object> for TaskPool{
+    fn as_mut(&mut self) -> &mut Object{
+        self.pool.upcast_mut()
+    }
+}
+
+impl Deref for TaskPool{
+    type Target = Object;
+    fn deref(&self) -> &Object{
+        self.pool.upcast_ref()
+    }
+}
+
+impl DerefMut for TaskPool{
+    fn deref_mut(&mut self) -> &mut Object{
+        self.pool.upcast_mut()
+    }
+}
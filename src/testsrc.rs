@@ -0,0 +1,391 @@
+use ffi::*;
+use element::Element;
+use buffer::Buffer;
+use pad::Pad;
+use handle::SignalHandle;
+use reference::Reference;
+
+use std::ops::{Deref, DerefMut};
+
+/// Pattern generated by videotestsrc
+#[repr(i32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum VideoPattern{
+    Smpte = 0,
+    Snow = 1,
+    Black = 2,
+    White = 3,
+    Red = 4,
+    Green = 5,
+    Blue = 6,
+    Checkers1 = 7,
+    Checkers2 = 8,
+    Checkers4 = 9,
+    Checkers8 = 10,
+    Circular = 11,
+    Blink = 12,
+    Smpte75 = 13,
+    ZonePlate = 14,
+    Gamut = 15,
+    ChromaZonePlate = 16,
+    /// The color set with set_foreground_color
+    SolidColor = 17,
+    /// A moving ball, useful to see dropped or repeated frames
+    Ball = 18,
+    Smpte100 = 19,
+    Bar = 20,
+    Pinwheel = 21,
+    Spokes = 22,
+    Gradient = 23,
+    Colors = 24,
+}
+
+/// Size of the buffers generated by fakesrc, mirrors the "sizetype"
+/// property values
+#[repr(i32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum SizeType{
+    /// Buffers without memory
+    Empty = 1,
+    /// Buffers of sizemax bytes
+    Fixed = 2,
+    /// Buffers between sizemin and sizemax bytes
+    Random = 3,
+}
+
+/// Content of the buffers generated by fakesrc, mirrors the "filltype"
+/// property values
+#[repr(i32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum FillType{
+    /// Left uninitialized
+    Nothing = 1,
+    Zero = 2,
+    Random = 3,
+    /// Bytes counting from 0 to 255 in every buffer
+    Pattern = 4,
+    /// Bytes counting from 0 to 255 continuing across buffers
+    PatternSpan = 5,
+}
+
+/// Waveform generated by audiotestsrc
+#[repr(i32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum AudioWave{
+    Sine = 0,
+    Square = 1,
+    Saw = 2,
+    Triangle = 3,
+    Silence = 4,
+    WhiteNoise = 5,
+    PinkNoise = 6,
+    SineTable = 7,
+    /// A sine beep at the start of every second, useful to check A/V
+    /// sync against a Ball pattern
+    Ticks = 8,
+    GaussianNoise = 9,
+    RedNoise = 10,
+    BlueNoise = 11,
+    VioletNoise = 12,
+}
+
+macro_rules! element_wrapper(
+    ($t: ident, $field: ident) => (
+        impl Reference for $t{
+            fn reference(&self) -> $t{
+                $t{ $field: self.$field.reference() }
+            }
+        }
+
+        impl AsRef<Element> for $t{
+            fn as_ref(&self) -> &Element{
+                &self.$field
+            }
+        }
+
+        impl AsMut<Element> for $t{
+            fn as_mut(&mut self) -> &mut Element{
+                &mut self.$field
+            }
+        }
+
+        impl From<$t> for Element{
+            fn from(s: $t) -> Element{
+                s.$field
+            }
+        }
+
+        impl Deref for $t{
+            type Target = Element;
+            fn deref(&self) -> &Element{
+                &self.$field
+            }
+        }
+
+        impl DerefMut for $t{
+            fn deref_mut(&mut self) -> &mut Element{
+                &mut self.$field
+            }
+        }
+
+        impl ::Transfer for $t{
+            unsafe fn transfer(self) -> *mut GstElement{
+                self.$field.transfer()
+            }
+        }
+    )
+);
+
+/// Wrapper for videotestsrc, to build deterministic pipelines for tests
+/// and demos:
+///
+/// ```ignore
+/// let mut src = gst::testsrc::VideoTestSrc::new("src").unwrap();
+/// src.set_pattern(gst::testsrc::VideoPattern::Ball);
+/// src.set_num_buffers(100);
+/// pipeline.add(src.reference());
+/// ```
+pub struct VideoTestSrc{
+    src: Element
+}
+
+impl VideoTestSrc{
+    pub fn new(name: &str) -> Option<VideoTestSrc>{
+        Element::new("videotestsrc", name).map(VideoTestSrc::new_from_element)
+    }
+
+    pub fn new_from_element(element: Element) -> VideoTestSrc{
+        VideoTestSrc{ src: element }
+    }
+
+    pub fn set_pattern(&mut self, pattern: VideoPattern){
+        self.set("pattern", pattern as i32);
+    }
+
+    /// Produces buffers at the rate of the clock like a camera instead
+    /// of as fast as possible
+    pub fn set_is_live(&mut self, is_live: bool){
+        self.set("is-live", is_live as gboolean);
+    }
+
+    /// Sends EOS after num_buffers frames, -1 for unlimited
+    pub fn set_num_buffers(&mut self, num_buffers: i32){
+        self.set("num-buffers", num_buffers);
+    }
+
+    /// Color of the SolidColor pattern and of the moving parts of other
+    /// patterns in big-endian ARGB
+    pub fn set_foreground_color(&mut self, argb: u32){
+        self.set("foreground-color", argb);
+    }
+
+    pub fn set_background_color(&mut self, argb: u32){
+        self.set("background-color", argb);
+    }
+}
+
+element_wrapper!(VideoTestSrc, src);
+
+/// Wrapper for audiotestsrc
+pub struct AudioTestSrc{
+    src: Element
+}
+
+impl AudioTestSrc{
+    pub fn new(name: &str) -> Option<AudioTestSrc>{
+        Element::new("audiotestsrc", name).map(AudioTestSrc::new_from_element)
+    }
+
+    pub fn new_from_element(element: Element) -> AudioTestSrc{
+        AudioTestSrc{ src: element }
+    }
+
+    pub fn set_wave(&mut self, wave: AudioWave){
+        self.set("wave", wave as i32);
+    }
+
+    /// Frequency of the waveform in Hz
+    pub fn set_freq(&mut self, freq: f64){
+        self.set("freq", freq);
+    }
+
+    /// Volume between 0 and 1
+    pub fn set_volume(&mut self, volume: f64){
+        self.set("volume", volume);
+    }
+
+    /// Produces buffers at the rate of the clock like a microphone
+    /// instead of as fast as possible
+    pub fn set_is_live(&mut self, is_live: bool){
+        self.set("is-live", is_live as gboolean);
+    }
+
+    /// Sends EOS after num_buffers buffers, -1 for unlimited
+    pub fn set_num_buffers(&mut self, num_buffers: i32){
+        self.set("num-buffers", num_buffers);
+    }
+
+    pub fn set_samples_per_buffer(&mut self, samples: i32){
+        self.set("samplesperbuffer", samples);
+    }
+}
+
+element_wrapper!(AudioTestSrc, src);
+
+/// Wrapper for imagefreeze, which repeats the single image it receives
+/// as a video stream, eg: after a decoded png to show it as a slide
+pub struct ImageFreeze{
+    freeze: Element
+}
+
+impl ImageFreeze{
+    pub fn new(name: &str) -> Option<ImageFreeze>{
+        Element::new("imagefreeze", name).map(ImageFreeze::new_from_element)
+    }
+
+    pub fn new_from_element(element: Element) -> ImageFreeze{
+        ImageFreeze{ freeze: element }
+    }
+
+    /// Sends EOS after num_buffers frames, -1 for unlimited
+    pub fn set_num_buffers(&mut self, num_buffers: i32){
+        self.set("num-buffers", num_buffers);
+    }
+
+    /// Replaces the image when a new one arrives instead of ignoring it
+    pub fn set_allow_replace(&mut self, allow_replace: bool){
+        self.set("allow-replace", allow_replace as gboolean);
+    }
+
+    /// Produces frames at the rate of the clock instead of as fast as
+    /// possible
+    pub fn set_is_live(&mut self, is_live: bool){
+        self.set("is-live", is_live as gboolean);
+    }
+}
+
+element_wrapper!(ImageFreeze, freeze);
+
+/// Wrapper for fakesrc, which generates buffers with no particular
+/// format, eg: to test the flow through a branch without a real source
+pub struct FakeSrc{
+    src: Element
+}
+
+impl FakeSrc{
+    pub fn new(name: &str) -> Option<FakeSrc>{
+        Element::new("fakesrc", name).map(FakeSrc::new_from_element)
+    }
+
+    pub fn new_from_element(element: Element) -> FakeSrc{
+        FakeSrc{ src: element }
+    }
+
+    /// Sends EOS after num_buffers buffers, -1 for unlimited
+    pub fn set_num_buffers(&mut self, num_buffers: i32){
+        self.set("num-buffers", num_buffers);
+    }
+
+    pub fn set_size_type(&mut self, size_type: SizeType){
+        self.set("sizetype", size_type as i32);
+    }
+
+    /// Minimum and maximum size in bytes of the buffers, only the
+    /// maximum is used with SizeType::Fixed
+    pub fn set_size_range(&mut self, min: i32, max: i32){
+        self.set("sizemin", min);
+        self.set("sizemax", max);
+    }
+
+    pub fn set_fill_type(&mut self, fill_type: FillType){
+        self.set("filltype", fill_type as i32);
+    }
+
+    /// Bytes per second used to timestamp the buffers, 0 leaves them
+    /// without timestamps
+    pub fn set_datarate(&mut self, datarate: i32){
+        self.set("datarate", datarate);
+    }
+
+    /// Produces buffers at the rate of the clock instead of as fast as
+    /// possible
+    pub fn set_is_live(&mut self, is_live: bool){
+        self.set("is-live", is_live as gboolean);
+    }
+
+    /// Calls handler with every buffer and the pad it's pushed from,
+    /// right before it's pushed, from the streaming thread
+    pub fn connect_handoff<F>(&mut self, handler: F) -> Option<SignalHandle>
+        where F: FnMut(&Buffer, &Pad) + Send + 'static{
+        self.set("signal-handoffs", true as gboolean);
+        connect_handoff(&mut self.src, "handoff", handler)
+    }
+}
+
+element_wrapper!(FakeSrc, src);
+
+/// Wrapper for fakesink, which discards the buffers it receives. With a
+/// handoff handler it lets tests look at every buffer leaving a branch
+/// without an appsink:
+///
+/// ```ignore
+/// let mut sink = gst::testsrc::FakeSink::new("sink").unwrap();
+/// let (tx, rx) = std::sync::mpsc::channel();
+/// sink.connect_handoff(move |buffer, _pad| {
+///     tx.send(buffer.pts()).unwrap();
+/// }).unwrap().forget();
+/// pipeline.add_and_link(src, sink.reference().into());
+/// ```
+pub struct FakeSink{
+    sink: Element
+}
+
+impl FakeSink{
+    pub fn new(name: &str) -> Option<FakeSink>{
+        Element::new("fakesink", name).map(FakeSink::new_from_element)
+    }
+
+    pub fn new_from_element(element: Element) -> FakeSink{
+        FakeSink{ sink: element }
+    }
+
+    /// Renders the buffers at their timestamps instead of as fast as
+    /// they arrive, false by default
+    pub fn set_sync(&mut self, sync: bool){
+        self.set("sync", sync as gboolean);
+    }
+
+    /// Posts EOS after num_buffers buffers, -1 for unlimited
+    pub fn set_num_buffers(&mut self, num_buffers: i32){
+        self.set("num-buffers", num_buffers);
+    }
+
+    /// Calls handler with every buffer rendered and the pad it arrived
+    /// on, from the streaming thread. The buffer is released once the
+    /// handler returns, keep a reference to it to inspect it later
+    pub fn connect_handoff<F>(&mut self, handler: F) -> Option<SignalHandle>
+        where F: FnMut(&Buffer, &Pad) + Send + 'static{
+        self.set("signal-handoffs", true as gboolean);
+        connect_handoff(&mut self.sink, "handoff", handler)
+    }
+
+    /// Calls handler with the buffer that prerolls the sink when it goes
+    /// to paused
+    pub fn connect_preroll_handoff<F>(&mut self, handler: F) -> Option<SignalHandle>
+        where F: FnMut(&Buffer, &Pad) + Send + 'static{
+        self.set("signal-handoffs", true as gboolean);
+        connect_handoff(&mut self.sink, "preroll-handoff", handler)
+    }
+}
+
+element_wrapper!(FakeSink, sink);
+
+fn connect_handoff<F>(element: &mut Element, signal: &str, mut handler: F) -> Option<SignalHandle>
+    where F: FnMut(&Buffer, &Pad) + Send + 'static{
+    element.connect_values(signal, move |args| {
+        if let (Some(buffer), Some(pad)) = (args[1].get::<Buffer>(), args[2].get::<Pad>()){
+            handler(&buffer, &pad);
+        }
+        None
+    })
+}
@@ -0,0 +1,271 @@
+use ffi::*;
+use element::Element;
+use reference::Reference;
+use util::*;
+use handle::ProbeHandle;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::ops::{Deref, DerefMut};
+
+/// Horizontal alignment of the rendered text, mirrors
+/// GstBaseTextOverlayHAlign
+#[repr(i32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum HAlign{
+    Left = 0,
+    Center = 1,
+    Right = 2,
+    Position = 4,
+    Absolute = 5,
+}
+
+/// Vertical alignment of the rendered text, mirrors
+/// GstBaseTextOverlayVAlign
+#[repr(i32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum VAlign{
+    Baseline = 0,
+    Bottom = 1,
+    Top = 2,
+    Position = 3,
+    Center = 4,
+    Absolute = 5,
+}
+
+type TextQueue = Arc<Mutex<VecDeque<(u64, String)>>>;
+
+// shared by the references to the same overlay so the probe is only
+// installed once and removed when the last of them is dropped
+type SharedProbe = Arc<Mutex<Option<ProbeHandle>>>;
+
+/// Wrapper for textoverlay and any of the elements derived from
+/// GstBaseTextOverlay like clockoverlay or timeoverlay.
+///
+/// Apart from the usual properties it allows to schedule text changes
+/// at a certain running time, the text is changed from a probe in the
+/// video sink pad when the first buffer with that running time or later
+/// goes through the element so captions stay in sync with the video.
+/// Keep a reference around after adding the overlay to a bin to be able
+/// to keep scheduling text, the probe and any pending text are released
+/// once the last TextOverlay for the element is dropped:
+///
+/// ```ignore
+/// let mut overlay = gst::TextOverlay::new("captions").unwrap();
+/// pipeline.add(overlay.reference());
+/// overlay.schedule_text(2_000_000_000, "Hello");
+/// ```
+pub struct TextOverlay{
+    overlay: Element,
+    scheduled: TextQueue,
+    probe: SharedProbe,
+}
+
+impl TextOverlay{
+    /// Creates a new textoverlay element with the given name
+    pub fn new(name: &str) -> Option<TextOverlay>{
+        Element::new("textoverlay", name).map(TextOverlay::new_from_element)
+    }
+
+    /// Creates a new clockoverlay element with the given name
+    pub fn new_clock_overlay(name: &str) -> Option<TextOverlay>{
+        Element::new("clockoverlay", name).map(TextOverlay::new_from_element)
+    }
+
+    /// Creates a new timeoverlay element with the given name
+    pub fn new_time_overlay(name: &str) -> Option<TextOverlay>{
+        Element::new("timeoverlay", name).map(TextOverlay::new_from_element)
+    }
+
+    /// Wraps an already existing GstBaseTextOverlay based element, for
+    /// example one retrieved by name from a pipeline
+    pub fn new_from_element(element: Element) -> TextOverlay{
+        TextOverlay{
+            overlay: element,
+            scheduled: Arc::new(Mutex::new(VecDeque::new())),
+            probe: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Sets the text to be displayed immediately
+    pub fn set_text(&mut self, text: &str){
+        self.set("text", text);
+    }
+
+    /// Pango font description of the font to be used for rendering,
+    /// eg: "Sans Bold 24"
+    pub fn set_font_desc(&mut self, font: &str){
+        self.set("font-desc", font);
+    }
+
+    pub fn set_halignment(&mut self, align: HAlign){
+        self.set("halignment", align as i32);
+    }
+
+    pub fn set_valignment(&mut self, align: VAlign){
+        self.set("valignment", align as i32);
+    }
+
+    /// Color to use for the text in big-endian ARGB
+    pub fn set_color(&mut self, argb: u32){
+        self.set("color", argb);
+    }
+
+    /// Shows or hides the overlay without having to clear the text
+    pub fn set_visible(&mut self, visible: bool){
+        self.set("silent", !visible as gboolean);
+    }
+
+    pub fn is_visible(&self) -> bool{
+        self.get::<gboolean>("silent") == 0
+    }
+
+    /// Draws a shaded background behind the text
+    pub fn set_shaded_background(&mut self, shaded: bool){
+        self.set("shaded-background", shaded as gboolean);
+    }
+
+    /// Only used by clockoverlay, format of the time in strftime syntax
+    pub fn set_time_format(&mut self, format: &str){
+        self.set("time-format", format);
+    }
+
+    /// Schedules text to be shown once the video reaches running_time
+    /// in nanoseconds.
+    ///
+    /// The first call installs a buffer probe on the "video_sink" pad,
+    /// returns false if the pad couldn't be found.
+    pub fn schedule_text(&mut self, running_time: u64, text: &str) -> bool{
+        if !self.install_probe(){
+            return false;
+        }
+        let mut scheduled = self.scheduled.lock().unwrap();
+        let pos = scheduled.iter().position(|&(t, _)| t > running_time).unwrap_or(scheduled.len());
+        scheduled.insert(pos, (running_time, text.to_string()));
+        true
+    }
+
+    /// Removes any pending text that hasn't been shown yet
+    pub fn clear_scheduled(&mut self){
+        self.scheduled.lock().unwrap().clear();
+    }
+
+    fn install_probe(&mut self) -> bool{
+        let shared = self.probe.clone();
+        let mut probe = shared.lock().unwrap();
+        if probe.is_some(){
+            return true;
+        }
+        let mut pad = match self.static_pad("video_sink"){
+            Some(pad) => pad,
+            None => return false
+        };
+        unsafe{
+            let data: *mut Guarded<TextQueue> = Box::into_raw(Box::new(Guarded::new(Box::new(self.scheduled.clone()))));
+            let id = gst_pad_add_probe(pad.gst_pad_mut(), GST_PAD_PROBE_TYPE_BUFFER, Some(text_probe), data as gpointer, Some(destroy_text_queue));
+            if id != 0{
+                *probe = Some(ProbeHandle::new(pad.gst_pad_mut(), id));
+            }
+            id != 0
+        }
+    }
+}
+
+extern "C" fn text_probe(pad: *mut GstPad, info: *mut GstPadProbeInfo, data: gpointer) -> GstPadProbeReturn{
+    unsafe{
+        let scheduled = &*(data as *const Guarded<TextQueue>);
+        scheduled.call_shared_with(|| gst_pad_get_parent_element(pad), GST_PAD_PROBE_OK,
+            |scheduled| text_probe_scheduled(pad, info, scheduled))
+    }
+}
+
+unsafe fn text_probe_scheduled(pad: *mut GstPad, info: *mut GstPadProbeInfo, scheduled: &TextQueue) -> GstPadProbeReturn{
+    let buffer = gst_pad_probe_info_get_buffer(info);
+    if buffer == ptr::null_mut() || (*buffer).pts == GST_CLOCK_TIME_NONE{
+        return GST_PAD_PROBE_OK;
+    }
+
+    let segment_event = gst_pad_get_sticky_event(pad, GST_EVENT_SEGMENT, 0);
+    let running_time = if segment_event != ptr::null_mut(){
+        let mut segment: *const GstSegment = ptr::null();
+        gst_event_parse_segment(segment_event, &mut segment);
+        let running_time = gst_segment_to_running_time(segment, GST_FORMAT_TIME, (*buffer).pts);
+        gst_mini_object_unref(segment_event as *mut GstMiniObject);
+        running_time
+    }else{
+        (*buffer).pts
+    };
+    // outside of the segment, NONE would compare as later than any text
+    if running_time == GST_CLOCK_TIME_NONE{
+        return GST_PAD_PROBE_OK;
+    }
+
+    let text = {
+        let mut scheduled = scheduled.lock().unwrap();
+        let mut text = None;
+        while scheduled.front().map(|&(t, _)| t <= running_time).unwrap_or(false){
+            text = scheduled.pop_front().map(|(_, text)| text);
+        }
+        text
+    };
+
+    if let Some(text) = text{
+        if let Some(mut overlay) = Element::new_from_gst_element(gst_pad_get_parent_element(pad)){
+            overlay.set("text", &text[..]);
+        }
+    }
+    GST_PAD_PROBE_OK
+}
+
+extern "C" fn destroy_text_queue(data: gpointer){
+    unsafe{
+        catch_panic(ptr::null_mut(), (), || drop(Box::from_raw(data as *mut Guarded<TextQueue>)));
+    }
+}
+
+impl Reference for TextOverlay{
+    fn reference(&self) -> TextOverlay{
+        TextOverlay{
+            overlay: self.overlay.reference(),
+            scheduled: self.scheduled.clone(),
+            probe: self.probe.clone(),
+        }
+    }
+}
+
+impl AsRef<Element> for TextOverlay{
+    fn as_ref(&self) -> &Element{
+        &self.overlay
+    }
+}
+
+impl AsMut<Element> for TextOverlay{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.overlay
+    }
+}
+
+impl From<TextOverlay> for Element{
+    fn from(t: TextOverlay) -> Element{
+        t.overlay
+    }
+}
+
+impl Deref for TextOverlay{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.overlay
+    }
+}
+
+impl DerefMut for TextOverlay{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.overlay
+    }
+}
+
+impl ::Transfer for TextOverlay{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.overlay.transfer()
+    }
+}
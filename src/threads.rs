@@ -0,0 +1,176 @@
+use ffi::*;
+use util::*;
+use bus::{Bus, BusSyncReply};
+use element::Element;
+use message::Message;
+use task::Task;
+use error::{Error, Result};
+
+#[cfg(target_os="linux")]
+use std::io;
+#[cfg(target_os="linux")]
+use std::os::raw::{c_int, c_ulong};
+
+/// The values of a StreamStatus message, posted by elements when the
+/// threads running their streaming loops are created, start or stop
+/// running the loop and are destroyed
+pub struct StreamStatus{
+    /// GST_STREAM_STATUS_TYPE_CREATE, ENTER, LEAVE, DESTROY...
+    pub status_type: GstStreamStatusType,
+    /// The element the thread belongs to
+    pub owner: Option<Element>,
+    /// The task running the streaming loop, if the thread is run by a
+    /// GstTask
+    pub task: Option<Task>,
+}
+
+impl StreamStatus{
+    pub unsafe fn new_from_gst_message(msg: *mut GstMessage) -> Option<StreamStatus>{
+        if (*msg)._type != GST_MESSAGE_STREAM_STATUS{
+            return None;
+        }
+        let mut status_type: GstStreamStatusType = 0;
+        let mut owner: *mut GstElement = ptr::null_mut();
+        gst_message_parse_stream_status(msg, &mut status_type, &mut owner);
+        let owner = if owner != ptr::null_mut(){
+            gst_object_ref(owner as gpointer);
+            Element::new_from_gst_element(owner)
+        }else{
+            None
+        };
+        let object = gst_message_get_stream_status_object(msg);
+        let task = if object != ptr::null() && g_type_check_value_holds(object as *mut GValue, gst_task_get_type()) != 0{
+            let task = g_value_get_object(object);
+            if task != ptr::null_mut(){
+                gst_object_ref(task);
+            }
+            Task::new_from_gst_task(task as *mut GstTask)
+        }else{
+            None
+        };
+        Some(StreamStatus{
+            status_type: status_type,
+            owner: owner,
+            task: task,
+        })
+    }
+
+    /// The thread started running the streaming loop, the message is
+    /// posted from the thread itself
+    pub fn is_enter(&self) -> bool{
+        self.status_type == GST_STREAM_STATUS_TYPE_ENTER
+    }
+
+    /// The thread stopped running the streaming loop, the message is
+    /// posted from the thread itself
+    pub fn is_leave(&self) -> bool{
+        self.status_type == GST_STREAM_STATUS_TYPE_LEAVE
+    }
+}
+
+/// Calls callback from every streaming thread of a pipeline when it
+/// starts and stops running its loop, so the priority or the CPU core
+/// of the thread can be changed from the thread itself:
+///
+/// ```ignore
+/// gst::threads::on_stream_threads(&mut pipeline.bus().unwrap(), |status: &gst::threads::StreamStatus| {
+///     let is_audio_sink = status.owner.as_ref().map(|owner| owner.name() == "audiosink").unwrap_or(false);
+///     if status.is_enter() && is_audio_sink{
+///         let _ = gst::threads::set_realtime_priority(50);
+///         let _ = gst::threads::set_affinity(&[3]);
+///     }
+/// });
+/// ```
+///
+/// The callback is called only with ENTER and LEAVE statuses. It's set
+/// as the sync handler of the bus, which replaces any other one, every
+/// message is still queued for the watches.
+pub fn on_stream_threads<F: FnMut(&StreamStatus) + Send + 'static>(bus: &mut Bus, mut callback: F){
+    bus.set_sync_handler(move |msg: &Message| {
+        if let Some(status) = msg.stream_status(){
+            if status.is_enter() || status.is_leave(){
+                callback(&status);
+            }
+        }
+        BusSyncReply::Pass
+    });
+}
+
+#[cfg(target_os="linux")]
+#[repr(C)]
+struct SchedParam{
+    sched_priority: c_int,
+}
+
+#[cfg(target_os="linux")]
+const SCHED_OTHER: c_int = 0;
+#[cfg(target_os="linux")]
+const SCHED_FIFO: c_int = 1;
+#[cfg(target_os="linux")]
+const PRIO_PROCESS: c_int = 0;
+// size of cpu_set_t in glibc, enough for 1024 cores
+#[cfg(target_os="linux")]
+const CPU_SET_WORDS: usize = 1024 / 64;
+
+#[cfg(target_os="linux")]
+extern "C" {
+    fn pthread_self() -> c_ulong;
+    fn pthread_setschedparam(thread: c_ulong, policy: c_int, param: *const SchedParam) -> c_int;
+    fn setpriority(which: c_int, who: c_int, prio: c_int) -> c_int;
+    fn sched_setaffinity(pid: c_int, cpusetsize: usize, mask: *const u64) -> c_int;
+}
+
+#[cfg(target_os="linux")]
+fn os_error(what: &str, err: io::Error) -> Error{
+    Error::new(0, 0, &format!("Couldn't set {} of the thread: {}", what, err))
+}
+
+/// Runs the calling thread with the SCHED_FIFO realtime policy and
+/// priority, from 1 to 99. Needs CAP_SYS_NICE or an rtprio limit that
+/// allows it
+#[cfg(target_os="linux")]
+pub fn set_realtime_priority(priority: i32) -> Result<()>{
+    let param = SchedParam{ sched_priority: priority };
+    let ret = unsafe{ pthread_setschedparam(pthread_self(), SCHED_FIFO, &param) };
+    if ret == 0{
+        Ok(())
+    }else{
+        Err(os_error("the realtime priority", io::Error::from_raw_os_error(ret)))
+    }
+}
+
+/// Sets the nice value of the calling thread, from -20, the highest
+/// priority, to 19. Also returns the thread to the normal scheduling
+/// policy if it was realtime
+#[cfg(target_os="linux")]
+pub fn set_nice(nice: i32) -> Result<()>{
+    let param = SchedParam{ sched_priority: 0 };
+    let ret = unsafe{ pthread_setschedparam(pthread_self(), SCHED_OTHER, &param) };
+    if ret != 0{
+        return Err(os_error("the scheduling policy", io::Error::from_raw_os_error(ret)));
+    }
+    // on linux setpriority with who 0 applies only to the calling thread
+    if unsafe{ setpriority(PRIO_PROCESS, 0, nice) } == 0{
+        Ok(())
+    }else{
+        Err(os_error("the nice value", io::Error::last_os_error()))
+    }
+}
+
+/// Restricts the calling thread to run in the passed cores
+#[cfg(target_os="linux")]
+pub fn set_affinity(cores: &[usize]) -> Result<()>{
+    let mut mask = [0u64; CPU_SET_WORDS];
+    for &core in cores{
+        if core >= CPU_SET_WORDS * 64{
+            return Err(Error::new(0, 0, &format!("Core {} out of range", core)));
+        }
+        mask[core / 64] |= 1 << (core % 64);
+    }
+    let ret = unsafe{ sched_setaffinity(0, mem::size_of_val(&mask), mask.as_ptr()) };
+    if ret == 0{
+        Ok(())
+    }else{
+        Err(os_error("the affinity", io::Error::last_os_error()))
+    }
+}
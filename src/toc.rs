@@ -0,0 +1,492 @@
+use ffi::*;
+use util::*;
+use reference::Reference;
+use miniobject::MiniObject;
+use taglist::TagList;
+use element::Element;
+use version::ApiAvailability;
+use ::Transfer;
+
+use std::ops::{Deref, DerefMut};
+
+const TOC_LOOP: ApiAvailability = ApiAvailability::new("TocEntry loop", 1, 4, 0);
+
+/// Table of contents of a stream: the chapters of a matroska file or
+/// the tracks of a CD or cue sheet. Demuxers post it on the bus in a
+/// Toc message
+#[derive(Clone)]
+pub struct Toc{
+    toc: MiniObject<GstToc>
+}
+
+impl Toc{
+    pub unsafe fn new_from_gst_toc(toc: *mut GstToc) -> Option<Toc>{
+        MiniObject::new(toc)
+            .map(|miniobject| Toc{ toc: miniobject })
+    }
+
+    /// Creates an empty toc to fill with entries, eg: to write the
+    /// chapters of a file with a muxer:
+    ///
+    /// ```ignore
+    /// let mut toc = gst::Toc::new(GST_TOC_SCOPE_GLOBAL);
+    /// let mut edition = gst::TocEntry::new(GST_TOC_ENTRY_TYPE_EDITION, "edition");
+    /// let mut chapter = gst::TocEntry::new(GST_TOC_ENTRY_TYPE_CHAPTER, "chapter.1");
+    /// chapter.set_start_stop(Some(0), Some(60_000_000_000));
+    /// chapter.set_title("Introduction");
+    /// edition.append_sub_entry(chapter);
+    /// toc.append_entry(edition);
+    /// gst::TocSetter::new(&mux).unwrap().set_toc(&toc);
+    /// ```
+    pub fn new(scope: GstTocScope) -> Toc{
+        unsafe{
+            Toc::new_from_gst_toc(gst_toc_new(scope)).unwrap()
+        }
+    }
+
+    /// Copies the toc of a toc event, eg: received in an event probe,
+    /// with whether it's an update of a previous one. None if it's not a
+    /// toc event
+    pub unsafe fn from_event(event: *mut GstEvent) -> Option<(Toc, bool)>{
+        if event == ptr::null_mut() || (*event)._type != GST_EVENT_TOC{
+            return None;
+        }
+        let mut toc: *mut GstToc = ptr::null_mut();
+        let mut updated: gboolean = 0;
+        gst_event_parse_toc(event, &mut toc, &mut updated);
+        Toc::new_from_gst_toc(toc).map(|toc| (toc, updated != 0))
+    }
+
+    pub fn scope(&self) -> GstTocScope{
+        unsafe{
+            gst_toc_get_scope(self.gst_toc())
+        }
+    }
+
+    /// Top level entries, usually editions or titles containing the
+    /// chapters
+    pub fn entries(&self) -> Vec<TocEntry>{
+        unsafe{
+            entries_from_list(gst_toc_get_entries(self.gst_toc()))
+        }
+    }
+
+    pub fn find_entry(&self, uid: &str) -> Option<TocEntry>{
+        let cuid = CString::new(uid).unwrap();
+        unsafe{
+            let entry = gst_toc_find_entry(self.gst_toc(), cuid.as_ptr());
+            if entry != ptr::null_mut(){
+                gst_mini_object_ref(entry as *mut GstMiniObject);
+            }
+            TocEntry::new_from_gst_toc_entry(entry)
+        }
+    }
+
+    /// The innermost chapters and tracks with a start time, sorted by
+    /// start time, which is what players navigate through
+    pub fn chapters(&self) -> Vec<TocEntry>{
+        fn collect(entries: Vec<TocEntry>, chapters: &mut Vec<TocEntry>){
+            for entry in entries{
+                let sub_entries = entry.sub_entries();
+                if !sub_entries.is_empty(){
+                    collect(sub_entries, chapters);
+                }else if (entry.entry_type() == GST_TOC_ENTRY_TYPE_CHAPTER || entry.entry_type() == GST_TOC_ENTRY_TYPE_TRACK)
+                        && entry.start().is_some(){
+                    chapters.push(entry);
+                }
+            }
+        }
+        let mut chapters = vec![];
+        collect(self.entries(), &mut chapters);
+        chapters.sort_by_key(|chapter| chapter.start());
+        chapters
+    }
+
+    pub fn tags(&self) -> Option<TagList>{
+        unsafe{
+            let tags = gst_toc_get_tags(self.gst_toc());
+            if tags != ptr::null_mut(){
+                gst_mini_object_ref(tags as *mut GstMiniObject);
+            }
+            TagList::new_from_gst_taglist(tags)
+        }
+    }
+
+    /// Replaces the tags of the toc, the toc has to be writable
+    pub fn set_tags(&mut self, tags: &TagList){
+        unsafe{
+            // set_tags takes ownership of the passed reference
+            gst_mini_object_ref(tags.gst_taglist() as *mut GstMiniObject);
+            gst_toc_set_tags(self.gst_toc_mut(), tags.gst_taglist() as *mut GstTagList);
+        }
+    }
+
+    /// Adds a top level entry, the toc has to be writable
+    pub fn append_entry(&mut self, entry: TocEntry){
+        unsafe{
+            gst_toc_append_entry(self.gst_toc_mut(), entry.transfer());
+        }
+    }
+
+    pub fn is_writable(&self) -> bool{
+        unsafe{
+            gst_mini_object_is_writable(self.toc.gst_miniobject()) != 0
+        }
+    }
+
+    pub fn make_writable(self) -> Toc{
+        Toc{ toc: self.toc.make_writable() }
+    }
+
+    /// A toc event with this toc, to send downstream, eg: from a source
+    /// or a parser that knows the chapters of the stream
+    pub fn event(&self, updated: bool) -> *mut GstEvent{
+        unsafe{
+            gst_event_new_toc(self.gst_toc() as *mut GstToc, updated as gboolean)
+        }
+    }
+
+    pub unsafe fn gst_toc(&self) -> *const GstToc{
+        self.toc.as_ptr()
+    }
+
+    pub unsafe fn gst_toc_mut(&mut self) -> *mut GstToc{
+        self.toc.as_mut_ptr()
+    }
+}
+
+impl ::Transfer<GstToc> for Toc{
+    unsafe fn transfer(self) -> *mut GstToc{
+        self.toc.transfer()
+    }
+}
+
+impl Reference for Toc{
+    fn reference(&self) -> Toc{
+        Toc{ toc: self.toc.reference() }
+    }
+}
+
+impl AsRef<MiniObject> for Toc{
+    fn as_ref(&self) -> &MiniObject{
+        self.toc.upcast_ref()
+    }
+}
+
+impl AsMut<MiniObject> for Toc{
+    fn as_mut(&mut self) -> &mut MiniObject{
+        self.toc.upcast_mut()
+    }
+}
+
+impl Deref for Toc{
+    type Target = MiniObject;
+    fn deref(&self) -> &MiniObject{
+        self.toc.upcast_ref()
+    }
+}
+
+impl DerefMut for Toc{
+    fn deref_mut(&mut self) -> &mut MiniObject{
+        self.toc.upcast_mut()
+    }
+}
+
+/// An edition, chapter, track... of a Toc, identified by an uid unique
+/// in the Toc
+#[derive(Clone)]
+pub struct TocEntry{
+    entry: MiniObject<GstTocEntry>
+}
+
+impl TocEntry{
+    pub unsafe fn new_from_gst_toc_entry(entry: *mut GstTocEntry) -> Option<TocEntry>{
+        MiniObject::new(entry)
+            .map(|miniobject| TocEntry{ entry: miniobject })
+    }
+
+    /// Creates an entry with an uid that has to be unique in the toc
+    /// it's added to
+    pub fn new(entry_type: GstTocEntryType, uid: &str) -> TocEntry{
+        let cuid = CString::new(uid).unwrap();
+        unsafe{
+            TocEntry::new_from_gst_toc_entry(gst_toc_entry_new(entry_type, cuid.as_ptr())).unwrap()
+        }
+    }
+
+    pub fn uid(&self) -> &str{
+        unsafe{
+            from_c_str!(gst_toc_entry_get_uid(self.gst_toc_entry()))
+        }
+    }
+
+    pub fn entry_type(&self) -> GstTocEntryType{
+        unsafe{
+            gst_toc_entry_get_entry_type(self.gst_toc_entry())
+        }
+    }
+
+    /// Start time of the entry in nanoseconds, if known
+    pub fn start(&self) -> Option<u64>{
+        self.start_stop().0
+    }
+
+    /// Stop time of the entry in nanoseconds, if known
+    pub fn stop(&self) -> Option<u64>{
+        self.start_stop().1
+    }
+
+    fn start_stop(&self) -> (Option<u64>, Option<u64>){
+        let mut start: gint64 = -1;
+        let mut stop: gint64 = -1;
+        unsafe{
+            gst_toc_entry_get_start_stop_times(self.gst_toc_entry(), &mut start, &mut stop);
+        }
+        let time = |t: gint64| if t >= 0 { Some(t as u64) } else { None };
+        (time(start), time(stop))
+    }
+
+    /// Sets the start and stop times in nanoseconds, None if unknown.
+    /// The entry has to be writable
+    pub fn set_start_stop(&mut self, start: Option<u64>, stop: Option<u64>){
+        let time = |t: Option<u64>| t.map(|t| t as gint64).unwrap_or(-1);
+        unsafe{
+            gst_toc_entry_set_start_stop_times(self.gst_toc_entry_mut(), time(start), time(stop));
+        }
+    }
+
+    /// Whether the sub entries are alternatives, eg: the editions or
+    /// angles of a title, only one of them being played
+    pub fn is_alternative(&self) -> bool{
+        unsafe{
+            gst_toc_entry_is_alternative(self.gst_toc_entry()) != 0
+        }
+    }
+
+    /// Whether the sub entries are played one after the other, eg: the
+    /// chapters of an edition
+    pub fn is_sequence(&self) -> bool{
+        unsafe{
+            gst_toc_entry_is_sequence(self.gst_toc_entry()) != 0
+        }
+    }
+
+    /// The loop type and repeat count of the entry, if set. Needs
+    /// GStreamer 1.4, returns None with older versions
+    pub fn get_loop(&self) -> Option<(GstTocLoopType, i32)>{
+        let mut loop_type = GST_TOC_LOOP_NONE;
+        let mut repeat_count = 0;
+        unsafe{
+            let get_loop: unsafe extern "C" fn(*const GstTocEntry, *mut GstTocLoopType, *mut gint) -> gboolean
+                = TOC_LOOP.function("gst_toc_entry_get_loop").ok()?;
+            if get_loop(self.gst_toc_entry(), &mut loop_type, &mut repeat_count) != 0{
+                Some((loop_type, repeat_count))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// repeat_count -1 repeats forever. The entry has to be writable.
+    /// Needs GStreamer 1.4, with older versions the loop isn't set and
+    /// this returns false
+    pub fn set_loop(&mut self, loop_type: GstTocLoopType, repeat_count: i32) -> bool{
+        unsafe{
+            let set_loop: unsafe extern "C" fn(*mut GstTocEntry, GstTocLoopType, gint) = match TOC_LOOP.function("gst_toc_entry_set_loop"){
+                Ok(set_loop) => set_loop,
+                Err(_) => return false,
+            };
+            set_loop(self.gst_toc_entry_mut(), loop_type, repeat_count);
+            true
+        }
+    }
+
+    /// The entry containing this one, None for top level entries
+    pub fn parent(&self) -> Option<TocEntry>{
+        unsafe{
+            let parent = gst_toc_entry_get_parent(self.gst_toc_entry() as *mut GstTocEntry);
+            if parent != ptr::null_mut(){
+                gst_mini_object_ref(parent as *mut GstMiniObject);
+            }
+            TocEntry::new_from_gst_toc_entry(parent)
+        }
+    }
+
+    /// Adds a sub entry, eg: a chapter to an edition. The entry has to
+    /// be writable
+    pub fn append_sub_entry(&mut self, entry: TocEntry){
+        unsafe{
+            gst_toc_entry_append_sub_entry(self.gst_toc_entry_mut(), entry.transfer());
+        }
+    }
+
+    pub fn sub_entries(&self) -> Vec<TocEntry>{
+        unsafe{
+            entries_from_list(gst_toc_entry_get_sub_entries(self.gst_toc_entry()))
+        }
+    }
+
+    pub fn tags(&self) -> Option<TagList>{
+        unsafe{
+            let tags = gst_toc_entry_get_tags(self.gst_toc_entry());
+            if tags != ptr::null_mut(){
+                gst_mini_object_ref(tags as *mut GstMiniObject);
+            }
+            TagList::new_from_gst_taglist(tags)
+        }
+    }
+
+    /// Replaces the tags of the entry, the entry has to be writable
+    pub fn set_tags(&mut self, tags: &TagList){
+        unsafe{
+            // set_tags takes ownership of the passed reference
+            gst_mini_object_ref(tags.gst_taglist() as *mut GstMiniObject);
+            gst_toc_entry_set_tags(self.gst_toc_entry_mut(), tags.gst_taglist() as *mut GstTagList);
+        }
+    }
+
+    /// The title tag of the entry, the chapter name in most formats
+    pub fn title(&self) -> Option<String>{
+        self.tags().and_then(|tags| tags.get::<String>("title"))
+    }
+
+    /// Sets the title tag keeping the other tags of the entry, which has
+    /// to be writable
+    pub fn set_title(&mut self, title: &str){
+        let mut tags = self.tags().map(|tags| tags.make_writable()).unwrap_or_else(TagList::new);
+        tags.set("title", title);
+        self.set_tags(&tags);
+    }
+
+    pub fn is_writable(&self) -> bool{
+        unsafe{
+            gst_mini_object_is_writable(self.entry.gst_miniobject()) != 0
+        }
+    }
+
+    pub fn make_writable(self) -> TocEntry{
+        TocEntry{ entry: self.entry.make_writable() }
+    }
+
+    pub unsafe fn gst_toc_entry(&self) -> *const GstTocEntry{
+        self.entry.as_ptr()
+    }
+
+    pub unsafe fn gst_toc_entry_mut(&mut self) -> *mut GstTocEntry{
+        self.entry.as_mut_ptr()
+    }
+}
+
+impl ::Transfer<GstTocEntry> for TocEntry{
+    unsafe fn transfer(self) -> *mut GstTocEntry{
+        self.entry.transfer()
+    }
+}
+
+impl Reference for TocEntry{
+    fn reference(&self) -> TocEntry{
+        TocEntry{ entry: self.entry.reference() }
+    }
+}
+
+impl AsRef<MiniObject> for TocEntry{
+    fn as_ref(&self) -> &MiniObject{
+        self.entry.upcast_ref()
+    }
+}
+
+impl AsMut<MiniObject> for TocEntry{
+    fn as_mut(&mut self) -> &mut MiniObject{
+        self.entry.upcast_mut()
+    }
+}
+
+impl Deref for TocEntry{
+    type Target = MiniObject;
+    fn deref(&self) -> &MiniObject{
+        self.entry.upcast_ref()
+    }
+}
+
+impl DerefMut for TocEntry{
+    fn deref_mut(&mut self) -> &mut MiniObject{
+        self.entry.upcast_mut()
+    }
+}
+
+/// Access to the GstTocSetter interface implemented by muxers like
+/// matroskamux or mp4mux, to write chapters into the files they create
+pub struct TocSetter{
+    element: Element
+}
+
+impl TocSetter{
+    /// Returns the toc setter interface of element. If the element is a
+    /// bin the first child implementing the interface is returned
+    pub fn new(element: &Element) -> Option<TocSetter>{
+        unsafe{
+            let instance = element.gst_element() as *mut GTypeInstance;
+            if g_type_check_instance_is_a(instance, gst_toc_setter_get_type()) != 0{
+                Some(TocSetter{ element: element.reference() })
+            }else if g_type_check_instance_is_a(instance, gst_bin_get_type()) != 0{
+                let element = gst_bin_get_by_interface(element.gst_element() as *mut GstBin, gst_toc_setter_get_type());
+                Element::new_from_gst_element(element).map(|element| TocSetter{ element: element })
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Sets the toc to write, it has to be set before the muxer starts
+    /// writing the file
+    pub fn set_toc(&mut self, toc: &Toc){
+        unsafe{
+            gst_toc_setter_set_toc(self.gst_toc_setter_mut(), toc.gst_toc() as *mut GstToc);
+        }
+    }
+
+    pub fn toc(&self) -> Option<Toc>{
+        unsafe{
+            Toc::new_from_gst_toc(gst_toc_setter_get_toc(self.gst_toc_setter() as *mut GstTocSetter))
+        }
+    }
+
+    /// Removes the toc set
+    pub fn reset(&mut self){
+        unsafe{
+            gst_toc_setter_reset(self.gst_toc_setter_mut());
+        }
+    }
+
+    pub fn element(&self) -> &Element{
+        &self.element
+    }
+
+    pub unsafe fn gst_toc_setter(&self) -> *const GstTocSetter{
+        self.element.gst_element() as *const GstTocSetter
+    }
+
+    pub unsafe fn gst_toc_setter_mut(&mut self) -> *mut GstTocSetter{
+        self.element.gst_element_mut() as *mut GstTocSetter
+    }
+}
+
+impl Reference for TocSetter{
+    fn reference(&self) -> TocSetter{
+        TocSetter{ element: self.element.reference() }
+    }
+}
+
+/// The lists are owned by the toc or the parent entry, each entry is
+/// reffed
+unsafe fn entries_from_list(mut node: *mut GList) -> Vec<TocEntry>{
+    let mut entries = vec![];
+    while node != ptr::null_mut(){
+        gst_mini_object_ref((*node).data as *mut GstMiniObject);
+        if let Some(entry) = TocEntry::new_from_gst_toc_entry((*node).data as *mut GstTocEntry){
+            entries.push(entry);
+        }
+        node = (*node).next;
+    }
+    entries
+}
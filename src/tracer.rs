@@ -0,0 +1,580 @@
+use ffi::*;
+use util::*;
+use bin::Bin;
+use buffer::Buffer;
+use buffer_list::BufferList;
+use element::Element;
+use message::Message;
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use structure::Structure;
+use object::Object;
+use panic::catch_panic;
+use registration::register_type;
+use version::ApiAvailability;
+use debug::{LogFunctionId, DebugLevel, debug_add_log_function, debug_remove_log_function, debug_set_threshold_from_string};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+const ACTIVE_TRACERS: ApiAvailability = ApiAvailability::new("LeakTracker", 1, 18, 0);
+const TRACER_HOOKS: ApiAvailability = ApiAvailability::new("TracerHooks", 1, 8, 0);
+
+/// Selects the tracers GStreamer loads, same as the GST_TRACERS
+/// environment variable, eg: "rusage" or "latency(flags=element)". Has
+/// to be called before init, tracers are only loaded then
+pub fn set_tracers(tracers: &str){
+    let cname = CString::new("GST_TRACERS").unwrap();
+    let ctracers = CString::new(tracers).unwrap();
+    unsafe{
+        g_setenv(cname.as_ptr(), ctracers.as_ptr(), 1);
+    }
+}
+
+/// Calls f with every record logged by the active tracers, like the
+/// thread-rusage records of the rusage tracer. Tracers log through the
+/// GST_TRACER debug category, whose threshold is raised so the records
+/// aren't filtered out.
+pub fn add_record_function<F: Fn(&Structure) + Send + Sync + 'static>(f: F) -> LogFunctionId{
+    debug_set_threshold_from_string("GST_TRACER:7", false);
+    debug_add_log_function(move |record| {
+        if record.level == DebugLevel::Trace && record.category.name() == "GST_TRACER"{
+            if let Some(structure) = Structure::from_string(record.message){
+                f(&structure);
+            }
+        }
+    })
+}
+
+/// A record logged by the latency tracer, enabled with
+/// set_tracers("latency(flags=pipeline+element+reported)"). Times are
+/// in nanoseconds, ts is when the record was logged
+#[derive(Clone,Debug,PartialEq)]
+pub enum LatencyRecord{
+    /// Time a buffer took from the src pad of a source to the sink pad
+    /// of a sink
+    Pipeline{
+        src_element: String,
+        src: String,
+        sink_element: String,
+        sink: String,
+        time: u64,
+        ts: u64,
+    },
+    /// Time a buffer spent inside an element, from its sink pad to src
+    Element{
+        element: String,
+        src: String,
+        time: u64,
+        ts: u64,
+    },
+    /// Latency an element reported when answering a latency query
+    Reported{
+        element: String,
+        live: bool,
+        min: u64,
+        max: u64,
+        ts: u64,
+    },
+}
+
+impl LatencyRecord{
+    /// Parses a record of the latency tracer, None for records of other
+    /// tracers
+    pub fn from_structure(record: &Structure) -> Option<LatencyRecord>{
+        let string = |name: &str| record.get_string(name).map(|value| value.to_string());
+        let ts = record.get::<u64>("ts").unwrap_or(0);
+        match record.name(){
+            "latency" => Some(LatencyRecord::Pipeline{
+                src_element: string("src-element")?,
+                src: string("src")?,
+                sink_element: string("sink-element")?,
+                sink: string("sink")?,
+                time: record.get::<u64>("time")?,
+                ts: ts,
+            }),
+            "element-latency" => Some(LatencyRecord::Element{
+                element: string("element")?,
+                src: string("src")?,
+                time: record.get::<u64>("time")?,
+                ts: ts,
+            }),
+            "element-reported-latency" => Some(LatencyRecord::Reported{
+                element: string("element")?,
+                live: record.get::<bool>("live").unwrap_or(false),
+                min: record.get::<u64>("min")?,
+                max: record.get::<u64>("max").unwrap_or(GST_CLOCK_TIME_NONE),
+                ts: ts,
+            }),
+            _ => None
+        }
+    }
+}
+
+/// Calls f with every record logged by the latency tracer, eg: to
+/// feed a latency analyzer without parsing the GST_DEBUG output
+pub fn add_latency_function<F: Fn(&LatencyRecord) + Send + Sync + 'static>(f: F) -> LogFunctionId{
+    add_record_function(move |record| {
+        if let Some(latency) = LatencyRecord::from_structure(record){
+            f(&latency);
+        }
+    })
+}
+
+/// CPU load of a thread as reported by the rusage tracer
+#[derive(Clone,Debug)]
+pub struct ThreadCpuUsage{
+    pub thread_id: u64,
+    /// Element whose streaming task runs in the thread, if known
+    pub element: Option<String>,
+    /// Load since the thread started, in percent of one core
+    pub average_load: f64,
+    /// Load over the last measuring window, in percent of one core
+    pub current_load: f64,
+    /// CPU time used by the thread in nanoseconds
+    pub time: u64,
+}
+
+/// CPU load of the whole process as reported by the rusage tracer
+#[derive(Clone,Debug)]
+pub struct ProcessCpuUsage{
+    /// Load since the process started, in percent of all the cores
+    pub average_load: f64,
+    /// Load over the last measuring window, in percent of all the cores
+    pub current_load: f64,
+    /// CPU time used by the process in nanoseconds
+    pub time: u64,
+}
+
+#[derive(Default)]
+struct CpuState{
+    threads: HashMap<u64, ThreadCpuUsage>,
+    elements: HashMap<u64, String>,
+    process: Option<ProcessCpuUsage>,
+}
+
+impl CpuState{
+    fn update(&mut self, record: &Structure){
+        // the tracer reports loads in per mille
+        let average_load = record.get::<u32>("average-cpuload").unwrap_or(0) as f64 / 10.;
+        let current_load = record.get::<u32>("current-cpuload").unwrap_or(0) as f64 / 10.;
+        let time = record.get::<u64>("time").unwrap_or(0);
+        match record.name(){
+            "thread-rusage" => {
+                if let Some(thread_id) = record.get::<u64>("thread-id"){
+                    let element = self.elements.get(&thread_id).cloned();
+                    self.threads.insert(thread_id, ThreadCpuUsage{
+                        thread_id: thread_id,
+                        element: element,
+                        average_load: average_load,
+                        current_load: current_load,
+                        time: time,
+                    });
+                }
+            }
+            "proc-rusage" => {
+                self.process = Some(ProcessCpuUsage{
+                    average_load: average_load,
+                    current_load: current_load,
+                    time: time,
+                });
+            }
+            _ => ()
+        }
+    }
+}
+
+/// Collects the records of the rusage tracer into per thread and per
+/// process CPU metrics. Streaming threads are attributed to the element
+/// running their task, like a source or a queue, so the load of each
+/// branch of a pipeline can be told apart:
+///
+/// ```ignore
+/// gst::tracer::set_tracers("rusage");
+/// gst::init();
+/// let monitor = gst::tracer::CpuUsageMonitor::new();
+/// pipeline.play();
+/// monitor.attach(&pipeline);
+/// // later
+/// for usage in monitor.elements(){
+///     println!("{}: {:.1}%", usage.element.unwrap(), usage.current_load);
+/// }
+/// ```
+pub struct CpuUsageMonitor{
+    state: Arc<Mutex<CpuState>>,
+    log_function: Option<LogFunctionId>,
+}
+
+impl CpuUsageMonitor{
+    /// Starts collecting records, set_tracers("rusage") has to be
+    /// called before init for there to be any
+    pub fn new() -> CpuUsageMonitor{
+        let state = Arc::new(Mutex::new(CpuState::default()));
+        let records = state.clone();
+        let log_function = add_record_function(move |record| {
+            records.lock().unwrap_or_else(|e| e.into_inner()).update(record);
+        });
+        CpuUsageMonitor{
+            state: state,
+            log_function: Some(log_function),
+        }
+    }
+
+    /// Finds out which elements own the streaming threads of bin. Tasks
+    /// are only started once the pipeline is at least PAUSED, attach
+    /// again when elements are added to a running pipeline
+    pub fn attach(&self, bin: &Bin){
+        for element in bin.iter_recurse().filter_map(|element| element.ok()){
+            for mut pad in element.src_pads().into_iter().chain(element.sink_pads()){
+                let state = self.state.clone();
+                // the probe removes itself from the first buffer
+                pad.add_probe(GST_PAD_PROBE_TYPE_BUFFER | GST_PAD_PROBE_TYPE_BUFFER_LIST, move |pad: &mut Pad, _info: &mut PadProbeInfo| {
+                    unsafe{
+                        let gst_pad = pad.gst_pad() as *mut GstPad;
+                        if (*gst_pad).task == ptr::null_mut(){
+                            return PadProbeReturn::Remove;
+                        }
+                        let element = gst_pad_get_parent_element(gst_pad);
+                        if element != ptr::null_mut(){
+                            let name = gst_object_get_name(element as *mut GstObject);
+                            let thread_id = g_thread_self() as usize as u64;
+                            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+                            state.elements.insert(thread_id, from_c_str!(name).to_string());
+                            g_free(name as gpointer);
+                            gst_object_unref(element as gpointer);
+                        }
+                    }
+                    PadProbeReturn::Remove
+                }).map(|probe| probe.forget());
+            }
+        }
+    }
+
+    /// Load of the whole process, None until the first record arrives
+    pub fn process(&self) -> Option<ProcessCpuUsage>{
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).process.clone()
+    }
+
+    /// Load of every thread that has been measured
+    pub fn threads(&self) -> Vec<ThreadCpuUsage>{
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.threads.values().map(|usage| {
+            let mut usage = usage.clone();
+            usage.element = state.elements.get(&usage.thread_id).cloned();
+            usage
+        }).collect()
+    }
+
+    /// Load of the threads whose element is known, sorted from the most
+    /// to the least loaded
+    pub fn elements(&self) -> Vec<ThreadCpuUsage>{
+        let mut usages: Vec<ThreadCpuUsage> = self.threads().into_iter().filter(|usage| usage.element.is_some()).collect();
+        usages.sort_by(|a, b| b.current_load.partial_cmp(&a.current_load).unwrap_or(::std::cmp::Ordering::Equal));
+        usages
+    }
+}
+
+impl Drop for CpuUsageMonitor{
+    fn drop(&mut self){
+        if let Some(log_function) = self.log_function.take(){
+            debug_remove_log_function(log_function);
+        }
+    }
+}
+
+/// An object created while a LeakTracker was tracking that was still
+/// alive at the checkpoint
+#[derive(Clone,Debug,PartialEq)]
+pub struct LeakedObject{
+    /// eg: "GstBuffer" or "GstPipeline"
+    pub type_name: String,
+    pub address: String,
+}
+
+/// Tracks the objects and mini objects created from a point on using the
+/// leaks tracer, so tests can check that every pipeline, element, buffer...
+/// created while running them was released, whether the reference was
+/// held by the test or leaked by the bindings:
+///
+/// ```ignore
+/// gst::tracer::set_tracers("leaks");
+/// gst::init();
+/// let mut tracker = gst::tracer::LeakTracker::start().unwrap();
+/// {
+///     let mut pipeline = gst::Pipeline::new_from_str("videotestsrc num-buffers=10 ! fakesink").unwrap();
+///     pipeline.play();
+///     // ...
+///     pipeline.set_null_state();
+/// }
+/// tracker.assert_released();
+/// ```
+///
+/// Objects GStreamer keeps cached on purpose, like the plugin features
+/// loaded the first time an element is made, show up too so it's better
+/// to create a first instance of what the test uses before starting.
+pub struct LeakTracker{
+    tracer: Object,
+}
+
+impl LeakTracker{
+    /// Starts tracking, None if the leaks tracer isn't active:
+    /// set_tracers("leaks") has to be called before init. Needs
+    /// GStreamer 1.18, always None with older versions
+    pub fn start() -> Option<LeakTracker>{
+        let mut tracer = leaks_tracer()?;
+        tracer.emit("activity-start-tracking");
+        Some(LeakTracker{ tracer: tracer })
+    }
+
+    /// Objects created since the tracking started or the last
+    /// checkpoint that are still alive
+    pub fn checkpoint(&mut self) -> Vec<LeakedObject>{
+        self.tracer.emit_with_values("activity-get-checkpoint", &[])
+            .and_then(|value| value.get::<Structure>())
+            .map(|checkpoint| checkpoint.get_structure_list("objects-created-list").iter().map(|object| {
+                LeakedObject{
+                    type_name: object.get_string("type-name").unwrap_or("").to_string(),
+                    address: object.get_string("address").unwrap_or("").to_string(),
+                }
+            }).collect())
+            .unwrap_or(vec![])
+    }
+
+    /// Panics listing the objects created since the tracking started or
+    /// the last checkpoint that are still alive
+    pub fn assert_released(&mut self){
+        let leaked = self.checkpoint();
+        if !leaked.is_empty(){
+            let objects: Vec<String> = leaked.iter().map(|object| format!("{} ({})", object.type_name, object.address)).collect();
+            panic!("{} objects weren't released: {}", leaked.len(), objects.join(", "));
+        }
+    }
+}
+
+impl Drop for LeakTracker{
+    fn drop(&mut self){
+        self.tracer.emit("activity-stop-tracking");
+    }
+}
+
+fn leaks_tracer() -> Option<Object>{
+    unsafe{
+        let active_tracers: unsafe extern "C" fn() -> *mut GList = ACTIVE_TRACERS.function("gst_tracing_get_active_tracers").ok()?;
+        let tracers = active_tracers();
+        let mut found = None;
+        let mut item = tracers;
+        while item != ptr::null_mut(){
+            let gtype = (*(*((*item).data as *mut GTypeInstance)).g_class).g_type;
+            let tracer = Object::new((*item).data as *mut GstObject);
+            if found.is_none() && from_c_str!(g_type_name(gtype)) == "GstLeaksTracer"{
+                found = tracer;
+            }
+            item = (*item).next;
+        }
+        // the list holds a reference to each tracer, taken by the wrappers
+        g_list_free(tracers);
+        found
+    }
+}
+
+/// The points of the data flow a TracerHooks implementation is called
+/// from
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum Hook{
+    PadPushPre,
+    PadPushPost,
+    PadPushListPre,
+    PadPushEventPre,
+    ElementPostMessagePre,
+}
+
+impl Hook{
+    /// Name of the hook in GStreamer, eg: "pad-push-pre"
+    pub fn detail(self) -> &'static str{
+        match self{
+            Hook::PadPushPre => "pad-push-pre",
+            Hook::PadPushPost => "pad-push-post",
+            Hook::PadPushListPre => "pad-push-list-pre",
+            Hook::PadPushEventPre => "pad-push-event-pre",
+            Hook::ElementPostMessagePre => "element-post-message-pre",
+        }
+    }
+}
+
+/// A tracer implemented in rust. Its methods are called from every
+/// thread that pushes data or posts messages, at the same time, so
+/// they have to be fast and take care of their own locking. ts is the
+/// time since GStreamer was initialized in nanoseconds.
+///
+/// ```ignore
+/// struct BufferCounter(AtomicUsize);
+///
+/// impl gst::tracer::TracerHooks for BufferCounter{
+///     fn pad_push_pre(&self, _ts: u64, _pad: &gst::Pad, _buffer: &gst::Buffer){
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// gst::tracer::add_tracer(&[gst::tracer::Hook::PadPushPre], BufferCounter(AtomicUsize::new(0)));
+/// ```
+pub trait TracerHooks: Send + Sync + 'static{
+    /// Before buffer is pushed from pad
+    fn pad_push_pre(&self, _ts: u64, _pad: &Pad, _buffer: &Buffer){}
+
+    /// After a push from pad returned ret
+    fn pad_push_post(&self, _ts: u64, _pad: &Pad, _ret: GstFlowReturn){}
+
+    /// Before list is pushed from pad
+    fn pad_push_list_pre(&self, _ts: u64, _pad: &Pad, _list: &BufferList){}
+
+    /// Before event is pushed from pad, the event is borrowed
+    fn pad_push_event_pre(&self, _ts: u64, _pad: &Pad, _event: *mut GstEvent){}
+
+    /// Before element posts message on the bus
+    fn element_post_message_pre(&self, _ts: u64, _element: &Element, _message: &Message){}
+}
+
+/// Installs tracer for the rest of the process, calling it from the
+/// hooks listed. Only the hooks listed are registered so the ones not
+/// implemented don't slow down the data flow. Has to be called after
+/// init, GST_TRACERS doesn't need to be set. Needs GStreamer 1.8,
+/// returns false with older versions
+pub fn add_tracer<T: TracerHooks>(hooks: &[Hook], tracer: T) -> bool{
+    unsafe{
+        let register_hook: unsafe extern "C" fn(*mut GstTracer, *const gchar, GCallback) = match TRACER_HOOKS.function("gst_tracing_register_hook"){
+            Ok(register_hook) => register_hook,
+            Err(_) => return false,
+        };
+        let gtype = rust_tracer_type();
+        if gtype == 0{
+            return false;
+        }
+        let instance = g_object_new(gtype, ptr::null::<gchar>()) as *mut RustTracer;
+        if instance == ptr::null_mut(){
+            return false;
+        }
+        // the tracing hooks don't hold a reference, this one is never
+        // released so the tracer stays alive while they can be called
+        gst_object_ref_sink(instance as gpointer);
+        let imp: Guarded<dyn TracerHooks> = Guarded::new(Box::new(tracer));
+        (*instance).imp = Box::into_raw(Box::new(imp));
+        for hook in hooks{
+            let callback = match *hook{
+                Hook::PadPushPre => c_callback(hook_pad_push_pre as *const ()),
+                Hook::PadPushPost => c_callback(hook_pad_push_post as *const ()),
+                Hook::PadPushListPre => c_callback(hook_pad_push_list_pre as *const ()),
+                Hook::PadPushEventPre => c_callback(hook_pad_push_event_pre as *const ()),
+                Hook::ElementPostMessagePre => c_callback(hook_element_post_message_pre as *const ()),
+            };
+            let detail = CString::new(hook.detail()).unwrap();
+            register_hook(instance as *mut GstTracer, detail.as_ptr(), callback);
+        }
+        true
+    }
+}
+
+/// Instance struct of the tracer type used for TracerHooks
+#[repr(C)]
+struct RustTracer{
+    parent: GstTracer,
+    imp: *mut Guarded<dyn TracerHooks>,
+}
+
+unsafe fn rust_tracer_type() -> GType{
+    let tracer_get_type: unsafe extern "C" fn() -> GType = match TRACER_HOOKS.function("gst_tracer_get_type"){
+        Ok(tracer_get_type) => tracer_get_type,
+        Err(_) => return 0,
+    };
+    register_type::<RustTracer>(tracer_get_type(), "GstRsTracer", mem::size_of::<GstTracerClass>(),
+        mem::size_of::<RustTracer>(), Some(tracer_class_init), None)
+}
+
+// the hooks are called from every thread at once, they aren't called
+// anymore once one of them panicked
+unsafe fn tracer_imp<'a>(tracer: *mut RustTracer) -> Option<&'a Guarded<dyn TracerHooks>>{
+    let imp = (*tracer).imp;
+    if imp != ptr::null_mut() && !(*imp).is_poisoned() { Some(&*imp) } else { None }
+}
+
+// the hooks get borrowed objects, the wrappers take their own reference
+unsafe fn borrowed_pad(pad: *mut GstPad) -> Option<Pad>{
+    if pad == ptr::null_mut(){
+        return None;
+    }
+    gst_object_ref(pad as gpointer);
+    Pad::new(pad)
+}
+
+extern "C" fn tracer_class_init(klass: gpointer, _data: gpointer){
+    catch_panic(ptr::null_mut(), (), || unsafe{
+        let object_class = klass as *mut GObjectClass;
+        (*object_class).finalize = Some(tracer_finalize);
+    })
+}
+
+extern "C" fn hook_pad_push_pre(tracer: *mut RustTracer, ts: GstClockTime, pad: *mut GstPad, buffer: *mut GstBuffer){
+    unsafe{
+        if let (Some(imp), Some(pad)) = (tracer_imp(tracer), borrowed_pad(pad)){
+            if let Some(buffer) = Buffer::new(gst_mini_object_ref(buffer as *mut GstMiniObject) as *mut GstBuffer){
+                imp.call_shared(ptr::null_mut(), (), |imp| imp.pad_push_pre(ts, &pad, &buffer));
+            }
+        }
+    }
+}
+
+extern "C" fn hook_pad_push_post(tracer: *mut RustTracer, ts: GstClockTime, pad: *mut GstPad, ret: GstFlowReturn){
+    unsafe{
+        if let (Some(imp), Some(pad)) = (tracer_imp(tracer), borrowed_pad(pad)){
+            imp.call_shared(ptr::null_mut(), (), |imp| imp.pad_push_post(ts, &pad, ret));
+        }
+    }
+}
+
+extern "C" fn hook_pad_push_list_pre(tracer: *mut RustTracer, ts: GstClockTime, pad: *mut GstPad, list: *mut GstBufferList){
+    unsafe{
+        if let (Some(imp), Some(pad)) = (tracer_imp(tracer), borrowed_pad(pad)){
+            if let Some(list) = BufferList::new_from_gst_buffer_list(gst_mini_object_ref(list as *mut GstMiniObject) as *mut GstBufferList){
+                imp.call_shared(ptr::null_mut(), (), |imp| imp.pad_push_list_pre(ts, &pad, &list));
+            }
+        }
+    }
+}
+
+extern "C" fn hook_pad_push_event_pre(tracer: *mut RustTracer, ts: GstClockTime, pad: *mut GstPad, event: *mut GstEvent){
+    unsafe{
+        if let (Some(imp), Some(pad)) = (tracer_imp(tracer), borrowed_pad(pad)){
+            imp.call_shared(ptr::null_mut(), (), |imp| imp.pad_push_event_pre(ts, &pad, event));
+        }
+    }
+}
+
+extern "C" fn hook_element_post_message_pre(tracer: *mut RustTracer, ts: GstClockTime, element: *mut GstElement, message: *mut GstMessage){
+    unsafe{
+        let imp = match tracer_imp(tracer){
+            Some(imp) => imp,
+            None => return,
+        };
+        if element == ptr::null_mut(){
+            return;
+        }
+        gst_object_ref(element as gpointer);
+        if let (Some(element), Some(message)) = (Element::new_from_gst_element(element), Message::new(message)){
+            imp.call_shared(ptr::null_mut(), (), |imp| imp.element_post_message_pre(ts, &element, &message));
+        }
+    }
+}
+
+extern "C" fn tracer_finalize(object: *mut GObject){
+    unsafe{
+        let tracer = object as *mut RustTracer;
+        if (*tracer).imp != ptr::null_mut(){
+            let imp = Box::from_raw((*tracer).imp);
+            (*tracer).imp = ptr::null_mut();
+            catch_panic(ptr::null_mut(), (), move || drop(imp));
+        }
+        let klass = (*(object as *mut GTypeInstance)).g_class;
+        let parent_class = g_type_class_peek_parent(klass as gpointer) as *mut GObjectClass;
+        if let Some(parent_finalize) = (*parent_class).finalize{
+            parent_finalize(object);
+        }
+    }
+}
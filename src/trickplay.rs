@@ -0,0 +1,112 @@
+use ffi::*;
+use util::*;
+
+use element::Element;
+use message::Message;
+
+/// Result of a step, parsed from a StepDone message
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub struct StepResult{
+    pub format: GstFormat,
+    /// Amount stepped, in frames for step_frame
+    pub amount: u64,
+    /// Duration of the stepped data in nanoseconds
+    pub duration: u64,
+    /// True if the step ended because the stream finished
+    pub eos: bool,
+}
+
+impl Element{
+    /// Steps n frames forward while the pipeline is paused, eg: for a
+    /// frame accurate editor. A StepDone message is posted once done and
+    /// the new frame prerolled, see Message::step_result.
+    ///
+    /// ```ignore
+    /// pipeline.pause();
+    /// // on the video sink so audio isn't stepped n buffers too
+    /// videosink.step_frame(1);
+    /// ```
+    ///
+    /// To step backwards do a seek with a negative rate first.
+    pub fn step_frame(&mut self, n: u64) -> bool{
+        unsafe{
+            let event = gst_event_new_step(GST_FORMAT_BUFFERS, n, 1.0, 1, 0);
+            event != ptr::null_mut() && self.send_event(event)
+        }
+    }
+
+    /// Steps duration nanoseconds forward while the pipeline is paused
+    pub fn step_time(&mut self, duration: u64) -> bool{
+        unsafe{
+            let event = gst_event_new_step(GST_FORMAT_TIME, duration, 1.0, 1, 0);
+            event != ptr::null_mut() && self.send_event(event)
+        }
+    }
+
+    /// Seeks to the keyframe before position, in nanoseconds, which is
+    /// fast since nothing before the position has to be decoded and
+    /// discarded. The position reached can be earlier than requested
+    pub fn seek_to_keyframe(&mut self, position: u64) -> bool{
+        self.seek_simple(GST_FORMAT_TIME, GST_SEEK_FLAG_FLUSH | GST_SEEK_FLAG_KEY_UNIT | GST_SEEK_FLAG_SNAP_BEFORE, position as i64)
+    }
+
+    /// Changes the playback rate from the current position, negative
+    /// rates play backwards. High rates only decode keyframes and skip
+    /// audio so fast-forward stays smooth. It flushes the pipeline, for
+    /// a change without a flush use set_rate_instant
+    pub fn set_trick_rate(&mut self, rate: f64) -> bool{
+        if rate == 0.0{
+            return false;
+        }
+        let position = match self.query_position(GST_FORMAT_TIME){
+            Some(position) => position,
+            None => return false,
+        };
+        let mut flags = GST_SEEK_FLAG_FLUSH | GST_SEEK_FLAG_ACCURATE;
+        if rate.abs() > 2.0{
+            flags = GST_SEEK_FLAG_FLUSH | GST_SEEK_FLAG_TRICKMODE | GST_SEEK_FLAG_TRICKMODE_KEY_UNITS | GST_SEEK_FLAG_TRICKMODE_NO_AUDIO;
+        }
+        if rate > 0.0{
+            self.seek(rate, GST_FORMAT_TIME, flags, GST_SEEK_TYPE_SET, position, GST_SEEK_TYPE_END, 0)
+        }else{
+            self.seek(rate, GST_FORMAT_TIME, flags, GST_SEEK_TYPE_SET, 0, GST_SEEK_TYPE_SET, position)
+        }
+    }
+
+    /// Changes the playback rate without flushing or seeking, so the
+    /// change is seamless, eg: 1.0 to 1.5 in a player. The direction
+    /// can't change, use set_trick_rate for that. Needs GStreamer 1.18
+    /// and demuxers that support it
+    pub fn set_rate_instant(&mut self, rate: f64) -> bool{
+        if rate == 0.0{
+            return false;
+        }
+        self.seek(rate, GST_FORMAT_TIME, GST_SEEK_FLAG_INSTANT_RATE_CHANGE, GST_SEEK_TYPE_NONE, 0, GST_SEEK_TYPE_NONE, 0)
+    }
+}
+
+impl Message{
+    /// The result of a step for StepDone messages, None for any other
+    pub fn step_result(&self) -> Option<StepResult>{
+        unsafe{
+            if self.ty() != GST_MESSAGE_STEP_DONE{
+                return None;
+            }
+            let mut format = GST_FORMAT_UNDEFINED;
+            let mut amount = 0;
+            let mut rate = 0.0;
+            let mut flush = 0;
+            let mut intermediate = 0;
+            let mut duration = 0;
+            let mut eos = 0;
+            gst_message_parse_step_done(self.gst_message() as *mut GstMessage, &mut format, &mut amount,
+                &mut rate, &mut flush, &mut intermediate, &mut duration, &mut eos);
+            Some(StepResult{
+                format: format,
+                amount: amount,
+                duration: duration,
+                eos: eos != 0,
+            })
+        }
+    }
+}
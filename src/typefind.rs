@@ -0,0 +1,110 @@
+use ffi::*;
+use util::*;
+use caps::Caps;
+use buffer::Buffer;
+use version::ApiAvailability;
+
+const TYPE_FIND_WITH_EXTENSION: ApiAvailability = ApiAvailability::new("type_find_helper_with_extension", 1, 16, 0);
+
+/// How sure a typefinder is about the type it found. Typefinders can
+/// report any value, they are rounded down to the closest level
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq,PartialOrd)]
+pub enum Probability{
+    None = GST_TYPE_FIND_NONE,
+    Minimum = GST_TYPE_FIND_MINIMUM,
+    Possible = GST_TYPE_FIND_POSSIBLE,
+    Likely = GST_TYPE_FIND_LIKELY,
+    NearlyCertain = GST_TYPE_FIND_NEARLY_CERTAIN,
+    Maximum = GST_TYPE_FIND_MAXIMUM,
+}
+
+impl Probability{
+    pub fn from_gst(probability: GstTypeFindProbability) -> Probability{
+        match probability{
+            p if p >= GST_TYPE_FIND_MAXIMUM => Probability::Maximum,
+            p if p >= GST_TYPE_FIND_NEARLY_CERTAIN => Probability::NearlyCertain,
+            p if p >= GST_TYPE_FIND_LIKELY => Probability::Likely,
+            p if p >= GST_TYPE_FIND_POSSIBLE => Probability::Possible,
+            p if p >= GST_TYPE_FIND_MINIMUM => Probability::Minimum,
+            _ => Probability::None,
+        }
+    }
+}
+
+unsafe fn found(caps: *mut GstCaps, probability: GstTypeFindProbability) -> Option<(Caps, Probability)>{
+    Caps::new(caps).map(|caps| (caps, Probability::from_gst(probability)))
+}
+
+/// Identifies the media type of data by running every typefinder in
+/// the registry on it, eg: to choose the demuxer for a file already
+/// in memory:
+///
+/// ```ignore
+/// let data = std::fs::read("video.mp4").unwrap();
+/// if let Some((caps, probability)) = gst::typefind::type_find_helper_for_data(&data){
+///     println!("{} ({:?})", caps.to_string(), probability);
+/// }
+/// ```
+///
+/// Only the start of the data is usually needed, a few KB are enough
+/// for most formats.
+pub fn type_find_helper_for_data(data: &[u8]) -> Option<(Caps, Probability)>{
+    let mut probability = GST_TYPE_FIND_NONE;
+    unsafe{
+        let caps = gst_type_find_helper_for_data(ptr::null_mut(), data.as_ptr(), data.len() as gsize, &mut probability);
+        found(caps, probability)
+    }
+}
+
+/// Same as type_find_helper_for_data but the typefinders for extension,
+/// eg: "mp4", are tried first, which is faster and resolves ambiguous
+/// data. Needs GStreamer 1.16, older versions ignore extension
+pub fn type_find_helper_for_data_with_extension(data: &[u8], extension: &str) -> Option<(Caps, Probability)>{
+    let helper: unsafe extern "C" fn(*mut GstObject, *const guint8, gsize, *const gchar, *mut GstTypeFindProbability) -> *mut GstCaps =
+        match unsafe{ TYPE_FIND_WITH_EXTENSION.function("gst_type_find_helper_for_data_with_extension") }{
+            Ok(helper) => helper,
+            Err(_) => return type_find_helper_for_data(data),
+        };
+    let mut probability = GST_TYPE_FIND_NONE;
+    let cextension = CString::new(extension).unwrap();
+    unsafe{
+        let caps = helper(ptr::null_mut(), data.as_ptr(), data.len() as gsize, cextension.as_ptr(), &mut probability);
+        found(caps, probability)
+    }
+}
+
+/// Identifies the media type of the data of buffer
+pub fn type_find_helper_for_buffer(buffer: &Buffer) -> Option<(Caps, Probability)>{
+    let mut probability = GST_TYPE_FIND_NONE;
+    unsafe{
+        let caps = gst_type_find_helper_for_buffer(ptr::null_mut(), buffer.gst_buffer() as *mut GstBuffer, &mut probability);
+        found(caps, probability)
+    }
+}
+
+/// Same as type_find_helper_for_buffer trying the typefinders for
+/// extension first. Needs GStreamer 1.16, older versions ignore
+/// extension
+pub fn type_find_helper_for_buffer_with_extension(buffer: &Buffer, extension: &str) -> Option<(Caps, Probability)>{
+    let helper: unsafe extern "C" fn(*mut GstObject, *mut GstBuffer, *const gchar, *mut GstTypeFindProbability) -> *mut GstCaps =
+        match unsafe{ TYPE_FIND_WITH_EXTENSION.function("gst_type_find_helper_for_buffer_with_extension") }{
+            Ok(helper) => helper,
+            Err(_) => return type_find_helper_for_buffer(buffer),
+        };
+    let mut probability = GST_TYPE_FIND_NONE;
+    let cextension = CString::new(extension).unwrap();
+    unsafe{
+        let caps = helper(ptr::null_mut(), buffer.gst_buffer() as *mut GstBuffer, cextension.as_ptr(), &mut probability);
+        found(caps, probability)
+    }
+}
+
+/// The media type usually stored in files with extension, eg: "ogg",
+/// without looking at any data
+pub fn type_find_helper_for_extension(extension: &str) -> Option<Caps>{
+    let cextension = CString::new(extension).unwrap();
+    unsafe{
+        Caps::new(gst_type_find_helper_for_extension(ptr::null_mut(), cextension.as_ptr()))
+    }
+}
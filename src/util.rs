@@ -3,6 +3,16 @@ pub use std::ffi::CStr;
 pub use std::str;
 pub use std::mem;
 pub use std::ptr;
+pub use panic::catch_panic;
+
+use panic::catch_panic_with;
+use structure::Structure;
+use version::ApiAvailability;
+
+use ffi::{GstElement, GstMessageType, GstStructure, GQuark, GST_MESSAGE_ERROR, gchar, gint, gpointer,
+    gst_object_ref, gst_element_message_full, gst_structure_copy, g_strdup};
+
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Converts nanoseconds to seconds
 pub fn ns_to_s(ns: u64) -> f64{
@@ -14,6 +24,126 @@ pub fn s_to_ns(s: f64) -> u64{
     (s * 1000000000.0) as u64
 }
 
+/// Casts callback, a pointer to an extern "C" fn, to the function
+/// pointer type a C API stores it as, eg: a signal handler to GCallback
+/// or a bus watch to GSourceFunc. The C side calls it back with its
+/// real signature, which has to match the one the signal or hook
+/// expects
+pub unsafe fn c_callback<G: Copy>(callback: *const ()) -> G{
+    assert_eq!(mem::size_of::<G>(), mem::size_of::<*const ()>());
+    mem::transmute_copy(&callback)
+}
+
+/// Error messages with a details structure, GStreamer 1.10
+pub const ERROR_DETAILS: ApiAvailability = ApiAvailability::new("error message details", 1, 10, 0);
+
+/// Posts an error message on behalf of element, like GST_ELEMENT_ERROR
+/// does in C. details are added to the message if the GStreamer in use
+/// supports it or as the debug string otherwise. file, function and
+/// line are the caller's, for the debug log
+pub unsafe fn post_element_error(element: *mut GstElement, domain: GQuark, code: gint, text: &str,
+    details: Option<&Structure>, file: &str, function: &str, line: u32){
+    let text = CString::new(text.replace('\0', "")).unwrap();
+    let file = CString::new(file).unwrap();
+    let function = CString::new(function).unwrap();
+    if let Some(details) = details{
+        if let Ok(message_full_with_details) = ERROR_DETAILS.function::<unsafe extern "C" fn(*mut GstElement, GstMessageType, GQuark, gint,
+                *mut gchar, *mut gchar, *const gchar, *const gchar, gint, *mut GstStructure)>("gst_element_message_full_with_details"){
+            message_full_with_details(element, GST_MESSAGE_ERROR, domain, code, g_strdup(text.as_ptr()), ptr::null_mut(),
+                file.as_ptr(), function.as_ptr(), line as gint, gst_structure_copy(details.gst_structure()));
+            return;
+        }
+    }
+    let debug = details.map(|details| CString::new(details.to_string()).unwrap());
+    let debug = debug.as_ref().map_or(ptr::null_mut(), |debug| g_strdup(debug.as_ptr()));
+    gst_element_message_full(element, GST_MESSAGE_ERROR, domain, code, g_strdup(text.as_ptr()), debug,
+        file.as_ptr(), function.as_ptr(), line as gint);
+}
+
+/// A rust callback handed to C code, like a probe or a bus watch, that
+/// is called through catch_panic. After a panic the callback is marked
+/// as poisoned and never called again, since whatever state it captured
+/// can't be trusted anymore, and every later call returns the fallback.
+pub struct Guarded<T: ?Sized>{
+    poisoned: AtomicBool,
+    callback: Box<T>,
+}
+
+impl<T: ?Sized> Guarded<T>{
+    pub fn new(callback: Box<T>) -> Guarded<T>{
+        Guarded{
+            poisoned: AtomicBool::new(false),
+            callback: callback,
+        }
+    }
+
+    pub fn is_poisoned(&self) -> bool{
+        self.poisoned.load(Ordering::Acquire)
+    }
+
+    /// Calls f with the callback unless it's poisoned. If f panics the
+    /// panic handler is called for element, which can be null, the
+    /// callback is poisoned and fallback returned
+    pub fn call<R, F: FnOnce(&mut T) -> R>(&mut self, element: *mut GstElement, fallback: R, f: F) -> R{
+        self.call_with(|| unsafe{
+            if element != ptr::null_mut(){
+                gst_object_ref(element as gpointer);
+            }
+            element
+        }, fallback, f)
+    }
+
+    /// Same as call but the element is only looked up if f panics, see
+    /// catch_panic_with
+    pub fn call_with<R, E: FnOnce() -> *mut GstElement, F: FnOnce(&mut T) -> R>(&mut self, element: E, fallback: R, f: F) -> R{
+        if self.is_poisoned(){
+            return fallback;
+        }
+        let mut returned = false;
+        let ret = {
+            let callback = &mut *self.callback;
+            catch_panic_with(element, fallback, || {
+                let ret = f(callback);
+                returned = true;
+                ret
+            })
+        };
+        if !returned{
+            self.poisoned.store(true, Ordering::Release);
+        }
+        ret
+    }
+
+    /// Same as call for callbacks called from several threads at once
+    /// through a shared reference, like the hooks of a tracer
+    pub fn call_shared<R, F: FnOnce(&T) -> R>(&self, element: *mut GstElement, fallback: R, f: F) -> R{
+        self.call_shared_with(|| unsafe{
+            if element != ptr::null_mut(){
+                gst_object_ref(element as gpointer);
+            }
+            element
+        }, fallback, f)
+    }
+
+    /// Same as call_shared but the element is only looked up if f
+    /// panics, see catch_panic_with
+    pub fn call_shared_with<R, E: FnOnce() -> *mut GstElement, F: FnOnce(&T) -> R>(&self, element: E, fallback: R, f: F) -> R{
+        if self.is_poisoned(){
+            return fallback;
+        }
+        let mut returned = false;
+        let ret = catch_panic_with(element, fallback, || {
+            let ret = f(&self.callback);
+            returned = true;
+            ret
+        });
+        if !returned{
+            self.poisoned.store(true, Ordering::Release);
+        }
+        ret
+    }
+}
+
 macro_rules! from_c_str{
 	($c_string: expr) => (
 		str::from_utf8(CStr::from_ptr($c_string).to_bytes()).unwrap();
@@ -0,0 +1,331 @@
+use ffi::*;
+use util::*;
+use element::Element;
+use bin::Bin;
+use caps::Caps;
+use device_monitor::{Device, DeviceMonitor};
+use reference::Reference;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::ops::{Deref, DerefMut};
+
+/// How v4l2src gets the frames from the driver, mirrors the "io-mode"
+/// property values
+#[repr(i32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum IoMode{
+    Auto = 0,
+    ReadWrite = 1,
+    Mmap = 2,
+    UserPtr = 3,
+    /// Export the driver buffers as dmabuf so downstream elements
+    /// can import them without copying
+    DmaBuf = 4,
+    /// Import dmabuf buffers allocated downstream
+    DmaBufImport = 5,
+}
+
+/// A capture device found by probe_devices()
+#[derive(Clone)]
+pub struct V4l2Device{
+    /// Path of the device node, eg: /dev/video0
+    pub path: String,
+    /// Name reported by the driver
+    pub name: String,
+    /// Persistent name under /dev/v4l/by-id if any, for USB cameras it
+    /// contains the vendor, product and serial number
+    pub id: Option<String>,
+    /// All the formats the device can produce
+    pub caps: Caps,
+}
+
+impl V4l2Device{
+    /// Returns the framerates supported for the given resolution as
+    /// (numerator, denominator)
+    pub fn framerates(&self, width: i32, height: i32) -> Vec<(i32,i32)>{
+        caps_framerates(&self.caps, width, height)
+    }
+
+    /// Returns true if the device can produce the given caps
+    pub fn supports(&self, caps: &Caps) -> bool{
+        self.caps.can_intersect(caps)
+    }
+
+    /// Creates a v4l2src already configured to capture from this device
+    pub fn create_source(&self, name: &str) -> Option<V4l2Src>{
+        V4l2Src::new(name).map(|mut src| {
+            src.set_device(&self.path);
+            src
+        })
+    }
+
+    /// Pairs a device found by a DeviceMonitor with its v4l2 node, None
+    /// if it's not a video4linux capture device
+    pub fn from_device(device: &Device) -> Option<V4l2Device>{
+        let properties = device.properties()?;
+        if properties.get_string("device.api") != Some("v4l2") || !device.has_classes("Video/Source"){
+            return None;
+        }
+        let path = properties.get_string("device.path")?.to_string();
+        let id = device_ids().into_iter()
+            .find(|&(ref id_path, _)| id_path == Path::new(&path))
+            .map(|(_, id)| id);
+        Some(V4l2Device{
+            path: path,
+            name: device.display_name(),
+            id: id,
+            caps: device.caps()?,
+        })
+    }
+
+    /// Creates a bin with a v4l2src capturing from this device with
+    /// io_mode followed by a capsfilter with caps, and a "src" ghost pad
+    /// so it can be linked as a source. Returns None if the device can't
+    /// produce caps, eg:
+    ///
+    /// ```ignore
+    /// let device = gst::v4l2src::find_device_by_serial("C920_1234").unwrap();
+    /// let caps = gst::Caps::from_string("video/x-raw,format=NV12,width=1920,height=1080").unwrap();
+    /// let src = device.create_capture_bin("camera", &caps, gst::v4l2src::IoMode::DmaBuf).unwrap();
+    /// pipeline.add_and_link(src.into(), encoder.into());
+    /// ```
+    pub fn create_capture_bin(&self, name: &str, caps: &Caps, io_mode: IoMode) -> Option<Bin>{
+        if !self.supports(caps){
+            return None;
+        }
+        let mut bin = Bin::new(name)?;
+        let mut src = self.create_source("")?;
+        src.set_io_mode(io_mode);
+        let mut capsfilter = Element::new("capsfilter", "")?;
+        capsfilter.set("caps", caps);
+        if !bin.add_and_link(src.into(), capsfilter.reference()){
+            return None;
+        }
+        let mut target = capsfilter.static_pad("src")?;
+        let cname = CString::new("src").unwrap();
+        unsafe{
+            let pad = gst_ghost_pad_new(cname.as_ptr(), target.gst_pad_mut());
+            if pad == ptr::null_mut(){
+                return None;
+            }
+            gst_pad_set_active(pad, 1);
+            if gst_element_add_pad(bin.gst_element_mut(), pad) == 0{
+                return None;
+            }
+        }
+        Some(bin)
+    }
+}
+
+/// Lists all the video4linux capture devices in the system.
+///
+/// Each device is opened by setting a v4l2src to READY to query its name
+/// and formats, devices that can't be opened are skipped.
+pub fn probe_devices() -> Vec<V4l2Device>{
+    let mut paths = match fs::read_dir("/dev"){
+        Ok(entries) => entries.filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.starts_with("video"))
+                .unwrap_or(false))
+            .collect::<Vec<_>>(),
+        Err(_) => return vec![]
+    };
+    paths.sort();
+    let ids = device_ids();
+    paths.iter().filter_map(|path| probe_device(path, &ids)).collect()
+}
+
+/// Lists the video4linux capture devices known to the device monitor,
+/// which doesn't need to open them so it's faster than probe_devices
+/// and doesn't fail for devices in use by another process
+pub fn monitor_devices() -> Vec<V4l2Device>{
+    let mut monitor = match DeviceMonitor::new(){
+        Some(monitor) => monitor,
+        None => return vec![]
+    };
+    monitor.add_filter("Video/Source", None);
+    let mut devices = monitor.devices().iter()
+        .filter_map(V4l2Device::from_device)
+        .collect::<Vec<_>>();
+    devices.sort_by(|a, b| a.path.cmp(&b.path));
+    devices
+}
+
+/// Returns the first device that can produce the given caps
+pub fn find_device_by_caps(caps: &Caps) -> Option<V4l2Device>{
+    probe_devices().into_iter().find(|device| device.supports(caps))
+}
+
+/// Returns the device whose persistent id contains serial, useful to
+/// always open the same USB camera regardless of the order in which
+/// the devices were enumerated
+pub fn find_device_by_serial(serial: &str) -> Option<V4l2Device>{
+    probe_devices().into_iter().find(|device| {
+        device.id.as_ref().map(|id| id.contains(serial)).unwrap_or(false)
+    })
+}
+
+fn caps_framerates(caps: &Caps, width: i32, height: i32) -> Vec<(i32,i32)>{
+    let mut framerates = vec![];
+    for i in 0..caps.len(){
+        if let Some(structure) = caps.structure(i){
+            if structure.get_int("width") == Some(width) && structure.get_int("height") == Some(height){
+                for framerate in structure.get_fraction_list("framerate"){
+                    if !framerates.contains(&framerate){
+                        framerates.push(framerate);
+                    }
+                }
+            }
+        }
+    }
+    framerates
+}
+
+fn device_ids() -> Vec<(PathBuf, String)>{
+    match fs::read_dir("/dev/v4l/by-id"){
+        Ok(entries) => entries.filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let id = entry.file_name().to_string_lossy().into_owned();
+                fs::canonicalize(entry.path()).ok().map(|path| (path, id))
+            })
+            .collect(),
+        Err(_) => vec![]
+    }
+}
+
+fn probe_device(path: &Path, ids: &[(PathBuf, String)]) -> Option<V4l2Device>{
+    let path_str = match path.to_str(){
+        Some(path) => path.to_string(),
+        None => return None
+    };
+    let mut src = match V4l2Src::new(""){
+        Some(src) => src,
+        None => return None
+    };
+    src.set_device(&path_str);
+    if src.set_ready_state() == GST_STATE_CHANGE_FAILURE{
+        src.set_null_state();
+        return None;
+    }
+    let name = src.device_name().unwrap_or(String::new());
+    let caps = src.static_pad("src").and_then(|pad| pad.query_caps(None));
+    src.set_null_state();
+    let id = ids.iter().find(|&&(ref id_path, _)| id_path == path).map(|&(_, ref id)| id.clone());
+    caps.map(|caps| V4l2Device{
+        path: path_str,
+        name: name,
+        id: id,
+        caps: caps,
+    })
+}
+
+/// Wrapper for v4l2src with typed access to its properties
+pub struct V4l2Src{
+    v4l2src: Element
+}
+
+impl V4l2Src{
+    pub fn new(name: &str) -> Option<V4l2Src>{
+        Element::new("v4l2src", name).map(|e| V4l2Src{ v4l2src: e })
+    }
+
+    pub fn new_from_element(element: Element) -> V4l2Src{
+        V4l2Src{ v4l2src: element }
+    }
+
+    /// Sets the device node to capture from, eg: /dev/video0
+    pub fn set_device(&mut self, device: &str){
+        self.set("device", device);
+    }
+
+    pub fn device(&self) -> Option<String>{
+        self.get_string("device")
+    }
+
+    /// Name of the device as reported by the driver, only available once
+    /// the element is in READY state or later
+    pub fn device_name(&self) -> Option<String>{
+        self.get_string("device-name")
+    }
+
+    pub fn set_io_mode(&mut self, mode: IoMode){
+        self.set("io-mode", mode as i32);
+    }
+
+    pub fn io_mode(&self) -> IoMode{
+        match self.get::<i32>("io-mode"){
+            1 => IoMode::ReadWrite,
+            2 => IoMode::Mmap,
+            3 => IoMode::UserPtr,
+            4 => IoMode::DmaBuf,
+            5 => IoMode::DmaBufImport,
+            _ => IoMode::Auto,
+        }
+    }
+
+    /// Sets the analog video standard for devices like TV tuners or
+    /// capture cards using its nick, eg: "PAL", "NTSC", "SECAM"
+    pub fn set_norm(&mut self, norm: &str){
+        self.set_from_str("norm", norm);
+    }
+
+    /// Returns the current analog video standard or None if the device
+    /// doesn't have one
+    pub fn norm(&self) -> Option<String>{
+        self.get_enum_nick("norm").and_then(|norm| if norm == "none" { None } else { Some(norm) })
+    }
+
+    /// Returns the framerates the device supports at the given resolution,
+    /// the element needs to be at least in READY state
+    pub fn framerates(&self, width: i32, height: i32) -> Vec<(i32,i32)>{
+        let mut src = self.reference();
+        let caps = src.static_pad("src").and_then(|pad| pad.query_caps(None));
+        caps.map(|caps| caps_framerates(&caps, width, height)).unwrap_or(vec![])
+    }
+}
+
+impl Reference for V4l2Src{
+    fn reference(&self) -> V4l2Src{
+        V4l2Src{ v4l2src: self.v4l2src.reference() }
+    }
+}
+
+impl AsRef<Element> for V4l2Src{
+    fn as_ref(&self) -> &Element{
+        &self.v4l2src
+    }
+}
+
+impl AsMut<Element> for V4l2Src{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.v4l2src
+    }
+}
+
+impl From<V4l2Src> for Element{
+    fn from(b: V4l2Src) -> Element{
+        b.v4l2src
+    }
+}
+
+impl Deref for V4l2Src{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.v4l2src
+    }
+}
+
+impl DerefMut for V4l2Src{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.v4l2src
+    }
+}
+
+impl ::Transfer for V4l2Src{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.v4l2src.transfer()
+    }
+}
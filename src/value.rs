@@ -0,0 +1,512 @@
+use ffi::*;
+use util::*;
+use object::Object;
+use element::Element;
+use pad::Pad;
+use caps::Caps;
+use buffer::Buffer;
+use sample::Sample;
+use element_factory::ElementFactory;
+use structure::Structure;
+use promise::{Promise, promise_type};
+
+use std::os::raw::c_void;
+use std::marker::PhantomData;
+use std::fmt::{self, Debug, Formatter};
+
+// G_TYPE_MAKE_FUNDAMENTAL(x) for the fundamental types
+pub const G_TYPE_NONE: GType = 1 << 2;
+pub const G_TYPE_BOOLEAN: GType = 5 << 2;
+pub const G_TYPE_INT: GType = 6 << 2;
+pub const G_TYPE_UINT: GType = 7 << 2;
+pub const G_TYPE_INT64: GType = 10 << 2;
+pub const G_TYPE_UINT64: GType = 11 << 2;
+pub const G_TYPE_ENUM: GType = 12 << 2;
+pub const G_TYPE_FLAGS: GType = 13 << 2;
+pub const G_TYPE_FLOAT: GType = 14 << 2;
+pub const G_TYPE_DOUBLE: GType = 15 << 2;
+pub const G_TYPE_STRING: GType = 16 << 2;
+
+// set in the types of signal params and return values that are passed
+// without copying
+pub const G_SIGNAL_TYPE_STATIC_SCOPE: GType = 1;
+
+/// A dynamically typed value as passed to and returned from signals.
+///
+/// ```ignore
+/// let value = gst::Value::from(5u32);
+/// assert_eq!(value.get::<u32>(), Some(5));
+/// ```
+///
+/// Values aren't Send nor Sync as they can hold any type, including
+/// objects that can only be used from one thread. Convert them to the
+/// rust type they hold to pass them to other threads.
+#[repr(C)]
+pub struct Value{
+    value: GValue,
+    // zero sized, the layout is still the one of GValue
+    thread: PhantomData<*const ()>,
+}
+
+impl Value{
+    /// Creates a value of type gtype holding the default value of that
+    /// type, eg: 0 or null
+    pub fn new(gtype: GType) -> Value{
+        unsafe{
+            let mut value = Value{ value: mem::zeroed(), thread: PhantomData };
+            g_value_init(&mut value.value, gtype & !G_SIGNAL_TYPE_STATIC_SCOPE);
+            value
+        }
+    }
+
+    /// Copies the contents of value
+    pub unsafe fn new_from_gvalue(value: *const GValue) -> Value{
+        let mut copy = Value::new((*value).g_type);
+        g_value_copy(value, &mut copy.value);
+        copy
+    }
+
+    /// Creates a value of the enum type gtype, eg: the result of
+    /// gst_autoplug_select_result_get_type()
+    pub fn from_enum(gtype: GType, value: i32) -> Value{
+        let mut ret = Value::new(gtype);
+        unsafe{
+            g_value_set_enum(&mut ret.value, value);
+        }
+        ret
+    }
+
+    /// Creates a value of the enum type called type_name from the nick of
+    /// one of its values, eg: from_enum_nick("GstAutoplugSelectResult", "skip").
+    /// Returns None if the type isn't registered or doesn't have that value
+    pub fn from_enum_nick(type_name: &str, nick: &str) -> Option<Value>{
+        let ctype_name = CString::new(type_name).unwrap();
+        let cnick = CString::new(nick).unwrap();
+        unsafe{
+            let gtype = g_type_from_name(ctype_name.as_ptr());
+            if gtype == 0 || g_type_fundamental(gtype) != G_TYPE_ENUM{
+                return None;
+            }
+            let enum_class = g_type_class_ref(gtype) as *mut GEnumClass;
+            let enum_value = g_enum_get_value_by_nick(enum_class, cnick.as_ptr());
+            let ret = if enum_value != ptr::null_mut(){
+                Some(Value::from_enum(gtype, (*enum_value).value))
+            }else{
+                None
+            };
+            g_type_class_unref(enum_class as gpointer);
+            ret
+        }
+    }
+
+    /// Creates a value of the flags type gtype
+    pub fn from_flags(gtype: GType, value: u32) -> Value{
+        let mut ret = Value::new(gtype);
+        unsafe{
+            g_value_set_flags(&mut ret.value, value);
+        }
+        ret
+    }
+
+    pub fn value_type(&self) -> GType{
+        self.value.g_type
+    }
+
+    /// Name of the type of the value, eg: "gint" or "GstElement"
+    pub fn type_name(&self) -> String{
+        unsafe{
+            from_c_str!(g_type_name(self.value.g_type)).to_string()
+        }
+    }
+
+    /// Returns true if the value holds gtype or a subtype of it
+    pub fn holds(&self, gtype: GType) -> bool{
+        unsafe{
+            g_type_is_a(self.value.g_type, gtype) != 0
+        }
+    }
+
+    /// Returns the contents of the value if it holds a T
+    pub fn get<T: FromValue>(&self) -> Option<T>{
+        T::from_value(self)
+    }
+
+    /// Returns the integer value of an enum value
+    pub fn enum_value(&self) -> Option<i32>{
+        unsafe{
+            if g_type_fundamental(self.value.g_type) == G_TYPE_ENUM{
+                Some(g_value_get_enum(&self.value))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Returns the nick of an enum value, eg: "try"
+    pub fn enum_nick(&self) -> Option<String>{
+        self.enum_value().and_then(|value| unsafe{
+            let enum_class = g_type_class_ref(self.value.g_type) as *mut GEnumClass;
+            let enum_value = g_enum_get_value(enum_class, value);
+            let nick = if enum_value != ptr::null_mut(){
+                Some(from_c_str!((*enum_value).value_nick).to_string())
+            }else{
+                None
+            };
+            g_type_class_unref(enum_class as gpointer);
+            nick
+        })
+    }
+
+    pub fn flags_value(&self) -> Option<u32>{
+        unsafe{
+            if g_type_fundamental(self.value.g_type) == G_TYPE_FLAGS{
+                Some(g_value_get_flags(&self.value))
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Converts the value to gtype, returns a copy if it's already of
+    /// that type or None if it can't be converted. Integers can be
+    /// converted to enums and flags
+    pub fn convert(&self, gtype: GType) -> Option<Value>{
+        let gtype = gtype & !G_SIGNAL_TYPE_STATIC_SCOPE;
+        unsafe{
+            if self.holds(gtype){
+                Some(self.clone())
+            }else if g_type_fundamental(gtype) == G_TYPE_ENUM && g_type_fundamental(self.value.g_type) != G_TYPE_ENUM{
+                self.convert(G_TYPE_INT).map(|value| Value::from_enum(gtype, g_value_get_int(&value.value)))
+            }else if g_type_fundamental(gtype) == G_TYPE_FLAGS && g_type_fundamental(self.value.g_type) != G_TYPE_FLAGS{
+                self.convert(G_TYPE_UINT).map(|value| Value::from_flags(gtype, g_value_get_uint(&value.value)))
+            }else if g_value_type_transformable(self.value.g_type, gtype) != 0{
+                let mut ret = Value::new(gtype);
+                if g_value_transform(&self.value, &mut ret.value) != 0{
+                    Some(ret)
+                }else{
+                    None
+                }
+            }else{
+                None
+            }
+        }
+    }
+
+    /// The value as a readable string, for debugging
+    pub fn to_string(&self) -> String{
+        unsafe{
+            let contents = g_strdup_value_contents(&self.value);
+            let ret = from_c_str!(contents).to_string();
+            g_free(contents as gpointer);
+            ret
+        }
+    }
+
+    pub unsafe fn gst_value(&self) -> *const GValue{
+        &self.value
+    }
+
+    pub unsafe fn gst_value_mut(&mut self) -> *mut GValue{
+        &mut self.value
+    }
+}
+
+impl Clone for Value{
+    fn clone(&self) -> Value{
+        unsafe{ Value::new_from_gvalue(&self.value) }
+    }
+}
+
+impl Drop for Value{
+    fn drop(&mut self){
+        unsafe{
+            if self.value.g_type != 0{
+                g_value_unset(&mut self.value);
+            }
+        }
+    }
+}
+
+impl Debug for Value{
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result{
+        write!(fmt, "Value({}: {})", self.type_name(), self.to_string())
+    }
+}
+
+/// Types that can be stored in a Value
+pub trait ToValue{
+    fn to_value(&self) -> Value;
+}
+
+/// Types that can be extracted from a Value
+pub trait FromValue: Sized{
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+impl<'a, T: ToValue> From<&'a T> for Value{
+    fn from(t: &'a T) -> Value{
+        t.to_value()
+    }
+}
+
+macro_rules! value_impl(
+    ($t: ty, $gtype: expr, $setter: ident, $getter: ident, $ctype: ty) => (
+        impl ToValue for $t{
+            fn to_value(&self) -> Value{
+                let mut value = Value::new($gtype);
+                unsafe{ $setter(&mut value.value, *self as $ctype) };
+                value
+            }
+        }
+
+        impl FromValue for $t{
+            fn from_value(value: &Value) -> Option<$t>{
+                if value.holds($gtype){
+                    Some(unsafe{ $getter(&value.value) } as $t)
+                }else{
+                    None
+                }
+            }
+        }
+
+        impl From<$t> for Value{
+            fn from(t: $t) -> Value{
+                t.to_value()
+            }
+        }
+    )
+);
+
+value_impl!(i32, G_TYPE_INT, g_value_set_int, g_value_get_int, gint);
+value_impl!(u32, G_TYPE_UINT, g_value_set_uint, g_value_get_uint, guint);
+value_impl!(i64, G_TYPE_INT64, g_value_set_int64, g_value_get_int64, gint64);
+value_impl!(u64, G_TYPE_UINT64, g_value_set_uint64, g_value_get_uint64, guint64);
+value_impl!(f32, G_TYPE_FLOAT, g_value_set_float, g_value_get_float, gfloat);
+value_impl!(f64, G_TYPE_DOUBLE, g_value_set_double, g_value_get_double, gdouble);
+
+impl ToValue for bool{
+    fn to_value(&self) -> Value{
+        let mut value = Value::new(G_TYPE_BOOLEAN);
+        unsafe{ g_value_set_boolean(&mut value.value, *self as gboolean) };
+        value
+    }
+}
+
+impl FromValue for bool{
+    fn from_value(value: &Value) -> Option<bool>{
+        if value.holds(G_TYPE_BOOLEAN){
+            Some(unsafe{ g_value_get_boolean(&value.value) } != 0)
+        }else{
+            None
+        }
+    }
+}
+
+impl From<bool> for Value{
+    fn from(b: bool) -> Value{
+        b.to_value()
+    }
+}
+
+impl ToValue for str{
+    fn to_value(&self) -> Value{
+        let cstr = CString::new(self).unwrap();
+        let mut value = Value::new(G_TYPE_STRING);
+        unsafe{ g_value_set_string(&mut value.value, cstr.as_ptr()) };
+        value
+    }
+}
+
+impl ToValue for String{
+    fn to_value(&self) -> Value{
+        self.as_str().to_value()
+    }
+}
+
+impl<'a> From<&'a str> for Value{
+    fn from(s: &'a str) -> Value{
+        s.to_value()
+    }
+}
+
+impl FromValue for String{
+    fn from_value(value: &Value) -> Option<String>{
+        if value.holds(G_TYPE_STRING){
+            unsafe{
+                let s = g_value_get_string(&value.value);
+                if s != ptr::null(){
+                    Some(from_c_str!(s).to_string())
+                }else{
+                    None
+                }
+            }
+        }else{
+            None
+        }
+    }
+}
+
+/// Value of the object's own type holding a new reference to it
+unsafe fn object_to_value(object: *mut c_void) -> Value{
+    let mut value = Value{ value: mem::zeroed(), thread: PhantomData };
+    g_value_init_from_instance(&mut value.value, object);
+    value
+}
+
+/// New reference to the object held by value if it's of type gtype
+unsafe fn object_from_value(value: &Value, gtype: GType) -> *mut c_void{
+    if value.holds(gtype){
+        g_value_dup_object(&value.value)
+    }else{
+        ptr::null_mut()
+    }
+}
+
+impl ToValue for Object{
+    fn to_value(&self) -> Value{
+        unsafe{ object_to_value(self.gst_object() as *mut c_void) }
+    }
+}
+
+impl FromValue for Object{
+    fn from_value(value: &Value) -> Option<Object>{
+        unsafe{ Object::new(object_from_value(value, gst_object_get_type()) as *mut GstObject) }
+    }
+}
+
+impl ToValue for Element{
+    fn to_value(&self) -> Value{
+        unsafe{ object_to_value(self.gst_element() as *mut c_void) }
+    }
+}
+
+impl FromValue for Element{
+    fn from_value(value: &Value) -> Option<Element>{
+        unsafe{ Element::new_from_gst_element(object_from_value(value, gst_element_get_type()) as *mut GstElement) }
+    }
+}
+
+impl ToValue for Pad{
+    fn to_value(&self) -> Value{
+        unsafe{ object_to_value(self.gst_pad() as *mut c_void) }
+    }
+}
+
+impl FromValue for Pad{
+    fn from_value(value: &Value) -> Option<Pad>{
+        unsafe{ Pad::new(object_from_value(value, gst_pad_get_type()) as *mut GstPad) }
+    }
+}
+
+impl ToValue for Caps{
+    fn to_value(&self) -> Value{
+        let mut value = Value::new(unsafe{ gst_caps_get_type() });
+        unsafe{ g_value_set_boxed(&mut value.value, self.gst_caps() as gconstpointer) };
+        value
+    }
+}
+
+impl FromValue for Caps{
+    fn from_value(value: &Value) -> Option<Caps>{
+        unsafe{
+            if value.holds(gst_caps_get_type()){
+                Caps::new(g_value_dup_boxed(&value.value) as *mut GstCaps)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+impl ToValue for Buffer{
+    fn to_value(&self) -> Value{
+        let mut value = Value::new(unsafe{ gst_buffer_get_type() });
+        unsafe{ g_value_set_boxed(&mut value.value, self.gst_buffer() as gconstpointer) };
+        value
+    }
+}
+
+impl FromValue for Buffer{
+    fn from_value(value: &Value) -> Option<Buffer>{
+        unsafe{
+            if value.holds(gst_buffer_get_type()){
+                Buffer::new(g_value_dup_boxed(&value.value) as *mut GstBuffer)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+impl ToValue for Sample{
+    fn to_value(&self) -> Value{
+        let mut value = Value::new(unsafe{ gst_sample_get_type() });
+        unsafe{ g_value_set_boxed(&mut value.value, self.gst_sample() as gconstpointer) };
+        value
+    }
+}
+
+impl FromValue for Sample{
+    fn from_value(value: &Value) -> Option<Sample>{
+        unsafe{
+            if value.holds(gst_sample_get_type()){
+                Sample::new(g_value_dup_boxed(&value.value) as *mut GstSample)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+impl ToValue for ElementFactory{
+    fn to_value(&self) -> Value{
+        unsafe{ object_to_value(self.gst_elementfactory() as *mut c_void) }
+    }
+}
+
+impl FromValue for ElementFactory{
+    fn from_value(value: &Value) -> Option<ElementFactory>{
+        unsafe{ ElementFactory::new(object_from_value(value, gst_element_factory_get_type()) as *mut GstElementFactory) }
+    }
+}
+
+impl ToValue for Structure{
+    fn to_value(&self) -> Value{
+        let mut value = Value::new(unsafe{ gst_structure_get_type() });
+        unsafe{ g_value_set_boxed(&mut value.value, self.gst_structure() as gconstpointer) };
+        value
+    }
+}
+
+impl FromValue for Structure{
+    fn from_value(value: &Value) -> Option<Structure>{
+        unsafe{
+            if value.holds(gst_structure_get_type()){
+                Structure::new_owned(g_value_dup_boxed(&value.value) as *mut GstStructure)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+impl ToValue for Promise{
+    fn to_value(&self) -> Value{
+        // a promise can't exist without its type
+        let mut value = Value::new(promise_type().unwrap());
+        unsafe{ g_value_set_boxed(&mut value.value, self.gst_promise() as gconstpointer) };
+        value
+    }
+}
+
+impl FromValue for Promise{
+    fn from_value(value: &Value) -> Option<Promise>{
+        unsafe{
+            if promise_type().map(|ty| value.holds(ty)).unwrap_or(false){
+                Promise::new_from_gst_promise(g_value_dup_boxed(&value.value) as *mut GstPromise)
+            }else{
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use ffi::*;
+use element::Element;
+use buffer::Buffer;
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use handle::ProbeHandle;
+use reference::Reference;
+
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Wrapper for the valve element, which drops all the data going
+/// through it while closed without changing the state of the pipeline,
+/// eg: to pause the recording branch of a tee while the preview keeps
+/// playing:
+///
+/// ```ignore
+/// let mut valve = gst::valve::Valve::new_from_element(pipeline.get_by_name("record_valve").unwrap());
+/// valve.close();
+/// // later, resume the recording from the next keyframe
+/// let mut src = valve.static_pad("src").unwrap();
+/// let dropper = gst::valve::Dropper::until_keyframe(&mut src).unwrap();
+/// valve.open();
+/// ```
+///
+/// Events other than buffers still go through while closed, so the
+/// elements after the valve keep the right segment and caps.
+pub struct Valve{
+    valve: Element
+}
+
+impl Valve{
+    pub fn new(name: &str) -> Option<Valve>{
+        Element::new("valve", name).map(Valve::new_from_element)
+    }
+
+    /// Wraps an already existing valve, eg: retrieved by name from a
+    /// pipeline
+    pub fn new_from_element(element: Element) -> Valve{
+        Valve{ valve: element }
+    }
+
+    pub fn set_drop(&mut self, drop: bool){
+        self.set("drop", drop as gboolean);
+    }
+
+    pub fn is_dropping(&self) -> bool{
+        self.get::<gboolean>("drop") != 0
+    }
+
+    /// Starts dropping the data, same as set_drop(true)
+    pub fn close(&mut self){
+        self.set_drop(true);
+    }
+
+    /// Lets the data through again, same as set_drop(false)
+    pub fn open(&mut self){
+        self.set_drop(false);
+    }
+}
+
+impl Reference for Valve{
+    fn reference(&self) -> Valve{
+        Valve{ valve: self.valve.reference() }
+    }
+}
+
+impl AsRef<Element> for Valve{
+    fn as_ref(&self) -> &Element{
+        &self.valve
+    }
+}
+
+impl AsMut<Element> for Valve{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.valve
+    }
+}
+
+impl From<Valve> for Element{
+    fn from(v: Valve) -> Element{
+        v.valve
+    }
+}
+
+impl Deref for Valve{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.valve
+    }
+}
+
+impl DerefMut for Valve{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.valve
+    }
+}
+
+impl ::Transfer for Valve{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.valve.transfer()
+    }
+}
+
+/// Drops the buffers going through a pad for which a closure returns
+/// true, from the streaming thread. The probe is removed when the
+/// Dropper is dropped
+pub struct Dropper{
+    dropped: Arc<AtomicUsize>,
+    _probe: ProbeHandle,
+}
+
+impl Dropper{
+    pub fn install<F>(pad: &mut Pad, mut drop: F) -> Option<Dropper>
+        where F: FnMut(&Buffer) -> bool + Send + 'static{
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let probe_dropped = dropped.clone();
+        let probe = pad.add_probe(GST_PAD_PROBE_TYPE_BUFFER, move |_pad: &mut Pad, info: &mut PadProbeInfo| {
+            match info.buffer(){
+                Some(buffer) if drop(buffer) => {
+                    probe_dropped.fetch_add(1, Ordering::Relaxed);
+                    PadProbeReturn::Drop
+                }
+                _ => PadProbeReturn::Ok
+            }
+        })?;
+        Some(Dropper{
+            dropped: dropped,
+            _probe: probe,
+        })
+    }
+
+    /// Drops buffers until the first keyframe and lets everything
+    /// through after it, so a decoder or muxer after pad never starts
+    /// with frames it can't decode
+    pub fn until_keyframe(pad: &mut Pad) -> Option<Dropper>{
+        let mut waiting = true;
+        Dropper::install(pad, move |buffer| {
+            if waiting && buffer.flags() & GST_BUFFER_FLAG_DELTA_UNIT == 0{
+                waiting = false;
+            }
+            waiting
+        })
+    }
+
+    /// Number of buffers dropped so far
+    pub fn dropped(&self) -> usize{
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,144 @@
+use ffi::*;
+use util::*;
+
+use error::{Error, Result};
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// address of every symbol looked up so far, 0 if it wasn't found, so
+// the loaded libraries are searched once per symbol
+static SYMBOLS: Mutex<Option<HashMap<String, usize>>> = Mutex::new(None);
+
+/// Version of the GStreamer library in use as major, minor, micro and
+/// nano. nano is 1 for git builds and 2... for prereleases
+pub fn version() -> (u32, u32, u32, u32){
+    let (mut major, mut minor, mut micro, mut nano) = (0, 0, 0, 0);
+    unsafe{
+        gst_version(&mut major, &mut minor, &mut micro, &mut nano);
+    }
+    (major, minor, micro, nano)
+}
+
+/// Version of the GStreamer library in use as a string, eg:
+/// "GStreamer 1.16.2"
+pub fn version_string() -> String{
+    unsafe{
+        let version = gst_version_string();
+        let ret = from_c_str!(version).to_string();
+        g_free(version as gpointer);
+        ret
+    }
+}
+
+/// True if the GStreamer library in use is at least major.minor.micro
+pub fn check_version(major: u32, minor: u32, micro: u32) -> bool{
+    let (running_major, running_minor, running_micro, _) = version();
+    (running_major, running_minor, running_micro) >= (major, minor, micro)
+}
+
+// the version required at build time through the v1_* features, APIs
+// up to it don't need to ask the library
+fn required_version() -> (u32, u32, u32){
+    if cfg!(feature = "v1_18"){
+        (1, 18, 0)
+    }else if cfg!(feature = "v1_14"){
+        (1, 14, 0)
+    }else if cfg!(feature = "v1_10"){
+        (1, 10, 0)
+    }else if cfg!(feature = "v1_6"){
+        (1, 6, 0)
+    }else{
+        (1, 0, 0)
+    }
+}
+
+/// An API that only exists since some GStreamer version. Wrappers of
+/// newer APIs check it before calling them and return an unsupported
+/// error instead of failing to link, so the same binary runs against
+/// older and newer versions:
+///
+/// ```ignore
+/// match pipeline.set_latency(200_000_000){
+///     Err(ref err) if err.is_unsupported() => println!("{}", err),
+///     _ => (),
+/// }
+/// ```
+///
+/// Applications can check their own requirements the same way, eg:
+/// before using an element property added in a recent release.
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub struct ApiAvailability{
+    pub api: &'static str,
+    pub since: (u32, u32, u32),
+}
+
+impl ApiAvailability{
+    pub const fn new(api: &'static str, major: u32, minor: u32, micro: u32) -> ApiAvailability{
+        ApiAvailability{
+            api: api,
+            since: (major, minor, micro),
+        }
+    }
+
+    pub fn is_available(&self) -> bool{
+        required_version() >= self.since || check_version(self.since.0, self.since.1, self.since.2)
+    }
+
+    /// Ok if the API is available, an error for which is_unsupported
+    /// is true otherwise
+    pub fn check(&self) -> Result<()>{
+        if self.is_available(){
+            Ok(())
+        }else{
+            Err(Error::unsupported(self.api, self.since))
+        }
+    }
+
+    /// Looks up the C function called symbol once the API is known to
+    /// be available. Functions newer than the oldest supported version
+    /// can't be linked directly or the binary wouldn't load with older
+    /// versions, so they are resolved from the loaded libraries instead.
+    /// Same errors as check, also if the symbol can't be found
+    pub fn symbol(&self, symbol: &str) -> Result<gpointer>{
+        self.check()?;
+        let mut symbols = SYMBOLS.lock().unwrap_or_else(|e| e.into_inner());
+        let symbols = symbols.get_or_insert_with(HashMap::new);
+        let address = match symbols.get(symbol){
+            Some(&address) => address,
+            None => {
+                let address = unsafe{ lookup_symbol(symbol) } as usize;
+                symbols.insert(symbol.to_string(), address);
+                address
+            }
+        };
+        if address != 0{
+            Ok(address as gpointer)
+        }else{
+            Err(Error::unsupported(self.api, self.since))
+        }
+    }
+
+    /// symbol as a function of type F, eg:
+    /// `unsafe extern "C" fn() -> *mut GstPromise`, which has to match
+    /// the C declaration
+    pub unsafe fn function<F: Copy>(&self, symbol: &str) -> Result<F>{
+        assert_eq!(mem::size_of::<F>(), mem::size_of::<gpointer>());
+        self.symbol(symbol).map(|address| mem::transmute_copy(&address))
+    }
+}
+
+// searches the loaded libraries for symbol, null if it's not there
+unsafe fn lookup_symbol(symbol: &str) -> gpointer{
+    let name = CString::new(symbol).unwrap();
+    let module = g_module_open(ptr::null(), G_MODULE_BIND_LAZY);
+    if module == ptr::null_mut(){
+        return ptr::null_mut();
+    }
+    let mut address = ptr::null_mut();
+    if g_module_symbol(module, name.as_ptr(), &mut address) == 0{
+        address = ptr::null_mut();
+    }
+    g_module_close(module);
+    address
+}
@@ -3,6 +3,8 @@ use ffi::*;
 use std::mem;
 use std::slice::from_raw_parts;
 
+/// A mapped video buffer, not Send since the mapping belongs to the
+/// thread that mapped it
 pub struct VideoFrame{
     vf: GstVideoFrame,
     buffer: Buffer
@@ -72,7 +74,7 @@ impl<'a> VideoPlane<'a>{
 
     pub fn data<T:'a>(&self) -> &'a[T]{
         unsafe{
-	        from_raw_parts( mem::transmute(self.vf.data[self.p]), self.len::<T>())
+	        from_raw_parts( self.vf.data[self.p] as *const T, self.len::<T>())
 	    }
     }
 
@@ -117,7 +119,7 @@ impl<'a> VideoComponent<'a>{
     pub fn data<T:'a>(&self) -> &'a[T]{
         unsafe{
             let data = GST_VIDEO_FRAME_COMP_DATA!(self.vf,self.c);
-	        from_raw_parts( mem::transmute(data), self.len::<T>())
+	        from_raw_parts( data as *const T, self.len::<T>())
 	    }
     }
 
@@ -142,6 +144,31 @@ impl VideoFrame{
         }
     }
 
+    /// Maps a buffer in GL memory, eg: from glupload or a GL decoder, to
+    /// access the textures of its planes with texture_id. The buffer
+    /// should be waited on with gl::wait_sync before using them
+    #[cfg(feature = "gl")]
+    pub unsafe fn new_gl(mut vi: GstVideoInfo, mut buffer: Buffer) -> Option<VideoFrame>{
+        let mut gstframe = mem::zeroed();
+        if gst_video_frame_map(&mut gstframe, &mut vi, buffer.gst_buffer_mut(), GST_MAP_READ | GST_MAP_GL) != 0{
+            Some(VideoFrame{ vf: gstframe, buffer: buffer })
+        }else{
+        	None
+        }
+    }
+
+    /// The GL texture of plane for frames mapped with new_gl, None for
+    /// frames mapped in system memory or if the plane doesn't exist
+    #[cfg(feature = "gl")]
+    pub fn texture_id(&self, plane: u32) -> Option<u32>{
+        if plane >= self.n_planes() || self.vf.map[plane as usize].flags & GST_MAP_GL == 0{
+            return None;
+        }
+        unsafe{
+            Some(*(self.vf.data[plane as usize] as *const guint))
+        }
+    }
+
     #[inline]
     pub fn info(&self) -> &::VideoInfo{
         &self.vf.info
@@ -111,14 +111,14 @@ impl VideoInfo{
     }
 
     pub fn to_caps(&self) -> Option<::Caps>{
-        unsafe{ Caps::new(gst_video_info_to_caps(mem::transmute(self))) }
+        unsafe{ Caps::new(gst_video_info_to_caps(self as *const VideoInfo as *mut VideoInfo)) }
     }
 }
 
 impl PartialEq for VideoInfo{
     fn eq(&self, other: &VideoInfo) -> bool{
         unsafe{
-            gst_video_info_is_equal(mem::transmute(self), mem::transmute(other)) != 0
+            gst_video_info_is_equal(self, other) != 0
         }
     }
 }
@@ -0,0 +1,293 @@
+use ffi::*;
+use element::Element;
+use message::Message;
+use bus::{Bus, BusSyncReply};
+use reference::Reference;
+
+#[cfg(any(target_os="windows", target_os="macos"))]
+use std::os::raw::c_void;
+
+/// Access to the GstVideoOverlay interface implemented by video sinks
+/// that can render into a window provided by the application.
+pub struct VideoOverlay{
+    sink: Element
+}
+
+impl VideoOverlay{
+    /// Returns the video overlay interface of element. If the element is
+    /// a bin, like autovideosink, the first child implementing the
+    /// interface is returned, which for autovideosink is only available
+    /// once it's in READY state
+    pub fn new(element: &Element) -> Option<VideoOverlay>{
+        unsafe{
+            let instance = element.gst_element() as *mut GTypeInstance;
+            if g_type_check_instance_is_a(instance, gst_video_overlay_get_type()) != 0{
+                Some(VideoOverlay{ sink: element.reference() })
+            }else if g_type_check_instance_is_a(instance, gst_bin_get_type()) != 0{
+                let sink = gst_bin_get_by_interface(element.gst_element() as *mut GstBin, gst_video_overlay_get_type());
+                Element::new_from_gst_element(sink).map(|sink| VideoOverlay{ sink: sink })
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Creates the native video sink for the current platform:
+    /// d3d11videosink or d3dvideosink on windows, osxvideosink on
+    /// macOS and xvimagesink or ximagesink on linux. glimagesink is
+    /// used as fallback if none of those is available.
+    pub fn new_native_sink(name: &str) -> Option<Element>{
+        native_sinks().iter()
+            .filter_map(|factory| Element::new(factory, name))
+            .next()
+    }
+
+    /// Whether msg is the prepare-window-handle message a sink posts when
+    /// it needs a window, at which point set_window_handle has to be
+    /// called before the sink continues. It's posted from the streaming
+    /// thread so it has to be handled from a sync handler on the bus
+    pub fn is_prepare_window_handle(msg: &Message) -> bool{
+        unsafe{
+            gst_is_video_overlay_prepare_window_handle_message(msg.gst_message() as *mut GstMessage) != 0
+        }
+    }
+
+    /// The sink that posted msg if it's a prepare-window-handle message
+    pub fn from_prepare_window_handle(msg: &Message) -> Option<VideoOverlay>{
+        if !VideoOverlay::is_prepare_window_handle(msg){
+            return None;
+        }
+        unsafe{
+            let src = msg.src();
+            gst_object_ref(src as gpointer);
+            Element::new_from_gst_element(src as *mut GstElement).and_then(|sink| VideoOverlay::new(&sink))
+        }
+    }
+
+    /// Installs a sync handler on bus that gives handle to any sink
+    /// asking for a window, so the video is embedded in the application
+    /// window even for sinks created dynamically, eg: by playbin or
+    /// autovideosink:
+    ///
+    /// ```ignore
+    /// let mut bus = pipeline.bus().unwrap();
+    /// gst::VideoOverlay::set_window_handle_on_prepare(&mut bus, window.xid() as usize);
+    /// pipeline.play();
+    /// ```
+    ///
+    /// Replaces any other sync handler on the bus
+    pub fn set_window_handle_on_prepare(bus: &mut Bus, handle: usize){
+        bus.set_sync_handler(move |msg: &Message| {
+            match VideoOverlay::from_prepare_window_handle(msg){
+                Some(mut overlay) => {
+                    overlay.set_window_handle(handle);
+                    BusSyncReply::Drop
+                }
+                None => BusSyncReply::Pass,
+            }
+        });
+    }
+
+    /// Tells the sink to render into the window identified by handle, a
+    /// XID on X11, a HWND on windows or an NSView on macOS.
+    ///
+    /// Should be called before the sink goes to READY or when the sink
+    /// posts a prepare-window-handle message.
+    pub fn set_window_handle(&mut self, handle: usize){
+        unsafe{
+            gst_video_overlay_set_window_handle(self.gst_videooverlay_mut(), handle as guintptr)
+        }
+    }
+
+    /// Renders into the window with the passed HWND
+    #[cfg(target_os="windows")]
+    pub fn set_hwnd(&mut self, hwnd: *mut c_void){
+        self.set_window_handle(hwnd as usize)
+    }
+
+    /// Renders into the passed NSView
+    #[cfg(target_os="macos")]
+    pub fn set_nsview(&mut self, nsview: *mut c_void){
+        self.set_window_handle(nsview as usize)
+    }
+
+    /// Renders into the X11 window with the passed XID
+    #[cfg(target_os="linux")]
+    pub fn set_xid(&mut self, xid: u64){
+        self.set_window_handle(xid as usize)
+    }
+
+    /// Returns the CALayer caopengllayersink renders to so it can be
+    /// added to the application's layer hierarchy
+    #[cfg(target_os="macos")]
+    pub fn calayer(&self) -> Option<*mut c_void>{
+        use util::*;
+        let cname = CString::new("layer").unwrap();
+        unsafe{
+            let mut layer: gpointer = ptr::null_mut();
+            g_object_get(self.sink.gst_element() as *mut c_void, cname.as_ptr(), &mut layer, ptr::null::<gchar>());
+            if layer != ptr::null_mut(){
+                Some(layer as *mut c_void)
+            }else{
+                None
+            }
+        }
+    }
+
+    /// Sets the area of the window the video will be rendered to, by
+    /// default the whole window is used
+    pub fn set_render_rectangle(&mut self, x: i32, y: i32, width: i32, height: i32) -> bool{
+        unsafe{
+            gst_video_overlay_set_render_rectangle(self.gst_videooverlay_mut(), x, y, width, height) != 0
+        }
+    }
+
+    /// Asks the sink to redraw the last frame, should be called when
+    /// the window is exposed or resized
+    pub fn expose(&mut self){
+        unsafe{
+            gst_video_overlay_expose(self.gst_videooverlay_mut())
+        }
+    }
+
+    /// Enables or disables the sink handling mouse and keyboard events
+    /// from the window
+    pub fn handle_events(&mut self, handle_events: bool){
+        unsafe{
+            gst_video_overlay_handle_events(self.gst_videooverlay_mut(), handle_events as gboolean)
+        }
+    }
+
+    pub fn element(&self) -> &Element{
+        &self.sink
+    }
+
+    pub unsafe fn gst_videooverlay(&self) -> *const GstVideoOverlay{
+        self.sink.gst_element() as *const GstVideoOverlay
+    }
+
+    pub unsafe fn gst_videooverlay_mut(&mut self) -> *mut GstVideoOverlay{
+        self.sink.gst_element_mut() as *mut GstVideoOverlay
+    }
+}
+
+impl Reference for VideoOverlay{
+    fn reference(&self) -> VideoOverlay{
+        VideoOverlay{ sink: self.sink.reference() }
+    }
+}
+
+#[cfg(target_os="windows")]
+fn native_sinks() -> &'static [&'static str]{
+    &["d3d11videosink", "d3dvideosink", "glimagesink"]
+}
+
+#[cfg(target_os="macos")]
+fn native_sinks() -> &'static [&'static str]{
+    &["osxvideosink", "glimagesink"]
+}
+
+#[cfg(not(any(target_os="windows", target_os="macos")))]
+fn native_sinks() -> &'static [&'static str]{
+    &["xvimagesink", "ximagesink", "glimagesink"]
+}
+
+/// The native video sink of the platform, already rendering into a
+/// window created with any windowing library that exposes a
+/// raw-window-handle, eg: winit:
+///
+/// ```ignore
+/// let handle = window.raw_window_handle();
+/// let sink = gst::NativeVideoSink::new("videosink", handle).unwrap();
+/// playbin.set_video_sink(&sink);
+/// ```
+///
+/// The sink is chosen from the kind of handle: d3d11videosink or
+/// d3dvideosink for win32 windows, osxvideosink for AppKit views and
+/// xvimagesink or ximagesink for X11 windows, falling back to
+/// glimagesink. Wayland surfaces need the display too and aren't
+/// supported.
+#[cfg(feature = "raw-window-handle")]
+pub struct NativeVideoSink{
+    overlay: VideoOverlay
+}
+
+#[cfg(feature = "raw-window-handle")]
+impl NativeVideoSink{
+    pub fn new(name: &str, handle: ::raw_window_handle::RawWindowHandle) -> Option<NativeVideoSink>{
+        use raw_window_handle::RawWindowHandle;
+        let (factories, handle): (&[&str], usize) = match handle{
+            RawWindowHandle::Win32(handle) => (&["d3d11videosink", "d3dvideosink", "glimagesink"], handle.hwnd as usize),
+            RawWindowHandle::AppKit(handle) => (&["osxvideosink", "glimagesink"], handle.ns_view as usize),
+            RawWindowHandle::Xlib(handle) => (&["xvimagesink", "ximagesink", "glimagesink"], handle.window as usize),
+            RawWindowHandle::Xcb(handle) => (&["xvimagesink", "ximagesink", "glimagesink"], handle.window as usize),
+            _ => return None
+        };
+        if handle == 0{
+            return None;
+        }
+        let sink = factories.iter()
+            .filter_map(|factory| Element::new(factory, name))
+            .next()?;
+        let mut overlay = VideoOverlay::new(&sink)?;
+        // before READY so the sink never opens its own window
+        overlay.set_window_handle(handle);
+        Some(NativeVideoSink{ overlay: overlay })
+    }
+
+    /// The overlay interface of the sink, eg: to set the render
+    /// rectangle or expose when the window is resized
+    pub fn overlay(&mut self) -> &mut VideoOverlay{
+        &mut self.overlay
+    }
+}
+
+#[cfg(feature = "raw-window-handle")]
+impl Reference for NativeVideoSink{
+    fn reference(&self) -> NativeVideoSink{
+        NativeVideoSink{ overlay: self.overlay.reference() }
+    }
+}
+
+#[cfg(feature = "raw-window-handle")]
+impl AsRef<Element> for NativeVideoSink{
+    fn as_ref(&self) -> &Element{
+        &self.overlay.sink
+    }
+}
+
+#[cfg(feature = "raw-window-handle")]
+impl AsMut<Element> for NativeVideoSink{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.overlay.sink
+    }
+}
+
+#[cfg(feature = "raw-window-handle")]
+impl From<NativeVideoSink> for Element{
+    fn from(s: NativeVideoSink) -> Element{
+        s.overlay.sink
+    }
+}
+
+#[cfg(feature = "raw-window-handle")]
+impl ::std::ops::Deref for NativeVideoSink{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.overlay.sink
+    }
+}
+
+#[cfg(feature = "raw-window-handle")]
+impl ::std::ops::DerefMut for NativeVideoSink{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.overlay.sink
+    }
+}
+
+#[cfg(feature = "raw-window-handle")]
+impl ::Transfer for NativeVideoSink{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.overlay.sink.transfer()
+    }
+}
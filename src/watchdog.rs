@@ -0,0 +1,312 @@
+use ffi::*;
+use util::*;
+
+use pad::{Pad, PadProbeInfo, PadProbeReturn};
+use pipeline::Pipeline;
+use message::Message;
+use structure::Structure;
+use handle::ProbeHandle;
+use reference::Reference;
+use panic::catch_panic;
+
+use std::sync::{Arc, Mutex, Condvar};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+static WATCHDOG_ERROR_QUARK: &'static str = "gstreamer1.0-rs-watchdog-error-quark";
+
+/// Details of a stall detected by a Watchdog
+#[derive(Clone,Debug,PartialEq)]
+pub struct Stalled{
+    /// Path of the watched pad, eg: "/pipeline0/rtspsrc0/recv_rtp_src_0_96:src"
+    pub pad: String,
+    /// Time since the last buffer when the stall was detected
+    pub elapsed: Duration,
+}
+
+impl Stalled{
+    /// The stall reported by an error message posted by a Watchdog,
+    /// None for any other message
+    pub fn from_message(msg: &Message) -> Option<Stalled>{
+        unsafe{
+            if msg.ty() != GST_MESSAGE_ERROR{
+                return None;
+            }
+            let details = match ERROR_DETAILS.function::<unsafe extern "C" fn(*mut GstMessage, *mut *const GstStructure)>("gst_message_parse_error_details"){
+                Ok(parse_error_details) => {
+                    let mut details: *const GstStructure = ptr::null();
+                    parse_error_details(msg.gst_message() as *mut GstMessage, &mut details);
+                    Structure::new_from_gst_structure(details as *mut GstStructure)?.copy()
+                }
+                Err(_) => {
+                    let mut error: *mut GError = ptr::null_mut();
+                    let mut debug: *mut gchar = ptr::null_mut();
+                    gst_message_parse_error(msg.gst_message() as *mut GstMessage, &mut error, &mut debug);
+                    let details = if error != ptr::null_mut() && (*error).domain == error_domain() && debug != ptr::null_mut(){
+                        Structure::from_string(&CStr::from_ptr(debug).to_string_lossy())
+                    }else{
+                        None
+                    };
+                    if error != ptr::null_mut(){
+                        g_error_free(error);
+                    }
+                    g_free(debug as gpointer);
+                    details?
+                }
+            };
+            if details.name() != "stalled"{
+                return None;
+            }
+            Some(Stalled{
+                pad: details.get_string("pad")?.to_string(),
+                elapsed: Duration::from_millis(details.get::<u64>("elapsed-ms")?),
+            })
+        }
+    }
+}
+
+/// The GError domain of the errors posted by a Watchdog
+pub fn error_domain() -> GQuark{
+    let cname = CString::new(WATCHDOG_ERROR_QUARK).unwrap();
+    unsafe{
+        g_quark_from_string(cname.as_ptr())
+    }
+}
+
+struct Inner{
+    last_buffer: Option<Instant>,
+    timeout: Duration,
+    stalled: bool,
+    stalls: u64,
+    post_error: bool,
+    stop: bool,
+    restart: Option<Pipeline>,
+    on_stalled: Option<Box<dyn FnMut(&Stalled) + Send>>,
+    on_recovered: Option<Box<dyn FnMut() + Send>>,
+}
+
+struct Shared{
+    inner: Mutex<Inner>,
+    cond: Condvar,
+}
+
+/// Watches the buffers going through a pad and reports a stall when
+/// none passes for longer than a timeout, eg: a network source that
+/// stopped receiving data without an error:
+///
+/// ```ignore
+/// let mut pad = depay.static_pad("src").unwrap();
+/// let watchdog = gst::watchdog::Watchdog::attach(&mut pad, Duration::from_secs(5)).unwrap();
+/// watchdog.set_restart(pipeline.reference());
+/// // in the bus watch
+/// if let Some(stalled) = gst::watchdog::Stalled::from_message(&msg){
+///     println!("no data on {} for {:?}", stalled.pad, stalled.elapsed);
+/// }
+/// ```
+///
+/// The timer starts with the first buffer, or when arm() is called, so
+/// a pipeline that takes time to connect isn't reported. On a stall an
+/// error is posted on behalf of the element of the pad, with its
+/// details readable with Stalled::from_message, and the stalled handler
+/// and restart run from the watchdog thread. Once buffers flow again
+/// the recovered handler is called and the watchdog can fire again.
+pub struct Watchdog{
+    shared: Arc<Shared>,
+    _probe: ProbeHandle,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog{
+    pub fn attach(pad: &mut Pad, timeout: Duration) -> Option<Watchdog>{
+        let shared = Arc::new(Shared{
+            inner: Mutex::new(Inner{
+                last_buffer: None,
+                timeout: timeout,
+                stalled: false,
+                stalls: 0,
+                post_error: true,
+                stop: false,
+                restart: None,
+                on_stalled: None,
+                on_recovered: None,
+            }),
+            cond: Condvar::new(),
+        });
+        let probe_shared = shared.clone();
+        let mask = GST_PAD_PROBE_TYPE_BUFFER | GST_PAD_PROBE_TYPE_BUFFER_LIST;
+        let probe = pad.add_probe(mask, move |_pad: &mut Pad, _info: &mut PadProbeInfo| {
+            let mut on_recovered = {
+                let mut inner = probe_shared.inner.lock().unwrap_or_else(|e| e.into_inner());
+                let was_armed = inner.last_buffer.is_some();
+                inner.last_buffer = Some(Instant::now());
+                if !was_armed || inner.stalled{
+                    probe_shared.cond.notify_all();
+                }
+                if !inner.stalled{
+                    return PadProbeReturn::Ok;
+                }
+                inner.stalled = false;
+                inner.on_recovered.take()
+            };
+            // without the lock so the handler can use the watchdog
+            if let Some(ref mut handler) = on_recovered{
+                catch_panic(ptr::null_mut(), (), || handler());
+            }
+            let mut inner = probe_shared.inner.lock().unwrap_or_else(|e| e.into_inner());
+            if inner.on_recovered.is_none(){
+                inner.on_recovered = on_recovered;
+            }
+            PadProbeReturn::Ok
+        })?;
+        let pad_ptr = unsafe{
+            gst_object_ref(pad.gst_pad() as gpointer);
+            pad.gst_pad() as usize
+        };
+        let thread_shared = shared.clone();
+        let thread = thread::spawn(move || watch(thread_shared, pad_ptr));
+        Some(Watchdog{
+            shared: shared,
+            _probe: probe,
+            thread: Some(thread),
+        })
+    }
+
+    /// Starts the timer now instead of waiting for the first buffer,
+    /// eg: right after setting the pipeline to playing
+    pub fn arm(&self){
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).last_buffer = Some(Instant::now());
+        self.shared.cond.notify_all();
+    }
+
+    /// Stops the timer until the next buffer or arm(), eg: when the
+    /// pipeline is paused on purpose
+    pub fn disarm(&self){
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).last_buffer = None;
+        self.shared.cond.notify_all();
+    }
+
+    pub fn set_timeout(&self, timeout: Duration){
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).timeout = timeout;
+        self.shared.cond.notify_all();
+    }
+
+    /// Whether to post an error on the bus on stalls, true by default.
+    /// Applications that handle stalls only from the handlers can
+    /// disable it so their bus watch doesn't treat it as fatal
+    pub fn set_post_error(&self, post: bool){
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).post_error = post;
+    }
+
+    /// Restarts pipeline on stalls, taking it to NULL and back to the
+    /// state it was in. The watchdog is disarmed until the next buffer
+    pub fn set_restart(&self, pipeline: Pipeline){
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).restart = Some(pipeline);
+    }
+
+    /// Calls handler on every stall, from the watchdog thread so it can
+    /// change the state of the pipeline
+    pub fn connect_stalled<F: FnMut(&Stalled) + Send + 'static>(&self, handler: F){
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).on_stalled = Some(Box::new(handler));
+    }
+
+    /// Calls handler when buffers flow again after a stall, from the
+    /// streaming thread
+    pub fn connect_recovered<F: FnMut() + Send + 'static>(&self, handler: F){
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).on_recovered = Some(Box::new(handler));
+    }
+
+    pub fn is_stalled(&self) -> bool{
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).stalled
+    }
+
+    /// Number of stalls detected so far
+    pub fn stalls(&self) -> u64{
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).stalls
+    }
+}
+
+impl Drop for Watchdog{
+    fn drop(&mut self){
+        self.shared.inner.lock().unwrap_or_else(|e| e.into_inner()).stop = true;
+        self.shared.cond.notify_all();
+        if let Some(thread) = self.thread.take(){
+            let _ = thread.join();
+        }
+    }
+}
+
+// runs in the watchdog thread, pad is a *mut GstPad with a reference
+// that's released when it finishes
+fn watch(shared: Arc<Shared>, pad: usize){
+    let pad = pad as *mut GstPad;
+    let pad_path = unsafe{
+        let path = gst_object_get_path_string(pad as *mut GstObject);
+        let ret = from_c_str!(path).to_string();
+        g_free(path as gpointer);
+        ret
+    };
+    let mut inner = shared.inner.lock().unwrap_or_else(|e| e.into_inner());
+    while !inner.stop{
+        let elapsed = match inner.last_buffer{
+            Some(last_buffer) if !inner.stalled => last_buffer.elapsed(),
+            _ => {
+                inner = shared.cond.wait(inner).unwrap_or_else(|e| e.into_inner());
+                continue;
+            }
+        };
+        if elapsed < inner.timeout{
+            let wait = inner.timeout - elapsed;
+            inner = shared.cond.wait_timeout(inner, wait).unwrap_or_else(|e| e.into_inner()).0;
+            continue;
+        }
+        inner.stalled = true;
+        inner.stalls += 1;
+        let stalled = Stalled{ pad: pad_path.clone(), elapsed: elapsed };
+        if inner.post_error{
+            post_stalled(pad, &stalled);
+        }
+        let mut on_stalled = inner.on_stalled.take();
+        let restart = inner.restart.as_ref().map(|pipeline| pipeline.reference());
+        drop(inner);
+        // without the lock so the handlers can use the watchdog
+        if let Some(ref mut on_stalled) = on_stalled{
+            catch_panic(ptr::null_mut(), (), || on_stalled(&stalled));
+        }
+        if let Some(mut pipeline) = restart{
+            restart_pipeline(&mut pipeline);
+        }
+        inner = shared.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if inner.on_stalled.is_none(){
+            inner.on_stalled = on_stalled;
+        }
+        if inner.restart.is_some(){
+            inner.last_buffer = None;
+        }
+    }
+    unsafe{
+        gst_object_unref(pad as gpointer);
+    }
+}
+
+fn post_stalled(pad: *mut GstPad, stalled: &Stalled){
+    let elapsed_ms = stalled.elapsed.as_secs() * 1000 + stalled.elapsed.subsec_nanos() as u64 / 1_000_000;
+    let mut details = Structure::new("stalled");
+    details.set("pad", &stalled.pad);
+    details.set("elapsed-ms", &elapsed_ms);
+    unsafe{
+        let element = gst_pad_get_parent_element(pad);
+        if element == ptr::null_mut(){
+            return;
+        }
+        post_element_error(element, error_domain(), 0, &format!("No data on {} for {}ms", stalled.pad, elapsed_ms),
+            Some(&details), file!(), "post_stalled", line!());
+        gst_object_unref(element as gpointer);
+    }
+}
+
+fn restart_pipeline(pipeline: &mut Pipeline){
+    let (current, pending, _) = pipeline.get_state(0);
+    let target = if pending != GST_STATE_VOID_PENDING { pending } else { current };
+    pipeline.set_state(GST_STATE_NULL);
+    pipeline.set_state(target);
+}
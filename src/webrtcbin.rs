@@ -0,0 +1,765 @@
+use ffi::*;
+use element::Element;
+use pad::Pad;
+use caps::Caps;
+use object::Object;
+use promise::{Promise, PromiseResult, promise_type};
+use structure::Structure;
+use reference::Reference;
+use handle::SignalHandle;
+use sdp::SdpMessage;
+use value::{Value, ToValue, FromValue};
+use util::*;
+
+use std::ops::{Deref, DerefMut};
+
+/// Wrapper for webrtcbin, the element implementing a WebRTC peer
+/// connection.
+///
+/// The negotiation follows the JavaScript RTCPeerConnection API, the
+/// signaling between the peers is left to the application:
+///
+/// ```ignore
+/// let mut webrtc = gst::WebRtcBin::new("webrtc").unwrap();
+/// let mut offerer = webrtc.reference();
+/// webrtc.connect_on_negotiation_needed(move || {
+///     let mut webrtc = offerer.reference();
+///     offerer.create_offer_async(move |offer| {
+///         let offer = offer.unwrap();
+///         webrtc.set_local_description(&offer);
+///         signaling.send_sdp(&offer.sdp.as_text());
+///     });
+/// }).unwrap().forget();
+/// webrtc.connect_on_ice_candidate(move |mline_index, candidate| {
+///     signaling.send_candidate(mline_index, candidate);
+/// }).unwrap().forget();
+/// ```
+pub struct WebRtcBin{
+    webrtcbin: Element
+}
+
+impl WebRtcBin{
+    /// Creates a new webrtcbin element with the given name
+    pub fn new(name: &str) -> Option<WebRtcBin>{
+        Element::new("webrtcbin", name).map(WebRtcBin::new_from_element)
+    }
+
+    /// Wraps an already existing webrtcbin, for example one retrieved by
+    /// name from a pipeline
+    pub fn new_from_element(element: Element) -> WebRtcBin{
+        WebRtcBin{ webrtcbin: element }
+    }
+
+    /// Sets the STUN server used to discover the public address, eg:
+    /// set_stun_server("stun.l.google.com", 19302)
+    pub fn set_stun_server(&mut self, host: &str, port: u16){
+        self.set("stun-server", &format!("stun://{}:{}", host, port)[..]);
+    }
+
+    /// Sets the TURN server used to relay the media when no direct
+    /// connection is possible
+    pub fn set_turn_server(&mut self, server: &TurnServer){
+        self.set("turn-server", &server.to_uri()[..]);
+    }
+
+    /// Adds a TURN server to the ones set with set_turn_server, returns
+    /// false if the uri is not valid or this version of webrtcbin
+    /// doesn't support several servers
+    pub fn add_turn_server(&mut self, server: &TurnServer) -> bool{
+        self.emit_with_values("add-turn-server", &[Value::from(&server.to_uri()[..])])
+            .and_then(|ret| ret.get::<bool>())
+            .unwrap_or(false)
+    }
+
+    pub fn set_bundle_policy(&mut self, policy: BundlePolicy){
+        self.set_from_str("bundle-policy", policy.nick());
+    }
+
+    pub fn set_ice_transport_policy(&mut self, policy: IceTransportPolicy){
+        self.set_from_str("ice-transport-policy", policy.nick());
+    }
+
+    /// Adds a transceiver with the given direction, caps restricts the
+    /// codecs that can be negotiated for it. Returns the transceiver so
+    /// its direction can be changed later with set_transceiver_direction
+    pub fn add_transceiver(&mut self, direction: TransceiverDirection, caps: Option<&Caps>) -> Option<Object>{
+        let direction = match Value::from_enum_nick("GstWebRTCRTPTransceiverDirection", direction.nick()){
+            Some(direction) => direction,
+            None => return None,
+        };
+        let caps = match caps{
+            Some(caps) => Value::from(caps),
+            None => Value::new(unsafe{ gst_caps_get_type() }),
+        };
+        self.emit_with_values("add-transceiver", &[direction, caps])
+            .and_then(|ret| ret.get::<Object>())
+    }
+
+    /// Returns the transceivers of the connection, one per media line
+    pub fn transceivers(&mut self) -> Vec<Object>{
+        let mut transceivers = vec![];
+        while let Some(transceiver) = self.emit_with_values("get-transceiver", &[Value::from(transceivers.len() as i32)])
+                .and_then(|ret| ret.get::<Object>()){
+            transceivers.push(transceiver);
+        }
+        transceivers
+    }
+
+    /// Creates an offer to be sent to the remote peer, usually from the
+    /// "on-negotiation-needed" handler. Blocks until webrtcbin replies
+    pub fn create_offer(&mut self) -> Option<SessionDescription>{
+        self.create_description("create-offer", "offer")
+    }
+
+    /// Same as create_offer but f is called with the offer once it's
+    /// ready, from webrtcbin's thread
+    pub fn create_offer_async<F: FnOnce(Option<SessionDescription>) + Send + 'static>(&mut self, f: F) -> bool{
+        self.create_description_async("create-offer", "offer", f)
+    }
+
+    /// Creates an answer to the offer set with set_remote_description.
+    /// Blocks until webrtcbin replies
+    pub fn create_answer(&mut self) -> Option<SessionDescription>{
+        self.create_description("create-answer", "answer")
+    }
+
+    /// Same as create_answer but f is called with the answer once it's
+    /// ready, from webrtcbin's thread
+    pub fn create_answer_async<F: FnOnce(Option<SessionDescription>) + Send + 'static>(&mut self, f: F) -> bool{
+        self.create_description_async("create-answer", "answer", f)
+    }
+
+    fn create_description(&mut self, signal: &str, field: &str) -> Option<SessionDescription>{
+        let promise = Promise::new().ok()?;
+        if !self.emit_with_promise(signal, &[Value::new(unsafe{ gst_structure_get_type() })], &promise){
+            return None;
+        }
+        match promise.wait(){
+            Ok(PromiseResult::Replied) => promise.get_reply_field::<SessionDescription>(field),
+            _ => None,
+        }
+    }
+
+    fn create_description_async<F>(&mut self, signal: &str, field: &str, f: F) -> bool
+        where F: FnOnce(Option<SessionDescription>) + Send + 'static{
+        let mut f = Some(f);
+        let field = field.to_string();
+        let promise = match Promise::new_with_change_func(move |promise| {
+            if let Some(f) = f.take(){
+                f(promise.get_reply_field::<SessionDescription>(&field))
+            }
+        }){
+            Ok(promise) => promise,
+            Err(_) => return false,
+        };
+        self.emit_with_promise(signal, &[Value::new(unsafe{ gst_structure_get_type() })], &promise)
+    }
+
+    /// Sets the description of this end of the connection, the offer or
+    /// answer created with create_offer or create_answer
+    pub fn set_local_description(&mut self, description: &SessionDescription){
+        self.emit_with_values("set-local-description", &[description.to_value(), no_promise()]);
+    }
+
+    /// Sets the description received from the remote peer
+    pub fn set_remote_description(&mut self, description: &SessionDescription){
+        self.emit_with_values("set-remote-description", &[description.to_value(), no_promise()]);
+    }
+
+    pub fn local_description(&self) -> Option<SessionDescription>{
+        self.get_value("local-description").and_then(|value| value.get::<SessionDescription>())
+    }
+
+    pub fn remote_description(&self) -> Option<SessionDescription>{
+        self.get_value("remote-description").and_then(|value| value.get::<SessionDescription>())
+    }
+
+    /// Adds an ICE candidate received from the remote peer for the media
+    /// line mline_index, candidate is the "candidate:..." line
+    pub fn add_ice_candidate(&mut self, mline_index: u32, candidate: &str){
+        self.emit_with_values("add-ice-candidate", &[Value::from(mline_index), Value::from(candidate)]);
+    }
+
+    /// Calls handler when a new negotiation is needed, eg: after adding
+    /// a transceiver or linking a new sink pad. The offerer should
+    /// create and send an offer from it
+    pub fn connect_on_negotiation_needed<F>(&mut self, mut handler: F) -> Option<SignalHandle>
+        where F: FnMut() + Send + 'static{
+        self.connect_values("on-negotiation-needed", move |_| {
+            handler();
+            None
+        })
+    }
+
+    /// Calls handler with every local ICE candidate found, with the
+    /// index of its media line, they have to be sent to the remote peer
+    pub fn connect_on_ice_candidate<F>(&mut self, mut handler: F) -> Option<SignalHandle>
+        where F: FnMut(u32, &str) + Send + 'static{
+        self.connect_values("on-ice-candidate", move |args| {
+            let mline_index = args.get(1).and_then(|mline| mline.get::<u32>());
+            let candidate = args.get(2).and_then(|candidate| candidate.get::<String>());
+            if let (Some(mline_index), Some(candidate)) = (mline_index, candidate){
+                handler(mline_index, &candidate);
+            }
+            None
+        })
+    }
+
+    /// Returns the statistics of the whole connection or, if pad is
+    /// passed, only the ones related to the stream going through it.
+    ///
+    /// Blocks until webrtcbin replies, which it does from its own thread,
+    /// so it can be called from the main loop. Use get_stats_async to
+    /// avoid blocking.
+    pub fn get_stats(&mut self, pad: Option<&Pad>) -> Option<WebRtcStats>{
+        let promise = Promise::new().ok()?;
+        if !self.emit_get_stats(pad, &promise){
+            return None;
+        }
+        promise.wait_reply().map(WebRtcStats::new)
+    }
+
+    /// Same as get_stats but f is called with the statistics once they
+    /// are ready, from webrtcbin's thread
+    pub fn get_stats_async<F: FnOnce(Option<WebRtcStats>) + Send + 'static>(&mut self, pad: Option<&Pad>, f: F) -> bool{
+        let mut f = Some(f);
+        let promise = match Promise::new_with_change_func(move |promise| {
+            if let Some(f) = f.take(){
+                f(promise.get_reply().map(WebRtcStats::new))
+            }
+        }){
+            Ok(promise) => promise,
+            Err(_) => return false,
+        };
+        self.emit_get_stats(pad, &promise)
+    }
+
+    fn emit_get_stats(&mut self, pad: Option<&Pad>, promise: &Promise) -> bool{
+        let pad = match pad{
+            Some(pad) => Value::from(pad),
+            None => Value::new(unsafe{ gst_pad_get_type() }),
+        };
+        self.emit_with_promise("get-stats", &[pad], promise)
+    }
+
+    /// Emits an action signal whose last argument is a promise webrtcbin
+    /// replies to, false if the signal doesn't exist
+    fn emit_with_promise(&mut self, signal: &str, args: &[Value], promise: &Promise) -> bool{
+        // the signals have no return value so emit_with_values can't tell
+        // if they exist, waiting on a promise nobody replies would block
+        // forever
+        let csignal = CString::new(signal).unwrap();
+        unsafe{
+            let gtype = (*(*(self.gst_element() as *mut GTypeInstance)).g_class).g_type;
+            if g_signal_lookup(csignal.as_ptr(), gtype) == 0{
+                return false;
+            }
+        }
+        let mut args = args.to_vec();
+        args.push(Value::from(promise));
+        self.emit_with_values(signal, &args);
+        true
+    }
+}
+
+// a NULL promise for the signals that take an optional one, webrtcbin
+// needs GStreamer 1.14 so the type is always there
+fn no_promise() -> Value{
+    Value::new(promise_type().unwrap())
+}
+
+/// Changes the direction of a transceiver returned by add_transceiver
+/// or webrtcbin's "get-transceivers"
+pub fn set_transceiver_direction(transceiver: &mut Object, direction: TransceiverDirection){
+    transceiver.set_from_str("direction", direction.nick());
+}
+
+/// Kind of a session description, mirrors GstWebRTCSDPType
+#[repr(u32)]
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum SdpType{
+    Offer = GST_WEBRTC_SDP_TYPE_OFFER,
+    Pranswer = GST_WEBRTC_SDP_TYPE_PRANSWER,
+    Answer = GST_WEBRTC_SDP_TYPE_ANSWER,
+    Rollback = GST_WEBRTC_SDP_TYPE_ROLLBACK,
+}
+
+impl SdpType{
+    fn from_gst_type(sdp_type: GstWebRTCSDPType) -> SdpType{
+        match sdp_type{
+            GST_WEBRTC_SDP_TYPE_PRANSWER => SdpType::Pranswer,
+            GST_WEBRTC_SDP_TYPE_ANSWER => SdpType::Answer,
+            GST_WEBRTC_SDP_TYPE_ROLLBACK => SdpType::Rollback,
+            _ => SdpType::Offer,
+        }
+    }
+
+    /// Name of the type as used in the JavaScript API, eg: "offer"
+    pub fn name(self) -> &'static str{
+        match self{
+            SdpType::Offer => "offer",
+            SdpType::Pranswer => "pranswer",
+            SdpType::Answer => "answer",
+            SdpType::Rollback => "rollback",
+        }
+    }
+}
+
+/// An offer or answer exchanged with the remote peer, mirrors
+/// GstWebRTCSessionDescription. The remote one is usually built from
+/// the received text:
+///
+/// ```ignore
+/// let sdp = gst::SdpMessage::parse(&text).unwrap();
+/// webrtc.set_remote_description(&gst::webrtcbin::SessionDescription::new(gst::webrtcbin::SdpType::Answer, sdp));
+/// ```
+#[derive(Clone)]
+pub struct SessionDescription{
+    pub sdp_type: SdpType,
+    pub sdp: SdpMessage,
+}
+
+impl SessionDescription{
+    pub fn new(sdp_type: SdpType, sdp: SdpMessage) -> SessionDescription{
+        SessionDescription{
+            sdp_type: sdp_type,
+            sdp: sdp,
+        }
+    }
+
+    /// Copies description
+    pub unsafe fn new_from_gst_session_description(description: *const GstWebRTCSessionDescription) -> Option<SessionDescription>{
+        if description == ptr::null(){
+            return None;
+        }
+        let mut sdp = ptr::null_mut();
+        gst_sdp_message_copy((*description).sdp, &mut sdp);
+        SdpMessage::new_from_gst_sdp_message(sdp)
+            .map(|sdp| SessionDescription::new(SdpType::from_gst_type((*description)._type), sdp))
+    }
+}
+
+impl ToValue for SessionDescription{
+    fn to_value(&self) -> Value{
+        unsafe{
+            let mut value = Value::new(gst_webrtc_session_description_get_type());
+            let description = gst_webrtc_session_description_new(self.sdp_type as GstWebRTCSDPType, self.sdp.clone().into_gst_sdp_message());
+            g_value_take_boxed(value.gst_value_mut(), description as gconstpointer);
+            value
+        }
+    }
+}
+
+impl FromValue for SessionDescription{
+    fn from_value(value: &Value) -> Option<SessionDescription>{
+        unsafe{
+            if value.holds(gst_webrtc_session_description_get_type()){
+                SessionDescription::new_from_gst_session_description(g_value_get_boxed(value.gst_value()) as *const GstWebRTCSessionDescription)
+            }else{
+                None
+            }
+        }
+    }
+}
+
+/// How media streams are grouped on transports, mirrors
+/// GstWebRTCBundlePolicy
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum BundlePolicy{
+    None,
+    Balanced,
+    MaxCompat,
+    MaxBundle,
+}
+
+impl BundlePolicy{
+    pub fn nick(self) -> &'static str{
+        match self{
+            BundlePolicy::None => "none",
+            BundlePolicy::Balanced => "balanced",
+            BundlePolicy::MaxCompat => "max-compat",
+            BundlePolicy::MaxBundle => "max-bundle",
+        }
+    }
+}
+
+/// Which ICE candidates can be used, mirrors GstWebRTCICETransportPolicy
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum IceTransportPolicy{
+    All,
+    /// Only use TURN relays, hides the local addresses from the peer
+    Relay,
+}
+
+impl IceTransportPolicy{
+    pub fn nick(self) -> &'static str{
+        match self{
+            IceTransportPolicy::All => "all",
+            IceTransportPolicy::Relay => "relay",
+        }
+    }
+}
+
+/// Mirrors GstWebRTCRTPTransceiverDirection
+#[derive(Copy,Clone,Debug,PartialEq)]
+pub enum TransceiverDirection{
+    Inactive,
+    SendOnly,
+    RecvOnly,
+    SendRecv,
+}
+
+impl TransceiverDirection{
+    pub fn nick(self) -> &'static str{
+        match self{
+            TransceiverDirection::Inactive => "inactive",
+            TransceiverDirection::SendOnly => "sendonly",
+            TransceiverDirection::RecvOnly => "recvonly",
+            TransceiverDirection::SendRecv => "sendrecv",
+        }
+    }
+}
+
+/// A TURN server with its credentials, builds the uri webrtcbin
+/// expects escaping the credentials as needed:
+///
+/// ```ignore
+/// let turn = gst::webrtcbin::TurnServer::new("turn.example.com", 3478, "user", "p@ss")
+///     .tls(true);
+/// webrtcbin.set_turn_server(&turn);
+/// ```
+#[derive(Clone,Debug)]
+pub struct TurnServer{
+    host: String,
+    port: u16,
+    username: String,
+    password: String,
+    tls: bool,
+    transport: Option<String>,
+}
+
+impl TurnServer{
+    pub fn new(host: &str, port: u16, username: &str, password: &str) -> TurnServer{
+        TurnServer{
+            host: host.to_string(),
+            port: port,
+            username: username.to_string(),
+            password: password.to_string(),
+            tls: false,
+            transport: None,
+        }
+    }
+
+    /// Connect to the server using TLS, turns:// instead of turn://
+    pub fn tls(mut self, tls: bool) -> TurnServer{
+        self.tls = tls;
+        self
+    }
+
+    /// Transport used to talk to the server, "udp" or "tcp"
+    pub fn transport(mut self, transport: &str) -> TurnServer{
+        self.transport = Some(transport.to_string());
+        self
+    }
+
+    pub fn to_uri(&self) -> String{
+        let mut uri = format!("{}://{}:{}@{}:{}",
+            if self.tls { "turns" } else { "turn" },
+            uri_escape(&self.username), uri_escape(&self.password),
+            self.host, self.port);
+        if let Some(ref transport) = self.transport{
+            uri.push_str("?transport=");
+            uri.push_str(transport);
+        }
+        uri
+    }
+}
+
+/// Percent encodes everything but the unreserved characters of RFC 3986
+/// so credentials can contain ':', '@' or '/'
+fn uri_escape(s: &str) -> String{
+    let mut escaped = String::with_capacity(s.len());
+    for b in s.bytes(){
+        match b{
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => escaped.push(b as char),
+            _ => escaped.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    escaped
+}
+
+/// Common fields of all the stats in a webrtcbin report
+#[derive(Clone,Debug)]
+pub struct StatsHeader{
+    /// Unique id of the stats in the report, other stats refer to it
+    pub id: String,
+    /// Time the stats were taken at in milliseconds
+    pub timestamp: f64,
+}
+
+/// Stats of an RTP stream being received
+#[derive(Clone,Debug)]
+pub struct InboundRtpStats{
+    pub header: StatsHeader,
+    pub ssrc: Option<u32>,
+    pub codec_id: Option<String>,
+    pub packets_received: Option<u64>,
+    pub bytes_received: Option<u64>,
+    pub packets_lost: Option<i64>,
+    /// Jitter in seconds
+    pub jitter: Option<f64>,
+    pub fir_count: Option<u32>,
+    pub pli_count: Option<u32>,
+    pub nack_count: Option<u32>,
+}
+
+/// Stats of an RTP stream being sent
+#[derive(Clone,Debug)]
+pub struct OutboundRtpStats{
+    pub header: StatsHeader,
+    pub ssrc: Option<u32>,
+    pub codec_id: Option<String>,
+    pub packets_sent: Option<u64>,
+    pub bytes_sent: Option<u64>,
+    /// Id of the remote-inbound-rtp stats with what the peer reported
+    /// receiving of this stream
+    pub remote_id: Option<String>,
+    pub fir_count: Option<u32>,
+    pub pli_count: Option<u32>,
+    pub nack_count: Option<u32>,
+}
+
+/// What the remote peer reported through RTCP about a stream we send
+#[derive(Clone,Debug)]
+pub struct RemoteInboundRtpStats{
+    pub header: StatsHeader,
+    pub ssrc: Option<u32>,
+    pub codec_id: Option<String>,
+    pub packets_lost: Option<i64>,
+    /// Jitter in seconds
+    pub jitter: Option<f64>,
+    /// Round trip time in seconds
+    pub round_trip_time: Option<f64>,
+    pub local_id: Option<String>,
+}
+
+/// A pair of local and remote ICE candidates
+#[derive(Clone,Debug)]
+pub struct CandidatePairStats{
+    pub header: StatsHeader,
+    pub local_candidate_id: Option<String>,
+    pub remote_candidate_id: Option<String>,
+    pub nominated: Option<bool>,
+    pub bytes_sent: Option<u64>,
+    pub bytes_received: Option<u64>,
+}
+
+/// A codec in use by one of the streams
+#[derive(Clone,Debug)]
+pub struct CodecStats{
+    pub header: StatsHeader,
+    pub payload_type: Option<u32>,
+    pub clock_rate: Option<u32>,
+    pub channels: Option<u32>,
+    pub mime_type: Option<String>,
+    pub sdp_fmtp_line: Option<String>,
+}
+
+/// One entry in a webrtcbin stats report
+#[derive(Clone,Debug)]
+pub enum Stats{
+    InboundRtp(InboundRtpStats),
+    OutboundRtp(OutboundRtpStats),
+    RemoteInboundRtp(RemoteInboundRtpStats),
+    CandidatePair(CandidatePairStats),
+    Codec(CodecStats),
+    /// Any other kind of stats, with its type name, eg: "transport",
+    /// "local-candidate"... Use WebRtcStats::get to access its fields
+    Other(StatsHeader, String),
+}
+
+/// Report returned by WebRtcBin::get_stats, the typed accessors cover
+/// the most used stats, the full report can still be traversed through
+/// structure()
+pub struct WebRtcStats{
+    structure: Structure
+}
+
+impl WebRtcStats{
+    pub fn new(structure: Structure) -> WebRtcStats{
+        WebRtcStats{ structure: structure }
+    }
+
+    /// The raw report, a structure with a field per stats id each
+    /// holding a structure with the stats
+    pub fn structure(&self) -> &Structure{
+        &self.structure
+    }
+
+    /// Returns the raw stats with the given id
+    pub fn get(&self, id: &str) -> Option<Structure>{
+        self.structure.get_structure(id)
+    }
+
+    /// All the stats in the report
+    pub fn stats(&self) -> Vec<Stats>{
+        self.structure.field_names().iter()
+            .filter_map(|id| self.structure.get_structure(id))
+            .map(|s| parse_stats(&s))
+            .collect()
+    }
+
+    pub fn inbound_rtp(&self) -> Vec<InboundRtpStats>{
+        self.stats().into_iter().filter_map(|stats| match stats{
+            Stats::InboundRtp(stats) => Some(stats),
+            _ => None,
+        }).collect()
+    }
+
+    pub fn outbound_rtp(&self) -> Vec<OutboundRtpStats>{
+        self.stats().into_iter().filter_map(|stats| match stats{
+            Stats::OutboundRtp(stats) => Some(stats),
+            _ => None,
+        }).collect()
+    }
+
+    pub fn remote_inbound_rtp(&self) -> Vec<RemoteInboundRtpStats>{
+        self.stats().into_iter().filter_map(|stats| match stats{
+            Stats::RemoteInboundRtp(stats) => Some(stats),
+            _ => None,
+        }).collect()
+    }
+
+    pub fn candidate_pairs(&self) -> Vec<CandidatePairStats>{
+        self.stats().into_iter().filter_map(|stats| match stats{
+            Stats::CandidatePair(stats) => Some(stats),
+            _ => None,
+        }).collect()
+    }
+
+    pub fn codecs(&self) -> Vec<CodecStats>{
+        self.stats().into_iter().filter_map(|stats| match stats{
+            Stats::Codec(stats) => Some(stats),
+            _ => None,
+        }).collect()
+    }
+}
+
+/// Integer fields changed type between webrtcbin versions, reads any of
+/// them as i64
+fn get_i64(s: &Structure, name: &str) -> Option<i64>{
+    s.get::<i64>(name)
+        .or_else(|| s.get::<i32>(name).map(|v| v as i64))
+        .or_else(|| s.get::<u32>(name).map(|v| v as i64))
+}
+
+fn get_u64(s: &Structure, name: &str) -> Option<u64>{
+    s.get::<u64>(name)
+        .or_else(|| s.get::<u32>(name).map(|v| v as u64))
+}
+
+fn parse_stats(s: &Structure) -> Stats{
+    let header = StatsHeader{
+        id: s.get::<String>("id").unwrap_or_default(),
+        timestamp: s.get_double("timestamp").unwrap_or(0.),
+    };
+    // "type" is a GstWebRTCStatsType whose nicks are the names used in
+    // the W3C spec, eg: "inbound-rtp"
+    let stats_type = s.get_value("type")
+        .and_then(|value| value.enum_nick())
+        .unwrap_or_else(|| s.name().to_string());
+    match &stats_type[..]{
+        "inbound-rtp" => Stats::InboundRtp(InboundRtpStats{
+            header: header,
+            ssrc: s.get_uint("ssrc"),
+            codec_id: s.get("codec-id"),
+            packets_received: get_u64(s, "packets-received"),
+            bytes_received: get_u64(s, "bytes-received"),
+            packets_lost: get_i64(s, "packets-lost"),
+            jitter: s.get_double("jitter"),
+            fir_count: s.get_uint("fir-count"),
+            pli_count: s.get_uint("pli-count"),
+            nack_count: s.get_uint("nack-count"),
+        }),
+        "outbound-rtp" => Stats::OutboundRtp(OutboundRtpStats{
+            header: header,
+            ssrc: s.get_uint("ssrc"),
+            codec_id: s.get("codec-id"),
+            packets_sent: get_u64(s, "packets-sent"),
+            bytes_sent: get_u64(s, "bytes-sent"),
+            remote_id: s.get("remote-id"),
+            fir_count: s.get_uint("fir-count"),
+            pli_count: s.get_uint("pli-count"),
+            nack_count: s.get_uint("nack-count"),
+        }),
+        "remote-inbound-rtp" => Stats::RemoteInboundRtp(RemoteInboundRtpStats{
+            header: header,
+            ssrc: s.get_uint("ssrc"),
+            codec_id: s.get("codec-id"),
+            packets_lost: get_i64(s, "packets-lost"),
+            jitter: s.get_double("jitter"),
+            round_trip_time: s.get_double("round-trip-time"),
+            local_id: s.get("local-id"),
+        }),
+        "candidate-pair" => Stats::CandidatePair(CandidatePairStats{
+            header: header,
+            local_candidate_id: s.get("local-candidate-id"),
+            remote_candidate_id: s.get("remote-candidate-id"),
+            nominated: s.get_bool("nominated"),
+            bytes_sent: get_u64(s, "bytes-sent"),
+            bytes_received: get_u64(s, "bytes-received"),
+        }),
+        "codec" => Stats::Codec(CodecStats{
+            header: header,
+            payload_type: s.get_uint("payload-type"),
+            clock_rate: s.get_uint("clock-rate"),
+            channels: s.get_uint("channels"),
+            mime_type: s.get("mime-type"),
+            sdp_fmtp_line: s.get("sdp-fmtp-line"),
+        }),
+        _ => Stats::Other(header, stats_type),
+    }
+}
+
+impl Reference for WebRtcBin{
+    fn reference(&self) -> WebRtcBin{
+        WebRtcBin{ webrtcbin: self.webrtcbin.reference() }
+    }
+}
+
+impl AsRef<Element> for WebRtcBin{
+    fn as_ref(&self) -> &Element{
+        &self.webrtcbin
+    }
+}
+
+impl AsMut<Element> for WebRtcBin{
+    fn as_mut(&mut self) -> &mut Element{
+        &mut self.webrtcbin
+    }
+}
+
+impl From<WebRtcBin> for Element{
+    fn from(w: WebRtcBin) -> Element{
+        w.webrtcbin
+    }
+}
+
+impl Deref for WebRtcBin{
+    type Target = Element;
+    fn deref(&self) -> &Element{
+        &self.webrtcbin
+    }
+}
+
+impl DerefMut for WebRtcBin{
+    fn deref_mut(&mut self) -> &mut Element{
+        &mut self.webrtcbin
+    }
+}
+
+impl ::Transfer for WebRtcBin{
+    unsafe fn transfer(self) -> *mut GstElement{
+        self.webrtcbin.transfer()
+    }
+}